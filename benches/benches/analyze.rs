@@ -0,0 +1,72 @@
+#[cfg(feature = "embed-ipadic")]
+use std::path::PathBuf;
+
+#[cfg(feature = "embed-ipadic")]
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+#[cfg(feature = "embed-ipadic")]
+use lindera::tokenizer::{Tokenizer, TokenizerBuilder};
+#[cfg(feature = "embed-ipadic")]
+use lindera_benches::CORPORA;
+
+#[cfg(feature = "embed-ipadic")]
+fn bench_analyze(c: &mut Criterion, group_name: &str, tokenizer: &Tokenizer) {
+    let mut group = c.benchmark_group(group_name);
+    for corpus in CORPORA {
+        group.throughput(Throughput::Bytes(corpus.text.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(corpus.name),
+            corpus.text,
+            |b, text| b.iter(|| tokenizer.tokenize(text).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+#[cfg(feature = "embed-ipadic")]
+fn bench_analyze_filters(c: &mut Criterion) {
+    // A search analyzer: normalize the text, drop the particles and symbols, index base forms
+    let config = serde_json::json!({
+        "segmenter": {
+            "mode": "normal",
+            "dictionary": "embedded://ipadic"
+        },
+        "character_filters": [
+            { "kind": "unicode_normalize", "args": { "kind": "nfkc" } },
+            { "kind": "japanese_iteration_mark", "args": { "normalize_kanji": true, "normalize_kana": true } }
+        ],
+        "token_filters": [
+            { "kind": "japanese_stop_tags", "args": { "tags": ["助詞", "助動詞", "記号"] } },
+            { "kind": "japanese_base_form", "args": {} },
+            { "kind": "lowercase", "args": {} }
+        ]
+    });
+    let tokenizer = Tokenizer::from_config(&config).unwrap();
+
+    bench_analyze(c, "analyze-filters-ipadic", &tokenizer);
+}
+
+#[cfg(feature = "embed-ipadic")]
+fn bench_analyze_config(c: &mut Criterion) {
+    let config_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../resources")
+        .join("config")
+        .join("lindera.yml");
+    let tokenizer = TokenizerBuilder::from_file(&config_file)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    bench_analyze(c, "analyze-config-ipadic", &tokenizer);
+}
+
+#[cfg(feature = "embed-ipadic")]
+criterion_group!(benches, bench_analyze_filters, bench_analyze_config);
+
+#[cfg(feature = "embed-ipadic")]
+criterion_main!(benches);
+
+#[cfg(not(feature = "embed-ipadic"))]
+fn main() {
+    println!("Embedded IPADIC feature is not enabled");
+}
@@ -0,0 +1,106 @@
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+use lindera::dictionary::{DictionaryBuilder, MatrixEncoding, Metadata, load_dictionary};
+use lindera_benches::write_synthetic_sources;
+
+/// The lexicon sizes of the synthetic dictionaries.
+const WORDS: &[usize] = &[1_000, 10_000, 100_000];
+
+fn bench_build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dictionary-build");
+    group.sample_size(10);
+    for &words in WORDS {
+        let input_dir = tempfile::tempdir().unwrap();
+        write_synthetic_sources(input_dir.path(), words).unwrap();
+
+        group.bench_with_input(BenchmarkId::from_parameter(words), &words, |b, _| {
+            b.iter(|| {
+                let output_dir = tempfile::tempdir().unwrap();
+                DictionaryBuilder::new(Metadata::default())
+                    .build_dictionary(input_dir.path(), output_dir.path())
+                    .unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_load(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dictionary-load");
+    for &words in WORDS {
+        let input_dir = tempfile::tempdir().unwrap();
+        let output_dir = tempfile::tempdir().unwrap();
+        write_synthetic_sources(input_dir.path(), words).unwrap();
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), output_dir.path())
+            .unwrap();
+        let uri = output_dir.path().to_str().unwrap().to_string();
+
+        group.bench_with_input(BenchmarkId::from_parameter(words), &uri, |b, uri| {
+            b.iter(|| load_dictionary(uri).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Looks up every connection cost of the matrix, in the plain, quantized and sharded encodings.
+fn bench_connection_cost(c: &mut Criterion) {
+    let mut group = c.benchmark_group("connection-cost");
+    let encodings = [
+        ("plain", MatrixEncoding::Plain, None),
+        ("quantized", MatrixEncoding::Quantized, None),
+        ("sharded", MatrixEncoding::Plain, Some(4)),
+    ];
+    for (name, matrix_encoding, matrix_shard_size) in encodings {
+        let input_dir = tempfile::tempdir().unwrap();
+        let output_dir = tempfile::tempdir().unwrap();
+        write_synthetic_sources(input_dir.path(), 1_000).unwrap();
+        let metadata = Metadata {
+            matrix_encoding,
+            matrix_shard_size,
+            ..Default::default()
+        };
+        DictionaryBuilder::new(metadata)
+            .build_dictionary(input_dir.path(), output_dir.path())
+            .unwrap();
+        let dictionary = load_dictionary(output_dir.path().to_str().unwrap()).unwrap();
+        let matrix = &dictionary.connection_cost_matrix;
+        let (forward_size, backward_size) = matrix.size();
+
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let mut total = 0i64;
+                for backward_id in 0..backward_size {
+                    for forward_id in 0..forward_size {
+                        total += matrix.cost(black_box(forward_id), black_box(backward_id)) as i64;
+                    }
+                }
+                total
+            })
+        });
+    }
+    group.finish();
+}
+
+#[cfg(feature = "embed-ipadic")]
+fn bench_load_embedded(c: &mut Criterion) {
+    c.bench_function("dictionary-load-embedded-ipadic", |b| {
+        b.iter(|| load_dictionary("embedded://ipadic").unwrap())
+    });
+}
+
+#[cfg(feature = "embed-ipadic")]
+criterion_group!(
+    benches,
+    bench_build,
+    bench_load,
+    bench_connection_cost,
+    bench_load_embedded
+);
+
+#[cfg(not(feature = "embed-ipadic"))]
+criterion_group!(benches, bench_build, bench_load, bench_connection_cost);
+
+criterion_main!(benches);
@@ -0,0 +1,67 @@
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+use lindera::dictionary::{Dictionary, DictionaryBuilder, Metadata, load_dictionary};
+use lindera::mode::Mode;
+use lindera::segmenter::Segmenter;
+use lindera::tokenizer::Tokenizer;
+use lindera_benches::{CORPORA, compare_quantization, quantize, write_synthetic_sources};
+
+/// Prints how far the tokenization with the quantized matrix of `dictionary` is from the one
+/// with its plain matrix, and benchmarks the tokenization with both.
+fn bench_quantization(c: &mut Criterion, name: &str, dictionary: Dictionary) {
+    let report = compare_quantization(&dictionary, CORPORA).unwrap();
+    println!(
+        "{name}: cost error max {} mean {:.2}, lines {}/{} ({:.2}%), tokens {}/{} ({:.2}%)",
+        report.max_cost_error,
+        report.mean_cost_error,
+        report.agreeing_lines,
+        report.lines,
+        report.line_agreement() * 100.0,
+        report.agreeing_tokens,
+        report.tokens,
+        report.token_agreement() * 100.0,
+    );
+
+    let quantized = quantize(&dictionary).unwrap();
+    let mut group = c.benchmark_group(format!("quantization-{name}"));
+    for (encoding, dictionary) in [("plain", dictionary), ("quantized", quantized)] {
+        let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+        for corpus in CORPORA {
+            group.throughput(Throughput::Bytes(corpus.text.len() as u64));
+            group.bench_with_input(
+                BenchmarkId::new(encoding, corpus.name),
+                corpus.text,
+                |b, text| b.iter(|| tokenizer.tokenize(text).unwrap()),
+            );
+        }
+    }
+    group.finish();
+}
+
+fn bench_quantization_synthetic(c: &mut Criterion) {
+    let input_dir = tempfile::tempdir().unwrap();
+    let output_dir = tempfile::tempdir().unwrap();
+    write_synthetic_sources(input_dir.path(), 10_000).unwrap();
+    DictionaryBuilder::new(Metadata::default())
+        .build_dictionary(input_dir.path(), output_dir.path())
+        .unwrap();
+    let dictionary = load_dictionary(output_dir.path().to_str().unwrap()).unwrap();
+    bench_quantization(c, "synthetic", dictionary);
+}
+
+#[cfg(feature = "embed-ipadic")]
+fn bench_quantization_ipadic(c: &mut Criterion) {
+    bench_quantization(c, "ipadic", load_dictionary("embedded://ipadic").unwrap());
+}
+
+#[cfg(feature = "embed-ipadic")]
+criterion_group!(
+    benches,
+    bench_quantization_synthetic,
+    bench_quantization_ipadic
+);
+
+#[cfg(not(feature = "embed-ipadic"))]
+criterion_group!(benches, bench_quantization_synthetic);
+
+criterion_main!(benches);
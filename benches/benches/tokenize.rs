@@ -0,0 +1,84 @@
+#[cfg(feature = "embed-ipadic")]
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+#[cfg(feature = "embed-ipadic")]
+use lindera::dictionary::load_dictionary;
+#[cfg(feature = "embed-ipadic")]
+use lindera::mode::{Mode, Penalty};
+#[cfg(feature = "embed-ipadic")]
+use lindera::segmenter::Segmenter;
+#[cfg(feature = "embed-ipadic")]
+use lindera::tokenizer::Tokenizer;
+#[cfg(feature = "embed-ipadic")]
+use lindera_benches::CORPORA;
+
+#[cfg(feature = "embed-ipadic")]
+fn bench_tokenize(c: &mut Criterion, group_name: &str, mode: Mode) {
+    let dictionary = load_dictionary("embedded://ipadic").unwrap();
+    let tokenizer = Tokenizer::new(Segmenter::new(mode, dictionary, None));
+
+    let mut group = c.benchmark_group(group_name);
+    for corpus in CORPORA {
+        group.throughput(Throughput::Bytes(corpus.text.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(corpus.name),
+            corpus.text,
+            |b, text| b.iter(|| tokenizer.tokenize(text).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+#[cfg(feature = "embed-ipadic")]
+fn bench_tokenize_normal(c: &mut Criterion) {
+    bench_tokenize(c, "tokenize-normal-ipadic", Mode::Normal);
+}
+
+#[cfg(feature = "embed-ipadic")]
+fn bench_tokenize_decompose(c: &mut Criterion) {
+    bench_tokenize(
+        c,
+        "tokenize-decompose-ipadic",
+        Mode::Decompose(Penalty::default()),
+    );
+}
+
+#[cfg(feature = "embed-ipadic")]
+fn bench_tokenize_details(c: &mut Criterion) {
+    let dictionary = load_dictionary("embedded://ipadic").unwrap();
+    let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+
+    let mut group = c.benchmark_group("tokenize-details-ipadic");
+    for corpus in CORPORA {
+        group.throughput(Throughput::Bytes(corpus.text.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(corpus.name),
+            corpus.text,
+            |b, text| {
+                b.iter(|| {
+                    let mut tokens = tokenizer.tokenize(text).unwrap();
+                    for token in tokens.iter_mut() {
+                        let _details = token.details();
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+#[cfg(feature = "embed-ipadic")]
+criterion_group!(
+    benches,
+    bench_tokenize_normal,
+    bench_tokenize_decompose,
+    bench_tokenize_details,
+);
+
+#[cfg(feature = "embed-ipadic")]
+criterion_main!(benches);
+
+#[cfg(not(feature = "embed-ipadic"))]
+fn main() {
+    println!("Embedded IPADIC feature is not enabled");
+}
@@ -0,0 +1,276 @@
+//! The corpora and the fixtures shared by the benchmarks of the workspace.
+//!
+//! The corpora are small samples of the kinds of text Lindera is run on: news articles, social
+//! media posts, and technical prose mixing Japanese with code and English. The benchmarks that
+//! need a system dictionary run with the `embed-ipadic` feature; the dictionary build and load
+//! benchmarks use a synthetic dictionary and run without it.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use lindera::LinderaResult;
+use lindera::dictionary::Dictionary;
+use lindera::error::LinderaErrorKind;
+use lindera::mode::Mode;
+use lindera::segmenter::Segmenter;
+use lindera::tokenizer::Tokenizer;
+use lindera_dictionary::dictionary::connection_cost_matrix::{
+    ConnectionCostMatrix, QUANTIZED_MATRIX_FLAG, QuantizedCosts,
+};
+
+/// A sample of text to benchmark on.
+#[derive(Debug, Clone, Copy)]
+pub struct Corpus {
+    pub name: &'static str,
+    pub text: &'static str,
+}
+
+/// Sentences from news articles: long, formal, kanji-heavy.
+pub const NEWS: Corpus = Corpus {
+    name: "news",
+    text: include_str!("../corpora/news.txt"),
+};
+
+/// Social media posts: short, colloquial, with emoji and repeated kana.
+pub const SOCIAL: Corpus = Corpus {
+    name: "social",
+    text: include_str!("../corpora/social.txt"),
+};
+
+/// Technical prose mixing Japanese with identifiers, numbers and English.
+pub const CODE_MIXED: Corpus = Corpus {
+    name: "code-mixed",
+    text: include_str!("../corpora/code_mixed.txt"),
+};
+
+/// All the corpora, in the order the benchmarks report them.
+pub const CORPORA: &[Corpus] = &[NEWS, SOCIAL, CODE_MIXED];
+
+/// The number of left and right context ids of the synthetic dictionary.
+const CONTEXT_IDS: usize = 16;
+
+/// The first kanji the words of the synthetic dictionary are made of.
+const FIRST_KANJI: u32 = 0x4E00;
+
+/// Writes the sources of a synthetic IPADIC-format dictionary of `words` entries to `dir`, to
+/// benchmark building and loading a dictionary of a known size.
+///
+/// The words are two-kanji compounds with deterministic context ids and costs, so every run
+/// builds the same dictionary.
+pub fn write_synthetic_sources(dir: &Path, words: usize) -> LinderaResult<()> {
+    let write = |name: &str, contents: &str| {
+        fs::write(dir.join(name), contents)
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))
+    };
+
+    write(
+        "char.def",
+        "DEFAULT 0 1 0\nHIRAGANA 1 1 0\nKATAKANA 1 1 0\nKANJI 0 0 2\nALPHA 1 1 0\nNUMERIC 1 1 0\n\
+         0x3041..0x3096 HIRAGANA\n0x30A1..0x30F6 KATAKANA\n0x4E00..0x9FAF KANJI\n\
+         0x0030..0x0039 NUMERIC\n0x0041..0x005A ALPHA\n0x0061..0x007A ALPHA\n",
+    )?;
+    write(
+        "unk.def",
+        "DEFAULT,0,0,3000,名詞,一般,*,*,*,*,*,*,*\nHIRAGANA,0,0,3000,名詞,一般,*,*,*,*,*,*,*\n\
+         KATAKANA,0,0,3000,名詞,一般,*,*,*,*,*,*,*\nKANJI,0,0,3000,名詞,一般,*,*,*,*,*,*,*\n\
+         ALPHA,0,0,3000,名詞,固有名詞,一般,*,*,*,*,*,*\nNUMERIC,0,0,3000,名詞,数,*,*,*,*,*,*,*\n",
+    )?;
+
+    let mut matrix = format!("{CONTEXT_IDS} {CONTEXT_IDS}\n");
+    for right_id in 0..CONTEXT_IDS {
+        for left_id in 0..CONTEXT_IDS {
+            let cost = (right_id * 31 + left_id * 17) % 400;
+            let _ = writeln!(matrix, "{right_id} {left_id} {cost}");
+        }
+    }
+    write("matrix.def", &matrix)?;
+
+    // The kanji are drawn from the first `span` of the block, so that the words share prefixes
+    let span = ((words as f64).sqrt().ceil() as u32).max(1);
+    let mut lexicon = String::new();
+    for i in 0..words as u32 {
+        let word: String = [FIRST_KANJI + i / span, FIRST_KANJI + i % span]
+            .into_iter()
+            .filter_map(char::from_u32)
+            .collect();
+        let context_id = i as usize % CONTEXT_IDS;
+        let cost = 1000 + (i * 7919) % 4000;
+        let _ = writeln!(
+            lexicon,
+            "{word},{context_id},{context_id},{cost},名詞,一般,*,*,*,*,{word},*,*"
+        );
+    }
+    write("lex.csv", &lexicon)?;
+
+    Ok(())
+}
+
+/// How far the tokenization with a quantized connection cost matrix is from the one with the
+/// plain matrix it was quantized from.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QuantizationReport {
+    /// The largest difference between a quantized cost and the plain one.
+    pub max_cost_error: i32,
+    /// The mean difference between a quantized cost and the plain one.
+    pub mean_cost_error: f64,
+    /// The number of lines of the corpora.
+    pub lines: usize,
+    /// The number of lines segmented into the same words with both matrices.
+    pub agreeing_lines: usize,
+    /// The number of tokens of the corpora with the plain matrix.
+    pub tokens: usize,
+    /// The number of those tokens also produced, as the same word, with the quantized matrix.
+    pub agreeing_tokens: usize,
+}
+
+impl QuantizationReport {
+    /// Returns the share of the lines segmented into the same words.
+    pub fn line_agreement(&self) -> f64 {
+        self.agreeing_lines as f64 / self.lines.max(1) as f64
+    }
+
+    /// Returns the share of the tokens also produced with the quantized matrix.
+    pub fn token_agreement(&self) -> f64 {
+        self.agreeing_tokens as f64 / self.tokens.max(1) as f64
+    }
+}
+
+/// Returns `dictionary` with its connection cost matrix quantized as `matrix_encoding` set to
+/// `"quantized"` would have built it.
+///
+/// Returns an error if the matrix of `dictionary` is not a plain one.
+pub fn quantize(dictionary: &Dictionary) -> LinderaResult<Dictionary> {
+    let matrix = &dictionary.connection_cost_matrix;
+    let costs = matrix.raw_costs().ok_or_else(|| {
+        LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+            "the connection cost matrix is already quantized or sharded"
+        ))
+    })?;
+    let (forward_size, backward_size) = matrix.size();
+    let mut data = Vec::new();
+    for value in [
+        QUANTIZED_MATRIX_FLAG,
+        forward_size as i16,
+        backward_size as i16,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(&QuantizedCosts::encode(costs, forward_size));
+
+    let mut quantized = dictionary.clone();
+    quantized.connection_cost_matrix = ConnectionCostMatrix::load(data)?;
+    Ok(quantized)
+}
+
+/// Compares the costs of the plain connection cost matrix of `dictionary` with the ones of the
+/// quantized matrix, and the tokenization of every line of `corpora` with both.
+pub fn compare_quantization(
+    dictionary: &Dictionary,
+    corpora: &[Corpus],
+) -> LinderaResult<QuantizationReport> {
+    let quantized = quantize(dictionary)?;
+    let plain_matrix = &dictionary.connection_cost_matrix;
+    let quantized_matrix = &quantized.connection_cost_matrix;
+    let (forward_size, backward_size) = plain_matrix.size();
+
+    let mut report = QuantizationReport::default();
+    let (mut total_error, mut costs) = (0i64, 0i64);
+    for backward_id in 0..backward_size {
+        for forward_id in 0..forward_size {
+            let cost = plain_matrix.cost(forward_id, backward_id);
+            if cost == i16::MAX as i32 {
+                continue;
+            }
+            let error = (quantized_matrix.cost(forward_id, backward_id) - cost).abs();
+            report.max_cost_error = report.max_cost_error.max(error);
+            total_error += error as i64;
+            costs += 1;
+        }
+    }
+    report.mean_cost_error = total_error as f64 / costs.max(1) as f64;
+
+    let plain = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary.clone(), None));
+    let quantized = Tokenizer::new(Segmenter::new(Mode::Normal, quantized, None));
+    for line in corpora.iter().flat_map(|corpus| corpus.text.lines()) {
+        let words = |tokenizer: &Tokenizer| -> LinderaResult<Vec<_>> {
+            Ok(tokenizer
+                .tokenize(line)?
+                .iter()
+                .map(|token| (token.byte_start, token.byte_end, token.word_id))
+                .collect())
+        };
+        let (plain_words, quantized_words) = (words(&plain)?, words(&quantized)?);
+        report.lines += 1;
+        if plain_words == quantized_words {
+            report.agreeing_lines += 1;
+        }
+        report.tokens += plain_words.len();
+        report.agreeing_tokens += plain_words
+            .iter()
+            .filter(|word| quantized_words.contains(word))
+            .count();
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use lindera::dictionary::{DictionaryBuilder, Metadata, load_dictionary};
+    use lindera::mode::Mode;
+    use lindera::segmenter::Segmenter;
+    use lindera::tokenizer::Tokenizer;
+
+    use crate::{CORPORA, compare_quantization, quantize, write_synthetic_sources};
+
+    #[test]
+    fn test_corpora() {
+        assert_eq!(3, CORPORA.len());
+        for corpus in CORPORA {
+            assert!(corpus.text.lines().count() >= 10, "{}", corpus.name);
+        }
+    }
+
+    #[test]
+    fn test_synthetic_dictionary() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let output_dir = tempfile::tempdir().unwrap();
+        write_synthetic_sources(input_dir.path(), 100).unwrap();
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), output_dir.path())
+            .unwrap();
+
+        let dictionary = load_dictionary(output_dir.path().to_str().unwrap()).unwrap();
+        let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+
+        // "一丁" is the second word of the lexicon
+        let tokens = tokenizer.tokenize("一丁").unwrap();
+        assert_eq!(1, tokens.len());
+        assert_eq!("一丁", tokens[0].surface);
+        for corpus in CORPORA {
+            assert!(!tokenizer.tokenize(corpus.text).unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_compare_quantization() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let output_dir = tempfile::tempdir().unwrap();
+        write_synthetic_sources(input_dir.path(), 1_000).unwrap();
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), output_dir.path())
+            .unwrap();
+        let dictionary = load_dictionary(output_dir.path().to_str().unwrap()).unwrap();
+
+        // The costs of the synthetic matrix span 400, so the step is 2 and a cost is off by 1
+        let report = compare_quantization(&dictionary, CORPORA).unwrap();
+        assert_eq!(1, report.max_cost_error);
+        assert_eq!(55, report.lines);
+        assert_eq!(report.lines, report.agreeing_lines);
+        assert_eq!(report.tokens, report.agreeing_tokens);
+
+        // A matrix that is already quantized cannot be compared
+        assert!(quantize(&quantize(&dictionary).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use std::error::Error;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn Error>> {
+    if std::env::var_os("LINDERA_DICTIONARIES_PATH").is_none()
+        && std::env::var_os("LINDERA_CACHE").is_none()
+        && cfg!(not(feature = "embed-cc-canto"))
+    {
+        return Ok(());
+    }
+
+    // Without a local copy of the source, which is not downloaded, the dictionary can only be
+    // built when it is embedded, where the missing source is reported as an error
+    if cfg!(not(feature = "embed-cc-canto"))
+        && std::env::var_os("LINDERA_CC_CANTO_SOURCE").is_none()
+    {
+        println!("cargo:rerun-if-changed=build.rs");
+        println!("cargo:rerun-if-env-changed=LINDERA_CC_CANTO_SOURCE");
+        return Ok(());
+    }
+
+    use std::fs;
+    use std::path::Path;
+
+    use lindera_dictionary::{
+        assets::{FetchParams, fetch},
+        builder::DictionaryBuilder,
+        dictionary::metadata::Metadata,
+    };
+
+    // The source of CC-Canto is not mirrored, so it is not downloaded: set
+    // LINDERA_CC_CANTO_SOURCE to the dictionary file in the CC-CEDICT format downloaded from the
+    // CC-Canto site
+    let fetch_params = FetchParams {
+        file_name: "cccanto-webdist.txt",
+        input_dir: "cccanto",
+        output_dir: "lindera-cc-canto",
+        dummy_input: "测试,0,0,3000,*,*,*,*,ce4 shi4,caak1 si3,測試,测试,to test/\n",
+        download_urls: &[],
+        md5_hash: "",
+    };
+
+    // Read and deserialize metadata directly from JSON file
+    let metadata_path = Path::new("metadata.json");
+    let metadata_json = fs::read_to_string(metadata_path)?;
+    let metadata: Metadata = serde_json::from_str(&metadata_json)?;
+
+    let builder = DictionaryBuilder::new(metadata);
+
+    fetch(fetch_params, builder).await?;
+
+    Ok(())
+}
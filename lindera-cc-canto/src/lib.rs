@@ -0,0 +1,9 @@
+#[cfg(feature = "embed-cc-canto")]
+pub mod embedded;
+
+pub const DICTIONARY_NAME: &str = "cc-canto";
+const VERERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub fn get_version() -> &'static str {
+    VERERSION
+}
@@ -20,7 +20,7 @@ macro_rules! decompress_data {
         static $name: once_cell::sync::Lazy<Vec<u8>> = once_cell::sync::Lazy::new(|| {
             // First check if this is compressed data by attempting to check aligned root
             let mut aligned = rkyv::util::AlignedVec::<16>::new();
-            aligned.extend_from_slice(&$bytes[..]);
+            aligned.extend_from_slice(lindera_dictionary::format::payload(&$bytes[..]));
             match rkyv::from_bytes::<CompressedData, rkyv::rancor::Error>(&aligned) {
                 Ok(compressed_data) => {
                     // Decompress it
@@ -28,18 +28,18 @@ macro_rules! decompress_data {
                         Ok(decompressed) => decompressed,
                         Err(_) => {
                             // Decompression failed, fall back to raw data
-                            $bytes.to_vec()
+                            lindera_dictionary::format::payload(&$bytes[..]).to_vec()
                         }
                     }
                 }
                 Err(_) => {
                     // Not compressed data format, use as raw binary
-                    $bytes.to_vec()
+                    lindera_dictionary::format::payload(&$bytes[..]).to_vec()
                 }
             }
         });
         #[cfg(not(feature = "compress"))]
-        const $name: &'static [u8] = $bytes;
+        const $name: &'static [u8] = lindera_dictionary::format::payload($bytes);
     };
 }
 
@@ -54,6 +54,17 @@ macro_rules! cccedict_data {
         #[cfg(not(feature = "embed-cc-cedict"))]
         decompress_data!($name, &[], $filename);
     };
+    // A component left out of the binary with the given feature
+    ($name: ident, $path: literal, $filename: literal, unless $feature: literal) => {
+        #[cfg(all(feature = "embed-cc-cedict", not(feature = $feature)))]
+        decompress_data!(
+            $name,
+            include_bytes!(concat!(env!("LINDERA_WORKDIR"), $path)),
+            $filename
+        );
+        #[cfg(any(not(feature = "embed-cc-cedict"), feature = $feature))]
+        decompress_data!($name, &[], $filename);
+    };
 }
 
 // Metadata-specific macro (skips compression/decompression processing)
@@ -78,13 +89,24 @@ cccedict_data!(
 );
 cccedict_data!(DA_DATA, "/lindera-cc-cedict/dict.da", "dict.da");
 cccedict_data!(VALS_DATA, "/lindera-cc-cedict/dict.vals", "dict.vals");
-cccedict_data!(UNKNOWN_DATA, "/lindera-cc-cedict/unk.bin", "unk.bin");
+cccedict_data!(
+    UNKNOWN_DATA,
+    "/lindera-cc-cedict/unk.bin",
+    "unk.bin",
+    unless "no-unknown-grouping"
+);
 cccedict_data!(
     WORDS_IDX_DATA,
     "/lindera-cc-cedict/dict.wordsidx",
-    "dict.wordsidx"
+    "dict.wordsidx",
+    unless "no-details"
+);
+cccedict_data!(
+    WORDS_DATA,
+    "/lindera-cc-cedict/dict.words",
+    "dict.words",
+    unless "no-details"
 );
-cccedict_data!(WORDS_DATA, "/lindera-cc-cedict/dict.words", "dict.words");
 cccedict_metadata!(
     METADATA_DATA,
     "/lindera-cc-cedict/metadata.json",
@@ -104,8 +126,8 @@ pub fn load() -> LinderaResult<Dictionary> {
                 WORDS_IDX_DATA.deref(),
                 WORDS_DATA.deref(),
                 true,
-            ),
-            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA.deref()),
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA.deref())?,
             character_definition: CharacterDefinition::load(&CHAR_DEFINITION_DATA)?,
             unknown_dictionary: UnknownDictionary::load(&UNKNOWN_DATA)?,
             metadata,
@@ -120,8 +142,8 @@ pub fn load() -> LinderaResult<Dictionary> {
                 WORDS_IDX_DATA,
                 WORDS_DATA,
                 true,
-            ),
-            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA),
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA)?,
             character_definition: CharacterDefinition::load(CHAR_DEFINITION_DATA)?,
             unknown_dictionary: UnknownDictionary::load(UNKNOWN_DATA)?,
             metadata,
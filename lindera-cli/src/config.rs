@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use lindera::LinderaResult;
+use lindera::error::LinderaErrorKind;
+
+/// A character filter or token filter entry in the CLI config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FilterConfig {
+    pub kind: String,
+    #[serde(default)]
+    pub args: Value,
+}
+
+/// Tokenization settings that can be given at the top level of the config file
+/// or in a named profile. Unset fields fall back to the next level.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ProfileConfig {
+    pub dict: Option<String>,
+    pub user_dict: Option<String>,
+    pub mode: Option<String>,
+    pub output: Option<String>,
+    pub keep_whitespace: Option<bool>,
+    pub fields: Option<Vec<String>>,
+    pub delimiter: Option<String>,
+    pub separator: Option<String>,
+    pub quote: Option<bool>,
+    pub sentence_per_line: Option<bool>,
+    pub character_filters: Option<Vec<FilterConfig>>,
+    pub token_filters: Option<Vec<FilterConfig>>,
+}
+
+impl ProfileConfig {
+    /// Returns a new profile where the fields set in `other` take precedence over `self`.
+    pub fn merge(self, other: ProfileConfig) -> ProfileConfig {
+        ProfileConfig {
+            dict: other.dict.or(self.dict),
+            user_dict: other.user_dict.or(self.user_dict),
+            mode: other.mode.or(self.mode),
+            output: other.output.or(self.output),
+            keep_whitespace: other.keep_whitespace.or(self.keep_whitespace),
+            fields: other.fields.or(self.fields),
+            delimiter: other.delimiter.or(self.delimiter),
+            separator: other.separator.or(self.separator),
+            quote: other.quote.or(self.quote),
+            sentence_per_line: other.sentence_per_line.or(self.sentence_per_line),
+            character_filters: other.character_filters.or(self.character_filters),
+            token_filters: other.token_filters.or(self.token_filters),
+        }
+    }
+}
+
+/// CLI config file (e.g. `lindera.toml`).
+///
+/// ```toml
+/// dict = "embedded://ipadic"
+/// output = "mecab"
+///
+/// [[character_filters]]
+/// kind = "unicode_normalize"
+/// args = { kind = "nfkc" }
+///
+/// [profiles.index]
+/// mode = "decompose"
+///
+/// [profiles.query]
+/// output = "wakati"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct CliConfig {
+    #[serde(flatten)]
+    pub defaults: ProfileConfig,
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
+}
+
+impl CliConfig {
+    pub fn from_file(path: &Path) -> LinderaResult<Self> {
+        let content = fs::read_to_string(path).map_err(|err| {
+            LinderaErrorKind::Io
+                .with_error(anyhow::anyhow!(err))
+                .add_context(format!("Failed to read config file: {}", path.display()))
+        })?;
+
+        Self::from_toml(&content).map_err(|err| {
+            err.add_context(format!("Failed to load config file: {}", path.display()))
+        })
+    }
+
+    pub fn from_toml(content: &str) -> LinderaResult<Self> {
+        toml::from_str(content)
+            .map_err(|err| LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!(err)))
+    }
+
+    /// Resolves the settings for the given profile.
+    ///
+    /// The fields of the profile override the top-level fields.
+    /// If `name` is `None`, only the top-level fields are returned.
+    pub fn resolve(&self, name: Option<&str>) -> LinderaResult<ProfileConfig> {
+        match name {
+            Some(name) => {
+                let profile = self.profiles.get(name).ok_or_else(|| {
+                    let mut names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+                    names.sort_unstable();
+                    LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                        "Unknown profile: {name} (available: {})",
+                        names.join(", ")
+                    ))
+                })?;
+                Ok(self.defaults.clone().merge(profile.clone()))
+            }
+            None => Ok(self.defaults.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const CONFIG: &str = r#"
+dict = "embedded://ipadic"
+output = "mecab"
+
+[[token_filters]]
+kind = "lowercase"
+
+[profiles.index]
+mode = "decompose"
+
+[profiles.query]
+output = "wakati"
+
+[[profiles.query.token_filters]]
+kind = "japanese_stop_tags"
+args = { tags = ["助詞"] }
+"#;
+
+    #[test]
+    fn test_resolve_defaults() {
+        let config = CliConfig::from_toml(CONFIG).unwrap();
+        let profile = config.resolve(None).unwrap();
+
+        assert_eq!(profile.dict.as_deref(), Some("embedded://ipadic"));
+        assert_eq!(profile.output.as_deref(), Some("mecab"));
+        assert_eq!(profile.mode, None);
+        assert_eq!(
+            profile.token_filters,
+            Some(vec![FilterConfig {
+                kind: "lowercase".to_string(),
+                args: Value::Null,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_resolve_profile() {
+        let config = CliConfig::from_toml(CONFIG).unwrap();
+
+        let index = config.resolve(Some("index")).unwrap();
+        assert_eq!(index.dict.as_deref(), Some("embedded://ipadic"));
+        assert_eq!(index.mode.as_deref(), Some("decompose"));
+        assert_eq!(index.output.as_deref(), Some("mecab"));
+
+        let query = config.resolve(Some("query")).unwrap();
+        assert_eq!(query.output.as_deref(), Some("wakati"));
+        assert_eq!(
+            query.token_filters,
+            Some(vec![FilterConfig {
+                kind: "japanese_stop_tags".to_string(),
+                args: json!({"tags": ["助詞"]}),
+            }])
+        );
+    }
+
+    #[test]
+    fn test_merge_false_overrides_true() {
+        let defaults = ProfileConfig {
+            keep_whitespace: Some(true),
+            quote: Some(true),
+            ..Default::default()
+        };
+        let merged = defaults.merge(ProfileConfig {
+            keep_whitespace: Some(false),
+            ..Default::default()
+        });
+        assert_eq!(Some(false), merged.keep_whitespace);
+        assert_eq!(Some(true), merged.quote);
+    }
+
+    #[test]
+    fn test_resolve_unknown_profile() {
+        let config = CliConfig::from_toml(CONFIG).unwrap();
+        assert!(config.resolve(Some("search")).is_err());
+    }
+
+    #[test]
+    fn test_invalid_toml() {
+        assert!(CliConfig::from_toml("dict = ").is_err());
+    }
+}
@@ -0,0 +1,978 @@
+use serde_json::{Map, Value, json};
+
+use lindera::LinderaResult;
+use lindera::error::LinderaErrorKind;
+
+use crate::validate::{Issue, Severity};
+
+/// Part-of-speech tags removed by `kuromoji_part_of_speech` when no `stoptags` are given
+/// (the `stoptags.txt` of Lucene).
+const KUROMOJI_DEFAULT_STOP_TAGS: &[&str] = &[
+    "接続詞",
+    "助詞",
+    "助詞-格助詞",
+    "助詞-格助詞-一般",
+    "助詞-格助詞-引用",
+    "助詞-格助詞-連語",
+    "助詞-接続助詞",
+    "助詞-係助詞",
+    "助詞-副助詞",
+    "助詞-間投助詞",
+    "助詞-並立助詞",
+    "助詞-終助詞",
+    "助詞-副助詞／並立助詞／終助詞",
+    "助詞-連体化",
+    "助詞-副詞化",
+    "助詞-特殊",
+    "助動詞",
+    "記号",
+    "記号-一般",
+    "記号-読点",
+    "記号-句点",
+    "記号-空白",
+    "記号-括弧開",
+    "記号-括弧閉",
+    "その他-間投",
+    "フィラー",
+    "非言語音",
+];
+
+/// Part-of-speech tags removed by `nori_part_of_speech` when no `stoptags` are given.
+const NORI_DEFAULT_STOP_TAGS: &[&str] = &[
+    "E", "IC", "J", "MAG", "MAJ", "MM", "SP", "SSC", "SSO", "SC", "SE", "XPN", "XSA", "XSN", "XSV",
+    "UNA", "NA", "VSV",
+];
+
+/// The ko-dic tags grouped under the coarse tags of Nori.
+const NORI_TAG_GROUPS: &[(&str, &[&str])] = &[
+    ("E", &["EP", "EF", "EC", "ETN", "ETM"]),
+    (
+        "J",
+        &["JKS", "JKC", "JKG", "JKO", "JKB", "JKV", "JKQ", "JX", "JC"],
+    ),
+];
+
+/// The IPADIC tags of the punctuation discarded by the Kuromoji tokenizer.
+const IPADIC_PUNCTUATION_TAGS: &[&str] = &[
+    "記号",
+    "記号,一般",
+    "記号,読点",
+    "記号,句点",
+    "記号,空白",
+    "記号,括弧開",
+    "記号,括弧閉",
+];
+
+/// The ko-dic tags of the punctuation discarded by the Nori tokenizer.
+const KO_DIC_PUNCTUATION_TAGS: &[&str] = &["SF", "SE", "SSO", "SSC", "SC", "SY"];
+
+/// The `_english_` stop words of Elasticsearch.
+const ENGLISH_STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into", "is", "it",
+    "no", "not", "of", "on", "or", "such", "that", "the", "their", "then", "there", "these",
+    "they", "this", "to", "was", "will", "with",
+];
+
+/// The options of `kuromoji_tokenizer` that are translated or reported specifically.
+const KUROMOJI_TOKENIZER_OPTIONS: &[&str] = &[
+    "mode",
+    "user_dictionary",
+    "user_dictionary_rules",
+    "discard_punctuation",
+    "discard_compound_token",
+];
+
+/// The options of `nori_tokenizer` that are translated or reported specifically.
+const NORI_TOKENIZER_OPTIONS: &[&str] = &[
+    "decompound_mode",
+    "user_dictionary",
+    "user_dictionary_rules",
+    "discard_punctuation",
+];
+
+/// The result of [`convert_analyzer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    /// The Lindera analyzer config.
+    pub config: Value,
+    /// The options of the analyzer that could not be translated, located by JSON pointers into
+    /// the settings.
+    pub issues: Vec<Issue>,
+}
+
+/// Translates an Elasticsearch / OpenSearch analyzer built on the Kuromoji (Japanese) or Nori
+/// (Korean) plugin into a Lindera analyzer config.
+///
+/// `settings` are the index settings, or their `analysis` section. The analyzer to translate is
+/// the one named `analyzer`, or the only analyzer of the settings. The tokenizer options, the
+/// character filters and the token filters that Lindera has no equivalent for are left out and
+/// reported as warnings.
+pub fn convert_analyzer(settings: &Value, analyzer: Option<&str>) -> LinderaResult<Conversion> {
+    let (analysis, analysis_pointer) = find_analysis(settings).ok_or_else(|| {
+        LinderaErrorKind::Args.with_error(anyhow::anyhow!("no analysis settings were found"))
+    })?;
+    let analyzers = analysis
+        .get("analyzer")
+        .and_then(Value::as_object)
+        .ok_or_else(|| {
+            LinderaErrorKind::Args.with_error(anyhow::anyhow!("no analyzer is defined"))
+        })?;
+
+    let name = match analyzer {
+        Some(name) => name.to_string(),
+        None if analyzers.len() == 1 => analyzers.keys().next().cloned().unwrap_or_default(),
+        None => {
+            return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                "the settings define several analyzers, choose one of: {}",
+                analyzers.keys().cloned().collect::<Vec<_>>().join(", ")
+            )));
+        }
+    };
+    let definition = analyzers.get(&name).ok_or_else(|| {
+        LinderaErrorKind::Args.with_error(anyhow::anyhow!("analyzer {name} is not defined"))
+    })?;
+
+    let mut converter = Converter {
+        analysis,
+        analysis_pointer: analysis_pointer.clone(),
+        segmenter: Map::new(),
+        character_filters: Vec::new(),
+        token_filters: Vec::new(),
+        issues: Vec::new(),
+    };
+    converter.analyzer(
+        definition,
+        &join(&join(&analysis_pointer, "analyzer"), &name),
+    )?;
+
+    Ok(Conversion {
+        config: json!({
+            "segmenter": converter.segmenter,
+            "character_filters": converter.character_filters,
+            "token_filters": converter.token_filters,
+        }),
+        issues: converter.issues,
+    })
+}
+
+/// Returns the `analysis` section of the settings and its JSON pointer.
+///
+/// The settings may be given as `{"settings": {"index": {"analysis": ...}}}`, with or without
+/// `settings` or `index`, as the response of `GET <index>/_settings`, or as the `analysis`
+/// section itself.
+fn find_analysis(settings: &Value) -> Option<(&Value, String)> {
+    const PATHS: &[&[&str]] = &[
+        &["settings", "index", "analysis"],
+        &["settings", "analysis"],
+        &["index", "analysis"],
+        &["analysis"],
+    ];
+
+    fn find<'a>(value: &'a Value, prefix: &str) -> Option<(&'a Value, String)> {
+        PATHS.iter().find_map(|path| {
+            let analysis = path.iter().try_fold(value, |value, key| value.get(key))?;
+            let pointer = path
+                .iter()
+                .fold(prefix.to_string(), |pointer, key| join(&pointer, key));
+            Some((analysis, pointer))
+        })
+    }
+
+    if let Some(found) = find(settings, "") {
+        return Some(found);
+    }
+    // The response of `GET <index>/_settings` is keyed by the name of the index
+    if let Some(object) = settings.as_object()
+        && object.len() == 1
+        && let Some((index, value)) = object.iter().next()
+        && let Some(found) = find(value, &join("", index))
+    {
+        return Some(found);
+    }
+    settings
+        .get("analyzer")
+        .is_some()
+        .then(|| (settings, String::new()))
+}
+
+/// Appends a reference token to a JSON pointer (RFC 6901).
+fn join(pointer: &str, token: &str) -> String {
+    format!("{pointer}/{}", token.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Returns the string options of a component as a list, accepting a single string too.
+fn strings(value: Option<&Value>) -> Option<Vec<String>> {
+    match value? {
+        Value::String(value) => Some(vec![value.clone()]),
+        Value::Array(values) => Some(
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(str::to_string))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+struct Converter<'a> {
+    analysis: &'a Value,
+    analysis_pointer: String,
+    segmenter: Map<String, Value>,
+    character_filters: Vec<Value>,
+    token_filters: Vec<Value>,
+    issues: Vec<Issue>,
+}
+
+impl Converter<'_> {
+    fn warning(&mut self, pointer: String, message: impl Into<String>) {
+        self.issues.push(Issue {
+            severity: Severity::Warning,
+            pointer,
+            message: message.into(),
+        });
+    }
+
+    /// Reports the options of a component other than `known` as left out.
+    fn unsupported_options(&mut self, options: &Value, pointer: &str, known: &[&str]) {
+        let Some(options) = options.as_object() else {
+            return;
+        };
+        for key in options.keys() {
+            if key != "type" && !known.contains(&key.as_str()) {
+                self.warning(
+                    join(pointer, key),
+                    format!("{key} is not supported by Lindera and was left out"),
+                );
+            }
+        }
+    }
+
+    fn character_filter(&mut self, kind: &str, args: Value) {
+        self.character_filters
+            .push(json!({ "kind": kind, "args": args }));
+    }
+
+    fn token_filter(&mut self, kind: &str, args: Value) {
+        self.token_filters
+            .push(json!({ "kind": kind, "args": args }));
+    }
+
+    fn analyzer(&mut self, definition: &Value, pointer: &str) -> LinderaResult<()> {
+        match definition.get("type").and_then(Value::as_str) {
+            Some("kuromoji") => {
+                let mut known = KUROMOJI_TOKENIZER_OPTIONS.to_vec();
+                known.extend(["stoptags", "stopwords", "stopwords_path"]);
+                self.unsupported_options(definition, pointer, &known);
+
+                // The tokenizer and the filters of the prebuilt `kuromoji` analyzer, taking their
+                // options from the analyzer
+                self.kuromoji_tokenizer(definition, pointer);
+                for filter_type in [
+                    "kuromoji_baseform",
+                    "kuromoji_part_of_speech",
+                    "cjk_width",
+                    "ja_stop",
+                    "kuromoji_stemmer",
+                    "lowercase",
+                ] {
+                    self.token_filter_of(filter_type, definition, pointer);
+                }
+                Ok(())
+            }
+            Some("nori") => {
+                let mut known = NORI_TOKENIZER_OPTIONS.to_vec();
+                known.push("stoptags");
+                self.unsupported_options(definition, pointer, &known);
+
+                // The tokenizer and the filters of the prebuilt `nori` analyzer
+                self.nori_tokenizer(definition, pointer);
+                for filter_type in ["nori_part_of_speech", "nori_readingform", "lowercase"] {
+                    self.token_filter_of(filter_type, definition, pointer);
+                }
+                Ok(())
+            }
+            Some("custom") | None => self.custom_analyzer(definition, pointer),
+            Some(analyzer_type) => Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                "the {analyzer_type} analyzer cannot be translated, only the kuromoji, nori and custom analyzers can"
+            ))),
+        }
+    }
+
+    fn custom_analyzer(&mut self, definition: &Value, pointer: &str) -> LinderaResult<()> {
+        self.unsupported_options(definition, pointer, &["tokenizer", "char_filter", "filter"]);
+
+        let tokenizer = definition.get("tokenizer").ok_or_else(|| {
+            LinderaErrorKind::Args.with_error(anyhow::anyhow!("the analyzer has no tokenizer"))
+        })?;
+        let (tokenizer, tokenizer_pointer) =
+            self.resolve(tokenizer, "tokenizer", &join(pointer, "tokenizer"));
+        match tokenizer.get("type").and_then(Value::as_str) {
+            Some("kuromoji_tokenizer") => {
+                self.unsupported_options(
+                    &tokenizer,
+                    &tokenizer_pointer,
+                    KUROMOJI_TOKENIZER_OPTIONS,
+                );
+                self.kuromoji_tokenizer(&tokenizer, &tokenizer_pointer);
+            }
+            Some("nori_tokenizer") => {
+                self.unsupported_options(&tokenizer, &tokenizer_pointer, NORI_TOKENIZER_OPTIONS);
+                self.nori_tokenizer(&tokenizer, &tokenizer_pointer);
+            }
+            tokenizer_type => {
+                return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                    "the {} tokenizer cannot be translated, only kuromoji_tokenizer and nori_tokenizer can",
+                    tokenizer_type.unwrap_or("untyped")
+                )));
+            }
+        }
+
+        for (key, component) in [
+            ("char_filter", "character filter"),
+            ("filter", "token filter"),
+        ] {
+            let entries = match definition.get(key) {
+                Some(Value::Array(entries)) => entries.clone(),
+                Some(entry) => vec![entry.clone()],
+                None => Vec::new(),
+            };
+            for (index, entry) in entries.iter().enumerate() {
+                let entry_pointer = join(&join(pointer, key), &index.to_string());
+                let (filter, filter_pointer) = self.resolve(entry, key, &entry_pointer);
+                let Some(filter_type) = filter.get("type").and_then(Value::as_str) else {
+                    self.warning(
+                        filter_pointer,
+                        format!("the {component} has no type and was left out"),
+                    );
+                    continue;
+                };
+                let known = if key == "char_filter" {
+                    self.character_filter_of(filter_type, &filter, &filter_pointer)
+                } else {
+                    self.token_filter_of(filter_type, &filter, &filter_pointer)
+                };
+                match known {
+                    Some(known) => self.unsupported_options(&filter, &filter_pointer, known),
+                    None => self.warning(
+                        filter_pointer,
+                        format!(
+                            "the {filter_type} {component} is not supported by Lindera and was left out"
+                        ),
+                    ),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolves a component of an analyzer: the name of a component defined in `section` of the
+    /// analysis settings, the name of a prebuilt component, or an inline definition.
+    fn resolve(&self, entry: &Value, section: &str, pointer: &str) -> (Value, String) {
+        let Some(name) = entry.as_str() else {
+            return (entry.clone(), pointer.to_string());
+        };
+        match self
+            .analysis
+            .get(section)
+            .and_then(|defined| defined.get(name))
+        {
+            Some(definition) => (
+                definition.clone(),
+                join(&join(&self.analysis_pointer, section), name),
+            ),
+            None => (json!({ "type": name }), pointer.to_string()),
+        }
+    }
+
+    fn kuromoji_tokenizer(&mut self, options: &Value, pointer: &str) {
+        self.segmenter
+            .insert("dictionary".to_string(), json!("embedded://ipadic"));
+
+        // The Kuromoji tokenizer defaults to the search mode
+        let mode = options
+            .get("mode")
+            .and_then(Value::as_str)
+            .unwrap_or("search");
+        let mode = match mode {
+            "normal" => "normal",
+            "search" => "decompose",
+            "extended" => {
+                self.warning(
+                    join(pointer, "mode"),
+                    "Lindera does not split unknown words into characters as the extended mode does, the decompose mode is used",
+                );
+                "decompose"
+            }
+            mode => {
+                self.warning(
+                    join(pointer, "mode"),
+                    format!("unknown mode {mode}, the decompose mode is used"),
+                );
+                "decompose"
+            }
+        };
+        if mode == "decompose"
+            && options
+                .get("discard_compound_token")
+                .and_then(Value::as_bool)
+                != Some(true)
+        {
+            self.warning(
+                join(pointer, "discard_compound_token"),
+                "Lindera outputs the parts of compound words without the compound words themselves, as with discard_compound_token",
+            );
+        }
+        self.segmenter.insert("mode".to_string(), json!(mode));
+
+        self.user_dictionary(options, pointer, "Kuromoji");
+        if options.get("discard_punctuation").and_then(Value::as_bool) != Some(false) {
+            self.token_filter(
+                "japanese_stop_tags",
+                json!({ "tags": IPADIC_PUNCTUATION_TAGS }),
+            );
+        }
+    }
+
+    fn nori_tokenizer(&mut self, options: &Value, pointer: &str) {
+        self.segmenter
+            .insert("dictionary".to_string(), json!("embedded://ko-dic"));
+        self.segmenter.insert("mode".to_string(), json!("normal"));
+
+        // The Nori tokenizer defaults to discarding the compound words
+        let decompound_mode = options
+            .get("decompound_mode")
+            .and_then(Value::as_str)
+            .unwrap_or("discard");
+        match decompound_mode {
+            "none" => {}
+            "discard" | "mixed" => {
+                self.token_filter("korean_decompound", json!({ "mode": decompound_mode }));
+            }
+            mode => {
+                self.warning(
+                    join(pointer, "decompound_mode"),
+                    format!("unknown decompound_mode {mode}, the discard mode is used"),
+                );
+                self.token_filter("korean_decompound", json!({ "mode": "discard" }));
+            }
+        }
+
+        self.user_dictionary(options, pointer, "Nori");
+        if options.get("discard_punctuation").and_then(Value::as_bool) != Some(false) {
+            self.token_filter(
+                "korean_stop_tags",
+                json!({ "tags": KO_DIC_PUNCTUATION_TAGS }),
+            );
+        }
+    }
+
+    fn user_dictionary(&mut self, options: &Value, pointer: &str, plugin: &str) {
+        if let Some(path) = options.get("user_dictionary").and_then(Value::as_str) {
+            self.warning(
+                join(pointer, "user_dictionary"),
+                format!(
+                    "{plugin} user dictionaries are not in the format of Lindera, convert {path} to a Lindera user dictionary and set segmenter.user_dictionary"
+                ),
+            );
+        }
+        if options.get("user_dictionary_rules").is_some() {
+            self.warning(
+                join(pointer, "user_dictionary_rules"),
+                "inline user dictionary rules are not supported, write them to a Lindera user dictionary and set segmenter.user_dictionary",
+            );
+        }
+    }
+
+    /// Appends the Lindera equivalent of a character filter, returning the options it supports,
+    /// or `None` if it has no equivalent.
+    fn character_filter_of(
+        &mut self,
+        filter_type: &str,
+        options: &Value,
+        pointer: &str,
+    ) -> Option<&'static [&'static str]> {
+        match filter_type {
+            "icu_normalizer" => {
+                let name = options
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .unwrap_or("nfkc_cf");
+                let decompose = options.get("mode").and_then(Value::as_str) == Some("decompose");
+                let kind = match (name, decompose) {
+                    ("nfc", false) => "nfc",
+                    ("nfc", true) => "nfd",
+                    ("nfkc" | "nfkc_cf", false) => "nfkc",
+                    ("nfkc" | "nfkc_cf", true) => "nfkd",
+                    (name, _) => {
+                        self.warning(
+                            join(pointer, "name"),
+                            format!("unknown normalization {name}, the filter was left out"),
+                        );
+                        return Some(&["name", "mode"]);
+                    }
+                };
+                if name == "nfkc_cf" {
+                    self.warning(
+                        join(pointer, "name"),
+                        "the case folding of nfkc_cf is not applied by the unicode_normalize character filter, add the lowercase token filter",
+                    );
+                }
+                self.character_filter("unicode_normalize", json!({ "kind": kind }));
+                Some(&["name", "mode"])
+            }
+            "kuromoji_iteration_mark" => {
+                let flag = |key| options.get(key).and_then(Value::as_bool).unwrap_or(true);
+                let args = json!({
+                    "normalize_kanji": flag("normalize_kanji"),
+                    "normalize_kana": flag("normalize_kana"),
+                });
+                self.character_filter("japanese_iteration_mark", args);
+                Some(&["normalize_kanji", "normalize_kana"])
+            }
+            "mapping" => {
+                let mut mapping = Map::new();
+                for (index, rule) in strings(options.get("mappings"))
+                    .unwrap_or_default()
+                    .iter()
+                    .enumerate()
+                {
+                    match rule.split_once("=>") {
+                        Some((from, to)) if !from.trim().is_empty() => {
+                            mapping.insert(from.trim().to_string(), json!(to.trim()));
+                        }
+                        _ => self.warning(
+                            join(&join(pointer, "mappings"), &index.to_string()),
+                            format!("invalid mapping {rule}, expected `from => to`"),
+                        ),
+                    }
+                }
+                if !mapping.is_empty() {
+                    self.character_filter("mapping", json!({ "mapping": mapping }));
+                }
+                Some(&["mappings"])
+            }
+            "pattern_replace" => {
+                match options.get("pattern").and_then(Value::as_str) {
+                    Some(pattern) => {
+                        let replacement = options
+                            .get("replacement")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default();
+                        self.character_filter(
+                            "regex",
+                            json!({ "pattern": pattern, "replacement": replacement }),
+                        );
+                    }
+                    None => self.warning(
+                        join(pointer, "pattern"),
+                        "the filter has no pattern and was left out",
+                    ),
+                }
+                Some(&["pattern", "replacement"])
+            }
+            _ => None,
+        }
+    }
+
+    /// Appends the Lindera equivalent of a token filter, returning the options it supports, or
+    /// `None` if it has no equivalent.
+    fn token_filter_of(
+        &mut self,
+        filter_type: &str,
+        options: &Value,
+        pointer: &str,
+    ) -> Option<&'static [&'static str]> {
+        match filter_type {
+            "kuromoji_baseform" => {
+                self.token_filter("japanese_base_form", json!({}));
+                Some(&[])
+            }
+            "kuromoji_part_of_speech" => {
+                let tags = strings(options.get("stoptags")).unwrap_or_else(|| {
+                    KUROMOJI_DEFAULT_STOP_TAGS
+                        .iter()
+                        .map(|tag| tag.to_string())
+                        .collect()
+                });
+                // Kuromoji joins the levels of the tags with `-`, Lindera with `,`
+                let tags = tags
+                    .iter()
+                    .map(|tag| tag.replace('-', ","))
+                    .collect::<Vec<_>>();
+                self.token_filter("japanese_stop_tags", json!({ "tags": tags }));
+                Some(&["stoptags"])
+            }
+            "kuromoji_readingform" => {
+                if options.get("use_romaji").and_then(Value::as_bool) == Some(true) {
+                    self.warning(
+                        join(pointer, "use_romaji"),
+                        "readings in romaji are not supported, the readings are in katakana",
+                    );
+                }
+                self.token_filter("japanese_reading_form", json!({}));
+                Some(&["use_romaji"])
+            }
+            "kuromoji_stemmer" => {
+                // Kuromoji stems words of at least `minimum_length` characters, Lindera words
+                // longer than `min`
+                let minimum_length = options
+                    .get("minimum_length")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(4);
+                self.token_filter(
+                    "japanese_katakana_stem",
+                    json!({ "min": minimum_length.saturating_sub(1).max(1) }),
+                );
+                Some(&["minimum_length"])
+            }
+            "kuromoji_number" => {
+                self.token_filter("japanese_number", json!({}));
+                Some(&[])
+            }
+            "ja_stop" | "stop" => {
+                let default = if filter_type == "ja_stop" {
+                    "_japanese_"
+                } else {
+                    "_english_"
+                };
+                let mut words = Vec::new();
+                match options.get("stopwords").unwrap_or(&json!(default)) {
+                    Value::String(list) if list == "_english_" => {
+                        words.extend(ENGLISH_STOP_WORDS.iter().map(|word| word.to_string()));
+                    }
+                    Value::String(list) if list == "_none_" => {}
+                    Value::String(list) => self.warning(
+                        join(pointer, "stopwords"),
+                        format!(
+                            "the predefined {list} stop words are not bundled with Lindera, give the words or a file of them"
+                        ),
+                    ),
+                    stopwords => words.extend(strings(Some(stopwords)).unwrap_or_default()),
+                }
+                let paths = strings(options.get("stopwords_path")).unwrap_or_default();
+                if !words.is_empty() || !paths.is_empty() {
+                    let mut args = json!({ "words": words });
+                    if !paths.is_empty() {
+                        args["paths"] = json!(paths);
+                    }
+                    self.token_filter("stop_words", args);
+                }
+                Some(&["stopwords", "stopwords_path"])
+            }
+            "nori_part_of_speech" => {
+                let tags = strings(options.get("stoptags")).unwrap_or_else(|| {
+                    NORI_DEFAULT_STOP_TAGS
+                        .iter()
+                        .map(|tag| tag.to_string())
+                        .collect()
+                });
+                let tags = tags
+                    .iter()
+                    .flat_map(
+                        |tag| match NORI_TAG_GROUPS.iter().find(|(group, _)| group == tag) {
+                            Some((_, tags)) => tags.iter().map(|tag| tag.to_string()).collect(),
+                            None => vec![tag.clone()],
+                        },
+                    )
+                    .collect::<Vec<_>>();
+                self.token_filter("korean_stop_tags", json!({ "tags": tags }));
+                Some(&["stoptags"])
+            }
+            "nori_readingform" => {
+                self.token_filter("korean_reading_form", json!({}));
+                Some(&[])
+            }
+            "cjk_width" | "lowercase" | "uppercase" => {
+                self.token_filter(filter_type, json!({}));
+                Some(&[])
+            }
+            "length" => {
+                let mut args = Map::new();
+                for key in ["min", "max"] {
+                    if let Some(value) = options.get(key).and_then(Value::as_u64) {
+                        args.insert(key.to_string(), json!(value));
+                    }
+                }
+                self.token_filter("length", Value::Object(args));
+                Some(&["min", "max"])
+            }
+            "truncate" => {
+                let length = options.get("length").and_then(Value::as_u64).unwrap_or(10);
+                self.token_filter("truncate", json!({ "length": length }));
+                Some(&["length"])
+            }
+            "limit" => {
+                let max_token_count = options
+                    .get("max_token_count")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(1);
+                self.token_filter(
+                    "limit_token_count",
+                    json!({ "max_token_count": max_token_count }),
+                );
+                Some(&["max_token_count"])
+            }
+            "unique" => {
+                let same_position = options
+                    .get("only_on_same_position")
+                    .and_then(Value::as_bool)
+                    == Some(true);
+                let mode = if same_position {
+                    "same_position"
+                } else {
+                    "all"
+                };
+                self.token_filter("unique", json!({ "mode": mode }));
+                Some(&["only_on_same_position"])
+            }
+            "keep" => {
+                match strings(options.get("keep_words")) {
+                    Some(words) => self.token_filter("keep_words", json!({ "words": words })),
+                    None => self.warning(
+                        join(pointer, "keep_words"),
+                        "the filter has no keep_words and was left out",
+                    ),
+                }
+                Some(&["keep_words"])
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lindera::character_filter::CharacterFilterLoader;
+    use lindera::token_filter::TokenFilterLoader;
+    use serde_json::json;
+
+    use super::*;
+
+    fn kinds(filters: &Value) -> Vec<&str> {
+        filters
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|filter| filter["kind"].as_str().unwrap())
+            .collect()
+    }
+
+    fn pointers(conversion: &Conversion) -> Vec<&str> {
+        conversion
+            .issues
+            .iter()
+            .map(|issue| issue.pointer.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_find_analysis() {
+        let analysis = json!({"analyzer": {"ja": {"type": "kuromoji"}}});
+        for (settings, expected) in [
+            (
+                json!({"settings": {"index": {"analysis": analysis}}}),
+                "/settings/index/analysis",
+            ),
+            (
+                json!({"settings": {"analysis": analysis}}),
+                "/settings/analysis",
+            ),
+            (json!({"index": {"analysis": analysis}}), "/index/analysis"),
+            (
+                json!({"my-index": {"settings": {"index": {"analysis": analysis}}}}),
+                "/my-index/settings/index/analysis",
+            ),
+            (analysis.clone(), ""),
+        ] {
+            let (found, pointer) = find_analysis(&settings).unwrap();
+            assert_eq!(&analysis, found);
+            assert_eq!(expected, pointer);
+        }
+
+        assert!(find_analysis(&json!({"settings": {}})).is_none());
+    }
+
+    #[test]
+    fn test_convert_kuromoji_analyzer() {
+        let settings = json!({
+            "analysis": {
+                "analyzer": {
+                    "ja": {
+                        "type": "kuromoji",
+                        "mode": "normal",
+                        "stoptags": ["助詞-格助詞-一般"],
+                        "stopwords": ["あれ"],
+                    }
+                }
+            }
+        });
+        let conversion = convert_analyzer(&settings, None).unwrap();
+
+        assert_eq!(
+            json!({"dictionary": "embedded://ipadic", "mode": "normal"}),
+            conversion.config["segmenter"]
+        );
+        assert_eq!(
+            vec![
+                "japanese_stop_tags",
+                "japanese_base_form",
+                "japanese_stop_tags",
+                "cjk_width",
+                "stop_words",
+                "japanese_katakana_stem",
+                "lowercase",
+            ],
+            kinds(&conversion.config["token_filters"])
+        );
+        assert_eq!(
+            json!({"tags": ["助詞,格助詞,一般"]}),
+            conversion.config["token_filters"][2]["args"]
+        );
+        assert_eq!(
+            json!({"min": 3}),
+            conversion.config["token_filters"][5]["args"]
+        );
+        assert!(conversion.issues.is_empty());
+    }
+
+    #[test]
+    fn test_convert_custom_analyzer() {
+        let settings = json!({
+            "settings": {
+                "index": {
+                    "analysis": {
+                        "char_filter": {
+                            "ja_map": {"type": "mapping", "mappings": ["リンデラ => Lindera"]}
+                        },
+                        "tokenizer": {
+                            "ja_tokenizer": {
+                                "type": "kuromoji_tokenizer",
+                                "mode": "search",
+                                "discard_compound_token": true,
+                                "discard_punctuation": false,
+                                "user_dictionary": "userdict_ja.txt",
+                                "nbest_cost": 1000,
+                            }
+                        },
+                        "filter": {
+                            "ja_stop": {"type": "ja_stop", "stopwords": "_japanese_"}
+                        },
+                        "analyzer": {
+                            "ja": {
+                                "type": "custom",
+                                "tokenizer": "ja_tokenizer",
+                                "char_filter": ["kuromoji_iteration_mark", "ja_map", "html_strip"],
+                                "filter": [
+                                    "kuromoji_baseform",
+                                    "ja_stop",
+                                    {"type": "limit", "max_token_count": 100, "consume_all_tokens": true},
+                                ],
+                            },
+                            "other": {"type": "standard"},
+                        }
+                    }
+                }
+            }
+        });
+        let conversion = convert_analyzer(&settings, Some("ja")).unwrap();
+
+        assert_eq!(
+            json!({"dictionary": "embedded://ipadic", "mode": "decompose"}),
+            conversion.config["segmenter"]
+        );
+        assert_eq!(
+            vec!["japanese_iteration_mark", "mapping"],
+            kinds(&conversion.config["character_filters"])
+        );
+        assert_eq!(
+            json!({"mapping": {"リンデラ": "Lindera"}}),
+            conversion.config["character_filters"][1]["args"]
+        );
+        assert_eq!(
+            vec!["japanese_base_form", "limit_token_count"],
+            kinds(&conversion.config["token_filters"])
+        );
+        assert_eq!(
+            vec![
+                "/settings/index/analysis/tokenizer/ja_tokenizer/nbest_cost",
+                "/settings/index/analysis/tokenizer/ja_tokenizer/user_dictionary",
+                "/settings/index/analysis/analyzer/ja/char_filter/2",
+                "/settings/index/analysis/filter/ja_stop/stopwords",
+                "/settings/index/analysis/analyzer/ja/filter/2/consume_all_tokens",
+            ],
+            pointers(&conversion)
+        );
+
+        // The translated filters are valid Lindera filters
+        for filter in conversion.config["character_filters"].as_array().unwrap() {
+            CharacterFilterLoader::load_from_value(
+                filter["kind"].as_str().unwrap(),
+                &filter["args"],
+            )
+            .unwrap();
+        }
+        for filter in conversion.config["token_filters"].as_array().unwrap() {
+            TokenFilterLoader::load_from_value(filter["kind"].as_str().unwrap(), &filter["args"])
+                .unwrap();
+        }
+
+        assert!(convert_analyzer(&settings, None).is_err());
+        assert!(convert_analyzer(&settings, Some("other")).is_err());
+        assert!(convert_analyzer(&settings, Some("missing")).is_err());
+    }
+
+    #[test]
+    fn test_convert_nori_analyzer() {
+        let settings = json!({
+            "analysis": {
+                "analyzer": {
+                    "ko": {"type": "nori", "decompound_mode": "none", "stoptags": ["E", "NNP"]}
+                }
+            }
+        });
+        let conversion = convert_analyzer(&settings, None).unwrap();
+
+        assert_eq!(
+            json!({"dictionary": "embedded://ko-dic", "mode": "normal"}),
+            conversion.config["segmenter"]
+        );
+        assert_eq!(
+            vec![
+                "korean_stop_tags",
+                "korean_stop_tags",
+                "korean_reading_form",
+                "lowercase"
+            ],
+            kinds(&conversion.config["token_filters"])
+        );
+        // The coarse tags of Nori are expanded to the tags of ko-dic
+        assert_eq!(
+            json!({"tags": ["EP", "EF", "EC", "ETN", "ETM", "NNP"]}),
+            conversion.config["token_filters"][1]["args"]
+        );
+        assert!(conversion.issues.is_empty());
+
+        // The compound words are split as in the discard mode by default
+        let settings = json!({
+            "analysis": {
+                "analyzer": {
+                    "ko": {"type": "custom", "tokenizer": "ko_tokenizer"}
+                },
+                "tokenizer": {
+                    "ko_tokenizer": {"type": "nori_tokenizer", "decompound_mode": "mixed"}
+                }
+            }
+        });
+        let conversion = convert_analyzer(&settings, None).unwrap();
+        assert_eq!(
+            vec!["korean_decompound", "korean_stop_tags"],
+            kinds(&conversion.config["token_filters"])
+        );
+        assert_eq!(
+            json!({"mode": "mixed"}),
+            conversion.config["token_filters"][0]["args"]
+        );
+        assert!(conversion.issues.is_empty());
+    }
+}
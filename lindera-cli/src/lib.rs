@@ -1,3 +1,9 @@
+pub mod config;
+pub mod convert;
+pub mod repl;
+pub mod segment;
+pub mod validate;
+
 const VERERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub fn get_version() -> &'static str {
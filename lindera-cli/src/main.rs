@@ -1,19 +1,45 @@
-use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use std::borrow::Cow;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Write};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 use clap::{Parser, Subcommand};
 
 use lindera::LinderaResult;
 use lindera::character_filter::CharacterFilterLoader;
-use lindera::dictionary::{DictionaryBuilder, DictionaryKind, Metadata};
+use lindera::dictionary::diff::diff_dictionaries;
+use lindera::dictionary::export::{export_dictionary, export_user_dictionary};
+use lindera::dictionary::mozc::MozcImporter;
+use lindera::dictionary::registry::locate_dictionary;
+use lindera::dictionary::skk::SkkImporter;
+use lindera::dictionary::{
+    BuildJob, BuildProgress, DictionaryBuilder, DictionaryKind, DuplicatePolicy, MatrixEncoding,
+    Metadata, Schema, format, format_timestamp, load_dictionary, load_reading_index,
+    load_user_dictionary_from_bin, peak_resident_memory,
+};
 use lindera::error::{LinderaError, LinderaErrorKind};
 use lindera::mode::Mode;
 use lindera::token::Token;
 use lindera::token_filter::TokenFilterLoader;
-use lindera::tokenizer::TokenizerBuilder;
+use lindera::tokenizer::compare::{Comparison, compare_tokenizers};
+use lindera::tokenizer::elasticsearch::AnalyzeResponse;
+use lindera::tokenizer::eval::{CorpusFormat, Score, evaluate, parse_corpus};
+use lindera::tokenizer::explain::Explanation;
+use lindera::tokenizer::golden::{read_corpus, run_golden_corpus};
+use lindera::tokenizer::graph::TokenGraph;
+use lindera::tokenizer::review::{DEFAULT_THETA, ReviewSelector, to_review_corpus};
+use lindera::tokenizer::schema;
+use lindera::tokenizer::{Tokenizer, TokenizerBuilder};
+use lindera_cli::config::{CliConfig, ProfileConfig};
+use lindera_cli::convert;
 use lindera_cli::get_version;
+use lindera_cli::repl::Repl;
+use lindera_cli::segment::{
+    self, Corpus, DEFAULT_CHUNK_SIZE, SENTENCE_TERMINATORS, SegmentOptions,
+};
+use lindera_cli::validate::{Severity, message, read_config, read_config_strict, validate_config};
 
 #[derive(Debug, Parser)]
 #[clap(
@@ -31,11 +57,32 @@ struct Args {
 enum Commands {
     List(ListArgs),
     Tokenize(TokenizeArgs),
+    Segment(SegmentArgs),
     Build(BuildArgs),
+    Bench(BenchArgs),
+    Compare(CompareArgs),
+    Test(TestArgs),
+    Eval(EvalArgs),
+    Review(ReviewArgs),
+    Repl(ReplArgs),
+    Validate(ValidateArgs),
+    ConfigSchema(ConfigSchemaArgs),
+    ValidateUserdict(ValidateUserdictArgs),
+    ImportSkk(ImportSkkArgs),
+    ImportMozc(ImportMozcArgs),
+    ConvertAnalyzer(ConvertAnalyzerArgs),
+    MigrateDict(MigrateDictArgs),
+    DumpFormat(DumpFormatArgs),
+    DictInfo(DictInfoArgs),
+    ExportDict(ExportDictArgs),
+    DiffDict(DiffDictArgs),
+    Candidates(CandidatesArgs),
     #[cfg(feature = "train")]
     Train(TrainArgs),
     #[cfg(feature = "train")]
     Export(ExportArgs),
+    #[cfg(feature = "signature")]
+    Sign(SignArgs),
 }
 
 #[derive(Debug, clap::Args)]
@@ -53,20 +100,30 @@ struct ListArgs {}
     version = get_version(),
 )]
 struct TokenizeArgs {
+    #[clap(
+        long = "config",
+        help = "CLI config file (TOML) defining dictionary, filters, mode and output format"
+    )]
+    config: Option<PathBuf>,
+    #[clap(
+        short = 'p',
+        long = "profile",
+        requires = "config",
+        help = "Named profile in the config file to use (e.g., index, query)"
+    )]
+    profile: Option<String>,
     #[clap(
         short = 'd',
         long = "dict",
-        required = true,
         help = "Dictionary directory path or URI (e.g., embedded://ipadic, /path/to/dictionary)"
     )]
-    dict: String,
+    dict: Option<String>,
     #[clap(
         short = 'o',
         long = "output",
-        default_value = "mecab",
-        help = "Output format (mecab|wakati|json)"
+        help = "Output format (mecab|wakati|json|graph|elasticsearch) [default: mecab]"
     )]
-    output: String,
+    output: Option<String>,
     #[clap(
         short = 'u',
         long = "user-dict",
@@ -76,10 +133,9 @@ struct TokenizeArgs {
     #[clap(
         short = 'm',
         long = "mode",
-        default_value = "normal",
-        help = "Tokenization mode (normal|decompose)"
+        help = "Tokenization mode (normal|decompose) [default: normal]"
     )]
-    mode: Mode,
+    mode: Option<Mode>,
     #[clap(
         short = 'c',
         long = "char-filter",
@@ -94,13 +150,311 @@ struct TokenizeArgs {
     token_filters: Option<Vec<String>>,
     #[clap(
         long = "keep-whitespace",
-        help = "Keep whitespace tokens in output (default: whitespace is ignored for MeCab compatibility)"
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        help = "Keep whitespace tokens in output (default: whitespace is ignored for MeCab compatibility). --keep-whitespace=false overrides the config file"
+    )]
+    keep_whitespace: Option<bool>,
+    #[clap(
+        long = "okurigana-variants",
+        help = "Also look up the words spelled with some okurigana left out (needs a dictionary built with --okurigana-variants)"
+    )]
+    okurigana_variants: bool,
+    #[clap(
+        short = 'f',
+        long = "fields",
+        value_delimiter = ',',
+        help = "Comma-separated token fields to output, in order (e.g., surface,part_of_speech,base_form,reading)"
+    )]
+    fields: Option<Vec<String>>,
+    #[clap(
+        long = "delimiter",
+        help = "Delimiter between the fields selected with --fields (default: \\t)"
+    )]
+    delimiter: Option<String>,
+    #[clap(
+        long = "separator",
+        help = "Separator between tokens in wakati output (default: a single space)"
+    )]
+    separator: Option<String>,
+    #[clap(
+        long = "quote",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        help = "Quote tokens containing the separator, a double quote or a line break in wakati output. --quote=false overrides the config file"
+    )]
+    quote: Option<bool>,
+    #[clap(
+        long = "sentence-per-line",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        help = "Write one sentence per line in wakati output. --sentence-per-line=false overrides the config file"
+    )]
+    sentence_per_line: Option<bool>,
+    #[clap(
+        long = "explain",
+        help = "Output the text after each character filter and the tokens after each token filter as JSON instead of the tokens"
     )]
-    keep_whitespace: bool,
+    explain: bool,
     #[clap(help = "Input text file (default: stdin)")]
     input_file: Option<PathBuf>,
 }
 
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Segment a corpus into wakati text on all cores for machine learning preprocessing",
+    version = get_version(),
+)]
+struct SegmentArgs {
+    #[clap(
+        short = 'd',
+        long = "dict",
+        required = true,
+        help = "Dictionary directory path or URI (e.g., embedded://ipadic, /path/to/dictionary)"
+    )]
+    dict: String,
+    #[clap(
+        short = 'u',
+        long = "user-dict",
+        help = "User dictionary path or URI (optional)"
+    )]
+    user_dict: Option<String>,
+    #[clap(
+        short = 'm',
+        long = "mode",
+        default_value = "normal",
+        help = "Tokenization mode (normal|decompose)"
+    )]
+    mode: Mode,
+    #[clap(
+        short = 'j',
+        long = "threads",
+        help = "Number of threads [default: number of CPUs]"
+    )]
+    threads: Option<usize>,
+    #[clap(
+        long = "chunk-size",
+        default_value_t = DEFAULT_CHUNK_SIZE,
+        help = "Size in bytes of the chunks of input handed to each thread"
+    )]
+    chunk_size: usize,
+    #[clap(
+        long = "separator",
+        help = "Separator between tokens (default: a single space)"
+    )]
+    separator: Option<String>,
+    #[clap(
+        long = "sentence-per-line",
+        help = "Write one sentence per line and skip empty lines"
+    )]
+    sentence_per_line: bool,
+    #[clap(short = 'o', long = "output", help = "Output file (default: stdout)")]
+    output: Option<PathBuf>,
+    #[clap(
+        short = 'q',
+        long = "quiet",
+        help = "Do not report the throughput on stderr"
+    )]
+    quiet: bool,
+    #[clap(help = "Input text file, memory-mapped (default: stdin)")]
+    input_file: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Tokenize text with several dictionaries and show where their segmentations differ",
+    version = get_version(),
+)]
+struct CompareArgs {
+    #[clap(
+        short = 'd',
+        long = "dict",
+        required = true,
+        num_args = 1,
+        help = "Dictionary directory path or URI to compare (specify two or more)"
+    )]
+    dicts: Vec<String>,
+    #[clap(
+        short = 'm',
+        long = "mode",
+        default_value = "normal",
+        help = "Tokenization mode (normal|decompose)"
+    )]
+    mode: Mode,
+    #[clap(
+        long = "diff-only",
+        help = "Only print the lines the dictionaries segment differently"
+    )]
+    diff_only: bool,
+    #[clap(
+        short = 'o',
+        long = "output",
+        default_value = "text",
+        help = "Output format (text|json, one JSON object per line)"
+    )]
+    output: String,
+    #[clap(help = "Input text file, one text per line (default: stdin)")]
+    input_file: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Score the segmentations and parts-of-speech against an annotated corpus",
+    version = get_version(),
+)]
+struct EvalArgs {
+    #[clap(long = "corpus", help = "Annotated corpus file")]
+    corpus: PathBuf,
+    #[clap(
+        long = "format",
+        default_value = "conllu",
+        help = "Format of the annotated corpus (conllu|mecab)"
+    )]
+    format: String,
+    #[clap(
+        long = "config",
+        help = "CLI config file (TOML) defining dictionary, filters and mode"
+    )]
+    config: Option<PathBuf>,
+    #[clap(
+        short = 'p',
+        long = "profile",
+        requires = "config",
+        help = "Named profile in the config file to use (e.g., index, query)"
+    )]
+    profile: Option<String>,
+    #[clap(short = 'd', long = "dict", help = "Dictionary directory path or URI")]
+    dict: Option<String>,
+    #[clap(short = 'u', long = "user-dict", help = "User dictionary path or URI")]
+    user_dict: Option<String>,
+    #[clap(
+        short = 'm',
+        long = "mode",
+        help = "Tokenization mode (normal|decompose) [default: normal]"
+    )]
+    mode: Option<Mode>,
+    #[clap(
+        long = "pos-depth",
+        default_value = "1",
+        help = "Number of part-of-speech levels to compare"
+    )]
+    pos_depth: usize,
+    #[clap(
+        short = 'o',
+        long = "output",
+        default_value = "text",
+        help = "Output format (text|json)"
+    )]
+    output: String,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Export the sentences of a corpus the segmentation is the least confident about",
+    version = get_version(),
+)]
+struct ReviewArgs {
+    #[clap(long = "corpus", help = "Corpus file, one text per line")]
+    corpus: PathBuf,
+    #[clap(
+        long = "config",
+        help = "CLI config file (TOML) defining dictionary and mode"
+    )]
+    config: Option<PathBuf>,
+    #[clap(
+        short = 'p',
+        long = "profile",
+        requires = "config",
+        help = "Named profile in the config file to use (e.g., index, query)"
+    )]
+    profile: Option<String>,
+    #[clap(short = 'd', long = "dict", help = "Dictionary directory path or URI")]
+    dict: Option<String>,
+    #[clap(short = 'u', long = "user-dict", help = "User dictionary path or URI")]
+    user_dict: Option<String>,
+    #[clap(
+        short = 'm',
+        long = "mode",
+        help = "Tokenization mode (normal|decompose) [default: normal]"
+    )]
+    mode: Option<Mode>,
+    #[clap(
+        long = "threshold",
+        default_value = "0.9",
+        help = "Marginal probability under which a token is uncertain"
+    )]
+    threshold: f64,
+    #[clap(
+        long = "theta",
+        default_value_t = DEFAULT_THETA,
+        help = "Scale of the costs in the probabilities of the segmentations"
+    )]
+    theta: f64,
+    #[clap(
+        short = 'n',
+        long = "limit",
+        help = "Maximum number of sentences to export (default: all)"
+    )]
+    limit: Option<usize>,
+    #[clap(
+        short = 'o',
+        long = "output",
+        default_value = "text",
+        help = "Output format (text: a golden corpus to correct, json: one JSON object per line)"
+    )]
+    output: String,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Check the segmentations of a golden corpus, for regression testing",
+    version = get_version(),
+)]
+struct TestArgs {
+    #[clap(
+        long = "corpus",
+        help = "Golden corpus file, one text and its expected segmentation separated by a tab per line"
+    )]
+    corpus: PathBuf,
+    #[clap(
+        long = "config",
+        help = "CLI config file (TOML) defining dictionary, filters and mode"
+    )]
+    config: Option<PathBuf>,
+    #[clap(
+        short = 'p',
+        long = "profile",
+        requires = "config",
+        help = "Named profile in the config file to use (e.g., index, query)"
+    )]
+    profile: Option<String>,
+    #[clap(short = 'd', long = "dict", help = "Dictionary directory path or URI")]
+    dict: Option<String>,
+    #[clap(short = 'u', long = "user-dict", help = "User dictionary path or URI")]
+    user_dict: Option<String>,
+    #[clap(
+        short = 'm',
+        long = "mode",
+        help = "Tokenization mode (normal|decompose) [default: normal]"
+    )]
+    mode: Option<Mode>,
+    #[clap(
+        short = 'o',
+        long = "output",
+        default_value = "text",
+        help = "Report format (text|json|junit)"
+    )]
+    output: String,
+}
+
 #[derive(Debug, clap::Args)]
 #[clap(author,
     about = "Build a morphological analysis dictionary",
@@ -111,7 +465,7 @@ struct BuildArgs {
         short = 's',
         long = "src",
         required = true,
-        help = "Source directory containing dictionary CSV files"
+        help = "Source directory containing dictionary CSV files, or a source archive (.tar.gz, .tgz, .tar, .zip)"
     )]
     src: PathBuf,
     #[clap(
@@ -134,295 +488,2140 @@ struct BuildArgs {
         help = "Build user dictionary (default: system dictionary)"
     )]
     user: bool,
+    #[clap(
+        short = 'e',
+        long = "encoding",
+        help = "Encoding of the source files (e.g., UTF-8, EUC-JP, Shift_JIS, auto); overrides the encoding in the metadata"
+    )]
+    encoding: Option<String>,
+    #[clap(
+        long = "duplicate-policy",
+        help = "How to resolve duplicate entries (keep_all|keep_first|keep_lowest_cost|error); overrides the duplicate policy in the metadata"
+    )]
+    duplicate_policy: Option<DuplicatePolicy>,
+    #[clap(
+        long = "no-reading-index",
+        help = "Do not write the reading index (dict.readings) used to look up words by reading"
+    )]
+    no_reading_index: bool,
+    #[clap(
+        long = "okurigana-variants",
+        help = "Write the okurigana variant table (dict.okurigana) used to look up words spelled with some okurigana left out"
+    )]
+    okurigana_variants: bool,
+    #[clap(
+        long = "matrix-shard-size",
+        help = "Split the connection cost matrix into shards of this many left ids, loaded when they are first used; overrides the matrix shard size in the metadata"
+    )]
+    matrix_shard_size: Option<u32>,
+    #[clap(
+        long = "matrix-encoding",
+        help = "Encoding of the connection costs (plain|quantized); quantized halves the memory of the matrix; overrides the matrix encoding in the metadata"
+    )]
+    matrix_encoding: Option<MatrixEncoding>,
+    #[clap(
+        long = "verify-reproducible",
+        help = "Build the dictionary a second time and fail if the output is not byte-identical"
+    )]
+    verify_reproducible: bool,
+    #[clap(long = "progress", help = "Show the progress of the build on stderr")]
+    progress: bool,
 }
 
-#[cfg(feature = "train")]
 #[derive(Debug, clap::Args)]
 #[clap(
     author,
-    about = "Train a morphological analysis model from corpus",
+    about = "Benchmark tokenization throughput, latency and memory usage",
     version = get_version(),
 )]
-struct TrainArgs {
+struct BenchArgs {
     #[clap(
-        short = 's',
-        long = "seed",
+        short = 'd',
+        long = "dict",
         required = true,
-        help = "Seed lexicon file (CSV format) to be weighted"
+        help = "Dictionary directory path or URI to benchmark (can be specified multiple times)"
     )]
-    seed: PathBuf,
+    dicts: Vec<String>,
     #[clap(
-        short = 'c',
-        long = "corpus",
-        required = true,
-        help = "Training corpus (annotated text)"
+        short = 'u',
+        long = "user-dict",
+        help = "User dictionary path or URI (optional)"
     )]
-    corpus: PathBuf,
+    user_dict: Option<String>,
     #[clap(
-        short = 'C',
-        long = "char-def",
-        required = true,
-        help = "Character definition file (char.def)"
+        short = 'm',
+        long = "mode",
+        default_value = "normal",
+        help = "Tokenization mode to benchmark (normal|decompose, can be specified multiple times)"
     )]
-    char_def: PathBuf,
+    modes: Vec<Mode>,
     #[clap(
-        short = 'u',
-        long = "unk-def",
+        short = 'i',
+        long = "input",
         required = true,
-        help = "Unknown word definition file (unk.def) to be weighted"
+        help = "Input corpus file (one text per line)"
     )]
-    unk_def: PathBuf,
+    input: PathBuf,
     #[clap(
-        short = 'f',
-        long = "feature-def",
-        required = true,
-        help = "Feature definition file (feature.def)"
+        short = 'n',
+        long = "iterations",
+        default_value = "1",
+        help = "Number of passes over the corpus for each configuration"
     )]
-    feature_def: PathBuf,
+    iterations: usize,
     #[clap(
-        short = 'r',
-        long = "rewrite-def",
-        required = true,
-        help = "Rewrite rule definition file (rewrite.def)"
+        short = 'w',
+        long = "warmup",
+        default_value = "1",
+        help = "Number of untimed passes over the corpus before measuring"
     )]
-    rewrite_def: PathBuf,
+    warmup: usize,
     #[clap(
         short = 'o',
         long = "output",
-        required = true,
-        help = "Output model file"
+        default_value = "table",
+        help = "Output format (table|json)"
     )]
-    output: PathBuf,
+    output: String,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Explore tokenization interactively",
+    version = get_version(),
+)]
+struct ReplArgs {
     #[clap(
-        short = 'l',
-        long = "lambda",
-        default_value = "0.01",
-        help = "L1 regularization (0.0-1.0)"
+        short = 'd',
+        long = "dict",
+        required = true,
+        help = "Dictionary directory path or URI (e.g., embedded://ipadic, /path/to/dictionary)"
     )]
-    lambda: f64,
+    dict: String,
     #[clap(
-        short = 'i',
-        long = "max-iterations",
-        default_value = "100",
-        help = "Maximum number of iterations for training"
+        short = 'u',
+        long = "user-dict",
+        help = "User dictionary path or URI (optional)"
     )]
-    iter: u64,
+    user_dict: Option<String>,
     #[clap(
-        short = 't',
-        long = "max-threads",
-        help = "Maximum number of threads (defaults to CPU core count, auto-adjusted based on dataset size)"
+        short = 'm',
+        long = "mode",
+        default_value = "normal",
+        help = "Tokenization mode (normal|decompose)"
     )]
-    max_threads: Option<usize>,
+    mode: Mode,
+    #[clap(
+        long = "no-color",
+        help = "Disable colorized output (also disabled when NO_COLOR is set or stdout is not a terminal)"
+    )]
+    no_color: bool,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Validate an analyzer (tokenizer) config file",
+    version = get_version(),
+)]
+struct ValidateArgs {
+    #[clap(
+        short = 'c',
+        long = "config",
+        required = true,
+        help = "Analyzer config file (JSON or YAML)"
+    )]
+    config: PathBuf,
+    #[clap(
+        long = "strict",
+        help = "Fail on unknown keys, with their line and column, instead of warning about them"
+    )]
+    strict: bool,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Print the JSON Schema of the analyzer (tokenizer) config",
+    version = get_version(),
+)]
+struct ConfigSchemaArgs {
+    #[clap(
+        short = 'o',
+        long = "output",
+        help = "File to write the schema to [default: stdout]"
+    )]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Check a user dictionary CSV file and report every malformed row",
+    version = get_version(),
+)]
+struct ValidateUserdictArgs {
+    #[clap(
+        short = 'm',
+        long = "metadata",
+        required_unless_present = "dict",
+        conflicts_with = "dict",
+        help = "Metadata configuration file (metadata.json) of the target dictionary"
+    )]
+    metadata: Option<PathBuf>,
+    #[clap(
+        short = 'd',
+        long = "dict",
+        help = "Target dictionary path or URI; context ids are also checked against its connection cost matrix"
+    )]
+    dict: Option<String>,
+    #[clap(help = "User dictionary CSV file")]
+    input_file: PathBuf,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Convert an SKK dictionary (SKK-JISYO) into a user dictionary CSV file",
+    version = get_version(),
+)]
+struct ImportSkkArgs {
+    #[clap(
+        short = 'm',
+        long = "metadata",
+        required_unless_present = "dict",
+        conflicts_with = "dict",
+        help = "Metadata configuration file (metadata.json) of the target dictionary"
+    )]
+    metadata: Option<PathBuf>,
+    #[clap(short = 'd', long = "dict", help = "Target dictionary path or URI")]
+    dict: Option<String>,
+    #[clap(
+        short = 'p',
+        long = "part-of-speech",
+        help = "Part-of-speech fields of the words, separated by commas [default: 名詞,固有名詞,一般]"
+    )]
+    part_of_speech: Option<String>,
+    #[clap(
+        short = 'c',
+        long = "cost",
+        allow_hyphen_values = true,
+        help = "Word cost of the words [default: 5000]"
+    )]
+    cost: Option<i16>,
+    #[clap(
+        short = 'e',
+        long = "encoding",
+        default_value = "auto",
+        help = "Character encoding of the SKK dictionary (auto detects UTF-8 and EUC-JP)"
+    )]
+    encoding: String,
+    #[clap(
+        short = 'o',
+        long = "output",
+        help = "User dictionary CSV file to write [default: stdout]"
+    )]
+    output: Option<PathBuf>,
+    #[clap(help = "SKK dictionary file (e.g., SKK-JISYO.L)")]
+    input_file: PathBuf,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Convert a Mozc / Google Japanese Input user dictionary export into a user dictionary CSV file",
+    version = get_version(),
+)]
+struct ImportMozcArgs {
+    #[clap(
+        short = 'm',
+        long = "metadata",
+        required_unless_present = "dict",
+        conflicts_with = "dict",
+        help = "Metadata configuration file (metadata.json) of the target dictionary"
+    )]
+    metadata: Option<PathBuf>,
+    #[clap(short = 'd', long = "dict", help = "Target dictionary path or URI")]
+    dict: Option<String>,
+    #[clap(
+        short = 'c',
+        long = "cost",
+        allow_hyphen_values = true,
+        help = "Word cost of the words [default: default_word_cost of the metadata]"
+    )]
+    cost: Option<i16>,
+    #[clap(
+        short = 'e',
+        long = "encoding",
+        default_value = "auto",
+        help = "Character encoding of the export (auto detects UTF-8 and UTF-16 with a BOM)"
+    )]
+    encoding: String,
+    #[clap(
+        short = 'o',
+        long = "output",
+        help = "User dictionary CSV file to write [default: stdout]"
+    )]
+    output: Option<PathBuf>,
+    #[clap(help = "Exported user dictionary file (tab-separated)")]
+    input_file: PathBuf,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Translate an Elasticsearch / OpenSearch Kuromoji or Nori analyzer into an analyzer config",
+    version = get_version(),
+)]
+struct ConvertAnalyzerArgs {
+    #[clap(help = "Index settings or analysis settings (JSON or YAML)")]
+    settings: PathBuf,
+    #[clap(
+        short = 'a',
+        long = "analyzer",
+        help = "Name of the analyzer to translate (required if the settings define several)"
+    )]
+    analyzer: Option<String>,
+    #[clap(
+        short = 'o',
+        long = "output",
+        help = "Analyzer config file to write (JSON, or YAML for .yaml / .yml files) [default: stdout]"
+    )]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Upgrade a dictionary built by an older version of Lindera to the current format in place",
+    version = get_version(),
+)]
+struct MigrateDictArgs {
+    #[clap(help = "Dictionary directory, or user dictionary file (.bin)")]
+    path: PathBuf,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Print a machine-readable description (JSON) of the binary dictionary format",
+    version = get_version(),
+)]
+struct DumpFormatArgs {
+    #[clap(
+        short = 'o',
+        long = "output",
+        help = "File to write the description to [default: stdout]"
+    )]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Show the license and provenance of a dictionary",
+    version = get_version(),
+)]
+struct DictInfoArgs {
+    #[clap(
+        short = 'd',
+        long = "dict",
+        required = true,
+        help = "Dictionary directory path or URI (e.g., embedded://ipadic, /path/to/dictionary)"
+    )]
+    dict: String,
+    #[clap(
+        short = 'o',
+        long = "output",
+        default_value = "text",
+        help = "Output format (text|json)"
+    )]
+    output: String,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Export a built dictionary back into MeCab-style source files",
+    version = get_version(),
+)]
+struct ExportDictArgs {
+    #[clap(
+        short = 'd',
+        long = "dict",
+        required_unless_present = "user_dict",
+        conflicts_with = "user_dict",
+        help = "Dictionary directory path or URI (e.g., embedded://ipadic, /path/to/dictionary)"
+    )]
+    dict: Option<String>,
+    #[clap(
+        short = 'u',
+        long = "user-dict",
+        help = "User dictionary file (.bin) to export as a CSV file"
+    )]
+    user_dict: Option<PathBuf>,
+    #[clap(
+        short = 'o',
+        long = "output",
+        help = "Output directory of the source files, or CSV file of the user dictionary [default: stdout]"
+    )]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Report the entries added, removed and changed between two dictionaries",
+    version = get_version(),
+)]
+struct DiffDictArgs {
+    #[clap(help = "Old dictionary directory path or URI")]
+    old: String,
+    #[clap(help = "New dictionary directory path or URI")]
+    new: String,
+    #[clap(
+        short = 's',
+        long = "summary",
+        help = "Only print the number of added, removed and changed entries"
+    )]
+    summary: bool,
+    #[clap(
+        short = 'o',
+        long = "output",
+        default_value = "text",
+        help = "Output format (text|json)"
+    )]
+    output: String,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Look up candidate surfaces for a kana reading prefix",
+    version = get_version(),
+)]
+struct CandidatesArgs {
+    #[clap(
+        short = 'd',
+        long = "dict",
+        required = true,
+        help = "Dictionary directory path"
+    )]
+    dict: PathBuf,
+    #[clap(
+        short = 'n',
+        long = "limit",
+        default_value = "10",
+        help = "Maximum number of candidates"
+    )]
+    limit: usize,
+    #[clap(
+        short = 'o',
+        long = "output",
+        default_value = "text",
+        help = "Output format (text|json)"
+    )]
+    output: String,
+    #[clap(help = "Reading prefix, in hiragana or katakana")]
+    reading: String,
+}
+
+#[cfg(feature = "signature")]
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Sign a dictionary with an ed25519 key, or generate a signing key",
+    version = get_version(),
+)]
+struct SignArgs {
+    #[clap(
+        short = 'k',
+        long = "key",
+        required = true,
+        help = "Signing key file, holding the hex-encoded 32-byte seed"
+    )]
+    key: PathBuf,
+    #[clap(
+        long = "generate-key",
+        help = "Generate a new signing key into the key file instead of signing"
+    )]
+    generate_key: bool,
+    #[clap(
+        help = "Dictionary directory, or dictionary archive or user dictionary file",
+        required_unless_present = "generate_key"
+    )]
+    path: Option<PathBuf>,
+}
+
+#[cfg(feature = "train")]
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Train a morphological analysis model from corpus",
+    version = get_version(),
+)]
+struct TrainArgs {
+    #[clap(
+        short = 's',
+        long = "seed",
+        required = true,
+        help = "Seed lexicon file (CSV format) to be weighted"
+    )]
+    seed: PathBuf,
+    #[clap(
+        short = 'c',
+        long = "corpus",
+        required = true,
+        help = "Training corpus (annotated text)"
+    )]
+    corpus: PathBuf,
+    #[clap(
+        short = 'C',
+        long = "char-def",
+        required = true,
+        help = "Character definition file (char.def)"
+    )]
+    char_def: PathBuf,
+    #[clap(
+        short = 'u',
+        long = "unk-def",
+        required = true,
+        help = "Unknown word definition file (unk.def) to be weighted"
+    )]
+    unk_def: PathBuf,
+    #[clap(
+        short = 'f',
+        long = "feature-def",
+        required = true,
+        help = "Feature definition file (feature.def)"
+    )]
+    feature_def: PathBuf,
+    #[clap(
+        short = 'r',
+        long = "rewrite-def",
+        required = true,
+        help = "Rewrite rule definition file (rewrite.def)"
+    )]
+    rewrite_def: PathBuf,
+    #[clap(
+        short = 'o',
+        long = "output",
+        required = true,
+        help = "Output model file"
+    )]
+    output: PathBuf,
+    #[clap(
+        short = 'l',
+        long = "lambda",
+        default_value = "0.01",
+        help = "L1 regularization (0.0-1.0)"
+    )]
+    lambda: f64,
+    #[clap(
+        short = 'i',
+        long = "max-iterations",
+        default_value = "100",
+        help = "Maximum number of iterations for training"
+    )]
+    iter: u64,
+    #[clap(
+        short = 't',
+        long = "max-threads",
+        help = "Maximum number of threads (defaults to CPU core count, auto-adjusted based on dataset size)"
+    )]
+    max_threads: Option<usize>,
+}
+
+#[cfg(feature = "train")]
+#[derive(Debug, clap::Args)]
+#[clap(
+    author,
+    about = "Export dictionary files from trained model",
+    version = get_version(),
+)]
+struct ExportArgs {
+    #[clap(
+        short = 'm',
+        long = "model",
+        required = true,
+        help = "Trained model file (.dat format)"
+    )]
+    model: PathBuf,
+    #[clap(
+        short = 'o',
+        long = "output",
+        required = true,
+        help = "Output directory (creates lex.csv, matrix.def, unk.def, char.def)"
+    )]
+    output: PathBuf,
+    #[clap(
+        long = "metadata",
+        help = "Base metadata.json file to update with trained model values"
+    )]
+    metadata: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy)]
+/// Formatter type
+pub enum Format {
+    Mecab,
+    Wakati,
+    Json,
+    Graph,
+    Elasticsearch,
+}
+
+impl FromStr for Format {
+    type Err = LinderaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mecab" => Ok(Format::Mecab),
+            "wakati" => Ok(Format::Wakati),
+            "json" => Ok(Format::Json),
+            "graph" => Ok(Format::Graph),
+            "elasticsearch" => Ok(Format::Elasticsearch),
+            _ => Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!("Invalid format: {s}"))),
+        }
+    }
+}
+
+fn main() -> LinderaResult<()> {
+    let args = Args::parse();
+
+    match args.command {
+        Commands::List(args) => list(args),
+        Commands::Tokenize(args) => tokenize(args),
+        Commands::Segment(args) => segment(args),
+        Commands::Build(args) => build(args),
+        Commands::Bench(args) => bench(args),
+        Commands::Compare(args) => compare(args),
+        Commands::Test(args) => test(args),
+        Commands::Eval(args) => eval(args),
+        Commands::Review(args) => review(args),
+        Commands::Repl(args) => repl(args),
+        Commands::Validate(args) => validate(args),
+        Commands::ConfigSchema(args) => config_schema(args),
+        Commands::ValidateUserdict(args) => validate_userdict(args),
+        Commands::ImportSkk(args) => import_skk(args),
+        Commands::ImportMozc(args) => import_mozc(args),
+        Commands::ConvertAnalyzer(args) => convert_analyzer(args),
+        Commands::MigrateDict(args) => migrate_dict(args),
+        Commands::DumpFormat(args) => dump_format(args),
+        Commands::DictInfo(args) => dict_info(args),
+        Commands::ExportDict(args) => export_dict(args),
+        Commands::DiffDict(args) => diff_dict(args),
+        Commands::Candidates(args) => candidates(args),
+        #[cfg(feature = "train")]
+        Commands::Train(args) => train(args),
+        #[cfg(feature = "train")]
+        Commands::Export(args) => export(args),
+        #[cfg(feature = "signature")]
+        Commands::Sign(args) => sign(args),
+    }
+}
+
+fn list(_args: ListArgs) -> LinderaResult<()> {
+    for dic in DictionaryKind::contained_variants() {
+        println!("{}", dic.as_str());
+    }
+    Ok(())
+}
+
+/// Token fields that are available regardless of the dictionary schema.
+const TOKEN_FIELDS: &[&str] = &[
+    "surface",
+    "byte_start",
+    "byte_end",
+    "position",
+    "position_length",
+    "word_id",
+    "details",
+    "upos",
+];
+
+/// Checks that every field name is a token field or a field stored in the dictionary details.
+fn validate_fields(fields: &[String], schema: &Schema) -> LinderaResult<()> {
+    let detail_fields = schema.get_custom_fields();
+    for field in fields {
+        if !TOKEN_FIELDS.contains(&field.as_str()) && !detail_fields.contains(field) {
+            return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                "Unknown field: {field} (available: {}, {})",
+                TOKEN_FIELDS.join(", "),
+                detail_fields.join(", ")
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the value of the given field of the token.
+/// Fields missing from the token details (e.g. for unknown words) are returned as `Value::Null`.
+fn token_field(token: &mut Token, field: &str) -> serde_json::Value {
+    match field {
+        "surface" => serde_json::json!(token.surface.as_ref()),
+        "byte_start" => serde_json::json!(token.byte_start),
+        "byte_end" => serde_json::json!(token.byte_end),
+        "position" => serde_json::json!(token.position),
+        "position_length" => serde_json::json!(token.position_length),
+        "word_id" => serde_json::json!(token.word_id.id),
+        "details" => serde_json::json!(token.details().join(",")),
+        "upos" => token
+            .upos()
+            .map_or(serde_json::Value::Null, |upos| serde_json::json!(upos)),
+        _ => token
+            .get(field)
+            .map_or(serde_json::Value::Null, |value| serde_json::json!(value)),
+    }
+}
+
+/// Replaces the escape sequences `\t`, `\n` and `\\` so that delimiters can be given on the command line.
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('t') => unescaped.push('\t'),
+                Some('n') => unescaped.push('\n'),
+                Some('\\') => unescaped.push('\\'),
+                Some(other) => {
+                    unescaped.push(c);
+                    unescaped.push(other);
+                }
+                None => unescaped.push(c),
+            }
+        } else {
+            unescaped.push(c);
+        }
+    }
+    unescaped
+}
+
+fn fields_output(mut tokens: Vec<Token>, fields: &[String], delimiter: &str) -> LinderaResult<()> {
+    for token in tokens.iter_mut() {
+        let values: Vec<String> = fields
+            .iter()
+            .map(|field| match token_field(token, field) {
+                serde_json::Value::String(value) => value,
+                serde_json::Value::Null => String::new(),
+                value => value.to_string(),
+            })
+            .collect();
+        println!("{}", values.join(delimiter));
+    }
+    println!("EOS");
+
+    Ok(())
+}
+
+fn json_fields_output(mut tokens: Vec<Token>, fields: &[String]) -> LinderaResult<()> {
+    let mut json_tokens = Vec::new();
+    for token in tokens.iter_mut() {
+        let mut obj = serde_json::Map::new();
+        for field in fields {
+            obj.insert(field.to_string(), token_field(token, field));
+        }
+        json_tokens.push(serde_json::Value::Object(obj));
+    }
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&json_tokens)
+            .map_err(|err| { LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)) })?
+    );
+
+    Ok(())
+}
+
+fn elasticsearch_output(response: AnalyzeResponse) -> LinderaResult<()> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&response)
+            .map_err(|err| { LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)) })?
+    );
+
+    Ok(())
+}
+
+fn mecab_output(mut tokens: Vec<Token>) -> LinderaResult<()> {
+    for token in tokens.iter_mut() {
+        let details = token.details().join(",");
+        println!("{}\t{}", token.surface.as_ref(), details);
+    }
+    println!("EOS");
+
+    Ok(())
+}
+
+fn json_output(mut tokens: Vec<Token>) -> LinderaResult<()> {
+    let mut json_tokens = Vec::new();
+    for token in tokens.iter_mut() {
+        let token_value = token.as_value();
+        json_tokens.push(token_value);
+    }
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&json_tokens)
+            .map_err(|err| { LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)) })?
+    );
+
+    Ok(())
+}
+
+fn graph_output(mut tokens: Vec<Token>) -> LinderaResult<()> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&TokenGraph::from_tokens(&mut tokens))
+            .map_err(|err| { LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)) })?
+    );
+
+    Ok(())
+}
+
+fn explain_output(explanation: Explanation) -> LinderaResult<()> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&explanation)
+            .map_err(|err| { LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)) })?
+    );
+
+    Ok(())
+}
+
+/// Options for wakati output.
+struct WakatiOptions {
+    separator: String,
+    quote: bool,
+    sentence_per_line: bool,
+}
+
+/// Wraps the surface in double quotes if it contains the separator, a double quote or a line break.
+/// Double quotes inside the surface are escaped by doubling them.
+fn quote_surface<'a>(surface: &'a str, separator: &str) -> Cow<'a, str> {
+    let needs_quote = (!separator.is_empty() && surface.contains(separator))
+        || surface.contains(['"', '\n', '\r']);
+    if needs_quote {
+        Cow::Owned(format!("\"{}\"", surface.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(surface)
+    }
+}
+
+fn wakati_output(tokens: Vec<Token>, options: &WakatiOptions) -> LinderaResult<()> {
+    let mut sentence = Vec::new();
+    for token in tokens.iter() {
+        let surface = token.surface.as_ref();
+        sentence.push(if options.quote {
+            quote_surface(surface, &options.separator)
+        } else {
+            Cow::Borrowed(surface)
+        });
+
+        if options.sentence_per_line && surface.ends_with(SENTENCE_TERMINATORS) {
+            println!("{}", sentence.join(&options.separator));
+            sentence.clear();
+        }
+    }
+    if !sentence.is_empty() {
+        println!("{}", sentence.join(&options.separator));
+    }
+
+    Ok(())
+}
+
+fn tokenize(args: TokenizeArgs) -> LinderaResult<()> {
+    // Settings from the config file (and profile), overridden by command line flags
+    let config = match &args.config {
+        Some(config_path) => CliConfig::from_file(config_path)?.resolve(args.profile.as_deref())?,
+        None => ProfileConfig::default(),
+    };
+
+    let mut builder = TokenizerBuilder::new()?;
+
+    // Set dictionary directory URI
+    let dict = args.dict.or(config.dict).ok_or_else(|| {
+        LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+            "Dictionary is not specified. Use --dict or set `dict` in the config file"
+        ))
+    })?;
+    builder.set_segmenter_dictionary(dict.as_str());
+
+    // Set user dictionary URI
+    if let Some(user_dic_uri) = args.user_dict.or(config.user_dict) {
+        builder.set_segmenter_user_dictionary(user_dic_uri.as_str());
+    }
+
+    // Mode
+    let mode = match args.mode {
+        Some(mode) => mode,
+        None => match config.mode {
+            Some(mode) => Mode::from_str(mode.as_str())?,
+            None => Mode::Normal,
+        },
+    };
+    builder.set_segmenter_mode(&mode);
+
+    // Keep whitespace (default is to ignore whitespace for MeCab compatibility)
+    builder.set_segmenter_keep_whitespace(
+        args.keep_whitespace
+            .or(config.keep_whitespace)
+            .unwrap_or(false),
+    );
+
+    // Okurigana variants
+    if args.okurigana_variants {
+        builder.set_segmenter_okurigana_variants(true);
+    }
+
+    // Filters from the config file are applied before the ones given on the command line
+    for filter in config.character_filters.iter().flatten() {
+        builder.append_character_filter(filter.kind.as_str(), &filter.args);
+    }
+    for filter in config.token_filters.iter().flatten() {
+        builder.append_token_filter(filter.kind.as_str(), &filter.args);
+    }
+
+    // Tokenizer
+    let mut tokenizer = builder
+        .build()
+        .map_err(|err| LinderaErrorKind::Args.with_error(err))?;
+
+    // output format
+    let output = args
+        .output
+        .or(config.output)
+        .unwrap_or_else(|| "mecab".to_string());
+    let output_format = Format::from_str(output.as_str())?;
+
+    // Output fields
+    let fields = args.fields.or(config.fields);
+    if let Some(fields) = &fields {
+        if matches!(
+            output_format,
+            Format::Wakati | Format::Graph | Format::Elasticsearch
+        ) {
+            return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                "--fields cannot be used with the {output} output format"
+            )));
+        }
+        validate_fields(
+            fields,
+            &tokenizer.segmenter.dictionary.metadata.dictionary_schema,
+        )?;
+    }
+    let delimiter = unescape(
+        args.delimiter
+            .or(config.delimiter)
+            .as_deref()
+            .unwrap_or("\t"),
+    );
+
+    // Wakati options
+    let wakati_options = WakatiOptions {
+        separator: unescape(
+            args.separator
+                .or(config.separator)
+                .as_deref()
+                .unwrap_or(" "),
+        ),
+        quote: args.quote.or(config.quote).unwrap_or(false),
+        sentence_per_line: args
+            .sentence_per_line
+            .or(config.sentence_per_line)
+            .unwrap_or(false),
+    };
+
+    // Character flters
+    for filter in args.character_filters.iter().flatten() {
+        let character_filter = CharacterFilterLoader::load_from_cli_flag(filter)?;
+        tokenizer.append_character_filter(character_filter);
+    }
+
+    // Token filters
+    for filter in args.token_filters.iter().flatten() {
+        let token_filter = TokenFilterLoader::load_from_cli_flag(filter)?;
+        tokenizer.append_token_filter(token_filter);
+    }
+
+    // input file
+    let mut reader: Box<dyn BufRead> = if let Some(input_file) = args.input_file {
+        Box::new(BufReader::new(File::open(input_file).map_err(|err| {
+            LinderaErrorKind::Io.with_error(anyhow::anyhow!(err))
+        })?))
+    } else {
+        Box::new(BufReader::new(io::stdin()))
+    };
+
+    loop {
+        // read the text to be tokenized from stdin
+        let mut line = Vec::new();
+        let size = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+        if size == 0 {
+            // EOS
+            break;
+        }
+        // Invalid UTF-8 is replaced with U+FFFD rather than failing the whole input
+        let text = String::from_utf8_lossy(&line);
+
+        if args.explain {
+            explain_output(tokenizer.explain(text.trim())?)?;
+            continue;
+        }
+
+        let tokens = tokenizer.tokenize(text.trim())?;
+
+        match (output_format, &fields) {
+            (Format::Mecab, Some(fields)) => {
+                fields_output(tokens, fields, &delimiter)?;
+            }
+            (Format::Json, Some(fields)) => {
+                json_fields_output(tokens, fields)?;
+            }
+            (Format::Mecab, None) => {
+                mecab_output(tokens)?;
+            }
+            (Format::Json, None) => {
+                json_output(tokens)?;
+            }
+            (Format::Wakati, _) => {
+                wakati_output(tokens, &wakati_options)?;
+            }
+            (Format::Graph, _) => {
+                graph_output(tokens)?;
+            }
+            (Format::Elasticsearch, _) => {
+                elasticsearch_output(AnalyzeResponse::new(text.trim(), &tokens))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn segment(args: SegmentArgs) -> LinderaResult<()> {
+    let mut builder = TokenizerBuilder::new()?;
+    builder.set_segmenter_dictionary(&args.dict);
+    if let Some(user_dic_uri) = &args.user_dict {
+        builder.set_segmenter_user_dictionary(user_dic_uri);
+    }
+    builder.set_segmenter_mode(&args.mode);
+    let tokenizer = builder
+        .build()
+        .map_err(|err| LinderaErrorKind::Args.with_error(err))?;
+
+    let corpus = match &args.input_file {
+        Some(input_file) => Corpus::from_file(input_file)?,
+        None => Corpus::from_reader(io::stdin().lock())?,
+    };
+
+    let options = SegmentOptions {
+        separator: unescape(args.separator.as_deref().unwrap_or(" ")),
+        sentence_per_line: args.sentence_per_line,
+        threads: args.threads.unwrap_or_else(num_cpus::get),
+        chunk_size: args.chunk_size,
+    };
+
+    let report = match &args.output {
+        Some(output) => {
+            let file = File::create(output).map_err(|err| {
+                LinderaErrorKind::Io
+                    .with_error(anyhow::anyhow!(err))
+                    .add_context(format!(
+                        "Failed to create output file: {}",
+                        output.display()
+                    ))
+            })?;
+            segment::segment(
+                &tokenizer,
+                corpus.text()?,
+                &options,
+                &mut BufWriter::new(file),
+            )?
+        }
+        None => segment::segment(
+            &tokenizer,
+            corpus.text()?,
+            &options,
+            &mut BufWriter::new(io::stdout().lock()),
+        )?,
+    };
+
+    if !args.quiet {
+        eprintln!("{report} using {} thread(s)", options.threads);
+    }
+
+    Ok(())
+}
+
+fn compare(args: CompareArgs) -> LinderaResult<()> {
+    let output_json = match args.output.as_str() {
+        "text" => false,
+        "json" => true,
+        _ => {
+            return Err(LinderaErrorKind::Args
+                .with_error(anyhow::anyhow!("Invalid format: {}", args.output)));
+        }
+    };
+    if args.dicts.len() < 2 {
+        return Err(LinderaErrorKind::Args
+            .with_error(anyhow::anyhow!("At least two dictionaries are required")));
+    }
+
+    let mut tokenizers = Vec::with_capacity(args.dicts.len());
+    for dict in &args.dicts {
+        let mut builder = TokenizerBuilder::new()?;
+        builder.set_segmenter_dictionary(dict);
+        builder.set_segmenter_mode(&args.mode);
+        let tokenizer = builder
+            .build()
+            .map_err(|err| LinderaErrorKind::Args.with_error(err))?;
+        tokenizers.push((dict.as_str(), tokenizer));
+    }
+    let tokenizers = tokenizers
+        .iter()
+        .map(|(name, tokenizer)| (*name, tokenizer))
+        .collect::<Vec<_>>();
+    let name_width = args.dicts.iter().map(|dict| dict.len()).max().unwrap_or(0);
+
+    let reader: Box<dyn BufRead> = match &args.input_file {
+        Some(input_file) => {
+            Box::new(BufReader::new(File::open(input_file).map_err(|err| {
+                LinderaErrorKind::Io.with_error(anyhow::anyhow!(err))
+            })?))
+        }
+        None => Box::new(BufReader::new(io::stdin())),
+    };
+    let mut writer = BufWriter::new(io::stdout().lock());
+    let io_error = |err: io::Error| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err));
+
+    let (mut lines, mut segments, mut disagreements) = (0, 0, 0);
+    for line in reader.lines() {
+        let line = line.map_err(io_error)?;
+        let text = line.trim_end_matches('\r');
+        if text.trim().is_empty() {
+            continue;
+        }
+        let comparison = compare_tokenizers(&tokenizers, text)?;
+        lines += 1;
+        segments += comparison.segments.len();
+        disagreements += comparison.disagreements().count();
+        if args.diff_only && comparison.is_agreed() {
+            continue;
+        }
+
+        if output_json {
+            let mut value = serde_json::to_value(&comparison)
+                .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?;
+            value["text"] = serde_json::Value::String(text.to_string());
+            writeln!(writer, "{value}").map_err(io_error)?;
+        } else {
+            writeln!(writer, "# {text}").map_err(io_error)?;
+            for (index, name) in comparison.names.iter().enumerate() {
+                writeln!(
+                    writer,
+                    "{name:<name_width$}\t{}",
+                    compared_segmentation(&comparison, index)
+                )
+                .map_err(io_error)?;
+            }
+        }
+    }
+    writer.flush().map_err(io_error)?;
+
+    let agreement = if segments == 0 {
+        100.0
+    } else {
+        100.0 * (segments - disagreements) as f64 / segments as f64
+    };
+    eprintln!(
+        "{lines} line(s), {segments} segment(s), {disagreements} segmented differently ({agreement:.1}% agreement)"
+    );
+    Ok(())
+}
+
+/// The tokens of a tokenizer in a comparison, separated by spaces, with the segments the
+/// tokenizers split differently in brackets.
+fn compared_segmentation(comparison: &Comparison, index: usize) -> String {
+    comparison
+        .segments
+        .iter()
+        .map(|segment| {
+            let tokens = segment.tokens[index]
+                .iter()
+                .map(|token| token.surface.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            if segment.agreed {
+                tokens
+            } else {
+                format!("[{tokens}]")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Builds the tokenizer of a corpus command from a CLI config file and profile, overridden by the
+/// command line flags.
+fn corpus_tokenizer(
+    config: Option<&Path>,
+    profile: Option<&str>,
+    dict: Option<String>,
+    user_dict: Option<String>,
+    mode: Option<Mode>,
+) -> LinderaResult<Tokenizer> {
+    let config = match config {
+        Some(config_path) => CliConfig::from_file(config_path)?.resolve(profile)?,
+        None => ProfileConfig::default(),
+    };
+
+    let mut builder = TokenizerBuilder::new()?;
+    let dict = dict.or(config.dict).ok_or_else(|| {
+        LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+            "Dictionary is not specified. Use --dict or set `dict` in the config file"
+        ))
+    })?;
+    builder.set_segmenter_dictionary(dict.as_str());
+    if let Some(user_dic_uri) = user_dict.or(config.user_dict) {
+        builder.set_segmenter_user_dictionary(user_dic_uri.as_str());
+    }
+    let mode = match mode {
+        Some(mode) => mode,
+        None => match config.mode {
+            Some(mode) => Mode::from_str(mode.as_str())?,
+            None => Mode::Normal,
+        },
+    };
+    builder.set_segmenter_mode(&mode);
+    if config.keep_whitespace.unwrap_or(false) {
+        builder.set_segmenter_keep_whitespace(true);
+    }
+    for filter in config.character_filters.iter().flatten() {
+        builder.append_character_filter(filter.kind.as_str(), &filter.args);
+    }
+    for filter in config.token_filters.iter().flatten() {
+        builder.append_token_filter(filter.kind.as_str(), &filter.args);
+    }
+    builder
+        .build()
+        .map_err(|err| LinderaErrorKind::Args.with_error(err))
+}
+
+fn eval(args: EvalArgs) -> LinderaResult<()> {
+    let output_json = match args.output.as_str() {
+        "text" => false,
+        "json" => true,
+        _ => {
+            return Err(LinderaErrorKind::Args
+                .with_error(anyhow::anyhow!("Invalid format: {}", args.output)));
+        }
+    };
+    let format = CorpusFormat::from_str(&args.format)?;
+    if args.pos_depth == 0 {
+        return Err(
+            LinderaErrorKind::Args.with_error(anyhow::anyhow!("pos-depth must be greater than 0"))
+        );
+    }
+
+    let tokenizer = corpus_tokenizer(
+        args.config.as_deref(),
+        args.profile.as_deref(),
+        args.dict,
+        args.user_dict,
+        args.mode,
+    )?;
+    let corpus = fs::read_to_string(&args.corpus)
+        .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+    let sentences = parse_corpus(&corpus, format)?;
+    let evaluation = evaluate(&tokenizer, &sentences, args.pos_depth)?;
+
+    let score = |score: &Score| {
+        serde_json::json!({
+            "correct": score.correct,
+            "predicted": score.predicted,
+            "reference": score.reference,
+            "precision": score.precision(),
+            "recall": score.recall(),
+            "f1": score.f1(),
+        })
+    };
+    if output_json {
+        let report = serde_json::json!({
+            "sentences": evaluation.sentences,
+            "mismatched_sentences": evaluation.mismatched_sentences,
+            "words": score(&evaluation.words),
+            "boundaries": score(&evaluation.boundaries),
+            "pos": {
+                "correct": evaluation.pos_correct,
+                "total": evaluation.pos_total,
+                "accuracy": evaluation.pos_accuracy(),
+            },
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report)
+                .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?
+        );
+    } else {
+        println!("sentences:  {}", evaluation.sentences);
+        for (name, score) in [
+            ("words", &evaluation.words),
+            ("boundaries", &evaluation.boundaries),
+        ] {
+            println!(
+                "{:<11} precision {:.4}  recall {:.4}  f1 {:.4}  ({} correct, {} predicted, {} reference)",
+                format!("{name}:"),
+                score.precision(),
+                score.recall(),
+                score.f1(),
+                score.correct,
+                score.predicted,
+                score.reference
+            );
+        }
+        println!(
+            "pos:        accuracy {:.4}  ({} of {} correctly segmented words)",
+            evaluation.pos_accuracy(),
+            evaluation.pos_correct,
+            evaluation.pos_total
+        );
+    }
+    if evaluation.mismatched_sentences > 0 {
+        eprintln!(
+            "warning: {} sentence(s) whose text does not match their words were skipped",
+            evaluation.mismatched_sentences
+        );
+    }
+    Ok(())
+}
+
+fn review(args: ReviewArgs) -> LinderaResult<()> {
+    let output_json = match args.output.as_str() {
+        "text" => false,
+        "json" => true,
+        _ => {
+            return Err(LinderaErrorKind::Args
+                .with_error(anyhow::anyhow!("Invalid format: {}", args.output)));
+        }
+    };
+    if args.theta <= 0.0 {
+        return Err(
+            LinderaErrorKind::Args.with_error(anyhow::anyhow!("theta must be greater than 0"))
+        );
+    }
+
+    let tokenizer = corpus_tokenizer(
+        args.config.as_deref(),
+        args.profile.as_deref(),
+        args.dict,
+        args.user_dict,
+        args.mode,
+    )?;
+    let corpus = fs::read_to_string(&args.corpus)
+        .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+    let selector = ReviewSelector {
+        threshold: args.threshold,
+        theta: args.theta,
+        limit: args.limit,
+    };
+    let sentences = selector.select(
+        &tokenizer,
+        corpus.lines().map(|line| line.trim_end_matches('\r')),
+    )?;
+
+    if output_json {
+        for sentence in &sentences {
+            println!(
+                "{}",
+                serde_json::to_string(sentence)
+                    .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?
+            );
+        }
+    } else {
+        print!("{}", to_review_corpus(&sentences));
+    }
+    eprintln!(
+        "{} sentence(s) below the threshold of {}",
+        sentences.len(),
+        args.threshold
+    );
+    Ok(())
+}
+
+fn test(args: TestArgs) -> LinderaResult<()> {
+    if !matches!(args.output.as_str(), "text" | "json" | "junit") {
+        return Err(
+            LinderaErrorKind::Args.with_error(anyhow::anyhow!("Invalid format: {}", args.output))
+        );
+    }
+
+    let tokenizer = corpus_tokenizer(
+        args.config.as_deref(),
+        args.profile.as_deref(),
+        args.dict,
+        args.user_dict,
+        args.mode,
+    )?;
+
+    let cases = read_corpus(&args.corpus)?;
+    let report = run_golden_corpus(&tokenizer, &cases)?;
+    let corpus = args.corpus.display().to_string();
+    match args.output.as_str() {
+        "json" => println!(
+            "{}",
+            serde_json::to_string_pretty(&report)
+                .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?
+        ),
+        "junit" => print!("{}", report.to_junit(&corpus)),
+        _ => {
+            for failure in &report.failures {
+                println!("{corpus}:{}: {}", failure.line, failure.text);
+                println!("  expected: {}", failure.expected.join(" "));
+                println!("  actual:   {}", failure.actual.join(" "));
+            }
+        }
+    }
+
+    if !report.is_ok() {
+        return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+            "{corpus}: {} of {} case(s) failed",
+            report.failures.len(),
+            report.cases
+        )));
+    }
+
+    eprintln!("{corpus}: OK ({} case(s))", report.cases);
+    Ok(())
+}
+
+fn build(args: BuildArgs) -> LinderaResult<()> {
+    let mut metadata: Metadata = serde_json::from_reader(
+        File::open(&args.metadata)
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?,
+    )
+    .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+    if let Some(encoding) = args.encoding {
+        metadata.encoding = encoding;
+    }
+    if let Some(duplicate_policy) = args.duplicate_policy {
+        metadata.duplicate_policy = duplicate_policy;
+    }
+    if args.no_reading_index {
+        metadata.reading_index_field = None;
+    }
+    if args.okurigana_variants {
+        metadata.okurigana_variants = true;
+    }
+    if let Some(matrix_shard_size) = args.matrix_shard_size {
+        metadata.matrix_shard_size = Some(matrix_shard_size);
+    }
+    if let Some(matrix_encoding) = args.matrix_encoding {
+        metadata.matrix_encoding = matrix_encoding;
+    }
+
+    let builder = DictionaryBuilder::new(metadata);
+    let job = if args.progress {
+        BuildJob::new().on_progress(print_build_progress)
+    } else {
+        BuildJob::new()
+    };
+
+    if args.user {
+        let output_file = if let Some(filename) = args.src.file_name() {
+            let mut output_file = Path::new(&args.dest).join(filename);
+            output_file.set_extension("bin");
+            output_file
+        } else {
+            return Err(LinderaErrorKind::Io.with_error(anyhow::anyhow!("failed to get filename")));
+        };
+        builder.build_user_dictionary_with_job(&args.src, &output_file, &job)?;
+        if args.verify_reproducible {
+            builder.verify_reproducible_user_dictionary(&args.src, &output_file)?;
+        }
+        Ok(())
+    } else {
+        let report = builder.build_dictionary_with_job(&args.src, &args.dest, &job)?;
+        if report.duplicates.duplicates > 0 {
+            eprintln!("{}", report.duplicates);
+        }
+        if args.progress {
+            eprintln!("{}", report.memory);
+        }
+        if args.verify_reproducible {
+            builder.verify_reproducible(&args.src, &args.dest)?;
+        }
+        Ok(())
+    }
+}
+
+fn print_build_progress(progress: &BuildProgress) {
+    let eta = progress.eta.map_or_else(
+        || "-".to_string(),
+        |eta| format!("{:.1}s", eta.as_secs_f64()),
+    );
+    eprintln!(
+        "[{:>5.1}%] {} ({}/{}), elapsed {:.1}s, eta {eta}",
+        progress.percent,
+        progress.phase,
+        progress.completed,
+        progress.total,
+        progress.elapsed.as_secs_f64()
+    );
 }
 
-#[cfg(feature = "train")]
-#[derive(Debug, clap::Args)]
-#[clap(
-    author,
-    about = "Export dictionary files from trained model",
-    version = get_version(),
-)]
-struct ExportArgs {
-    #[clap(
-        short = 'm',
-        long = "model",
-        required = true,
-        help = "Trained model file (.dat format)"
-    )]
-    model: PathBuf,
-    #[clap(
-        short = 'o',
-        long = "output",
-        required = true,
-        help = "Output directory (creates lex.csv, matrix.def, unk.def, char.def)"
-    )]
-    output: PathBuf,
-    #[clap(
-        long = "metadata",
-        help = "Base metadata.json file to update with trained model values"
-    )]
-    metadata: Option<PathBuf>,
+/// Result of benchmarking a single dictionary and mode combination.
+struct BenchReport {
+    dictionary: String,
+    mode: String,
+    load_time: Duration,
+    texts: usize,
+    tokens: usize,
+    elapsed: Duration,
+    p50: Duration,
+    p99: Duration,
+    peak_rss: Option<u64>,
 }
 
-#[derive(Debug, Clone, Copy)]
-/// Formatter type
-pub enum Format {
-    Mecab,
-    Wakati,
-    Json,
+impl BenchReport {
+    fn tokens_per_sec(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.tokens as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    fn as_value(&self) -> serde_json::Value {
+        serde_json::json!({
+            "dictionary": self.dictionary,
+            "mode": self.mode,
+            "load_time_ms": self.load_time.as_secs_f64() * 1000.0,
+            "texts": self.texts,
+            "tokens": self.tokens,
+            "elapsed_ms": self.elapsed.as_secs_f64() * 1000.0,
+            "tokens_per_sec": self.tokens_per_sec(),
+            "p50_latency_us": self.p50.as_secs_f64() * 1_000_000.0,
+            "p99_latency_us": self.p99.as_secs_f64() * 1_000_000.0,
+            "peak_rss_bytes": self.peak_rss,
+        })
+    }
 }
 
-impl FromStr for Format {
-    type Err = LinderaError;
+/// Returns the latency at the given percentile (0.0-1.0) using the nearest-rank method.
+/// The latencies must be sorted in ascending order.
+fn percentile(sorted_latencies: &[Duration], percentile: f64) -> Duration {
+    if sorted_latencies.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (percentile * sorted_latencies.len() as f64).ceil() as usize;
+    sorted_latencies[rank.clamp(1, sorted_latencies.len()) - 1]
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "mecab" => Ok(Format::Mecab),
-            "wakati" => Ok(Format::Wakati),
-            "json" => Ok(Format::Json),
-            _ => Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!("Invalid format: {s}"))),
+/// Returns the peak resident set size of the current process in bytes.
+///
+/// The value is the high-water mark for the whole process, so when several
+/// configurations are benchmarked in one run it never decreases.
+/// Only Linux is supported; `None` is returned on other platforms.
+fn bench(args: BenchArgs) -> LinderaResult<()> {
+    if args.iterations == 0 {
+        return Err(
+            LinderaErrorKind::Args.with_error(anyhow::anyhow!("iterations must be greater than 0"))
+        );
+    }
+
+    // output format
+    let output_json = match args.output.as_str() {
+        "table" => false,
+        "json" => true,
+        _ => {
+            return Err(LinderaErrorKind::Args
+                .with_error(anyhow::anyhow!("Invalid format: {}", args.output)));
+        }
+    };
+
+    // Load the corpus up front so that file I/O is not included in the measurements
+    let reader = BufReader::new(
+        File::open(&args.input)
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?,
+    );
+    let mut texts = Vec::new();
+    for line in reader.lines() {
+        let line = line.map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+        let text = line.trim();
+        if !text.is_empty() {
+            texts.push(text.to_string());
         }
     }
-}
+    if texts.is_empty() {
+        return Err(LinderaErrorKind::Args
+            .with_error(anyhow::anyhow!("input corpus is empty: {:?}", args.input)));
+    }
 
-fn main() -> LinderaResult<()> {
-    let args = Args::parse();
+    let mut reports = Vec::new();
+    for dict in &args.dicts {
+        for mode in &args.modes {
+            let mut builder = TokenizerBuilder::new()?;
+            builder.set_segmenter_dictionary(dict);
+            if let Some(user_dic_uri) = &args.user_dict {
+                builder.set_segmenter_user_dictionary(user_dic_uri);
+            }
+            builder.set_segmenter_mode(mode);
 
-    match args.command {
-        Commands::List(args) => list(args),
-        Commands::Tokenize(args) => tokenize(args),
-        Commands::Build(args) => build(args),
-        #[cfg(feature = "train")]
-        Commands::Train(args) => train(args),
-        #[cfg(feature = "train")]
-        Commands::Export(args) => export(args),
+            let load_start = Instant::now();
+            let tokenizer = builder
+                .build()
+                .map_err(|err| LinderaErrorKind::Args.with_error(err))?;
+            let load_time = load_start.elapsed();
+
+            for _ in 0..args.warmup {
+                for text in &texts {
+                    tokenizer.tokenize(text)?;
+                }
+            }
+
+            let mut latencies = Vec::with_capacity(texts.len() * args.iterations);
+            let mut tokens = 0;
+            for _ in 0..args.iterations {
+                for text in &texts {
+                    let start = Instant::now();
+                    let result = tokenizer.tokenize(text)?;
+                    latencies.push(start.elapsed());
+                    tokens += result.len();
+                }
+            }
+            latencies.sort_unstable();
+
+            reports.push(BenchReport {
+                dictionary: dict.clone(),
+                mode: mode.as_str().to_string(),
+                load_time,
+                texts: texts.len() * args.iterations,
+                tokens,
+                elapsed: latencies.iter().sum(),
+                p50: percentile(&latencies, 0.50),
+                p99: percentile(&latencies, 0.99),
+                peak_rss: peak_resident_memory(),
+            });
+        }
     }
-}
 
-fn list(_args: ListArgs) -> LinderaResult<()> {
-    for dic in DictionaryKind::contained_variants() {
-        println!("{}", dic.as_str());
+    if output_json {
+        let values: Vec<serde_json::Value> = reports.iter().map(BenchReport::as_value).collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&values)
+                .map_err(|err| { LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)) })?
+        );
+    } else {
+        println!(
+            "{:<30} {:<10} {:>10} {:>10} {:>14} {:>10} {:>10} {:>14}",
+            "dictionary",
+            "mode",
+            "load_ms",
+            "tokens",
+            "tokens/sec",
+            "p50_us",
+            "p99_us",
+            "peak_rss_mb"
+        );
+        for report in &reports {
+            println!(
+                "{:<30} {:<10} {:>10.1} {:>10} {:>14.1} {:>10.1} {:>10.1} {:>14}",
+                report.dictionary,
+                report.mode,
+                report.load_time.as_secs_f64() * 1000.0,
+                report.tokens,
+                report.tokens_per_sec(),
+                report.p50.as_secs_f64() * 1_000_000.0,
+                report.p99.as_secs_f64() * 1_000_000.0,
+                report
+                    .peak_rss
+                    .map(|bytes| format!("{:.1}", bytes as f64 / (1024.0 * 1024.0)))
+                    .unwrap_or_else(|| "n/a".to_string()),
+            );
+        }
     }
+
     Ok(())
 }
 
-fn mecab_output(mut tokens: Vec<Token>) -> LinderaResult<()> {
-    for token in tokens.iter_mut() {
-        let details = token.details().join(",");
-        println!("{}\t{}", token.surface.as_ref(), details);
-    }
-    println!("EOS");
+fn repl(args: ReplArgs) -> LinderaResult<()> {
+    let color =
+        !args.no_color && std::env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal();
 
-    Ok(())
+    Repl::new(args.dict, args.user_dict, args.mode, color)?.run()
 }
 
-fn json_output(mut tokens: Vec<Token>) -> LinderaResult<()> {
-    let mut json_tokens = Vec::new();
-    for token in tokens.iter_mut() {
-        let token_value = token.as_value();
-        json_tokens.push(token_value);
+fn validate(args: ValidateArgs) -> LinderaResult<()> {
+    let config = if args.strict {
+        match read_config_strict(&args.config) {
+            Ok(config) => config,
+            Err(err) if err.kind() == LinderaErrorKind::Deserialize => {
+                eprintln!("{}: {}", Severity::Error, message(&err));
+                return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                    "{}: 1 error(s), 0 warning(s)",
+                    args.config.display()
+                )));
+            }
+            Err(err) => return Err(err),
+        }
+    } else {
+        read_config(&args.config)?
+    };
+    let base_dir = args.config.parent().unwrap_or(Path::new("."));
+
+    let issues = validate_config(&config, base_dir);
+    for issue in &issues {
+        eprintln!("{issue}");
     }
 
-    println!(
-        "{}",
-        serde_json::to_string_pretty(&json_tokens)
-            .map_err(|err| { LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)) })?
-    );
+    let errors = issues
+        .iter()
+        .filter(|issue| issue.severity == Severity::Error)
+        .count();
+    let warnings = issues.len() - errors;
+    if errors > 0 {
+        return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+            "{}: {errors} error(s), {warnings} warning(s)",
+            args.config.display()
+        )));
+    }
 
+    println!("{}: OK ({warnings} warning(s))", args.config.display());
     Ok(())
 }
 
-fn wakati_output(tokens: Vec<Token>) -> LinderaResult<()> {
-    let mut it = tokens.iter().peekable();
-    while let Some(token) = it.next() {
-        if it.peek().is_some() {
-            print!("{} ", token.surface.as_ref());
-        } else {
-            println!("{}", token.surface.as_ref());
+fn config_schema(args: ConfigSchemaArgs) -> LinderaResult<()> {
+    let schema = serde_json::to_string_pretty(&schema::config_schema())
+        .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?;
+    match args.output {
+        Some(output) => fs::write(&output, format!("{schema}\n")).map_err(|err| {
+            LinderaErrorKind::Io
+                .with_error(anyhow::anyhow!(err))
+                .add_context(format!("Failed to write file: {}", output.display()))
+        }),
+        None => {
+            println!("{schema}");
+            Ok(())
+        }
+    }
+}
+
+fn validate_userdict(args: ValidateUserdictArgs) -> LinderaResult<()> {
+    let diagnostics = match (&args.dict, &args.metadata) {
+        (Some(dict), _) => {
+            let dictionary = load_dictionary(dict)?;
+            DictionaryBuilder::new(dictionary.metadata.clone())
+                .lint_user_dict(&args.input_file, Some(&dictionary.connection_cost_matrix))?
         }
+        (None, Some(metadata)) => {
+            let metadata: Metadata = serde_json::from_reader(
+                File::open(metadata)
+                    .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?,
+            )
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+            DictionaryBuilder::new(metadata).lint_user_dict(&args.input_file, None)?
+        }
+        (None, None) => {
+            return Err(LinderaErrorKind::Args
+                .with_error(anyhow::anyhow!("Either --dict or --metadata is required")));
+        }
+    };
+
+    for diagnostic in &diagnostics {
+        eprintln!("{}:{diagnostic}", args.input_file.display());
+    }
+
+    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+    let warnings = diagnostics.len() - errors;
+    if errors > 0 {
+        return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+            "{}: {errors} error(s), {warnings} warning(s)",
+            args.input_file.display()
+        )));
     }
 
+    println!("{}: OK ({warnings} warning(s))", args.input_file.display());
     Ok(())
 }
 
-fn tokenize(args: TokenizeArgs) -> LinderaResult<()> {
-    let mut builder = TokenizerBuilder::new()?;
-
-    // Set dictionary directory URI
-    builder.set_segmenter_dictionary(args.dict.as_str());
+/// Loads the metadata of the target dictionary of an import command.
+fn import_metadata(dict: Option<&str>, metadata: Option<&Path>) -> LinderaResult<Metadata> {
+    match (dict, metadata) {
+        (Some(dict), _) => Ok(load_dictionary(dict)?.metadata),
+        (None, Some(metadata)) => serde_json::from_reader(
+            File::open(metadata)
+                .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?,
+        )
+        .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err))),
+        (None, None) => Err(LinderaErrorKind::Args
+            .with_error(anyhow::anyhow!("Either --dict or --metadata is required"))),
+    }
+}
 
-    // Set user dictionary URI
-    if let Some(user_dic_uri) = args.user_dict {
-        builder.set_segmenter_user_dictionary(user_dic_uri.as_str());
+/// Writes the rows of an import command as a user dictionary CSV file, or to stdout.
+fn write_user_dictionary_rows(rows: &[Vec<String>], output: Option<&Path>) -> LinderaResult<()> {
+    let writer: Box<dyn io::Write> = match output {
+        Some(path) => Box::new(
+            File::create(path)
+                .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?,
+        ),
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(BufWriter::new(writer));
+    for row in rows {
+        writer
+            .write_record(row)
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
     }
+    writer
+        .flush()
+        .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))
+}
 
-    // Mode
-    builder.set_segmenter_mode(&args.mode);
+fn import_skk(args: ImportSkkArgs) -> LinderaResult<()> {
+    let metadata = import_metadata(args.dict.as_deref(), args.metadata.as_deref())?;
 
-    // Keep whitespace (default is to ignore whitespace for MeCab compatibility)
-    if args.keep_whitespace {
-        builder.set_segmenter_keep_whitespace(true);
+    let mut importer = SkkImporter::default();
+    if let Some(part_of_speech) = &args.part_of_speech {
+        importer.part_of_speech = part_of_speech.split(',').map(str::to_string).collect();
     }
+    if let Some(cost) = args.cost {
+        importer.word_cost = cost;
+    }
+    let rows = importer.import_file(&args.input_file, &args.encoding, &metadata)?;
+    write_user_dictionary_rows(&rows, args.output.as_deref())?;
 
-    // Tokenizer
-    let mut tokenizer = builder
-        .build()
-        .map_err(|err| LinderaErrorKind::Args.with_error(err))?;
+    eprintln!("{} word(s) imported", rows.len());
+    Ok(())
+}
 
-    // output format
-    let output_format = Format::from_str(args.output.as_str())?;
+fn import_mozc(args: ImportMozcArgs) -> LinderaResult<()> {
+    let metadata = import_metadata(args.dict.as_deref(), args.metadata.as_deref())?;
 
-    // Character flters
-    for filter in args.character_filters.iter().flatten() {
-        let character_filter = CharacterFilterLoader::load_from_cli_flag(filter)?;
-        tokenizer.append_character_filter(character_filter);
+    let importer = MozcImporter {
+        word_cost: args.cost,
+    };
+    let import = importer.import_file(&args.input_file, &args.encoding, &metadata)?;
+    write_user_dictionary_rows(&import.rows, args.output.as_deref())?;
+
+    for (category, count) in &import.skipped {
+        eprintln!("warning: {count} word(s) of category '{category}' skipped");
     }
+    eprintln!("{} word(s) imported", import.rows.len());
+    Ok(())
+}
 
-    // Token filters
-    for filter in args.token_filters.iter().flatten() {
-        let token_filter = TokenFilterLoader::load_from_cli_flag(filter)?;
-        tokenizer.append_token_filter(token_filter);
+fn convert_analyzer(args: ConvertAnalyzerArgs) -> LinderaResult<()> {
+    let settings = read_config(&args.settings)?;
+    let conversion = convert::convert_analyzer(&settings, args.analyzer.as_deref())?;
+    for issue in &conversion.issues {
+        eprintln!("{issue}");
     }
 
-    // input file
-    let mut reader: Box<dyn BufRead> = if let Some(input_file) = args.input_file {
-        Box::new(BufReader::new(File::open(input_file).map_err(|err| {
-            LinderaErrorKind::Io.with_error(anyhow::anyhow!(err))
-        })?))
+    let is_yaml = args
+        .output
+        .as_ref()
+        .and_then(|path| path.extension())
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"));
+    let config = if is_yaml {
+        serde_yaml_ng::to_string(&conversion.config)
+            .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?
     } else {
-        Box::new(BufReader::new(io::stdin()))
+        let mut config = serde_json::to_string_pretty(&conversion.config)
+            .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?;
+        config.push('\n');
+        config
     };
 
-    loop {
-        // read the text to be tokenized from stdin
-        let mut text = String::new();
-        let size = reader
-            .read_line(&mut text)
-            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
-        if size == 0 {
-            // EOS
-            break;
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, config).map_err(|err| {
+                LinderaErrorKind::Io
+                    .with_error(anyhow::anyhow!(err))
+                    .add_context(format!("Failed to write {}", path.display()))
+            })?;
+            eprintln!(
+                "{}: written ({} warning(s))",
+                path.display(),
+                conversion.issues.len()
+            );
         }
+        None => print!("{config}"),
+    }
 
-        let tokens = tokenizer.tokenize(text.trim())?;
+    Ok(())
+}
 
-        match output_format {
-            Format::Mecab => {
-                mecab_output(tokens)?;
-            }
-            Format::Json => {
-                json_output(tokens)?;
-            }
-            Format::Wakati => {
-                wakati_output(tokens)?;
-            }
+fn migrate_dict(args: MigrateDictArgs) -> LinderaResult<()> {
+    let report = if args.path.is_dir() {
+        format::migrate_dictionary(&args.path)?
+    } else {
+        format::migrate_user_dictionary(&args.path)?
+    };
+    println!("{}: {report}", args.path.display());
+    Ok(())
+}
+
+fn dump_format(args: DumpFormatArgs) -> LinderaResult<()> {
+    let description = serde_json::to_string_pretty(&format::layout::describe_format())
+        .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?;
+    match args.output {
+        Some(output) => fs::write(&output, format!("{description}\n")).map_err(|err| {
+            LinderaErrorKind::Io
+                .with_error(anyhow::anyhow!(err))
+                .add_context(format!("Failed to write file: {}", output.display()))
+        }),
+        None => {
+            println!("{description}");
+            Ok(())
+        }
+    }
+}
+
+fn dict_info(args: DictInfoArgs) -> LinderaResult<()> {
+    let output_json = match args.output.as_str() {
+        "text" => false,
+        "json" => true,
+        _ => {
+            return Err(LinderaErrorKind::Args
+                .with_error(anyhow::anyhow!("Invalid format: {}", args.output)));
         }
+    };
+
+    let location = locate_dictionary(&args.dict)?;
+    let dictionary = load_dictionary(&args.dict)?;
+    let metadata = dictionary.metadata();
+
+    if output_json {
+        let info = serde_json::json!({
+            "name": metadata.name,
+            "location": location,
+            "format_version": metadata.format_version,
+            "encoding": metadata.encoding,
+            "compress_algorithm": metadata.compress_algorithm,
+            "entries": dictionary.num_entries(),
+            "license": metadata.license,
+            "source_url": metadata.source_url,
+            "source_version": metadata.source_version,
+            "built_at": metadata.built_at,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&info)
+                .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?
+        );
+        return Ok(());
     }
 
+    let or_unset = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+    println!("name:               {}", metadata.name);
+    println!("location:           {location}");
+    println!("format_version:     {}", metadata.format_version);
+    println!("encoding:           {}", metadata.encoding);
+    println!("compress_algorithm: {:?}", metadata.compress_algorithm);
+    println!("entries:            {}", dictionary.num_entries());
+    println!("license:            {}", or_unset(&metadata.license));
+    println!("source_url:         {}", or_unset(&metadata.source_url));
+    println!("source_version:     {}", or_unset(&metadata.source_version));
+    println!(
+        "built_at:           {}",
+        metadata
+            .built_at
+            .map(format_timestamp)
+            .unwrap_or_else(|| "-".to_string())
+    );
     Ok(())
 }
 
-fn build(args: BuildArgs) -> LinderaResult<()> {
-    let metadata: Metadata = serde_json::from_reader(
-        File::open(&args.metadata)
-            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?,
-    )
-    .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+fn export_dict(args: ExportDictArgs) -> LinderaResult<()> {
+    if let Some(user_dict) = &args.user_dict {
+        let user_dictionary = load_user_dictionary_from_bin(user_dict)?;
+        let writer: Box<dyn io::Write> = match &args.output {
+            Some(path) => Box::new(
+                File::create(path)
+                    .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?,
+            ),
+            None => Box::new(io::stdout().lock()),
+        };
+        return export_user_dictionary(&user_dictionary, BufWriter::new(writer));
+    }
 
-    let builder = DictionaryBuilder::new(metadata);
+    let (Some(dict), Some(output)) = (&args.dict, &args.output) else {
+        return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+            "--output is required to export a dictionary"
+        )));
+    };
+    let dictionary = load_dictionary(dict)?;
+    export_dictionary(&dictionary, output)?;
+    eprintln!(
+        "{} entries exported into {}",
+        dictionary.num_entries(),
+        output.display()
+    );
+    Ok(())
+}
 
-    if args.user {
-        let output_file = if let Some(filename) = args.src.file_name() {
-            let mut output_file = Path::new(&args.dest).join(filename);
-            output_file.set_extension("bin");
-            output_file
+fn diff_dict(args: DiffDictArgs) -> LinderaResult<()> {
+    let output_json = match args.output.as_str() {
+        "text" => false,
+        "json" => true,
+        _ => {
+            return Err(LinderaErrorKind::Args
+                .with_error(anyhow::anyhow!("Invalid format: {}", args.output)));
+        }
+    };
+
+    let old = load_dictionary(&args.old)?;
+    let new = load_dictionary(&args.new)?;
+    let diff = diff_dictionaries(&old, &new);
+
+    if output_json {
+        let report = if args.summary {
+            serde_json::json!({
+                "added": diff.added.len(),
+                "removed": diff.removed.len(),
+                "changed": diff.changed.len(),
+                "mean_cost_delta": diff.mean_cost_delta(),
+                "old_matrix_size": diff.old_matrix_size,
+                "new_matrix_size": diff.new_matrix_size,
+                "changed_connection_costs": diff.changed_connection_costs,
+            })
         } else {
-            return Err(LinderaErrorKind::Io.with_error(anyhow::anyhow!("failed to get filename")));
+            serde_json::to_value(&diff)
+                .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?
         };
-        builder.build_user_dictionary(&args.src, &output_file)
-    } else {
-        builder.build_dictionary(&args.src, &args.dest)
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report)
+                .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?
+        );
+        return Ok(());
+    }
+
+    if !args.summary {
+        let row = |surface: &str, ids_and_cost: (u16, u16, i16), details: &[String]| {
+            let (left_id, right_id, word_cost) = ids_and_cost;
+            let mut fields = vec![
+                surface.to_string(),
+                left_id.to_string(),
+                right_id.to_string(),
+                word_cost.to_string(),
+            ];
+            fields.extend(details.iter().cloned());
+            fields.join(",")
+        };
+        for entry in &diff.removed {
+            let ids_and_cost = (entry.left_id, entry.right_id, entry.word_cost);
+            println!("- {}", row(&entry.surface, ids_and_cost, &entry.details));
+        }
+        for entry in &diff.added {
+            let ids_and_cost = (entry.left_id, entry.right_id, entry.word_cost);
+            println!("+ {}", row(&entry.surface, ids_and_cost, &entry.details));
+        }
+        for entry in &diff.changed {
+            println!(
+                "~ {} -> {},{},{} (cost {:+})",
+                row(&entry.surface, entry.old, &entry.details),
+                entry.new.0,
+                entry.new.1,
+                entry.new.2,
+                entry.cost_delta()
+            );
+        }
+    }
+
+    println!(
+        "added: {}, removed: {}, changed: {} (mean cost delta {:+.1})",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len(),
+        diff.mean_cost_delta()
+    );
+    match diff.changed_connection_costs {
+        Some(changed) => println!(
+            "connection costs: {changed} of {} changed",
+            diff.new_matrix_size.0 as usize * diff.new_matrix_size.1 as usize
+        ),
+        None => println!(
+            "connection costs: matrix resized from {}x{} to {}x{}",
+            diff.old_matrix_size.0,
+            diff.old_matrix_size.1,
+            diff.new_matrix_size.0,
+            diff.new_matrix_size.1
+        ),
+    }
+    Ok(())
+}
+
+fn candidates(args: CandidatesArgs) -> LinderaResult<()> {
+    let output_json = match args.output.as_str() {
+        "text" => false,
+        "json" => true,
+        _ => {
+            return Err(LinderaErrorKind::Args
+                .with_error(anyhow::anyhow!("Invalid format: {}", args.output)));
+        }
+    };
+
+    let reading_index = load_reading_index(&args.dict)?;
+    let candidates = reading_index.candidates(&args.reading, args.limit);
+
+    if output_json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&candidates)
+                .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?
+        );
+        return Ok(());
+    }
+
+    for candidate in candidates {
+        println!(
+            "{}\t{}\t{}",
+            candidate.surface, candidate.reading, candidate.word_cost
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "signature")]
+fn sign(args: SignArgs) -> LinderaResult<()> {
+    use lindera::dictionary::signature::SigningKey;
+
+    if args.generate_key {
+        if args.key.exists() {
+            return Err(LinderaErrorKind::Args
+                .with_error(anyhow::anyhow!("{} already exists", args.key.display())));
+        }
+        let key = SigningKey::generate()?;
+        std::fs::write(&args.key, format!("{}\n", key.seed_hex()))
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+        // The public key is what the loaders are configured to trust
+        println!("{}", key.public_key());
+        return Ok(());
     }
+
+    let key = SigningKey::from_file(&args.key)?;
+    let Some(path) = args.path else {
+        return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!("no path to sign")));
+    };
+    let signature_path = if path.is_dir() {
+        key.sign_dictionary(&path)?
+    } else {
+        key.sign_file(&path)?
+    };
+    println!("{}", signature_path.display());
+    Ok(())
 }
 
 #[cfg(feature = "train")]
@@ -592,3 +2791,41 @@ fn export(args: ExportArgs) -> LinderaResult<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokenize_args(flags: &[&str]) -> TokenizeArgs {
+        let args = Args::try_parse_from(["lindera", "tokenize"].iter().chain(flags)).unwrap();
+        match args.command {
+            Commands::Tokenize(args) => args,
+            command => panic!("unexpected command: {command:?}"),
+        }
+    }
+
+    #[test]
+    fn test_boolean_flags_override_config() {
+        let args = tokenize_args(&[]);
+        assert_eq!(None, args.keep_whitespace);
+        assert_eq!(None, args.quote);
+
+        let args = tokenize_args(&["--keep-whitespace", "--quote", "--sentence-per-line"]);
+        assert_eq!(Some(true), args.keep_whitespace);
+        assert_eq!(Some(true), args.quote);
+        assert_eq!(Some(true), args.sentence_per_line);
+
+        // `keep_whitespace = true` in the config file is turned off from the command line
+        let config = ProfileConfig {
+            keep_whitespace: Some(true),
+            ..Default::default()
+        };
+        let args = tokenize_args(&["--keep-whitespace=false"]);
+        assert_eq!(Some(false), args.keep_whitespace.or(config.keep_whitespace));
+
+        // The value is only taken with `=`, so the input file is not read as one
+        let args = tokenize_args(&["--keep-whitespace", "input.txt"]);
+        assert_eq!(Some(true), args.keep_whitespace);
+        assert_eq!(Some(PathBuf::from("input.txt")), args.input_file);
+    }
+}
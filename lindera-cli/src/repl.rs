@@ -0,0 +1,284 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+
+use lindera::LinderaResult;
+use lindera::dictionary::Lattice;
+use lindera::error::LinderaErrorKind;
+use lindera::mode::Mode;
+use lindera::token::Token;
+use lindera::tokenizer::{Tokenizer, TokenizerBuilder};
+
+const HELP: &str = "\
+Enter text to tokenize it, or one of the following commands:
+  :dict <uri>        Switch the dictionary (e.g., embedded://ipadic, /path/to/dictionary)
+  :user-dict <uri>   Switch the user dictionary (`:user-dict none` to remove it)
+  :mode <mode>       Switch the tokenization mode (normal|decompose)
+  :lattice <text>    Show all candidate edges of the lattice built for the text
+  :status            Show the current settings
+  :help              Show this help
+  :quit              Exit the REPL";
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+
+/// Returns the ANSI color for the major part-of-speech tag.
+fn pos_color(pos: &str) -> &'static str {
+    match pos {
+        // Nouns
+        "名詞" | "代名詞" | "NNG" | "NNP" | "NNB" | "NP" => "\x1b[34m",
+        // Verbs
+        "動詞" | "VV" | "VX" => "\x1b[32m",
+        // Adjectives and adverbs
+        "形容詞" | "形状詞" | "副詞" | "連体詞" | "VA" | "MAG" | "MM" => "\x1b[33m",
+        // Particles and auxiliary verbs
+        "助詞" | "助動詞" | "JKS" | "JKO" | "JKB" | "JX" | "EF" | "EC" | "EP" => "\x1b[35m",
+        // Symbols
+        "記号" | "補助記号" | "SF" | "SP" | "SS" => "\x1b[90m",
+        _ => "\x1b[36m",
+    }
+}
+
+/// Interactive tokenizer session that keeps the dictionary loaded between inputs.
+pub struct Repl {
+    dict: String,
+    user_dict: Option<String>,
+    mode: Mode,
+    color: bool,
+    tokenizer: Tokenizer,
+}
+
+impl Repl {
+    pub fn new(
+        dict: String,
+        user_dict: Option<String>,
+        mode: Mode,
+        color: bool,
+    ) -> LinderaResult<Self> {
+        let tokenizer = build_tokenizer(&dict, user_dict.as_deref(), &mode)?;
+        Ok(Self {
+            dict,
+            user_dict,
+            mode,
+            color,
+            tokenizer,
+        })
+    }
+
+    /// Reads lines from the terminal until `:quit` or end of input.
+    pub fn run(&mut self) -> LinderaResult<()> {
+        let mut editor = DefaultEditor::new()
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+
+        println!("Type :help for a list of commands.");
+        loop {
+            let line = match editor.readline("lindera> ") {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break,
+                Err(err) => {
+                    return Err(LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)));
+                }
+            };
+
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let _ = editor.add_history_entry(line);
+
+            match self.eval(line) {
+                Ok(true) => {}
+                Ok(false) => break,
+                // Errors are reported without leaving the session.
+                Err(err) => eprintln!("{}", self.paint(&err.to_string(), "\x1b[31m")),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Evaluates a single line of input.
+    /// Returns `false` if the session should end.
+    pub fn eval(&mut self, line: &str) -> LinderaResult<bool> {
+        let Some(command) = line.strip_prefix(':') else {
+            let tokens = self.tokenizer.tokenize(line)?;
+            self.print_tokens(tokens);
+            return Ok(true);
+        };
+
+        let (name, arg) = match command.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, arg.trim()),
+            None => (command, ""),
+        };
+
+        match name {
+            "q" | "quit" | "exit" => return Ok(false),
+            "h" | "help" => println!("{HELP}"),
+            "status" => self.print_status(),
+            "dict" => {
+                let dict = required_arg(name, arg)?;
+                self.tokenizer = build_tokenizer(dict, self.user_dict.as_deref(), &self.mode)?;
+                self.dict = dict.to_string();
+                self.print_status();
+            }
+            "user-dict" => {
+                let user_dict = match required_arg(name, arg)? {
+                    "none" => None,
+                    uri => Some(uri.to_string()),
+                };
+                self.tokenizer = build_tokenizer(&self.dict, user_dict.as_deref(), &self.mode)?;
+                self.user_dict = user_dict;
+                self.print_status();
+            }
+            "mode" => {
+                let mode = Mode::from_str(required_arg(name, arg)?)?;
+                self.tokenizer = build_tokenizer(&self.dict, self.user_dict.as_deref(), &mode)?;
+                self.mode = mode;
+                self.print_status();
+            }
+            "lattice" => self.print_lattice(required_arg(name, arg)?),
+            _ => {
+                return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                    "Unknown command: :{name} (type :help for a list of commands)"
+                )));
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn paint(&self, text: &str, color: &str) -> String {
+        if self.color {
+            format!("{color}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn print_status(&self) {
+        println!("dictionary: {}", self.dict);
+        println!(
+            "user dictionary: {}",
+            self.user_dict.as_deref().unwrap_or("none")
+        );
+        println!("mode: {}", self.mode.as_str());
+    }
+
+    fn print_tokens(&self, mut tokens: Vec<Token>) {
+        for token in tokens.iter_mut() {
+            let surface = token.surface.to_string();
+            let details = token.details();
+            let color = pos_color(details.first().copied().unwrap_or_default());
+            println!(
+                "{}\t{}",
+                self.paint(&surface, &format!("{BOLD}{color}")),
+                self.paint(&details.join(","), color)
+            );
+        }
+        println!("EOS");
+    }
+
+    /// Prints the edges of the lattice for the text, marking the edges on the best path with `*`.
+    /// Character filters and token filters are not applied.
+    fn print_lattice(&self, text: &str) {
+        let segmenter = &self.tokenizer.segmenter;
+        let dictionary = &segmenter.dictionary;
+
+        let mut lattice = Lattice::default();
+        lattice.set_text_with_variants(
+            &dictionary.prefix_dictionary,
+            &segmenter.user_dictionary.as_ref().map(|d| &d.dict),
+            &segmenter.okurigana_variants.as_ref().map(|v| &v.dict),
+            &dictionary.character_definition,
+            &dictionary.unknown_dictionary,
+            &dictionary.connection_cost_matrix,
+            text,
+            &segmenter.mode,
+        );
+
+        // (start, end, word id) of the edges on the best path
+        let offsets = lattice.tokens_offset();
+        let best_path: HashSet<(usize, usize, u32)> = offsets
+            .iter()
+            .enumerate()
+            .map(|(i, (start, word_id))| {
+                let end = offsets.get(i + 1).map_or(text.len(), |(next, _)| *next);
+                (*start, end, word_id.id)
+            })
+            .collect();
+
+        let mut edges: Vec<_> = lattice
+            .edges()
+            .filter(|edge| edge.start_index < edge.stop_index)
+            .collect();
+        edges.sort_by_key(|edge| (edge.start_index, edge.stop_index));
+
+        println!(
+            "{}",
+            self.paint(
+                &format!(
+                    "  {:>5} {:>5}  {:<12} {:<8} {:>6} {:>6} {:>6} {:>10}  details",
+                    "start", "end", "surface", "type", "left", "right", "cost", "path_cost"
+                ),
+                DIM
+            )
+        );
+        for edge in edges {
+            let start = edge.start_index as usize;
+            let stop = edge.stop_index as usize;
+            let word_id = edge.word_entry.word_id;
+            let details = if word_id.is_unknown() {
+                vec!["UNK"]
+            } else if word_id.is_system() {
+                dictionary.word_details(word_id.id as usize)
+            } else {
+                match &segmenter.user_dictionary {
+                    Some(user_dictionary) => user_dictionary.word_details(word_id.id as usize),
+                    None => vec!["UNK"],
+                }
+            };
+            let on_best_path = best_path.contains(&(start, stop, word_id.id));
+            let line = format!(
+                "{} {:>5} {:>5}  {:<12} {:<8} {:>6} {:>6} {:>6} {:>10}  {}",
+                if on_best_path { "*" } else { " " },
+                start,
+                stop,
+                &text[start..stop],
+                format!("{:?}", edge.edge_type),
+                edge.word_entry.left_id,
+                edge.word_entry.right_id,
+                edge.word_entry.word_cost,
+                edge.path_cost,
+                details.join(",")
+            );
+            if on_best_path {
+                let color = pos_color(details.first().copied().unwrap_or_default());
+                println!("{}", self.paint(&line, &format!("{BOLD}{color}")));
+            } else {
+                println!("{line}");
+            }
+        }
+    }
+}
+
+fn required_arg<'a>(command: &str, arg: &'a str) -> LinderaResult<&'a str> {
+    if arg.is_empty() {
+        Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!("Missing argument for :{command}")))
+    } else {
+        Ok(arg)
+    }
+}
+
+fn build_tokenizer(dict: &str, user_dict: Option<&str>, mode: &Mode) -> LinderaResult<Tokenizer> {
+    let mut builder = TokenizerBuilder::new()?;
+    builder.set_segmenter_dictionary(dict);
+    if let Some(user_dict) = user_dict {
+        builder.set_segmenter_user_dictionary(user_dict);
+    }
+    builder.set_segmenter_mode(mode);
+    builder.build()
+}
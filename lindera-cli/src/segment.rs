@@ -0,0 +1,336 @@
+//! Segmentation of a whole corpus into wakati text on all cores, for preprocessing the training
+//! data of word embeddings and language models.
+//!
+//! The corpus is split into chunks at line breaks, the chunks are segmented in parallel with a
+//! shared tokenizer and the output is written in the order of the input.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use lindera::LinderaResult;
+use lindera::dictionary::Lattice;
+use lindera::error::LinderaErrorKind;
+use lindera::tokenizer::Tokenizer;
+
+/// Characters that end a sentence with `sentence_per_line`.
+pub const SENTENCE_TERMINATORS: &[char] = &['。', '．', '！', '？', '!', '?'];
+
+/// Default size in bytes of the chunks of input handed to the threads.
+pub const DEFAULT_CHUNK_SIZE: usize = 1 << 20;
+
+/// Number of chunks per thread segmented before the output is written.
+const CHUNKS_PER_THREAD: usize = 4;
+
+/// The input corpus, memory-mapped if it is a file and the `mmap` feature is enabled.
+pub struct Corpus {
+    data: Box<dyn AsRef<[u8]>>,
+}
+
+impl Corpus {
+    pub fn from_file(path: &Path) -> LinderaResult<Self> {
+        #[cfg(feature = "mmap")]
+        {
+            let len = std::fs::metadata(path)
+                .map_err(|err| {
+                    LinderaErrorKind::Io
+                        .with_error(anyhow::anyhow!(err))
+                        .add_context(format!("Failed to read input file: {}", path.display()))
+                })?
+                .len();
+            // An empty file cannot be mapped
+            if len > 0 {
+                let mmap = lindera::dictionary::mmap_file(path)?;
+                return Ok(Self {
+                    data: Box::new(mmap),
+                });
+            }
+        }
+
+        let file = File::open(path).map_err(|err| {
+            LinderaErrorKind::Io
+                .with_error(anyhow::anyhow!(err))
+                .add_context(format!("Failed to open input file: {}", path.display()))
+        })?;
+        Self::from_reader(file)
+    }
+
+    pub fn from_reader<R: Read>(mut reader: R) -> LinderaResult<Self> {
+        let mut data = Vec::new();
+        reader
+            .read_to_end(&mut data)
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+        Ok(Self {
+            data: Box::new(data),
+        })
+    }
+
+    /// Returns the corpus as text, which must be valid UTF-8.
+    pub fn text(&self) -> LinderaResult<&str> {
+        std::str::from_utf8((*self.data).as_ref()).map_err(|err| {
+            LinderaErrorKind::Decode.with_error(anyhow::anyhow!("input is not valid UTF-8: {err}"))
+        })
+    }
+}
+
+/// Options of [`segment`].
+#[derive(Debug, Clone)]
+pub struct SegmentOptions {
+    /// String placed between tokens.
+    pub separator: String,
+    /// Start a new line after each token ending with a sentence terminator, and skip empty lines.
+    pub sentence_per_line: bool,
+    /// Number of threads.
+    pub threads: usize,
+    /// Size in bytes of the chunks of input handed to the threads.
+    pub chunk_size: usize,
+}
+
+impl Default for SegmentOptions {
+    fn default() -> Self {
+        Self {
+            separator: " ".to_string(),
+            sentence_per_line: false,
+            threads: num_cpus::get(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+}
+
+/// Throughput of [`segment`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SegmentReport {
+    pub bytes: usize,
+    pub lines: usize,
+    pub tokens: usize,
+    pub elapsed: Duration,
+}
+
+impl SegmentReport {
+    pub fn megabytes_per_sec(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.bytes as f64 / 1_000_000.0 / secs
+        } else {
+            0.0
+        }
+    }
+
+    pub fn tokens_per_sec(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.tokens as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+impl fmt::Display for SegmentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "segmented {} lines ({:.1} MB) into {} tokens in {:.2}s: {:.1} MB/s, {:.0} tokens/s",
+            self.lines,
+            self.bytes as f64 / 1_000_000.0,
+            self.tokens,
+            self.elapsed.as_secs_f64(),
+            self.megabytes_per_sec(),
+            self.tokens_per_sec()
+        )
+    }
+}
+
+/// Splits the text into chunks of at least `chunk_size` bytes that end at a line break, except
+/// for the last one.
+pub fn split_chunks(text: &str, chunk_size: usize) -> Vec<&str> {
+    let chunk_size = chunk_size.max(1);
+
+    let mut chunks = Vec::with_capacity(text.len() / chunk_size + 1);
+    let mut rest = text;
+    while !rest.is_empty() {
+        let end = if rest.len() <= chunk_size {
+            rest.len()
+        } else {
+            rest.as_bytes()[chunk_size..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(rest.len(), |i| chunk_size + i + 1)
+        };
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks
+}
+
+/// Segments the lines of a chunk, appending the wakati text to `output`.
+///
+/// Returns the number of lines and tokens.
+fn segment_chunk(
+    tokenizer: &Tokenizer,
+    lattice: &mut Lattice,
+    chunk: &str,
+    options: &SegmentOptions,
+    output: &mut String,
+) -> LinderaResult<(usize, usize)> {
+    let mut lines = 0;
+    let mut tokens = 0;
+    for line in chunk.lines() {
+        lines += 1;
+
+        let text = line.trim();
+        if text.is_empty() {
+            // Keep the output aligned with the input lines
+            if !options.sentence_per_line {
+                output.push('\n');
+            }
+            continue;
+        }
+
+        let mut line_start = true;
+        for token in tokenizer.tokenize_with_lattice(text, lattice)? {
+            if !line_start {
+                output.push_str(&options.separator);
+            }
+            output.push_str(&token.surface);
+            line_start = false;
+            tokens += 1;
+
+            if options.sentence_per_line && token.surface.ends_with(SENTENCE_TERMINATORS) {
+                output.push('\n');
+                line_start = true;
+            }
+        }
+        if !line_start || !options.sentence_per_line {
+            output.push('\n');
+        }
+    }
+    Ok((lines, tokens))
+}
+
+/// Segments every line of the text into wakati text written to `writer`, using
+/// `options.threads` threads.
+pub fn segment<W: Write>(
+    tokenizer: &Tokenizer,
+    text: &str,
+    options: &SegmentOptions,
+    writer: &mut W,
+) -> LinderaResult<SegmentReport> {
+    let start = Instant::now();
+    let threads = options.threads.max(1);
+    let chunks = split_chunks(text, options.chunk_size);
+
+    let mut report = SegmentReport {
+        bytes: text.len(),
+        ..Default::default()
+    };
+    for batch in chunks.chunks(threads * CHUNKS_PER_THREAD) {
+        let mut results = thread::scope(|scope| {
+            let handles = (0..threads.min(batch.len()))
+                .map(|worker| {
+                    scope.spawn(move || {
+                        let mut lattice = Lattice::default();
+                        batch
+                            .iter()
+                            .enumerate()
+                            .skip(worker)
+                            .step_by(threads)
+                            .map(|(index, chunk)| {
+                                let mut output = String::with_capacity(chunk.len() * 3 / 2);
+                                let (lines, tokens) = segment_chunk(
+                                    tokenizer,
+                                    &mut lattice,
+                                    chunk,
+                                    options,
+                                    &mut output,
+                                )?;
+                                Ok((index, output, lines, tokens))
+                            })
+                            .collect::<LinderaResult<Vec<_>>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|err| std::panic::resume_unwind(err))
+                })
+                .collect::<LinderaResult<Vec<_>>>()
+        })?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        results.sort_unstable_by_key(|(index, ..)| *index);
+
+        for (_, output, lines, tokens) in results {
+            writer
+                .write_all(output.as_bytes())
+                .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+            report.lines += lines;
+            report.tokens += tokens;
+        }
+    }
+    writer
+        .flush()
+        .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+
+    report.elapsed = start.elapsed();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_chunks() {
+        let text = "すもも\nもももももも\nもものうち\n";
+        assert_eq!(
+            vec!["すもも\nもももももも\n", "もものうち\n"],
+            split_chunks(text, 12)
+        );
+        assert_eq!(vec![text], split_chunks(text, 1000));
+        assert_eq!(
+            vec!["すもも\n", "もももももも\n", "もものうち\n"],
+            split_chunks(text, 1)
+        );
+        assert_eq!(vec!["a\n", "b"], split_chunks("a\nb", 0));
+        assert!(split_chunks("", 10).is_empty());
+
+        // The chunks cover the text
+        assert_eq!(text, split_chunks(text, 5).concat());
+    }
+
+    #[test]
+    fn test_corpus_text() {
+        let corpus = Corpus::from_reader("すもも\n".as_bytes()).unwrap();
+        assert_eq!("すもも\n", corpus.text().unwrap());
+
+        let corpus = Corpus::from_reader(&b"\xff\xfe"[..]).unwrap();
+        assert_eq!(LinderaErrorKind::Decode, corpus.text().unwrap_err().kind());
+    }
+
+    #[test]
+    fn test_segment_report() {
+        let report = SegmentReport {
+            bytes: 200_000_000,
+            lines: 1_000,
+            tokens: 50_000_000,
+            elapsed: Duration::from_secs(2),
+        };
+        assert_eq!(100.0, report.megabytes_per_sec());
+        assert_eq!(25_000_000.0, report.tokens_per_sec());
+        assert_eq!(
+            "segmented 1000 lines (200.0 MB) into 50000000 tokens in 2.00s: 100.0 MB/s, 25000000 tokens/s",
+            report.to_string()
+        );
+        assert_eq!(0.0, SegmentReport::default().megabytes_per_sec());
+    }
+}
@@ -0,0 +1,683 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use lindera::LinderaResult;
+use lindera::character_filter::{BoxCharacterFilter, CharacterFilterLoader};
+use lindera::dictionary::{load_dictionary, load_user_dictionary};
+use lindera::error::{LinderaError, LinderaErrorKind};
+use lindera::mode::Mode;
+use lindera::token_filter::TokenFilterLoader;
+use lindera::tokenizer::schema::parse_config_strict;
+
+/// Text used to check that the offsets produced by character filters can be corrected.
+/// It mixes characters that the bundled character filters are likely to rewrite.
+const OFFSET_PROBE_TEXT: &str = "ＡＢＣ１２３ ｱｲｳｴｵ ｶﾞｷﾞ abc ABC 123 \
+    すゝめ 時々 ㍿ ① Ⅻ café cafe\u{301} 東京スカイツリーの最寄り駅はとうきょうスカイツリー駅です。\n";
+
+const TOP_LEVEL_KEYS: &[&str] = &[
+    "segmenter",
+    "character_filters",
+    "token_filters",
+    "audit_offsets",
+    "max_input_bytes",
+    "timeout_ms",
+    "cache_capacity",
+];
+const SEGMENTER_KEYS: &[&str] = &[
+    "dictionary",
+    "user_dictionary",
+    "mode",
+    "keep_whitespace",
+    "okurigana_variants",
+    "trusted_keys",
+    "shared_dictionary",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found in an analyzer config, located by a JSON pointer (RFC 6901).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub severity: Severity,
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{}: {}: {}", self.severity, pointer, self.message)
+    }
+}
+
+/// Reads an analyzer config file (JSON, or YAML for `.yaml` / `.yml` files).
+pub fn read_config(path: &Path) -> LinderaResult<Value> {
+    let content = fs::read_to_string(path).map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to read config file: {}", path.display()))
+    })?;
+
+    let is_yaml = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"));
+
+    if is_yaml {
+        serde_yaml_ng::from_str(&content).map_err(|err| {
+            LinderaErrorKind::Deserialize
+                .with_error(anyhow::anyhow!(err))
+                .add_context(format!(
+                    "Failed to parse YAML config file: {}",
+                    path.display()
+                ))
+        })
+    } else {
+        serde_json::from_str(&content).map_err(|err| {
+            LinderaErrorKind::Deserialize
+                .with_error(anyhow::anyhow!(err))
+                .add_context(format!(
+                    "Failed to parse JSON config file: {}",
+                    path.display()
+                ))
+        })
+    }
+}
+
+/// Reads an analyzer config file like [`read_config`], but fails on a key that the config does
+/// not define, with the line and column of the key.
+pub fn read_config_strict(path: &Path) -> LinderaResult<Value> {
+    let content = fs::read_to_string(path).map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to read config file: {}", path.display()))
+    })?;
+
+    parse_config_strict(&content)
+}
+
+/// Validates an analyzer (tokenizer) config and returns all problems found.
+///
+/// The config has the same shape as the one accepted by `TokenizerBuilder::from_config`.
+/// Relative file references are resolved against `base_dir`.
+pub fn validate_config(config: &Value, base_dir: &Path) -> Vec<Issue> {
+    let mut validator = Validator {
+        base_dir,
+        issues: Vec::new(),
+    };
+    validator.validate(config);
+    validator.issues
+}
+
+struct Validator<'a> {
+    base_dir: &'a Path,
+    issues: Vec<Issue>,
+}
+
+impl Validator<'_> {
+    fn error(&mut self, pointer: String, message: impl Into<String>) {
+        self.issues.push(Issue {
+            severity: Severity::Error,
+            pointer,
+            message: message.into(),
+        });
+    }
+
+    fn warning(&mut self, pointer: String, message: impl Into<String>) {
+        self.issues.push(Issue {
+            severity: Severity::Warning,
+            pointer,
+            message: message.into(),
+        });
+    }
+
+    fn validate(&mut self, config: &Value) {
+        let Some(config) = config.as_object() else {
+            self.error(String::new(), "config must be an object");
+            return;
+        };
+
+        for key in config.keys() {
+            if !TOP_LEVEL_KEYS.contains(&key.as_str()) {
+                self.warning(
+                    pointer(&[key]),
+                    format!(
+                        "unknown key is ignored (expected one of: {})",
+                        TOP_LEVEL_KEYS.join(", ")
+                    ),
+                );
+            }
+        }
+
+        if let Some(audit_offsets) = config.get("audit_offsets")
+            && !audit_offsets.is_boolean()
+        {
+            self.error(
+                pointer(&["audit_offsets"]),
+                "audit_offsets must be a boolean",
+            );
+        }
+
+        for key in ["max_input_bytes", "timeout_ms", "cache_capacity"] {
+            if let Some(value) = config.get(key)
+                && !value.is_u64()
+            {
+                self.error(
+                    pointer(&[key]),
+                    format!("{key} must be a non-negative integer"),
+                );
+            }
+        }
+
+        match config.get("segmenter") {
+            Some(segmenter) => self.validate_segmenter(segmenter),
+            None => self.error(pointer(&["segmenter"]), "segmenter is required"),
+        }
+
+        let character_filters = self.validate_filters(config, "character_filters", |kind, args| {
+            CharacterFilterLoader::load_from_value(kind, args).map(Some)
+        });
+        self.validate_offsets(character_filters);
+
+        self.validate_filters(config, "token_filters", |kind, args| {
+            TokenFilterLoader::load_from_value(kind, args).map(|_| None::<()>)
+        });
+    }
+
+    fn validate_segmenter(&mut self, segmenter: &Value) {
+        let Some(segmenter) = segmenter.as_object() else {
+            self.error(pointer(&["segmenter"]), "segmenter must be an object");
+            return;
+        };
+
+        for key in segmenter.keys() {
+            if !SEGMENTER_KEYS.contains(&key.as_str()) {
+                self.warning(
+                    pointer(&["segmenter", key]),
+                    format!(
+                        "unknown key is ignored (expected one of: {})",
+                        SEGMENTER_KEYS.join(", ")
+                    ),
+                );
+            }
+        }
+
+        let dictionary = match segmenter.get("dictionary") {
+            Some(Value::String(uri)) => match load_dictionary(&self.resolve_uri(uri)) {
+                Ok(dictionary) => Some(dictionary),
+                Err(err) => {
+                    self.error(
+                        pointer(&["segmenter", "dictionary"]),
+                        format!("failed to load dictionary {uri:?}: {}", message(&err)),
+                    );
+                    None
+                }
+            },
+            Some(_) => {
+                self.error(
+                    pointer(&["segmenter", "dictionary"]),
+                    "dictionary must be a string (path or URI)",
+                );
+                None
+            }
+            None => {
+                self.error(
+                    pointer(&["segmenter", "dictionary"]),
+                    "dictionary is required (e.g., \"embedded://ipadic\" or \"/path/to/dictionary\")",
+                );
+                None
+            }
+        };
+
+        match segmenter.get("user_dictionary") {
+            Some(Value::String(uri)) => {
+                if let Some(dictionary) = &dictionary
+                    && let Err(err) =
+                        load_user_dictionary(&self.resolve_uri(uri), &dictionary.metadata)
+                {
+                    self.error(
+                        pointer(&["segmenter", "user_dictionary"]),
+                        format!("failed to load user dictionary {uri:?}: {}", message(&err)),
+                    );
+                }
+            }
+            Some(_) => self.error(
+                pointer(&["segmenter", "user_dictionary"]),
+                "user_dictionary must be a string (path or URI)",
+            ),
+            None => {}
+        }
+
+        match segmenter.get("mode") {
+            Some(Value::String(mode)) => {
+                if let Err(err) = Mode::from_str(mode) {
+                    self.error(
+                        pointer(&["segmenter", "mode"]),
+                        format!("invalid mode {mode:?}: {}", message(&err)),
+                    );
+                }
+            }
+            Some(mode) => {
+                if let Err(err) = serde_json::from_value::<Mode>(mode.clone()) {
+                    self.error(
+                        pointer(&["segmenter", "mode"]),
+                        format!("invalid mode: {err}"),
+                    );
+                }
+            }
+            None => {}
+        }
+
+        let keep_whitespace = match segmenter.get("keep_whitespace") {
+            Some(Value::Bool(keep_whitespace)) => *keep_whitespace,
+            Some(_) => {
+                self.error(
+                    pointer(&["segmenter", "keep_whitespace"]),
+                    "keep_whitespace must be a boolean",
+                );
+                true
+            }
+            None => false,
+        };
+        if !keep_whitespace
+            && let Some(dictionary) = &dictionary
+            && dictionary
+                .character_definition
+                .category_id_by_name("SPACE")
+                .is_none()
+        {
+            self.error(
+                pointer(&["segmenter"]),
+                "the SPACE category is not defined in char.def of the dictionary, so whitespace cannot be ignored; set keep_whitespace to true",
+            );
+        }
+
+        if let Some(shared_dictionary) = segmenter.get("shared_dictionary")
+            && !shared_dictionary.is_boolean()
+        {
+            self.error(
+                pointer(&["segmenter", "shared_dictionary"]),
+                "shared_dictionary must be a boolean",
+            );
+        }
+
+        match segmenter.get("trusted_keys") {
+            Some(Value::Array(keys)) => {
+                for (i, key) in keys.iter().enumerate() {
+                    let is_public_key = key.as_str().is_some_and(|key| {
+                        key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit())
+                    });
+                    if !is_public_key {
+                        self.error(
+                            pointer(&["segmenter", "trusted_keys", &i.to_string()]),
+                            "trusted key must be a hex-encoded 32-byte ed25519 public key",
+                        );
+                    }
+                }
+            }
+            Some(_) => self.error(
+                pointer(&["segmenter", "trusted_keys"]),
+                "trusted_keys must be an array of public keys",
+            ),
+            None => {}
+        }
+    }
+
+    /// Validates the filter list under `key` and returns the filters that
+    /// `load` returned, along with their index.
+    fn validate_filters<F, T>(
+        &mut self,
+        config: &serde_json::Map<String, Value>,
+        key: &str,
+        load: F,
+    ) -> Vec<(usize, T)>
+    where
+        F: Fn(&str, &Value) -> LinderaResult<Option<T>>,
+    {
+        let mut loaded = Vec::new();
+
+        let Some(filters) = config.get(key) else {
+            return loaded;
+        };
+        let Some(filters) = filters.as_array() else {
+            self.error(pointer(&[key]), format!("{key} must be an array"));
+            return loaded;
+        };
+
+        for (i, filter) in filters.iter().enumerate() {
+            let index = i.to_string();
+            let Some(filter) = filter.as_object() else {
+                self.error(
+                    pointer(&[key, &index]),
+                    "filter must be an object with \"kind\" and \"args\"",
+                );
+                continue;
+            };
+
+            let Some(kind) = filter.get("kind").and_then(Value::as_str) else {
+                self.error(
+                    pointer(&[key, &index, "kind"]),
+                    "kind is required and must be a string",
+                );
+                continue;
+            };
+
+            let args = filter.get("args").unwrap_or(&Value::Null);
+            self.validate_file_references(args, &[key, &index, "args"]);
+
+            match load(kind, args) {
+                Ok(Some(filter)) => loaded.push((i, filter)),
+                Ok(None) => {}
+                Err(err) => self.error(
+                    pointer(&[key, &index]),
+                    format!("invalid {kind:?} filter: {}", message(&err)),
+                ),
+            }
+        }
+
+        loaded
+    }
+
+    /// Checks that string arguments that look like file references (keys named `path` or `file`,
+    /// or ending with `_path` or `_file`, and the items of arrays named `paths` or ending with
+    /// `_paths`) point to existing files.
+    fn validate_file_references(&mut self, value: &Value, tokens: &[&str]) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    let mut child = tokens.to_vec();
+                    child.push(key);
+                    let is_file_key = key == "path"
+                        || key == "file"
+                        || key.ends_with("_path")
+                        || key.ends_with("_file");
+                    let is_files_key = key == "paths" || key.ends_with("_paths");
+                    match value {
+                        Value::String(path) if is_file_key => {
+                            self.validate_file_reference(path, &child);
+                        }
+                        Value::Array(paths) if is_files_key => {
+                            for (i, path) in paths.iter().enumerate() {
+                                let index = i.to_string();
+                                let mut item = child.clone();
+                                item.push(&index);
+                                match path {
+                                    Value::String(path) => {
+                                        self.validate_file_reference(path, &item)
+                                    }
+                                    _ => self.validate_file_references(path, &item),
+                                }
+                            }
+                        }
+                        _ => self.validate_file_references(value, &child),
+                    }
+                }
+            }
+            Value::Array(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    let index = i.to_string();
+                    let mut child = tokens.to_vec();
+                    child.push(&index);
+                    self.validate_file_references(value, &child);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn validate_file_reference(&mut self, path: &str, tokens: &[&str]) {
+        let path = self.base_dir.join(path);
+        if !path.is_file() {
+            self.error(
+                pointer(tokens),
+                format!("file not found: {}", path.display()),
+            );
+        }
+    }
+
+    /// Applies the character filters in order to a probe text and checks that the
+    /// offsets of the filtered text can be mapped back to character boundaries of
+    /// the input text, as the tokenizer does when correcting token offsets.
+    fn validate_offsets(&mut self, character_filters: Vec<(usize, BoxCharacterFilter)>) {
+        let mut text = OFFSET_PROBE_TEXT.to_string();
+
+        for (i, character_filter) in character_filters {
+            let index = i.to_string();
+            let input = text.clone();
+            let mapping = match character_filter.apply(&mut text) {
+                Ok(mapping) => mapping,
+                Err(err) => {
+                    self.error(
+                        pointer(&["character_filters", &index]),
+                        format!("failed to apply filter: {}", message(&err)),
+                    );
+                    return;
+                }
+            };
+
+            let mut previous = 0;
+            for (offset, _) in text.char_indices().chain([(text.len(), ' ')]) {
+                let corrected = mapping.correct_offset(offset, text.len());
+                let problem = if corrected > input.len() {
+                    Some("is beyond the end of the input")
+                } else if !input.is_char_boundary(corrected) {
+                    Some("is not a character boundary in the input")
+                } else if corrected < previous {
+                    Some("moves backwards")
+                } else {
+                    None
+                };
+                if let Some(problem) = problem {
+                    let mut start = corrected.min(input.len());
+                    while !input.is_char_boundary(start) {
+                        start -= 1;
+                    }
+                    let near: String = input[start..].chars().take(3).collect();
+                    self.warning(
+                        pointer(&["character_filters", &index]),
+                        format!(
+                            "token offsets may not be corrected reliably: filtered offset {offset} maps to {corrected}, which {problem} (near {near:?})"
+                        ),
+                    );
+                    break;
+                }
+                previous = corrected;
+            }
+        }
+    }
+
+    fn resolve_uri(&self, uri: &str) -> String {
+        if uri.contains("://") || Path::new(uri).is_absolute() {
+            uri.to_string()
+        } else {
+            self.base_dir.join(uri).to_string_lossy().to_string()
+        }
+    }
+}
+
+/// Builds a JSON pointer from reference tokens.
+fn pointer(tokens: &[&str]) -> String {
+    tokens
+        .iter()
+        .map(|token| format!("/{}", token.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+/// Returns the underlying message of the error without the kind.
+/// The message of an error, without the kind of the error.
+pub fn message(err: &LinderaError) -> String {
+    err.source()
+        .map_or_else(|| err.to_string(), |source| source.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn issues(config: Value) -> Vec<Issue> {
+        validate_config(&config, Path::new("."))
+    }
+
+    #[test]
+    fn test_pointer() {
+        assert_eq!(
+            pointer(&["token_filters", "0", "args"]),
+            "/token_filters/0/args"
+        );
+        assert_eq!(pointer(&["a/b", "c~d"]), "/a~1b/c~0d");
+    }
+
+    #[test]
+    fn test_missing_dictionary() {
+        let issues = issues(json!({"segmenter": {"mode": "normal"}}));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(issues[0].pointer, "/segmenter/dictionary");
+    }
+
+    #[test]
+    fn test_invalid_audit_offsets() {
+        let issues = issues(json!({"segmenter": {"mode": "normal"}, "audit_offsets": "yes"}));
+        assert!(
+            issues
+                .iter()
+                .any(|issue| issue.pointer == "/audit_offsets" && issue.severity == Severity::Error)
+        );
+    }
+
+    #[test]
+    fn test_invalid_limits() {
+        let issues = issues(json!({
+            "segmenter": {"mode": "normal"},
+            "max_input_bytes": -1,
+            "timeout_ms": "1s",
+            "cache_capacity": 1.5
+        }));
+        for key in ["/max_input_bytes", "/timeout_ms", "/cache_capacity"] {
+            assert!(
+                issues
+                    .iter()
+                    .any(|issue| issue.pointer == key && issue.severity == Severity::Error)
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_shared_dictionary() {
+        let issues = issues(json!({"segmenter": {"mode": "normal", "shared_dictionary": "yes"}}));
+        assert!(issues.iter().any(|issue| {
+            issue.pointer == "/segmenter/shared_dictionary" && issue.severity == Severity::Error
+        }));
+    }
+
+    #[test]
+    fn test_invalid_trusted_keys() {
+        let key_issues = issues(json!({
+            "segmenter": {"mode": "normal", "trusted_keys": ["0a".repeat(32), "0a", 1]}
+        }));
+        let pointers: Vec<&str> = key_issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .map(|issue| issue.pointer.as_str())
+            .collect();
+        assert!(!pointers.contains(&"/segmenter/trusted_keys/0"));
+        assert!(pointers.contains(&"/segmenter/trusted_keys/1"));
+        assert!(pointers.contains(&"/segmenter/trusted_keys/2"));
+
+        let key_issues = issues(json!({"segmenter": {"mode": "normal", "trusted_keys": "0a"}}));
+        assert!(
+            key_issues
+                .iter()
+                .any(|issue| issue.pointer == "/segmenter/trusted_keys")
+        );
+    }
+
+    #[test]
+    fn test_invalid_filters() {
+        let issues = issues(json!({
+            "segmenter": {"dictionary": "/path/to/nonexistent", "mode": "fast"},
+            "character_filters": [
+                {"kind": "unicode_normalize", "args": {"kind": "nfkc"}},
+                {"kind": "no_such_filter"}
+            ],
+            "token_filters": [
+                {"kind": "japanese_stop_tags", "args": {"tags": "助詞"}},
+                {"args": {}}
+            ],
+            "filters": []
+        }));
+
+        let pointers: Vec<(Severity, &str)> = issues
+            .iter()
+            .map(|issue| (issue.severity, issue.pointer.as_str()))
+            .collect();
+        assert_eq!(
+            pointers,
+            vec![
+                (Severity::Warning, "/filters"),
+                (Severity::Error, "/segmenter/dictionary"),
+                (Severity::Error, "/segmenter/mode"),
+                (Severity::Error, "/character_filters/1"),
+                // NFKC expands characters such as "㍿" into several characters
+                (Severity::Warning, "/character_filters/0"),
+                (Severity::Error, "/token_filters/0"),
+                (Severity::Error, "/token_filters/1/kind"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_references() {
+        let issues = issues(json!({
+            "segmenter": {"dictionary": "/path/to/nonexistent"},
+            "token_filters": [
+                {"kind": "stop_words", "args": {"words": ["a"], "words_path": "no/such/file.txt"}}
+            ]
+        }));
+        assert!(issues.iter().any(|issue| {
+            issue.severity == Severity::Error && issue.pointer == "/token_filters/0/args/words_path"
+        }));
+
+        let path_issues = validate_config(
+            &json!({
+                "segmenter": {"dictionary": "/path/to/nonexistent"},
+                "token_filters": [
+                    {"kind": "stop_words", "args": {"paths": ["Cargo.toml", "no/such/file.txt"]}}
+                ]
+            }),
+            Path::new("."),
+        );
+        assert!(path_issues.iter().any(|issue| {
+            issue.severity == Severity::Error && issue.pointer == "/token_filters/0/args/paths/1"
+        }));
+        assert!(
+            !path_issues
+                .iter()
+                .any(|issue| issue.pointer == "/token_filters/0/args/paths/0")
+        );
+    }
+}
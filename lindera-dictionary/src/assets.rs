@@ -15,6 +15,8 @@ use tokio::time::{Duration, sleep};
 
 use crate::LinderaResult;
 use crate::builder::DictionaryBuilder;
+use crate::builder::cedict::is_cedict_file;
+use crate::compress::{COMPRESSION_CHUNK_SIZE_VAR, COMPRESSION_LEVEL_VAR, CompressionSettings};
 use crate::error::LinderaErrorKind;
 
 const MAX_ROUND: usize = 3;
@@ -214,13 +216,32 @@ async fn download_with_retry(
     Err("Failed to download a valid file from all sources".into())
 }
 
+/// The environment variable giving a local copy of the source of the dictionary built into
+/// `output_dir`: `LINDERA_UNIDIC_CWJ_SOURCE` for `lindera-unidic-cwj`.
+pub fn source_var(output_dir: &str) -> String {
+    let name = output_dir.strip_prefix("lindera-").unwrap_or(output_dir);
+    format!(
+        "LINDERA_{}_SOURCE",
+        name.to_ascii_uppercase().replace('-', "_")
+    )
+}
+
 /// Fetch the necessary assets and then build the dictionary using `builder`
+///
+/// The source is downloaded from `download_urls`, unless the variable named by [`source_var`]
+/// gives a local copy of it. Dictionaries whose source is not mirrored have no download URLs,
+/// and are built from a local copy only.
+///
+/// The dictionary is compressed with the settings of `LINDERA_COMPRESSION_LEVEL` and
+/// `LINDERA_COMPRESSION_CHUNK_SIZE`, see [`CompressionSettings::from_env`].
 pub async fn fetch(params: FetchParams, builder: DictionaryBuilder) -> LinderaResult<()> {
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=Cargo.toml");
     println!("cargo:rerun-if-env-changed=LINDERA_DICTIONARIES_PATH");
     println!("cargo:rerun-if-env-changed=LINDERA_CACHE");
     println!("cargo:rerun-if-env-changed=DOCS_RS");
+    println!("cargo:rerun-if-env-changed={COMPRESSION_LEVEL_VAR}");
+    println!("cargo:rerun-if-env-changed={COMPRESSION_CHUNK_SIZE_VAR}");
 
     // Directory path for build package
     // if the `LINDERA_DICTS` variable is defined, behaves like a cache, where data is invalidated only:
@@ -229,25 +250,24 @@ pub async fn fetch(params: FetchParams, builder: DictionaryBuilder) -> LinderaRe
     // otherwise, keeps behavior of always redownloading and rebuilding
     let (build_dir, is_cache) = if let Some(path) = std::env::var_os("LINDERA_DICTIONARIES_PATH")
         .or_else(|| {
-            std::env::var_os("LINDERA_CACHE").map(|p| {
+            std::env::var_os("LINDERA_CACHE").inspect(|_| {
                 println!(
                     "cargo:warning=LINDERA_CACHE is deprecated. Please use LINDERA_DICTIONARIES_PATH instead."
                 );
-                p
             })
         }) {
         let mut cache_dir = PathBuf::from(path);
-        if !cache_dir.is_absolute() {
-            if let Ok(current_dir) = std::env::current_dir() {
-                // If current_dir is a crate directory in a workspace, try to find the workspace root
-                let mut root_dir = current_dir.clone();
-                if let Some(parent) = current_dir.parent() {
-                    if parent.join("Cargo.toml").exists() {
-                        root_dir = parent.to_path_buf();
-                    }
-                }
-                cache_dir = root_dir.join(cache_dir);
+        if !cache_dir.is_absolute()
+            && let Ok(current_dir) = std::env::current_dir()
+        {
+            // If current_dir is a crate directory in a workspace, try to find the workspace root
+            let mut root_dir = current_dir.clone();
+            if let Some(parent) = current_dir.parent()
+                && parent.join("Cargo.toml").exists()
+            {
+                root_dir = parent.to_path_buf();
             }
+            cache_dir = root_dir.join(cache_dir);
         }
 
         (
@@ -274,6 +294,16 @@ pub async fn fetch(params: FetchParams, builder: DictionaryBuilder) -> LinderaRe
 
     let output_dir = build_dir.join(params.output_dir);
 
+    // A local copy of the source, for offline builds and for the dictionaries whose source is
+    // not mirrored: either the source directory, or an archive like the one downloaded. The
+    // source directory and the dictionary files in the CC-CEDICT format are built from directly
+    let source_var = source_var(params.output_dir);
+    println!("cargo:rerun-if-env-changed={source_var}");
+    let local_source = std::env::var_os(&source_var).map(PathBuf::from);
+    let local_source_dir = local_source
+        .clone()
+        .filter(|path| path.is_dir() || is_cedict_file(path));
+
     // Fast path where the data is already in cache
     if is_cache && output_dir.is_dir() {
         return Ok(());
@@ -349,12 +379,28 @@ pub async fn fetch(params: FetchParams, builder: DictionaryBuilder) -> LinderaRe
                 .with_error(anyhow::anyhow!(err))
                 .add_context("Failed to write to dummy matrix.def")
         })?;
+    } else if let Some(source_dir) = &local_source_dir {
+        info!("Building from the source {}", source_dir.display());
     } else {
         // Source file path for build package
-        let source_path_for_build = &build_dir.join(params.file_name);
+        let source_path_for_build = &local_source
+            .clone()
+            .unwrap_or_else(|| build_dir.join(params.file_name));
 
         // Check if source file already exists and is valid
-        let need_download = if source_path_for_build.exists() {
+        let need_download = if local_source.is_some() {
+            info!(
+                "Building from the source archive {}",
+                source_path_for_build.display()
+            );
+            if !source_path_for_build.is_file() {
+                return Err(LinderaErrorKind::Io.with_error(anyhow::anyhow!(
+                    "{source_var} is not a file or directory: {}",
+                    source_path_for_build.display()
+                )));
+            }
+            false
+        } else if source_path_for_build.exists() {
             debug!(
                 "Found existing source file: {}",
                 source_path_for_build.display()
@@ -409,6 +455,14 @@ pub async fn fetch(params: FetchParams, builder: DictionaryBuilder) -> LinderaRe
         };
 
         if need_download {
+            if params.download_urls.is_empty() {
+                return Err(LinderaErrorKind::Io.with_error(anyhow::anyhow!(
+                    "the source of {} is not available for download; set {source_var} to the source directory or archive ({})",
+                    params.output_dir,
+                    params.file_name
+                )));
+            }
+
             // Download source file to build directory
             let tmp_download_path =
                 Path::new(&build_dir).join(params.file_name.to_owned() + ".download");
@@ -524,7 +578,11 @@ pub async fn fetch(params: FetchParams, builder: DictionaryBuilder) -> LinderaRe
     let _ = fs::remove_dir_all(&tmp_output_path);
 
     builder
-        .build_dictionary(&input_dir, &tmp_output_path)
+        .with_compression(CompressionSettings::from_env()?)
+        .build_dictionary(
+            local_source_dir.as_ref().unwrap_or(&input_dir),
+            &tmp_output_path,
+        )
         .map_err(|err| {
             LinderaErrorKind::Build
                 .with_error(anyhow::anyhow!("{err}"))
@@ -537,7 +595,9 @@ pub async fn fetch(params: FetchParams, builder: DictionaryBuilder) -> LinderaRe
     // Rename tmp_output_path to output_dir
     rename_directory(&tmp_output_path, &output_dir)?;
 
-    let _ = fs::remove_dir_all(input_dir);
+    if local_source_dir.is_none() {
+        let _ = fs::remove_dir_all(input_dir);
+    }
 
     Ok(())
 }
@@ -1,52 +1,169 @@
+pub mod archive;
+pub mod cedict;
 pub mod character_definition;
 pub mod connection_cost_matrix;
+pub mod export;
 pub mod metadata;
+pub mod mozc;
 pub mod prefix_dictionary;
+pub mod progress;
+pub mod skk;
 pub mod unknown_dictionary;
 pub mod user_dictionary;
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use csv::StringRecord;
+use unicode_normalization::UnicodeNormalization;
 
+use self::archive::{extract_archive, find_source_dir, is_archive};
+use self::cedict::{convert_cedict, find_cedict_file, is_cedict_file};
 use self::character_definition::CharacterDefinitionBuilderOptions;
 use self::connection_cost_matrix::ConnectionCostMatrixBuilderOptions;
 use self::metadata::MetadataBuilder;
-use self::prefix_dictionary::PrefixDictionaryBuilderOptions;
+use self::prefix_dictionary::{
+    DuplicateReport, PrefixDictionaryBuilder, PrefixDictionaryBuilderOptions,
+};
+use self::progress::{BuildJob, BuildPhase, MemoryReport};
 use self::unknown_dictionary::UnknownDictionaryBuilderOptions;
-use self::user_dictionary::{UserDictionaryBuilderOptions, build_user_dictionary};
+use self::user_dictionary::{
+    Diagnostic, UserDictionaryBuilder, UserDictionaryBuilderOptions, build_user_dictionary,
+};
 use crate::LinderaResult;
-use crate::dictionary::UserDictionary;
+use crate::compress::CompressionSettings;
 use crate::dictionary::character_definition::CharacterDefinition;
-use crate::dictionary::metadata::Metadata;
+use crate::dictionary::connection_cost_matrix::ConnectionCostMatrix;
+use crate::dictionary::metadata::{Metadata, SurfaceNormalization};
+use crate::dictionary::okurigana::OkuriganaVariants;
+use crate::dictionary::{Dictionary, UserDictionary};
 use crate::error::LinderaErrorKind;
+use crate::format::FORMAT_VERSION;
+use crate::loader::metadata::MetadataLoader;
+use crate::util::{build_timestamp, join, read_file};
+
+/// What the builder did with the source data of a system dictionary.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildReport {
+    pub duplicates: DuplicateReport,
+    /// Peak memory use of the build.
+    pub memory: MemoryReport,
+}
 
 #[derive(Clone)]
 pub struct DictionaryBuilder {
     metadata: Metadata,
+    /// Build time to record instead of the current time.
+    built_at: Option<u64>,
+    compression: CompressionSettings,
 }
 
 impl DictionaryBuilder {
     pub fn new(metadata: Metadata) -> Self {
-        Self { metadata }
+        Self {
+            metadata,
+            built_at: None,
+            compression: CompressionSettings::default(),
+        }
+    }
+
+    /// Sets the compression level and chunk size of the dictionary components.
+    pub fn with_compression(mut self, compression: CompressionSettings) -> Self {
+        self.compression = compression;
+        self
     }
 
     pub fn build_dictionary(&self, input_dir: &Path, output_dir: &Path) -> LinderaResult<()> {
+        self.build_dictionary_with_report(input_dir, output_dir)
+            .map(|_| ())
+    }
+
+    /// Builds the dictionary and reports what the builder did with the source data.
+    pub fn build_dictionary_with_report(
+        &self,
+        input_dir: &Path,
+        output_dir: &Path,
+    ) -> LinderaResult<BuildReport> {
+        self.build_dictionary_with_job(input_dir, output_dir, &BuildJob::new())
+    }
+
+    /// Builds the dictionary, reporting progress to the job and stopping if it is cancelled.
+    ///
+    /// `input_dir` may also be a source archive (`.tar.gz`, `.tgz`, `.tar` or `.zip`) as
+    /// distributed upstream. It is extracted into a temporary directory, and the directory
+    /// containing `matrix.def` is used as the source directory.
+    ///
+    /// `input_dir` may also be a dictionary file in the CC-CEDICT format, like CC-CEDICT and
+    /// CC-Canto, or an archive of one. It is converted into a source directory first, see
+    /// [`cedict`].
+    pub fn build_dictionary_with_job(
+        &self,
+        input_dir: &Path,
+        output_dir: &Path,
+        job: &BuildJob,
+    ) -> LinderaResult<BuildReport> {
+        if is_archive(input_dir) {
+            let extract_dir = TemporaryDirectory::new()?;
+            extract_archive(input_dir, extract_dir.path())?;
+            let source_dir = match find_source_dir(extract_dir.path()) {
+                Ok(source_dir) => source_dir,
+                // An archive of a dictionary in the CC-CEDICT format, like the one of CC-CEDICT
+                Err(err) => find_cedict_file(extract_dir.path())?.ok_or(err)?,
+            };
+            return self.build_dictionary_with_job(&source_dir, output_dir, job);
+        }
+
+        if is_cedict_file(input_dir) {
+            let source_dir = TemporaryDirectory::new()?;
+            convert_cedict(input_dir, source_dir.path(), &self.metadata)?;
+            return self.build_dictionary_with_job(source_dir.path(), output_dir, job);
+        }
+
+        job.begin(BuildPhase::SYSTEM_DICTIONARY);
         fs::create_dir_all(output_dir)
             .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
 
+        job.check(BuildPhase::Metadata)?;
         self.build_metadata(output_dir)?;
+        job.update(BuildPhase::Metadata, 1, 1);
+
+        job.check(BuildPhase::CharacterDefinition)?;
         let chardef = self.build_character_definition(input_dir, output_dir)?;
+        job.update(BuildPhase::CharacterDefinition, 1, 1);
+
+        job.check(BuildPhase::UnknownDictionary)?;
         self.build_unknown_dictionary(input_dir, output_dir, &chardef)?;
-        self.build_prefix_dictionary(input_dir, output_dir)?;
-        self.build_connection_cost_matrix(input_dir, output_dir)?;
+        job.update(BuildPhase::UnknownDictionary, 1, 1);
 
-        Ok(())
+        // The lexicon and the connection cost matrix are the largest parts and are built in parallel.
+        let (duplicates, matrix) = join(
+            || {
+                self.prefix_dictionary_builder()
+                    .build_with_job(input_dir, output_dir, job)
+            },
+            || {
+                job.check(BuildPhase::ConnectionCostMatrix)?;
+                self.build_connection_cost_matrix(input_dir, output_dir)?;
+                job.update(BuildPhase::ConnectionCostMatrix, 1, 1);
+                Ok(())
+            },
+        );
+        matrix?;
+
+        Ok(BuildReport {
+            duplicates: duplicates?,
+            memory: job.memory_report(),
+        })
     }
 
     pub fn build_metadata(&self, output_dir: &Path) -> LinderaResult<()> {
-        MetadataBuilder::new().build(&self.metadata, output_dir)
+        // The source metadata may predate format versioning; record the format actually written.
+        let mut metadata = self.metadata.clone();
+        metadata.format_version = FORMAT_VERSION;
+        metadata.built_at = Some(self.built_at.unwrap_or_else(build_timestamp));
+        MetadataBuilder::new().build(&metadata, output_dir)
     }
 
     pub fn build_character_definition(
@@ -57,6 +174,7 @@ impl DictionaryBuilder {
         CharacterDefinitionBuilderOptions::default()
             .encoding(self.metadata.encoding.clone())
             .compress_algorithm(self.metadata.compress_algorithm)
+            .compression(self.compression)
             .builder()
             .unwrap()
             .build(input_dir, output_dir)
@@ -71,6 +189,7 @@ impl DictionaryBuilder {
         UnknownDictionaryBuilderOptions::default()
             .encoding(self.metadata.encoding.clone())
             .compress_algorithm(self.metadata.compress_algorithm)
+            .compression(self.compression)
             .builder()
             .unwrap()
             .build(input_dir, chardef, output_dir)
@@ -81,16 +200,35 @@ impl DictionaryBuilder {
         input_dir: &Path,
         output_dir: &Path,
     ) -> LinderaResult<()> {
+        self.prefix_dictionary_builder()
+            .build(input_dir, output_dir)
+    }
+
+    fn prefix_dictionary_builder(&self) -> PrefixDictionaryBuilder {
         PrefixDictionaryBuilderOptions::default()
             .flexible_csv(self.metadata.flexible_csv)
             .encoding(self.metadata.encoding.clone())
             .compress_algorithm(self.metadata.compress_algorithm)
+            .compression(self.compression)
             .skip_invalid_cost_or_id(self.metadata.skip_invalid_cost_or_id)
             .normalize_details(self.metadata.normalize_details)
+            .surface_normalization(self.metadata.surface_normalization)
+            .duplicate_policy(self.metadata.duplicate_policy)
+            .reading_index_field(self.metadata.reading_index_field.clone())
+            .okurigana_variants(self.metadata.okurigana_variants)
             .schema(self.metadata.dictionary_schema.clone())
             .builder()
             .unwrap()
-            .build(input_dir, output_dir)
+    }
+
+    /// Builds the okurigana variant table of a dictionary already built, the one written to
+    /// `dict.okurigana` when the dictionary is built with `okurigana_variants` enabled.
+    pub fn build_okurigana_variants(
+        &self,
+        dictionary: &Dictionary,
+    ) -> LinderaResult<OkuriganaVariants> {
+        self.prefix_dictionary_builder()
+            .build_okurigana_variants_of(dictionary)
     }
 
     pub fn build_connection_cost_matrix(
@@ -101,6 +239,9 @@ impl DictionaryBuilder {
         ConnectionCostMatrixBuilderOptions::default()
             .encoding(self.metadata.encoding.clone())
             .compress_algorithm(self.metadata.compress_algorithm)
+            .compression(self.compression)
+            .shard_size(self.metadata.matrix_shard_size)
+            .cost_encoding(self.metadata.matrix_encoding)
             .builder()
             .unwrap()
             .build(input_dir, output_dir)
@@ -111,11 +252,157 @@ impl DictionaryBuilder {
         input_file: &Path,
         output_file: &Path,
     ) -> LinderaResult<()> {
-        let user_dict = self.build_user_dict(input_file)?;
-        build_user_dictionary(user_dict, output_file)
+        self.build_user_dictionary_with_job(input_file, output_file, &BuildJob::new())
+    }
+
+    /// Builds the user dictionary, reporting progress to the job and stopping if it is cancelled.
+    ///
+    /// The output file is written only after everything else is done,
+    /// so a cancelled build leaves no output behind.
+    pub fn build_user_dictionary_with_job(
+        &self,
+        input_file: &Path,
+        output_file: &Path,
+        job: &BuildJob,
+    ) -> LinderaResult<()> {
+        job.begin(BuildPhase::USER_DICTIONARY);
+        let user_dict = self
+            .user_dictionary_builder()
+            .build_with_job(input_file, job)?;
+
+        job.check(BuildPhase::WriteUserDictionary)?;
+        build_user_dictionary(user_dict, output_file)?;
+        job.update(BuildPhase::WriteUserDictionary, 1, 1);
+        Ok(())
+    }
+
+    /// Builds a user dictionary from a build script, to be embedded with
+    /// [`embed_user_dictionary!`](crate::embed_user_dictionary).
+    ///
+    /// `input_file` is the path of the CSV file relative to the package root; pass the same
+    /// string to the macro. The binary dictionary is written to
+    /// `$OUT_DIR/lindera-user-dictionary/<input_file>.bin`, and Cargo is told to run the build
+    /// script again when the CSV file changes.
+    ///
+    /// ```no_run
+    /// // build.rs
+    /// use lindera_dictionary::builder::DictionaryBuilder;
+    /// use lindera_dictionary::dictionary::metadata::Metadata;
+    ///
+    /// let metadata: Metadata =
+    ///     serde_json::from_str(&std::fs::read_to_string("metadata.json").unwrap()).unwrap();
+    /// DictionaryBuilder::new(metadata)
+    ///     .build_embedded_user_dictionary("resources/userdict.csv")
+    ///     .unwrap();
+    /// ```
+    pub fn build_embedded_user_dictionary(&self, input_file: &str) -> LinderaResult<PathBuf> {
+        let out_dir = std::env::var_os("OUT_DIR").ok_or_else(|| {
+            LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                "OUT_DIR is not set; build_embedded_user_dictionary must be called from a build script"
+            ))
+        })?;
+        println!("cargo:rerun-if-changed={input_file}");
+        self.build_embedded_user_dictionary_into(input_file, Path::new(&out_dir))
+    }
+
+    fn build_embedded_user_dictionary_into(
+        &self,
+        input_file: &str,
+        out_dir: &Path,
+    ) -> LinderaResult<PathBuf> {
+        // Must match the path `embed_user_dictionary!` builds with `concat!`
+        let output_file = PathBuf::from(format!(
+            "{}/lindera-user-dictionary/{input_file}.bin",
+            out_dir.display()
+        ));
+        if let Some(parent) = output_file.parent() {
+            fs::create_dir_all(parent).map_err(|err| {
+                LinderaErrorKind::Io
+                    .with_error(anyhow::anyhow!(err))
+                    .add_context(format!("Failed to create directory: {parent:?}"))
+            })?;
+        }
+        self.build_user_dictionary(Path::new(input_file), &output_file)?;
+        Ok(output_file)
+    }
+
+    /// Builds the dictionary again into a temporary directory and checks that the output
+    /// is byte-identical to the dictionary previously built into `output_dir`.
+    ///
+    /// The rebuild records the same build time as the original build.
+    pub fn verify_reproducible(&self, input_dir: &Path, output_dir: &Path) -> LinderaResult<()> {
+        let mut builder = self.clone();
+        builder.built_at = MetadataLoader::load(output_dir)?.built_at;
+
+        let rebuild_dir = TemporaryDirectory::new()?;
+        builder.build_dictionary(input_dir, rebuild_dir.path())?;
+
+        let mut differences = Vec::new();
+        for entry in fs::read_dir(output_dir)
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?
+        {
+            let entry =
+                entry.map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+            let file_name = entry.file_name();
+            let rebuilt_file = rebuild_dir.path().join(&file_name);
+            if rebuilt_file.exists() && !same_contents(&entry.path(), &rebuilt_file)? {
+                differences.push(file_name.to_string_lossy().into_owned());
+            }
+        }
+        differences.sort();
+
+        if differences.is_empty() {
+            Ok(())
+        } else {
+            Err(LinderaErrorKind::Build.with_error(anyhow::anyhow!(
+                "dictionary build is not reproducible: {} differ between builds",
+                differences.join(", ")
+            )))
+        }
+    }
+
+    /// Builds the user dictionary again and checks that the output is byte-identical
+    /// to the user dictionary previously built into `output_file`.
+    pub fn verify_reproducible_user_dictionary(
+        &self,
+        input_file: &Path,
+        output_file: &Path,
+    ) -> LinderaResult<()> {
+        let rebuild_dir = TemporaryDirectory::new()?;
+        let rebuilt_file = rebuild_dir.path().join("userdic.bin");
+        self.build_user_dictionary(input_file, &rebuilt_file)?;
+
+        if same_contents(output_file, &rebuilt_file)? {
+            Ok(())
+        } else {
+            Err(LinderaErrorKind::Build.with_error(anyhow::anyhow!(
+                "user dictionary build is not reproducible: {} differs between builds",
+                output_file.display()
+            )))
+        }
     }
 
     pub fn build_user_dict(&self, input_file: &Path) -> LinderaResult<UserDictionary> {
+        self.user_dictionary_builder().build(input_file)
+    }
+
+    /// Builds a user dictionary from the contents of a CSV file held in memory.
+    pub fn build_user_dict_from_bytes(&self, data: &[u8]) -> LinderaResult<UserDictionary> {
+        self.user_dictionary_builder().build_from_bytes(data)
+    }
+
+    /// Checks a user dictionary CSV file against the schema of this dictionary and
+    /// returns every problem found, instead of failing on the first bad row.
+    /// See [`UserDictionaryBuilder::lint`].
+    pub fn lint_user_dict(
+        &self,
+        input_file: &Path,
+        matrix: Option<&ConnectionCostMatrix>,
+    ) -> LinderaResult<Vec<Diagnostic>> {
+        self.user_dictionary_builder().lint(input_file, matrix)
+    }
+
+    fn user_dictionary_builder(&self) -> UserDictionaryBuilder {
         let userdic_schema = self.metadata.user_dictionary_schema.clone();
         let dict_schema = self.metadata.dictionary_schema.clone();
         let default_field_value = self.metadata.default_field_value.clone();
@@ -127,6 +414,7 @@ impl DictionaryBuilder {
             .default_left_context_id(self.metadata.default_left_context_id)
             .default_right_context_id(self.metadata.default_right_context_id)
             .flexible_csv(self.metadata.flexible_csv)
+            .surface_normalization(self.metadata.surface_normalization)
             .user_dictionary_handler(Some(Box::new(move |row: &StringRecord| {
                 // Map user dictionary fields to dictionary schema fields
                 let mut result = Vec::new();
@@ -150,6 +438,544 @@ impl DictionaryBuilder {
             })))
             .builder()
             .unwrap()
-            .build(input_file)
+    }
+}
+
+fn same_contents(a: &Path, b: &Path) -> LinderaResult<bool> {
+    Ok(read_file(a)? == read_file(b)?)
+}
+
+/// Directory under the system temporary directory that is removed when dropped.
+struct TemporaryDirectory(PathBuf);
+
+impl TemporaryDirectory {
+    fn new() -> LinderaResult<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .subsec_nanos();
+        let count = COUNTER.fetch_add(1, Ordering::SeqCst);
+        let path = std::env::temp_dir().join(format!(
+            "lindera-build-{}-{nanos}-{count}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&path).map_err(|err| {
+            LinderaErrorKind::Io
+                .with_error(anyhow::anyhow!(err))
+                .add_context(format!("Failed to create temporary directory: {path:?}"))
+        })?;
+        Ok(Self(path))
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TemporaryDirectory {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Applies the surface normalization to the rows read from the source CSV files.
+/// The surface is expected in the first field of each row.
+pub(crate) fn normalize_surfaces(
+    rows: Vec<StringRecord>,
+    normalization: SurfaceNormalization,
+) -> Vec<StringRecord> {
+    if normalization == SurfaceNormalization::None {
+        return rows;
+    }
+
+    let mut normalized_rows = Vec::with_capacity(rows.len());
+    for row in rows {
+        let Some(surface) = row.get(0) else {
+            normalized_rows.push(row);
+            continue;
+        };
+        let normalized: String = surface.nfkc().collect();
+        if normalized == surface {
+            normalized_rows.push(row);
+            continue;
+        }
+
+        let mut normalized_row = StringRecord::with_capacity(row.as_slice().len(), row.len());
+        normalized_row.push_field(&normalized);
+        for field in row.iter().skip(1) {
+            normalized_row.push_field(field);
+        }
+        if normalization == SurfaceNormalization::NfkcWithOriginal {
+            normalized_rows.push(row);
+        }
+        normalized_rows.push(normalized_row);
+    }
+
+    normalized_rows
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::loader::user_dictionary::EmbeddedUserDictionaryLoader;
+
+    fn rows() -> Vec<StringRecord> {
+        vec![
+            StringRecord::from(vec!["ｶﾀｶﾅ", "1", "1", "100", "名詞"]),
+            StringRecord::from(vec!["東京", "2", "2", "200", "名詞"]),
+        ]
+    }
+
+    pub(crate) fn write_sources(dir: &Path) {
+        fs::write(
+            dir.join("char.def"),
+            "DEFAULT 0 1 0\nKANJI 0 0 2\n0x4E00..0x9FAF KANJI\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("unk.def"),
+            "DEFAULT,0,0,0,名詞,一般,*,*,*,*,*,*,*\nKANJI,0,0,0,名詞,一般,*,*,*,*,*,*,*\n",
+        )
+        .unwrap();
+        fs::write(dir.join("matrix.def"), "1 1\n0 0 0\n").unwrap();
+        fs::write(
+            dir.join("lex.csv"),
+            "東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー\n\
+             京都,0,0,100,名詞,固有名詞,地域,一般,*,*,京都,キョウト,キョート\n\
+             東,0,0,200,名詞,一般,*,*,*,*,東,ヒガシ,ヒガシ\n",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_build_metadata_provenance() {
+        let output_dir = tempfile::tempdir().unwrap();
+        let metadata = Metadata {
+            license: Some("Apache-2.0".to_string()),
+            source_version: Some("2.1.1-20180720".to_string()),
+            ..Default::default()
+        };
+
+        DictionaryBuilder::new(metadata)
+            .build_metadata(output_dir.path())
+            .unwrap();
+
+        let built = MetadataLoader::load(output_dir.path()).unwrap();
+        assert_eq!(built.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(built.source_version.as_deref(), Some("2.1.1-20180720"));
+        assert!(built.built_at.is_some());
+    }
+
+    #[test]
+    fn test_verify_reproducible() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let output_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+
+        let builder = DictionaryBuilder::new(Metadata::default());
+        builder
+            .build_dictionary(input_dir.path(), output_dir.path())
+            .unwrap();
+        builder
+            .verify_reproducible(input_dir.path(), output_dir.path())
+            .unwrap();
+
+        // A modified artifact is reported
+        fs::write(output_dir.path().join("dict.vals"), b"modified").unwrap();
+        let err = builder
+            .verify_reproducible(input_dir.path(), output_dir.path())
+            .unwrap_err();
+        assert!(err.to_string().contains("dict.vals"));
+    }
+
+    #[test]
+    #[cfg(feature = "compress")]
+    fn test_build_dictionary_with_compression() {
+        use crate::dictionary::Dictionary;
+
+        let input_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+
+        let default_dir = tempfile::tempdir().unwrap();
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), default_dir.path())
+            .unwrap();
+
+        let stored_dir = tempfile::tempdir().unwrap();
+        DictionaryBuilder::new(Metadata::default())
+            .with_compression(CompressionSettings {
+                level: 0,
+                chunk_size: 0,
+            })
+            .build_dictionary(input_dir.path(), stored_dir.path())
+            .unwrap();
+
+        let default_words = fs::read(default_dir.path().join("dict.words")).unwrap();
+        let stored_words = fs::read(stored_dir.path().join("dict.words")).unwrap();
+        assert!(stored_words.len() > default_words.len());
+
+        let default_dictionary = Dictionary::load_from_path(default_dir.path()).unwrap();
+        let stored_dictionary = Dictionary::load_from_path(stored_dir.path()).unwrap();
+        assert_eq!(
+            &default_dictionary.prefix_dictionary.words_data[..],
+            &stored_dictionary.prefix_dictionary.words_data[..]
+        );
+    }
+
+    #[test]
+    fn test_build_okurigana_variants_of_built_dictionary() {
+        use crate::dictionary::Dictionary;
+        use crate::loader::okurigana::OkuriganaVariantsLoader;
+
+        let input_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+        fs::write(
+            input_dir.path().join("lex.csv"),
+            "受け付け,0,0,100,名詞,サ変接続,*,*,*,*,受け付け,ウケツケ,ウケツケ\n\
+             受け付ける,0,0,100,動詞,自立,*,*,一段,基本形,受け付ける,ウケツケル,ウケツケル\n\
+             受付,0,0,100,名詞,一般,*,*,*,*,受付,ウケツケ,ウケツケ\n",
+        )
+        .unwrap();
+        let metadata = Metadata {
+            okurigana_variants: true,
+            ..Default::default()
+        };
+        let output_dir = tempfile::tempdir().unwrap();
+        DictionaryBuilder::new(metadata)
+            .build_dictionary(input_dir.path(), output_dir.path())
+            .unwrap();
+
+        let dictionary = Dictionary::load_from_path(output_dir.path()).unwrap();
+        let built = OkuriganaVariantsLoader::load(output_dir.path()).unwrap();
+        let rebuilt = DictionaryBuilder::new(Metadata::default())
+            .build_okurigana_variants(&dictionary)
+            .unwrap();
+
+        let surfaces: Vec<String> = rebuilt
+            .dict
+            .entries()
+            .into_iter()
+            .map(|(surface, _)| surface)
+            .collect();
+        assert_eq!(vec!["受付け", "受付ける"], surfaces);
+        assert_eq!(built.dict.entries(), rebuilt.dict.entries());
+    }
+
+    #[test]
+    fn test_build_dictionary_with_matrix_shards() {
+        use std::io::{Cursor, Write};
+
+        use crate::dictionary::Dictionary;
+
+        let input_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+        let mut matrix_def = "3 5\n".to_string();
+        for forward_id in 0..3 {
+            for backward_id in 0..5 {
+                matrix_def.push_str(&format!(
+                    "{forward_id} {backward_id} {}\n",
+                    forward_id * 10 + backward_id
+                ));
+            }
+        }
+        fs::write(input_dir.path().join("matrix.def"), matrix_def).unwrap();
+
+        let whole_dir = tempfile::tempdir().unwrap();
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), whole_dir.path())
+            .unwrap();
+        let sharded_dir = tempfile::tempdir().unwrap();
+        let metadata = Metadata {
+            matrix_shard_size: Some(2),
+            ..Metadata::default()
+        };
+        DictionaryBuilder::new(metadata)
+            .build_dictionary(input_dir.path(), sharded_dir.path())
+            .unwrap();
+        for index in 0..3 {
+            assert!(
+                sharded_dir
+                    .path()
+                    .join(format!("matrix.{index}.mtx"))
+                    .is_file()
+            );
+        }
+        assert!(!sharded_dir.path().join("matrix.3.mtx").exists());
+
+        let whole = Dictionary::load_from_path(whole_dir.path()).unwrap();
+        let sharded = Dictionary::load_from_path(sharded_dir.path()).unwrap();
+        let matrix = &sharded.connection_cost_matrix;
+        assert!(matrix.is_sharded());
+        assert_eq!((3, 0), matrix.loaded_shards());
+        assert_eq!(13, matrix.cost(1, 3));
+        assert_eq!((3, 1), matrix.loaded_shards());
+        for forward_id in 0..3 {
+            for backward_id in 0..5 {
+                assert_eq!(
+                    whole.connection_cost_matrix.cost(forward_id, backward_id),
+                    matrix.cost(forward_id, backward_id)
+                );
+            }
+        }
+        assert_eq!((3, 3), matrix.loaded_shards());
+
+        // The shards of an archive are decoded when they are used
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for entry in fs::read_dir(sharded_dir.path()).unwrap() {
+            let path = entry.unwrap().path();
+            zip.start_file(
+                path.file_name().unwrap().to_string_lossy(),
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+            zip.write_all(&fs::read(&path).unwrap()).unwrap();
+        }
+        let zip = zip.finish().unwrap().into_inner();
+        let from_archive = Dictionary::from_archive_bytes(&zip).unwrap();
+        assert_eq!((3, 0), from_archive.connection_cost_matrix.loaded_shards());
+        assert_eq!(24, from_archive.connection_cost_matrix.cost(2, 4));
+
+        // An archive with an invalid shard is reported when the dictionary is loaded
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for entry in fs::read_dir(sharded_dir.path()).unwrap() {
+            let path = entry.unwrap().path();
+            let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+            zip.start_file(file_name.as_str(), zip::write::SimpleFileOptions::default())
+                .unwrap();
+            let mut data = fs::read(&path).unwrap();
+            if file_name == "matrix.2.mtx" {
+                // A format version from the future
+                data[8] = 0xff;
+            }
+            zip.write_all(&data).unwrap();
+        }
+        let zip = zip.finish().unwrap().into_inner();
+        let err = Dictionary::from_archive_bytes(&zip).err().unwrap();
+        assert!(format!("{err:?}").contains("matrix.2.mtx"));
+
+        // The shards are read when the dictionary is loaded, so removing one afterwards changes
+        // nothing
+        let sharded = Dictionary::load_from_path(sharded_dir.path()).unwrap();
+        fs::remove_file(sharded_dir.path().join("matrix.1.mtx")).unwrap();
+        let matrix = &sharded.connection_cost_matrix;
+        matrix.load_shards(0..5).unwrap();
+        assert_eq!((3, 3), matrix.loaded_shards());
+        assert_eq!(whole.connection_cost_matrix.cost(0, 2), matrix.cost(0, 2));
+
+        // A missing shard is reported when the dictionary is loaded
+        assert!(Dictionary::load_from_path(sharded_dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_build_dictionary_with_quantized_matrix() {
+        use crate::dictionary::Dictionary;
+        use crate::dictionary::metadata::MatrixEncoding;
+
+        let input_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+        let cost = |forward_id: i32, backward_id: i32| forward_id * 997 - backward_id * 31 - 3000;
+        let mut matrix_def = "4 5\n".to_string();
+        for forward_id in 0..4 {
+            for backward_id in 0..5 {
+                matrix_def.push_str(&format!(
+                    "{forward_id} {backward_id} {}\n",
+                    cost(forward_id, backward_id)
+                ));
+            }
+        }
+        fs::write(input_dir.path().join("matrix.def"), matrix_def).unwrap();
+
+        for matrix_shard_size in [None, Some(2)] {
+            let dict_dir = tempfile::tempdir().unwrap();
+            let metadata = Metadata {
+                matrix_encoding: MatrixEncoding::Quantized,
+                matrix_shard_size,
+                ..Metadata::default()
+            };
+            DictionaryBuilder::new(metadata)
+                .build_dictionary(input_dir.path(), dict_dir.path())
+                .unwrap();
+            let dictionary = Dictionary::load_from_path(dict_dir.path()).unwrap();
+            let matrix = &dictionary.connection_cost_matrix;
+            assert_eq!(MatrixEncoding::Quantized, matrix.encoding());
+            assert_eq!(matrix_shard_size.is_some(), matrix.is_sharded());
+            // The costs of a left id range over 2991, in steps of 12
+            for forward_id in 0..4 {
+                for backward_id in 0..5 {
+                    let expected = cost(forward_id as i32, backward_id as i32);
+                    let quantized = matrix.cost(forward_id, backward_id);
+                    assert!((expected - quantized).abs() <= 6, "{expected} {quantized}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_build_dictionary_from_archive() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let package_dir = source_dir.path().join("dict-1.0");
+        fs::create_dir(&package_dir).unwrap();
+        write_sources(&package_dir);
+
+        let archive_file = source_dir.path().join("dict-1.0.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            fs::File::create(&archive_file).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut tar = tar::Builder::new(encoder);
+        tar.append_dir_all("dict-1.0", &package_dir).unwrap();
+        tar.into_inner().unwrap().finish().unwrap();
+
+        let builder = DictionaryBuilder::new(Metadata::default());
+        let from_dir = tempfile::tempdir().unwrap();
+        builder
+            .build_dictionary(&package_dir, from_dir.path())
+            .unwrap();
+        let from_archive = tempfile::tempdir().unwrap();
+        builder
+            .build_dictionary(&archive_file, from_archive.path())
+            .unwrap();
+
+        for file_name in ["dict.da", "dict.vals", "dict.words", "matrix.mtx"] {
+            assert_eq!(
+                fs::read(from_dir.path().join(file_name)).unwrap(),
+                fs::read(from_archive.path().join(file_name)).unwrap(),
+                "{file_name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_build_dictionary_from_cedict() {
+        use std::io::Write;
+
+        let source_dir = tempfile::tempdir().unwrap();
+        let cedict = "# CC-CEDICT\n測試 测试 [ce4 shi4] /to test/\n";
+        let cedict_file = source_dir.path().join("cedict_ts.u8");
+        fs::write(&cedict_file, cedict).unwrap();
+
+        let archive_file = source_dir.path().join("cedict.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&archive_file).unwrap());
+        writer
+            .start_file("cedict_ts.u8", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(cedict.as_bytes()).unwrap();
+        writer.finish().unwrap();
+
+        let builder = DictionaryBuilder::new(Metadata::default());
+        let from_file = tempfile::tempdir().unwrap();
+        builder
+            .build_dictionary(&cedict_file, from_file.path())
+            .unwrap();
+        let from_archive = tempfile::tempdir().unwrap();
+        builder
+            .build_dictionary(&archive_file, from_archive.path())
+            .unwrap();
+
+        for file_name in [
+            "dict.da",
+            "dict.vals",
+            "dict.words",
+            "matrix.mtx",
+            "unk.bin",
+        ] {
+            assert!(from_file.path().join(file_name).is_file(), "{file_name}");
+            assert_eq!(
+                fs::read(from_file.path().join(file_name)).unwrap(),
+                fs::read(from_archive.path().join(file_name)).unwrap(),
+                "{file_name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_build_dictionary_with_job() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let output_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+
+        let percents = Arc::new(Mutex::new(Vec::new()));
+        let sink = percents.clone();
+        let job = BuildJob::new().on_progress(move |progress| {
+            sink.lock().unwrap().push(progress.percent);
+        });
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary_with_job(input_dir.path(), output_dir.path(), &job)
+            .unwrap();
+
+        let percents = percents.lock().unwrap();
+        assert!(!percents.is_empty());
+        assert!((percents.iter().cloned().fold(0.0, f64::max) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_build_user_dictionary_cancelled() {
+        let dir = tempfile::tempdir().unwrap();
+        let input_file = dir.path().join("userdic.csv");
+        let output_file = dir.path().join("userdic.bin");
+        fs::write(
+            &input_file,
+            "東京スカイツリー,カスタム名詞,トウキョウスカイツリー\n",
+        )
+        .unwrap();
+
+        let job = BuildJob::new();
+        job.cancel();
+        let err = DictionaryBuilder::new(Metadata::default())
+            .build_user_dictionary_with_job(&input_file, &output_file, &job)
+            .unwrap_err();
+        assert_eq!(err.kind(), LinderaErrorKind::Cancelled);
+        assert!(!output_file.exists());
+    }
+
+    #[test]
+    fn test_build_embedded_user_dictionary() {
+        let dir = tempfile::tempdir().unwrap();
+        let out_dir = tempfile::tempdir().unwrap();
+        let input_file = dir.path().join("userdic.csv");
+        fs::write(
+            &input_file,
+            "東京スカイツリー,カスタム名詞,トウキョウスカイツリー\n",
+        )
+        .unwrap();
+
+        let output_file = DictionaryBuilder::new(Metadata::default())
+            .build_embedded_user_dictionary_into(input_file.to_str().unwrap(), out_dir.path())
+            .unwrap();
+        assert!(output_file.starts_with(out_dir.path().join("lindera-user-dictionary")));
+
+        let data: &'static [u8] = Box::leak(fs::read(&output_file).unwrap().into_boxed_slice());
+        let user_dictionary = EmbeddedUserDictionaryLoader::new(data).load().unwrap();
+        assert_eq!(user_dictionary.dict.words_idx_data.len(), 4);
+    }
+
+    #[test]
+    fn test_normalize_surfaces_none() {
+        assert_eq!(
+            normalize_surfaces(rows(), SurfaceNormalization::None),
+            rows()
+        );
+    }
+
+    #[test]
+    fn test_normalize_surfaces_nfkc() {
+        let normalized = normalize_surfaces(rows(), SurfaceNormalization::Nfkc);
+        assert_eq!(normalized.len(), 2);
+        assert_eq!(
+            normalized[0],
+            StringRecord::from(vec!["カタカナ", "1", "1", "100", "名詞"])
+        );
+        assert_eq!(normalized[1], rows()[1]);
+    }
+
+    #[test]
+    fn test_normalize_surfaces_nfkc_with_original() {
+        let normalized = normalize_surfaces(rows(), SurfaceNormalization::NfkcWithOriginal);
+        let surfaces: Vec<&str> = normalized.iter().map(|row| &row[0]).collect();
+        assert_eq!(surfaces, vec!["ｶﾀｶﾅ", "カタカナ", "東京"]);
     }
 }
@@ -0,0 +1,283 @@
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{BufReader, Cursor, Read};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use log::debug;
+
+use crate::LinderaResult;
+use crate::error::LinderaErrorKind;
+
+/// File that marks the directory holding the dictionary sources in an archive.
+const SOURCE_MARKER: &str = "matrix.def";
+
+/// Format of a dictionary source archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    TarGz,
+    Tar,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Detects the archive format from the file name of `path`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+impl ArchiveFormat {
+    /// Detects the archive format from the first bytes of the data.
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(b"PK\x03\x04") || data.starts_with(b"PK\x05\x06") {
+            Some(ArchiveFormat::Zip)
+        } else if data.starts_with(&[0x1f, 0x8b]) {
+            Some(ArchiveFormat::TarGz)
+        } else if data.len() > 262 && &data[257..262] == b"ustar" {
+            Some(ArchiveFormat::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns `true` if `path` is a file that looks like a source archive.
+pub fn is_archive(path: &Path) -> bool {
+    path.is_file() && ArchiveFormat::from_path(path).is_some()
+}
+
+/// Extracts the archive into `output_dir`.
+///
+/// Entries that would be written outside of `output_dir` are rejected.
+pub fn extract_archive(archive_file: &Path, output_dir: &Path) -> LinderaResult<()> {
+    let format = ArchiveFormat::from_path(archive_file).ok_or_else(|| {
+        LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+            "unsupported archive format: {archive_file:?} (expected .tar.gz, .tgz, .tar or .zip)"
+        ))
+    })?;
+    debug!("extracting {archive_file:?} into {output_dir:?}");
+
+    let file = File::open(archive_file).map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to open archive: {archive_file:?}"))
+    })?;
+    let reader = BufReader::new(file);
+
+    let result = match format {
+        ArchiveFormat::TarGz => tar::Archive::new(GzDecoder::new(reader))
+            .unpack(output_dir)
+            .map_err(|err| anyhow::anyhow!(err)),
+        ArchiveFormat::Tar => tar::Archive::new(reader)
+            .unpack(output_dir)
+            .map_err(|err| anyhow::anyhow!(err)),
+        ArchiveFormat::Zip => zip::ZipArchive::new(reader)
+            .and_then(|mut archive| archive.extract(output_dir))
+            .map_err(|err| anyhow::anyhow!(err)),
+    };
+    result.map_err(|err| {
+        LinderaErrorKind::Io.with_error(err).add_context(format!(
+            "Failed to extract archive: {archive_file:?} to {output_dir:?}"
+        ))
+    })
+}
+
+/// Reads the regular files of an archive held in memory.
+///
+/// Only the files whose path (with `/` separators) is accepted by `keep` are read.
+pub fn read_archive<F>(data: &[u8], keep: F) -> LinderaResult<Vec<(String, Vec<u8>)>>
+where
+    F: Fn(&str) -> bool,
+{
+    let format = ArchiveFormat::detect(data).ok_or_else(|| {
+        LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+            "unsupported archive format (expected zip, tar or tar.gz data)"
+        ))
+    })?;
+
+    let result = match format {
+        ArchiveFormat::TarGz => read_tar(tar::Archive::new(GzDecoder::new(data)), &keep),
+        ArchiveFormat::Tar => read_tar(tar::Archive::new(data), &keep),
+        ArchiveFormat::Zip => read_zip(data, &keep),
+    };
+    result.map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(err)
+            .add_context("Failed to read archive")
+    })
+}
+
+fn read_tar<R: Read>(
+    mut archive: tar::Archive<R>,
+    keep: &dyn Fn(&str) -> bool,
+) -> anyhow::Result<Vec<(String, Vec<u8>)>> {
+    let mut files = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = normalize_entry_path(&entry.path()?.to_string_lossy());
+        if keep(&path) {
+            let mut content = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut content)?;
+            files.push((path, content));
+        }
+    }
+    Ok(files)
+}
+
+fn read_zip(data: &[u8], keep: &dyn Fn(&str) -> bool) -> anyhow::Result<Vec<(String, Vec<u8>)>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data))?;
+    let mut files = Vec::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if !file.is_file() {
+            continue;
+        }
+        let path = normalize_entry_path(&file.name()?);
+        if keep(&path) {
+            let mut content = Vec::with_capacity(file.size() as usize);
+            file.read_to_end(&mut content)?;
+            files.push((path, content));
+        }
+    }
+    Ok(files)
+}
+
+fn normalize_entry_path(path: &str) -> String {
+    path.replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+/// Finds the directory holding the dictionary sources in an extracted archive.
+///
+/// Upstream archives usually wrap the sources in a top-level directory
+/// (e.g. `mecab-ipadic-2.7.0-20070801/`), so the shallowest directory
+/// containing `matrix.def` is returned.
+pub fn find_source_dir(extracted_dir: &Path) -> LinderaResult<PathBuf> {
+    let mut queue = VecDeque::from([extracted_dir.to_path_buf()]);
+    while let Some(dir) = queue.pop_front() {
+        if dir.join(SOURCE_MARKER).is_file() {
+            return Ok(dir);
+        }
+
+        let mut subdirs = fs::read_dir(&dir)
+            .map_err(|err| {
+                LinderaErrorKind::Io
+                    .with_error(anyhow::anyhow!(err))
+                    .add_context(format!("Failed to read directory: {dir:?}"))
+            })?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_dir())
+            .collect::<Vec<_>>();
+        // Visit directories in name order so that the result does not depend on the file system.
+        subdirs.sort();
+        queue.extend(subdirs);
+    }
+
+    Err(LinderaErrorKind::NotFound.with_error(anyhow::anyhow!(
+        "no {SOURCE_MARKER} found in the extracted archive"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_archive_format_from_path() {
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("mecab-ipadic-2.7.0-20070801.tar.gz")),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("dict.TGZ")),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("dict.tar")),
+            Some(ArchiveFormat::Tar)
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("unidic-csj-3.1.1.zip")),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(ArchiveFormat::from_path(Path::new("mecab-ipadic")), None);
+    }
+
+    #[test]
+    fn test_extract_zip_and_find_source_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_file = dir.path().join("dict.zip");
+
+        let mut writer = zip::ZipWriter::new(File::create(&archive_file).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.add_directory("dict-1.0/", options).unwrap();
+        writer.start_file("dict-1.0/README", options).unwrap();
+        writer.write_all(b"readme").unwrap();
+        writer.add_directory("dict-1.0/src/", options).unwrap();
+        writer
+            .start_file("dict-1.0/src/matrix.def", options)
+            .unwrap();
+        writer.write_all(b"1 1\n0 0 0\n").unwrap();
+        writer.finish().unwrap();
+
+        let output_dir = dir.path().join("extracted");
+        extract_archive(&archive_file, &output_dir).unwrap();
+
+        assert_eq!(
+            find_source_dir(&output_dir).unwrap(),
+            output_dir.join("dict-1.0").join("src")
+        );
+    }
+
+    #[test]
+    fn test_read_archive() {
+        let mut data = Vec::new();
+        {
+            let mut builder = tar::Builder::new(&mut data);
+            for (path, content) in [
+                ("./dict/metadata.json", &b"{}"[..]),
+                ("dict/README", b"readme"),
+            ] {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(content.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                builder.append_data(&mut header, path, content).unwrap();
+            }
+            builder.finish().unwrap();
+        }
+        assert_eq!(ArchiveFormat::detect(&data), Some(ArchiveFormat::Tar));
+
+        let files = read_archive(&data, |path| path.ends_with(".json")).unwrap();
+        assert_eq!(
+            files,
+            vec![("dict/metadata.json".to_string(), b"{}".to_vec())]
+        );
+
+        let err = read_archive(b"not an archive", |_| true).unwrap_err();
+        assert_eq!(err.kind(), LinderaErrorKind::Args);
+    }
+
+    #[test]
+    fn test_find_source_dir_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("empty")).unwrap();
+
+        let err = find_source_dir(dir.path()).unwrap_err();
+        assert_eq!(err.kind(), LinderaErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,331 @@
+//! Conversion of the dictionaries in the CC-CEDICT text format (CC-CEDICT, CC-Canto and the
+//! dictionaries derived from them) into a MeCab-style source directory.
+//!
+//! Each line of these dictionaries gives the traditional and simplified forms of a word, its
+//! pinyin in brackets, the jyutping in braces for the Cantonese dictionaries, and the definitions
+//! between slashes:
+//!
+//! ```text
+//! # comments start with a hash
+//! 一丁點 一丁点 [yi1 ding1 dian3] {jat1 ding1 dim2} /a tiny bit/a wee bit/
+//! ```
+//!
+//! The converter writes one lexicon entry for the simplified form and one for the traditional
+//! form when it differs, with the fields of the dictionary schema (`pinyin`, `jyutping`,
+//! `traditional`, `simplified` and `definition`) filled by name. The dictionaries do not come
+//! with costs or a connection matrix, so every word gets the same cost, which favors the
+//! segmentation into the fewest words, and the unknown characters are single-character words.
+
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use log::debug;
+
+use crate::LinderaResult;
+use crate::builder::archive::is_archive;
+use crate::dictionary::metadata::Metadata;
+use crate::error::LinderaErrorKind;
+use crate::util::read_file;
+
+/// Cost of the words of the lexicon.
+const WORD_COST: i16 = 3000;
+
+/// Cost of an unknown word, higher than any sequence of the words that cover the same characters.
+const UNKNOWN_WORD_COST: i16 = 10000;
+
+/// Number of lines read to tell whether a file is in the CC-CEDICT format.
+const DETECTION_LINES: usize = 64;
+
+const CHAR_DEF: &str = "\
+DEFAULT 0 1 0
+SPACE 0 1 0
+ALPHA 1 1 0
+NUMERIC 1 1 0
+HANZI 0 0 1
+0x0020 SPACE
+0x3000 SPACE
+0x0030..0x0039 NUMERIC
+0xFF10..0xFF19 NUMERIC
+0x0041..0x005A ALPHA
+0x0061..0x007A ALPHA
+0xFF21..0xFF3A ALPHA
+0xFF41..0xFF5A ALPHA
+0x3400..0x4DBF HANZI
+0x4E00..0x9FFF HANZI
+0xF900..0xFAFF HANZI
+";
+
+const CATEGORIES: &[&str] = &["DEFAULT", "SPACE", "ALPHA", "NUMERIC", "HANZI"];
+
+/// An entry of a dictionary in the CC-CEDICT format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CedictEntry {
+    pub traditional: String,
+    pub simplified: String,
+    /// Pinyin with the tone numbers (e.g. `yi1 ding1 dian3`).
+    pub pinyin: String,
+    /// Jyutping of the Cantonese dictionaries (e.g. `jat1 ding1 dim2`).
+    pub jyutping: Option<String>,
+    pub definitions: Vec<String>,
+}
+
+impl CedictEntry {
+    /// The value of the lexicon field `name` for this entry, if the field is one the CC-CEDICT
+    /// format provides.
+    fn field(&self, name: &str) -> Option<String> {
+        match name {
+            "pinyin" => Some(self.pinyin.clone()),
+            "jyutping" => self.jyutping.clone(),
+            "traditional" => Some(self.traditional.clone()),
+            "simplified" => Some(self.simplified.clone()),
+            // Written like the definitions of CC-CEDICT-MeCab, each one followed by a slash
+            "definition" if !self.definitions.is_empty() => Some(
+                self.definitions
+                    .iter()
+                    .map(|definition| format!("{definition}/"))
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a line of a dictionary in the CC-CEDICT format.
+///
+/// Returns `None` for the comment lines, the empty lines and the lines in another format. The
+/// definitions are optional, as in the readings-only files of CC-Canto.
+pub fn parse_line(line: &str) -> Option<CedictEntry> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (traditional, rest) = line.split_once(' ')?;
+    let (simplified, rest) = rest.trim_start().split_once(' ')?;
+    let (pinyin, rest) = rest.trim_start().strip_prefix('[')?.split_once(']')?;
+    let rest = rest.trim_start();
+    let (jyutping, rest) = match rest.strip_prefix('{') {
+        Some(rest) => {
+            let (jyutping, rest) = rest.split_once('}')?;
+            (Some(jyutping.trim().to_string()), rest.trim_start())
+        }
+        None => (None, rest),
+    };
+    let definitions = match rest.strip_prefix('/') {
+        Some(rest) => rest
+            .split('/')
+            .map(str::trim)
+            .filter(|definition| !definition.is_empty())
+            .map(str::to_string)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Some(CedictEntry {
+        traditional: traditional.to_string(),
+        simplified: simplified.to_string(),
+        pinyin: pinyin.trim().to_string(),
+        jyutping,
+        definitions,
+    })
+}
+
+/// Returns `true` if `path` is a text file whose first entry is in the CC-CEDICT format.
+pub fn is_cedict_file(path: &Path) -> bool {
+    if !path.is_file() || is_archive(path) {
+        return false;
+    }
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+
+    BufReader::new(file)
+        .lines()
+        .take(DETECTION_LINES)
+        .map_while(Result::ok)
+        .find(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .and_then(|line| parse_line(&line))
+        .is_some()
+}
+
+/// Finds the dictionary file in the CC-CEDICT format in an extracted archive, the shallowest
+/// one in name order.
+pub fn find_cedict_file(extracted_dir: &Path) -> LinderaResult<Option<PathBuf>> {
+    let mut queue = VecDeque::from([extracted_dir.to_path_buf()]);
+    while let Some(dir) = queue.pop_front() {
+        let mut paths = fs::read_dir(&dir)
+            .map_err(|err| {
+                LinderaErrorKind::Io
+                    .with_error(anyhow::anyhow!(err))
+                    .add_context(format!("Failed to read directory: {dir:?}"))
+            })?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        if let Some(path) = paths.iter().find(|path| is_cedict_file(path)) {
+            return Ok(Some(path.clone()));
+        }
+        queue.extend(paths.into_iter().filter(|path| path.is_dir()));
+    }
+
+    Ok(None)
+}
+
+/// Converts the dictionary file `input_file` in the CC-CEDICT format into a source directory
+/// with the lexicon `lex.csv` in the schema of `metadata`, and the `char.def`, `unk.def` and
+/// `matrix.def` of a dictionary without context IDs.
+pub fn convert_cedict(
+    input_file: &Path,
+    output_dir: &Path,
+    metadata: &Metadata,
+) -> LinderaResult<()> {
+    debug!("converting {input_file:?} into {output_dir:?}");
+    let text = String::from_utf8(read_file(input_file)?).map_err(|err| {
+        LinderaErrorKind::Decode
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to decode file as UTF-8: {input_file:?}"))
+    })?;
+
+    let fields = &metadata.dictionary_schema.fields;
+    let mut lexicon = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(Vec::new());
+    for entry in text.lines().filter_map(parse_line) {
+        let mut surfaces = vec![entry.simplified.as_str()];
+        if entry.traditional != entry.simplified {
+            surfaces.push(entry.traditional.as_str());
+        }
+        for surface in surfaces {
+            let record = fields.iter().enumerate().map(|(index, name)| match index {
+                0 => surface.to_string(),
+                1 | 2 => "0".to_string(),
+                3 => WORD_COST.to_string(),
+                _ => entry
+                    .field(name)
+                    .unwrap_or_else(|| metadata.default_field_value.clone()),
+            });
+            lexicon
+                .write_record(record)
+                .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?;
+        }
+    }
+    let lexicon = lexicon
+        .into_inner()
+        .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?;
+
+    // The unknown words have the default value in the fields after the cost
+    let unknown_fields = vec![metadata.default_field_value.as_str(); fields.len().max(4) - 4];
+    let unk_def = CATEGORIES
+        .iter()
+        .map(|category| {
+            let mut record = vec![*category, "0", "0"];
+            let cost = UNKNOWN_WORD_COST.to_string();
+            record.push(&cost);
+            record.extend(&unknown_fields);
+            record.join(",") + "\n"
+        })
+        .collect::<String>();
+
+    for (file_name, data) in [
+        ("lex.csv", lexicon.as_slice()),
+        ("char.def", CHAR_DEF.as_bytes()),
+        ("unk.def", unk_def.as_bytes()),
+        ("matrix.def", b"1 1\n0 0 0\n".as_slice()),
+    ] {
+        let path = output_dir.join(file_name);
+        fs::write(&path, data).map_err(|err| {
+            LinderaErrorKind::Io
+                .with_error(anyhow::anyhow!(err))
+                .add_context(format!("Failed to write file: {path:?}"))
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::dictionary::schema::Schema;
+
+    #[test]
+    fn test_parse_line() {
+        let entry =
+            parse_line("一丁點 一丁点 [yi1 ding1 dian3] {jat1 ding1 dim2} /a tiny bit/a wee bit/")
+                .unwrap();
+        assert_eq!("一丁點", entry.traditional);
+        assert_eq!("一丁点", entry.simplified);
+        assert_eq!("yi1 ding1 dian3", entry.pinyin);
+        assert_eq!(Some("jat1 ding1 dim2"), entry.jyutping.as_deref());
+        assert_eq!(vec!["a tiny bit", "a wee bit"], entry.definitions);
+
+        let entry = parse_line("測試 测试 [ce4 shi4] /to test/test, quiz/").unwrap();
+        assert_eq!(None, entry.jyutping);
+        assert_eq!(vec!["to test", "test, quiz"], entry.definitions);
+
+        // The readings-only files of CC-Canto have no definitions
+        let entry = parse_line("一 一 [yi1] {jat1}").unwrap();
+        assert_eq!(Some("jat1"), entry.jyutping.as_deref());
+        assert!(entry.definitions.is_empty());
+
+        assert_eq!(None, parse_line("# CC-CEDICT"));
+        assert_eq!(None, parse_line(""));
+        assert_eq!(None, parse_line("测试,0,0,-1131,*,*,*,*,ce4 shi4"));
+    }
+
+    #[test]
+    fn test_convert_cedict() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let input_file = input_dir.path().join("cccanto.txt");
+        fs::write(
+            &input_file,
+            "# CC-Canto\n測試 测试 [ce4 shi4] {caak1 si3} /to test/test, quiz/\n香港 香港 [Xiang1 gang3] {hoeng1 gong2} /Hong Kong/\n",
+        )
+        .unwrap();
+        assert!(is_cedict_file(&input_file));
+        assert_eq!(
+            Some(input_file.clone()),
+            find_cedict_file(input_dir.path()).unwrap()
+        );
+
+        let metadata = Metadata {
+            dictionary_schema: Schema::new(
+                [
+                    "surface",
+                    "left_context_id",
+                    "right_context_id",
+                    "cost",
+                    "part_of_speech",
+                    "pinyin",
+                    "jyutping",
+                    "traditional",
+                    "simplified",
+                    "definition",
+                ]
+                .map(str::to_string)
+                .to_vec(),
+            ),
+            ..Metadata::default()
+        };
+        let output_dir = tempfile::tempdir().unwrap();
+        convert_cedict(&input_file, output_dir.path(), &metadata).unwrap();
+
+        assert_eq!(
+            "测试,0,0,3000,*,ce4 shi4,caak1 si3,測試,测试,\"to test/test, quiz/\"\n\
+             測試,0,0,3000,*,ce4 shi4,caak1 si3,測試,测试,\"to test/test, quiz/\"\n\
+             香港,0,0,3000,*,Xiang1 gang3,hoeng1 gong2,香港,香港,Hong Kong/\n",
+            fs::read_to_string(output_dir.path().join("lex.csv")).unwrap()
+        );
+        assert!(
+            fs::read_to_string(output_dir.path().join("unk.def"))
+                .unwrap()
+                .contains("HANZI,0,0,10000,*,*,*,*,*,*\n")
+        );
+        assert!(!is_cedict_file(&output_dir.path().join("lex.csv")));
+    }
+}
@@ -10,6 +10,7 @@ use encoding_rs::UTF_16LE;
 use log::debug;
 
 use crate::LinderaResult;
+use crate::compress::CompressionSettings;
 use crate::decompress::Algorithm;
 use crate::dictionary::character_definition::{
     CategoryData, CategoryId, CharacterDefinition, LookupTable,
@@ -57,6 +58,8 @@ pub struct CharacterDefinitionBuilder {
     encoding: Cow<'static, str>,
     #[builder(default = "Algorithm::Deflate")]
     compress_algorithm: Algorithm,
+    #[builder(default = "CompressionSettings::default()")]
+    compression: CompressionSettings,
     #[builder(default = "Vec::new()")]
     category_definition: Vec<CategoryData>,
     #[builder(default = "HashMap::new()")]
@@ -255,7 +258,12 @@ impl CharacterDefinitionBuilder {
                     ))
             })?);
 
-        compress_write(&chardef_buffer, self.compress_algorithm, &mut wtr_chardef)?;
+        compress_write(
+            &chardef_buffer,
+            self.compress_algorithm,
+            &self.compression,
+            &mut wtr_chardef,
+        )?;
 
         wtr_chardef.flush().map_err(|err| {
             LinderaErrorKind::Io
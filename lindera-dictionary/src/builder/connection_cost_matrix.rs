@@ -1,17 +1,22 @@
 use std::borrow::Cow;
 use std::fs::File;
-use std::io::{self, Write};
+use std::io::{self, BufRead, BufReader, Write};
 use std::path::Path;
 use std::str::FromStr;
 
-use byteorder::{LittleEndian, WriteBytesExt};
 use derive_builder::Builder;
+use encoding_rs_io::DecodeReaderBytesBuilder;
 use log::debug;
 
 use crate::LinderaResult;
+use crate::compress::CompressionSettings;
 use crate::decompress::Algorithm;
+use crate::dictionary::connection_cost_matrix::{
+    QUANTIZED_MATRIX_FLAG, QuantizedCosts, SHARDED_MATRIX_FLAG, matrix_shard_file_name,
+};
+use crate::dictionary::metadata::MatrixEncoding;
 use crate::error::LinderaErrorKind;
-use crate::util::{compress_write, read_file_with_encoding};
+use crate::util::{compress_write, is_auto_encoding, resolve_encoding};
 
 #[derive(Builder, Debug)]
 #[builder(name = ConnectionCostMatrixBuilderOptions)]
@@ -21,63 +26,171 @@ pub struct ConnectionCostMatrixBuilder {
     encoding: Cow<'static, str>,
     #[builder(default = "Algorithm::Deflate")]
     compress_algorithm: Algorithm,
+    #[builder(default = "CompressionSettings::default()")]
+    compression: CompressionSettings,
+    /// The number of backward ids of each shard of a sharded matrix, `None` to write the whole
+    /// matrix in `matrix.mtx`.
+    #[builder(default = "None")]
+    shard_size: Option<u32>,
+    #[builder(default = "MatrixEncoding::Plain")]
+    cost_encoding: MatrixEncoding,
 }
 
 impl ConnectionCostMatrixBuilder {
     pub fn build(&self, input_dir: &Path, output_dir: &Path) -> LinderaResult<()> {
         let matrix_data_path = input_dir.join("matrix.def");
         debug!("reading {matrix_data_path:?}");
-        let matrix_data = read_file_with_encoding(&matrix_data_path, &self.encoding)?;
 
-        let mut lines = Vec::new();
-        for line in matrix_data.lines() {
-            let fields: Vec<i32> = line
-                .split_whitespace()
-                .map(i32::from_str)
-                .collect::<Result<_, _>>()
-                .map_err(|err| LinderaErrorKind::Parse.with_error(anyhow::anyhow!(err)))?;
-            lines.push(fields);
-        }
-        let mut lines_it = lines.into_iter();
-        let header = lines_it.next().ok_or_else(|| {
-            LinderaErrorKind::Content.with_error(anyhow::anyhow!("unknown error"))
+        // The matrix is read line by line and written directly into the output buffer,
+        // since matrix.def of large dictionaries does not fit in memory as text.
+        let file = File::open(&matrix_data_path).map_err(|err| {
+            LinderaErrorKind::Io
+                .with_error(anyhow::anyhow!(err))
+                .add_context(format!("Failed to open file: {matrix_data_path:?}"))
         })?;
+        // matrix.def only contains ASCII, so with automatic detection it is read as UTF-8
+        // (or as indicated by a byte order mark).
+        let encoding = if is_auto_encoding(&self.encoding) {
+            None
+        } else {
+            Some(resolve_encoding(&self.encoding, &[])?)
+        };
+        let reader = BufReader::new(
+            DecodeReaderBytesBuilder::new()
+                .encoding(encoding)
+                .build(file),
+        );
+        let mut lines = reader.lines();
+
+        let header = lines
+            .next()
+            .transpose()
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?
+            .ok_or_else(|| {
+                LinderaErrorKind::Content.with_error(anyhow::anyhow!("unknown error"))
+            })?;
+        let header = parse_fields(&header, 2)?;
         let forward_size = header[0] as u32;
         let backward_size = header[1] as u32;
         let len = 3 + (forward_size * backward_size) as usize;
-        let mut costs = vec![i16::MAX; len];
-        costs[0] = -1; // Version flag for transposed layout
-        costs[1] = forward_size as i16;
-        costs[2] = backward_size as i16;
-        for fields in lines_it {
+
+        // Little-endian i16 costs
+        let mut matrix_mtx_buffer = i16::MAX.to_le_bytes().repeat(len);
+        let mut set_cost = |index: usize, cost: i16| {
+            matrix_mtx_buffer[index * 2..index * 2 + 2].copy_from_slice(&cost.to_le_bytes());
+        };
+        set_cost(0, -1); // Version flag for transposed layout
+        set_cost(1, forward_size as i16);
+        set_cost(2, backward_size as i16);
+        for line in lines {
+            let line = line.map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields = parse_fields(&line, 3)?;
             let forward_id = fields[0] as u32;
             let backward_id = fields[1] as u32;
             let cost = fields[2] as u16;
-            costs[3 + (forward_id + backward_id * forward_size) as usize] = cost as i16;
+            set_cost(
+                3 + (forward_id + backward_id * forward_size) as usize,
+                cost as i16,
+            );
+        }
+
+        match self.shard_size.filter(|_| backward_size > 0) {
+            Some(shard_size) => {
+                let shard_size = shard_size.clamp(1, backward_size);
+                // matrix.mtx only holds the sizes, and each shard the costs of its backward ids
+                let mut header = Vec::with_capacity(8);
+                let encoding = match self.cost_encoding {
+                    MatrixEncoding::Plain => 0,
+                    MatrixEncoding::Quantized => 1,
+                };
+                for value in [
+                    SHARDED_MATRIX_FLAG,
+                    forward_size as i16,
+                    backward_size as i16,
+                    shard_size as i16,
+                    encoding,
+                ] {
+                    header.extend_from_slice(&value.to_le_bytes());
+                }
+                self.write(&header, &output_dir.join("matrix.mtx"))?;
+
+                let costs = &matrix_mtx_buffer[3 * 2..];
+                let shard_len = (shard_size * forward_size) as usize * 2;
+                for (index, shard) in costs.chunks(shard_len.max(2)).enumerate() {
+                    let shard = self.encode(shard, forward_size);
+                    self.write(&shard, &output_dir.join(matrix_shard_file_name(index)))?;
+                }
+                Ok(())
+            }
+            None => {
+                let data = match self.cost_encoding {
+                    MatrixEncoding::Plain => matrix_mtx_buffer,
+                    MatrixEncoding::Quantized => {
+                        let mut data = Vec::new();
+                        for value in [
+                            QUANTIZED_MATRIX_FLAG,
+                            forward_size as i16,
+                            backward_size as i16,
+                        ] {
+                            data.extend_from_slice(&value.to_le_bytes());
+                        }
+                        data.extend_from_slice(
+                            &self.encode(&matrix_mtx_buffer[3 * 2..], forward_size),
+                        );
+                        data
+                    }
+                };
+                self.write(&data, &output_dir.join("matrix.mtx"))
+            }
         }
+    }
+
+    /// Encodes little-endian costs in the encoding of the matrix.
+    fn encode<'a>(&self, costs: &'a [u8], forward_size: u32) -> Cow<'a, [u8]> {
+        match self.cost_encoding {
+            MatrixEncoding::Plain => Cow::Borrowed(costs),
+            MatrixEncoding::Quantized => {
+                let costs = costs
+                    .chunks_exact(2)
+                    .map(|cost| i16::from_le_bytes([cost[0], cost[1]]))
+                    .collect::<Vec<_>>();
+                Cow::Owned(QuantizedCosts::encode(&costs, forward_size))
+            }
+        }
+    }
 
-        let wtr_matrix_mtx_path = output_dir.join(Path::new("matrix.mtx"));
-        let mut wtr_matrix_mtx = io::BufWriter::new(
-            File::create(wtr_matrix_mtx_path)
+    fn write(&self, data: &[u8], path: &Path) -> LinderaResult<()> {
+        let mut writer = io::BufWriter::new(
+            File::create(path)
                 .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?,
         );
-        let mut matrix_mtx_buffer = Vec::new();
-        for cost in costs {
-            matrix_mtx_buffer
-                .write_i16::<LittleEndian>(cost)
-                .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
-        }
 
         compress_write(
-            &matrix_mtx_buffer,
+            data,
             self.compress_algorithm,
-            &mut wtr_matrix_mtx,
+            &self.compression,
+            &mut writer,
         )?;
 
-        wtr_matrix_mtx
+        writer
             .flush()
-            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+            .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))
+    }
+}
 
-        Ok(())
+/// Parses a line of matrix.def that must have at least `expected` fields.
+fn parse_fields(line: &str, expected: usize) -> LinderaResult<Vec<i32>> {
+    let fields: Vec<i32> = line
+        .split_whitespace()
+        .map(i32::from_str)
+        .collect::<Result<_, _>>()
+        .map_err(|err| LinderaErrorKind::Parse.with_error(anyhow::anyhow!(err)))?;
+    if fields.len() < expected {
+        return Err(LinderaErrorKind::Content
+            .with_error(anyhow::anyhow!("invalid line in matrix.def: {line}")));
     }
+    Ok(fields)
 }
@@ -0,0 +1,373 @@
+//! Export of built dictionaries back into their source files.
+//!
+//! [`export_dictionary`] writes the files MeCab and [`DictionaryBuilder`](crate::builder::DictionaryBuilder)
+//! build a dictionary from: the lexicon `lex.csv`, `matrix.def`, `char.def`, `unk.def`, and the
+//! `metadata.json` to build them with. [`export_user_dictionary`] writes a user dictionary as a
+//! CSV file in the detailed format. The lexicon rows are written in the order of the word IDs, so
+//! that building the exported files again gives the same word IDs.
+//!
+//! A built dictionary does not keep everything its source had: the comments of the files, the
+//! details of the unknown words (written with the default field value), and the code points of
+//! `char.def` above U+FFFF, which the format cannot express.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use log::debug;
+
+use crate::LinderaResult;
+use crate::dictionary::character_definition::{CategoryId, CharacterDefinition};
+use crate::dictionary::connection_cost_matrix::ConnectionCostMatrix;
+use crate::dictionary::metadata::Metadata;
+use crate::dictionary::prefix_dictionary::PrefixDictionary;
+use crate::dictionary::unknown_dictionary::UnknownDictionary;
+use crate::dictionary::{Dictionary, UserDictionary};
+use crate::error::LinderaErrorKind;
+
+/// The last code point `char.def` can define.
+const MAX_CODE_POINT: u32 = 0xFFFF;
+
+/// Writes the source files of `dictionary` into `output_dir`.
+pub fn export_dictionary(dictionary: &Dictionary, output_dir: &Path) -> LinderaResult<()> {
+    debug!("exporting dictionary into {output_dir:?}");
+    std::fs::create_dir_all(output_dir).map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to create directory: {output_dir:?}"))
+    })?;
+
+    write_file(&output_dir.join("lex.csv"), |writer| {
+        write_lexicon(&dictionary.prefix_dictionary, writer)
+    })?;
+    write_file(&output_dir.join("matrix.def"), |writer| {
+        write_matrix_def(&dictionary.connection_cost_matrix, writer)
+    })?;
+    write_file(&output_dir.join("char.def"), |writer| {
+        write_char_def(&dictionary.character_definition, writer)
+    })?;
+    write_file(&output_dir.join("unk.def"), |writer| {
+        write_unk_def(
+            &dictionary.unknown_dictionary,
+            &dictionary.character_definition,
+            &dictionary.metadata,
+            writer,
+        )
+    })?;
+
+    // The exported files are in UTF-8, whatever the encoding of the original source
+    let metadata = Metadata {
+        encoding: "UTF-8".to_string(),
+        ..dictionary.metadata.clone()
+    };
+    write_file(&output_dir.join("metadata.json"), |writer| {
+        serde_json::to_writer_pretty(&mut *writer, &metadata)
+            .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?;
+        writeln!(writer).map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))
+    })
+}
+
+/// Writes the rows of `user_dictionary` as a user dictionary CSV file in the detailed format.
+pub fn export_user_dictionary<W: Write>(
+    user_dictionary: &UserDictionary,
+    writer: W,
+) -> LinderaResult<()> {
+    write_lexicon(&user_dictionary.dict, writer)
+}
+
+/// Writes the entries of a prefix dictionary as CSV rows of their surface, context IDs, cost and
+/// details, in the order of the word IDs.
+pub fn write_lexicon<W: Write>(
+    prefix_dictionary: &PrefixDictionary,
+    writer: W,
+) -> LinderaResult<()> {
+    let mut entries = prefix_dictionary.entries();
+    entries.sort_by_key(|(_, entry)| entry.word_id.id);
+
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_writer(writer);
+    for (surface, entry) in &entries {
+        let details = prefix_dictionary
+            .word_details_bytes(entry.word_id.id as usize)
+            .unwrap_or_default();
+        let mut record = vec![
+            surface.as_bytes().to_vec(),
+            entry.left_id.to_string().into_bytes(),
+            entry.right_id.to_string().into_bytes(),
+            entry.word_cost.to_string().into_bytes(),
+        ];
+        if !details.is_empty() {
+            record.extend(details.split(|&b| b == 0).map(<[u8]>::to_vec));
+        }
+        writer
+            .write_record(&record)
+            .map_err(|err| LinderaErrorKind::Serialize.with_error(anyhow::anyhow!(err)))?;
+    }
+    writer
+        .flush()
+        .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))
+}
+
+/// Writes the connection cost matrix in the format of `matrix.def`.
+pub fn write_matrix_def<W: Write>(
+    matrix: &ConnectionCostMatrix,
+    mut writer: W,
+) -> LinderaResult<()> {
+    let io_error = |err: io::Error| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err));
+    writeln!(writer, "{} {}", matrix.forward_size, matrix.backward_size).map_err(io_error)?;
+    for forward_id in 0..matrix.forward_size {
+        for backward_id in 0..matrix.backward_size {
+            writeln!(
+                writer,
+                "{forward_id} {backward_id} {}",
+                matrix.cost(forward_id, backward_id)
+            )
+            .map_err(io_error)?;
+        }
+    }
+    writer.flush().map_err(io_error)
+}
+
+/// Writes the character definition in the format of `char.def`: the categories, then the code
+/// point ranges of the categories other than the default one.
+pub fn write_char_def<W: Write>(
+    character_definition: &CharacterDefinition,
+    mut writer: W,
+) -> LinderaResult<()> {
+    let io_error = |err: io::Error| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err));
+    for (name, definition) in character_definition
+        .category_names
+        .iter()
+        .zip(&character_definition.category_definitions)
+    {
+        writeln!(
+            writer,
+            "{name} {} {} {}",
+            u8::from(definition.invoke),
+            u8::from(definition.group),
+            definition.length
+        )
+        .map_err(io_error)?;
+    }
+
+    let default_category = character_definition.category_id_by_name("DEFAULT");
+    for (start, end, category_ids) in character_definition.mapping.ranges() {
+        if start > MAX_CODE_POINT
+            || category_ids.is_empty()
+            || category_ids == [default_category.unwrap_or(CategoryId(usize::MAX))]
+        {
+            continue;
+        }
+        let end = end.min(MAX_CODE_POINT);
+        let names = category_ids
+            .iter()
+            .map(|&category_id| character_definition.category_name(category_id))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if start == end {
+            writeln!(writer, "0x{start:04X} {names}")
+        } else {
+            writeln!(writer, "0x{start:04X}..0x{end:04X} {names}")
+        }
+        .map_err(io_error)?;
+    }
+    writer.flush().map_err(io_error)
+}
+
+/// Writes the unknown word entries in the format of `unk.def`, with the default field value in
+/// the fields of the schema after the cost.
+pub fn write_unk_def<W: Write>(
+    unknown_dictionary: &UnknownDictionary,
+    character_definition: &CharacterDefinition,
+    metadata: &Metadata,
+    mut writer: W,
+) -> LinderaResult<()> {
+    let io_error = |err: io::Error| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err));
+    let details_len = metadata.dictionary_schema.fields.len().saturating_sub(4);
+    let details = vec![metadata.default_field_value.as_str(); details_len];
+
+    for (word_id, entry) in unknown_dictionary.costs.iter().enumerate() {
+        let Some(category_id) = unknown_dictionary
+            .category_references
+            .iter()
+            .position(|word_ids| word_ids.contains(&(word_id as u32)))
+        else {
+            continue;
+        };
+        let mut record = vec![
+            character_definition
+                .category_name(CategoryId(category_id))
+                .to_string(),
+            entry.left_id.to_string(),
+            entry.right_id.to_string(),
+            entry.word_cost.to_string(),
+        ];
+        record.extend(details.iter().map(|detail| detail.to_string()));
+        writeln!(writer, "{}", record.join(",")).map_err(io_error)?;
+    }
+    writer.flush().map_err(io_error)
+}
+
+fn write_file(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> LinderaResult<()>,
+) -> LinderaResult<()> {
+    let file = File::create(path).map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to create file: {path:?}"))
+    })?;
+    let mut writer = BufWriter::new(file);
+    write(&mut writer)?;
+    writer.flush().map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to write file: {path:?}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+    use crate::builder::DictionaryBuilder;
+    use crate::builder::user_dictionary::build_user_dictionary;
+    use crate::dictionary::schema::Schema;
+
+    const CHAR_DEF: &str = "\
+DEFAULT 0 1 0
+SPACE 0 1 0
+KANJI 0 0 2
+KATAKANA 1 1 0
+0x0020 SPACE
+0x30A1..0x30FF KATAKANA
+0x4E00..0x9FAF KANJI
+";
+
+    const LEX_CSV: &str = "\
+東京,1,1,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー
+東,2,2,300,名詞,一般,*,*,*,*,東,ヒガシ,ヒガシ
+京都,1,1,200,名詞,固有名詞,地域,一般,*,*,京都,キョウト,キョート
+東京,2,2,500,名詞,一般,*,*,*,*,東京,ヒガシキョウ,ヒガシキョー
+\"1,2\",2,2,400,名詞,数,*,*,*,*,\"1,2\",*,*
+";
+
+    fn write_source(dir: &Path) {
+        fs::write(dir.join("char.def"), CHAR_DEF).unwrap();
+        fs::write(
+            dir.join("unk.def"),
+            "DEFAULT,0,0,1000,*,*,*,*,*,*,*,*,*\nKANJI,1,1,2000,*,*,*,*,*,*,*,*,*\nKATAKANA,2,2,3000,*,*,*,*,*,*,*,*,*\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("matrix.def"),
+            "3 3\n0 0 0\n0 1 1\n0 2 2\n1 0 3\n1 1 4\n1 2 5\n2 0 6\n2 1 7\n2 2 -8\n",
+        )
+        .unwrap();
+        fs::write(dir.join("lex.csv"), LEX_CSV).unwrap();
+    }
+
+    fn metadata() -> Metadata {
+        Metadata {
+            dictionary_schema: Schema::default(),
+            ..Metadata::default()
+        }
+    }
+
+    #[test]
+    fn test_export_dictionary() {
+        let source_dir = tempfile::tempdir().unwrap();
+        write_source(source_dir.path());
+        let dict_dir = tempfile::tempdir().unwrap();
+        DictionaryBuilder::new(metadata())
+            .build_dictionary(source_dir.path(), dict_dir.path())
+            .unwrap();
+        let dictionary = Dictionary::load_from_path(dict_dir.path()).unwrap();
+
+        let export_dir = tempfile::tempdir().unwrap();
+        export_dictionary(&dictionary, export_dir.path()).unwrap();
+        let lex_csv = fs::read_to_string(export_dir.path().join("lex.csv")).unwrap();
+        assert_eq!(
+            "\
+\"1,2\",2,2,400,名詞,数,*,*,*,*,\"1,2\",*,*
+京都,1,1,200,名詞,固有名詞,地域,一般,*,*,京都,キョウト,キョート
+東,2,2,300,名詞,一般,*,*,*,*,東,ヒガシ,ヒガシ
+東京,1,1,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー
+東京,2,2,500,名詞,一般,*,*,*,*,東京,ヒガシキョウ,ヒガシキョー
+",
+            lex_csv
+        );
+        assert_eq!(
+            "\
+DEFAULT 0 1 0
+SPACE 0 1 0
+KANJI 0 0 2
+KATAKANA 1 1 0
+0x0020 SPACE
+0x30A1..0x30FF KATAKANA
+0x4E00..0x9FAF KANJI
+",
+            fs::read_to_string(export_dir.path().join("char.def")).unwrap()
+        );
+        assert_eq!(
+            "DEFAULT,0,0,1000,*,*,*,*,*,*,*,*,*\nKANJI,1,1,2000,*,*,*,*,*,*,*,*,*\nKATAKANA,2,2,3000,*,*,*,*,*,*,*,*,*\n",
+            fs::read_to_string(export_dir.path().join("unk.def")).unwrap()
+        );
+        assert_eq!(
+            "3 3\n0 0 0\n0 1 1\n0 2 2\n1 0 3\n1 1 4\n1 2 5\n2 0 6\n2 1 7\n2 2 -8\n",
+            fs::read_to_string(export_dir.path().join("matrix.def")).unwrap()
+        );
+
+        // The exported files build the same dictionary
+        let metadata: Metadata =
+            serde_json::from_slice(&fs::read(export_dir.path().join("metadata.json")).unwrap())
+                .unwrap();
+        let rebuilt_dir = tempfile::tempdir().unwrap();
+        DictionaryBuilder::new(metadata)
+            .build_dictionary(export_dir.path(), rebuilt_dir.path())
+            .unwrap();
+        for file_name in [
+            "dict.da",
+            "dict.vals",
+            "dict.words",
+            "dict.wordsidx",
+            "matrix.mtx",
+            "char_def.bin",
+            "unk.bin",
+        ] {
+            assert_eq!(
+                fs::read(dict_dir.path().join(file_name)).unwrap(),
+                fs::read(rebuilt_dir.path().join(file_name)).unwrap(),
+                "{file_name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_export_user_dictionary() {
+        let user_csv = tempfile::NamedTempFile::new().unwrap();
+        fs::write(
+            user_csv.path(),
+            "東京スカイツリー,トウキョウスカイツリー,トーキョースカイツリー\n",
+        )
+        .unwrap();
+        let user_dictionary = DictionaryBuilder::new(metadata())
+            .build_user_dict(user_csv.path())
+            .unwrap();
+        let user_bin = tempfile::tempdir().unwrap();
+        build_user_dictionary(user_dictionary, &user_bin.path().join("user.bin")).unwrap();
+        let user_dictionary =
+            UserDictionary::load(&fs::read(user_bin.path().join("user.bin")).unwrap()).unwrap();
+
+        let mut csv = Vec::new();
+        export_user_dictionary(&user_dictionary, &mut csv).unwrap();
+        assert_eq!(
+            "東京スカイツリー,1288,1288,-10000,*,*,*,*,*,*,*,トウキョウスカイツリー,トーキョースカイツリー\n",
+            String::from_utf8(csv).unwrap()
+        );
+    }
+}
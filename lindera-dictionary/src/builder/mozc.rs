@@ -0,0 +1,274 @@
+//! Import of the user dictionaries of Mozc and Google Japanese Input into user dictionaries.
+//!
+//! The dictionary tool of Mozc exports a user dictionary as tab-separated lines of a reading in
+//! hiragana, a word, a part-of-speech category and an optional comment (the tabs are shown as
+//! `\t`):
+//!
+//! ```text
+//! りんでら\tリンデラ\t固有名詞\tmorphological analyzer
+//! ```
+//!
+//! The exports of Microsoft IME have the same columns after a header of `!` lines, and are read
+//! too. The categories of the nouns, symbols and interjections are mapped to the parts-of-speech
+//! of IPADIC and written as the rows of a user dictionary in the detailed format. The other
+//! categories (verbs, adjectives, affixes, the suppressed words...) have no inflection or
+//! attachment information a user dictionary row could carry, and are skipped.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use crate::LinderaResult;
+use crate::builder::user_dictionary::detailed_row;
+use crate::dictionary::metadata::Metadata;
+use crate::dictionary::reading_index::to_katakana;
+use crate::util::read_file_with_encoding;
+
+/// The part-of-speech of a Mozc category in IPADIC, with its context ID in mecab-ipadic-2.7.0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpadicPartOfSpeech {
+    pub part_of_speech: &'static [&'static str],
+    pub context_id: u16,
+}
+
+/// The part-of-speech of a Mozc category, or `None` for the categories that are not imported.
+pub fn ipadic_part_of_speech(category: &str) -> Option<IpadicPartOfSpeech> {
+    let (part_of_speech, context_id): (&'static [&'static str], u16) = match category {
+        "名詞" | "短縮よみ" | "アルファベット" => (&["名詞", "一般"], 1285),
+        "固有名詞" => (&["名詞", "固有名詞", "一般"], 1288),
+        "人名" => (&["名詞", "固有名詞", "人名", "一般"], 1289),
+        "姓" => (&["名詞", "固有名詞", "人名", "姓"], 1290),
+        "名" => (&["名詞", "固有名詞", "人名", "名"], 1291),
+        "組織" => (&["名詞", "固有名詞", "組織"], 1292),
+        "地名" => (&["名詞", "固有名詞", "地域", "一般"], 1293),
+        "名詞サ変" => (&["名詞", "サ変接続"], 1283),
+        "名詞形動" => (&["名詞", "形容動詞語幹"], 1287),
+        "数" => (&["名詞", "数"], 1295),
+        "記号" | "顔文字" => (&["記号", "一般"], 5),
+        "感動詞" => (&["感動詞"], 3),
+        _ => return None,
+    };
+    Some(IpadicPartOfSpeech {
+        part_of_speech,
+        context_id,
+    })
+}
+
+/// An entry of a Mozc user dictionary export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MozcEntry {
+    /// The reading in hiragana.
+    pub reading: String,
+    pub word: String,
+    /// The part-of-speech category, in the Japanese names of the dictionary tool.
+    pub category: String,
+}
+
+/// Parses a line of a Mozc user dictionary export.
+///
+/// Returns `None` for the empty lines, the comment (`#`) and header (`!`) lines and the lines
+/// with less than three columns.
+pub fn parse_line(line: &str) -> Option<MozcEntry> {
+    if line.starts_with('#') || line.starts_with('!') {
+        return None;
+    }
+    let mut columns = line.split('\t');
+    let reading = columns.next()?.trim();
+    let word = columns.next()?.trim();
+    let category = columns.next()?.trim();
+    if reading.is_empty() || word.is_empty() {
+        return None;
+    }
+
+    Some(MozcEntry {
+        reading: reading.to_string(),
+        word: word.to_string(),
+        category: category.to_string(),
+    })
+}
+
+/// The rows imported from a Mozc user dictionary, with the number of entries skipped per
+/// category.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MozcImport {
+    pub rows: Vec<Vec<String>>,
+    pub skipped: BTreeMap<String, usize>,
+}
+
+/// Converts a Mozc user dictionary export into the rows of a user dictionary in the detailed
+/// format of a dictionary.
+#[derive(Debug, Clone, Default)]
+pub struct MozcImporter {
+    /// The cost of the words, `default_word_cost` of the metadata if `None`.
+    pub word_cost: Option<i16>,
+}
+
+impl MozcImporter {
+    /// Imports the entries of `text` in the schema of `metadata`.
+    ///
+    /// The words get the context IDs of their part-of-speech in IPADIC if `metadata` is the one
+    /// of IPADIC or IPADIC NEologd, and the context IDs of the simple format otherwise. See
+    /// [`detailed_row`] for the fields.
+    pub fn import(&self, text: &str, metadata: &Metadata) -> MozcImport {
+        let ipadic = matches!(metadata.name.as_str(), "ipadic" | "ipadic-neologd");
+        let word_cost = self.word_cost.unwrap_or(metadata.default_word_cost);
+
+        let mut seen = HashSet::new();
+        let mut import = MozcImport::default();
+        for entry in text.lines().filter_map(parse_line) {
+            let Some(pos) = ipadic_part_of_speech(&entry.category) else {
+                *import.skipped.entry(entry.category).or_default() += 1;
+                continue;
+            };
+            let reading = to_katakana(&entry.reading);
+            if !seen.insert((entry.word.clone(), reading.clone(), pos.context_id)) {
+                continue;
+            }
+            let context_ids = if ipadic {
+                (pos.context_id, pos.context_id)
+            } else {
+                (
+                    metadata.default_left_context_id,
+                    metadata.default_right_context_id,
+                )
+            };
+            import.rows.push(detailed_row(
+                metadata,
+                &entry.word,
+                &reading,
+                pos.part_of_speech,
+                context_ids,
+                word_cost,
+            ));
+        }
+        import
+    }
+
+    /// Imports the Mozc user dictionary export `input_file`, decoded from `encoding` (`auto` to
+    /// detect UTF-8 and the UTF-16 of the Microsoft IME exports from their byte order mark).
+    pub fn import_file(
+        &self,
+        input_file: &Path,
+        encoding: &str,
+        metadata: &Metadata,
+    ) -> LinderaResult<MozcImport> {
+        let text = read_file_with_encoding(input_file, encoding)?;
+        Ok(self.import(&text, metadata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOZC_EXPORT: &str = "\
+!Microsoft IME Dictionary Tool
+りんでら\tリンデラ\t固有名詞\tmorphological analyzer
+やまだ\t山田\t姓\t
+たろう\t太郎\t名
+ぐぐる\tググる\t動詞ラ行五段
+よろ\tよろしくお願いします\t短縮よみ
+りんでら\tリンデラ\t固有名詞
+わたし\t私\t抑制単語
+";
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            Some(MozcEntry {
+                reading: "やまだ".to_string(),
+                word: "山田".to_string(),
+                category: "姓".to_string(),
+            }),
+            parse_line("やまだ\t山田\t姓\tcomment")
+        );
+        assert_eq!(None, parse_line("!Microsoft IME Dictionary Tool"));
+        assert_eq!(None, parse_line("# comment"));
+        assert_eq!(None, parse_line("やまだ\t山田"));
+        assert_eq!(None, parse_line(""));
+    }
+
+    #[test]
+    fn test_import() {
+        let metadata = Metadata {
+            name: "ipadic".to_string(),
+            ..Metadata::default()
+        };
+        let import = MozcImporter::default().import(MOZC_EXPORT, &metadata);
+        assert_eq!(
+            vec![
+                vec![
+                    "リンデラ",
+                    "1288",
+                    "1288",
+                    "-10000",
+                    "名詞",
+                    "固有名詞",
+                    "一般",
+                    "*",
+                    "*",
+                    "*",
+                    "リンデラ",
+                    "リンデラ",
+                    "リンデラ"
+                ],
+                vec![
+                    "山田",
+                    "1290",
+                    "1290",
+                    "-10000",
+                    "名詞",
+                    "固有名詞",
+                    "人名",
+                    "姓",
+                    "*",
+                    "*",
+                    "山田",
+                    "ヤマダ",
+                    "ヤマダ"
+                ],
+                vec![
+                    "太郎",
+                    "1291",
+                    "1291",
+                    "-10000",
+                    "名詞",
+                    "固有名詞",
+                    "人名",
+                    "名",
+                    "*",
+                    "*",
+                    "太郎",
+                    "タロウ",
+                    "タロウ"
+                ],
+                vec![
+                    "よろしくお願いします",
+                    "1285",
+                    "1285",
+                    "-10000",
+                    "名詞",
+                    "一般",
+                    "*",
+                    "*",
+                    "*",
+                    "*",
+                    "よろしくお願いします",
+                    "ヨロ",
+                    "ヨロ"
+                ],
+            ],
+            import.rows
+        );
+        assert_eq!(
+            BTreeMap::from([("動詞ラ行五段".to_string(), 1), ("抑制単語".to_string(), 1)]),
+            import.skipped
+        );
+
+        // The other dictionaries get the context IDs of the simple format
+        let importer = MozcImporter {
+            word_cost: Some(3000),
+        };
+        let import = importer.import("やまだ\t山田\t姓\n", &Metadata::default());
+        assert_eq!(vec!["山田", "1288", "1288", "3000"], import.rows[0][..4]);
+    }
+}
@@ -1,10 +1,12 @@
 use std::borrow::Cow;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::fs::File;
+use std::io;
 use std::io::Write;
-use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use anyhow::anyhow;
 use byteorder::{LittleEndian, WriteBytesExt};
@@ -12,32 +14,77 @@ use csv::StringRecord;
 use daachorse::DoubleArrayAhoCorasickBuilder;
 use derive_builder::Builder;
 use encoding_rs::{Encoding, UTF_8};
-use encoding_rs_io::DecodeReaderBytesBuilder;
 use glob::glob;
-use log::debug;
+use log::{debug, info};
+#[cfg(feature = "parallel")]
+use rayon::slice::ParallelSliceMut;
 
 use crate::LinderaResult;
+use crate::builder::normalize_surfaces;
+use crate::builder::progress::{BuildJob, BuildPhase};
+use crate::compress::CompressionSettings;
 use crate::decompress::Algorithm;
+use crate::dictionary::Dictionary;
+use crate::dictionary::metadata::{DuplicatePolicy, SurfaceNormalization};
+use crate::dictionary::okurigana::{OkuriganaVariants, okurigana_variants};
+use crate::dictionary::prefix_dictionary::{PrefixDictionary, SHARED_DETAILS_FLAG};
+use crate::dictionary::reading_index::ReadingIndex;
 use crate::dictionary::schema::Schema;
 use crate::error::LinderaErrorKind;
-use crate::util::compress_write;
+use crate::util::{
+    compress_write, is_auto_encoding, join, num_threads, par_map, read_file, resolve_encoding,
+};
 use crate::viterbi::WordEntry;
 
+/// Summary of how duplicate entries were resolved while building the dictionary.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DuplicateReport {
+    pub policy: DuplicatePolicy,
+    /// Number of rows that duplicate an earlier row.
+    pub duplicates: usize,
+    /// Number of rows removed from the dictionary.
+    pub removed: usize,
+}
+
+impl fmt::Display for DuplicateReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "found {} duplicate entries, removed {} (duplicate policy: {})",
+            self.duplicates, self.removed, self.policy
+        )
+    }
+}
+
 #[derive(Builder)]
 #[builder(name = PrefixDictionaryBuilderOptions)]
 #[builder(build_fn(name = "builder"))]
 pub struct PrefixDictionaryBuilder {
     #[builder(default = "true")]
     flexible_csv: bool,
-    /* If set to UTF-8, it can also read UTF-16 files with BOM. */
+    /* If set to UTF-8, it can also read UTF-16 files with BOM.
+    If set to `auto`, the encoding of each file is detected (UTF-8, EUC-JP or Shift_JIS). */
     #[builder(default = "\"UTF-8\".into()", setter(into))]
     encoding: Cow<'static, str>,
     #[builder(default = "Algorithm::Deflate")]
     compress_algorithm: Algorithm,
+    #[builder(default = "CompressionSettings::default()")]
+    compression: CompressionSettings,
     #[builder(default = "false")]
     normalize_details: bool,
     #[builder(default = "false")]
     skip_invalid_cost_or_id: bool,
+    #[builder(default = "SurfaceNormalization::None")]
+    surface_normalization: SurfaceNormalization,
+    #[builder(default = "DuplicatePolicy::KeepAll")]
+    duplicate_policy: DuplicatePolicy,
+    /* Field indexed by reading in dict.readings. If set to `None`, or if the schema has no such
+    field, no reading index is written. */
+    #[builder(default = "Some(\"reading\".into())", setter(into))]
+    reading_index_field: Option<String>,
+    /* Write the okurigana variant table dict.okurigana for okurigana-tolerant lookups. */
+    #[builder(default = "false")]
+    okurigana_variants: bool,
     #[builder(default = "Schema::default()")]
     schema: Schema,
 }
@@ -49,34 +96,81 @@ impl PrefixDictionaryBuilder {
             flexible_csv: true,
             encoding: "UTF-8".into(),
             compress_algorithm: Algorithm::Deflate,
+            compression: CompressionSettings::default(),
             normalize_details: false,
             skip_invalid_cost_or_id: false,
+            surface_normalization: SurfaceNormalization::None,
+            duplicate_policy: DuplicatePolicy::KeepAll,
+            reading_index_field: Some("reading".into()),
+            okurigana_variants: false,
             schema,
         }
     }
 
     /// Main method for building the dictionary
     pub fn build(&self, input_dir: &Path, output_dir: &Path) -> LinderaResult<()> {
+        self.build_with_report(input_dir, output_dir).map(|_| ())
+    }
+
+    /// Builds the dictionary and reports how duplicate entries were resolved.
+    pub fn build_with_report(
+        &self,
+        input_dir: &Path,
+        output_dir: &Path,
+    ) -> LinderaResult<DuplicateReport> {
+        self.build_with_job(input_dir, output_dir, &BuildJob::new())
+    }
+
+    /// Builds the dictionary, reporting progress to the job and stopping if it is cancelled.
+    pub fn build_with_job(
+        &self,
+        input_dir: &Path,
+        output_dir: &Path,
+        job: &BuildJob,
+    ) -> LinderaResult<DuplicateReport> {
         // 1. Load CSV data
-        let rows = self.load_csv_data(input_dir)?;
+        let (rows, report) = self.load_csv_data(input_dir, job)?;
 
         // 2. Build word entry map
+        job.check(BuildPhase::IndexLexicon)?;
         let word_entry_map = self.build_word_entry_map(&rows)?;
+        job.update(BuildPhase::IndexLexicon, 1, 1);
 
         // 3. Write dictionary files
+        job.check(BuildPhase::WriteLexicon)?;
         self.write_dictionary_files(output_dir, &rows, &word_entry_map)?;
+        job.update(BuildPhase::WriteLexicon, 1, 1);
 
-        Ok(())
+        Ok(report)
     }
 
     /// Load data from CSV files
-    fn load_csv_data(&self, input_dir: &Path) -> LinderaResult<Vec<StringRecord>> {
+    fn load_csv_data(
+        &self,
+        input_dir: &Path,
+        job: &BuildJob,
+    ) -> LinderaResult<(Vec<StringRecord>, DuplicateReport)> {
         let filenames = self.collect_csv_files(input_dir)?;
-        let encoding = self.get_encoding()?;
-        let mut rows = self.read_csv_files(&filenames, encoding)?;
+        // With automatic detection, the encoding is decided for each file.
+        let encoding = if is_auto_encoding(&self.encoding) {
+            None
+        } else {
+            Some(self.get_encoding()?)
+        };
+        let rows = self.read_csv_files(&filenames, encoding, job)?;
+        let mut rows = normalize_surfaces(rows, self.surface_normalization);
 
         // Sort dictionary entries by the first column (word)
         // Change sorting method based on normalization settings
+        #[cfg(feature = "parallel")]
+        if self.normalize_details {
+            // Sort after normalizing characters (―→—, ～→〜)
+            rows.par_sort_by_cached_key(|row| normalize(&row[0]));
+        } else {
+            // Sort using original strings directly
+            rows.par_sort_by(|a, b| a[0].cmp(&b[0]))
+        }
+        #[cfg(not(feature = "parallel"))]
         if self.normalize_details {
             // Sort after normalizing characters (―→—, ～→〜)
             rows.sort_by_key(|row| normalize(&row[0]));
@@ -85,7 +179,76 @@ impl PrefixDictionaryBuilder {
             rows.sort_by(|a, b| a[0].cmp(&b[0]))
         }
 
-        Ok(rows)
+        // The sort is stable, so rows with the same surface keep the order of the files.
+        self.resolve_duplicates(rows)
+    }
+
+    /// Resolve duplicate rows according to the duplicate policy.
+    /// Rows are duplicates when every field except the cost is identical.
+    fn resolve_duplicates(
+        &self,
+        rows: Vec<StringRecord>,
+    ) -> LinderaResult<(Vec<StringRecord>, DuplicateReport)> {
+        let mut report = DuplicateReport {
+            policy: self.duplicate_policy,
+            ..Default::default()
+        };
+        let cost_index = self.schema.get_field_index("cost");
+        let cost = |row: &StringRecord| {
+            cost_index
+                .and_then(|index| row.get(index))
+                .and_then(|cost| i16::from_str(cost.trim()).ok())
+        };
+
+        // fields except the cost -> index of the kept row in `resolved`
+        let mut kept: HashMap<Vec<String>, usize> = HashMap::new();
+        let mut resolved: Vec<StringRecord> = Vec::with_capacity(rows.len());
+
+        for row in rows {
+            let key: Vec<String> = row
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| Some(*index) != cost_index)
+                .map(|(_, field)| field.to_string())
+                .collect();
+
+            let Some(&index) = kept.get(&key) else {
+                kept.insert(key, resolved.len());
+                resolved.push(row);
+                continue;
+            };
+
+            report.duplicates += 1;
+            match self.duplicate_policy {
+                DuplicatePolicy::KeepAll => resolved.push(row),
+                DuplicatePolicy::KeepFirst => report.removed += 1,
+                DuplicatePolicy::KeepLowestCost => {
+                    report.removed += 1;
+                    if let (Some(new_cost), Some(kept_cost)) = (cost(&row), cost(&resolved[index]))
+                        && new_cost < kept_cost
+                    {
+                        resolved[index] = row;
+                    }
+                }
+                DuplicatePolicy::Error => {
+                    return Err(LinderaErrorKind::Content
+                        .with_error(anyhow!(
+                            "duplicate entry: {}",
+                            row.iter().collect::<Vec<_>>().join(",")
+                        ))
+                        .add_context(format!(
+                            "Duplicate entries are not allowed by the duplicate policy (first entry: {})",
+                            resolved[index].iter().collect::<Vec<_>>().join(",")
+                        )));
+                }
+            }
+        }
+
+        if report.duplicates > 0 {
+            info!("{report}");
+        }
+
+        Ok((resolved, report))
     }
 
     /// Collect .csv file paths from input directory
@@ -140,43 +303,84 @@ impl PrefixDictionaryBuilder {
     }
 
     /// Read CSV files
+    ///
+    /// Files are parsed in parallel, and large files are further split into chunks
+    /// at record boundaries. The rows are returned in the order of the files.
     fn read_csv_files(
         &self,
         filenames: &[PathBuf],
-        encoding: &'static Encoding,
+        encoding: Option<&'static Encoding>,
+        job: &BuildJob,
     ) -> LinderaResult<Vec<StringRecord>> {
+        let total = filenames.len();
+        let completed = AtomicUsize::new(0);
+        job.update(BuildPhase::ReadLexicon, 0, total);
+        let files = par_map(filenames, |filename| {
+            job.check(BuildPhase::ReadLexicon)?;
+            let rows = self.read_csv_file(filename, encoding)?;
+            let completed = completed.fetch_add(1, Ordering::SeqCst) + 1;
+            job.update(BuildPhase::ReadLexicon, completed, total);
+            Ok(rows)
+        });
+
         let mut rows: Vec<StringRecord> = vec![];
+        for file_rows in files {
+            rows.extend(file_rows?);
+        }
 
-        for filename in filenames {
-            debug!("reading {filename:?}");
+        Ok(rows)
+    }
 
-            let file = File::open(filename).map_err(|err| {
-                LinderaErrorKind::Io
-                    .with_error(anyhow::anyhow!(err))
-                    .add_context(format!("Failed to open CSV file: {filename:?}"))
-            })?;
-            let reader: Box<dyn Read> = if encoding == UTF_8 {
-                Box::new(file)
-            } else {
-                Box::new(
-                    DecodeReaderBytesBuilder::new()
-                        .encoding(Some(encoding))
-                        .build(file),
-                )
-            };
-            let mut rdr = csv::ReaderBuilder::new()
-                .has_headers(false)
-                .flexible(self.flexible_csv)
-                .from_reader(reader);
-
-            for result in rdr.records() {
-                let record = result.map_err(|err| {
-                    LinderaErrorKind::Content
-                        .with_error(anyhow!(err))
-                        .add_context(format!("Failed to parse CSV record in file: {filename:?}"))
+    /// Read a CSV file
+    fn read_csv_file(
+        &self,
+        filename: &Path,
+        encoding: Option<&'static Encoding>,
+    ) -> LinderaResult<Vec<StringRecord>> {
+        debug!("reading {filename:?}");
+
+        let buffer = read_file(filename)?;
+        let encoding = match encoding {
+            Some(encoding) => encoding,
+            None => {
+                let encoding = resolve_encoding(&self.encoding, &buffer).map_err(|err| {
+                    err.add_context(format!("Failed to decode CSV file: {filename:?}"))
                 })?;
-                rows.push(record);
+                debug!("decoding {filename:?} as {}", encoding.name());
+                encoding
             }
+        };
+        // UTF-8 data is left to the CSV reader, which rejects invalid sequences.
+        let data: Cow<[u8]> = if encoding == UTF_8 {
+            Cow::Borrowed(&buffer)
+        } else {
+            Cow::Owned(encoding.decode(&buffer).0.into_owned().into_bytes())
+        };
+
+        let chunks = split_records(&data, num_threads());
+        let mut rows: Vec<StringRecord> = vec![];
+        for chunk_rows in par_map(&chunks, |chunk| self.parse_csv(chunk, filename)) {
+            rows.extend(chunk_rows?);
+        }
+
+        Ok(rows)
+    }
+
+    /// Parse CSV records
+    fn parse_csv(&self, data: &[u8], filename: &Path) -> LinderaResult<Vec<StringRecord>> {
+        let mut rdr = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(self.flexible_csv)
+            .from_reader(data);
+
+        let mut rows: Vec<StringRecord> = vec![];
+        for result in rdr.records() {
+            let record = result.map_err(|err| {
+                LinderaErrorKind::Content
+                    .with_error(anyhow!(err))
+                    .add_context(format!("Failed to parse CSV record in file: {filename:?}"))
+            })?;
+            rows.push(record);
         }
 
         Ok(rows)
@@ -311,68 +515,140 @@ impl PrefixDictionaryBuilder {
         rows: &[StringRecord],
         word_entry_map: &BTreeMap<String, Vec<WordEntry>>,
     ) -> LinderaResult<()> {
-        // Write dict.words and dict.wordsidx
-        self.write_words_files(output_dir, rows)?;
-
-        // Write dict.da
-        self.write_double_array_file(output_dir, word_entry_map)?;
-
-        // Write dict.vals
-        self.write_values_file(output_dir, word_entry_map)?;
+        let ((words, (readings, okurigana)), (da, vals)) = join(
+            || {
+                join(
+                    // Write dict.words and dict.wordsidx
+                    || self.write_words_files(output_dir, rows),
+                    || {
+                        join(
+                            // Write dict.readings
+                            || self.write_reading_index_file(output_dir, rows),
+                            // Write dict.okurigana
+                            || self.write_okurigana_file(output_dir, rows, word_entry_map),
+                        )
+                    },
+                )
+            },
+            || {
+                join(
+                    // Write dict.da
+                    || self.write_double_array_file(output_dir, word_entry_map),
+                    // Write dict.vals
+                    || self.write_values_file(output_dir, word_entry_map),
+                )
+            },
+        );
+        words?;
+        readings?;
+        okurigana?;
+        da?;
+        vals?;
 
         Ok(())
     }
 
-    /// Write word detail files (dict.words, dict.wordsidx)
-    fn write_words_files(&self, output_dir: &Path, rows: &[StringRecord]) -> LinderaResult<()> {
+    /// Build the word details (dict.words) and the offset of each word in them (dict.wordsidx)
+    ///
+    /// Many words share the same details, so each distinct record is written to dict.words
+    /// only once and the offsets of all the words with those details point to it. The leading
+    /// part-of-speech and conjugation details are written once as well, and the records of the
+    /// words point to them (see [`SHARED_DETAILS_FLAG`]).
+    fn build_words(&self, rows: &[StringRecord]) -> LinderaResult<(Vec<u8>, Vec<u8>)> {
+        let shared_fields = self.shared_detail_fields();
         let mut dict_words_buffer = Vec::new();
         let mut dict_wordsidx_buffer = Vec::new();
+        // details -> offset of the record in dict.words
+        let mut offsets: HashMap<String, u32> = HashMap::new();
+        let mut shared_records = 0;
 
         for row in rows.iter() {
-            let offset = dict_words_buffer.len();
+            // Create word details from the row data (5th column and beyond)
+            let details: Vec<Cow<str>> = row
+                .iter()
+                .skip(4)
+                .map(|detail| {
+                    if self.normalize_details {
+                        Cow::Owned(normalize(detail))
+                    } else {
+                        Cow::Borrowed(detail)
+                    }
+                })
+                .collect();
+            let joined_details = details.join("\0");
+
+            let offset = match offsets.get(&joined_details) {
+                Some(&offset) => offset,
+                None => {
+                    let shared_details = (details.len() > shared_fields)
+                        .then(|| details[..shared_fields].join("\0"))
+                        // A reference to the shared details takes 4 bytes
+                        .filter(|shared_details| shared_details.len() >= 4);
+                    let offset = match shared_details {
+                        Some(shared_details) => {
+                            let shared_offset = match offsets.get(&shared_details) {
+                                Some(&shared_offset) => shared_offset,
+                                None => {
+                                    let shared_offset = write_details_record(
+                                        &mut dict_words_buffer,
+                                        &shared_details,
+                                        None,
+                                    )?;
+                                    offsets.insert(shared_details, shared_offset);
+                                    shared_offset
+                                }
+                            };
+                            shared_records += 1;
+                            write_details_record(
+                                &mut dict_words_buffer,
+                                &details[shared_fields..].join("\0"),
+                                Some(shared_offset),
+                            )?
+                        }
+                        None => {
+                            write_details_record(&mut dict_words_buffer, &joined_details, None)?
+                        }
+                    };
+                    offsets.insert(joined_details, offset);
+                    offset
+                }
+            };
+
             dict_wordsidx_buffer
-                .write_u32::<LittleEndian>(offset as u32)
+                .write_u32::<LittleEndian>(offset)
                 .map_err(|err| {
                     LinderaErrorKind::Io
                         .with_error(anyhow::anyhow!(err))
                         .add_context("Failed to write word index offset to dict.wordsidx buffer")
                 })?;
+        }
+        debug!(
+            "{} records of word details for {} words, {} of them with shared details",
+            offsets.len(),
+            rows.len(),
+            shared_records
+        );
 
-            // Create word details from the row data (5th column and beyond)
-            let joined_details = if self.normalize_details {
-                row.iter()
-                    .skip(4)
-                    .map(normalize)
-                    .collect::<Vec<String>>()
-                    .join("\0")
-            } else {
-                row.iter().skip(4).collect::<Vec<&str>>().join("\0")
-            };
-            let joined_details_len = u32::try_from(joined_details.len()).map_err(|err| {
-                LinderaErrorKind::Serialize
-                    .with_error(anyhow::anyhow!(err))
-                    .add_context(format!(
-                        "Word details length too large: {} bytes",
-                        joined_details.len()
-                    ))
-            })?;
+        Ok((dict_words_buffer, dict_wordsidx_buffer))
+    }
 
-            // Write to dict.words buffer
-            dict_words_buffer
-                .write_u32::<LittleEndian>(joined_details_len)
-                .map_err(|err| {
-                    LinderaErrorKind::Serialize
-                        .with_error(anyhow::anyhow!(err))
-                        .add_context("Failed to write word details length to dict.words buffer")
-                })?;
-            dict_words_buffer
-                .write_all(joined_details.as_bytes())
-                .map_err(|err| {
-                    LinderaErrorKind::Serialize
-                        .with_error(anyhow::anyhow!(err))
-                        .add_context("Failed to write word details to dict.words buffer")
-                })?;
-        }
+    /// The number of leading details written once for all the words that share them: the
+    /// part-of-speech and conjugation fields at the start of the details of the schema.
+    fn shared_detail_fields(&self) -> usize {
+        self.schema
+            .get_custom_fields()
+            .iter()
+            .take_while(|name| {
+                ["part_of_speech", "major_pos", "pos_detail", "conjugation"]
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix))
+            })
+            .count()
+    }
+
+    /// Write word detail files (dict.words, dict.wordsidx)
+    fn write_words_files(&self, output_dir: &Path, rows: &[StringRecord]) -> LinderaResult<()> {
+        let (dict_words_buffer, dict_wordsidx_buffer) = self.build_words(rows)?;
 
         // Write dict.words file
         let dict_words_path = output_dir.join(Path::new("dict.words"));
@@ -388,6 +664,7 @@ impl PrefixDictionaryBuilder {
         compress_write(
             &dict_words_buffer,
             self.compress_algorithm,
+            &self.compression,
             &mut dict_words_writer,
         )?;
 
@@ -413,6 +690,7 @@ impl PrefixDictionaryBuilder {
         compress_write(
             &dict_wordsidx_buffer,
             self.compress_algorithm,
+            &self.compression,
             &mut dict_wordsidx_writer,
         )?;
 
@@ -427,14 +705,232 @@ impl PrefixDictionaryBuilder {
         Ok(())
     }
 
-    /// Write double array file (dict.da)
-    fn write_double_array_file(
+    /// Build the reading index of the rows
+    ///
+    /// Returns `None` if the reading index is disabled or the schema has no reading field.
+    fn build_reading_index(&self, rows: &[StringRecord]) -> LinderaResult<Option<ReadingIndex>> {
+        let Some(reading_field) = self.reading_index_field.as_deref() else {
+            return Ok(None);
+        };
+        if self.schema.get_field_index(reading_field).is_none() {
+            debug!("skipping the reading index: no {reading_field} field in the schema");
+            return Ok(None);
+        }
+
+        let mut words = Vec::with_capacity(rows.len());
+        for (row_id, row) in rows.iter().enumerate() {
+            // Only the words of the dictionary are indexed
+            let (Some(word_cost), Some(_), Some(_)) = (
+                self.parse_word_cost(row)?,
+                self.parse_left_id(row)?,
+                self.parse_right_id(row)?,
+            ) else {
+                continue;
+            };
+            let (Some(surface), Some(reading)) = (
+                self.get_field_value(row, "surface")?,
+                self.get_field_value(row, reading_field)?,
+            ) else {
+                continue;
+            };
+            words.push((surface, reading, row_id as u32, word_cost));
+        }
+
+        Ok(Some(ReadingIndex::build(words.iter().map(
+            |(surface, reading, word_id, word_cost)| {
+                (surface.as_str(), reading.as_str(), *word_id, *word_cost)
+            },
+        ))))
+    }
+
+    /// Write reading index file (dict.readings)
+    fn write_reading_index_file(
+        &self,
+        output_dir: &Path,
+        rows: &[StringRecord],
+    ) -> LinderaResult<()> {
+        let Some(reading_index) = self.build_reading_index(rows)? else {
+            return Ok(());
+        };
+
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&reading_index).map_err(|err| {
+            LinderaErrorKind::Serialize
+                .with_error(anyhow::anyhow!(err))
+                .add_context("Failed to serialize reading index")
+        })?;
+
+        let dict_readings_path = output_dir.join(Path::new("dict.readings"));
+        let mut dict_readings_writer =
+            io::BufWriter::new(File::create(&dict_readings_path).map_err(|err| {
+                LinderaErrorKind::Io
+                    .with_error(anyhow::anyhow!(err))
+                    .add_context(format!(
+                        "Failed to create dict.readings file: {dict_readings_path:?}"
+                    ))
+            })?);
+
+        compress_write(
+            &bytes,
+            self.compress_algorithm,
+            &self.compression,
+            &mut dict_readings_writer,
+        )?;
+
+        dict_readings_writer.flush().map_err(|err| {
+            LinderaErrorKind::Io
+                .with_error(anyhow::anyhow!(err))
+                .add_context(format!(
+                    "Failed to flush dict.readings file: {dict_readings_path:?}"
+                ))
+        })?;
+
+        Ok(())
+    }
+
+    /// Build the okurigana variant table of the surfaces
+    ///
+    /// Returns `None` if the table is disabled. A word conjugates if its conjugation type or
+    /// conjugation form is set.
+    fn build_okurigana_variants(
+        &self,
+        rows: &[StringRecord],
+        word_entry_map: &BTreeMap<String, Vec<WordEntry>>,
+    ) -> LinderaResult<Option<OkuriganaVariants>> {
+        if !self.okurigana_variants {
+            return Ok(None);
+        }
+
+        self.okurigana_variant_table(word_entry_map, |word_entry| {
+            let Some(row) = rows.get(word_entry.word_id.id as usize) else {
+                return Ok(None);
+            };
+            let mut conjugates = false;
+            for field_name in ["conjugation_type", "conjugation_form"] {
+                if let Some(value) = self.get_field_value(row, field_name)? {
+                    conjugates |= value != "*";
+                }
+            }
+            Ok(Some(conjugates))
+        })
+        .map(Some)
+    }
+
+    /// Build the okurigana variant table of the words of a dictionary already built
+    ///
+    /// The table is the one written to `dict.okurigana` when the dictionary is built with
+    /// `okurigana_variants` enabled, whatever its metadata says.
+    pub fn build_okurigana_variants_of(
+        &self,
+        dictionary: &Dictionary,
+    ) -> LinderaResult<OkuriganaVariants> {
+        let mut word_entry_map: BTreeMap<String, Vec<WordEntry>> = BTreeMap::new();
+        for (surface, word_entry) in dictionary.words() {
+            word_entry_map.entry(surface).or_default().push(word_entry);
+        }
+
+        // The details of a word are its fields after the surface, the context ids and the cost
+        let indices: Vec<usize> = ["conjugation_type", "conjugation_form"]
+            .iter()
+            .filter_map(|field_name| self.schema.get_field_index(field_name)?.checked_sub(4))
+            .collect();
+        self.okurigana_variant_table(&word_entry_map, |word_entry| {
+            let word_id = word_entry.word_id.id as usize;
+            Ok(Some(indices.iter().any(|&index| {
+                dictionary
+                    .word_detail(word_id, index)
+                    .map(str::trim)
+                    .is_some_and(|value| !value.is_empty() && value != "*")
+            })))
+        })
+    }
+
+    /// Build the table of the okurigana variants of the surfaces, with `conjugates` telling
+    /// whether a word conjugates, or `None` to leave it out.
+    fn okurigana_variant_table(
+        &self,
+        word_entry_map: &BTreeMap<String, Vec<WordEntry>>,
+        mut conjugates: impl FnMut(&WordEntry) -> LinderaResult<Option<bool>>,
+    ) -> LinderaResult<OkuriganaVariants> {
+        let mut variant_map: BTreeMap<String, Vec<WordEntry>> = BTreeMap::new();
+        for (surface, word_entries) in word_entry_map {
+            for word_entry in word_entries {
+                let Some(conjugates) = conjugates(word_entry)? else {
+                    continue;
+                };
+
+                for variant in okurigana_variants(surface, conjugates) {
+                    // The words of the dictionary are looked up as they are
+                    if !word_entry_map.contains_key(&variant) {
+                        variant_map.entry(variant).or_default().push(*word_entry);
+                    }
+                }
+            }
+        }
+        debug!("{} okurigana variants", variant_map.len());
+
+        Ok(OkuriganaVariants {
+            dict: PrefixDictionary::load(
+                self.build_double_array(&variant_map)?,
+                self.build_values(&variant_map)?,
+                Vec::new(),
+                Vec::new(),
+                true,
+            )?,
+        })
+    }
+
+    /// Write okurigana variant table file (dict.okurigana)
+    fn write_okurigana_file(
         &self,
         output_dir: &Path,
+        rows: &[StringRecord],
         word_entry_map: &BTreeMap<String, Vec<WordEntry>>,
     ) -> LinderaResult<()> {
+        let Some(okurigana_variants) = self.build_okurigana_variants(rows, word_entry_map)? else {
+            return Ok(());
+        };
+
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&okurigana_variants).map_err(|err| {
+            LinderaErrorKind::Serialize
+                .with_error(anyhow::anyhow!(err))
+                .add_context("Failed to serialize okurigana variant table")
+        })?;
+
+        let dict_okurigana_path = output_dir.join(Path::new("dict.okurigana"));
+        let mut dict_okurigana_writer =
+            io::BufWriter::new(File::create(&dict_okurigana_path).map_err(|err| {
+                LinderaErrorKind::Io
+                    .with_error(anyhow::anyhow!(err))
+                    .add_context(format!(
+                        "Failed to create dict.okurigana file: {dict_okurigana_path:?}"
+                    ))
+            })?);
+
+        compress_write(
+            &bytes,
+            self.compress_algorithm,
+            &self.compression,
+            &mut dict_okurigana_writer,
+        )?;
+
+        dict_okurigana_writer.flush().map_err(|err| {
+            LinderaErrorKind::Io
+                .with_error(anyhow::anyhow!(err))
+                .add_context(format!(
+                    "Failed to flush dict.okurigana file: {dict_okurigana_path:?}"
+                ))
+        })?;
+
+        Ok(())
+    }
+
+    /// Build the double array of the surfaces
+    fn build_double_array(
+        &self,
+        word_entry_map: &BTreeMap<String, Vec<WordEntry>>,
+    ) -> LinderaResult<Vec<u8>> {
         let mut id = 0u32;
-        let mut keyset: Vec<(&[u8], u32)> = vec![];
+        let mut keyset: Vec<(&[u8], u32)> = Vec::with_capacity(word_entry_map.len());
 
         for (key, word_entries) in word_entry_map {
             let len = word_entries.len() as u32;
@@ -456,7 +952,23 @@ impl PrefixDictionaryBuilder {
                     ))
             })?;
 
+        // The keys borrow from the map, so only the built automaton is left to serialize.
         let dict_da_buffer = dict_da.serialize();
+        debug!(
+            "double array of {keyset_len} keys: {} bytes",
+            dict_da_buffer.len()
+        );
+
+        Ok(dict_da_buffer)
+    }
+
+    /// Write double array file (dict.da)
+    fn write_double_array_file(
+        &self,
+        output_dir: &Path,
+        word_entry_map: &BTreeMap<String, Vec<WordEntry>>,
+    ) -> LinderaResult<()> {
+        let dict_da_buffer = self.build_double_array(word_entry_map)?;
 
         let dict_da_path = output_dir.join(Path::new("dict.da"));
         let mut dict_da_writer =
@@ -469,18 +981,18 @@ impl PrefixDictionaryBuilder {
         compress_write(
             &dict_da_buffer,
             self.compress_algorithm,
+            &self.compression,
             &mut dict_da_writer,
         )?;
 
         Ok(())
     }
 
-    /// Write values file (dict.vals)
-    fn write_values_file(
+    /// Build the values of the double array, the word entries of each surface
+    fn build_values(
         &self,
-        output_dir: &Path,
         word_entry_map: &BTreeMap<String, Vec<WordEntry>>,
-    ) -> LinderaResult<()> {
+    ) -> LinderaResult<Vec<u8>> {
         let mut dict_vals_buffer = Vec::new();
         for word_entries in word_entry_map.values() {
             for word_entry in word_entries {
@@ -495,6 +1007,17 @@ impl PrefixDictionaryBuilder {
             }
         }
 
+        Ok(dict_vals_buffer)
+    }
+
+    /// Write values file (dict.vals)
+    fn write_values_file(
+        &self,
+        output_dir: &Path,
+        word_entry_map: &BTreeMap<String, Vec<WordEntry>>,
+    ) -> LinderaResult<()> {
+        let dict_vals_buffer = self.build_values(word_entry_map)?;
+
         let dict_vals_path = output_dir.join(Path::new("dict.vals"));
         let mut dict_vals_writer =
             io::BufWriter::new(File::create(&dict_vals_path).map_err(|err| {
@@ -508,6 +1031,7 @@ impl PrefixDictionaryBuilder {
         compress_write(
             &dict_vals_buffer,
             self.compress_algorithm,
+            &self.compression,
             &mut dict_vals_writer,
         )?;
 
@@ -527,6 +1051,94 @@ fn normalize(text: &str) -> String {
     text.to_string().replace('―', "—").replace('～', "〜")
 }
 
+/// Appends a record of `dict.words` holding `details`, after the offset of the record of the
+/// details it shares with other words if any, and returns the offset of the record.
+fn write_details_record(
+    buffer: &mut Vec<u8>,
+    details: &str,
+    shared_offset: Option<u32>,
+) -> LinderaResult<u32> {
+    let offset = u32::try_from(buffer.len()).map_err(|err| {
+        LinderaErrorKind::Serialize
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Word details too large: {} bytes", buffer.len()))
+    })?;
+    let len = u32::try_from(details.len())
+        .ok()
+        .filter(|len| len & SHARED_DETAILS_FLAG == 0)
+        .ok_or_else(|| {
+            LinderaErrorKind::Serialize
+                .with_error(anyhow::anyhow!("{} bytes", details.len()))
+                .add_context("Word details length too large")
+        })?;
+
+    let write = |buffer: &mut Vec<u8>| -> io::Result<()> {
+        match shared_offset {
+            Some(shared_offset) => {
+                buffer.write_u32::<LittleEndian>(len | SHARED_DETAILS_FLAG)?;
+                buffer.write_u32::<LittleEndian>(shared_offset)?;
+            }
+            None => buffer.write_u32::<LittleEndian>(len)?,
+        }
+        buffer.write_all(details.as_bytes())
+    };
+    write(buffer).map_err(|err| {
+        LinderaErrorKind::Serialize
+            .with_error(anyhow::anyhow!(err))
+            .add_context("Failed to write word details to dict.words buffer")
+    })?;
+
+    Ok(offset)
+}
+
+/// Splits CSV data into about `n` chunks of similar size.
+/// Chunks end at a newline outside a quoted field, so no record spans two chunks.
+fn split_records(data: &[u8], n: usize) -> Vec<&[u8]> {
+    let target = data.len() / n.max(1) + 1;
+    let mut chunks = Vec::with_capacity(n);
+    let mut start = 0;
+    let mut field_start = true;
+    let mut in_quotes = false;
+
+    let mut i = 0;
+    while i < data.len() {
+        let b = data[i];
+        if in_quotes {
+            if b == b'"' {
+                if data.get(i + 1) == Some(&b'"') {
+                    // Escaped quote
+                    i += 1;
+                } else {
+                    in_quotes = false;
+                }
+            }
+            field_start = false;
+        } else {
+            match b {
+                b'"' if field_start => {
+                    in_quotes = true;
+                    field_start = false;
+                }
+                b',' => field_start = true,
+                b'\n' => {
+                    if i + 1 - start >= target {
+                        chunks.push(&data[start..=i]);
+                        start = i + 1;
+                    }
+                    field_start = true;
+                }
+                _ => field_start = false,
+            }
+        }
+        i += 1;
+    }
+    if start < data.len() {
+        chunks.push(&data[start..]);
+    }
+
+    chunks
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -683,6 +1295,203 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_read_csv_files_auto_encoding() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut filenames = Vec::new();
+        for (name, encoding) in [
+            ("euc.csv", encoding_rs::EUC_JP),
+            ("sjis.csv", encoding_rs::SHIFT_JIS),
+        ] {
+            let path = dir.path().join(name);
+            let (bytes, _, _) = encoding.encode(
+                "東京,1285,1285,3003,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー\n",
+            );
+            std::fs::write(&path, bytes).unwrap();
+            filenames.push(path);
+        }
+
+        let mut builder = PrefixDictionaryBuilder::new(Schema::default());
+        builder.encoding = "auto".into();
+
+        let rows = builder
+            .read_csv_files(&filenames, None, &BuildJob::new())
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        for row in rows {
+            assert_eq!(&row[0], "東京");
+            assert_eq!(&row[11], "トウキョウ");
+        }
+    }
+
+    #[test]
+    fn test_split_records() {
+        let data = b"a,1\nb,2\n\"c\nd\",3\ne,\"\"\"\n\",4\nf,5";
+        for n in 1..=8 {
+            let chunks = split_records(data, n);
+            assert_eq!(chunks.concat(), data.to_vec());
+            // Every chunk ends at a record boundary
+            let records: usize = chunks
+                .iter()
+                .map(|chunk| {
+                    csv::ReaderBuilder::new()
+                        .has_headers(false)
+                        .from_reader(*chunk)
+                        .records()
+                        .count()
+                })
+                .sum();
+            assert_eq!(records, 5, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_read_csv_files_preserves_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lex.csv");
+        let data: String = (0..1000)
+            .map(|i| format!("word{i},1,1,100,名詞\n"))
+            .collect();
+        std::fs::write(&path, data).unwrap();
+
+        let builder = PrefixDictionaryBuilder::new(Schema::default());
+        let rows = builder
+            .read_csv_files(&[path], Some(UTF_8), &BuildJob::new())
+            .unwrap();
+        assert_eq!(rows.len(), 1000);
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(&row[0], format!("word{i}"));
+        }
+    }
+
+    fn duplicate_rows() -> Vec<StringRecord> {
+        vec![
+            StringRecord::from(vec!["東京", "1285", "1285", "3003", "名詞", "トウキョウ"]),
+            StringRecord::from(vec!["東京", "1285", "1285", "2000", "名詞", "トウキョウ"]),
+            StringRecord::from(vec!["東京", "1285", "1285", "2500", "名詞", "トーキョー"]),
+        ]
+    }
+
+    fn resolve(policy: DuplicatePolicy) -> LinderaResult<(Vec<StringRecord>, DuplicateReport)> {
+        let mut builder = PrefixDictionaryBuilder::new(Schema::default());
+        builder.duplicate_policy = policy;
+        builder.resolve_duplicates(duplicate_rows())
+    }
+
+    #[test]
+    fn test_resolve_duplicates_keep_all() {
+        let (rows, report) = resolve(DuplicatePolicy::KeepAll).unwrap();
+        assert_eq!(rows, duplicate_rows());
+        assert_eq!((report.duplicates, report.removed), (1, 0));
+    }
+
+    #[test]
+    fn test_resolve_duplicates_keep_first() {
+        let (rows, report) = resolve(DuplicatePolicy::KeepFirst).unwrap();
+        let costs: Vec<&str> = rows.iter().map(|row| &row[3]).collect();
+        assert_eq!(costs, vec!["3003", "2500"]);
+        assert_eq!((report.duplicates, report.removed), (1, 1));
+    }
+
+    #[test]
+    fn test_resolve_duplicates_keep_lowest_cost() {
+        let (rows, report) = resolve(DuplicatePolicy::KeepLowestCost).unwrap();
+        let costs: Vec<&str> = rows.iter().map(|row| &row[3]).collect();
+        assert_eq!(costs, vec!["2000", "2500"]);
+        assert_eq!((report.duplicates, report.removed), (1, 1));
+    }
+
+    #[test]
+    fn test_resolve_duplicates_error() {
+        assert!(resolve(DuplicatePolicy::Error).is_err());
+    }
+
+    #[test]
+    fn test_build_words_deduplicates_details() {
+        let builder = PrefixDictionaryBuilder::new(Schema::default());
+        let rows = vec![
+            StringRecord::from(vec!["東京", "1", "1", "100", "名詞", "固有名詞"]),
+            StringRecord::from(vec!["大阪", "1", "1", "200", "名詞", "固有名詞"]),
+            StringRecord::from(vec!["行く", "2", "2", "300", "動詞", "自立"]),
+        ];
+
+        let (words, wordsidx) = builder.build_words(&rows).unwrap();
+        let offsets: Vec<u32> = wordsidx
+            .chunks(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        assert_eq!(offsets[0], offsets[1]);
+        assert_ne!(offsets[0], offsets[2]);
+
+        let details = |offset: u32| {
+            let offset = offset as usize;
+            let len = u32::from_le_bytes(words[offset..offset + 4].try_into().unwrap()) as usize;
+            std::str::from_utf8(&words[offset + 4..offset + 4 + len]).unwrap()
+        };
+        assert_eq!(details(offsets[1]), "名詞\0固有名詞");
+        assert_eq!(details(offsets[2]), "動詞\0自立");
+        // Only the two distinct records are stored
+        assert_eq!(
+            words.len(),
+            2 * 4 + "名詞\0固有名詞".len() + "動詞\0自立".len()
+        );
+    }
+
+    #[test]
+    fn test_build_words_shares_leading_details() {
+        let builder = PrefixDictionaryBuilder::new(Schema::default());
+        let row = |surface: &str, reading: &str| {
+            StringRecord::from(vec![
+                surface,
+                "1",
+                "1",
+                "100",
+                "動詞",
+                "自立",
+                "*",
+                "*",
+                "五段・カ行イ音便",
+                "基本形",
+                surface,
+                reading,
+                reading,
+            ])
+        };
+        let rows = vec![
+            row("行く", "イク"),
+            row("書く", "カク"),
+            row("行く", "イク"),
+        ];
+
+        let (words, wordsidx) = builder.build_words(&rows).unwrap();
+        let offsets: Vec<usize> = wordsidx
+            .chunks(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()) as usize)
+            .collect();
+        let read_u32 =
+            |offset: usize| u32::from_le_bytes(words[offset..offset + 4].try_into().unwrap());
+
+        // The part-of-speech and conjugation details are written first, once
+        let shared = "動詞\0自立\0*\0*\0五段・カ行イ音便\0基本形";
+        assert_eq!(shared.len() as u32, read_u32(0));
+        assert_eq!(shared.as_bytes(), &words[4..4 + shared.len()]);
+
+        // and the records of the words point to them
+        for (offset, own) in [
+            (offsets[0], "行く\0イク\0イク"),
+            (offsets[1], "書く\0カク\0カク"),
+        ] {
+            assert_eq!(own.len() as u32 | SHARED_DETAILS_FLAG, read_u32(offset));
+            assert_eq!(0, read_u32(offset + 4));
+            assert_eq!(own.as_bytes(), &words[offset + 8..offset + 8 + own.len()]);
+        }
+        assert_eq!(offsets[0], offsets[2]);
+        assert_eq!(
+            words.len(),
+            4 + shared.len() + 2 * 8 + "行く\0イク\0イク".len() + "書く\0カク\0カク".len()
+        );
+    }
+
     #[test]
     fn test_get_common_field_value() {
         let schema = Schema::default();
@@ -0,0 +1,467 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::LinderaResult;
+use crate::error::LinderaErrorKind;
+
+/// A step of a dictionary build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuildPhase {
+    Metadata,
+    CharacterDefinition,
+    UnknownDictionary,
+    ReadLexicon,
+    IndexLexicon,
+    WriteLexicon,
+    ConnectionCostMatrix,
+    ReadUserDictionary,
+    IndexUserDictionary,
+    WriteUserDictionary,
+}
+
+impl BuildPhase {
+    /// Phases of a system dictionary build.
+    pub const SYSTEM_DICTIONARY: &'static [BuildPhase] = &[
+        BuildPhase::Metadata,
+        BuildPhase::CharacterDefinition,
+        BuildPhase::UnknownDictionary,
+        BuildPhase::ReadLexicon,
+        BuildPhase::IndexLexicon,
+        BuildPhase::WriteLexicon,
+        BuildPhase::ConnectionCostMatrix,
+    ];
+
+    /// Phases of a user dictionary build.
+    pub const USER_DICTIONARY: &'static [BuildPhase] = &[
+        BuildPhase::ReadUserDictionary,
+        BuildPhase::IndexUserDictionary,
+        BuildPhase::WriteUserDictionary,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BuildPhase::Metadata => "metadata",
+            BuildPhase::CharacterDefinition => "character_definition",
+            BuildPhase::UnknownDictionary => "unknown_dictionary",
+            BuildPhase::ReadLexicon => "read_lexicon",
+            BuildPhase::IndexLexicon => "index_lexicon",
+            BuildPhase::WriteLexicon => "write_lexicon",
+            BuildPhase::ConnectionCostMatrix => "connection_cost_matrix",
+            BuildPhase::ReadUserDictionary => "read_user_dictionary",
+            BuildPhase::IndexUserDictionary => "index_user_dictionary",
+            BuildPhase::WriteUserDictionary => "write_user_dictionary",
+        }
+    }
+
+    /// Rough share of the build time spent in the phase, used to compute the overall percentage.
+    fn weight(&self) -> f64 {
+        match self {
+            BuildPhase::Metadata
+            | BuildPhase::CharacterDefinition
+            | BuildPhase::UnknownDictionary => 1.0,
+            BuildPhase::ReadLexicon => 40.0,
+            BuildPhase::IndexLexicon => 20.0,
+            BuildPhase::WriteLexicon => 30.0,
+            BuildPhase::ConnectionCostMatrix => 7.0,
+            BuildPhase::ReadUserDictionary => 40.0,
+            BuildPhase::IndexUserDictionary => 40.0,
+            BuildPhase::WriteUserDictionary => 20.0,
+        }
+    }
+}
+
+impl fmt::Display for BuildPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A progress notification passed to the callback of a [`BuildJob`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildProgress {
+    /// The phase that made progress.
+    pub phase: BuildPhase,
+    /// Work units of the phase that are done.
+    pub completed: usize,
+    /// Work units of the phase in total.
+    pub total: usize,
+    /// Progress of the whole build, from 0 to 100.
+    pub percent: f64,
+    /// Time since the build started.
+    pub elapsed: Duration,
+    /// Estimated time until the build finishes, once any progress has been made.
+    pub eta: Option<Duration>,
+}
+
+/// Requests a running build to stop.
+///
+/// Clones share the same state, so the token can be handed to another thread
+/// and cancelled from there.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+type ProgressCallback = Arc<dyn Fn(&BuildProgress) + Send + Sync>;
+
+/// Peak memory use of a build, as seen by the operating system.
+///
+/// The figures are the high-water mark of the resident set size of the whole process,
+/// so they include anything else the process allocated. They are only available on Linux.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// Peak resident set size in bytes at the end of the build.
+    pub peak_bytes: Option<u64>,
+    /// Peak resident set size in bytes when the build began.
+    pub start_bytes: Option<u64>,
+    /// Peak resident set size in bytes of the whole process so far when each phase finished, in
+    /// the order they finished. The values are cumulative and never go down; see
+    /// [`phase_increases`](Self::phase_increases) for the growth during each phase.
+    pub peak_after_phase: Vec<(BuildPhase, u64)>,
+}
+
+impl MemoryReport {
+    /// Returns how much the peak resident set size grew until each phase finished, since the
+    /// previous phase finished or, for the first one, since the build began.
+    ///
+    /// The phases building the lexicon and the connection cost matrix run in parallel, so the
+    /// growth is attributed to them in the order they finished.
+    pub fn phase_increases(&self) -> Vec<(BuildPhase, u64)> {
+        let mut previous = self.start_bytes.unwrap_or(0);
+        self.peak_after_phase
+            .iter()
+            .map(|(phase, bytes)| {
+                let increase = bytes.saturating_sub(previous);
+                previous = previous.max(*bytes);
+                (*phase, increase)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(peak_bytes) = self.peak_bytes else {
+            return f.write_str("peak memory: unavailable");
+        };
+        write!(f, "peak memory: {:.1} MiB", mib(peak_bytes))?;
+        for ((phase, bytes), (_, increase)) in
+            self.peak_after_phase.iter().zip(self.phase_increases())
+        {
+            write!(
+                f,
+                "\n  after {phase}: {:.1} MiB (+{:.1} MiB)",
+                mib(*bytes),
+                mib(increase)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn mib(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+/// Returns the high-water mark of the resident set size of the process in bytes, or `None` on
+/// platforms other than Linux.
+#[cfg(target_os = "linux")]
+pub fn peak_resident_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kib: u64 = line
+        .trim_start_matches("VmHWM:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn peak_resident_memory() -> Option<u64> {
+    None
+}
+
+#[derive(Debug)]
+struct JobState {
+    started: Instant,
+    /// Completed fraction of each phase, in the order the phases are known.
+    phases: Vec<(BuildPhase, f64)>,
+    /// Peak memory use when the build began.
+    memory_start: Option<u64>,
+    /// Peak memory use when each phase finished.
+    memory: Vec<(BuildPhase, u64)>,
+}
+
+/// Tracks a long-running dictionary build.
+///
+/// The builder reports its progress to the callback and checks the cancellation token
+/// between steps. A cancelled build stops with a [`LinderaErrorKind::Cancelled`] error.
+/// User dictionaries are written only at the end, so a cancelled user dictionary build
+/// leaves no output file; a cancelled system dictionary build may leave some of the
+/// files in the output directory.
+///
+/// ```
+/// use lindera_dictionary::builder::progress::BuildJob;
+///
+/// let job = BuildJob::new().on_progress(|progress| {
+///     eprintln!("{} {:.0}%", progress.phase, progress.percent);
+/// });
+/// let token = job.cancellation_token();
+/// // Call `token.cancel()` from another thread to abort the build.
+/// # drop(token);
+/// ```
+#[derive(Clone)]
+pub struct BuildJob {
+    callback: Option<ProgressCallback>,
+    token: CancellationToken,
+    state: Arc<Mutex<JobState>>,
+}
+
+impl Default for BuildJob {
+    fn default() -> Self {
+        Self {
+            callback: None,
+            token: CancellationToken::new(),
+            state: Arc::new(Mutex::new(JobState {
+                started: Instant::now(),
+                phases: Vec::new(),
+                memory_start: None,
+                memory: Vec::new(),
+            })),
+        }
+    }
+}
+
+impl fmt::Debug for BuildJob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BuildJob")
+            .field("token", &self.token)
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BuildJob {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the function called whenever the build makes progress.
+    ///
+    /// The function may be called from several threads at once.
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&BuildProgress) + Send + Sync + 'static,
+    {
+        self.callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Uses an existing cancellation token instead of a new one.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.token = token;
+        self
+    }
+
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Starts the clock and declares the phases of the build.
+    pub(crate) fn begin(&self, phases: &[BuildPhase]) {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        state.started = Instant::now();
+        state.phases = phases.iter().map(|phase| (*phase, 0.0)).collect();
+        state.memory_start = peak_resident_memory();
+        state.memory.clear();
+    }
+
+    /// Peak memory use of the build so far.
+    pub fn memory_report(&self) -> MemoryReport {
+        let state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        MemoryReport {
+            peak_bytes: peak_resident_memory(),
+            start_bytes: state.memory_start,
+            peak_after_phase: state.memory.clone(),
+        }
+    }
+
+    /// Fails if the build has been cancelled.
+    pub(crate) fn check(&self, phase: BuildPhase) -> LinderaResult<()> {
+        if self.is_cancelled() {
+            return Err(LinderaErrorKind::Cancelled
+                .with_error(anyhow::anyhow!("dictionary build was cancelled"))
+                .add_context(format!("Cancelled during phase: {phase}")));
+        }
+        Ok(())
+    }
+
+    /// Records that `completed` of `total` work units of the phase are done and notifies
+    /// the callback.
+    pub(crate) fn update(&self, phase: BuildPhase, completed: usize, total: usize) {
+        let fraction = if total == 0 {
+            1.0
+        } else {
+            (completed as f64 / total as f64).min(1.0)
+        };
+        let (percent, elapsed) = {
+            let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+            match state.phases.iter_mut().find(|(p, _)| *p == phase) {
+                Some(entry) => entry.1 = fraction,
+                None => state.phases.push((phase, fraction)),
+            }
+            if fraction >= 1.0
+                && !state.memory.iter().any(|(p, _)| *p == phase)
+                && let Some(bytes) = peak_resident_memory()
+            {
+                state.memory.push((phase, bytes));
+            }
+            let total_weight: f64 = state.phases.iter().map(|(p, _)| p.weight()).sum();
+            let done_weight: f64 = state.phases.iter().map(|(p, f)| p.weight() * f).sum();
+            (100.0 * done_weight / total_weight, state.started.elapsed())
+        };
+
+        if let Some(callback) = &self.callback {
+            let eta = if percent >= 100.0 {
+                Some(Duration::ZERO)
+            } else if percent > 0.0 {
+                Some(elapsed.mul_f64((100.0 - percent) / percent))
+            } else {
+                None
+            };
+            callback(&BuildProgress {
+                phase,
+                completed,
+                total,
+                percent,
+                elapsed,
+                eta,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_percent() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let job = BuildJob::new().on_progress(move |progress| {
+            sink.lock()
+                .unwrap()
+                .push((progress.phase, progress.percent));
+        });
+        job.begin(BuildPhase::USER_DICTIONARY);
+
+        job.update(BuildPhase::ReadUserDictionary, 1, 2);
+        job.update(BuildPhase::ReadUserDictionary, 2, 2);
+        job.update(BuildPhase::IndexUserDictionary, 1, 1);
+        job.update(BuildPhase::WriteUserDictionary, 1, 1);
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.len(), 4);
+        assert!((reports[0].1 - 20.0).abs() < 1e-9);
+        assert!((reports[1].1 - 40.0).abs() < 1e-9);
+        assert!((reports[2].1 - 80.0).abs() < 1e-9);
+        assert!((reports[3].1 - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let job = BuildJob::new();
+        let token = job.cancellation_token();
+        assert!(job.check(BuildPhase::ReadLexicon).is_ok());
+
+        token.cancel();
+        assert!(job.is_cancelled());
+        let err = job.check(BuildPhase::ReadLexicon).unwrap_err();
+        assert_eq!(err.kind(), LinderaErrorKind::Cancelled);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_memory_report() {
+        let job = BuildJob::new();
+        job.begin(BuildPhase::USER_DICTIONARY);
+        job.update(BuildPhase::ReadUserDictionary, 1, 2);
+        job.update(BuildPhase::ReadUserDictionary, 2, 2);
+        job.update(BuildPhase::IndexUserDictionary, 1, 1);
+
+        let report = job.memory_report();
+        assert!(report.peak_bytes.unwrap() > 0);
+        let phases: Vec<BuildPhase> = report
+            .peak_after_phase
+            .iter()
+            .map(|(phase, _)| *phase)
+            .collect();
+        assert_eq!(
+            phases,
+            vec![
+                BuildPhase::ReadUserDictionary,
+                BuildPhase::IndexUserDictionary
+            ]
+        );
+        // The high-water mark never goes down
+        let start = report.start_bytes.unwrap();
+        let peaks = &report.peak_after_phase;
+        assert!(start <= peaks[0].1);
+        assert!(peaks[0].1 <= peaks[1].1);
+        assert!(peaks[1].1 <= report.peak_bytes.unwrap());
+
+        // The increases add up to the growth of the peak during the phases
+        let increases = report.phase_increases();
+        assert_eq!(
+            peaks[1].1 - start,
+            increases.iter().map(|(_, bytes)| bytes).sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn test_phase_increases() {
+        let report = MemoryReport {
+            peak_bytes: Some(500),
+            start_bytes: Some(100),
+            peak_after_phase: vec![
+                (BuildPhase::ReadLexicon, 300),
+                (BuildPhase::IndexLexicon, 300),
+                (BuildPhase::WriteLexicon, 450),
+            ],
+        };
+        assert_eq!(
+            vec![
+                (BuildPhase::ReadLexicon, 200),
+                (BuildPhase::IndexLexicon, 0),
+                (BuildPhase::WriteLexicon, 150),
+            ],
+            report.phase_increases()
+        );
+    }
+}
@@ -0,0 +1,313 @@
+//! Import of SKK dictionaries (SKK-JISYO) into user dictionaries.
+//!
+//! An SKK dictionary maps a reading in hiragana to its conversion candidates, each one optionally
+//! followed by an annotation after a semicolon:
+//!
+//! ```text
+//! ;; okuri-nasi entries.
+//! とうきょうとちょう /東京都庁/
+//! ふじさん /富士山;日本一高い山/不二山/
+//! ```
+//!
+//! The candidates are written as the rows of a user dictionary in the detailed format, so they
+//! can be given a cost that does not override the words of the system dictionary. Only the
+//! okuri-nasi entries are imported: the okuri-ari ones (`あるk /歩/`) are the stems of
+//! inflected words, and the entries of numbers (`#`), prefixes and suffixes (`>`) and
+//! abbreviations have no complete reading. The single-character candidates are left out too, as
+//! they are conversion candidates rather than words.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::LinderaResult;
+use crate::builder::user_dictionary::detailed_row;
+use crate::dictionary::metadata::Metadata;
+use crate::dictionary::reading_index::to_katakana;
+use crate::util::read_file_with_encoding;
+
+/// Part-of-speech of the imported words, the proper nouns of IPADIC and UniDic.
+pub const DEFAULT_PART_OF_SPEECH: &[&str] = &["名詞", "固有名詞", "一般"];
+
+/// Cost of the imported words, in the range of the proper nouns of IPADIC, so that the words of
+/// the system dictionary are preferred when both can be used.
+pub const DEFAULT_WORD_COST: i16 = 5000;
+
+/// An okuri-nasi entry of an SKK dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkkEntry {
+    /// The reading in hiragana.
+    pub reading: String,
+    /// The candidates, without their annotations.
+    pub candidates: Vec<String>,
+}
+
+/// Parses a line of an SKK dictionary.
+///
+/// Returns `None` for the comment lines, the okuri-ari entries and the entries whose reading is
+/// not in hiragana.
+pub fn parse_line(line: &str) -> Option<SkkEntry> {
+    if line.starts_with(';') {
+        return None;
+    }
+    let (reading, candidates) = line.split_once(" /")?;
+    if reading.is_empty() || !reading.chars().all(is_reading_char) {
+        return None;
+    }
+
+    Some(SkkEntry {
+        reading: reading.to_string(),
+        candidates: candidates.split('/').filter_map(decode_candidate).collect(),
+    })
+}
+
+fn is_reading_char(c: char) -> bool {
+    matches!(c, '\u{3041}'..='\u{3096}' | 'ー')
+}
+
+/// Decodes a candidate: strips the annotation and evaluates the `(concat "...")` expressions
+/// SKK uses for the candidates that contain a slash or a semicolon (`\057`, `\073`).
+///
+/// Returns `None` for the empty candidates and the other Lisp expressions.
+pub fn decode_candidate(candidate: &str) -> Option<String> {
+    let candidate = candidate
+        .split_once(';')
+        .map_or(candidate, |(word, _)| word);
+    if candidate.is_empty() {
+        return None;
+    }
+    if !candidate.starts_with('(') {
+        return Some(candidate.to_string());
+    }
+
+    let literal = candidate.strip_prefix("(concat \"")?.strip_suffix("\")")?;
+    let mut decoded = String::new();
+    let mut chars = literal.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            decoded.push(c);
+            continue;
+        }
+        let escaped = chars.clone().take(3).collect::<String>();
+        if escaped.len() == 3 && escaped.chars().all(|c| c.is_digit(8)) {
+            decoded.push(char::from_u32(u32::from_str_radix(&escaped, 8).ok()?)?);
+            chars.nth(2);
+        } else {
+            decoded.push(chars.next()?);
+        }
+    }
+    (!decoded.is_empty()).then_some(decoded)
+}
+
+/// Converts an SKK dictionary into the rows of a user dictionary in the detailed format of a
+/// dictionary.
+#[derive(Debug, Clone)]
+pub struct SkkImporter {
+    /// The part-of-speech fields of the words, in the order of the schema.
+    pub part_of_speech: Vec<String>,
+    pub word_cost: i16,
+}
+
+impl Default for SkkImporter {
+    fn default() -> Self {
+        Self {
+            part_of_speech: DEFAULT_PART_OF_SPEECH
+                .iter()
+                .map(|pos| pos.to_string())
+                .collect(),
+            word_cost: DEFAULT_WORD_COST,
+        }
+    }
+}
+
+impl SkkImporter {
+    /// The user dictionary rows of the candidates of `text`, in the schema of `metadata`.
+    ///
+    /// Each word is written once per reading, with the context IDs of the simple format. See
+    /// [`detailed_row`] for the fields.
+    pub fn rows(&self, text: &str, metadata: &Metadata) -> Vec<Vec<String>> {
+        let mut seen = HashSet::new();
+        let mut rows = Vec::new();
+        for entry in text.lines().filter_map(parse_line) {
+            let reading = to_katakana(&entry.reading);
+            for surface in entry.candidates {
+                if surface.chars().count() < 2
+                    || surface == entry.reading
+                    || !seen.insert((surface.clone(), reading.clone()))
+                {
+                    continue;
+                }
+                rows.push(detailed_row(
+                    metadata,
+                    &surface,
+                    &reading,
+                    &self.part_of_speech,
+                    (
+                        metadata.default_left_context_id,
+                        metadata.default_right_context_id,
+                    ),
+                    self.word_cost,
+                ));
+            }
+        }
+        rows
+    }
+
+    /// The user dictionary rows of the SKK dictionary file `input_file`, decoded from `encoding`
+    /// (`auto` to detect UTF-8 and EUC-JP, the encoding of the SKK-JISYO files).
+    pub fn import_file(
+        &self,
+        input_file: &Path,
+        encoding: &str,
+        metadata: &Metadata,
+    ) -> LinderaResult<Vec<Vec<String>>> {
+        let text = read_file_with_encoding(input_file, encoding)?;
+        Ok(self.rows(&text, metadata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SKK_JISYO: &str = "\
+;; -*- mode: fundamental; coding: euc-jp -*-
+;; okuri-ari entries.
+あるk /歩/
+;; okuri-nasi entries.
+とうきょうとちょう /東京都庁/
+ふじさん /富士山;日本一高い山/不二山/ふじさん/
+ふじ /富士/藤/
+#ねん /#1年/
+>し /氏/
+http /(concat \"http:\\057\\057\")/
+でぃーぶいでぃー /(concat \"DVD\\073R\")/(skk-current-date)/
+";
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            Some(SkkEntry {
+                reading: "ふじさん".to_string(),
+                candidates: vec![
+                    "富士山".to_string(),
+                    "不二山".to_string(),
+                    "ふじさん".to_string()
+                ],
+            }),
+            parse_line("ふじさん /富士山;日本一高い山/不二山/ふじさん/")
+        );
+        assert_eq!(None, parse_line(";; okuri-nasi entries."));
+        assert_eq!(None, parse_line("あるk /歩/"));
+        assert_eq!(None, parse_line("#ねん /#1年/"));
+        assert_eq!(None, parse_line(">し /氏/"));
+        assert_eq!(None, parse_line("http /(concat \"http:\\057\\057\")/"));
+    }
+
+    #[test]
+    fn test_decode_candidate() {
+        assert_eq!(Some("東京".to_string()), decode_candidate("東京;地名"));
+        assert_eq!(
+            Some("DVD;R".to_string()),
+            decode_candidate("(concat \"DVD\\073R\")")
+        );
+        assert_eq!(
+            Some("a\"b".to_string()),
+            decode_candidate("(concat \"a\\\"b\")")
+        );
+        assert_eq!(None, decode_candidate("(skk-current-date)"));
+        assert_eq!(None, decode_candidate(""));
+    }
+
+    #[test]
+    fn test_rows() {
+        let rows = SkkImporter::default().rows(SKK_JISYO, &Metadata::default());
+        assert_eq!(
+            vec![
+                vec![
+                    "東京都庁",
+                    "1288",
+                    "1288",
+                    "5000",
+                    "名詞",
+                    "固有名詞",
+                    "一般",
+                    "*",
+                    "*",
+                    "*",
+                    "東京都庁",
+                    "トウキョウトチョウ",
+                    "トウキョウトチョウ"
+                ],
+                vec![
+                    "富士山",
+                    "1288",
+                    "1288",
+                    "5000",
+                    "名詞",
+                    "固有名詞",
+                    "一般",
+                    "*",
+                    "*",
+                    "*",
+                    "富士山",
+                    "フジサン",
+                    "フジサン"
+                ],
+                vec![
+                    "不二山",
+                    "1288",
+                    "1288",
+                    "5000",
+                    "名詞",
+                    "固有名詞",
+                    "一般",
+                    "*",
+                    "*",
+                    "*",
+                    "不二山",
+                    "フジサン",
+                    "フジサン"
+                ],
+                vec![
+                    "富士",
+                    "1288",
+                    "1288",
+                    "5000",
+                    "名詞",
+                    "固有名詞",
+                    "一般",
+                    "*",
+                    "*",
+                    "*",
+                    "富士",
+                    "フジ",
+                    "フジ"
+                ],
+                vec![
+                    "DVD;R",
+                    "1288",
+                    "1288",
+                    "5000",
+                    "名詞",
+                    "固有名詞",
+                    "一般",
+                    "*",
+                    "*",
+                    "*",
+                    "DVD;R",
+                    "ディーブイディー",
+                    "ディーブイディー"
+                ],
+            ],
+            rows
+        );
+
+        let importer = SkkImporter {
+            part_of_speech: vec!["名詞".to_string()],
+            word_cost: -1000,
+        };
+        let rows = importer.rows("ふじ /富士/\n", &Metadata::default());
+        assert_eq!("-1000", rows[0][3]);
+        assert_eq!(vec!["名詞", "*", "*", "*"], rows[0][4..8].to_vec());
+    }
+}
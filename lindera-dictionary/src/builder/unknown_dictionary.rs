@@ -7,6 +7,7 @@ use derive_builder::Builder;
 use log::debug;
 
 use crate::LinderaResult;
+use crate::compress::CompressionSettings;
 use crate::decompress::Algorithm;
 use crate::dictionary::character_definition::CharacterDefinition;
 use crate::dictionary::unknown_dictionary::parse_unk;
@@ -21,6 +22,8 @@ pub struct UnknownDictionaryBuilder {
     encoding: Cow<'static, str>,
     #[builder(default = "Algorithm::Deflate")]
     compress_algorithm: Algorithm,
+    #[builder(default = "CompressionSettings::default()")]
+    compression: CompressionSettings,
 }
 
 impl UnknownDictionaryBuilder {
@@ -53,7 +56,12 @@ impl UnknownDictionaryBuilder {
                 .map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?,
         );
 
-        compress_write(&unk_buffer, self.compress_algorithm, &mut wtr_unk)?;
+        compress_write(
+            &unk_buffer,
+            self.compress_algorithm,
+            &self.compression,
+            &mut wtr_unk,
+        )?;
 
         wtr_unk
             .flush()
@@ -1,4 +1,5 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::fs;
 use std::fs::File;
 use std::io;
@@ -12,11 +13,88 @@ use derive_builder::Builder;
 use log::debug;
 
 use crate::LinderaResult;
+use crate::builder::normalize_surfaces;
+use crate::builder::progress::{BuildJob, BuildPhase};
 use crate::dictionary::UserDictionary;
+use crate::dictionary::connection_cost_matrix::ConnectionCostMatrix;
+use crate::dictionary::metadata::{Metadata, SurfaceNormalization};
 use crate::dictionary::prefix_dictionary::PrefixDictionary;
 use crate::error::LinderaErrorKind;
+use crate::format::write_format_header;
 use crate::viterbi::WordEntry;
 
+/// Number of rows read between progress notifications.
+const PROGRESS_INTERVAL: usize = 10_000;
+
+/// Severity of a user dictionary diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The row prevents the user dictionary from being built.
+    Error,
+    /// The row can be built but is likely a mistake.
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found in a user dictionary CSV file.
+///
+/// Displayed as `line:column: severity: message` (the column is omitted
+/// when the problem concerns the whole row).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// 1-based line number in the CSV file.
+    pub line: u64,
+    /// 1-based column (field) number, if the problem is in a specific field.
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn error(line: u64, column: Option<usize>, message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            line,
+            column,
+            message,
+        }
+    }
+
+    fn warning(line: u64, column: Option<usize>, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            line,
+            column,
+            message,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(
+                f,
+                "{}:{}: {}: {}",
+                self.line, column, self.severity, self.message
+            ),
+            None => write!(f, "{}: {}: {}", self.line, self.severity, self.message),
+        }
+    }
+}
+
 type StringRecordProcessor = Option<Box<dyn Fn(&StringRecord) -> LinderaResult<Vec<String>>>>;
 
 #[derive(Builder)]
@@ -36,25 +114,64 @@ pub struct UserDictionaryBuilder {
     default_right_context_id: u16,
     #[builder(default = "true")]
     flexible_csv: bool,
+    #[builder(default = "SurfaceNormalization::None")]
+    surface_normalization: SurfaceNormalization,
     #[builder(setter(strip_option), default = "None")]
     user_dictionary_handler: StringRecordProcessor,
 }
 
 impl UserDictionaryBuilder {
     pub fn build(&self, input_file: &Path) -> LinderaResult<UserDictionary> {
+        self.build_with_job(input_file, &BuildJob::new())
+    }
+
+    /// Builds the user dictionary, reporting progress to the job and stopping if it is cancelled.
+    ///
+    /// Reading is reported in bytes of the input file.
+    pub fn build_with_job(
+        &self,
+        input_file: &Path,
+        job: &BuildJob,
+    ) -> LinderaResult<UserDictionary> {
         debug!("reading {input_file:?}");
+        job.check(BuildPhase::ReadUserDictionary)?;
+        let file = File::open(input_file).map_err(|err| {
+            LinderaErrorKind::Io
+                .with_error(anyhow::anyhow!(err))
+                .add_context(format!(
+                    "Failed to open user dictionary CSV file: {input_file:?}"
+                ))
+        })?;
+        let file_size = file
+            .metadata()
+            .map(|metadata| metadata.len() as usize)
+            .unwrap_or_default();
+
+        self.build_from_reader(file, file_size, &format!("file: {input_file:?}"), job)
+    }
+
+    /// Builds the user dictionary from the contents of a CSV file held in memory, e.g. bytes
+    /// whose signature was checked.
+    pub fn build_from_bytes(&self, data: &[u8]) -> LinderaResult<UserDictionary> {
+        self.build_from_reader(data, data.len(), "user dictionary data", &BuildJob::new())
+    }
+
+    /// Builds the user dictionary from CSV read from `reader`, of `size` bytes. `source` names
+    /// the input in errors.
+    fn build_from_reader<R: io::Read>(
+        &self,
+        reader: R,
+        size: usize,
+        source: &str,
+        job: &BuildJob,
+    ) -> LinderaResult<UserDictionary> {
+        job.check(BuildPhase::ReadUserDictionary)?;
+        job.update(BuildPhase::ReadUserDictionary, 0, size);
 
         let mut rdr = csv::ReaderBuilder::new()
             .has_headers(false)
             .flexible(self.flexible_csv)
-            .from_path(input_file)
-            .map_err(|err| {
-                LinderaErrorKind::Io
-                    .with_error(anyhow::anyhow!(err))
-                    .add_context(format!(
-                        "Failed to open user dictionary CSV file: {input_file:?}"
-                    ))
-            })?;
+            .from_reader(reader);
 
         let mut rows: Vec<StringRecord> = vec![];
         for (line_num, result) in rdr.records().enumerate() {
@@ -62,13 +179,23 @@ impl UserDictionaryBuilder {
                 LinderaErrorKind::Content
                     .with_error(anyhow::anyhow!(err))
                     .add_context(format!(
-                        "Failed to parse CSV record at line {} in file: {:?}",
-                        line_num + 1,
-                        input_file
+                        "Failed to parse CSV record at line {} in {source}",
+                        line_num + 1
                     ))
             })?;
+            let offset = record
+                .position()
+                .map_or(0, |position| position.byte() as usize);
             rows.push(record);
+            if rows.len().is_multiple_of(PROGRESS_INTERVAL) {
+                job.check(BuildPhase::ReadUserDictionary)?;
+                job.update(BuildPhase::ReadUserDictionary, offset, size);
+            }
         }
+        job.update(BuildPhase::ReadUserDictionary, size, size);
+
+        job.check(BuildPhase::IndexUserDictionary)?;
+        let mut rows = normalize_surfaces(rows, self.surface_normalization);
         rows.sort_by_key(|row| row[0].to_string());
 
         let mut word_entry_map: BTreeMap<String, Vec<WordEntry>> = BTreeMap::new();
@@ -230,10 +357,268 @@ impl UserDictionaryBuilder {
             }
         }
 
-        let dict = PrefixDictionary::load(da_bytes, vals_data, words_idx_data, words_data, false);
+        let dict = PrefixDictionary::load(da_bytes, vals_data, words_idx_data, words_data, false)?;
+        job.update(BuildPhase::IndexUserDictionary, 1, 1);
 
         Ok(UserDictionary { dict })
     }
+
+    /// Checks the user dictionary CSV file without building it.
+    ///
+    /// Unlike [`UserDictionaryBuilder::build`], which stops at the first bad row,
+    /// this reports every problem found with its line and column: malformed rows,
+    /// wrong column counts, invalid or out-of-range context ids and costs, non-UTF-8
+    /// bytes, and surfaces that are defined more than once. If `matrix` is given,
+    /// context ids are also checked against its size.
+    /// An error is returned only if the file cannot be read.
+    pub fn lint(
+        &self,
+        input_file: &Path,
+        matrix: Option<&ConnectionCostMatrix>,
+    ) -> LinderaResult<Vec<Diagnostic>> {
+        let data = fs::read(input_file).map_err(|err| {
+            LinderaErrorKind::Io
+                .with_error(anyhow::anyhow!(err))
+                .add_context(format!(
+                    "Failed to open user dictionary CSV file: {input_file:?}"
+                ))
+        })?;
+
+        Ok(self.lint_data(&data, matrix))
+    }
+
+    fn lint_data(&self, data: &[u8], matrix: Option<&ConnectionCostMatrix>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        // Column counts are checked per row below, so the reader is always flexible.
+        let mut rdr = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(data);
+
+        // surface -> (line of the first definition, entry without the surface)
+        let mut entries: HashMap<String, (u64, Vec<String>)> = HashMap::new();
+
+        let mut record = csv::ByteRecord::new();
+        loop {
+            let line = rdr.position().line();
+            match rdr.read_byte_record(&mut record) {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(err) => {
+                    let line = err.position().map_or(line, |pos| pos.line());
+                    diagnostics.push(Diagnostic::error(
+                        line,
+                        None,
+                        format!("malformed CSV row: {err}"),
+                    ));
+                    if matches!(err.kind(), csv::ErrorKind::Io(_)) {
+                        break;
+                    }
+                    continue;
+                }
+            }
+            let line = record.position().map_or(line, |pos| pos.line());
+
+            let mut fields = Vec::with_capacity(record.len());
+            for (i, field) in record.iter().enumerate() {
+                match std::str::from_utf8(field) {
+                    Ok(field) => fields.push(field),
+                    Err(err) => diagnostics.push(Diagnostic::error(
+                        line,
+                        Some(i + 1),
+                        format!(
+                            "invalid UTF-8 sequence at byte {} of the field",
+                            err.valid_up_to() + 1
+                        ),
+                    )),
+                }
+            }
+            if fields.len() < record.len() {
+                continue;
+            }
+
+            let detailed = fields.len() != self.user_dictionary_fields_num;
+            if detailed && fields.len() < self.dictionary_fields_num {
+                diagnostics.push(Diagnostic::error(
+                    line,
+                    None,
+                    format!(
+                        "expected {} or {}+ fields, found {}",
+                        self.user_dictionary_fields_num,
+                        self.dictionary_fields_num,
+                        fields.len()
+                    ),
+                ));
+                continue;
+            }
+
+            let surface = fields[0];
+            if surface.is_empty() {
+                diagnostics.push(Diagnostic::error(
+                    line,
+                    Some(1),
+                    "surface is empty".to_string(),
+                ));
+                continue;
+            }
+
+            if detailed {
+                let left_id = lint_number(
+                    &mut diagnostics,
+                    line,
+                    2,
+                    "left context id",
+                    fields[1],
+                    u16::MIN.into(),
+                    u16::MAX.into(),
+                );
+                let right_id = lint_number(
+                    &mut diagnostics,
+                    line,
+                    3,
+                    "right context id",
+                    fields[2],
+                    u16::MIN.into(),
+                    u16::MAX.into(),
+                );
+                lint_number(
+                    &mut diagnostics,
+                    line,
+                    4,
+                    "word cost",
+                    fields[3],
+                    i16::MIN.into(),
+                    i16::MAX.into(),
+                );
+
+                if let Some(matrix) = matrix {
+                    // The left id indexes the backward side of the matrix and the right id the forward side.
+                    if let Some(left_id) = left_id
+                        && left_id >= i64::from(matrix.backward_size)
+                    {
+                        diagnostics.push(Diagnostic::error(
+                            line,
+                            Some(2),
+                            format!(
+                                "left context id {left_id} is out of range for the connection cost matrix (0..{})",
+                                matrix.backward_size
+                            ),
+                        ));
+                    }
+                    if let Some(right_id) = right_id
+                        && right_id >= i64::from(matrix.forward_size)
+                    {
+                        diagnostics.push(Diagnostic::error(
+                            line,
+                            Some(3),
+                            format!(
+                                "right context id {right_id} is out of range for the connection cost matrix (0..{})",
+                                matrix.forward_size
+                            ),
+                        ));
+                    }
+                }
+            }
+
+            let entry: Vec<String> = fields[1..].iter().map(|s| s.to_string()).collect();
+            match entries.get(surface) {
+                Some((first_line, first_entry)) if *first_entry == entry => {
+                    diagnostics.push(Diagnostic::warning(
+                        line,
+                        Some(1),
+                        format!(
+                            "duplicate entry for '{surface}' (first defined at line {first_line})"
+                        ),
+                    ));
+                }
+                Some((first_line, _)) => {
+                    diagnostics.push(Diagnostic::warning(
+                        line,
+                        Some(1),
+                        format!("conflicting entries for '{surface}' (first defined at line {first_line})"),
+                    ));
+                }
+                None => {
+                    entries.insert(surface.to_string(), (line, entry));
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+/// Parses a numeric field of a user dictionary row, recording a diagnostic if it is
+/// not an integer or is outside `min..=max`.
+fn lint_number(
+    diagnostics: &mut Vec<Diagnostic>,
+    line: u64,
+    column: usize,
+    name: &str,
+    value: &str,
+    min: i64,
+    max: i64,
+) -> Option<i64> {
+    let Ok(n) = value.parse::<i64>() else {
+        diagnostics.push(Diagnostic::error(
+            line,
+            Some(column),
+            format!("invalid {name} '{value}'"),
+        ));
+        return None;
+    };
+    if n < min || n > max {
+        diagnostics.push(Diagnostic::error(
+            line,
+            Some(column),
+            format!("{name} {n} is out of range ({min}..={max})"),
+        ));
+        return None;
+    }
+    Some(n)
+}
+
+/// A row of a user dictionary in the detailed format of `metadata`, for the importers of the
+/// dictionaries of other tools.
+///
+/// The fields are filled by name: the part-of-speech fields with `part_of_speech` in order,
+/// `base_form` with the surface, `reading` and `pronunciation` with `reading`, and the others
+/// with the default value.
+pub fn detailed_row(
+    metadata: &Metadata,
+    surface: &str,
+    reading: &str,
+    part_of_speech: &[impl AsRef<str>],
+    (left_context_id, right_context_id): (u16, u16),
+    word_cost: i16,
+) -> Vec<String> {
+    let mut part_of_speech = part_of_speech.iter();
+    metadata
+        .dictionary_schema
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, name)| match (index, name.as_str()) {
+            (0, _) => surface.to_string(),
+            (1, _) => left_context_id.to_string(),
+            (2, _) => right_context_id.to_string(),
+            (3, _) => word_cost.to_string(),
+            (_, "base_form") => surface.to_string(),
+            (_, "reading" | "pronunciation") => reading.to_string(),
+            (_, name) if is_part_of_speech_field(name) => part_of_speech
+                .next()
+                .map_or(&metadata.default_field_value as &str, AsRef::as_ref)
+                .to_string(),
+            _ => metadata.default_field_value.clone(),
+        })
+        .collect()
+}
+
+/// The part-of-speech fields, named `part_of_speech*` in the schemas of the dictionaries and
+/// `major_pos` and `pos_detail_*` in the default schema.
+fn is_part_of_speech_field(name: &str) -> bool {
+    name.starts_with("part_of_speech") || name == "major_pos" || name.starts_with("pos_detail")
 }
 
 pub fn build_user_dictionary(user_dict: UserDictionary, output_file: &Path) -> LinderaResult<()> {
@@ -267,6 +652,7 @@ pub fn build_user_dictionary(user_dict: UserDictionary, output_file: &Path) -> L
                 "Failed to serialize user dictionary to file: {output_file:?}"
             ))
     })?;
+    write_format_header(&mut wtr)?;
     wtr.write_all(&bytes).map_err(|err| {
         LinderaErrorKind::Io
             .with_error(anyhow::anyhow!(err))
@@ -284,3 +670,64 @@ pub fn build_user_dictionary(user_dict: UserDictionary, output_file: &Path) -> L
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(data: &str) -> Vec<Diagnostic> {
+        UserDictionaryBuilderOptions::default()
+            .builder()
+            .unwrap()
+            .lint_data(data.as_bytes(), None)
+    }
+
+    #[test]
+    fn test_lint_valid() {
+        let data = "東京スカイツリー,カスタム名詞,トウキョウスカイツリー\n\
+                    とうきょうスカイツリー駅,1288,1288,-1000,名詞,固有名詞,一般,*,*,*,とうきょうスカイツリー駅,トウキョウスカイツリーエキ,トウキョウスカイツリーエキ\n";
+        assert!(lint(data).is_empty());
+    }
+
+    #[test]
+    fn test_lint_column_count() {
+        let diagnostics = lint("東京,カスタム名詞,トウキョウ\n東京スカイツリー,カスタム名詞\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].is_error());
+        assert_eq!(diagnostics[0].line, 2);
+        assert_eq!(diagnostics[0].column, None);
+    }
+
+    #[test]
+    fn test_lint_out_of_range() {
+        let diagnostics =
+            lint("東京,70000,1288,-40000,名詞,固有名詞,一般,*,*,*,東京,トウキョウ,トウキョウ\n");
+        let columns: Vec<Option<usize>> = diagnostics.iter().map(|d| d.column).collect();
+        assert_eq!(columns, vec![Some(2), Some(4)]);
+        assert!(diagnostics.iter().all(Diagnostic::is_error));
+    }
+
+    #[test]
+    fn test_lint_invalid_utf8() {
+        let mut data = "東京,カスタム名詞,トウキョウ\n".as_bytes().to_vec();
+        data.extend_from_slice(b"abc,\xff\xfe,def\n");
+        let diagnostics = UserDictionaryBuilderOptions::default()
+            .builder()
+            .unwrap()
+            .lint_data(&data, None);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].to_string().split(": ").next(), Some("2:2"));
+    }
+
+    #[test]
+    fn test_lint_duplicates() {
+        let diagnostics = lint(
+            "東京,カスタム名詞,トウキョウ\n東京,カスタム名詞,トウキョウ\n東京,地名,トウキョウ\n",
+        );
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().all(|d| !d.is_error()));
+        assert!(diagnostics[0].message.starts_with("duplicate entry"));
+        assert!(diagnostics[1].message.starts_with("conflicting entries"));
+        assert_eq!(diagnostics[1].line, 3);
+    }
+}
@@ -1,31 +1,105 @@
 use std::io::Write;
 
-use flate2::Compression;
 use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+use flate2::{Compress, Compression, Crc, FlushCompress, Status};
 
-use crate::decompress::{Algorithm, CompressedData};
+use crate::LinderaResult;
+use crate::decompress::{Algorithm, CHUNK_INDEX_MAGIC, CompressedData};
+use crate::error::LinderaErrorKind;
 
-#[allow(dead_code)]
-fn algorithm_compression_ratio_estimation() -> f64 {
-    unimplemented!()
+/// Environment variable setting the compression level of the dictionary components, from 0
+/// (fastest, largest) to 9 (slowest, smallest).
+pub const COMPRESSION_LEVEL_VAR: &str = "LINDERA_COMPRESSION_LEVEL";
+
+/// Environment variable setting the size in bytes of the chunks compressed independently.
+pub const COMPRESSION_CHUNK_SIZE_VAR: &str = "LINDERA_COMPRESSION_CHUNK_SIZE";
+
+/// The settings of the compression of the dictionary components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionSettings {
+    /// The compression level, from 0 to 9.
+    pub level: u32,
+    /// The size of the chunks of the uncompressed data that are compressed independently, so
+    /// that they are decompressed on several threads with the `parallel` feature. Smaller chunks
+    /// compress a little less. 0 compresses the data as a single chunk.
+    pub chunk_size: usize,
+}
+
+impl Default for CompressionSettings {
+    fn default() -> Self {
+        Self {
+            level: Compression::default().level(),
+            chunk_size: 0,
+        }
+    }
+}
+
+impl CompressionSettings {
+    /// Reads the settings from `LINDERA_COMPRESSION_LEVEL` and `LINDERA_COMPRESSION_CHUNK_SIZE`,
+    /// so that build scripts building embedded dictionaries honor them. Unset variables keep the
+    /// default settings.
+    pub fn from_env() -> LinderaResult<Self> {
+        let mut settings = Self::default();
+        if let Ok(value) = std::env::var(COMPRESSION_LEVEL_VAR) {
+            settings.level = value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|level| *level <= 9)
+                .ok_or_else(|| {
+                    LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                        "{COMPRESSION_LEVEL_VAR} must be a level from 0 to 9, not {value:?}"
+                    ))
+                })?;
+        }
+        if let Ok(value) = std::env::var(COMPRESSION_CHUNK_SIZE_VAR) {
+            settings.chunk_size = value.trim().parse().map_err(|_| {
+                LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                    "{COMPRESSION_CHUNK_SIZE_VAR} must be a number of bytes, not {value:?}"
+                ))
+            })?;
+        }
+        Ok(settings)
+    }
 }
 
 pub fn compress(data: &[u8], algorithm: Algorithm) -> anyhow::Result<CompressedData> {
+    compress_with_settings(data, algorithm, &CompressionSettings::default())
+}
+
+/// Compresses `data` with the level and the chunk size of `settings`.
+///
+/// The chunks are ended by full flushes of a single stream, which any decoder reads as a whole,
+/// and the stream is followed by an index of the chunks for the decoders that read them in
+/// parallel.
+pub fn compress_with_settings(
+    data: &[u8],
+    algorithm: Algorithm,
+    settings: &CompressionSettings,
+) -> anyhow::Result<CompressedData> {
+    let level = Compression::new(settings.level);
+    if settings.chunk_size > 0 && data.len() > settings.chunk_size && algorithm != Algorithm::Raw {
+        return Ok(CompressedData::new(
+            algorithm,
+            compress_chunks(data, algorithm, level, settings.chunk_size)?,
+        ));
+    }
+
     match algorithm {
         Algorithm::Deflate => {
-            let mut e = DeflateEncoder::new(Vec::new(), Compression::default());
+            let mut e = DeflateEncoder::new(Vec::new(), level);
             e.write_all(data)?;
 
             Ok(CompressedData::new(algorithm, e.finish()?))
         }
         Algorithm::Zlib => {
-            let mut e = ZlibEncoder::new(Vec::new(), Compression::default());
+            let mut e = ZlibEncoder::new(Vec::new(), level);
             e.write_all(data)?;
 
             Ok(CompressedData::new(algorithm, e.finish()?))
         }
         Algorithm::Gzip => {
-            let mut e = GzEncoder::new(Vec::new(), Compression::default());
+            let mut e = GzEncoder::new(Vec::new(), level);
             e.write_all(data)?;
             Ok(CompressedData::new(algorithm, e.finish()?))
         }
@@ -33,6 +107,73 @@ pub fn compress(data: &[u8], algorithm: Algorithm) -> anyhow::Result<CompressedD
     }
 }
 
+/// Header of a gzip member without file name nor modification time.
+const GZIP_HEADER: [u8; 10] = [0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+
+/// Length of the zlib header written by [`Compress`].
+const ZLIB_HEADER_LEN: usize = 2;
+
+fn compress_chunks(
+    data: &[u8],
+    algorithm: Algorithm,
+    level: Compression,
+    chunk_size: usize,
+) -> anyhow::Result<Vec<u8>> {
+    let mut output = Vec::with_capacity(data.len() / 2);
+    let mut compress = Compress::new(level, algorithm == Algorithm::Zlib);
+    if algorithm == Algorithm::Gzip {
+        output.extend_from_slice(&GZIP_HEADER);
+    }
+
+    // The offset of the deflate data of each chunk in the output, and its uncompressed length
+    let mut index: Vec<(u32, u32)> = Vec::new();
+    for (i, chunk) in data.chunks(chunk_size).enumerate() {
+        let offset = if i == 0 && algorithm == Algorithm::Zlib {
+            ZLIB_HEADER_LEN
+        } else {
+            output.len()
+        };
+        index.push((u32::try_from(offset)?, u32::try_from(chunk.len())?));
+        // A full flush resets the history, so that the chunk is decoded without the ones before
+        deflate(&mut compress, chunk, &mut output, FlushCompress::Full)?;
+    }
+    deflate(&mut compress, &[], &mut output, FlushCompress::Finish)?;
+
+    if algorithm == Algorithm::Gzip {
+        let mut crc = Crc::new();
+        crc.update(data);
+        output.extend_from_slice(&crc.sum().to_le_bytes());
+        output.extend_from_slice(&crc.amount().to_le_bytes());
+    }
+
+    for (offset, len) in &index {
+        output.extend_from_slice(&offset.to_le_bytes());
+        output.extend_from_slice(&len.to_le_bytes());
+    }
+    output.extend_from_slice(&u32::try_from(index.len())?.to_le_bytes());
+    output.extend_from_slice(CHUNK_INDEX_MAGIC);
+    Ok(output)
+}
+
+fn deflate(
+    compress: &mut Compress,
+    mut input: &[u8],
+    output: &mut Vec<u8>,
+    flush: FlushCompress,
+) -> anyhow::Result<()> {
+    loop {
+        output.reserve(input.len() / 2 + 1024);
+        let total_in = compress.total_in();
+        let status = compress.compress_vec(input, output, flush)?;
+        input = &input[(compress.total_in() - total_in) as usize..];
+        // The flush is complete when the output was not filled up
+        let flushed = !matches!(flush, FlushCompress::Finish) && output.len() < output.capacity();
+        if status == Status::StreamEnd || (input.is_empty() && flushed) {
+            return Ok(());
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rand::{Rng, SeedableRng, rngs::SmallRng};
@@ -60,4 +201,68 @@ mod tests {
 
         assert_eq!(&buf, &data);
     }
+
+    #[test]
+    fn compress_decompress_chunks() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let buf = (0..50000)
+            .map(|i| if i % 3 == 0 { rng.random() } else { b'a' })
+            .collect::<Vec<u8>>();
+
+        let settings = CompressionSettings {
+            level: 9,
+            chunk_size: 4096,
+        };
+        for algorithm in [Algorithm::Deflate, Algorithm::Zlib, Algorithm::Gzip] {
+            let single = compress(&buf, algorithm).unwrap();
+            let chunked = compress_with_settings(&buf, algorithm, &settings).unwrap();
+            assert!(chunked.data.ends_with(CHUNK_INDEX_MAGIC));
+            assert_eq!(buf, decompress(chunked.clone()).unwrap());
+
+            // The stream is read as a whole by the decoders that ignore the index
+            let decoded = match algorithm {
+                Algorithm::Deflate => {
+                    let mut decoded = Vec::new();
+                    std::io::Read::read_to_end(
+                        &mut flate2::read::DeflateDecoder::new(chunked.data.as_slice()),
+                        &mut decoded,
+                    )
+                    .unwrap();
+                    decoded
+                }
+                Algorithm::Zlib => {
+                    let mut decoded = Vec::new();
+                    std::io::Read::read_to_end(
+                        &mut flate2::read::ZlibDecoder::new(chunked.data.as_slice()),
+                        &mut decoded,
+                    )
+                    .unwrap();
+                    decoded
+                }
+                _ => {
+                    let mut decoded = Vec::new();
+                    std::io::Read::read_to_end(
+                        &mut flate2::read::GzDecoder::new(chunked.data.as_slice()),
+                        &mut decoded,
+                    )
+                    .unwrap();
+                    decoded
+                }
+            };
+            assert_eq!(buf, decoded);
+            assert!(single.data.len() < chunked.data.len());
+        }
+
+        // Data smaller than a chunk is compressed as before
+        let settings = CompressionSettings {
+            chunk_size: buf.len(),
+            ..CompressionSettings::default()
+        };
+        assert_eq!(
+            compress(&buf, Algorithm::Deflate).unwrap().data,
+            compress_with_settings(&buf, Algorithm::Deflate, &settings)
+                .unwrap()
+                .data
+        );
+    }
 }
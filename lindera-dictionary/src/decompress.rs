@@ -1,5 +1,9 @@
-use std::{io::Read, str::FromStr};
+use alloc::vec::Vec;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::io::Read;
 
+#[cfg(feature = "std")]
 use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 use serde::{Deserialize, Serialize};
@@ -23,7 +27,6 @@ use crate::error::{LinderaError, LinderaErrorKind};
 )]
 #[repr(u32)] // explicit representation for consistency
 #[serde(rename_all = "lowercase")]
-
 pub enum Algorithm {
     Deflate = 0,
     Zlib = 1,
@@ -73,7 +76,91 @@ impl CompressedData {
     }
 }
 
+/// Magic bytes ending the index of the chunks of data compressed in chunks.
+pub const CHUNK_INDEX_MAGIC: &[u8; 8] = b"LNDRCHNK";
+
+/// The offsets of the deflate data of the chunks and their uncompressed lengths, if `data` was
+/// compressed in chunks, with the offset of the index.
+#[cfg(feature = "parallel")]
+fn chunk_index(data: &[u8]) -> Option<(usize, Vec<(usize, usize)>)> {
+    let read_u32 = |offset: usize| -> Option<usize> {
+        let bytes = data.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    };
+
+    let end = data.len().checked_sub(CHUNK_INDEX_MAGIC.len())?;
+    if &data[end..] != CHUNK_INDEX_MAGIC {
+        return None;
+    }
+    let count = read_u32(end.checked_sub(4)?)?;
+    let index_start = end.checked_sub(4)?.checked_sub(count.checked_mul(8)?)?;
+    let chunks = (0..count)
+        .map(|i| {
+            Some((
+                read_u32(index_start + 8 * i)?,
+                read_u32(index_start + 8 * i + 4)?,
+            ))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    // The offsets increase and are before the index
+    let ordered = chunks.windows(2).all(|pair| pair[0].0 <= pair[1].0)
+        && chunks.last().is_none_or(|last| last.0 <= index_start);
+    ordered.then_some((index_start, chunks))
+}
+
+/// Decompresses the chunks of data compressed in chunks on several threads.
+#[cfg(feature = "parallel")]
+fn decompress_chunks(
+    data: &[u8],
+    index_start: usize,
+    chunks: &[(usize, usize)],
+) -> anyhow::Result<Vec<u8>> {
+    use flate2::{Decompress, FlushDecompress};
+
+    let ranges = chunks
+        .iter()
+        .enumerate()
+        .map(|(i, (offset, len))| {
+            let end = chunks.get(i + 1).map_or(index_start, |next| next.0);
+            (*offset..end, *len)
+        })
+        .collect::<Vec<_>>();
+    let decompressed = crate::util::par_map(&ranges, |(range, len)| {
+        let mut output = Vec::with_capacity(*len);
+        Decompress::new(false).decompress_vec(
+            &data[range.clone()],
+            &mut output,
+            FlushDecompress::Sync,
+        )?;
+        if output.len() != *len {
+            anyhow::bail!(
+                "chunk at {} decompressed to {} bytes instead of {len}",
+                range.start,
+                output.len()
+            );
+        }
+        Ok(output)
+    });
+
+    let mut output = Vec::with_capacity(chunks.iter().map(|(_, len)| len).sum());
+    for chunk in decompressed {
+        output.extend_from_slice(&chunk?);
+    }
+    Ok(output)
+}
+
+/// Decompresses data compressed by [`compress`](crate::compress::compress). Data compressed in
+/// chunks is decompressed on several threads with the `parallel` feature.
+#[cfg(feature = "std")]
 pub fn decompress(data: CompressedData) -> anyhow::Result<Vec<u8>> {
+    #[cfg(feature = "parallel")]
+    if data.algorithm != Algorithm::Raw
+        && let Some((index_start, chunks)) = chunk_index(&data.data)
+        && let Ok(output) = decompress_chunks(&data.data, index_start, &chunks)
+    {
+        return Ok(output);
+    }
+
     match data.algorithm {
         Algorithm::Deflate => {
             let mut decoder = DeflateDecoder::new(data.data.as_slice());
@@ -1,33 +1,82 @@
 pub mod character_definition;
 pub mod connection_cost_matrix;
+pub mod diff;
 pub mod metadata;
+pub mod okurigana;
 pub mod prefix_dictionary;
+pub mod reader;
+pub mod reading_index;
 pub mod schema;
 pub mod unknown_dictionary;
 
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::str;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
 use std::fs;
+#[cfg(feature = "std")]
 use std::path::Path;
-use std::str;
 
-use byteorder::{ByteOrder, LittleEndian};
-use once_cell::sync::Lazy;
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 use serde::{Deserialize, Serialize};
 
 use crate::LinderaResult;
+#[cfg(feature = "std")]
+use crate::builder::archive::read_archive;
 use crate::dictionary::character_definition::CharacterDefinition;
 use crate::dictionary::connection_cost_matrix::ConnectionCostMatrix;
+#[cfg(feature = "std")]
+use crate::dictionary::connection_cost_matrix::{
+    is_matrix_shard_file_name, matrix_shard_file_name,
+};
 use crate::dictionary::metadata::Metadata;
-use crate::dictionary::prefix_dictionary::PrefixDictionary;
+use crate::dictionary::prefix_dictionary::{PrefixDictionary, WordDetailsRecord};
 use crate::dictionary::unknown_dictionary::UnknownDictionary;
 use crate::error::LinderaErrorKind;
+#[cfg(feature = "std")]
+use crate::format::DICTIONARY_COMPONENTS;
+use crate::format::{check_format_version, strip_format_header};
+#[cfg(feature = "std")]
 use crate::loader::character_definition::CharacterDefinitionLoader;
+#[cfg(feature = "std")]
 use crate::loader::connection_cost_matrix::ConnectionCostMatrixLoader;
+#[cfg(feature = "std")]
 use crate::loader::metadata::MetadataLoader;
+#[cfg(feature = "std")]
 use crate::loader::prefix_dictionary::PrefixDictionaryLoader;
+#[cfg(feature = "std")]
 use crate::loader::unknown_dictionary::UnknownDictionaryLoader;
+use crate::viterbi::WordEntry;
 
-pub static UNK: Lazy<Vec<&str>> = Lazy::new(|| vec!["UNK"]);
+pub const UNK: &[&str] = &["UNK"];
+
+/// Binary components of a dictionary held in memory, e.g. embedded with `include_bytes!`.
+///
+/// Each field holds the contents of the file of the same name in a dictionary directory.
+/// The components must not be compressed, i.e. the dictionary must have been built without
+/// the `compress` feature.
+#[derive(Debug, Clone, Copy)]
+pub struct DictionaryBytes {
+    /// `metadata.json`
+    pub metadata: &'static [u8],
+    /// `char_def.bin`
+    pub char_def: &'static [u8],
+    /// `unk.bin`
+    pub unk: &'static [u8],
+    /// `matrix.mtx`
+    pub matrix: &'static [u8],
+    /// `dict.da`
+    pub da: &'static [u8],
+    /// `dict.vals`
+    pub vals: &'static [u8],
+    /// `dict.wordsidx`
+    pub words_idx: &'static [u8],
+    /// `dict.words`
+    pub words: &'static [u8],
+}
 
 #[derive(Clone)]
 pub struct Dictionary {
@@ -39,44 +88,99 @@ pub struct Dictionary {
 }
 
 impl Dictionary {
+    /// Returns the metadata of the dictionary, including its license and provenance.
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Returns the number of entries in the lexicon.
+    pub fn num_entries(&self) -> usize {
+        self.prefix_dictionary.words_idx_data.len() / 4
+    }
+
+    /// Returns `UNK` for every word: with the `no-details` feature, `dict.words` is not read.
+    #[cfg(feature = "no-details")]
+    pub fn word_details(&self, _word_id: usize) -> Vec<&str> {
+        UNK.to_vec()
+    }
+
+    /// Returns the details of a word of the lexicon, or an empty vector if the word id is out of
+    /// range. A record of `dict.words` that is truncated or not UTF-8 gives `UNK`.
+    #[cfg(not(feature = "no-details"))]
     pub fn word_details(&self, word_id: usize) -> Vec<&str> {
         if 4 * word_id >= self.prefix_dictionary.words_idx_data.len() {
-            return vec![];
+            return Vec::new();
         }
+        split_details(self.prefix_dictionary.word_details_record(word_id))
+    }
 
-        let idx: usize = match LittleEndian::read_u32(
-            &self.prefix_dictionary.words_idx_data[4 * word_id..][..4],
-        )
-        .try_into()
-        {
-            Ok(value) => value,
-            Err(_) => return UNK.to_vec(), // return empty vector if conversion fails
-        };
-        let data = &self.prefix_dictionary.words_data[idx..];
-        let joined_details_len: usize = match LittleEndian::read_u32(data).try_into() {
-            Ok(value) => value,
-            Err(_) => return UNK.to_vec(), // return empty vector if conversion fails
+    /// Returns the detail at `index` of `UNK`, the details of every word with the `no-details`
+    /// feature.
+    #[cfg(feature = "no-details")]
+    pub fn word_detail(&self, _word_id: usize, index: usize) -> Option<&str> {
+        UNK.get(index).copied()
+    }
+
+    /// Returns the detail at `index` of a word of the lexicon without splitting its other
+    /// details, or `None` if the word or the detail does not exist.
+    #[cfg(not(feature = "no-details"))]
+    pub fn word_detail(&self, word_id: usize, index: usize) -> Option<&str> {
+        detail_at(self.prefix_dictionary.word_details_record(word_id)?, index)
+    }
+
+    /// Returns every word of the lexicon with its surface, in the byte order of the surfaces
+    /// and the order of the words of a surface.
+    ///
+    /// The id of the word of an entry is the one [`word_details`](Self::word_details) takes.
+    pub fn words(&self) -> impl Iterator<Item = (String, WordEntry)> + use<> {
+        self.prefix_dictionary.entries().into_iter()
+    }
+
+    /// Returns the details of a word separated by NUL bytes, or `None` if the word does not
+    /// exist.
+    pub fn word_details_bytes(&self, word_id: usize) -> Option<Cow<'_, [u8]>> {
+        self.prefix_dictionary.word_details_bytes(word_id)
+    }
+
+    /// Load dictionary from its binary components held in memory
+    ///
+    /// The connection cost matrix and the lexicon are used in place without being copied.
+    /// This does not need the `std` feature.
+    pub fn from_bytes(bytes: &DictionaryBytes) -> LinderaResult<Self> {
+        let strip = |data: &'static [u8], file_name: &str| {
+            strip_format_header(data)
+                .map_err(|err| err.add_context(alloc::format!("Invalid file: {file_name}")))
         };
-        let joined_details_bytes: &[u8] =
-            &self.prefix_dictionary.words_data[idx + 4..idx + 4 + joined_details_len];
-
-        let mut details = Vec::new();
-        for bytes in joined_details_bytes.split(|&b| b == 0) {
-            let detail = match str::from_utf8(bytes) {
-                Ok(s) => s,
-                Err(_) => return UNK.to_vec(), // return empty vector if conversion fails
-            };
-            details.push(detail);
-        }
-        details
+
+        let metadata = Metadata::load(bytes.metadata)?;
+        check_format_version(metadata.format_version)?;
+
+        Ok(Dictionary {
+            prefix_dictionary: PrefixDictionary::load(
+                strip(bytes.da, "dict.da")?,
+                strip(bytes.vals, "dict.vals")?,
+                strip(bytes.words_idx, "dict.wordsidx")?,
+                strip(bytes.words, "dict.words")?,
+                true,
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(strip(bytes.matrix, "matrix.mtx")?)?,
+            character_definition: CharacterDefinition::load(strip(
+                bytes.char_def,
+                "char_def.bin",
+            )?)?,
+            unknown_dictionary: UnknownDictionary::load(strip(bytes.unk, "unk.bin")?)?,
+            metadata,
+        })
     }
 
     /// Load dictionary from a directory containing dictionary files
+    #[cfg(feature = "std")]
     pub fn load_from_path(dict_path: &Path) -> LinderaResult<Self> {
         Self::load_from_path_with_options(dict_path, false)
     }
 
     /// Load dictionary from a directory with options
+    #[cfg(feature = "std")]
     pub fn load_from_path_with_options(dict_path: &Path, use_mmap: bool) -> LinderaResult<Self> {
         // Verify that the dictionary directory exists
         if !dict_path.exists() {
@@ -95,6 +199,7 @@ impl Dictionary {
 
         // Load each component from the dictionary directory
         let metadata = MetadataLoader::load(dict_path)?;
+        check_format_version(metadata.format_version)?;
         let character_definition = CharacterDefinitionLoader::load(dict_path)?;
 
         let connection_cost_matrix = {
@@ -130,7 +235,99 @@ impl Dictionary {
         })
     }
 
+    /// Load dictionary from a zip, tar or tar.gz archive held in memory
+    ///
+    /// The archive contains the files of a built dictionary, at its top level or in a
+    /// directory (the shallowest directory containing `metadata.json` is used).
+    #[cfg(feature = "std")]
+    pub fn from_archive_bytes(data: &[u8]) -> LinderaResult<Self> {
+        let is_dictionary_file = |path: &str| {
+            let file_name = path.rsplit('/').next().unwrap_or(path);
+            file_name == "metadata.json"
+                || DICTIONARY_COMPONENTS.contains(&file_name)
+                || is_matrix_shard_file_name(file_name)
+        };
+        let files = read_archive(data, is_dictionary_file)?;
+
+        let split = |path: &str| match path.rsplit_once('/') {
+            Some((dir, file_name)) => (dir.to_string(), file_name.to_string()),
+            None => (String::new(), path.to_string()),
+        };
+        let dir = files
+            .iter()
+            .map(|(path, _)| split(path))
+            .filter(|(_, file_name)| file_name == "metadata.json")
+            .map(|(dir, _)| dir)
+            .min_by_key(|dir| {
+                (
+                    dir.matches('/').count() + usize::from(!dir.is_empty()),
+                    dir.clone(),
+                )
+            })
+            .ok_or_else(|| {
+                LinderaErrorKind::NotFound
+                    .with_error(anyhow::anyhow!("no metadata.json found in the archive"))
+            })?;
+        Self::from_files(files.into_iter().filter_map(|(path, content)| {
+            let (file_dir, file_name) = split(&path);
+            (file_dir == dir).then_some((file_name, content))
+        }))
+        .map_err(|err| err.add_context("Invalid dictionary archive"))
+    }
+
+    /// Load dictionary from the contents of the files of a dictionary directory held in memory,
+    /// by file name
+    ///
+    /// Other files are ignored.
+    #[cfg(feature = "std")]
+    pub fn from_files<I>(files: I) -> LinderaResult<Self>
+    where
+        I: IntoIterator<Item = (String, Vec<u8>)>,
+    {
+        let (shards, files): (HashMap<String, Vec<u8>>, HashMap<String, Vec<u8>>) = files
+            .into_iter()
+            .partition(|(file_name, _)| is_matrix_shard_file_name(file_name));
+        let file = |file_name: &str| {
+            files.get(file_name).map(Vec::as_slice).ok_or_else(|| {
+                LinderaErrorKind::NotFound.with_error(anyhow::anyhow!("{file_name} not found"))
+            })
+        };
+
+        let metadata: Metadata = serde_json::from_slice(file("metadata.json")?).map_err(|err| {
+            LinderaErrorKind::Deserialize
+                .with_error(anyhow::anyhow!(err))
+                .add_context("Failed to deserialize metadata.json file")
+        })?;
+        check_format_version(metadata.format_version)?;
+
+        let connection_cost_matrix = ConnectionCostMatrixLoader::load_from_bytes_with_shards(
+            file("matrix.mtx")?,
+            move |index| {
+                let file_name = matrix_shard_file_name(index);
+                shards.get(&file_name).cloned().ok_or_else(|| {
+                    LinderaErrorKind::NotFound.with_error(anyhow::anyhow!("{file_name} not found"))
+                })
+            },
+        )?;
+
+        Ok(Dictionary {
+            prefix_dictionary: PrefixDictionaryLoader::load_from_bytes(
+                file("dict.da")?,
+                file("dict.vals")?,
+                file("dict.wordsidx")?,
+                file("dict.words")?,
+            )?,
+            connection_cost_matrix,
+            character_definition: CharacterDefinitionLoader::load_from_bytes(file(
+                "char_def.bin",
+            )?)?,
+            unknown_dictionary: UnknownDictionaryLoader::load_from_bytes(file("unk.bin")?)?,
+            metadata,
+        })
+    }
+
     /// Save dictionary to a directory
+    #[cfg(feature = "std")]
     pub fn save_to_path(&self, dict_path: &Path) -> LinderaResult<()> {
         // Create directory if it doesn't exist
         fs::create_dir_all(dict_path)
@@ -151,35 +348,229 @@ pub struct UserDictionary {
 impl UserDictionary {
     pub fn load(user_dict_data: &[u8]) -> LinderaResult<UserDictionary> {
         let mut aligned = rkyv::util::AlignedVec::<16>::new();
-        aligned.extend_from_slice(user_dict_data);
+        aligned.extend_from_slice(strip_format_header(user_dict_data)?);
         rkyv::from_bytes::<UserDictionary, rkyv::rancor::Error>(&aligned).map_err(|err| {
             LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!(err.to_string()))
         })
     }
 
+    /// Returns the details of a user dictionary word, or `UNK` if the word id is out of range or
+    /// its record is truncated or not UTF-8.
     pub fn word_details(&self, word_id: usize) -> Vec<&str> {
-        if 4 * word_id >= self.dict.words_idx_data.len() {
-            return UNK.to_vec(); // return empty vector if conversion fails
+        split_details(self.dict.word_details_record(word_id))
+    }
+
+    /// Returns the detail at `index` of a user dictionary word without splitting its other
+    /// details, or `None` if the word or the detail does not exist.
+    pub fn word_detail(&self, word_id: usize, index: usize) -> Option<&str> {
+        detail_at(self.dict.word_details_record(word_id)?, index)
+    }
+}
+
+/// Splits the details of a word, or returns `UNK` if they are missing or not UTF-8.
+fn split_details(record: Option<WordDetailsRecord<'_>>) -> Vec<&str> {
+    record
+        .and_then(|record| {
+            record
+                .fields()
+                .map(|bytes| str::from_utf8(bytes).ok())
+                .collect()
+        })
+        .unwrap_or_else(|| UNK.to_vec())
+}
+
+/// Returns the detail at `index` of the details of a word.
+fn detail_at(record: WordDetailsRecord<'_>, index: usize) -> Option<&str> {
+    let bytes = record.fields().nth(index)?;
+    str::from_utf8(bytes).ok()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+
+    use super::*;
+    use crate::builder::DictionaryBuilder;
+    use crate::builder::tests::write_sources;
+
+    #[test]
+    fn test_from_archive_bytes() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let dict_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), dict_dir.path())
+            .unwrap();
+        let expected = Dictionary::load_from_path(dict_dir.path()).unwrap();
+
+        // tar.gz with the files in a directory
+        let mut tar_gz = GzEncoder::new(Vec::new(), Compression::default());
+        {
+            let mut builder = tar::Builder::new(&mut tar_gz);
+            builder
+                .append_dir_all("lindera-test", dict_dir.path())
+                .unwrap();
+            builder.finish().unwrap();
+        }
+        let tar_gz = tar_gz.finish().unwrap();
+
+        // zip with the files at the top level
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for entry in fs::read_dir(dict_dir.path()).unwrap() {
+            let path = entry.unwrap().path();
+            zip.start_file(
+                path.file_name().unwrap().to_string_lossy(),
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+            zip.write_all(&fs::read(&path).unwrap()).unwrap();
+        }
+        let zip = zip.finish().unwrap().into_inner();
+
+        for data in [tar_gz, zip] {
+            let dictionary = Dictionary::from_archive_bytes(&data).unwrap();
+            assert_eq!(dictionary.metadata.name, expected.metadata.name);
+            assert_eq!(dictionary.num_entries(), expected.num_entries());
+            assert_eq!(dictionary.word_details(0), expected.word_details(0));
+        }
+
+        let err = Dictionary::from_archive_bytes(b"not an archive")
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::Args);
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-details"))]
+    fn test_word_detail() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let dict_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), dict_dir.path())
+            .unwrap();
+        let dictionary = Dictionary::load_from_path(dict_dir.path()).unwrap();
+
+        for word_id in 0..dictionary.num_entries() {
+            let details = dictionary.word_details(word_id);
+            for (index, detail) in details.iter().enumerate() {
+                assert_eq!(Some(*detail), dictionary.word_detail(word_id, index));
+            }
+            assert_eq!(None, dictionary.word_detail(word_id, details.len()));
         }
-        let idx = LittleEndian::read_u32(&self.dict.words_idx_data[4 * word_id..][..4]);
-        let data = &self.dict.words_data[idx as usize..];
+        assert_eq!(None, dictionary.word_detail(dictionary.num_entries(), 0));
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-details"))]
+    fn test_word_details_truncated() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let dict_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), dict_dir.path())
+            .unwrap();
+        let mut dictionary = Dictionary::load_from_path(dict_dir.path()).unwrap();
+
+        // Cut the record of the last word in the middle of its details
+        let words = dictionary.prefix_dictionary.words_data.to_vec();
+        dictionary.prefix_dictionary.words_data =
+            crate::util::Data::Vec(words[..words.len() - 1].to_vec());
+        let last = dictionary.num_entries() - 1;
+        assert_eq!(UNK.to_vec(), dictionary.word_details(last));
+        assert_eq!(None, dictionary.word_detail(last, 0));
+        assert!(dictionary.word_details(dictionary.num_entries()).is_empty());
 
-        // Parse the data in the same format as main Dictionary
-        let joined_details_len: usize = match LittleEndian::read_u32(data).try_into() {
-            Ok(value) => value,
-            Err(_) => return UNK.to_vec(), // return empty vector if conversion fails
+        let user_dictionary = UserDictionary {
+            dict: dictionary.prefix_dictionary.clone(),
         };
-        let joined_details_bytes: &[u8] =
-            &self.dict.words_data[idx as usize + 4..idx as usize + 4 + joined_details_len];
-
-        let mut details = Vec::new();
-        for bytes in joined_details_bytes.split(|&b| b == 0) {
-            let detail = match str::from_utf8(bytes) {
-                Ok(s) => s,
-                Err(_) => return UNK.to_vec(), // return empty vector if conversion fails
-            };
-            details.push(detail);
+        assert_eq!(UNK.to_vec(), user_dictionary.word_details(last));
+        assert_eq!(UNK.to_vec(), user_dictionary.word_details(last + 1));
+        assert_ne!(UNK.to_vec(), user_dictionary.word_details(0));
+    }
+
+    #[test]
+    fn test_raw_components() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let dict_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), dict_dir.path())
+            .unwrap();
+        let dictionary = Dictionary::load_from_path(dict_dir.path()).unwrap();
+
+        let words: Vec<(String, WordEntry)> = dictionary.words().collect();
+        let surfaces: Vec<&str> = words.iter().map(|(surface, _)| surface.as_str()).collect();
+        assert_eq!(surfaces, vec!["京都", "東", "東京"]);
+        for (surface, entry) in &words {
+            let details = dictionary
+                .word_details_bytes(entry.word_id.id as usize)
+                .unwrap();
+            // The base form of the sources is the 7th detail
+            let reading = details.split(|&b| b == 0).nth(6).unwrap();
+            assert_eq!(reading, surface.as_bytes());
         }
-        details
+        assert_eq!(dictionary.word_details_bytes(words.len()), None);
+
+        let matrix = &dictionary.connection_cost_matrix;
+        assert_eq!(matrix.size(), (1, 1));
+        assert_eq!(matrix.raw_costs(), Some(&[0i16][..]));
+
+        let kanji = dictionary
+            .character_definition
+            .category_id_by_name("KANJI")
+            .unwrap();
+        let ranges: Vec<(u32, u32)> = dictionary
+            .character_definition
+            .category_ranges()
+            .filter(|(_, _, categories)| categories.contains(&kanji))
+            .map(|(start, end, _)| (start, end))
+            .collect();
+        assert_eq!(ranges, vec![(0x4E00, 0x9FAF)]);
+    }
+
+    #[cfg(not(feature = "compress"))]
+    #[test]
+    fn test_from_bytes() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let dict_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), dict_dir.path())
+            .unwrap();
+        let expected = Dictionary::load_from_path(dict_dir.path()).unwrap();
+
+        let file = |file_name: &str| -> &'static [u8] {
+            fs::read(dict_dir.path().join(file_name)).unwrap().leak()
+        };
+        let bytes = DictionaryBytes {
+            metadata: file("metadata.json"),
+            char_def: file("char_def.bin"),
+            unk: file("unk.bin"),
+            matrix: file("matrix.mtx"),
+            da: file("dict.da"),
+            vals: file("dict.vals"),
+            words_idx: file("dict.wordsidx"),
+            words: file("dict.words"),
+        };
+        let dictionary = Dictionary::from_bytes(&bytes).unwrap();
+        assert_eq!(dictionary.metadata.name, expected.metadata.name);
+        assert_eq!(dictionary.num_entries(), expected.num_entries());
+        assert_eq!(dictionary.word_details(0), expected.word_details(0));
+        assert!(matches!(
+            dictionary.prefix_dictionary.words_data,
+            crate::util::Data::Static(_)
+        ));
+
+        let err = Dictionary::from_bytes(&DictionaryBytes {
+            metadata: b"not json",
+            ..bytes
+        })
+        .err()
+        .unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::Deserialize);
     }
 }
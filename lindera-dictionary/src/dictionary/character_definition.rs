@@ -1,3 +1,6 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 use serde::{Deserialize, Serialize};
 
@@ -59,6 +62,22 @@ impl<T: Copy + Clone> LookupTable<T> {
             .unwrap_or_else(|val| val - 1);
         &self.values[idx][..]
     }
+
+    /// Returns the ranges of the table with their values: the first and the last code point of
+    /// each range, the last one ending at `u32::MAX`.
+    pub fn ranges(&self) -> impl Iterator<Item = (u32, u32, &[T])> + '_ {
+        self.boundaries
+            .iter()
+            .zip(&self.values)
+            .enumerate()
+            .map(|(i, (&start, values))| {
+                let end = self
+                    .boundaries
+                    .get(i + 1)
+                    .map_or(u32::MAX, |&next| next - 1);
+                (start, end, &values[..])
+            })
+    }
 }
 
 impl<T: Copy + Clone + Archive> ArchivedLookupTable<T> {
@@ -111,6 +130,12 @@ impl CharacterDefinition {
     pub fn lookup_categories(&self, c: char) -> &[CategoryId] {
         self.mapping.eval(c as u32)
     }
+
+    /// Returns the table of the categories of the characters: the first and the last code point
+    /// of each range with the categories of its characters.
+    pub fn category_ranges(&self) -> impl Iterator<Item = (u32, u32, &[CategoryId])> + '_ {
+        self.mapping.ranges()
+    }
 }
 
 impl ArchivedCharacterDefinition {
@@ -133,6 +158,9 @@ impl ArchivedCharacterDefinition {
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
     use crate::dictionary::character_definition::LookupTable;
 
     #[test]
@@ -1,46 +1,324 @@
+use alloc::format;
+#[cfg(feature = "std")]
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+use crate::LinderaResult;
+use crate::dictionary::metadata::MatrixEncoding;
+use crate::dictionary::reader::ByteReader;
+use crate::error::LinderaErrorKind;
 use crate::util::Data;
 
 use byteorder::{ByteOrder, LittleEndian};
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 
+/// The first value of a `matrix.mtx` whose costs are split into shards, followed by the forward
+/// size, the backward size and the number of backward ids of each shard.
+pub const SHARDED_MATRIX_FLAG: i16 = -2;
+
+/// The first value of a `matrix.mtx` whose costs are quantized, followed by the forward size,
+/// the backward size and the [`QuantizedCosts`] of the backward ids.
+pub const QUANTIZED_MATRIX_FLAG: i16 = -3;
+
+/// The code of a quantized cost that stands for `i16::MAX`, the cost of the connections that are
+/// missing from `matrix.def`, so that they keep their cost.
+const MAX_COST_CODE: u8 = u8::MAX;
+
+/// Costs quantized to a byte each.
+///
+/// The costs of a backward id are `bias + code * step`, with the bias and the step of the
+/// backward id: the bias is the lowest cost and the step the range of the costs divided by 254,
+/// so that a cost is off by half a step at most. Encoded, each backward id has its bias (`i16`)
+/// and its step (`u16`), followed by the codes in the layout of `matrix.mtx`.
+#[derive(Clone, Default)]
+pub struct QuantizedCosts {
+    biases: Vec<i16>,
+    steps: Vec<u16>,
+    codes: Vec<u8>,
+}
+
+impl QuantizedCosts {
+    /// Encodes the costs of the backward ids, `forward_size` costs each.
+    pub fn encode(costs: &[i16], forward_size: u32) -> Vec<u8> {
+        let forward_size = forward_size.max(1) as usize;
+        let rows = costs.len() / forward_size;
+        let mut header = Vec::with_capacity(rows * 4);
+        let mut codes = Vec::with_capacity(costs.len());
+        for row in costs.chunks(forward_size) {
+            let known = row.iter().copied().filter(|&cost| cost != i16::MAX);
+            let (min, max) = known.fold((i32::MAX, i32::MIN), |(min, max), cost| {
+                (min.min(cost as i32), max.max(cost as i32))
+            });
+            let (bias, step) = if min > max {
+                (0, 1)
+            } else {
+                (
+                    min,
+                    ((max - min) as u32)
+                        .div_ceil(MAX_COST_CODE as u32 - 1)
+                        .max(1),
+                )
+            };
+            header.extend_from_slice(&(bias as i16).to_le_bytes());
+            header.extend_from_slice(&(step as u16).to_le_bytes());
+            codes.extend(row.iter().map(|&cost| {
+                if cost == i16::MAX {
+                    MAX_COST_CODE
+                } else {
+                    let code = ((cost as i32 - bias) as u32 + step / 2) / step;
+                    code.min(MAX_COST_CODE as u32 - 1) as u8
+                }
+            }));
+        }
+        header.extend_from_slice(&codes);
+        header
+    }
+
+    /// Decodes the costs of `rows` backward ids, `forward_size` costs each.
+    fn decode(reader: &mut ByteReader, forward_size: u32, rows: u32) -> LinderaResult<Self> {
+        let mut biases = Vec::with_capacity(rows as usize);
+        let mut steps = Vec::with_capacity(rows as usize);
+        for _ in 0..rows {
+            biases.push(reader.read_i16()?);
+            steps.push(reader.read_u16()?);
+        }
+        let codes = reader
+            .read_bytes(forward_size as usize * rows as usize)?
+            .to_vec();
+        Ok(Self {
+            biases,
+            steps,
+            codes,
+        })
+    }
+
+    #[inline]
+    fn cost(&self, forward_size: u32, forward_id: u32, backward_id: u32) -> i32 {
+        let code = self.codes[(forward_id + backward_id * forward_size) as usize];
+        if code == MAX_COST_CODE {
+            return i16::MAX as i32;
+        }
+        let backward_id = backward_id as usize;
+        self.biases[backward_id] as i32 + code as i32 * self.steps[backward_id] as i32
+    }
+}
+
+/// Returns the name of the file of the shard `index` of a sharded matrix.
+///
+/// The shard `index` holds the costs of the backward ids (the left ids of the words) from
+/// `index * shard_size` of the matrix.
+#[cfg(feature = "std")]
+pub fn matrix_shard_file_name(index: usize) -> String {
+    format!("matrix.{index}.mtx")
+}
+
+/// Returns whether `file_name` is the name of a shard of a sharded matrix.
+pub fn is_matrix_shard_file_name(file_name: &str) -> bool {
+    file_name
+        .strip_prefix("matrix.")
+        .and_then(|name| name.strip_suffix(".mtx"))
+        .is_some_and(|index| !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Reads the header of a sharded `matrix.mtx`: the forward size, the backward size, the shard
+/// size and the encoding of the shards. Returns `None` if the matrix is not sharded.
+pub fn read_sharded_matrix_header(
+    data: &[u8],
+) -> LinderaResult<Option<(u32, u32, u32, MatrixEncoding)>> {
+    let mut reader = ByteReader::new(data, "matrix.mtx");
+    if data.len() < 2 || reader.read_i16()? != SHARDED_MATRIX_FLAG {
+        return Ok(None);
+    }
+    let (forward_size, backward_size, shard_size) =
+        (reader.read_i16()?, reader.read_i16()?, reader.read_i16()?);
+    if forward_size < 0 || backward_size < 0 || shard_size <= 0 {
+        return Err(reader.error(format!(
+            "invalid sharded matrix size {forward_size}x{backward_size} in shards of {shard_size}"
+        )));
+    }
+    // The encoding was added after the shards, and they are plain without it
+    let encoding = match reader.remaining().len() {
+        0 => MatrixEncoding::Plain,
+        _ => match reader.read_i16()? {
+            0 => MatrixEncoding::Plain,
+            1 => MatrixEncoding::Quantized,
+            encoding => return Err(reader.error(format!("unknown matrix encoding {encoding}"))),
+        },
+    };
+    Ok(Some((
+        forward_size as u32,
+        backward_size as u32,
+        shard_size as u32,
+        encoding,
+    )))
+}
+
+/// Reads the decoded data of a shard of a sharded matrix, from its index.
+#[cfg(feature = "std")]
+pub type ShardReader = dyn Fn(usize) -> LinderaResult<Vec<u8>> + Send + Sync;
+
+/// The costs of a shard, in the encoding of the matrix.
+#[cfg(feature = "std")]
+enum ShardCosts {
+    Plain(Vec<i16>),
+    Quantized(QuantizedCosts),
+}
+
+/// The shards of a sharded matrix, each read the first time one of its costs is looked up.
+#[cfg(feature = "std")]
+struct MatrixShards {
+    forward_size: u32,
+    backward_size: u32,
+    shard_size: u32,
+    encoding: MatrixEncoding,
+    shards: Vec<OnceLock<ShardCosts>>,
+    read_shard: Box<ShardReader>,
+}
+
+#[cfg(feature = "std")]
+impl MatrixShards {
+    #[inline]
+    fn cost(&self, forward_id: u32, backward_id: u32) -> i32 {
+        let index = (backward_id / self.shard_size) as usize;
+        let costs = match self.shards[index].get() {
+            Some(costs) => costs,
+            // Every shard was decoded when the matrix was created, and is read again the same
+            None => self.load(index).unwrap_or_else(|err| {
+                unreachable!("{} was checked: {err}", matrix_shard_file_name(index))
+            }),
+        };
+        let row = backward_id % self.shard_size;
+        match costs {
+            ShardCosts::Plain(costs) => {
+                costs[(forward_id + row * self.forward_size) as usize] as i32
+            }
+            ShardCosts::Quantized(costs) => costs.cost(self.forward_size, forward_id, row),
+        }
+    }
+
+    fn load(&self, index: usize) -> LinderaResult<&ShardCosts> {
+        if let Some(costs) = self.shards[index].get() {
+            return Ok(costs);
+        }
+        let costs = self.decode(index)?;
+        // Another thread may have loaded the shard meanwhile, with the same costs
+        Ok(self.shards[index].get_or_init(|| costs))
+    }
+
+    fn decode(&self, index: usize) -> LinderaResult<ShardCosts> {
+        let data = (self.read_shard)(index)?;
+        let first = index as u32 * self.shard_size;
+        let rows = self.shard_size.min(self.backward_size - first);
+        let size = (rows * self.forward_size) as usize;
+        let expected_len = match self.encoding {
+            MatrixEncoding::Plain => size * 2,
+            MatrixEncoding::Quantized => rows as usize * 4 + size,
+        };
+        if data.len() != expected_len {
+            return Err(LinderaErrorKind::Content.with_error(anyhow::anyhow!(
+                "{} has {} bytes instead of the {size} costs of the backward ids from {first}",
+                matrix_shard_file_name(index),
+                data.len()
+            )));
+        }
+        let costs = match self.encoding {
+            MatrixEncoding::Plain => {
+                let mut costs = vec![0i16; size];
+                LittleEndian::read_i16_into(&data, &mut costs);
+                ShardCosts::Plain(costs)
+            }
+            MatrixEncoding::Quantized => {
+                let mut reader = ByteReader::new(&data, "matrix shard");
+                ShardCosts::Quantized(QuantizedCosts::decode(
+                    &mut reader,
+                    self.forward_size,
+                    rows,
+                )?)
+            }
+        };
+        Ok(costs)
+    }
+}
+
 #[derive(Clone, Archive, RkyvSerialize, RkyvDeserialize)]
 pub struct ConnectionCostMatrix {
     /// The connection cost matrix data.
     /// Previously, this was `Data` (byte array) and costs were read using `LittleEndian::read_i16` at runtime.
     /// Changed to `Vec<i16>` to enable direct array indexing and avoid deserialization overhead during tokenization.
+    /// It is empty for a sharded or quantized matrix, whose costs are in its shards or quantized.
     pub costs_data: Vec<i16>,
     pub backward_size: u32,
     pub forward_size: u32,
+    #[rkyv(with = rkyv::with::Skip)]
+    quantized: Option<QuantizedCosts>,
+    #[cfg(feature = "std")]
+    #[rkyv(with = rkyv::with::Skip)]
+    shards: Option<Arc<MatrixShards>>,
 }
 
 impl ConnectionCostMatrix {
-    pub fn load(conn_data: impl Into<Data>) -> ConnectionCostMatrix {
+    /// Loads a matrix from the bytes of `matrix.mtx`, in the transposed format, the quantized one
+    /// or the old one.
+    ///
+    /// Returns an error if the header is invalid or the data is shorter than the matrix.
+    pub fn load(conn_data: impl Into<Data>) -> LinderaResult<ConnectionCostMatrix> {
         let conn_data = conn_data.into();
-        let first_v = LittleEndian::read_i16(&conn_data[0..2]);
-
-        if first_v == -1 {
-            // New format (transposed)
-            let forward_size = LittleEndian::read_i16(&conn_data[2..4]) as u32;
-            let backward_size = LittleEndian::read_i16(&conn_data[4..6]) as u32;
-            let size = conn_data.len() / 2 - 3;
-            let mut costs_data = vec![0i16; size];
-            LittleEndian::read_i16_into(&conn_data[6..], &mut costs_data);
-
-            ConnectionCostMatrix {
-                costs_data,
-                backward_size,
-                forward_size,
+        let mut reader = ByteReader::new(&conn_data, "matrix.mtx");
+        let first_v = reader.read_i16()?;
+        if first_v == SHARDED_MATRIX_FLAG {
+            return Err(LinderaErrorKind::Content.with_error(anyhow::anyhow!(
+                "the costs of matrix.mtx are split into shards, which are only loaded from the \
+                 directory or an archive of the dictionary"
+            )));
+        }
+
+        if first_v == QUANTIZED_MATRIX_FLAG {
+            let (forward_size, backward_size) = (reader.read_i16()?, reader.read_i16()?);
+            if forward_size < 0 || backward_size < 0 {
+                return Err(reader.error(format!(
+                    "invalid matrix size {forward_size}x{backward_size}"
+                )));
             }
+            let quantized =
+                QuantizedCosts::decode(&mut reader, forward_size as u32, backward_size as u32)?;
+            return Ok(ConnectionCostMatrix {
+                costs_data: Vec::new(),
+                backward_size: backward_size as u32,
+                forward_size: forward_size as u32,
+                quantized: Some(quantized),
+                #[cfg(feature = "std")]
+                shards: None,
+            });
+        }
+
+        // The new format starts with -1 and is transposed
+        let transposed = first_v == -1;
+        let (forward_size, backward_size) = if transposed {
+            (reader.read_i16()?, reader.read_i16()?)
         } else {
-            // Old format
-            let forward_size = first_v as u32;
-            let backward_size = LittleEndian::read_i16(&conn_data[2..4]) as u32;
-            let size = conn_data.len() / 2 - 2;
-            let mut old_costs_data = vec![0i16; size];
-            LittleEndian::read_i16_into(&conn_data[4..], &mut old_costs_data);
+            (first_v, reader.read_i16()?)
+        };
+        if forward_size < 0 || backward_size < 0 {
+            return Err(reader.error(format!(
+                "invalid matrix size {forward_size}x{backward_size}"
+            )));
+        }
+        let forward_size = forward_size as u32;
+        let backward_size = backward_size as u32;
+
+        let size = forward_size as usize * backward_size as usize;
+        let mut costs_data = vec![0i16; size];
+        LittleEndian::read_i16_into(reader.read_bytes(size * 2)?, &mut costs_data);
 
+        if !transposed {
             // Transpose to new layout in memory
-            let mut costs_data = vec![0i16; size];
+            let old_costs_data = costs_data;
+            costs_data = vec![0i16; size];
             for f in 0..forward_size {
                 for b in 0..backward_size {
                     let old_id = (b + f * backward_size) as usize;
@@ -48,31 +326,163 @@ impl ConnectionCostMatrix {
                     costs_data[new_id] = old_costs_data[old_id];
                 }
             }
+        }
+
+        Ok(ConnectionCostMatrix {
+            costs_data,
+            backward_size,
+            forward_size,
+            quantized: None,
+            #[cfg(feature = "std")]
+            shards: None,
+        })
+    }
+
+    /// Creates a matrix whose costs are split into shards of `shard_size` backward ids, as
+    /// written by the builder with `matrix_shard_size`.
+    ///
+    /// `read_shard` reads the decoded data of a shard: the little-endian costs of its backward
+    /// ids in the layout of `matrix.mtx`, or their [`QuantizedCosts`] with the quantized
+    /// encoding. It must return the same data every time it is called.
+    ///
+    /// Every shard is read and decoded once here, so that a missing or invalid shard is returned
+    /// as an error, and dropped. A shard is then read and decoded again the first time one of
+    /// its costs is looked up, so the shards that are never used take no memory.
+    #[cfg(feature = "std")]
+    pub fn from_shards(
+        forward_size: u32,
+        backward_size: u32,
+        shard_size: u32,
+        encoding: MatrixEncoding,
+        read_shard: Box<ShardReader>,
+    ) -> LinderaResult<ConnectionCostMatrix> {
+        if shard_size == 0 {
+            return Err(LinderaErrorKind::Args
+                .with_error(anyhow::anyhow!("the shard size of a matrix must not be 0")));
+        }
+        let shard_count = backward_size.div_ceil(shard_size) as usize;
+        let shards = MatrixShards {
+            forward_size,
+            backward_size,
+            shard_size,
+            encoding,
+            shards: (0..shard_count).map(|_| OnceLock::new()).collect(),
+            read_shard,
+        };
+        for index in 0..shard_count {
+            shards.decode(index).map_err(|err| {
+                err.add_context(format!("Invalid shard: {}", matrix_shard_file_name(index)))
+            })?;
+        }
+        Ok(ConnectionCostMatrix {
+            costs_data: Vec::new(),
+            backward_size,
+            forward_size,
+            quantized: None,
+            shards: Some(Arc::new(shards)),
+        })
+    }
 
-            ConnectionCostMatrix {
-                costs_data,
-                backward_size,
-                forward_size,
+    /// Returns the encoding of the costs.
+    pub fn encoding(&self) -> MatrixEncoding {
+        #[cfg(feature = "std")]
+        if let Some(shards) = &self.shards {
+            return shards.encoding;
+        }
+        if self.quantized.is_some() {
+            MatrixEncoding::Quantized
+        } else {
+            MatrixEncoding::Plain
+        }
+    }
+
+    /// Returns the number of forward ids (the right ids of the words) and of backward ids (the
+    /// left ids of the words) of the matrix.
+    pub fn size(&self) -> (u32, u32) {
+        (self.forward_size, self.backward_size)
+    }
+
+    /// Returns the costs as they are held in memory, at `forward_id + backward_id * forward_size`.
+    ///
+    /// Returns `None` for a quantized or sharded matrix, whose costs are not held as they are;
+    /// read them with [`cost`](Self::cost).
+    pub fn raw_costs(&self) -> Option<&[i16]> {
+        if self.quantized.is_some() || self.is_sharded() {
+            return None;
+        }
+        Some(&self.costs_data)
+    }
+
+    /// Returns whether the costs are split into shards.
+    pub fn is_sharded(&self) -> bool {
+        #[cfg(feature = "std")]
+        return self.shards.is_some();
+        #[cfg(not(feature = "std"))]
+        false
+    }
+
+    /// Returns the number of shards and the number of them that are loaded, `(1, 1)` for a
+    /// matrix that is not sharded.
+    pub fn loaded_shards(&self) -> (usize, usize) {
+        #[cfg(feature = "std")]
+        if let Some(shards) = &self.shards {
+            let loaded = shards.shards.iter().filter(|shard| shard.get().is_some());
+            return (shards.shards.len(), loaded.count());
+        }
+        (1, 1)
+    }
+
+    /// Loads the shards of the backward ids `backward_ids` ahead of their use, e.g. the hot
+    /// shards at startup, so that they are not decoded during tokenization.
+    ///
+    /// Does nothing for a matrix that is not sharded.
+    pub fn load_shards(&self, backward_ids: core::ops::Range<u32>) -> LinderaResult<()> {
+        #[cfg(feature = "std")]
+        if let Some(shards) = &self.shards {
+            let end = backward_ids.end.min(self.backward_size);
+            if backward_ids.start < end {
+                let first = backward_ids.start / shards.shard_size;
+                let last = (end - 1) / shards.shard_size;
+                for index in first..=last {
+                    shards.load(index as usize)?;
+                }
             }
         }
+        #[cfg(not(feature = "std"))]
+        let _ = backward_ids;
+        Ok(())
     }
 
+    /// Returns the connection cost from a word with the right id `forward_id` to a word with
+    /// the left id `backward_id`.
+    ///
+    /// For a sharded matrix, the shard of `backward_id` is decoded if it is not loaded yet. It
+    /// cannot fail, since every shard was checked when the matrix was created.
     #[inline]
     pub fn cost(&self, forward_id: u32, backward_id: u32) -> i32 {
         let cost_id = (forward_id + backward_id * self.forward_size) as usize;
-        self.costs_data[cost_id] as i32
+        // The costs of a quantized or sharded matrix are not in `costs_data`, which is empty,
+        // so that the plain costs are looked up with the bounds check only
+        match self.costs_data.get(cost_id) {
+            Some(&cost) => cost as i32,
+            None => self.encoded_cost(forward_id, backward_id, cost_id),
+        }
     }
-}
 
-impl ArchivedConnectionCostMatrix {
     #[inline]
-    pub fn cost(&self, forward_id: u32, backward_id: u32) -> i32 {
-        let cost_id = (forward_id + backward_id * self.forward_size) as usize;
-        self.costs_data[cost_id].to_native() as i32
+    fn encoded_cost(&self, forward_id: u32, backward_id: u32, cost_id: usize) -> i32 {
+        #[cfg(feature = "std")]
+        if let Some(shards) = &self.shards {
+            return shards.cost(forward_id, backward_id);
+        }
+        if let Some(quantized) = &self.quantized {
+            return quantized.cost(self.forward_size, forward_id, backward_id);
+        }
+        self.costs_data[cost_id] as i32
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
     use byteorder::{LittleEndian, WriteBytesExt};
@@ -92,7 +502,7 @@ mod tests {
         data.write_i16::<LittleEndian>(14).unwrap();
         data.write_i16::<LittleEndian>(15).unwrap();
 
-        let matrix = ConnectionCostMatrix::load(data);
+        let matrix = ConnectionCostMatrix::load(data).unwrap();
         assert_eq!(matrix.forward_size, 2);
         assert_eq!(matrix.backward_size, 3);
         assert_eq!(matrix.cost(0, 0), 10);
@@ -117,7 +527,7 @@ mod tests {
         data.write_i16::<LittleEndian>(13).unwrap();
         data.write_i16::<LittleEndian>(15).unwrap();
 
-        let matrix = ConnectionCostMatrix::load(data);
+        let matrix = ConnectionCostMatrix::load(data).unwrap();
         assert_eq!(matrix.forward_size, 2);
         assert_eq!(matrix.backward_size, 3);
         assert_eq!(matrix.cost(0, 0), 10);
@@ -127,4 +537,122 @@ mod tests {
         assert_eq!(matrix.cost(0, 2), 14);
         assert_eq!(matrix.cost(1, 2), 15);
     }
+
+    #[test]
+    fn test_from_shards() {
+        // The costs of the old format test, in shards of 2 backward ids
+        let shards = [vec![10i16, 11, 12, 13], vec![14, 15]];
+        let matrix = ConnectionCostMatrix::from_shards(
+            2,
+            3,
+            2,
+            MatrixEncoding::Plain,
+            Box::new(move |index| {
+                Ok(shards[index]
+                    .iter()
+                    .flat_map(|cost| cost.to_le_bytes())
+                    .collect())
+            }),
+        )
+        .unwrap();
+        assert!(matrix.is_sharded());
+        assert_eq!((2, 0), matrix.loaded_shards());
+        assert_eq!(matrix.cost(1, 2), 15);
+        assert_eq!((2, 1), matrix.loaded_shards());
+        assert_eq!(matrix.cost(0, 0), 10);
+        assert_eq!(matrix.cost(1, 0), 11);
+        assert_eq!(matrix.cost(0, 1), 12);
+        assert_eq!(matrix.cost(1, 1), 13);
+        assert_eq!(matrix.cost(0, 2), 14);
+
+        // A shard of the wrong size is reported when the matrix is created
+        assert!(
+            ConnectionCostMatrix::from_shards(
+                2,
+                3,
+                2,
+                MatrixEncoding::Plain,
+                Box::new(|_| Ok(vec![0; 6])),
+            )
+            .is_err()
+        );
+
+        assert!(
+            ConnectionCostMatrix::from_shards(
+                2,
+                3,
+                0,
+                MatrixEncoding::Plain,
+                Box::new(|_| Ok(Vec::new()))
+            )
+            .is_err()
+        );
+
+        // A sharded matrix.mtx cannot be loaded without its shards
+        let mut data = Vec::new();
+        for value in [SHARDED_MATRIX_FLAG, 2, 3, 2] {
+            data.write_i16::<LittleEndian>(value).unwrap();
+        }
+        assert_eq!(
+            Some((2, 3, 2, MatrixEncoding::Plain)),
+            read_sharded_matrix_header(&data).unwrap()
+        );
+        assert!(ConnectionCostMatrix::load(data).is_err());
+        assert!(is_matrix_shard_file_name("matrix.12.mtx"));
+        assert!(!is_matrix_shard_file_name("matrix.mtx"));
+        assert!(!is_matrix_shard_file_name("matrix..mtx"));
+    }
+
+    #[test]
+    fn test_load_quantized() {
+        // [forward_id + backward_id * forward_size], with a missing connection
+        let costs = [-300, 0, 700, 5, 5, i16::MAX];
+        let mut data = Vec::new();
+        for value in [QUANTIZED_MATRIX_FLAG, 3, 2] {
+            data.write_i16::<LittleEndian>(value).unwrap();
+        }
+        data.extend(QuantizedCosts::encode(&costs, 3));
+        assert_eq!(6 + 2 * 4 + 6, data.len());
+
+        let matrix = ConnectionCostMatrix::load(data.clone()).unwrap();
+        assert_eq!(MatrixEncoding::Quantized, matrix.encoding());
+        assert!(matrix.costs_data.is_empty());
+        assert_eq!(matrix.forward_size, 3);
+        assert_eq!(matrix.backward_size, 2);
+        // A step of 4 for the first backward id, and exact costs for the second one
+        assert_eq!(matrix.cost(0, 0), -300);
+        assert!(matrix.cost(1, 0).abs() <= 2);
+        assert!((matrix.cost(2, 0) - 700).abs() <= 2);
+        assert_eq!(matrix.cost(0, 1), 5);
+        assert_eq!(matrix.cost(1, 1), 5);
+        assert_eq!(matrix.cost(2, 1), i16::MAX as i32);
+
+        // One code short
+        data.pop();
+        assert!(ConnectionCostMatrix::load(data).is_err());
+    }
+
+    #[test]
+    fn test_load_corrupted() {
+        let mut data = Vec::new();
+        data.write_i16::<LittleEndian>(-1).unwrap(); // version
+        data.write_i16::<LittleEndian>(2).unwrap(); // forward_size
+        data.write_i16::<LittleEndian>(3).unwrap(); // backward_size
+        for cost in 10..15 {
+            data.write_i16::<LittleEndian>(cost).unwrap();
+        }
+        // One cost short
+        assert!(ConnectionCostMatrix::load(data.clone()).is_err());
+
+        data.write_i16::<LittleEndian>(15).unwrap();
+        assert!(ConnectionCostMatrix::load(data.clone()).is_ok());
+
+        // A negative size
+        data[2] = 0xfe;
+        data[3] = 0xff;
+        assert!(ConnectionCostMatrix::load(data).is_err());
+
+        assert!(ConnectionCostMatrix::load(Vec::new()).is_err());
+        assert!(ConnectionCostMatrix::load(vec![0xff, 0xff, 0x02]).is_err());
+    }
 }
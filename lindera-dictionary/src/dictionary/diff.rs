@@ -0,0 +1,245 @@
+//! Comparison of the lexicons and connection cost matrices of two dictionaries.
+//!
+//! The entries of the dictionaries are matched by their surface and details: an entry found in
+//! both with other context IDs or another cost is changed, and the others are added or removed.
+//! A surface with several entries of the same details, which only differ by their context IDs
+//! or cost, has its entries paired in the order of their word IDs.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde::Serialize;
+
+use crate::dictionary::Dictionary;
+
+/// An entry of a dictionary, with its details.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffEntry {
+    pub surface: String,
+    pub left_id: u16,
+    pub right_id: u16,
+    pub word_cost: i16,
+    pub details: Vec<String>,
+}
+
+/// An entry whose context IDs or cost differ between two dictionaries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangedEntry {
+    pub surface: String,
+    pub details: Vec<String>,
+    /// `(left_id, right_id, word_cost)` in the old dictionary.
+    pub old: (u16, u16, i16),
+    /// `(left_id, right_id, word_cost)` in the new dictionary.
+    pub new: (u16, u16, i16),
+}
+
+impl ChangedEntry {
+    /// The change of the cost, negative if the entry is preferred more in the new dictionary.
+    pub fn cost_delta(&self) -> i32 {
+        i32::from(self.new.2) - i32::from(self.old.2)
+    }
+}
+
+/// The differences between two dictionaries, in the order of the surfaces.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DictionaryDiff {
+    pub added: Vec<DiffEntry>,
+    pub removed: Vec<DiffEntry>,
+    pub changed: Vec<ChangedEntry>,
+    /// `(forward_size, backward_size)` of the old and the new connection cost matrix.
+    pub old_matrix_size: (u32, u32),
+    pub new_matrix_size: (u32, u32),
+    /// The number of connection costs that differ, when the matrices have the same size.
+    pub changed_connection_costs: Option<usize>,
+}
+
+impl DictionaryDiff {
+    /// Whether the lexicons and the connection cost matrices are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.old_matrix_size == self.new_matrix_size
+            && self.changed_connection_costs == Some(0)
+    }
+
+    /// The mean change of the cost of the changed entries.
+    pub fn mean_cost_delta(&self) -> f64 {
+        if self.changed.is_empty() {
+            return 0.0;
+        }
+        let total: i64 = self
+            .changed
+            .iter()
+            .map(|entry| i64::from(entry.cost_delta()))
+            .sum();
+        total as f64 / self.changed.len() as f64
+    }
+}
+
+type EntryKey = (String, Vec<String>);
+type EntryValue = (u16, u16, i16);
+
+/// The entries of a dictionary by surface and details, in the order of their word IDs.
+fn entries_by_key(dictionary: &Dictionary) -> BTreeMap<EntryKey, Vec<EntryValue>> {
+    let mut entries = dictionary.prefix_dictionary.entries();
+    entries.sort_by_key(|(_, entry)| entry.word_id.id);
+
+    let mut by_key: BTreeMap<EntryKey, Vec<EntryValue>> = BTreeMap::new();
+    for (surface, entry) in entries {
+        let details = dictionary
+            .prefix_dictionary
+            .word_details_bytes(entry.word_id.id as usize)
+            .filter(|details| !details.is_empty())
+            .map(|details| {
+                details
+                    .split(|&b| b == 0)
+                    .map(|detail| String::from_utf8_lossy(detail).into_owned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        by_key.entry((surface, details)).or_default().push((
+            entry.left_id,
+            entry.right_id,
+            entry.word_cost,
+        ));
+    }
+    by_key
+}
+
+/// Compares the dictionary `new` with the dictionary `old`.
+pub fn diff_dictionaries(old: &Dictionary, new: &Dictionary) -> DictionaryDiff {
+    let mut old_entries = entries_by_key(old);
+    let mut new_entries = entries_by_key(new);
+
+    let mut diff = DictionaryDiff {
+        old_matrix_size: (
+            old.connection_cost_matrix.forward_size,
+            old.connection_cost_matrix.backward_size,
+        ),
+        new_matrix_size: (
+            new.connection_cost_matrix.forward_size,
+            new.connection_cost_matrix.backward_size,
+        ),
+        ..DictionaryDiff::default()
+    };
+    if diff.old_matrix_size == diff.new_matrix_size {
+        // The costs are looked up one by one, since the matrices may be sharded
+        let (forward_size, backward_size) = diff.old_matrix_size;
+        diff.changed_connection_costs = Some(
+            (0..backward_size)
+                .flat_map(|backward_id| {
+                    (0..forward_size).map(move |forward_id| (forward_id, backward_id))
+                })
+                .filter(|&(forward_id, backward_id)| {
+                    old.connection_cost_matrix.cost(forward_id, backward_id)
+                        != new.connection_cost_matrix.cost(forward_id, backward_id)
+                })
+                .count(),
+        );
+    }
+
+    let keys = old_entries
+        .keys()
+        .chain(new_entries.keys())
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    for key in keys {
+        let mut removed = old_entries.remove(&key).unwrap_or_default();
+        let mut added = new_entries.remove(&key).unwrap_or_default();
+        // The entries found in both are unchanged
+        removed.retain(
+            |value| match added.iter().position(|other| other == value) {
+                Some(position) => {
+                    added.remove(position);
+                    false
+                }
+                None => true,
+            },
+        );
+
+        let (surface, details) = key;
+        let paired = removed.len().min(added.len());
+        for (old, new) in removed.iter().zip(&added) {
+            diff.changed.push(ChangedEntry {
+                surface: surface.clone(),
+                details: details.clone(),
+                old: *old,
+                new: *new,
+            });
+        }
+        let entry = |&(left_id, right_id, word_cost): &EntryValue| DiffEntry {
+            surface: surface.clone(),
+            left_id,
+            right_id,
+            word_cost,
+            details: details.clone(),
+        };
+        diff.removed.extend(removed[paired..].iter().map(entry));
+        diff.added.extend(added[paired..].iter().map(entry));
+    }
+    diff
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+    use crate::builder::DictionaryBuilder;
+    use crate::builder::tests::write_sources;
+    use crate::dictionary::metadata::Metadata;
+
+    fn build(dir: &Path, lex_csv: &str, matrix_def: &str) -> Dictionary {
+        let input_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+        fs::write(input_dir.path().join("matrix.def"), matrix_def).unwrap();
+        fs::write(input_dir.path().join("lex.csv"), lex_csv).unwrap();
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), dir)
+            .unwrap();
+        Dictionary::load_from_path(dir).unwrap()
+    }
+
+    #[test]
+    fn test_diff_dictionaries() {
+        let old_dir = tempfile::tempdir().unwrap();
+        let old = build(
+            old_dir.path(),
+            "\
+東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー
+京都,0,0,200,名詞,固有名詞,地域,一般,*,*,京都,キョウト,キョート
+大阪,0,0,300,名詞,固有名詞,地域,一般,*,*,大阪,オオサカ,オーサカ
+",
+            "1 1\n0 0 0\n",
+        );
+        let new_dir = tempfile::tempdir().unwrap();
+        let new = build(
+            new_dir.path(),
+            "\
+東京,0,0,50,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー
+京都,0,0,200,名詞,固有名詞,地域,一般,*,*,京都,キョウト,キョート
+京都,0,0,400,名詞,固有名詞,人名,姓,*,*,京都,キョウト,キョート
+",
+            "1 1\n0 0 10\n",
+        );
+
+        let diff = diff_dictionaries(&old, &new);
+        fn surfaces(entries: &[DiffEntry]) -> Vec<&str> {
+            entries.iter().map(|entry| entry.surface.as_str()).collect()
+        }
+        assert_eq!(vec!["京都"], surfaces(&diff.added));
+        assert_eq!("人名", diff.added[0].details[2]);
+        assert_eq!(vec!["大阪"], surfaces(&diff.removed));
+        assert_eq!(1, diff.changed.len());
+        assert_eq!("東京", diff.changed[0].surface);
+        assert_eq!(-50, diff.changed[0].cost_delta());
+        assert_eq!(-50.0, diff.mean_cost_delta());
+        assert_eq!(Some(1), diff.changed_connection_costs);
+        assert!(!diff.is_empty());
+
+        assert!(diff_dictionaries(&old, &old).is_empty());
+    }
+}
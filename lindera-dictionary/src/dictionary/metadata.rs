@@ -1,14 +1,179 @@
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 use serde::{Deserialize, Serialize};
 
 use crate::decompress::Algorithm;
 use crate::dictionary::schema::Schema;
+use crate::error::{LinderaError, LinderaErrorKind};
+use crate::format::{FORMAT_VERSION, LEGACY_FORMAT_VERSION};
 
 const DEFAULT_COMPRESS_ALGORITHM: Algorithm = Algorithm::Deflate;
 const DEFAULT_WORD_COST: i16 = -10000;
 const DEFAULT_LEFT_CONTEXT_ID: u16 = 1288;
 const DEFAULT_RIGHT_CONTEXT_ID: u16 = 1288;
 const DEFAULT_FIELD_VALUE: &str = "*";
+const DEFAULT_READING_INDEX_FIELD: &str = "reading";
+
+/// Metadata without `format_version` was written before the format was versioned.
+fn legacy_format_version() -> u32 {
+    LEGACY_FORMAT_VERSION
+}
+
+/// The reading index is built by default, from the `reading` field.
+fn default_reading_index_field() -> Option<String> {
+    Some(DEFAULT_READING_INDEX_FIELD.to_string())
+}
+
+/// Normalization applied by the builder to the surfaces of dictionary entries.
+///
+/// Use this to keep the dictionary consistent with a `unicode_normalize` (NFKC)
+/// character filter at analysis time.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    RkyvSerialize,
+    RkyvDeserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SurfaceNormalization {
+    /// Surfaces are registered as they are.
+    #[default]
+    None,
+    /// Surfaces are replaced with their NFKC normalized form.
+    Nfkc,
+    /// Entries whose NFKC normalized surface differs are registered under both
+    /// the original and the normalized surface.
+    NfkcWithOriginal,
+}
+
+/// How the builder resolves duplicate entries in the source CSV files.
+///
+/// Rows are duplicates when every field except the cost is identical, i.e. the same
+/// word with the same part of speech, context ids and readings. This typically happens
+/// when an additional dictionary such as NEologd is merged into IPADIC.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    RkyvSerialize,
+    RkyvDeserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePolicy {
+    /// Keep every duplicate row.
+    #[default]
+    KeepAll,
+    /// Keep the row that appears first.
+    KeepFirst,
+    /// Keep the row with the lowest cost.
+    KeepLowestCost,
+    /// Fail the build.
+    Error,
+}
+
+impl DuplicatePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DuplicatePolicy::KeepAll => "keep_all",
+            DuplicatePolicy::KeepFirst => "keep_first",
+            DuplicatePolicy::KeepLowestCost => "keep_lowest_cost",
+            DuplicatePolicy::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for DuplicatePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DuplicatePolicy {
+    type Err = LinderaError;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy {
+            "keep_all" => Ok(DuplicatePolicy::KeepAll),
+            "keep_first" => Ok(DuplicatePolicy::KeepFirst),
+            "keep_lowest_cost" => Ok(DuplicatePolicy::KeepLowestCost),
+            "error" => Ok(DuplicatePolicy::Error),
+            _ => Err(LinderaErrorKind::Args
+                .with_error(anyhow::anyhow!("Invalid duplicate policy: {policy}"))),
+        }
+    }
+}
+
+/// How the builder encodes the costs of the connection cost matrix.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Archive,
+    RkyvSerialize,
+    RkyvDeserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MatrixEncoding {
+    /// Each cost is stored as it is, in 16 bits.
+    #[default]
+    Plain,
+    /// Each cost is stored in 8 bits, relative to a bias and a step of its left id, which
+    /// halves the memory of the matrix. The costs are rounded to the nearest multiple of the
+    /// step, which is 1/254 of the range of the costs of the left id.
+    Quantized,
+}
+
+impl MatrixEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatrixEncoding::Plain => "plain",
+            MatrixEncoding::Quantized => "quantized",
+        }
+    }
+}
+
+impl fmt::Display for MatrixEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MatrixEncoding {
+    type Err = LinderaError;
+
+    fn from_str(encoding: &str) -> Result<Self, Self::Err> {
+        match encoding {
+            "plain" => Ok(MatrixEncoding::Plain),
+            "quantized" => Ok(MatrixEncoding::Quantized),
+            _ => Err(LinderaErrorKind::Args
+                .with_error(anyhow::anyhow!("Invalid matrix encoding: {encoding}"))),
+        }
+    }
+}
 
 #[derive(Clone, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize)]
 
@@ -27,19 +192,43 @@ pub struct ModelInfo {
 #[derive(Clone, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize)]
 
 pub struct Metadata {
-    pub name: String,                   // Name of the dictionary
-    pub encoding: String,               // Character encoding
-    pub compress_algorithm: Algorithm,  // Compression algorithm
-    pub default_word_cost: i16,         // Word cost for simple user dictionary
-    pub default_left_context_id: u16,   // Context ID for simple user dictionary
-    pub default_right_context_id: u16,  // Context ID for simple user dictionary
-    pub default_field_value: String,    // Default value for fields in simple user dictionary
-    pub flexible_csv: bool,             // Handle CSV columns flexibly
-    pub skip_invalid_cost_or_id: bool,  // Skip invalid cost or ID
-    pub normalize_details: bool,        // Normalize characters
-    pub dictionary_schema: Schema,      // Schema for the dictionary
+    #[serde(default = "legacy_format_version")]
+    pub format_version: u32, // Binary format version of the dictionary
+    pub name: String,                  // Name of the dictionary
+    pub encoding: String,              // Character encoding
+    pub compress_algorithm: Algorithm, // Compression algorithm
+    pub default_word_cost: i16,        // Word cost for simple user dictionary
+    pub default_left_context_id: u16,  // Context ID for simple user dictionary
+    pub default_right_context_id: u16, // Context ID for simple user dictionary
+    pub default_field_value: String,   // Default value for fields in simple user dictionary
+    pub flexible_csv: bool,            // Handle CSV columns flexibly
+    pub skip_invalid_cost_or_id: bool, // Skip invalid cost or ID
+    pub normalize_details: bool,       // Normalize characters
+    #[serde(default)]
+    pub surface_normalization: SurfaceNormalization, // Normalize entry surfaces
+    #[serde(default)]
+    pub duplicate_policy: DuplicatePolicy, // Resolve duplicate entries
+    #[serde(default = "default_reading_index_field")]
+    pub reading_index_field: Option<String>, // Field indexed in dict.readings (none to skip it)
+    #[serde(default)]
+    pub okurigana_variants: bool, // Write the okurigana variant table dict.okurigana
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matrix_shard_size: Option<u32>, // Left ids per shard of matrix.mtx (none for a single file)
+    #[serde(default)]
+    pub matrix_encoding: MatrixEncoding, // Encoding of the costs of matrix.mtx
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub upos_mapping: BTreeMap<String, String>, // Part-of-speech prefix to Universal POS tag
+    pub dictionary_schema: Schema,     // Schema for the dictionary
     pub user_dictionary_schema: Schema, // Schema for user dictionary
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>, // License of the lexical data (SPDX identifier)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>, // Where the source data was obtained
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_version: Option<String>, // Version or commit of the source data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub built_at: Option<u64>, // Build time in seconds since the Unix epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub model_info: Option<ModelInfo>, // Training model information (optional)
 }
 
@@ -84,6 +273,7 @@ impl Metadata {
         userdic_schema: Schema,
     ) -> Self {
         Self {
+            format_version: FORMAT_VERSION,
             encoding,
             compress_algorithm,
             default_word_cost: simple_word_cost,
@@ -95,11 +285,44 @@ impl Metadata {
             flexible_csv,
             skip_invalid_cost_or_id,
             normalize_details,
+            surface_normalization: SurfaceNormalization::None,
+            duplicate_policy: DuplicatePolicy::KeepAll,
+            reading_index_field: default_reading_index_field(),
+            okurigana_variants: false,
+            matrix_shard_size: None,
+            matrix_encoding: MatrixEncoding::Plain,
+            upos_mapping: BTreeMap::new(),
             user_dictionary_schema: userdic_schema,
+            license: None,
+            source_url: None,
+            source_version: None,
+            built_at: None,
             model_info: None,
         }
     }
 
+    /// Returns the Universal POS tag (`NOUN`, `VERB`, ...) of a part-of-speech, the fields of the
+    /// part-of-speech joined with commas (`名詞,固有名詞,地域,一般`).
+    ///
+    /// The keys of `upos_mapping` are prefixes of the part-of-speech that end at a field, or at a
+    /// `+` of a compound tag (`VV` for `VV+EP`), and the longest matching key is used.
+    pub fn upos(&self, part_of_speech: &str) -> Option<&str> {
+        if self.upos_mapping.is_empty() {
+            return None;
+        }
+
+        let mut ends = part_of_speech
+            .match_indices([',', '+'])
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        ends.push(part_of_speech.len());
+
+        ends.into_iter()
+            .rev()
+            .find_map(|end| self.upos_mapping.get(&part_of_speech[..end]))
+            .map(String::as_str)
+    }
+
     /// Load metadata from binary data (JSON format or compressed binary format).
     /// This provides a consistent interface with other dictionary components.
     pub fn load(data: &[u8]) -> crate::LinderaResult<Self> {
@@ -110,9 +333,10 @@ impl Metadata {
         }
 
         // Try to deserialize as JSON first (for uncompressed metadata.json files)
-        if let Ok(metadata) = serde_json::from_slice(data) {
-            return Ok(metadata);
-        }
+        let json_err = match serde_json::from_slice(data) {
+            Ok(metadata) => return Ok(metadata),
+            Err(err) => err,
+        };
 
         // If JSON fails, try to decompress as rkyv-encoded compressed data
         #[cfg(feature = "compress")]
@@ -121,30 +345,19 @@ impl Metadata {
 
             if let Ok(compressed_data) =
                 rkyv::from_bytes::<CompressedData, rkyv::rancor::Error>(data)
+                && let Ok(decompressed) = decompress(compressed_data)
             {
-                if let Ok(decompressed) = decompress(compressed_data) {
-                    // Try to parse the decompressed data as JSON
-                    if let Ok(metadata) = serde_json::from_slice(&decompressed) {
-                        return Ok(metadata);
-                    }
+                // Try to parse the decompressed data as JSON
+                if let Ok(metadata) = serde_json::from_slice(&decompressed) {
+                    return Ok(metadata);
                 }
             }
         }
 
-        #[cfg(not(feature = "compress"))]
-        {
-            // Without compress feature, data should be raw JSON
-            return serde_json::from_slice(data).map_err(|err| {
-                crate::error::LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!(err))
-            });
-        }
-
         // If all attempts fail, return an error
-        Err(
-            crate::error::LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!(
-                "Failed to deserialize metadata from any supported format"
-            )),
-        )
+        Err(crate::error::LinderaErrorKind::Deserialize
+            .with_error(anyhow::anyhow!(json_err))
+            .add_context("Failed to deserialize metadata from any supported format"))
     }
 
     /// Load metadata with fallback to default values.
@@ -208,4 +421,103 @@ mod tests {
         assert_eq!(deserialized.name, "default");
         // Schema no longer has name field
     }
+
+    #[test]
+    fn test_duplicate_policy_from_str() {
+        for policy in [
+            DuplicatePolicy::KeepAll,
+            DuplicatePolicy::KeepFirst,
+            DuplicatePolicy::KeepLowestCost,
+            DuplicatePolicy::Error,
+        ] {
+            assert_eq!(DuplicatePolicy::from_str(policy.as_str()).unwrap(), policy);
+        }
+        assert!(DuplicatePolicy::from_str("keep_last").is_err());
+    }
+
+    #[test]
+    fn test_metadata_surface_normalization() {
+        let mut value = serde_json::to_value(Metadata::default()).unwrap();
+        value
+            .as_object_mut()
+            .unwrap()
+            .remove("surface_normalization");
+        let metadata: Metadata = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(metadata.surface_normalization, SurfaceNormalization::None);
+
+        value["surface_normalization"] = serde_json::json!("nfkc_with_original");
+        let metadata: Metadata = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            metadata.surface_normalization,
+            SurfaceNormalization::NfkcWithOriginal
+        );
+    }
+
+    #[test]
+    fn test_metadata_reading_index_field() {
+        let mut value = serde_json::to_value(Metadata::default()).unwrap();
+        assert_eq!(value["reading_index_field"], "reading");
+
+        value.as_object_mut().unwrap().remove("reading_index_field");
+        let metadata: Metadata = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(metadata.reading_index_field.as_deref(), Some("reading"));
+
+        value["reading_index_field"] = serde_json::Value::Null;
+        let metadata: Metadata = serde_json::from_value(value).unwrap();
+        assert_eq!(metadata.reading_index_field, None);
+    }
+
+    #[test]
+    fn test_metadata_upos() {
+        let mut metadata = Metadata::default();
+        assert_eq!(None, metadata.upos("名詞,一般,*,*"));
+        // An empty mapping is not written
+        let value = serde_json::to_value(&metadata).unwrap();
+        assert!(value.get("upos_mapping").is_none());
+
+        for (part_of_speech, upos) in [
+            ("名詞", "NOUN"),
+            ("名詞,固有名詞", "PROPN"),
+            ("名詞,数", "NUM"),
+            ("VV", "VERB"),
+        ] {
+            metadata
+                .upos_mapping
+                .insert(part_of_speech.to_string(), upos.to_string());
+        }
+
+        assert_eq!(Some("NOUN"), metadata.upos("名詞,一般,*,*"));
+        assert_eq!(Some("PROPN"), metadata.upos("名詞,固有名詞,地域,一般"));
+        assert_eq!(Some("NUM"), metadata.upos("名詞,数,*,*"));
+        // A key only matches whole fields
+        assert_eq!(Some("NOUN"), metadata.upos("名詞,数詞,*,*"));
+        assert_eq!(Some("NOUN"), metadata.upos("名詞"));
+        assert_eq!(Some("VERB"), metadata.upos("VV+EP,*,F"));
+        assert_eq!(None, metadata.upos("VVX"));
+        assert_eq!(None, metadata.upos("動詞,自立,*,*"));
+    }
+
+    #[test]
+    fn test_metadata_provenance() {
+        // Provenance fields are optional and omitted when unset
+        let value = serde_json::to_value(Metadata::default()).unwrap();
+        assert!(value.get("license").is_none());
+        let metadata: Metadata = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(metadata.license, None);
+        assert_eq!(metadata.built_at, None);
+
+        let mut value = value;
+        value["license"] = serde_json::json!("Apache-2.0");
+        value["source_url"] = serde_json::json!("https://example.com/dict.tar.gz");
+        value["source_version"] = serde_json::json!("2.1.1-20180720");
+        value["built_at"] = serde_json::json!(1700000000);
+        let metadata: Metadata = serde_json::from_value(value).unwrap();
+        assert_eq!(metadata.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(
+            metadata.source_url.as_deref(),
+            Some("https://example.com/dict.tar.gz")
+        );
+        assert_eq!(metadata.source_version.as_deref(), Some("2.1.1-20180720"));
+        assert_eq!(metadata.built_at, Some(1700000000));
+    }
 }
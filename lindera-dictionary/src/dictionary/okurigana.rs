@@ -0,0 +1,133 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::LinderaResult;
+use crate::dictionary::prefix_dictionary::PrefixDictionary;
+use crate::error::LinderaErrorKind;
+use crate::viterbi::is_kanji;
+
+fn is_hiragana(c: char) -> bool {
+    matches!(c as u32, 0x3041..=0x309f)
+}
+
+/// Returns the spellings of a surface with some okurigana left out (`受付け` and `受付` for
+/// `受け付け`), in the order they are usually shortened.
+///
+/// The okurigana are the runs of hiragana following a kanji. The ones between two kanji are left
+/// out from the first one on; the one at the end is left out last, and only from a compound word
+/// that does not conjugate, since the ending of a verb or an adjective cannot be dropped
+/// (`取扱う` for `取り扱う`, but no `取扱`).
+pub fn okurigana_variants(surface: &str, conjugates: bool) -> Vec<String> {
+    // (byte range, following a kanji, followed by a kanji) of the runs of hiragana
+    let chars = surface.char_indices().collect::<Vec<_>>();
+    let mut runs = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        if !is_hiragana(chars[index].1) {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        while index < chars.len() && is_hiragana(chars[index].1) {
+            index += 1;
+        }
+        let after_kanji = start > 0 && is_kanji(chars[start - 1].1);
+        let before_kanji = index < chars.len() && is_kanji(chars[index].1);
+        let byte_start = chars[start].0;
+        let byte_end = chars
+            .get(index)
+            .map_or(surface.len(), |(offset, _)| *offset);
+        runs.push((byte_start..byte_end, after_kanji, before_kanji));
+    }
+
+    let internal = runs
+        .iter()
+        .filter(|(_, after_kanji, before_kanji)| *after_kanji && *before_kanji)
+        .count();
+    if internal == 0 {
+        return Vec::new();
+    }
+    let droppable = runs
+        .into_iter()
+        .filter(|(range, after_kanji, before_kanji)| {
+            *after_kanji && (*before_kanji || (!conjugates && range.end == surface.len()))
+        })
+        .map(|(range, ..)| range)
+        .collect::<Vec<_>>();
+
+    (1..=droppable.len())
+        .map(|count| {
+            let mut variant = String::with_capacity(surface.len());
+            let mut offset = 0;
+            for range in &droppable[..count] {
+                variant.push_str(&surface[offset..range.start]);
+                offset = range.end;
+            }
+            variant.push_str(&surface[offset..]);
+            variant
+        })
+        .collect()
+}
+
+/// The okurigana variant table of a dictionary (`dict.okurigana`).
+///
+/// The table maps the okurigana variants of the surfaces to the entries of the words they are
+/// spelled from, so a text spelled `受付け` is analyzed as `受け付け`. Variants that are words of
+/// the dictionary themselves are left out.
+#[derive(Clone, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize)]
+pub struct OkuriganaVariants {
+    /// The variants, with the entries of the system dictionary as values.
+    pub dict: PrefixDictionary,
+}
+
+impl OkuriganaVariants {
+    pub fn load(data: &[u8]) -> LinderaResult<OkuriganaVariants> {
+        let mut aligned = rkyv::util::AlignedVec::<16>::new();
+        aligned.extend_from_slice(data);
+        rkyv::from_bytes::<OkuriganaVariants, rkyv::rancor::Error>(&aligned).map_err(|err| {
+            LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!(err.to_string()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn test_okurigana_variants() {
+        assert_eq!(
+            vec!["受付け".to_string(), "受付".to_string()],
+            okurigana_variants("受け付け", false)
+        );
+        assert_eq!(
+            vec!["申込み".to_string(), "申込".to_string()],
+            okurigana_variants("申し込み", false)
+        );
+        assert_eq!(
+            vec!["引落とし".to_string(), "引落".to_string()],
+            okurigana_variants("引き落とし", false)
+        );
+
+        // The ending of a verb is kept
+        assert_eq!(
+            vec!["受付ける".to_string()],
+            okurigana_variants("受け付ける", true)
+        );
+        assert_eq!(
+            vec!["取扱う".to_string()],
+            okurigana_variants("取り扱う", true)
+        );
+
+        // Words that are not compounds have no variants
+        assert!(okurigana_variants("明かり", false).is_empty());
+        assert!(okurigana_variants("書く", true).is_empty());
+        assert!(okurigana_variants("ひらがな", false).is_empty());
+        assert!(okurigana_variants("東京", false).is_empty());
+    }
+}
@@ -1,9 +1,17 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
 use daachorse::DoubleArrayAhoCorasick;
 use rkyv::rancor::Fallible;
 use rkyv::with::{ArchiveWith, DeserializeWith, SerializeWith};
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Place, Serialize as RkyvSerialize};
 use serde::{Deserialize, Serialize};
 
+use crate::LinderaResult;
+use crate::dictionary::reader::ByteReader;
 use crate::{util::Data, viterbi::WordEntry};
 
 /// Match structure for common prefix iterator compatibility
@@ -68,6 +76,8 @@ impl<D: Fallible + ?Sized>
 }
 
 mod double_array_serde {
+    use alloc::vec::Vec;
+
     use daachorse::DoubleArrayAhoCorasick;
     use serde::{Deserialize, Deserializer, Serializer};
 
@@ -102,24 +112,197 @@ pub struct PrefixDictionary {
     pub is_system: bool,
 }
 
+/// The size of a serialized state of the double array: its base, its failure link, and its
+/// output position and check byte.
+const DOUBLE_ARRAY_STATE_LEN: usize = 12;
+
+/// The size of a serialized output of the double array: its value, its length and its parent.
+const DOUBLE_ARRAY_OUTPUT_LEN: usize = 12;
+
+/// Checks that the bytes of `dict.da` are a double array whose states, outputs and values are all
+/// in range, so that it can be deserialized without the checks of `deserialize_unchecked()`.
+///
+/// `entries` is the number of word entries in `dict.vals`: the value of every output points to a
+/// run of them.
+///
+/// daachorse has no checked deserialization, so this follows the layout serialized by
+/// daachorse 1.0.1, which is pinned in the workspace manifest for that reason.
+fn validate_double_array(da_bytes: &[u8], entries: usize) -> LinderaResult<()> {
+    let mut reader = ByteReader::new(da_bytes, "dict.da");
+
+    let num_states = reader.read_u32()? as usize;
+    if num_states == 0 {
+        return Err(reader.error("no states"));
+    }
+    let states = reader.read_bytes(num_states.saturating_mul(DOUBLE_ARRAY_STATE_LEN))?;
+    let num_outputs = reader.read_u32()? as usize;
+    let outputs = reader.read_bytes(num_outputs.saturating_mul(DOUBLE_ARRAY_OUTPUT_LEN))?;
+    let _match_kind = reader.read_u8()?;
+    let _num_states = reader.read_u32()?;
+
+    let read_u32 = |chunk: &[u8], offset: usize| {
+        u32::from_le_bytes([
+            chunk[offset],
+            chunk[offset + 1],
+            chunk[offset + 2],
+            chunk[offset + 3],
+        ])
+    };
+
+    for (state_id, state) in states.chunks_exact(DOUBLE_ARRAY_STATE_LEN).enumerate() {
+        let base = read_u32(state, 0) as usize;
+        let fail = read_u32(state, 4) as usize;
+        let output_pos = (read_u32(state, 8) >> 8) as usize;
+        // The children of a state are at base ^ c for every byte c
+        if (base != 0 && (base | 0xff) >= num_states)
+            || fail >= num_states
+            || output_pos > num_outputs
+        {
+            return Err(reader.error(format!("state {state_id} is out of range")));
+        }
+    }
+
+    for (output_id, output) in outputs.chunks_exact(DOUBLE_ARRAY_OUTPUT_LEN).enumerate() {
+        let value = read_u32(output, 0);
+        let length = read_u32(output, 4);
+        let parent = read_u32(output, 8) as usize;
+        let offset = (value >> 5) as usize;
+        let len = (value & ((1u32 << 5) - 1u32)) as usize;
+        if length == 0 || parent > num_outputs || offset + len > entries {
+            return Err(reader.error(format!("output {output_id} is out of range")));
+        }
+    }
+
+    Ok(())
+}
+
+/// Set in the length of a record of `dict.words` whose leading details are stored once for all
+/// the words that share them (format version 3). The length is followed by the offset of the
+/// record holding the shared details, and the record holds the other details of the word.
+pub const SHARED_DETAILS_FLAG: u32 = 1 << 31;
+
+/// The details of a word as they are stored in `dict.words`, separated by NUL bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordDetailsRecord<'a> {
+    /// The leading details the word shares with other words, if they are stored apart.
+    pub shared: Option<&'a [u8]>,
+    /// The other details of the word, or all of them.
+    pub own: &'a [u8],
+}
+
+impl<'a> WordDetailsRecord<'a> {
+    /// Returns the details one by one, without copying them.
+    pub fn fields(&self) -> impl Iterator<Item = &'a [u8]> + use<'a> {
+        let Self { shared, own } = *self;
+        shared
+            .into_iter()
+            .flat_map(|shared| shared.split(|&b| b == 0))
+            .chain(own.split(|&b| b == 0))
+    }
+
+    /// Returns the details separated by NUL bytes, copied only if some of them are shared.
+    pub fn joined(&self) -> Cow<'a, [u8]> {
+        match self.shared {
+            None => Cow::Borrowed(self.own),
+            Some(shared) => {
+                let mut joined = Vec::with_capacity(shared.len() + 1 + self.own.len());
+                joined.extend_from_slice(shared);
+                joined.push(0);
+                joined.extend_from_slice(self.own);
+                Cow::Owned(joined)
+            }
+        }
+    }
+}
+
+/// Reads the record of `dict.words` at `offset`.
+fn read_word_details(words_data: &[u8], offset: usize) -> LinderaResult<WordDetailsRecord<'_>> {
+    let mut words = ByteReader::new(words_data.get(offset..).unwrap_or_default(), "dict.words");
+    let len = words.read_u32()?;
+    if len & SHARED_DETAILS_FLAG == 0 {
+        return Ok(WordDetailsRecord {
+            shared: None,
+            own: words.read_bytes(len as usize)?,
+        });
+    }
+
+    let shared_offset = words.read_u32()? as usize;
+    let own = words.read_bytes((len & !SHARED_DETAILS_FLAG) as usize)?;
+    let mut shared = ByteReader::new(
+        words_data.get(shared_offset..).unwrap_or_default(),
+        "dict.words",
+    );
+    let shared_len = shared.read_u32()?;
+    if shared_len & SHARED_DETAILS_FLAG != 0 {
+        return Err(shared.error(format!(
+            "the shared details at offset {shared_offset} are shared themselves"
+        )));
+    }
+    Ok(WordDetailsRecord {
+        shared: Some(shared.read_bytes(shared_len as usize)?),
+        own,
+    })
+}
+
+/// Checks that every record of `dict.wordsidx` points to a record of `dict.words` within its
+/// bounds.
+fn validate_words(words_idx_data: &[u8], words_data: &[u8]) -> LinderaResult<()> {
+    let mut reader = ByteReader::new(words_idx_data, "dict.wordsidx");
+    if !words_idx_data.len().is_multiple_of(4) {
+        return Err(reader.error(format!(
+            "{} bytes is not a multiple of 4",
+            words_idx_data.len()
+        )));
+    }
+
+    while !reader.remaining().is_empty() {
+        let word_id = reader.position() / 4;
+        let idx = reader.read_u32()? as usize;
+        read_word_details(words_data, idx)
+            .map_err(|err| err.add_context(format!("The details of word {word_id}")))?;
+    }
+
+    Ok(())
+}
+
 impl PrefixDictionary {
+    /// Loads a prefix dictionary from the bytes of `dict.da`, `dict.vals`, `dict.wordsidx` and
+    /// `dict.words`.
+    ///
+    /// The components are checked before they are used, so a truncated or corrupted component
+    /// returns an error instead of a panic or out-of-bounds reads during lookups.
     pub fn load(
         da_data: impl Into<Data>,
         vals_data: impl Into<Data>,
         words_idx_data: impl Into<Data>,
         words_data: impl Into<Data>,
         is_system: bool,
-    ) -> PrefixDictionary {
+    ) -> LinderaResult<PrefixDictionary> {
         let da_bytes = da_data.into();
+        let vals_data = vals_data.into();
+        let words_idx_data = words_idx_data.into();
+        let words_data = words_data.into();
+
+        if !vals_data.len().is_multiple_of(WordEntry::SERIALIZED_LEN) {
+            return Err(ByteReader::new(&vals_data, "dict.vals").error(format!(
+                "{} bytes is not a multiple of {}",
+                vals_data.len(),
+                WordEntry::SERIALIZED_LEN
+            )));
+        }
+        validate_double_array(&da_bytes, vals_data.len() / WordEntry::SERIALIZED_LEN)?;
+        validate_words(&words_idx_data, &words_data)?;
+
+        // The states, the outputs and the values of the double array were checked above
         let (da, _) = unsafe { DoubleArrayAhoCorasick::deserialize_unchecked(&da_bytes[..]) };
 
-        PrefixDictionary {
+        Ok(PrefixDictionary {
             da,
-            vals_data: vals_data.into(),
-            words_idx_data: words_idx_data.into(),
-            words_data: words_data.into(),
+            vals_data,
+            words_idx_data,
+            words_data,
             is_system,
-        }
+        })
     }
 
     pub fn prefix<'a>(&'a self, s: &'a str) -> impl Iterator<Item = (usize, WordEntry)> + 'a {
@@ -170,6 +353,85 @@ impl PrefixDictionary {
             })
     }
 
+    /// Returns the record of the details of a word in `dict.words`, or `None` if the word id or
+    /// its record is out of range.
+    pub fn word_details_record(&self, word_id: usize) -> Option<WordDetailsRecord<'_>> {
+        let idx_start = word_id.checked_mul(4)?;
+        let idx_bytes = self
+            .words_idx_data
+            .get(idx_start..idx_start.checked_add(4)?)?;
+        let idx = u32::from_le_bytes(idx_bytes.try_into().ok()?) as usize;
+        read_word_details(&self.words_data, idx).ok()
+    }
+
+    /// Returns the details of a word separated by NUL bytes, or `None` if the word id or its
+    /// record is out of range.
+    pub fn word_details_bytes(&self, word_id: usize) -> Option<Cow<'_, [u8]>> {
+        self.word_details_record(word_id)
+            .map(|record| record.joined())
+    }
+
+    /// Returns every entry of the dictionary with its surface, in the byte order of the surfaces
+    /// and the order of the entries of a surface.
+    ///
+    /// The surfaces are only stored as the keys of the double array, so its trie is walked from
+    /// the root: the children of a state are at `base ^ c` for the bytes `c` they check, and a
+    /// state is the end of a surface if its first output is as long as the path to it.
+    pub fn entries(&self) -> Vec<(String, WordEntry)> {
+        let da_bytes = self.da.serialize();
+        let read_u32 = |bytes: &[u8], offset: usize| {
+            u32::from_le_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ])
+        };
+        let num_states = read_u32(&da_bytes, 0) as usize;
+        let states = &da_bytes[4..4 + num_states * DOUBLE_ARRAY_STATE_LEN];
+        // The outputs follow the states and their count
+        let outputs = &da_bytes[8 + states.len()..];
+
+        let mut entries = Vec::new();
+        let mut stack = vec![(0usize, Vec::new())];
+        while let Some((state_id, key)) = stack.pop() {
+            let state = &states[state_id * DOUBLE_ARRAY_STATE_LEN..][..DOUBLE_ARRAY_STATE_LEN];
+            let output_pos = (read_u32(state, 8) >> 8) as usize;
+            if output_pos > 0 {
+                let output = &outputs[(output_pos - 1) * DOUBLE_ARRAY_OUTPUT_LEN..];
+                if read_u32(output, 4) as usize == key.len()
+                    && let Ok(surface) = String::from_utf8(key.clone())
+                {
+                    let value = read_u32(output, 0);
+                    let offset = (value >> 5) as usize * WordEntry::SERIALIZED_LEN;
+                    let len = (value & ((1u32 << 5) - 1u32)) as usize;
+                    for i in 0..len {
+                        let entry = WordEntry::deserialize(
+                            &self.vals_data[offset + WordEntry::SERIALIZED_LEN * i..],
+                            self.is_system,
+                        );
+                        entries.push((surface.clone(), entry));
+                    }
+                }
+            }
+
+            let base = read_u32(state, 0) as usize;
+            if base == 0 {
+                continue;
+            }
+            // The children are pushed in reverse so that they are visited in byte order
+            for c in (1..=u8::MAX).rev() {
+                let child_id = base ^ c as usize;
+                if child_id < num_states && states[child_id * DOUBLE_ARRAY_STATE_LEN + 8] == c {
+                    let mut child_key = key.clone();
+                    child_key.push(c);
+                    stack.push((child_id, child_key));
+                }
+            }
+        }
+        entries
+    }
+
     /// Common prefix iterator using character array input
     pub fn common_prefix_iterator(&self, suffix: &[char]) -> Vec<Match> {
         // Warning: This method takes &[char], but daachorse works on bytes (str).
@@ -313,3 +575,165 @@ impl ArchivedPrefixDictionary {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use daachorse::DoubleArrayAhoCorasick;
+
+    use crate::dictionary::prefix_dictionary::PrefixDictionary;
+    use crate::viterbi::WordEntry;
+
+    /// The components of a dictionary of "東京" and "東", one entry each.
+    fn components() -> (Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>) {
+        // The value of a word is the offset of its entries << 5 | their count
+        let da = DoubleArrayAhoCorasick::<u32>::with_values([("東京", 1), ("東", (1 << 5) | 1)])
+            .unwrap()
+            .serialize();
+
+        let mut vals = Vec::new();
+        vals.extend_from_slice(&[0, 0, 0, 0, 100, 0, 0, 0, 0, 0]);
+        vals.extend_from_slice(&[1, 0, 0, 0, 200, 0, 0, 0, 0, 0]);
+
+        let mut words = Vec::new();
+        let mut words_idx = Vec::new();
+        for details in ["名詞\0固有名詞", "名詞"] {
+            words_idx.extend_from_slice(&(words.len() as u32).to_le_bytes());
+            words.extend_from_slice(&(details.len() as u32).to_le_bytes());
+            words.extend_from_slice(details.as_bytes());
+        }
+
+        (da, vals, words_idx, words)
+    }
+
+    #[test]
+    fn test_load() {
+        let (da, vals, words_idx, words) = components();
+        let dictionary = PrefixDictionary::load(da, vals, words_idx, words, true).unwrap();
+
+        let entries = dictionary.prefix("東京都").collect::<Vec<_>>();
+        assert_eq!(2, entries.len());
+        assert!(
+            entries
+                .iter()
+                .any(|(end, entry)| *end == 6 && entry.word_cost == 100)
+        );
+        assert_eq!(
+            Some("名詞".as_bytes()),
+            dictionary.word_details_bytes(1).as_deref()
+        );
+        assert_eq!(WordEntry::SERIALIZED_LEN * 2, dictionary.vals_data.len());
+    }
+
+    #[test]
+    fn test_entries() {
+        let (da, vals, words_idx, words) = components();
+        let dictionary = PrefixDictionary::load(da, vals, words_idx, words, true).unwrap();
+
+        let entries = dictionary
+            .entries()
+            .into_iter()
+            .map(|(surface, entry)| (surface, entry.word_id.id, entry.word_cost))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![("東".to_string(), 1, 200), ("東京".to_string(), 0, 100)],
+            entries
+        );
+    }
+
+    #[test]
+    fn test_validate_built_double_array() {
+        use alloc::string::String;
+
+        use daachorse::DoubleArrayAhoCorasickBuilder;
+
+        use super::validate_double_array;
+
+        // Enough keys sharing prefixes for the states to fill several blocks of 256, built as
+        // the dictionary builder does, one entry per key
+        let keys: Vec<String> = (0..3000u32)
+            .map(|i| {
+                [0x4E00 + i / 60, 0x4E00 + i % 60, 0x3041 + i % 7]
+                    .into_iter()
+                    .filter_map(char::from_u32)
+                    .collect()
+            })
+            .collect();
+        let keyset = keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (key.as_bytes(), ((i as u32) << 5) | 1));
+        let da = DoubleArrayAhoCorasickBuilder::new()
+            .build_with_values(keyset)
+            .unwrap()
+            .serialize();
+
+        // The layout checked by the validation is the one serialized by the pinned daachorse
+        validate_double_array(&da, keys.len()).unwrap();
+        assert!(validate_double_array(&da, keys.len() - 1).is_err());
+
+        let vals = vec![0u8; WordEntry::SERIALIZED_LEN * keys.len()];
+        let mut words = Vec::new();
+        let mut words_idx = Vec::new();
+        for _ in &keys {
+            words_idx.extend_from_slice(&(words.len() as u32).to_le_bytes());
+            words.extend_from_slice(&0u32.to_le_bytes());
+        }
+        let dictionary = PrefixDictionary::load(da, vals, words_idx, words, true).unwrap();
+        for key in &keys {
+            assert!(dictionary.prefix(key).any(|(end, _)| end == key.len()));
+        }
+    }
+
+    #[test]
+    fn test_load_corrupted() {
+        let (da, vals, words_idx, words) = components();
+
+        // A truncated double array
+        assert!(
+            PrefixDictionary::load(
+                da[..da.len() - 8].to_vec(),
+                vals.clone(),
+                words_idx.clone(),
+                words.clone(),
+                true
+            )
+            .is_err()
+        );
+
+        // A failure link out of range
+        let mut corrupted = da.clone();
+        corrupted[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(
+            PrefixDictionary::load(
+                corrupted,
+                vals.clone(),
+                words_idx.clone(),
+                words.clone(),
+                true
+            )
+            .is_err()
+        );
+
+        // The entries of "東" are missing
+        assert!(
+            PrefixDictionary::load(
+                da.clone(),
+                vals[..WordEntry::SERIALIZED_LEN].to_vec(),
+                words_idx.clone(),
+                words.clone(),
+                true
+            )
+            .is_err()
+        );
+
+        // The details of the second word are truncated
+        assert!(
+            PrefixDictionary::load(da, vals, words_idx, words[..words.len() - 1].to_vec(), true)
+                .is_err()
+        );
+    }
+}
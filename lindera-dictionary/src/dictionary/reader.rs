@@ -0,0 +1,114 @@
+//! A bounds-checked reader of the little-endian binary components of a dictionary.
+//!
+//! The components of a dictionary may come from an untrusted source, or be truncated or corrupted
+//! on disk. The loaders read them through [`ByteReader`], which returns an error when the data is
+//! shorter than what it reads, instead of panicking.
+
+use alloc::format;
+
+use crate::LinderaResult;
+use crate::error::{LinderaError, LinderaErrorKind};
+
+/// Reads little-endian integers and byte slices from the front of a component.
+#[derive(Debug, Clone)]
+pub struct ByteReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    name: &'static str,
+}
+
+impl<'a> ByteReader<'a> {
+    /// A reader of `data`, the component `name` in the errors.
+    pub fn new(data: &'a [u8], name: &'static str) -> Self {
+        Self {
+            data,
+            position: 0,
+            name,
+        }
+    }
+
+    /// The offset of the next byte to read.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The bytes not read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.data[self.position..]
+    }
+
+    /// Reads the next `len` bytes.
+    pub fn read_bytes(&mut self, len: usize) -> LinderaResult<&'a [u8]> {
+        let bytes = self
+            .position
+            .checked_add(len)
+            .and_then(|end| self.data.get(self.position..end))
+            .ok_or_else(|| {
+                self.error(format!(
+                    "truncated: {len} bytes needed at offset {}, {} left",
+                    self.position,
+                    self.data.len() - self.position
+                ))
+            })?;
+        self.position += len;
+        Ok(bytes)
+    }
+
+    /// Reads the next `N` bytes into an array.
+    fn read_array<const N: usize>(&mut self) -> LinderaResult<[u8; N]> {
+        let bytes = self.read_bytes(N)?;
+        // read_bytes returned exactly N bytes
+        Ok(bytes.try_into().unwrap_or([0; N]))
+    }
+
+    pub fn read_u8(&mut self) -> LinderaResult<u8> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    pub fn read_i16(&mut self) -> LinderaResult<i16> {
+        Ok(i16::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u16(&mut self) -> LinderaResult<u16> {
+        Ok(u16::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u32(&mut self) -> LinderaResult<u32> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    /// An error about the component, for the checks of the values read.
+    pub fn error(&self, message: impl core::fmt::Display) -> LinderaError {
+        LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!(
+            "corrupted dictionary component {}: {message}",
+            self.name
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::dictionary::reader::ByteReader;
+
+    #[test]
+    fn test_byte_reader() {
+        let data = [0xff, 0xff, 0x02, 0x00, 0x03, 0x00, 0x00, 0x00, 0x7f];
+        let mut reader = ByteReader::new(&data, "test.bin");
+
+        assert_eq!(-1, reader.read_i16().unwrap());
+        assert_eq!(2, reader.read_u16().unwrap());
+        assert_eq!(3, reader.read_u32().unwrap());
+        assert_eq!(8, reader.position());
+        assert_eq!(&[0x7f], reader.remaining());
+
+        let err = reader.read_u16().err().unwrap();
+        assert!(err.to_string().contains("test.bin"));
+        // A failed read does not move the reader
+        assert_eq!(0x7f, reader.read_u8().unwrap());
+        assert!(reader.read_bytes(1).is_err());
+        assert!(reader.read_bytes(usize::MAX).is_err());
+        assert_eq!(0, reader.read_bytes(0).unwrap().len());
+    }
+}
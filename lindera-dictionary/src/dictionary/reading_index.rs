@@ -0,0 +1,373 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::LinderaResult;
+use crate::error::LinderaErrorKind;
+
+/// Readings the lexicon uses for words without a reading.
+const NO_READING: &[&str] = &["", "*"];
+
+/// Converts the hiragana in `text` to katakana, so that readings can be looked up with either.
+pub fn to_katakana(text: &str) -> String {
+    text.chars()
+        .map(|c| match c as u32 {
+            codepoint @ 0x3041..=0x3096 => char::from_u32(codepoint + 0x60).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c as u32, 0x3041..=0x3096 | 0x309d..=0x309f | 0x30a1..=0x30ff)
+}
+
+/// The characters of the surface that are not kana.
+fn kanji_skeleton(surface: &str) -> String {
+    surface.chars().filter(|c| !is_kana(*c)).collect()
+}
+
+/// An entry of the reading index.
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize,
+)]
+pub struct ReadingEntry {
+    /// The reading, in katakana.
+    pub reading: String,
+    pub surface: String,
+    pub word_id: u32,
+    pub word_cost: i16,
+}
+
+/// A candidate surface for a reading, as returned by [`ReadingIndex::candidates`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Candidate {
+    pub surface: String,
+    /// The reading of the word, in katakana.
+    pub reading: String,
+    pub word_id: u32,
+    pub word_cost: i16,
+}
+
+/// Index of the lexicon by reading, for kana-kanji conversion (`dict.readings`).
+///
+/// The entries are sorted by reading, so that the words whose reading starts with a prefix are
+/// adjacent.
+#[derive(
+    Debug, Clone, Default, Serialize, Deserialize, Archive, RkyvSerialize, RkyvDeserialize,
+)]
+pub struct ReadingIndex {
+    pub entries: Vec<ReadingEntry>,
+    /// Indices of the entries, ordered by surface.
+    pub by_surface: Vec<u32>,
+}
+
+impl ReadingIndex {
+    /// Builds the index from `(surface, reading, word_id, word_cost)` of the words.
+    ///
+    /// Words without a reading are skipped.
+    pub fn build<'a, I>(words: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a str, u32, i16)>,
+    {
+        let mut entries = words
+            .into_iter()
+            .filter(|(_, reading, _, _)| !NO_READING.contains(reading))
+            .map(|(surface, reading, word_id, word_cost)| ReadingEntry {
+                reading: to_katakana(reading),
+                surface: surface.to_string(),
+                word_id,
+                word_cost,
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| {
+            (&a.reading, a.word_cost, a.word_id).cmp(&(&b.reading, b.word_cost, b.word_id))
+        });
+        let mut by_surface = (0..entries.len() as u32).collect::<Vec<_>>();
+        by_surface.sort_by_key(|&index| &entries[index as usize].surface);
+
+        Self {
+            entries,
+            by_surface,
+        }
+    }
+
+    pub fn load(data: &[u8]) -> LinderaResult<ReadingIndex> {
+        let mut aligned = rkyv::util::AlignedVec::<16>::new();
+        aligned.extend_from_slice(data);
+        rkyv::from_bytes::<ReadingIndex, rkyv::rancor::Error>(&aligned).map_err(|err| {
+            LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!(err.to_string()))
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entries with the reading, which may be hiragana or katakana, ordered by word
+    /// cost.
+    pub fn lookup(&self, reading: &str) -> &[ReadingEntry] {
+        let reading = to_katakana(reading);
+        let start = self
+            .entries
+            .partition_point(|entry| entry.reading.as_str() < reading.as_str());
+        let end = self
+            .entries
+            .partition_point(|entry| entry.reading.as_str() <= reading.as_str());
+        &self.entries[start..end]
+    }
+
+    /// Returns the entries with the surface, ordered by reading.
+    pub fn surface_entries(&self, surface: &str) -> impl Iterator<Item = &ReadingEntry> {
+        let surface_of = |index: &u32| self.entries[*index as usize].surface.as_str();
+        let start = self
+            .by_surface
+            .partition_point(|index| surface_of(index) < surface);
+        let end = self
+            .by_surface
+            .partition_point(|index| surface_of(index) <= surface);
+        self.by_surface[start..end]
+            .iter()
+            .map(|index| &self.entries[*index as usize])
+    }
+
+    /// Returns the distinct surfaces with the reading, ordered by word cost.
+    pub fn homophones(&self, reading: &str) -> Vec<&str> {
+        let mut surfaces: Vec<&str> = Vec::new();
+        for entry in self.lookup(reading) {
+            if !surfaces.contains(&entry.surface.as_str()) {
+                surfaces.push(&entry.surface);
+            }
+        }
+        surfaces
+    }
+
+    /// Returns the other spellings of the surface, ordered by word cost.
+    ///
+    /// A spelling is a variant if it has a reading of the surface and either the same kanji in
+    /// the same order (okurigana variants such as `引っ越し`, `引越し` and `引越`), or is written
+    /// in kana only (`林檎` and `りんご`).
+    pub fn variants(&self, surface: &str) -> Vec<&str> {
+        let skeleton = kanji_skeleton(surface);
+
+        let mut readings: Vec<&str> = self
+            .surface_entries(surface)
+            .map(|entry| entry.reading.as_str())
+            .collect();
+        readings.dedup();
+
+        let mut variants: Vec<&ReadingEntry> = Vec::new();
+        for reading in readings {
+            for entry in self.lookup(reading) {
+                let entry_skeleton = kanji_skeleton(&entry.surface);
+                if entry.surface != surface
+                    && (entry_skeleton == skeleton
+                        || entry_skeleton.is_empty()
+                        || skeleton.is_empty())
+                    && !variants
+                        .iter()
+                        .any(|variant| variant.surface == entry.surface)
+                {
+                    variants.push(entry);
+                }
+            }
+        }
+        variants.sort_by_key(|entry| entry.word_cost);
+        variants
+            .into_iter()
+            .map(|entry| entry.surface.as_str())
+            .collect()
+    }
+
+    /// Returns the entries whose reading starts with `prefix`, which may be hiragana or katakana.
+    pub fn prefix_entries(&self, prefix: &str) -> &[ReadingEntry] {
+        let prefix = to_katakana(prefix);
+        let start = self
+            .entries
+            .partition_point(|entry| entry.reading.as_str() < prefix.as_str());
+        let len = self.entries[start..]
+            .iter()
+            .take_while(|entry| entry.reading.starts_with(&prefix))
+            .count();
+        &self.entries[start..start + len]
+    }
+
+    /// Returns up to `limit` candidate surfaces for a reading prefix, ranked by word cost.
+    ///
+    /// A surface listed with several parts of speech is returned once, with its lowest cost.
+    pub fn candidates(&self, prefix: &str, limit: usize) -> Vec<Candidate> {
+        let mut entries = self.prefix_entries(prefix).iter().collect::<Vec<_>>();
+        entries.sort_by_key(|entry| (entry.word_cost, entry.reading.len(), entry.word_id));
+
+        let mut candidates: Vec<Candidate> = Vec::new();
+        for entry in entries {
+            if candidates.len() >= limit {
+                break;
+            }
+            if candidates
+                .iter()
+                .any(|candidate| candidate.surface == entry.surface)
+            {
+                continue;
+            }
+            candidates.push(Candidate {
+                surface: entry.surface.clone(),
+                reading: entry.reading.clone(),
+                word_id: entry.word_id,
+                word_cost: entry.word_cost,
+            });
+        }
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    fn index() -> ReadingIndex {
+        ReadingIndex::build(vec![
+            ("東京", "トウキョウ", 0, 100),
+            ("京都", "キョウト", 1, 100),
+            ("東", "ヒガシ", 2, 200),
+            ("今日", "キョウ", 3, 50),
+            ("京", "キョウ", 4, 300),
+            ("今日", "キョウ", 5, 80),
+            ("記号", "*", 6, 0),
+        ])
+    }
+
+    #[test]
+    fn test_to_katakana() {
+        assert_eq!("キョウト", to_katakana("きょうと"));
+        assert_eq!("キョウト", to_katakana("キョウト"));
+        assert_eq!("東京ー", to_katakana("東京ー"));
+    }
+
+    #[test]
+    fn test_build() {
+        let index = index();
+        assert_eq!(6, index.len());
+        let readings = index
+            .entries
+            .iter()
+            .map(|entry| entry.reading.as_str())
+            .collect::<Vec<_>>();
+        let mut sorted = readings.clone();
+        sorted.sort();
+        assert_eq!(sorted, readings);
+    }
+
+    #[test]
+    fn test_prefix_entries() {
+        let index = index();
+        let surfaces = |prefix| {
+            index
+                .prefix_entries(prefix)
+                .iter()
+                .map(|entry| entry.surface.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["今日", "今日", "京", "京都"], surfaces("きょう"));
+        assert_eq!(vec!["京都"], surfaces("キョウト"));
+        assert!(surfaces("きょうとう").is_empty());
+        assert!(surfaces("あ").is_empty());
+        assert_eq!(6, surfaces("").len());
+    }
+
+    #[test]
+    fn test_lookup() {
+        let index = index();
+        let word_ids = |reading| {
+            index
+                .lookup(reading)
+                .iter()
+                .map(|entry| entry.word_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![3, 5, 4], word_ids("きょう"));
+        assert_eq!(vec![3, 5, 4], word_ids("キョウ"));
+        assert_eq!(vec![1], word_ids("きょうと"));
+        assert!(word_ids("きょ").is_empty());
+        assert!(word_ids("").is_empty());
+    }
+
+    #[test]
+    fn test_homophones() {
+        let index = index();
+        assert_eq!(vec!["今日", "京"], index.homophones("きょう"));
+        assert!(index.homophones("きょ").is_empty());
+    }
+
+    #[test]
+    fn test_variants() {
+        let index = ReadingIndex::build(vec![
+            ("引っ越し", "ヒッコシ", 0, 100),
+            ("引越し", "ヒッコシ", 1, 200),
+            ("引越", "ヒッコシ", 2, 300),
+            ("ひっこし", "ヒッコシ", 3, 400),
+            ("林檎", "リンゴ", 4, 100),
+            ("りんご", "リンゴ", 5, 200),
+            ("リンゴ", "リンゴ", 6, 300),
+            ("機械", "キカイ", 7, 100),
+            ("機会", "キカイ", 8, 200),
+        ]);
+
+        assert_eq!(
+            vec!["引越し", "引越", "ひっこし"],
+            index.variants("引っ越し")
+        );
+        assert_eq!(vec!["りんご", "リンゴ"], index.variants("林檎"));
+        assert_eq!(vec!["林檎", "リンゴ"], index.variants("りんご"));
+        // Homophones with other kanji are not variants
+        assert!(index.variants("機械").is_empty());
+        assert!(index.variants("未登録").is_empty());
+    }
+
+    #[test]
+    fn test_surface_entries() {
+        let index = index();
+        let word_ids = index
+            .surface_entries("今日")
+            .map(|entry| entry.word_id)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![3, 5], word_ids);
+        assert_eq!(0, index.surface_entries("明日").count());
+    }
+
+    #[test]
+    fn test_candidates() {
+        let index = index();
+        let candidates = index.candidates("きょう", 10);
+        assert_eq!(
+            vec!["今日", "京都", "京"],
+            candidates
+                .iter()
+                .map(|candidate| candidate.surface.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(50, candidates[0].word_cost);
+        assert_eq!(3, candidates[0].word_id);
+
+        assert_eq!(2, index.candidates("きょう", 2).len());
+        assert!(index.candidates("きょう", 0).is_empty());
+    }
+
+    #[test]
+    fn test_load() {
+        let index = index();
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&index).unwrap();
+        let loaded = ReadingIndex::load(&bytes).unwrap();
+        assert_eq!(index.entries, loaded.entries);
+        assert_eq!(index.by_surface, loaded.by_surface);
+        assert!(ReadingIndex::load(b"invalid").is_err());
+    }
+}
@@ -1,9 +1,19 @@
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap as HashMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(feature = "std")]
 use csv::StringRecord;
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
+#[cfg(feature = "std")]
 use crate::LinderaResult;
+#[cfg(feature = "std")]
 use crate::error::LinderaErrorKind;
 
 /// Dictionary schema that defines the structure of dictionary entries
@@ -123,6 +133,7 @@ impl Schema {
     }
 
     /// Validate that CSV row has all required fields
+    #[cfg(feature = "std")]
     pub fn validate_fields(&self, row: &StringRecord) -> LinderaResult<()> {
         if row.len() < self.fields.len() {
             return Err(LinderaErrorKind::Content.with_error(anyhow::anyhow!(
@@ -189,7 +200,7 @@ pub enum FieldType {
     Custom,
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
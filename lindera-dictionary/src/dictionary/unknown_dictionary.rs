@@ -1,4 +1,7 @@
-use std::str::FromStr;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::str::FromStr;
 
 use log::warn;
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
@@ -9,7 +12,7 @@ use crate::dictionary::character_definition::CategoryId;
 use crate::error::LinderaErrorKind;
 use crate::viterbi::WordEntry;
 
-#[derive(Serialize, Deserialize, Clone, Archive, RkyvSerialize, RkyvDeserialize)]
+#[derive(Serialize, Deserialize, Clone, Default, Archive, RkyvSerialize, RkyvDeserialize)]
 
 pub struct UnknownDictionary {
     pub category_references: Vec<Vec<u32>>,
@@ -17,12 +20,40 @@ pub struct UnknownDictionary {
 }
 
 impl UnknownDictionary {
+    /// Loads an unknown dictionary from the bytes of `unk.bin`.
+    ///
+    /// The archive is validated before it is deserialized, and every word id of a category must
+    /// refer to an entry, so a corrupted file returns an error.
+    ///
+    /// With the `no-unknown-grouping` feature, empty data loads an unknown dictionary without
+    /// entries, and each character that does not start a word of the dictionary is an unknown word.
     pub fn load(unknown_data: &[u8]) -> LinderaResult<UnknownDictionary> {
+        // `unk.bin` is not embedded with the `no-unknown-grouping` feature
+        #[cfg(feature = "no-unknown-grouping")]
+        if unknown_data.is_empty() {
+            return Ok(UnknownDictionary::default());
+        }
+
         let mut aligned = rkyv::util::AlignedVec::<16>::new();
         aligned.extend_from_slice(unknown_data);
-        rkyv::from_bytes::<UnknownDictionary, rkyv::rancor::Error>(&aligned).map_err(|err| {
-            LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!(err.to_string()))
-        })
+        let unknown_dictionary =
+            rkyv::from_bytes::<UnknownDictionary, rkyv::rancor::Error>(&aligned).map_err(
+                |err| LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!(err.to_string())),
+            )?;
+
+        let entries = unknown_dictionary.costs.len();
+        for (category_id, word_ids) in unknown_dictionary.category_references.iter().enumerate() {
+            if let Some(word_id) = word_ids
+                .iter()
+                .find(|word_id| **word_id as usize >= entries)
+            {
+                return Err(LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!(
+                    "corrupted dictionary component unk.bin: category {category_id} refers to entry {word_id} of {entries}"
+                )));
+            }
+        }
+
+        Ok(unknown_dictionary)
     }
 
     pub fn word_entry(&self, word_id: u32) -> WordEntry {
@@ -30,7 +61,10 @@ impl UnknownDictionary {
     }
 
     pub fn lookup_word_ids(&self, category_id: CategoryId) -> &[u32] {
-        &self.category_references[category_id.0][..]
+        // The categories of a character definition that does not match the dictionary have no words
+        self.category_references
+            .get(category_id.0)
+            .map_or(&[], |word_ids| &word_ids[..])
     }
 
     /// Unknown word generation with callback system
@@ -273,6 +307,52 @@ impl ArchivedUnknownDictionary {
     }
 
     pub fn lookup_word_ids(&self, category_id: CategoryId) -> &[rkyv::rend::u32_le] {
-        self.category_references[category_id.0].as_slice()
+        self.category_references
+            .get(category_id.0)
+            .map_or(&[], |word_ids| word_ids.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    use crate::dictionary::character_definition::CategoryId;
+    use crate::dictionary::unknown_dictionary::UnknownDictionary;
+    use crate::viterbi::WordEntry;
+
+    fn serialize(unknown_dictionary: &UnknownDictionary) -> alloc::vec::Vec<u8> {
+        rkyv::to_bytes::<rkyv::rancor::Error>(unknown_dictionary)
+            .unwrap()
+            .to_vec()
+    }
+
+    #[test]
+    fn test_load() {
+        let unknown_dictionary = UnknownDictionary {
+            category_references: vec![vec![0], vec![0, 1]],
+            costs: vec![WordEntry::default(); 2],
+        };
+        let bytes = serialize(&unknown_dictionary);
+
+        let loaded = UnknownDictionary::load(&bytes).unwrap();
+        assert_eq!(&[0, 1], loaded.lookup_word_ids(CategoryId(1)));
+        assert!(loaded.lookup_word_ids(CategoryId(2)).is_empty());
+
+        assert!(UnknownDictionary::load(&bytes[..bytes.len() / 2]).is_err());
+        assert!(UnknownDictionary::load(&[]).is_err());
+    }
+
+    #[test]
+    fn test_load_dangling_word_id() {
+        let unknown_dictionary = UnknownDictionary {
+            category_references: vec![vec![0, 2]],
+            costs: vec![WordEntry::default(); 2],
+        };
+        let err = UnknownDictionary::load(&serialize(&unknown_dictionary))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("unk.bin"));
     }
 }
@@ -1,4 +1,4 @@
-use std::fmt;
+use core::fmt;
 
 use serde::{Deserialize, Serialize};
 
@@ -18,6 +18,10 @@ pub enum LinderaErrorKind {
     Dictionary,
     Mode,
     FeatureDisabled,
+    Cancelled,
+    InputTooLarge,
+    Timeout,
+    Signature,
 }
 
 impl LinderaErrorKind {
@@ -0,0 +1,382 @@
+//! Versioning of the binary dictionary format.
+//!
+//! Every binary component of a dictionary (`char_def.bin`, `unk.bin`, `matrix.mtx`,
+//! `dict.*` and user dictionary `.bin` files) starts with a 16-byte header: the magic
+//! bytes `LINDERA\0`, the format version as a little-endian `u32`, and the 4-byte layout
+//! tag `LE32`. The same version is recorded as `format_version` in `metadata.json`.
+//!
+//! The layout of the payload does not depend on the platform that built it: integers are
+//! little-endian, `usize` values in rkyv archives are 32 bits wide and archived primitives
+//! are aligned (the 16-byte header keeps the payload aligned). Dictionaries can therefore be
+//! built on one platform and loaded on 32-bit and big-endian targets, which convert the
+//! integers when they read them. Components written before the layout tag was introduced
+//! have zeros in its place and the same layout.
+//!
+//! Files written before the header was introduced have no header and are treated as
+//! format version 1. They can still be loaded, and [`migrate_dictionary`] upgrades
+//! them in place.
+//!
+//! Format version 3 lets the records of `dict.words` point to details they share with other
+//! words. The payloads of versions 1 and 2 are read as they are.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+pub mod layout;
+
+#[cfg(feature = "std")]
+use std::fs;
+#[cfg(feature = "std")]
+use std::io::Write;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+#[cfg(feature = "std")]
+use log::debug;
+
+use crate::LinderaResult;
+#[cfg(feature = "std")]
+use crate::builder::metadata::MetadataBuilder;
+use crate::error::LinderaErrorKind;
+#[cfg(feature = "std")]
+use crate::loader::metadata::MetadataLoader;
+#[cfg(feature = "std")]
+use crate::util::read_file;
+
+/// Format version written by this version of the crate.
+pub const FORMAT_VERSION: u32 = 3;
+
+/// Format version of dictionaries built before the format was versioned.
+pub const LEGACY_FORMAT_VERSION: u32 = 1;
+
+/// Length of the header at the start of each binary component.
+pub const HEADER_LEN: usize = 16;
+
+const MAGIC: &[u8; 8] = b"LINDERA\0";
+
+/// Layout tag: little-endian integers and 32-bit `usize` values.
+const LAYOUT: &[u8; 4] = b"LE32";
+
+/// Offset of the layout tag in the header.
+const LAYOUT_OFFSET: usize = MAGIC.len() + 4;
+
+/// Binary components of a system dictionary.
+pub const DICTIONARY_COMPONENTS: &[&str] = &[
+    "char_def.bin",
+    "unk.bin",
+    "matrix.mtx",
+    "dict.da",
+    "dict.vals",
+    "dict.wordsidx",
+    "dict.words",
+];
+
+/// Returns the header written at the start of each binary component.
+pub fn format_header() -> [u8; HEADER_LEN] {
+    let mut header = [0u8; HEADER_LEN];
+    header[..MAGIC.len()].copy_from_slice(MAGIC);
+    header[MAGIC.len()..LAYOUT_OFFSET].copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+    header[LAYOUT_OFFSET..HEADER_LEN].copy_from_slice(LAYOUT);
+    header
+}
+
+/// Writes the format header.
+#[cfg(feature = "std")]
+pub fn write_format_header<W: Write>(writer: &mut W) -> LinderaResult<()> {
+    writer.write_all(&format_header()).map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(err)
+            .add_context("Failed to write format header")
+    })
+}
+
+const fn has_header(data: &[u8]) -> bool {
+    if data.len() < HEADER_LEN {
+        return false;
+    }
+    let mut i = 0;
+    while i < MAGIC.len() {
+        if data[i] != MAGIC[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Returns the format version of a binary component.
+pub fn read_format_version(data: &[u8]) -> u32 {
+    if has_header(data) {
+        let mut version = [0u8; 4];
+        version.copy_from_slice(&data[MAGIC.len()..LAYOUT_OFFSET]);
+        u32::from_le_bytes(version)
+    } else {
+        LEGACY_FORMAT_VERSION
+    }
+}
+
+/// Returns the data of a binary component without its header.
+///
+/// This is a `const fn` so that embedded dictionaries can strip the header at compile time.
+/// It does not check the version; use [`strip_format_header`] for data read at run time.
+pub const fn payload(data: &[u8]) -> &[u8] {
+    if has_header(data) {
+        data.split_at(HEADER_LEN).1
+    } else {
+        data
+    }
+}
+
+/// Fails if data of the given format version cannot be read by this version of the crate.
+pub fn check_format_version(version: u32) -> LinderaResult<()> {
+    if (LEGACY_FORMAT_VERSION..=FORMAT_VERSION).contains(&version) {
+        Ok(())
+    } else {
+        Err(LinderaErrorKind::Dictionary.with_error(anyhow::anyhow!(
+            "unsupported dictionary format version {version} (supported: {LEGACY_FORMAT_VERSION} to {FORMAT_VERSION}); \
+             the dictionary was probably built by a newer version of Lindera"
+        )))
+    }
+}
+
+/// Fails if the layout tag of a binary component is not the one this version of the crate
+/// reads.
+pub fn check_layout(data: &[u8]) -> LinderaResult<()> {
+    if !has_header(data) {
+        return Ok(());
+    }
+    let layout = &data[LAYOUT_OFFSET..HEADER_LEN];
+    if layout == LAYOUT || layout == [0u8; 4] {
+        Ok(())
+    } else {
+        Err(LinderaErrorKind::Dictionary.with_error(anyhow::anyhow!(
+            "unsupported dictionary layout {} (supported: {}); \
+             the dictionary was probably built by a newer version of Lindera",
+            String::from_utf8_lossy(layout),
+            String::from_utf8_lossy(LAYOUT)
+        )))
+    }
+}
+
+/// Checks the format version and layout of a binary component and returns its data without
+/// the header.
+pub fn strip_format_header(data: &[u8]) -> LinderaResult<&[u8]> {
+    check_format_version(read_format_version(data))?;
+    check_layout(data)?;
+    Ok(payload(data))
+}
+
+/// What [`migrate_dictionary`] did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// Format version of the dictionary before the migration.
+    pub from_version: u32,
+    /// Format version of the dictionary after the migration.
+    pub to_version: u32,
+    /// Files that were rewritten.
+    pub migrated_files: Vec<String>,
+}
+
+impl fmt::Display for MigrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.migrated_files.is_empty() {
+            write!(f, "already at format version {}", self.to_version)
+        } else {
+            write!(
+                f,
+                "migrated {} from format version {} to {}",
+                self.migrated_files.join(", "),
+                self.from_version,
+                self.to_version
+            )
+        }
+    }
+}
+
+/// Upgrades a dictionary built by an older version of the crate to the current format, in place.
+///
+/// Each file is written to a temporary file next to it and then renamed over the original,
+/// so an interrupted migration leaves every file either in the old or in the new format.
+#[cfg(feature = "std")]
+pub fn migrate_dictionary(dict_dir: &Path) -> LinderaResult<MigrationReport> {
+    let mut metadata = MetadataLoader::load(dict_dir)?;
+    check_format_version(metadata.format_version)?;
+
+    let mut migrated_files = Vec::new();
+    for file_name in DICTIONARY_COMPONENTS {
+        if migrate_component(&dict_dir.join(file_name))? {
+            migrated_files.push(file_name.to_string());
+        }
+    }
+
+    let from_version = metadata.format_version;
+    if from_version != FORMAT_VERSION {
+        metadata.format_version = FORMAT_VERSION;
+        MetadataBuilder::new().build(&metadata, dict_dir)?;
+        migrated_files.push("metadata.json".to_string());
+    }
+
+    Ok(MigrationReport {
+        from_version,
+        to_version: FORMAT_VERSION,
+        migrated_files,
+    })
+}
+
+/// Upgrades a user dictionary (`.bin`) built by an older version of the crate, in place.
+#[cfg(feature = "std")]
+pub fn migrate_user_dictionary(file: &Path) -> LinderaResult<MigrationReport> {
+    let data = read_file(file)?;
+    let from_version = read_format_version(&data);
+    check_format_version(from_version)?;
+
+    let mut migrated_files = Vec::new();
+    if migrate_component(file)? {
+        migrated_files.push(file.display().to_string());
+    }
+
+    Ok(MigrationReport {
+        from_version,
+        to_version: FORMAT_VERSION,
+        migrated_files,
+    })
+}
+
+/// Adds the current header to a binary component. Returns `false` if it is already current.
+#[cfg(feature = "std")]
+fn migrate_component(file: &Path) -> LinderaResult<bool> {
+    let data = read_file(file)?;
+    let version = read_format_version(&data);
+    check_format_version(version)?;
+    check_layout(&data)?;
+    if version == FORMAT_VERSION {
+        return Ok(false);
+    }
+    debug!("migrating {file:?} from format version {version} to {FORMAT_VERSION}");
+
+    // Version 1 is the same data without the header, and version 3 reads the data of version 2
+    // as it is: only its records of dict.words may share details.
+    let mut migrated = Vec::with_capacity(HEADER_LEN + data.len());
+    migrated.extend_from_slice(&format_header());
+    migrated.extend_from_slice(payload(&data));
+
+    let tmp_file = file.with_extension("migrating");
+    fs::write(&tmp_file, &migrated).map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to write file: {tmp_file:?}"))
+    })?;
+    fs::rename(&tmp_file, file).map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to replace file: {file:?}"))
+    })?;
+
+    Ok(true)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::builder::DictionaryBuilder;
+    use crate::builder::tests::write_sources;
+    use crate::dictionary::Dictionary;
+    use crate::dictionary::metadata::Metadata;
+
+    #[test]
+    fn test_header_round_trip() {
+        let data = [&format_header()[..], b"payload"].concat();
+        assert_eq!(read_format_version(&data), FORMAT_VERSION);
+        assert_eq!(strip_format_header(&data).unwrap(), b"payload");
+
+        // Data without a header is the legacy format
+        assert_eq!(read_format_version(b"payload"), LEGACY_FORMAT_VERSION);
+        assert_eq!(strip_format_header(b"payload").unwrap(), b"payload");
+    }
+
+    #[test]
+    fn test_header_layout() {
+        let expected = [&b"LINDERA\0"[..], &FORMAT_VERSION.to_le_bytes(), b"LE32"].concat();
+        assert_eq!(format_header().to_vec(), expected);
+
+        // Headers written before the layout tag was introduced
+        let mut data = format_header().to_vec();
+        data[12..16].fill(0);
+        data.extend_from_slice(b"payload");
+        assert_eq!(strip_format_header(&data).unwrap(), b"payload");
+
+        data[12..16].copy_from_slice(b"BE64");
+        let err = strip_format_header(&data).unwrap_err();
+        assert_eq!(err.kind(), LinderaErrorKind::Dictionary);
+    }
+
+    #[test]
+    fn test_archive_layout() {
+        use crate::decompress::{Algorithm, CompressedData};
+
+        // The archive is the same on every platform: the bytes, the algorithm as a
+        // little-endian `u32`, then the relative pointer and the length of the bytes as
+        // little-endian 32-bit integers.
+        let archive = rkyv::to_bytes::<rkyv::rancor::Error>(&CompressedData::new(
+            Algorithm::Gzip,
+            vec![1, 2, 3],
+        ))
+        .unwrap();
+        assert_eq!(
+            archive.as_slice(),
+            &[1, 2, 3, 0, 2, 0, 0, 0, 248, 255, 255, 255, 3, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_newer_version_rejected() {
+        let mut data = format_header().to_vec();
+        data[8..12].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        data.extend_from_slice(b"payload");
+
+        let err = strip_format_header(&data).unwrap_err();
+        assert_eq!(err.kind(), LinderaErrorKind::Dictionary);
+    }
+
+    #[test]
+    fn test_migrate_dictionary() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let dict_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), dict_dir.path())
+            .unwrap();
+        let built: Vec<Vec<u8>> = DICTIONARY_COMPONENTS
+            .iter()
+            .map(|file_name| fs::read(dict_dir.path().join(file_name)).unwrap())
+            .collect();
+
+        // Turn the dictionary into the legacy format
+        for (file_name, data) in DICTIONARY_COMPONENTS.iter().zip(&built) {
+            fs::write(dict_dir.path().join(file_name), payload(data)).unwrap();
+        }
+        let metadata_file = dict_dir.path().join("metadata.json");
+        let mut metadata: serde_json::Value =
+            serde_json::from_slice(&fs::read(&metadata_file).unwrap()).unwrap();
+        metadata.as_object_mut().unwrap().remove("format_version");
+        fs::write(&metadata_file, metadata.to_string()).unwrap();
+
+        // The legacy format can be loaded
+        let dictionary = Dictionary::load_from_path(dict_dir.path()).unwrap();
+        assert_eq!(dictionary.metadata.format_version, LEGACY_FORMAT_VERSION);
+
+        let report = migrate_dictionary(dict_dir.path()).unwrap();
+        assert_eq!(report.from_version, LEGACY_FORMAT_VERSION);
+        assert_eq!(report.migrated_files.len(), DICTIONARY_COMPONENTS.len() + 1);
+        for (file_name, data) in DICTIONARY_COMPONENTS.iter().zip(&built) {
+            assert_eq!(&fs::read(dict_dir.path().join(file_name)).unwrap(), data);
+        }
+        let dictionary = Dictionary::load_from_path(dict_dir.path()).unwrap();
+        assert_eq!(dictionary.metadata.format_version, FORMAT_VERSION);
+
+        // Migrating again does nothing
+        let report = migrate_dictionary(dict_dir.path()).unwrap();
+        assert!(report.migrated_files.is_empty());
+    }
+}
@@ -0,0 +1,615 @@
+//! Machine-readable description of the binary dictionary format.
+//!
+//! [`describe_format`] returns the layout of each binary component as written by this version
+//! of the crate. It is assembled from the constants and types the builder serializes with, and
+//! the tests parse freshly built components with nothing but the description, so it cannot drift
+//! from what the builder writes. External tools can use it to read the artifacts without
+//! depending on this crate.
+
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::type_name;
+
+use serde::Serialize;
+
+use crate::decompress::{Algorithm, CHUNK_INDEX_MAGIC};
+use crate::dictionary::UserDictionary;
+use crate::dictionary::character_definition::CharacterDefinition;
+use crate::dictionary::connection_cost_matrix::{QUANTIZED_MATRIX_FLAG, SHARDED_MATRIX_FLAG};
+use crate::dictionary::okurigana::OkuriganaVariants;
+use crate::dictionary::prefix_dictionary::SHARED_DETAILS_FLAG;
+use crate::dictionary::reading_index::ReadingIndex;
+use crate::dictionary::unknown_dictionary::UnknownDictionary;
+use crate::format::{FORMAT_VERSION, HEADER_LEN, LAYOUT, LAYOUT_OFFSET, MAGIC};
+use crate::viterbi::WordEntry;
+
+/// Description of the binary format written by this version of the crate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FormatDescription {
+    pub format_version: u32,
+    /// The header at the start of every binary component.
+    pub header: Vec<Field>,
+    /// How the payload that follows the header is stored.
+    pub container: Container,
+    pub components: Vec<Component>,
+}
+
+/// A fixed-size field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Field {
+    pub name: String,
+    /// `u16le`, `i16le`, `u32le`, `i32le` or `bytes`.
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// Offset of the field in its record, or from the end of the data for the fields of the
+    /// container.
+    pub offset: usize,
+    pub size: usize,
+    /// The value of the field if it is constant, with the bytes as a string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    pub description: String,
+}
+
+impl Field {
+    fn new(name: &str, ty: &str, offset: usize, size: usize, description: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            ty: ty.to_owned(),
+            offset,
+            size,
+            value: None,
+            description: description.to_owned(),
+        }
+    }
+
+    fn with_value(mut self, value: String) -> Self {
+        self.value = Some(value);
+        self
+    }
+}
+
+/// How the payload of a component is stored after the header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Container {
+    /// The payload is stored as it is.
+    Raw,
+    /// The payload is compressed and wrapped in an rkyv archive of `CompressedData`, whose root
+    /// object is at the end of the data.
+    Compressed {
+        archive_type: String,
+        /// The fields of the root object, with their offsets from the end of the data. The
+        /// offset of the compressed bytes is relative to the position of its own field.
+        root: Vec<Field>,
+        /// The ids of the compression algorithms.
+        algorithms: Vec<(String, u32)>,
+        /// Deflate data compressed in chunks ends with an index of the chunks followed by this
+        /// magic string.
+        chunk_index_magic: String,
+    },
+}
+
+/// A binary component of a dictionary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Component {
+    /// File name, with `{index}` standing for the index of a shard.
+    pub file: String,
+    pub description: String,
+    /// Whether the container of [`FormatDescription::container`] applies. User dictionaries
+    /// are not compressed.
+    pub contained: bool,
+    pub payload: Payload,
+}
+
+/// The layout of the payload of a component.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Payload {
+    /// A sequence of fixed-size records.
+    Records {
+        record_size: usize,
+        fields: Vec<Field>,
+    },
+    /// A sequence of records made of a length and as many bytes of UTF-8 text.
+    LengthPrefixed {
+        length: Field,
+        separator: String,
+        /// Records that continue the text of another record.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        shared: Option<Box<SharedText>>,
+    },
+    /// A sequence of `i16le` values, whose first value tells the variant of the layout.
+    Matrix { variants: Vec<MatrixVariant> },
+    /// A double-array Aho-Corasick automaton serialized by the `daachorse` crate.
+    DoubleArray {
+        value_type: String,
+        value_fields: Vec<BitField>,
+    },
+    /// An rkyv archive of a Rust type, with 32-bit little-endian `usize` values.
+    Rkyv { root_type: String },
+}
+
+/// The records of a [`Payload::LengthPrefixed`] component whose length has the bit `flag` set:
+/// the length, without the bit, is followed by the offset of another record, and the text of the
+/// record follows the text of that record after a separator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SharedText {
+    pub flag: u32,
+    pub offset: Field,
+}
+
+/// A variant of the layout of `matrix.mtx`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MatrixVariant {
+    pub name: String,
+    /// The first value of the matrix for this variant, `None` if it is a size.
+    pub flag: Option<i16>,
+    pub header: Vec<Field>,
+    pub description: String,
+}
+
+/// A range of bits of an integer value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BitField {
+    pub name: String,
+    pub shift: u32,
+    pub bits: u32,
+}
+
+/// Returns the description of the binary format written by this version of the crate.
+pub fn describe_format() -> FormatDescription {
+    let version_len = LAYOUT_OFFSET - MAGIC.len();
+    let header = vec![
+        Field::new(
+            "magic",
+            "bytes",
+            0,
+            MAGIC.len(),
+            "Identifies a Lindera component",
+        )
+        .with_value(String::from_utf8_lossy(MAGIC).into_owned()),
+        Field::new(
+            "format_version",
+            "u32le",
+            MAGIC.len(),
+            version_len,
+            "Format version of the component",
+        )
+        .with_value(FORMAT_VERSION.to_string()),
+        Field::new(
+            "layout",
+            "bytes",
+            LAYOUT_OFFSET,
+            HEADER_LEN - LAYOUT_OFFSET,
+            "Little-endian integers and 32-bit usize values; zeros in older components",
+        )
+        .with_value(String::from_utf8_lossy(LAYOUT).into_owned()),
+    ];
+
+    FormatDescription {
+        format_version: FORMAT_VERSION,
+        header,
+        container: container(),
+        components: components(),
+    }
+}
+
+fn container() -> Container {
+    if cfg!(feature = "compress") {
+        Container::Compressed {
+            archive_type: type_name::<crate::decompress::CompressedData>().to_owned(),
+            root: vec![
+                Field::new(
+                    "algorithm",
+                    "u32le",
+                    12,
+                    4,
+                    "Id of the compression algorithm",
+                ),
+                Field::new(
+                    "data_offset",
+                    "i32le",
+                    8,
+                    4,
+                    "Offset of the compressed bytes from this field",
+                ),
+                Field::new("data_len", "u32le", 4, 4, "Length of the compressed bytes"),
+            ],
+            algorithms: Algorithm::variants()
+                .into_iter()
+                .map(|algorithm| (algorithm.as_str().to_owned(), algorithm as u32))
+                .collect(),
+            chunk_index_magic: String::from_utf8_lossy(CHUNK_INDEX_MAGIC).into_owned(),
+        }
+    } else {
+        Container::Raw
+    }
+}
+
+fn rkyv<T>(file: &str, description: &str) -> Component {
+    Component {
+        file: file.to_owned(),
+        description: description.to_owned(),
+        contained: true,
+        payload: Payload::Rkyv {
+            root_type: type_name::<T>().to_owned(),
+        },
+    }
+}
+
+fn i16_fields(names: &[(&str, &str)]) -> Vec<Field> {
+    names
+        .iter()
+        .enumerate()
+        .map(|(index, (name, description))| Field::new(name, "i16le", 2 * index, 2, description))
+        .collect()
+}
+
+fn components() -> Vec<Component> {
+    let forward_size = ("forward_size", "Number of right ids of the words");
+    let backward_size = ("backward_size", "Number of left ids of the words");
+    let matrix = Payload::Matrix {
+        variants: vec![
+            MatrixVariant {
+                name: "transposed".to_owned(),
+                flag: Some(-1),
+                header: i16_fields(&[("flag", "Always -1"), forward_size, backward_size]),
+                description:
+                    "The costs follow as i16le, at forward_id + backward_id * forward_size"
+                        .to_owned(),
+            },
+            MatrixVariant {
+                name: "sharded".to_owned(),
+                flag: Some(SHARDED_MATRIX_FLAG),
+                header: i16_fields(&[
+                    ("flag", "Marks a sharded matrix"),
+                    forward_size,
+                    backward_size,
+                    ("shard_size", "Number of backward ids of each shard"),
+                    ("encoding", "0 for plain costs, 1 for quantized costs"),
+                ]),
+                description: "The costs are in matrix.{index}.mtx".to_owned(),
+            },
+            MatrixVariant {
+                name: "quantized".to_owned(),
+                flag: Some(QUANTIZED_MATRIX_FLAG),
+                header: i16_fields(&[
+                    ("flag", "Marks a quantized matrix"),
+                    forward_size,
+                    backward_size,
+                ]),
+                description: "The bias (i16le) and step (u16le) of each backward id follow, then \
+                              a u8 code per cost in the transposed layout; a cost is \
+                              bias + code * step, and code 255 stands for i16::MAX"
+                    .to_owned(),
+            },
+            MatrixVariant {
+                name: "legacy".to_owned(),
+                flag: None,
+                header: i16_fields(&[forward_size, backward_size]),
+                description: "Written by older versions; the costs follow as i16le, at \
+                              backward_id + forward_id * backward_size"
+                    .to_owned(),
+            },
+        ],
+    };
+
+    vec![
+        rkyv::<CharacterDefinition>("char_def.bin", "Character categories"),
+        rkyv::<UnknownDictionary>("unk.bin", "Word entries of unknown words"),
+        Component {
+            file: "matrix.mtx".to_owned(),
+            description: "Connection costs".to_owned(),
+            contained: true,
+            payload: matrix,
+        },
+        Component {
+            file: "matrix.{index}.mtx".to_owned(),
+            description: "Costs of a shard of a sharded matrix, plain (i16le) or quantized"
+                .to_owned(),
+            contained: true,
+            payload: Payload::Records {
+                record_size: 2,
+                fields: vec![Field::new("cost", "i16le", 0, 2, "Connection cost")],
+            },
+        },
+        Component {
+            file: "dict.da".to_owned(),
+            description: "Surfaces of the words".to_owned(),
+            contained: true,
+            payload: Payload::DoubleArray {
+                value_type: "u32".to_owned(),
+                value_fields: vec![
+                    BitField {
+                        name: "count".to_owned(),
+                        shift: 0,
+                        bits: 5,
+                    },
+                    BitField {
+                        name: "first_entry".to_owned(),
+                        shift: 5,
+                        bits: 27,
+                    },
+                ],
+            },
+        },
+        Component {
+            file: "dict.vals".to_owned(),
+            description: "Word entries of the surfaces, in the order of dict.da".to_owned(),
+            contained: true,
+            payload: Payload::Records {
+                record_size: WordEntry::SERIALIZED_LEN,
+                fields: vec![
+                    Field::new(
+                        "word_id",
+                        "u32le",
+                        0,
+                        4,
+                        "Index of the word in dict.wordsidx",
+                    ),
+                    Field::new("word_cost", "i16le", 4, 2, "Cost of the word"),
+                    Field::new("left_id", "u16le", 6, 2, "Left context id"),
+                    Field::new("right_id", "u16le", 8, 2, "Right context id"),
+                ],
+            },
+        },
+        Component {
+            file: "dict.wordsidx".to_owned(),
+            description: "Offset of the details of each word in dict.words".to_owned(),
+            contained: true,
+            payload: Payload::Records {
+                record_size: 4,
+                fields: vec![Field::new(
+                    "offset",
+                    "u32le",
+                    0,
+                    4,
+                    "Offset of the record; words with the same details share it",
+                )],
+            },
+        },
+        Component {
+            file: "dict.words".to_owned(),
+            description: "Details of the words".to_owned(),
+            contained: true,
+            payload: Payload::LengthPrefixed {
+                length: Field::new("length", "u32le", 0, 4, "Length of the details in bytes"),
+                separator: "\0".to_owned(),
+                shared: Some(Box::new(SharedText {
+                    flag: SHARED_DETAILS_FLAG,
+                    offset: Field::new(
+                        "shared_offset",
+                        "u32le",
+                        4,
+                        4,
+                        "Offset of the record of the part-of-speech and conjugation details the word shares with other words",
+                    ),
+                })),
+            },
+        },
+        rkyv::<ReadingIndex>("dict.readings", "Words by reading, optional"),
+        rkyv::<OkuriganaVariants>(
+            "dict.okurigana",
+            "Okurigana variants of the surfaces, optional",
+        ),
+        Component {
+            contained: false,
+            ..rkyv::<UserDictionary>("*.bin", "User dictionary")
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::builder::DictionaryBuilder;
+    use crate::builder::tests::write_sources;
+    use crate::decompress::{CompressedData, decompress};
+    use crate::dictionary::Dictionary;
+    use crate::dictionary::metadata::Metadata;
+    use crate::format::format_header;
+
+    fn read_field(data: &[u8], field: &Field) -> i64 {
+        let bytes = &data[field.offset..field.offset + field.size];
+        match field.ty.as_str() {
+            "u16le" => u16::from_le_bytes([bytes[0], bytes[1]]) as i64,
+            "i16le" => i16::from_le_bytes([bytes[0], bytes[1]]) as i64,
+            "u32le" => u32::from_le_bytes(bytes.try_into().unwrap()) as i64,
+            "i32le" => i32::from_le_bytes(bytes.try_into().unwrap()) as i64,
+            ty => panic!("unexpected type {ty}"),
+        }
+    }
+
+    /// Unpacks the payload of a component by following the description.
+    fn payload(description: &FormatDescription, data: &[u8]) -> Vec<u8> {
+        let header_len: usize = description.header.iter().map(|field| field.size).sum();
+        let data = &data[header_len..];
+        let Container::Compressed { root, .. } = &description.container else {
+            return data.to_vec();
+        };
+        // The fields of the root object are located from the end of the data
+        let field = |name: &str| {
+            let field = root.iter().find(|field| field.name == name).unwrap();
+            let position = data.len() - field.offset;
+            (
+                position,
+                read_field(
+                    &data[position..],
+                    &Field {
+                        offset: 0,
+                        ..field.clone()
+                    },
+                ),
+            )
+        };
+        let (_, algorithm) = field("algorithm");
+        let (position, data_offset) = field("data_offset");
+        let data_start = position as i64 + data_offset;
+        let (_, data_len) = field("data_len");
+        let algorithm = Algorithm::variants()
+            .into_iter()
+            .find(|candidate| *candidate as u32 as i64 == algorithm)
+            .unwrap();
+        let bytes = data[data_start as usize..(data_start + data_len) as usize].to_vec();
+        decompress(CompressedData::new(algorithm, bytes)).unwrap()
+    }
+
+    #[test]
+    fn test_header() {
+        let description = describe_format();
+        let header = format_header();
+        let header_len: usize = description.header.iter().map(|field| field.size).sum();
+        assert_eq!(header_len, header.len());
+        for field in &description.header {
+            let bytes = &header[field.offset..field.offset + field.size];
+            match field.ty.as_str() {
+                "bytes" => assert_eq!(
+                    field.value.as_deref(),
+                    Some(String::from_utf8_lossy(bytes).as_ref())
+                ),
+                _ => assert_eq!(
+                    field.value.as_deref(),
+                    Some(read_field(&header, field).to_string().as_str())
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_components_match_builder() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let dict_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), dict_dir.path())
+            .unwrap();
+        let dictionary = Dictionary::load_from_path(dict_dir.path()).unwrap();
+        let description = describe_format();
+        let component = |file: &str| {
+            let component = description
+                .components
+                .iter()
+                .find(|component| component.file == file)
+                .unwrap();
+            let data = fs::read(dict_dir.path().join(file)).unwrap();
+            (component.payload.clone(), payload(&description, &data))
+        };
+
+        // The word entries
+        let (
+            Payload::Records {
+                record_size,
+                fields,
+            },
+            vals,
+        ) = component("dict.vals")
+        else {
+            panic!("dict.vals is not described as records");
+        };
+        assert_eq!(vals, &dictionary.prefix_dictionary.vals_data[..]);
+        for record in vals.chunks(record_size) {
+            let entry = WordEntry::deserialize(record, true);
+            let values: Vec<i64> = fields
+                .iter()
+                .map(|field| read_field(record, field))
+                .collect();
+            assert_eq!(
+                values,
+                vec![
+                    entry.word_id.id as i64,
+                    entry.word_cost as i64,
+                    entry.left_id as i64,
+                    entry.right_id as i64
+                ]
+            );
+        }
+
+        // The details of the words
+        let (
+            Payload::Records {
+                record_size,
+                fields,
+            },
+            wordsidx,
+        ) = component("dict.wordsidx")
+        else {
+            panic!("dict.wordsidx is not described as records");
+        };
+        let (
+            Payload::LengthPrefixed {
+                length,
+                separator,
+                shared: Some(shared),
+            },
+            words,
+        ) = component("dict.words")
+        else {
+            panic!("dict.words is not described as length-prefixed records");
+        };
+        let text = |offset: usize| {
+            let len = read_field(&words[offset..], &length) as u32;
+            if len & shared.flag == 0 {
+                let start = offset + length.offset + length.size;
+                return (None, &words[start..start + len as usize]);
+            }
+            let start = offset + shared.offset.offset + shared.offset.size;
+            let len = (len & !shared.flag) as usize;
+            let shared_offset = read_field(&words[offset..], &shared.offset) as usize;
+            (Some(shared_offset), &words[start..start + len])
+        };
+        let mut shared_records = 0;
+        for (word_id, record) in wordsidx.chunks(record_size).enumerate() {
+            let offset = read_field(record, &fields[0]) as usize;
+            let details = match text(offset) {
+                (None, details) => details.to_vec(),
+                (Some(shared_offset), own) => {
+                    shared_records += 1;
+                    let (None, shared) = text(shared_offset) else {
+                        panic!("shared details are shared themselves");
+                    };
+                    [shared, separator.as_bytes(), own].concat()
+                }
+            };
+            assert_eq!(
+                Some(details),
+                dictionary
+                    .prefix_dictionary
+                    .word_details_bytes(word_id)
+                    .map(|details| details.into_owned())
+            );
+        }
+        assert!(shared_records > 0);
+
+        // The connection cost matrix
+        let (Payload::Matrix { variants }, matrix) = component("matrix.mtx") else {
+            panic!("matrix.mtx is not described as a matrix");
+        };
+        let flag = i16::from_le_bytes([matrix[0], matrix[1]]);
+        let variant = variants
+            .iter()
+            .find(|variant| variant.flag == Some(flag))
+            .unwrap();
+        let field = |name: &str| {
+            let field = variant
+                .header
+                .iter()
+                .find(|field| field.name == name)
+                .unwrap();
+            read_field(&matrix, field) as i32
+        };
+        assert_eq!(
+            field("forward_size"),
+            dictionary.connection_cost_matrix.forward_size as i32
+        );
+        assert_eq!(
+            field("backward_size"),
+            dictionary.connection_cost_matrix.backward_size as i32
+        );
+    }
+}
@@ -1,13 +1,31 @@
+//! Dictionary data structures, builders and loaders for Lindera.
+//!
+//! With the default `std` feature disabled, the crate is `no_std` + `alloc`: the lattice and
+//! Viterbi search ([`viterbi`]), the dictionary components ([`dictionary`]) and the format
+//! header helpers ([`format`]) remain available, and a dictionary is loaded from byte slices
+//! with [`Dictionary::from_bytes`](dictionary::Dictionary::from_bytes). Building, compression,
+//! file and memory-mapped loading require `std`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 #[cfg(feature = "build_rs")]
 pub mod assets;
+#[cfg(feature = "std")]
 pub mod builder;
+#[cfg(feature = "std")]
 pub mod compress;
 pub mod decompress;
 pub mod dictionary;
 pub mod error;
+pub mod format;
+#[cfg(feature = "std")]
 pub mod loader;
 pub mod macros;
 pub mod mode;
+#[cfg(feature = "signature")]
+pub mod signature;
 pub mod util;
 pub mod viterbi;
 
@@ -1,7 +1,11 @@
 pub mod character_definition;
 pub mod connection_cost_matrix;
 pub mod metadata;
+pub mod okurigana;
 pub mod prefix_dictionary;
+pub mod reading_index;
+#[cfg(feature = "s3")]
+pub mod s3;
 pub mod unknown_dictionary;
 pub mod user_dictionary;
 
@@ -33,6 +37,14 @@ pub trait DictionaryLoader {
             "This loader does not support load_temporary function"
         )))
     }
+
+    /// Load dictionary from a URI (optional; used by loaders registered for a URI scheme)
+    fn load_from_uri(&self, uri: &str) -> LinderaResult<Dictionary> {
+        let _ = uri;
+        Err(LinderaErrorKind::Io.with_error(anyhow::anyhow!(
+            "This loader does not support load_from_uri function"
+        )))
+    }
 }
 
 pub struct FSDictionaryLoader;
@@ -6,17 +6,28 @@ use crate::decompress::{CompressedData, decompress};
 use crate::dictionary::character_definition::CharacterDefinition;
 #[cfg(feature = "compress")]
 use crate::error::LinderaErrorKind;
-use crate::util::read_file;
+use crate::format::strip_format_header;
+use crate::util::read_component;
 
 pub struct CharacterDefinitionLoader {}
 
 impl CharacterDefinitionLoader {
-    #[allow(unused_mut)]
     pub fn load(input_dir: &Path) -> LinderaResult<CharacterDefinition> {
-        let raw_data = read_file(input_dir.join("char_def.bin").as_path())?;
+        Self::decode(&read_component(input_dir.join("char_def.bin").as_path())?)
+    }
 
+    /// Load from the contents of `char_def.bin`
+    pub fn load_from_bytes(data: &[u8]) -> LinderaResult<CharacterDefinition> {
+        Self::decode(
+            strip_format_header(data)
+                .map_err(|err| err.add_context("Invalid file: char_def.bin"))?,
+        )
+    }
+
+    #[allow(unused_mut)]
+    fn decode(raw_data: &[u8]) -> LinderaResult<CharacterDefinition> {
         let mut aligned_data = rkyv::util::AlignedVec::<16>::new();
-        aligned_data.extend_from_slice(&raw_data);
+        aligned_data.extend_from_slice(raw_data);
 
         #[cfg(feature = "compress")]
         {
@@ -3,20 +3,110 @@ use std::path::Path;
 use crate::LinderaResult;
 #[cfg(feature = "compress")]
 use crate::decompress::{CompressedData, decompress};
-use crate::dictionary::connection_cost_matrix::ConnectionCostMatrix;
-#[cfg(feature = "compress")]
+use crate::dictionary::connection_cost_matrix::{
+    ConnectionCostMatrix, matrix_shard_file_name, read_sharded_matrix_header,
+};
+use crate::dictionary::metadata::MatrixEncoding;
 use crate::error::LinderaErrorKind;
+use crate::format::strip_format_header;
 #[cfg(feature = "mmap")]
-use crate::util::mmap_file;
-use crate::util::read_file;
+use crate::util::mmap_component;
+use crate::util::read_component;
 
 pub struct ConnectionCostMatrixLoader {}
 
 impl ConnectionCostMatrixLoader {
-    #[allow(unused_mut)]
+    /// Loads `matrix.mtx` from the directory of a dictionary. The shards of a sharded matrix are
+    /// read from the directory and checked here, and decoded when they are first used.
     pub fn load(input_dir: &Path) -> LinderaResult<ConnectionCostMatrix> {
-        let mut data = read_file(input_dir.join("matrix.mtx").as_path())?;
+        let data = Self::decode(
+            read_component(input_dir.join("matrix.mtx").as_path())?,
+            "matrix.mtx",
+        )?;
+        match read_sharded_matrix_header(&data)? {
+            Some((forward_size, backward_size, shard_size, encoding)) => {
+                Self::load_shards_from_dir(
+                    input_dir,
+                    forward_size,
+                    backward_size,
+                    shard_size,
+                    encoding,
+                )
+            }
+            None => ConnectionCostMatrix::load(data),
+        }
+    }
+
+    /// Load from the contents of `matrix.mtx`
+    pub fn load_from_bytes(data: &[u8]) -> LinderaResult<ConnectionCostMatrix> {
+        Self::load_from_bytes_with_shards(data, |index| {
+            Err(LinderaErrorKind::NotFound.with_error(anyhow::anyhow!(
+                "{} not found",
+                matrix_shard_file_name(index)
+            )))
+        })
+    }
+
+    /// Load from the contents of `matrix.mtx`, with `shard` returning the contents of the shard
+    /// of a sharded matrix from its index, the same every time. Every shard is checked here, and
+    /// decoded again when it is first used.
+    pub fn load_from_bytes_with_shards<F>(
+        data: &[u8],
+        shard: F,
+    ) -> LinderaResult<ConnectionCostMatrix>
+    where
+        F: Fn(usize) -> LinderaResult<Vec<u8>> + Send + Sync + 'static,
+    {
+        let data = Self::decode(
+            strip_format_header(data)
+                .map_err(|err| err.add_context("Invalid file: matrix.mtx"))?
+                .to_vec(),
+            "matrix.mtx",
+        )?;
+        match read_sharded_matrix_header(&data)? {
+            Some((forward_size, backward_size, shard_size, encoding)) => {
+                ConnectionCostMatrix::from_shards(
+                    forward_size,
+                    backward_size,
+                    shard_size,
+                    encoding,
+                    Box::new(move |index| {
+                        let file_name = matrix_shard_file_name(index);
+                        let data = shard(index)?;
+                        let data = strip_format_header(&data)
+                            .map_err(|err| err.add_context(format!("Invalid file: {file_name}")))?;
+                        Self::decode(data.to_vec(), &file_name)
+                    }),
+                )
+            }
+            None => ConnectionCostMatrix::load(data),
+        }
+    }
+
+    fn load_shards_from_dir(
+        input_dir: &Path,
+        forward_size: u32,
+        backward_size: u32,
+        shard_size: u32,
+        encoding: MatrixEncoding,
+    ) -> LinderaResult<ConnectionCostMatrix> {
+        // The shards are read once, so that they are decoded from the data that was checked
+        let files = (0..backward_size.div_ceil(shard_size) as usize)
+            .map(|index| read_component(&input_dir.join(matrix_shard_file_name(index))))
+            .collect::<LinderaResult<Vec<_>>>()?;
+        ConnectionCostMatrix::from_shards(
+            forward_size,
+            backward_size,
+            shard_size,
+            encoding,
+            Box::new(move |index| {
+                Self::decode(files[index].clone(), &matrix_shard_file_name(index))
+            }),
+        )
+    }
 
+    #[allow(unused_mut, unused_variables)]
+    fn decode(mut data: Vec<u8>, file_name: &str) -> LinderaResult<Vec<u8>> {
         #[cfg(feature = "compress")]
         {
             let mut aligned_data = rkyv::util::AlignedVec::<16>::new();
@@ -27,7 +117,7 @@ impl ConnectionCostMatrixLoader {
                     |err| {
                         LinderaErrorKind::Deserialize
                             .with_error(anyhow::anyhow!(err.to_string()))
-                            .add_context("Failed to deserialize matrix.mtx data")
+                            .add_context(format!("Failed to deserialize {file_name} data"))
                     },
                 )?;
             data = decompress(compressed_data).map_err(|err| {
@@ -37,13 +127,24 @@ impl ConnectionCostMatrixLoader {
             })?;
         }
 
-        Ok(ConnectionCostMatrix::load(data))
+        Ok(data)
     }
 
     #[cfg(feature = "mmap")]
     pub fn load_mmap(input_dir: &Path) -> LinderaResult<ConnectionCostMatrix> {
-        let data = mmap_file(input_dir.join("matrix.mtx").as_path())?;
+        let data = mmap_component(input_dir.join("matrix.mtx").as_path())?;
+        if let Some((forward_size, backward_size, shard_size, encoding)) =
+            read_sharded_matrix_header(&data)?
+        {
+            return Self::load_shards_from_dir(
+                input_dir,
+                forward_size,
+                backward_size,
+                shard_size,
+                encoding,
+            );
+        }
 
-        Ok(ConnectionCostMatrix::load(data))
+        ConnectionCostMatrix::load(data)
     }
 }
@@ -0,0 +1,126 @@
+use std::path::Path;
+
+use crate::LinderaResult;
+#[cfg(feature = "compress")]
+use crate::decompress::{CompressedData, decompress};
+use crate::dictionary::okurigana::OkuriganaVariants;
+use crate::error::LinderaErrorKind;
+use crate::format::strip_format_header;
+use crate::util::read_component;
+
+pub struct OkuriganaVariantsLoader {}
+
+impl OkuriganaVariantsLoader {
+    /// Load the okurigana variant table (`dict.okurigana`) of a dictionary directory
+    ///
+    /// The table is only written when the dictionary is built with `okurigana_variants`
+    /// enabled; other dictionaries return a `NotFound` error.
+    pub fn load(input_dir: &Path) -> LinderaResult<OkuriganaVariants> {
+        let path = input_dir.join("dict.okurigana");
+        if !path.is_file() {
+            return Err(LinderaErrorKind::NotFound
+                .with_error(anyhow::anyhow!(
+                    "okurigana variant table not found: {}",
+                    path.display()
+                ))
+                .add_context("Rebuild the dictionary with okurigana_variants enabled"));
+        }
+        Self::decode(&read_component(&path)?)
+    }
+
+    /// Load from the contents of `dict.okurigana`
+    pub fn load_from_bytes(data: &[u8]) -> LinderaResult<OkuriganaVariants> {
+        Self::decode(
+            strip_format_header(data)
+                .map_err(|err| err.add_context("Invalid file: dict.okurigana"))?,
+        )
+    }
+
+    fn decode(raw_data: &[u8]) -> LinderaResult<OkuriganaVariants> {
+        #[cfg(feature = "compress")]
+        {
+            let mut aligned_data = rkyv::util::AlignedVec::<16>::new();
+            aligned_data.extend_from_slice(raw_data);
+            let compressed_data: CompressedData =
+                rkyv::from_bytes::<CompressedData, rkyv::rancor::Error>(&aligned_data).map_err(
+                    |err| {
+                        LinderaErrorKind::Deserialize
+                            .with_error(anyhow::anyhow!(err.to_string()))
+                            .add_context("Failed to deserialize dict.okurigana data")
+                    },
+                )?;
+
+            let decompressed_data = decompress(compressed_data).map_err(|err| {
+                LinderaErrorKind::Compression
+                    .with_error(err)
+                    .add_context("Failed to decompress okurigana variant table data")
+            })?;
+
+            OkuriganaVariants::load(&decompressed_data)
+        }
+
+        #[cfg(not(feature = "compress"))]
+        {
+            OkuriganaVariants::load(raw_data)
+                .map_err(|err| err.add_context("Failed to deserialize dict.okurigana data"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::DictionaryBuilder;
+    use crate::builder::tests::write_sources;
+    use crate::dictionary::Dictionary;
+    use crate::dictionary::metadata::Metadata;
+
+    #[test]
+    fn test_load() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let dict_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+        std::fs::write(
+            input_dir.path().join("okurigana.csv"),
+            "受け付け,0,0,100,名詞,一般,*,*,*,*,受け付け,ウケツケ,ウケツケ\n\
+             受付,0,0,100,名詞,一般,*,*,*,*,受付,ウケツケ,ウケツケ\n\
+             受け付ける,0,0,100,動詞,自立,*,*,一段,基本形,受け付ける,ウケツケル,ウケツケル\n",
+        )
+        .unwrap();
+        let metadata = Metadata {
+            okurigana_variants: true,
+            ..Default::default()
+        };
+        DictionaryBuilder::new(metadata)
+            .build_dictionary(input_dir.path(), dict_dir.path())
+            .unwrap();
+
+        let dictionary = Dictionary::load_from_path(dict_dir.path()).unwrap();
+        let okurigana_variants = OkuriganaVariantsLoader::load(dict_dir.path()).unwrap();
+        let base_forms = |surface: &str| {
+            okurigana_variants
+                .dict
+                .find_surface(surface)
+                .iter()
+                .map(|word_entry| {
+                    assert!(word_entry.word_id.is_system());
+                    dictionary.word_details(word_entry.word_id.id as usize)[6].to_string()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["受け付け"], base_forms("受付け"));
+        assert_eq!(vec!["受け付ける"], base_forms("受付ける"));
+        // 受付 is a word of the dictionary
+        assert!(base_forms("受付").is_empty());
+
+        // Dictionaries built without the table
+        let default_dir = tempfile::tempdir().unwrap();
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), default_dir.path())
+            .unwrap();
+        let err = OkuriganaVariantsLoader::load(default_dir.path())
+            .err()
+            .unwrap();
+        assert_eq!(LinderaErrorKind::NotFound, err.kind());
+    }
+}
@@ -6,20 +6,50 @@ use crate::decompress::{CompressedData, decompress};
 use crate::dictionary::prefix_dictionary::PrefixDictionary;
 #[cfg(feature = "compress")]
 use crate::error::LinderaErrorKind;
+use crate::format::strip_format_header;
 #[cfg(feature = "mmap")]
-use crate::util::mmap_file;
-use crate::util::read_file;
+use crate::util::mmap_component;
+use crate::util::read_component;
 
 pub struct PrefixDictionaryLoader {}
 
 impl PrefixDictionaryLoader {
-    #[allow(unused_mut)]
     pub fn load(input_dir: &Path) -> LinderaResult<PrefixDictionary> {
-        let mut da_data = read_file(input_dir.join("dict.da").as_path())?;
-        let mut vals_data = read_file(input_dir.join("dict.vals").as_path())?;
-        let mut words_idx_data = read_file(input_dir.join("dict.wordsidx").as_path())?;
-        let mut words_data = read_file(input_dir.join("dict.words").as_path())?;
+        Self::decode(
+            read_component(input_dir.join("dict.da").as_path())?,
+            read_component(input_dir.join("dict.vals").as_path())?,
+            read_component(input_dir.join("dict.wordsidx").as_path())?,
+            read_component(input_dir.join("dict.words").as_path())?,
+        )
+    }
 
+    /// Load from the contents of `dict.da`, `dict.vals`, `dict.wordsidx` and `dict.words`
+    pub fn load_from_bytes(
+        da_data: &[u8],
+        vals_data: &[u8],
+        words_idx_data: &[u8],
+        words_data: &[u8],
+    ) -> LinderaResult<PrefixDictionary> {
+        let strip = |data, file_name: &str| {
+            strip_format_header(data)
+                .map(<[u8]>::to_vec)
+                .map_err(|err| err.add_context(format!("Invalid file: {file_name}")))
+        };
+        Self::decode(
+            strip(da_data, "dict.da")?,
+            strip(vals_data, "dict.vals")?,
+            strip(words_idx_data, "dict.wordsidx")?,
+            strip(words_data, "dict.words")?,
+        )
+    }
+
+    #[allow(unused_mut)]
+    fn decode(
+        mut da_data: Vec<u8>,
+        mut vals_data: Vec<u8>,
+        mut words_idx_data: Vec<u8>,
+        mut words_data: Vec<u8>,
+    ) -> LinderaResult<PrefixDictionary> {
         #[cfg(feature = "compress")]
         {
             let mut aligned = rkyv::util::AlignedVec::<16>::new();
@@ -93,28 +123,16 @@ impl PrefixDictionaryLoader {
             })?;
         }
 
-        Ok(PrefixDictionary::load(
-            da_data,
-            vals_data,
-            words_idx_data,
-            words_data,
-            true,
-        ))
+        PrefixDictionary::load(da_data, vals_data, words_idx_data, words_data, true)
     }
 
     #[cfg(feature = "mmap")]
     pub fn load_mmap(input_dir: &Path) -> LinderaResult<PrefixDictionary> {
-        let da_data = mmap_file(input_dir.join("dict.da").as_path())?;
-        let vals_data = mmap_file(input_dir.join("dict.vals").as_path())?;
-        let words_idx_data = mmap_file(input_dir.join("dict.wordsidx").as_path())?;
-        let words_data = mmap_file(input_dir.join("dict.words").as_path())?;
+        let da_data = mmap_component(input_dir.join("dict.da").as_path())?;
+        let vals_data = mmap_component(input_dir.join("dict.vals").as_path())?;
+        let words_idx_data = mmap_component(input_dir.join("dict.wordsidx").as_path())?;
+        let words_data = mmap_component(input_dir.join("dict.words").as_path())?;
 
-        Ok(PrefixDictionary::load(
-            da_data,
-            vals_data,
-            words_idx_data,
-            words_data,
-            true,
-        ))
+        PrefixDictionary::load(da_data, vals_data, words_idx_data, words_data, true)
     }
 }
@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use crate::LinderaResult;
+#[cfg(feature = "compress")]
+use crate::decompress::{CompressedData, decompress};
+use crate::dictionary::reading_index::ReadingIndex;
+use crate::error::LinderaErrorKind;
+use crate::format::strip_format_header;
+use crate::util::read_component;
+
+pub struct ReadingIndexLoader {}
+
+impl ReadingIndexLoader {
+    /// Load the reading index (`dict.readings`) of a dictionary directory
+    ///
+    /// Dictionaries built before the reading index was introduced, or with a schema without a
+    /// `reading` field, have no `dict.readings` and return a `NotFound` error.
+    pub fn load(input_dir: &Path) -> LinderaResult<ReadingIndex> {
+        let path = input_dir.join("dict.readings");
+        if !path.is_file() {
+            return Err(LinderaErrorKind::NotFound
+                .with_error(anyhow::anyhow!(
+                    "reading index not found: {}",
+                    path.display()
+                ))
+                .add_context("Rebuild the dictionary with a schema that has a reading field"));
+        }
+        Self::decode(&read_component(&path)?)
+    }
+
+    /// Load from the contents of `dict.readings`
+    pub fn load_from_bytes(data: &[u8]) -> LinderaResult<ReadingIndex> {
+        Self::decode(
+            strip_format_header(data)
+                .map_err(|err| err.add_context("Invalid file: dict.readings"))?,
+        )
+    }
+
+    fn decode(raw_data: &[u8]) -> LinderaResult<ReadingIndex> {
+        #[cfg(feature = "compress")]
+        {
+            let mut aligned_data = rkyv::util::AlignedVec::<16>::new();
+            aligned_data.extend_from_slice(raw_data);
+            let compressed_data: CompressedData =
+                rkyv::from_bytes::<CompressedData, rkyv::rancor::Error>(&aligned_data).map_err(
+                    |err| {
+                        LinderaErrorKind::Deserialize
+                            .with_error(anyhow::anyhow!(err.to_string()))
+                            .add_context("Failed to deserialize dict.readings data")
+                    },
+                )?;
+
+            let decompressed_data = decompress(compressed_data).map_err(|err| {
+                LinderaErrorKind::Compression
+                    .with_error(err)
+                    .add_context("Failed to decompress reading index data")
+            })?;
+
+            ReadingIndex::load(&decompressed_data)
+        }
+
+        #[cfg(not(feature = "compress"))]
+        {
+            ReadingIndex::load(raw_data)
+                .map_err(|err| err.add_context("Failed to deserialize dict.readings data"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::DictionaryBuilder;
+    use crate::builder::tests::write_sources;
+    use crate::dictionary::metadata::Metadata;
+
+    #[test]
+    fn test_load() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let dict_dir = tempfile::tempdir().unwrap();
+        write_sources(input_dir.path());
+        DictionaryBuilder::new(Metadata::default())
+            .build_dictionary(input_dir.path(), dict_dir.path())
+            .unwrap();
+
+        let reading_index = ReadingIndexLoader::load(dict_dir.path()).unwrap();
+        assert_eq!(3, reading_index.len());
+        let candidates = reading_index.candidates("きょう", 10);
+        assert_eq!(1, candidates.len());
+        assert_eq!("京都", candidates[0].surface);
+        assert_eq!("キョウト", candidates[0].reading);
+        assert_eq!(100, candidates[0].word_cost);
+
+        // The reading index can be disabled
+        let metadata = Metadata {
+            reading_index_field: None,
+            ..Default::default()
+        };
+        let disabled_dir = tempfile::tempdir().unwrap();
+        DictionaryBuilder::new(metadata)
+            .build_dictionary(input_dir.path(), disabled_dir.path())
+            .unwrap();
+        assert!(!disabled_dir.path().join("dict.readings").exists());
+
+        // Dictionaries without a reading index
+        std::fs::remove_file(dict_dir.path().join("dict.readings")).unwrap();
+        let err = ReadingIndexLoader::load(dict_dir.path()).err().unwrap();
+        assert_eq!(LinderaErrorKind::NotFound, err.kind());
+    }
+}
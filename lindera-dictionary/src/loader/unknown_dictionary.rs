@@ -6,17 +6,27 @@ use crate::decompress::{CompressedData, decompress};
 use crate::dictionary::unknown_dictionary::UnknownDictionary;
 #[cfg(feature = "compress")]
 use crate::error::LinderaErrorKind;
-use crate::util::read_file;
+use crate::format::strip_format_header;
+use crate::util::read_component;
 
 pub struct UnknownDictionaryLoader {}
 
 impl UnknownDictionaryLoader {
-    #[allow(unused_mut)]
     pub fn load(input_dir: &Path) -> LinderaResult<UnknownDictionary> {
-        let raw_data = read_file(input_dir.join("unk.bin").as_path())?;
+        Self::decode(&read_component(input_dir.join("unk.bin").as_path())?)
+    }
 
+    /// Load from the contents of `unk.bin`
+    pub fn load_from_bytes(data: &[u8]) -> LinderaResult<UnknownDictionary> {
+        Self::decode(
+            strip_format_header(data).map_err(|err| err.add_context("Invalid file: unk.bin"))?,
+        )
+    }
+
+    #[allow(unused_mut)]
+    fn decode(raw_data: &[u8]) -> LinderaResult<UnknownDictionary> {
         let mut aligned_data = rkyv::util::AlignedVec::<16>::new();
-        aligned_data.extend_from_slice(&raw_data);
+        aligned_data.extend_from_slice(raw_data);
 
         #[cfg(feature = "compress")]
         {
@@ -38,7 +48,7 @@ impl UnknownDictionaryLoader {
             let mut aligned_decompressed = rkyv::util::AlignedVec::<16>::new();
             aligned_decompressed.extend_from_slice(&decompressed_data);
 
-            return UnknownDictionary::load(&aligned_decompressed);
+            UnknownDictionary::load(&aligned_decompressed)
         }
 
         #[cfg(not(feature = "compress"))]
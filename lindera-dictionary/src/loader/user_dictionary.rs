@@ -23,4 +23,33 @@ impl UserDictionaryLoader {
     ) -> LinderaResult<UserDictionary> {
         builder.build_user_dict(path.as_ref())
     }
+
+    /// Load user dictionary from the contents of a CSV file held in memory
+    pub fn load_from_csv_bytes(
+        builder: DictionaryBuilder,
+        data: &[u8],
+    ) -> LinderaResult<UserDictionary> {
+        builder.build_user_dict_from_bytes(data)
+    }
+}
+
+/// Loader for a user dictionary compiled into the binary with
+/// [`embed_user_dictionary!`](crate::embed_user_dictionary)
+pub struct EmbeddedUserDictionaryLoader {
+    data: &'static [u8],
+}
+
+impl EmbeddedUserDictionaryLoader {
+    pub const fn new(data: &'static [u8]) -> Self {
+        Self { data }
+    }
+
+    /// The embedded binary user dictionary
+    pub fn data(&self) -> &'static [u8] {
+        self.data
+    }
+
+    pub fn load(&self) -> LinderaResult<UserDictionary> {
+        UserDictionary::load(self.data)
+    }
 }
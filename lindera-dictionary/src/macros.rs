@@ -10,7 +10,7 @@ macro_rules! decompress_data {
             use $crate::decompress::{CompressedData, decompress};
 
             let mut aligned = rkyv::util::AlignedVec::<16>::new();
-            aligned.extend_from_slice(&$bytes[..]);
+            aligned.extend_from_slice($crate::format::payload(&$bytes[..]));
             match rkyv::from_bytes::<CompressedData, rkyv::rancor::Error>(&aligned) {
                 Ok(compressed_data) => {
                     // Successfully decoded as CompressedData, now decompress it
@@ -18,17 +18,44 @@ macro_rules! decompress_data {
                         Ok(decompressed) => decompressed,
                         Err(_) => {
                             // Decompression failed, fall back to raw data
-                            $bytes.to_vec()
+                            $crate::format::payload(&$bytes[..]).to_vec()
                         }
                     }
                 }
                 Err(_) => {
                     // Not compressed data format, use as raw binary
-                    $bytes.to_vec()
+                    $crate::format::payload(&$bytes[..]).to_vec()
                 }
             }
         });
         #[cfg(not(feature = "compress"))]
-        const $name: &'static [u8] = $bytes;
+        const $name: &'static [u8] = $crate::format::payload($bytes);
+    };
+}
+
+/// Embeds a user dictionary built by
+/// [`DictionaryBuilder::build_embedded_user_dictionary`](crate::builder::DictionaryBuilder::build_embedded_user_dictionary)
+/// in a build script, and returns an
+/// [`EmbeddedUserDictionaryLoader`](crate::loader::user_dictionary::EmbeddedUserDictionaryLoader)
+/// for it. The argument is the same path that was passed to the build script helper.
+///
+/// ```ignore
+/// use lindera_dictionary::embed_user_dictionary;
+/// use lindera_dictionary::loader::user_dictionary::EmbeddedUserDictionaryLoader;
+///
+/// static USER_DICTIONARY: EmbeddedUserDictionaryLoader =
+///     embed_user_dictionary!("resources/userdict.csv");
+///
+/// let user_dictionary = USER_DICTIONARY.load()?;
+/// ```
+#[macro_export]
+macro_rules! embed_user_dictionary {
+    ($input_file: literal) => {
+        $crate::loader::user_dictionary::EmbeddedUserDictionaryLoader::new(include_bytes!(concat!(
+            env!("OUT_DIR"),
+            "/lindera-user-dictionary/",
+            $input_file,
+            ".bin"
+        )))
     };
 }
@@ -0,0 +1,412 @@
+//! Ed25519 signatures of dictionaries, to refuse dictionaries that were not published by a trusted
+//! party or were modified after they were signed.
+//!
+//! - A dictionary directory is signed by `dictionary.sig` in the directory. The signed message is
+//!   a manifest of the SHA-256 digests of all the other files of the directory, so no file can be
+//!   modified, added, removed or swapped with the file of another signed dictionary.
+//! - A single file (an archive of a dictionary, a user dictionary) is signed by `<file>.sig` next
+//!   to it, over the bytes of the file.
+//!
+//! Signatures and keys are stored as hexadecimal text. A signing key is the 32-byte seed of an
+//! ed25519 key pair.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use ring::digest::{SHA256, digest};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{ED25519, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+
+use crate::LinderaResult;
+use crate::error::LinderaErrorKind;
+
+/// The signature of a dictionary directory, in the directory.
+pub const SIGNATURE_FILE: &str = "dictionary.sig";
+
+/// The extension appended to the name of a signed file to name its signature.
+pub const SIGNATURE_EXTENSION: &str = "sig";
+
+const PUBLIC_KEY_LEN: usize = 32;
+const SEED_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex<const N: usize>(text: &str, what: &str) -> LinderaResult<[u8; N]> {
+    let text = text.trim();
+    let invalid = || {
+        LinderaErrorKind::Signature.with_error(anyhow::anyhow!(
+            "invalid {what}: expected {} hexadecimal digits",
+            N * 2
+        ))
+    };
+    if text.len() != N * 2 || !text.is_ascii() {
+        return Err(invalid());
+    }
+
+    let mut bytes = [0u8; N];
+    for (byte, pair) in bytes.iter_mut().zip(text.as_bytes().chunks_exact(2)) {
+        let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+        *byte = u8::from_str_radix(pair, 16).map_err(|_| invalid())?;
+    }
+    Ok(bytes)
+}
+
+fn read(path: &Path) -> LinderaResult<Vec<u8>> {
+    fs::read(path).map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to read {}", path.display()))
+    })
+}
+
+fn write(path: &Path, contents: &str) -> LinderaResult<()> {
+    fs::write(path, contents).map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to write {}", path.display()))
+    })
+}
+
+/// The ed25519 public key of a party whose dictionaries are trusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicKey([u8; PUBLIC_KEY_LEN]);
+
+impl PublicKey {
+    pub fn from_bytes(bytes: [u8; PUBLIC_KEY_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; PUBLIC_KEY_LEN] {
+        &self.0
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = crate::error::LinderaError;
+
+    /// Parses a key written as 64 hexadecimal digits.
+    fn from_str(text: &str) -> LinderaResult<Self> {
+        from_hex(text, "public key").map(Self)
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&to_hex(&self.0))
+    }
+}
+
+/// The private key a publisher signs its dictionaries with.
+pub struct SigningKey {
+    seed: [u8; SEED_LEN],
+    key_pair: Ed25519KeyPair,
+}
+
+impl SigningKey {
+    /// Generates a new key from the system's secure random number generator.
+    pub fn generate() -> LinderaResult<Self> {
+        let mut seed = [0u8; SEED_LEN];
+        SystemRandom::new().fill(&mut seed).map_err(|_| {
+            LinderaErrorKind::Signature.with_error(anyhow::anyhow!("failed to generate a key"))
+        })?;
+        Self::from_seed(seed)
+    }
+
+    pub fn from_seed(seed: [u8; SEED_LEN]) -> LinderaResult<Self> {
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(&seed).map_err(|err| {
+            LinderaErrorKind::Signature.with_error(anyhow::anyhow!("invalid signing key: {err}"))
+        })?;
+        Ok(Self { seed, key_pair })
+    }
+
+    /// Reads a key from a file holding its seed as 64 hexadecimal digits.
+    pub fn from_file(path: &Path) -> LinderaResult<Self> {
+        let text = String::from_utf8(read(path)?).map_err(|err| {
+            LinderaErrorKind::Signature.with_error(anyhow::anyhow!("invalid signing key: {err}"))
+        })?;
+        Self::from_seed(from_hex(&text, "signing key")?)
+    }
+
+    /// The seed of the key as hexadecimal digits, to store it. Keep it secret.
+    pub fn seed_hex(&self) -> String {
+        to_hex(&self.seed)
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        let mut bytes = [0u8; PUBLIC_KEY_LEN];
+        bytes.copy_from_slice(self.key_pair.public_key().as_ref());
+        PublicKey(bytes)
+    }
+
+    /// Signs `message`, returning the signature as hexadecimal digits.
+    pub fn sign(&self, message: &[u8]) -> String {
+        to_hex(self.key_pair.sign(message).as_ref())
+    }
+
+    /// Signs the dictionary directory `dict_dir`, writing [`SIGNATURE_FILE`] in it.
+    pub fn sign_dictionary(&self, dict_dir: &Path) -> LinderaResult<PathBuf> {
+        let signature = self.sign(dictionary_manifest(dict_dir)?.as_bytes());
+        let signature_path = dict_dir.join(SIGNATURE_FILE);
+        write(&signature_path, &format!("{signature}\n"))?;
+        Ok(signature_path)
+    }
+
+    /// Signs the file `path`, writing its signature next to it.
+    pub fn sign_file(&self, path: &Path) -> LinderaResult<PathBuf> {
+        let signature = self.sign(&read(path)?);
+        let signature_path = signature_path(path);
+        write(&signature_path, &format!("{signature}\n"))?;
+        Ok(signature_path)
+    }
+}
+
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The seed is secret
+        f.debug_struct("SigningKey")
+            .field("public_key", &self.public_key())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The path of the signature of the file `path`: `path` with `.sig` appended.
+pub fn signature_path(path: &Path) -> PathBuf {
+    let mut signature_path = path.as_os_str().to_owned();
+    signature_path.push(".");
+    signature_path.push(SIGNATURE_EXTENSION);
+    PathBuf::from(signature_path)
+}
+
+/// The message a dictionary directory is signed over: a line `<sha256>  <file name>`, as written
+/// by `sha256sum`, for every file of the directory but [`SIGNATURE_FILE`], sorted by name.
+/// Subdirectories are not part of the dictionary.
+pub fn dictionary_manifest(dict_dir: &Path) -> LinderaResult<String> {
+    Ok(manifest(&read_dictionary_files(dict_dir)?))
+}
+
+/// Reads every file of the dictionary directory `dict_dir` but [`SIGNATURE_FILE`], by name.
+fn read_dictionary_files(dict_dir: &Path) -> LinderaResult<BTreeMap<String, Vec<u8>>> {
+    let entries = fs::read_dir(dict_dir).map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to read {}", dict_dir.display()))
+    })?;
+
+    let mut files = BTreeMap::new();
+    for entry in entries {
+        let entry = entry.map_err(|err| LinderaErrorKind::Io.with_error(anyhow::anyhow!(err)))?;
+        if !entry.path().is_file() {
+            continue;
+        }
+        let file_name = entry.file_name().into_string().map_err(|name| {
+            LinderaErrorKind::Signature.with_error(anyhow::anyhow!(
+                "file name is not valid UTF-8: {}",
+                name.to_string_lossy()
+            ))
+        })?;
+        if file_name != SIGNATURE_FILE {
+            let data = read(&entry.path())?;
+            files.insert(file_name, data);
+        }
+    }
+    Ok(files)
+}
+
+fn manifest(files: &BTreeMap<String, Vec<u8>>) -> String {
+    let mut manifest = String::new();
+    for (file_name, data) in files {
+        let file_digest = digest(&SHA256, data);
+        manifest.push_str(&format!("{}  {file_name}\n", to_hex(file_digest.as_ref())));
+    }
+    manifest
+}
+
+/// Checks signatures against a set of trusted public keys. A signature by any of the keys is
+/// accepted, so that keys can be rotated.
+#[derive(Debug, Clone, Default)]
+pub struct SignatureVerifier {
+    trusted_keys: Vec<PublicKey>,
+}
+
+impl SignatureVerifier {
+    pub fn new(trusted_keys: Vec<PublicKey>) -> Self {
+        Self { trusted_keys }
+    }
+
+    /// A verifier trusting the keys written as hexadecimal digits.
+    pub fn from_hex_keys<S: AsRef<str>>(trusted_keys: &[S]) -> LinderaResult<Self> {
+        trusted_keys
+            .iter()
+            .map(|key| key.as_ref().parse())
+            .collect::<LinderaResult<Vec<PublicKey>>>()
+            .map(Self::new)
+    }
+
+    pub fn trusted_keys(&self) -> &[PublicKey] {
+        &self.trusted_keys
+    }
+
+    /// Checks that `signature`, as hexadecimal digits, is a signature of `message` by a trusted
+    /// key.
+    pub fn verify(&self, message: &[u8], signature: &str) -> LinderaResult<()> {
+        let signature = from_hex::<SIGNATURE_LEN>(signature, "signature")?;
+        let trusted = self.trusted_keys.iter().any(|key| {
+            UnparsedPublicKey::new(&ED25519, key.as_bytes())
+                .verify(message, &signature)
+                .is_ok()
+        });
+        if trusted {
+            Ok(())
+        } else {
+            Err(LinderaErrorKind::Signature.with_error(anyhow::anyhow!(
+                "the signature does not match any trusted key"
+            )))
+        }
+    }
+
+    /// Checks the signature of the dictionary directory `dict_dir`.
+    pub fn verify_dictionary(&self, dict_dir: &Path) -> LinderaResult<()> {
+        self.verify_dictionary_files(dict_dir).map(|_| ())
+    }
+
+    /// Checks the signature of the dictionary directory `dict_dir` and returns the bytes of its
+    /// files by name, so that the bytes checked are the bytes used. Each file is read once.
+    pub fn verify_dictionary_files(
+        &self,
+        dict_dir: &Path,
+    ) -> LinderaResult<BTreeMap<String, Vec<u8>>> {
+        let signature = read_signature(&dict_dir.join(SIGNATURE_FILE))?;
+        let files = read_dictionary_files(dict_dir)?;
+        self.verify(manifest(&files).as_bytes(), &signature)
+            .map_err(|err| {
+                err.add_context(format!(
+                    "Dictionary {} is not signed by a trusted key or was modified",
+                    dict_dir.display()
+                ))
+            })?;
+        Ok(files)
+    }
+
+    /// Checks the signature of the file `path` and returns its bytes, so that the bytes checked
+    /// are the bytes used.
+    pub fn verify_file(&self, path: &Path) -> LinderaResult<Vec<u8>> {
+        let signature = read_signature(&signature_path(path))?;
+        let data = read(path)?;
+        self.verify(&data, &signature).map_err(|err| {
+            err.add_context(format!(
+                "{} is not signed by a trusted key or was modified",
+                path.display()
+            ))
+        })?;
+        Ok(data)
+    }
+}
+
+fn read_signature(signature_path: &Path) -> LinderaResult<String> {
+    match fs::read_to_string(signature_path) {
+        Ok(signature) => Ok(signature),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Err(LinderaErrorKind::Signature
+            .with_error(anyhow::anyhow!(
+                "unsigned: {} does not exist",
+                signature_path.display()
+            ))),
+        Err(err) => Err(LinderaErrorKind::Io
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to read {}", signature_path.display()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::error::LinderaErrorKind;
+    use crate::signature::{
+        PublicKey, SIGNATURE_FILE, SignatureVerifier, SigningKey, signature_path,
+    };
+
+    #[test]
+    fn test_sign_and_verify_dictionary() {
+        let dict_dir = tempfile::tempdir().unwrap();
+        fs::write(dict_dir.path().join("dict.da"), b"da").unwrap();
+        fs::write(dict_dir.path().join("metadata.json"), b"{}").unwrap();
+
+        let key = SigningKey::generate().unwrap();
+        let other_key = SigningKey::generate().unwrap();
+        key.sign_dictionary(dict_dir.path()).unwrap();
+        assert!(dict_dir.path().join(SIGNATURE_FILE).exists());
+
+        let verifier = SignatureVerifier::new(vec![other_key.public_key(), key.public_key()]);
+        verifier.verify_dictionary(dict_dir.path()).unwrap();
+
+        // Only the other key is trusted
+        let err = SignatureVerifier::new(vec![other_key.public_key()])
+            .verify_dictionary(dict_dir.path())
+            .unwrap_err();
+        assert_eq!(LinderaErrorKind::Signature, err.kind());
+
+        // A modified file, then an added one
+        fs::write(dict_dir.path().join("dict.da"), b"dA").unwrap();
+        assert!(verifier.verify_dictionary(dict_dir.path()).is_err());
+        fs::write(dict_dir.path().join("dict.da"), b"da").unwrap();
+        verifier.verify_dictionary(dict_dir.path()).unwrap();
+        fs::write(dict_dir.path().join("dict.words"), b"").unwrap();
+        assert!(verifier.verify_dictionary(dict_dir.path()).is_err());
+
+        // An unsigned dictionary
+        fs::remove_file(dict_dir.path().join(SIGNATURE_FILE)).unwrap();
+        let err = verifier.verify_dictionary(dict_dir.path()).unwrap_err();
+        assert!(err.to_string().contains("unsigned"));
+    }
+
+    #[test]
+    fn test_sign_and_verify_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("userdic.bin");
+        fs::write(&path, b"user dictionary").unwrap();
+
+        let key = SigningKey::generate().unwrap();
+        let verifier = SignatureVerifier::new(vec![key.public_key()]);
+        assert!(verifier.verify_file(&path).is_err());
+
+        assert_eq!(signature_path(&path), key.sign_file(&path).unwrap());
+        assert_eq!(
+            b"user dictionary".to_vec(),
+            verifier.verify_file(&path).unwrap()
+        );
+
+        fs::write(&path, b"user dictionarY").unwrap();
+        assert!(verifier.verify_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_keys() {
+        let key = SigningKey::generate().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("signing.key");
+        fs::write(&key_path, format!("{}\n", key.seed_hex())).unwrap();
+        let restored = SigningKey::from_file(&key_path).unwrap();
+        assert_eq!(key.public_key(), restored.public_key());
+        assert!(!format!("{key:?}").contains(&key.seed_hex()));
+
+        let public_key = key.public_key();
+        assert_eq!(
+            public_key,
+            public_key.to_string().parse::<PublicKey>().unwrap()
+        );
+        assert!("abcd".parse::<PublicKey>().is_err());
+        assert!("zz".repeat(32).parse::<PublicKey>().is_err());
+
+        let verifier = SignatureVerifier::from_hex_keys(&[public_key.to_string()]).unwrap();
+        let signature = key.sign(b"message");
+        verifier.verify(b"message", &signature).unwrap();
+        assert!(verifier.verify(b"massage", &signature).is_err());
+        assert!(verifier.verify(b"message", "00").is_err());
+    }
+}
@@ -1249,7 +1249,7 @@ impl SerializableModel {
             "version": self.metadata.version,
             "training_iterations": self.metadata.iterations,
             "regularization": self.metadata.regularization,
-            "updated_at": std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs()
+            "updated_at": crate::util::build_timestamp()
         });
 
         // Write updated metadata
@@ -1,20 +1,39 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Deref;
+#[cfg(feature = "std")]
 use std::fs::File;
+#[cfg(feature = "std")]
 use std::io::{Read, Write};
-use std::ops::Deref;
+#[cfg(feature = "std")]
 use std::path::Path;
+#[cfg(feature = "mmap")]
+use std::sync::Arc;
 
 #[cfg(feature = "mmap")]
 use memmap2::Mmap;
 
+#[cfg(feature = "std")]
 use anyhow::anyhow;
-use encoding_rs::Encoding;
+#[cfg(feature = "std")]
+use encoding_rs::{EUC_JP, Encoding, SHIFT_JIS, UTF_8};
+#[cfg(feature = "std")]
+use log::debug;
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "std")]
 use crate::LinderaResult;
+#[cfg(feature = "std")]
+use crate::compress::CompressionSettings;
 #[cfg(feature = "compress")]
-use crate::compress::compress;
+use crate::compress::compress_with_settings;
+#[cfg(feature = "std")]
 use crate::decompress::Algorithm;
+#[cfg(feature = "std")]
 use crate::error::LinderaErrorKind;
+#[cfg(feature = "std")]
+use crate::format;
 
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 
@@ -22,9 +41,10 @@ use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 pub fn compress_write<W: Write>(
     buffer: &[u8],
     algorithm: Algorithm,
+    settings: &CompressionSettings,
     writer: &mut W,
 ) -> LinderaResult<()> {
-    let compressed = compress(buffer, algorithm).map_err(|err| {
+    let compressed = compress_with_settings(buffer, algorithm, settings).map_err(|err| {
         LinderaErrorKind::Compression
             .with_error(err)
             .add_context(format!(
@@ -39,6 +59,7 @@ pub fn compress_write<W: Write>(
             .add_context("Failed to serialize compressed data")
     })?;
 
+    format::write_format_header(writer)?;
     writer.write_all(&bytes).map_err(|err| {
         LinderaErrorKind::Io
             .with_error(err)
@@ -48,12 +69,14 @@ pub fn compress_write<W: Write>(
     Ok(())
 }
 
-#[cfg(not(feature = "compress"))]
+#[cfg(all(feature = "std", not(feature = "compress")))]
 pub fn compress_write<W: Write>(
     buffer: &[u8],
     _algorithm: Algorithm,
+    _settings: &CompressionSettings,
     writer: &mut W,
 ) -> LinderaResult<()> {
+    format::write_format_header(writer)?;
     writer.write_all(buffer).map_err(|err| {
         LinderaErrorKind::Io
             .with_error(err)
@@ -63,6 +86,19 @@ pub fn compress_write<W: Write>(
     Ok(())
 }
 
+/// Reads a binary dictionary component and returns its data without the format header.
+#[cfg(feature = "std")]
+pub fn read_component(filename: &Path) -> LinderaResult<Vec<u8>> {
+    let mut data = read_file(filename)?;
+    let header_len = data.len()
+        - format::strip_format_header(&data)
+            .map_err(|err| err.add_context(format!("Invalid file: {}", filename.display())))?
+            .len();
+    data.drain(..header_len);
+    Ok(data)
+}
+
+#[cfg(feature = "std")]
 pub fn read_file(filename: &Path) -> LinderaResult<Vec<u8>> {
     let mut input_read = File::open(filename).map_err(|err| {
         LinderaErrorKind::Io
@@ -79,6 +115,101 @@ pub fn read_file(filename: &Path) -> LinderaResult<Vec<u8>> {
     Ok(buffer)
 }
 
+/// Applies `f` to every item, on multiple threads if the `parallel` feature is enabled.
+/// The results are returned in the order of the items.
+#[cfg(feature = "std")]
+pub fn par_map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        items.par_iter().map(f).collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+/// Runs both closures, in parallel if the `parallel` feature is enabled.
+#[cfg(feature = "std")]
+pub fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        rayon::join(a, b)
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        (a(), b())
+    }
+}
+
+/// Returns the number of threads used for parallel work.
+#[cfg(feature = "std")]
+pub fn num_threads() -> usize {
+    #[cfg(feature = "parallel")]
+    {
+        rayon::current_num_threads()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        1
+    }
+}
+
+/// Returns the timestamp (seconds since the Unix epoch) to record in generated files.
+///
+/// `SOURCE_DATE_EPOCH` is honored so that builds can be reproduced byte for byte.
+#[cfg(feature = "std")]
+pub fn build_timestamp() -> u64 {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default()
+        })
+}
+
+/// Formats seconds since the Unix epoch as an ISO 8601 UTC date and time.
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
 #[cfg(feature = "mmap")]
 pub fn mmap_file(filename: &Path) -> LinderaResult<Mmap> {
     let file = File::open(filename).map_err(|err| {
@@ -95,24 +226,89 @@ pub fn mmap_file(filename: &Path) -> LinderaResult<Mmap> {
     Ok(mmap)
 }
 
-pub fn read_file_with_encoding(filepath: &Path, encoding_name: &str) -> LinderaResult<String> {
-    let encoding = Encoding::for_label_no_replacement(encoding_name.as_bytes());
-    let encoding = encoding.ok_or_else(|| {
+/// Memory-maps a binary dictionary component, skipping the format header.
+#[cfg(feature = "mmap")]
+pub fn mmap_component(filename: &Path) -> LinderaResult<Data> {
+    let mmap = mmap_file(filename)?;
+    let header_len = mmap.len()
+        - format::strip_format_header(&mmap)
+            .map_err(|err| err.add_context(format!("Invalid file: {}", filename.display())))?
+            .len();
+    Ok(Data::Map(Arc::new(mmap), header_len))
+}
+
+/// Encoding label that makes the builder detect the encoding of each source file.
+pub const AUTO_ENCODING: &str = "auto";
+
+/// Returns `true` if the encoding label requests automatic detection.
+pub fn is_auto_encoding(encoding_name: &str) -> bool {
+    encoding_name.eq_ignore_ascii_case(AUTO_ENCODING)
+}
+
+/// Detects the encoding of dictionary source data.
+///
+/// A byte order mark takes precedence. Otherwise the data is tried as UTF-8, EUC-JP and
+/// Shift_JIS, the encodings used by the MeCab dictionary distributions. If the data is
+/// valid in both EUC-JP and Shift_JIS, the one decoding to fewer half-width katakana is
+/// chosen, since they rarely appear in dictionary sources but are what EUC-JP bytes
+/// usually turn into when read as Shift_JIS.
+/// Returns `None` if the data is not valid in any of them.
+#[cfg(feature = "std")]
+pub fn detect_encoding(buffer: &[u8]) -> Option<&'static Encoding> {
+    if let Some((encoding, _)) = Encoding::for_bom(buffer) {
+        return Some(encoding);
+    }
+    if std::str::from_utf8(buffer).is_ok() {
+        return Some(UTF_8);
+    }
+
+    [EUC_JP, SHIFT_JIS]
+        .into_iter()
+        .filter_map(|encoding| {
+            let text = encoding.decode_without_bom_handling_and_without_replacement(buffer)?;
+            let halfwidth_katakana = text
+                .chars()
+                .filter(|c| ('\u{FF61}'..='\u{FF9F}').contains(c))
+                .count();
+            Some((halfwidth_katakana, encoding))
+        })
+        .min_by_key(|(halfwidth_katakana, _)| *halfwidth_katakana)
+        .map(|(_, encoding)| encoding)
+}
+
+/// Resolves an encoding label, detecting the encoding from `buffer` if the label is `auto`.
+#[cfg(feature = "std")]
+pub fn resolve_encoding(encoding_name: &str, buffer: &[u8]) -> LinderaResult<&'static Encoding> {
+    if is_auto_encoding(encoding_name) {
+        return detect_encoding(buffer).ok_or_else(|| {
+            LinderaErrorKind::Decode.with_error(anyhow!(
+                "Failed to detect encoding (expected UTF-8, EUC-JP or Shift_JIS)"
+            ))
+        });
+    }
+
+    Encoding::for_label_no_replacement(encoding_name.as_bytes()).ok_or_else(|| {
         LinderaErrorKind::Decode.with_error(anyhow!("Invalid encoding: {encoding_name}"))
-    })?;
+    })
+}
 
+#[cfg(feature = "std")]
+pub fn read_file_with_encoding(filepath: &Path, encoding_name: &str) -> LinderaResult<String> {
     let buffer = read_file(filepath)?;
+    let encoding = resolve_encoding(encoding_name, &buffer)
+        .map_err(|err| err.add_context(format!("Failed to decode file: {filepath:?}")))?;
+    debug!("decoding {filepath:?} as {}", encoding.name());
+
     Ok(encoding.decode(&buffer).0.into_owned())
 }
 
-use std::sync::Arc;
-
 #[derive(Clone)]
 pub enum Data {
     Static(&'static [u8]),
     Vec(Vec<u8>),
+    /// Memory-mapped file and the offset at which the data starts.
     #[cfg(feature = "mmap")]
-    Map(Arc<Mmap>),
+    Map(Arc<Mmap>, usize),
 }
 
 impl Archive for Data {
@@ -148,7 +344,7 @@ impl Deref for Data {
             Data::Static(s) => s,
             Data::Vec(v) => v,
             #[cfg(feature = "mmap")]
-            Data::Map(m) => m,
+            Data::Map(m, offset) => &m[*offset..],
         }
     }
 }
@@ -174,7 +370,7 @@ impl From<Vec<u8>> for Data {
 #[cfg(feature = "mmap")]
 impl From<Mmap> for Data {
     fn from(m: Mmap) -> Self {
-        Self::Map(Arc::new(m))
+        Self::Map(Arc::new(m), 0)
     }
 }
 
@@ -196,3 +392,40 @@ impl<'de> Deserialize<'de> for Data {
         Ok(Data::Vec(v))
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "東京,名詞,固有名詞,地域,トウキョウ\n";
+
+    #[test]
+    fn test_detect_encoding() {
+        for encoding in [UTF_8, EUC_JP, SHIFT_JIS] {
+            let (bytes, _, _) = encoding.encode(TEXT);
+            assert_eq!(detect_encoding(&bytes), Some(encoding));
+        }
+    }
+
+    #[test]
+    fn test_detect_encoding_bom() {
+        let bytes = [&[0xEF, 0xBB, 0xBF][..], TEXT.as_bytes()].concat();
+        assert_eq!(detect_encoding(&bytes), Some(UTF_8));
+    }
+
+    #[test]
+    fn test_resolve_encoding() {
+        let (bytes, _, _) = EUC_JP.encode(TEXT);
+        assert_eq!(resolve_encoding("auto", &bytes).unwrap(), EUC_JP);
+        assert_eq!(resolve_encoding("Shift_JIS", &bytes).unwrap(), SHIFT_JIS);
+        assert!(resolve_encoding("INVALID-ENCODING", &bytes).is_err());
+        assert!(resolve_encoding("auto", &[0xFF, 0xFF, 0xFF]).is_err());
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14T22:13:20Z");
+    }
+}
@@ -1,6 +1,13 @@
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
 use std::io;
+#[cfg(feature = "std")]
+use std::time::Instant;
 
-use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
+#[cfg(feature = "std")]
+use byteorder::WriteBytesExt;
+use byteorder::{ByteOrder, LittleEndian};
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 use serde::{Deserialize, Serialize};
 
@@ -10,6 +17,11 @@ use crate::dictionary::prefix_dictionary::PrefixDictionary;
 use crate::dictionary::unknown_dictionary::UnknownDictionary;
 use crate::mode::Mode;
 
+/// The cost of the words made of a character without unknown word entries, when `unk.bin` is
+/// left out of the dictionary with the `no-unknown-grouping` feature.
+#[cfg(feature = "no-unknown-grouping")]
+const FALLBACK_UNKNOWN_WORD_COST: i16 = 10000;
+
 /// Type of lexicon containing the word
 #[derive(
     Clone,
@@ -119,6 +131,7 @@ impl WordEntry {
         self.right_id as u32
     }
 
+    #[cfg(feature = "std")]
     pub fn serialize<W: io::Write>(&self, wtr: &mut W) -> io::Result<()> {
         wtr.write_u32::<LittleEndian>(self.word_id.id)?;
         wtr.write_i16::<LittleEndian>(self.word_cost)?;
@@ -184,8 +197,15 @@ pub struct Lattice {
     char_info_buffer: Vec<CharData>,
     categories_buffer: Vec<CategoryId>,
     char_category_cache: Vec<Vec<CategoryId>>,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+    interrupted: bool,
 }
 
+/// The number of characters between two checks of the deadline.
+#[cfg(feature = "std")]
+const DEADLINE_CHECK_INTERVAL: usize = 256;
+
 #[derive(Clone, Copy, Debug, Default)]
 struct CharData {
     byte_offset: u32,
@@ -202,6 +222,40 @@ pub fn is_kanji(c: char) -> bool {
     (0x4E00..=0x9FAF).contains(&c) || (0x3400..=0x4DBF).contains(&c)
 }
 
+/// Reports the matches of the entries of a prefix dictionary in the text to the linked lists
+/// of matches starting at each byte (`matches_head` and `matches_store`).
+fn scan_prefix_dictionary(
+    dict: &PrefixDictionary,
+    is_system: bool,
+    text: &str,
+    matches_head: &mut [usize],
+    matches_store: &mut Vec<(usize, WordEntry, usize)>,
+) {
+    for m in dict.da.find_overlapping_iter(text) {
+        let start = m.start();
+        let id = m.value();
+        let count = id & ((1u32 << 5) - 1u32);
+        let offset = id >> 5u32;
+        let offset_bytes = (offset as usize) * WordEntry::SERIALIZED_LEN;
+
+        // Bounds check for safety, though daachorse should guarantee valid ids if built correctly
+        if offset_bytes < dict.vals_data.len() {
+            let data_slice = &dict.vals_data[offset_bytes..];
+            for i in 0..count {
+                let entry_offset = WordEntry::SERIALIZED_LEN * (i as usize);
+                if entry_offset + WordEntry::SERIALIZED_LEN <= data_slice.len() {
+                    let entry = WordEntry::deserialize(&data_slice[entry_offset..], is_system);
+                    if start < matches_head.len() {
+                        let next = matches_head[start];
+                        matches_head[start] = matches_store.len();
+                        matches_store.push((m.end(), entry, next));
+                    }
+                }
+            }
+        }
+    }
+}
+
 impl Lattice {
     /// Helper method to create an edge efficiently
     #[inline]
@@ -223,6 +277,18 @@ impl Lattice {
         }
     }
 
+    /// Sets the time after which `set_text` stops building the lattice. An interrupted lattice
+    /// has no path, and [`Lattice::is_interrupted`] returns true until the next `set_text`.
+    #[cfg(feature = "std")]
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline;
+    }
+
+    /// Whether the last call to `set_text` was interrupted by the deadline.
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted
+    }
+
     pub fn clear(&mut self) {
         for edge_vec in &mut self.ends_at {
             edge_vec.clear();
@@ -253,14 +319,41 @@ impl Lattice {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_text(
+        &mut self,
+        dict: &PrefixDictionary,
+        user_dict: &Option<&PrefixDictionary>,
+        char_definitions: &CharacterDefinition,
+        unknown_dictionary: &UnknownDictionary,
+        cost_matrix: &ConnectionCostMatrix,
+        text: &str,
+        search_mode: &Mode,
+    ) {
+        self.set_text_with_variants(
+            dict,
+            user_dict,
+            &None,
+            char_definitions,
+            unknown_dictionary,
+            cost_matrix,
+            text,
+            search_mode,
+        );
+    }
+
+    /// Same as [`Lattice::set_text`], also looking up the alternative spellings in
+    /// `variant_dict` (e.g. the okurigana variant table), whose entries are system words.
     #[inline(never)]
+    #[allow(clippy::too_many_arguments)]
     // Forward Viterbi implementation:
     // Constructs the lattice and calculates the path costs simultaneously.
     // This improves performance by avoiding a separate lattice traversal pass.
-    pub fn set_text(
+    pub fn set_text_with_variants(
         &mut self,
         dict: &PrefixDictionary,
         user_dict: &Option<&PrefixDictionary>,
+        variant_dict: &Option<&PrefixDictionary>,
         char_definitions: &CharacterDefinition,
         unknown_dictionary: &UnknownDictionary,
         cost_matrix: &ConnectionCostMatrix,
@@ -269,6 +362,7 @@ impl Lattice {
     ) {
         let len = text.len();
         self.set_capacity(len);
+        self.interrupted = false;
 
         // Pre-calculate character information for the text
         self.char_info_buffer.clear();
@@ -330,9 +424,11 @@ impl Lattice {
             }
         }
 
-        let mut start_edge = Edge::default();
-        start_edge.path_cost = 0;
-        start_edge.left_index = u16::MAX;
+        let start_edge = Edge {
+            path_cost: 0,
+            left_index: u16::MAX,
+            ..Default::default()
+        };
         self.ends_at[0].push(start_edge);
 
         // Index of the last character of unknown word
@@ -345,57 +441,29 @@ impl Lattice {
         let mut matches_store: Vec<(usize, WordEntry, usize)> = Vec::with_capacity(len * 10);
 
         // System dictionary scan
-        for m in dict.da.find_overlapping_iter(text) {
-            let start = m.start();
-            let id = m.value();
-            let count = id & ((1u32 << 5) - 1u32);
-            let offset = id >> 5u32;
-            let offset_bytes = (offset as usize) * WordEntry::SERIALIZED_LEN;
-
-            // Bounds check for safety, though daachorse should guarantee valid ids if built correctly
-            if offset_bytes < dict.vals_data.len() {
-                let data_slice = &dict.vals_data[offset_bytes..];
-                for i in 0..count {
-                    let entry_offset = WordEntry::SERIALIZED_LEN * (i as usize);
-                    if entry_offset + WordEntry::SERIALIZED_LEN <= data_slice.len() {
-                        let entry = WordEntry::deserialize(&data_slice[entry_offset..], true);
-                        if start < matches_head.len() {
-                            let next = matches_head[start];
-                            matches_head[start] = matches_store.len();
-                            matches_store.push((m.end(), entry, next));
-                        }
-                    }
-                }
-            }
+        scan_prefix_dictionary(dict, true, text, &mut matches_head, &mut matches_store);
+
+        // Alternative spellings of system words
+        if let Some(vd) = variant_dict {
+            scan_prefix_dictionary(vd, true, text, &mut matches_head, &mut matches_store);
         }
 
         // User dictionary scan
         if let Some(ud) = user_dict {
-            for m in ud.da.find_overlapping_iter(text) {
-                let start = m.start();
-                let id = m.value();
-                let count = id & ((1u32 << 5) - 1u32);
-                let offset = id >> 5u32;
-                let offset_bytes = (offset as usize) * WordEntry::SERIALIZED_LEN;
-
-                if offset_bytes < ud.vals_data.len() {
-                    let data_slice = &ud.vals_data[offset_bytes..];
-                    for i in 0..count {
-                        let entry_offset = WordEntry::SERIALIZED_LEN * (i as usize);
-                        if entry_offset + WordEntry::SERIALIZED_LEN <= data_slice.len() {
-                            let entry = WordEntry::deserialize(&data_slice[entry_offset..], false);
-                            if start < matches_head.len() {
-                                let next = matches_head[start];
-                                matches_head[start] = matches_store.len();
-                                matches_store.push((m.end(), entry, next));
-                            }
-                        }
-                    }
-                }
-            }
+            scan_prefix_dictionary(ud, false, text, &mut matches_head, &mut matches_store);
         }
 
         for char_idx in 0..self.char_info_buffer.len() - 1 {
+            #[cfg(feature = "std")]
+            if char_idx % DEADLINE_CHECK_INTERVAL == 0
+                && let Some(deadline) = self.deadline
+                && Instant::now() >= deadline
+            {
+                self.interrupted = true;
+                self.clear();
+                return;
+            }
+
             let start = self.char_info_buffer[char_idx].byte_offset as usize;
 
             // No arc is ending here.
@@ -454,9 +522,11 @@ impl Lattice {
 
         // Connect EOS
         if !self.ends_at[len].is_empty() {
-            let mut eos_edge = Edge::default();
-            eos_edge.start_index = len as u32;
-            eos_edge.stop_index = len as u32;
+            let mut eos_edge = Edge {
+                start_index: len as u32,
+                stop_index: len as u32,
+                ..Default::default()
+            };
             // Calculate cost for EOS
             let left_edges = &self.ends_at[len];
             let mut best_cost = i32::MAX;
@@ -498,6 +568,7 @@ impl Lattice {
         let category_data = char_definitions.lookup_definition(category);
         if category_data.invoke || !found {
             unknown_word_num_chars = 1;
+            #[cfg(not(feature = "no-unknown-grouping"))]
             if category_data.group {
                 for i in 1.. {
                     let next_idx = char_idx + i;
@@ -538,6 +609,26 @@ impl Lattice {
                 );
                 self.add_edge_in_lattice(edge, cost_matrix, search_mode);
             }
+            // Without `unk.bin`, a character not starting any word is a word of its own
+            #[cfg(feature = "no-unknown-grouping")]
+            if !found
+                && category_ord == 0
+                && unknown_dictionary.lookup_word_ids(category).is_empty()
+            {
+                let edge = Self::create_edge(
+                    EdgeType::UNKNOWN,
+                    WordEntry {
+                        word_id: WordId::new(LexType::Unknown, u32::MAX),
+                        word_cost: FALLBACK_UNKNOWN_WORD_COST,
+                        left_id: 0,
+                        right_id: 0,
+                    },
+                    start,
+                    start + byte_len,
+                    kanji_only,
+                );
+                self.add_edge_in_lattice(edge, cost_matrix, search_mode);
+            }
             return Some(start + byte_len);
         }
         unknown_word_index
@@ -600,11 +691,36 @@ impl Lattice {
         }
     }
 
+    /// Returns the edges of the lattice built by the last call to `set_text`,
+    /// ordered by their end position. Only edges reachable from the beginning of
+    /// the text are kept, so every edge has a valid `path_cost`. The BOS and EOS
+    /// edges are included as zero-length edges at the beginning and end of the text.
+    pub fn edges(&self) -> impl Iterator<Item = &Edge> {
+        self.ends_at.iter().flatten()
+    }
+
     pub fn tokens_offset(&self) -> Vec<(usize, WordId)> {
-        let mut offsets = Vec::new();
+        self.best_path()
+            .into_iter()
+            .map(|edge| (edge.start_index as usize, edge.word_entry.word_id))
+            .collect()
+    }
+
+    /// Returns the edges of the best path of the lattice built by the last call to `set_text`,
+    /// in order, without the BOS and EOS edges.
+    pub fn best_path(&self) -> Vec<&Edge> {
+        self.best_path_indices()
+            .into_iter()
+            .map(|(stop, index)| &self.ends_at[stop][index])
+            .collect()
+    }
+
+    /// The positions of the edges of the best path in `ends_at`, as `(stop, index)`.
+    fn best_path_indices(&self) -> Vec<(usize, usize)> {
+        let mut path = Vec::new();
 
         if self.ends_at.is_empty() {
-            return offsets;
+            return path;
         }
 
         let mut last_idx = self.ends_at.len() - 1;
@@ -613,14 +729,14 @@ impl Lattice {
         }
 
         if self.ends_at[last_idx].is_empty() {
-            return offsets;
+            return path;
         }
 
-        let idx = self.ends_at[last_idx].len() - 1;
-        let mut edge = &self.ends_at[last_idx][idx];
+        let mut position = (last_idx, self.ends_at[last_idx].len() - 1);
+        let mut edge = &self.ends_at[position.0][position.1];
 
         if edge.left_index == u16::MAX {
-            return offsets;
+            return path;
         }
 
         loop {
@@ -628,22 +744,176 @@ impl Lattice {
                 break;
             }
 
-            offsets.push((edge.start_index as usize, edge.word_entry.word_id));
+            path.push(position);
+
+            position = (edge.start_index as usize, edge.left_index as usize);
+            edge = &self.ends_at[position.0][position.1];
+        }
+
+        path.reverse();
+        path.pop(); // Remove EOS
+
+        path
+    }
+
+    /// Returns the marginal probability of each edge of the lattice built by the last call to
+    /// `set_text`, in the order of [`Lattice::edges`].
+    ///
+    /// The probability of a path is proportional to `exp(-theta * cost)`, with the costs of the
+    /// search (the penalties of `mode` included), and the marginal probability of an edge is the
+    /// sum of the probabilities of the paths through it: the smaller `theta`, the flatter the
+    /// distribution. It is computed with the forward-backward algorithm on the lattice, which
+    /// has to be built with the same `cost_matrix` and `mode`.
+    #[cfg(feature = "std")]
+    pub fn marginal_probabilities(
+        &self,
+        cost_matrix: &ConnectionCostMatrix,
+        mode: &Mode,
+        theta: f64,
+    ) -> Vec<f64> {
+        self.marginals(cost_matrix, mode, theta)
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Returns the marginal probabilities of the edges of [`Lattice::best_path`], in order. See
+    /// [`Lattice::marginal_probabilities`].
+    #[cfg(feature = "std")]
+    pub fn best_path_marginals(
+        &self,
+        cost_matrix: &ConnectionCostMatrix,
+        mode: &Mode,
+        theta: f64,
+    ) -> Vec<f64> {
+        let marginals = self.marginals(cost_matrix, mode, theta);
+        self.best_path_indices()
+            .into_iter()
+            .map(|(stop, index)| marginals[stop][index])
+            .collect()
+    }
+
+    /// The marginal probabilities of the edges, indexed as `ends_at`.
+    #[cfg(feature = "std")]
+    fn marginals(
+        &self,
+        cost_matrix: &ConnectionCostMatrix,
+        mode: &Mode,
+        theta: f64,
+    ) -> Vec<Vec<f64>> {
+        let mut marginals = self
+            .ends_at
+            .iter()
+            .map(|edges| vec![0.0; edges.len()])
+            .collect::<Vec<_>>();
+        // The EOS edge is the last edge of the last position
+        let Some(len) = self.ends_at.iter().rposition(|edges| !edges.is_empty()) else {
+            return marginals;
+        };
+        let eos_index = self.ends_at[len].len() - 1;
+        if len == 0 || self.ends_at[len][eos_index].start_index as usize != len {
+            return marginals;
+        }
 
-            let left_idx = edge.left_index as usize;
-            let start_idx = edge.start_index as usize;
+        // The cost of going from `left` to the word `right`, or to EOS
+        let transition = |left: &Edge, right: Option<&Edge>| -> f64 {
+            let cost = match right {
+                Some(right) => {
+                    cost_matrix.cost(left.word_entry.right_id(), right.word_entry.left_id())
+                        + mode.penalty_cost(left)
+                        + i32::from(right.word_entry.word_cost)
+                }
+                None => cost_matrix.cost(left.word_entry.right_id(), 0),
+            };
+            -theta * f64::from(cost)
+        };
+        let is_eos = |stop: usize, index: usize| stop == len && index == eos_index;
+
+        // Forward: the log of the sum of the probabilities of the paths from BOS to each edge
+        let mut alpha = marginals
+            .iter()
+            .map(|edges| vec![f64::NEG_INFINITY; edges.len()])
+            .collect::<Vec<_>>();
+        alpha[0][0] = 0.0;
+        for stop in 1..=len {
+            for (index, edge) in self.ends_at[stop].iter().enumerate() {
+                let start = edge.start_index as usize;
+                let right = (!is_eos(stop, index)).then_some(edge);
+                let lefts = self.ends_at[start]
+                    .iter()
+                    .enumerate()
+                    .filter(|&(left_index, _)| !is_eos(start, left_index))
+                    .map(|(left_index, left)| alpha[start][left_index] + transition(left, right));
+                alpha[stop][index] = log_sum_exp(lefts);
+            }
+        }
+        let log_z = alpha[len][eos_index];
+        if !log_z.is_finite() {
+            return marginals;
+        }
 
-            edge = &self.ends_at[start_idx][left_idx];
+        // The edges starting at each position, for the backward pass
+        let mut starts_at = vec![Vec::new(); len + 1];
+        for stop in 1..=len {
+            for (index, edge) in self.ends_at[stop].iter().enumerate() {
+                if !is_eos(stop, index) {
+                    starts_at[edge.start_index as usize].push((stop, index));
+                }
+            }
         }
 
-        offsets.reverse();
-        offsets.pop(); // Remove EOS
+        // Backward: the log of the sum of the probabilities of the paths from each edge to EOS
+        let mut beta = marginals
+            .iter()
+            .map(|edges| vec![f64::NEG_INFINITY; edges.len()])
+            .collect::<Vec<_>>();
+        beta[len][eos_index] = 0.0;
+        for stop in (0..=len).rev() {
+            for (index, edge) in self.ends_at[stop].iter().enumerate() {
+                if is_eos(stop, index) {
+                    continue;
+                }
+                let mut rights = starts_at[stop]
+                    .iter()
+                    .map(|&(right_stop, right_index)| {
+                        beta[right_stop][right_index]
+                            + transition(edge, Some(&self.ends_at[right_stop][right_index]))
+                    })
+                    .collect::<Vec<_>>();
+                if stop == len {
+                    rights.push(transition(edge, None));
+                }
+                beta[stop][index] = log_sum_exp(rights.into_iter());
+            }
+        }
+
+        for (stop, edges) in marginals.iter_mut().enumerate() {
+            for (index, marginal) in edges.iter_mut().enumerate() {
+                *marginal = (alpha[stop][index] + beta[stop][index] - log_z)
+                    .exp()
+                    .min(1.0);
+            }
+        }
+        marginals
+    }
+}
 
-        offsets
+/// The log of the sum of the exponentials of the values, `-inf` for none.
+#[cfg(feature = "std")]
+fn log_sum_exp(values: impl Iterator<Item = f64>) -> f64 {
+    let values = values.collect::<Vec<_>>();
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
     }
+    max + values
+        .iter()
+        .map(|value| (value - max).exp())
+        .sum::<f64>()
+        .ln()
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use crate::viterbi::{LexType, WordEntry, WordId};
 
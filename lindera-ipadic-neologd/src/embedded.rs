@@ -20,7 +20,7 @@ macro_rules! decompress_data {
         static $name: once_cell::sync::Lazy<Vec<u8>> = once_cell::sync::Lazy::new(|| {
             // First check if this is compressed data by attempting to check aligned root
             let mut aligned = rkyv::util::AlignedVec::<16>::new();
-            aligned.extend_from_slice(&$bytes[..]);
+            aligned.extend_from_slice(lindera_dictionary::format::payload(&$bytes[..]));
             match rkyv::from_bytes::<CompressedData, rkyv::rancor::Error>(&aligned) {
                 Ok(compressed_data) => {
                     // Decompress it
@@ -28,18 +28,18 @@ macro_rules! decompress_data {
                         Ok(decompressed) => decompressed,
                         Err(_) => {
                             // Decompression failed, fall back to raw data
-                            $bytes.to_vec()
+                            lindera_dictionary::format::payload(&$bytes[..]).to_vec()
                         }
                     }
                 }
                 Err(_) => {
                     // Not compressed data format, use as raw binary
-                    $bytes.to_vec()
+                    lindera_dictionary::format::payload(&$bytes[..]).to_vec()
                 }
             }
         });
         #[cfg(not(feature = "compress"))]
-        const $name: &'static [u8] = $bytes;
+        const $name: &'static [u8] = lindera_dictionary::format::payload($bytes);
     };
 }
 
@@ -54,6 +54,17 @@ macro_rules! ipadicneologd_data {
         #[cfg(not(feature = "embed-ipadic-neologd"))]
         decompress_data!($name, &[], $filename);
     };
+    // A component left out of the binary with the given feature
+    ($name: ident, $path: literal, $filename: literal, unless $feature: literal) => {
+        #[cfg(all(feature = "embed-ipadic-neologd", not(feature = $feature)))]
+        decompress_data!(
+            $name,
+            include_bytes!(concat!(env!("LINDERA_WORKDIR"), $path)),
+            $filename
+        );
+        #[cfg(any(not(feature = "embed-ipadic-neologd"), feature = $feature))]
+        decompress_data!($name, &[], $filename);
+    };
 }
 
 // Metadata-specific macro (skips compression/decompression processing)
@@ -78,16 +89,23 @@ ipadicneologd_data!(
 );
 ipadicneologd_data!(DA_DATA, "/lindera-ipadic-neologd/dict.da", "dict.da");
 ipadicneologd_data!(VALS_DATA, "/lindera-ipadic-neologd/dict.vals", "dict.vals");
-ipadicneologd_data!(UNKNOWN_DATA, "/lindera-ipadic-neologd/unk.bin", "unk.bin");
+ipadicneologd_data!(
+    UNKNOWN_DATA,
+    "/lindera-ipadic-neologd/unk.bin",
+    "unk.bin",
+    unless "no-unknown-grouping"
+);
 ipadicneologd_data!(
     WORDS_IDX_DATA,
     "/lindera-ipadic-neologd/dict.wordsidx",
-    "dict.wordsidx"
+    "dict.wordsidx",
+    unless "no-details"
 );
 ipadicneologd_data!(
     WORDS_DATA,
     "/lindera-ipadic-neologd/dict.words",
-    "dict.words"
+    "dict.words",
+    unless "no-details"
 );
 ipadicneologd_metadata!(
     METADATA_DATA,
@@ -108,8 +126,8 @@ pub fn load() -> LinderaResult<Dictionary> {
                 WORDS_IDX_DATA.deref(),
                 WORDS_DATA.deref(),
                 true,
-            ),
-            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA.deref()),
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA.deref())?,
             character_definition: CharacterDefinition::load(&CHAR_DEFINITION_DATA)?,
             unknown_dictionary: UnknownDictionary::load(&UNKNOWN_DATA)?,
             metadata,
@@ -124,8 +142,8 @@ pub fn load() -> LinderaResult<Dictionary> {
                 WORDS_IDX_DATA,
                 WORDS_DATA,
                 true,
-            ),
-            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA),
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA)?,
             character_definition: CharacterDefinition::load(CHAR_DEFINITION_DATA)?,
             unknown_dictionary: UnknownDictionary::load(UNKNOWN_DATA)?,
             metadata,
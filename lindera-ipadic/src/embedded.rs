@@ -20,7 +20,7 @@ macro_rules! decompress_data {
         static $name: once_cell::sync::Lazy<Vec<u8>> = once_cell::sync::Lazy::new(|| {
             // First check if this is compressed data by attempting to check aligned root
             let mut aligned = rkyv::util::AlignedVec::<16>::new();
-            aligned.extend_from_slice(&$bytes[..]);
+            aligned.extend_from_slice(lindera_dictionary::format::payload(&$bytes[..]));
             match rkyv::from_bytes::<CompressedData, rkyv::rancor::Error>(&aligned) {
                 Ok(compressed_data) => {
                     // Decompress it
@@ -28,18 +28,18 @@ macro_rules! decompress_data {
                         Ok(decompressed) => decompressed,
                         Err(_) => {
                             // Decompression failed, fall back to raw data
-                            $bytes.to_vec()
+                            lindera_dictionary::format::payload(&$bytes[..]).to_vec()
                         }
                     }
                 }
                 Err(_) => {
                     // Not compressed data format, use as raw binary
-                    $bytes.to_vec()
+                    lindera_dictionary::format::payload(&$bytes[..]).to_vec()
                 }
             }
         });
         #[cfg(not(feature = "compress"))]
-        const $name: &'static [u8] = $bytes;
+        const $name: &'static [u8] = lindera_dictionary::format::payload($bytes);
     };
 }
 
@@ -54,6 +54,17 @@ macro_rules! ipadic_data {
         #[cfg(not(feature = "embed-ipadic"))]
         decompress_data!($name, &[], $filename);
     };
+    // A component left out of the binary with the given feature
+    ($name: ident, $path: literal, $filename: literal, unless $feature: literal) => {
+        #[cfg(all(feature = "embed-ipadic", not(feature = $feature)))]
+        decompress_data!(
+            $name,
+            include_bytes!(concat!(env!("LINDERA_WORKDIR"), $path)),
+            $filename
+        );
+        #[cfg(any(not(feature = "embed-ipadic"), feature = $feature))]
+        decompress_data!($name, &[], $filename);
+    };
 }
 
 // Metadata-specific macro (skips compression/decompression processing)
@@ -74,13 +85,24 @@ ipadic_data!(
 ipadic_data!(CONNECTION_DATA, "/lindera-ipadic/matrix.mtx", "matrix.mtx");
 ipadic_data!(DA_DATA, "/lindera-ipadic/dict.da", "dict.da");
 ipadic_data!(VALS_DATA, "/lindera-ipadic/dict.vals", "dict.vals");
-ipadic_data!(UNKNOWN_DATA, "/lindera-ipadic/unk.bin", "unk.bin");
+ipadic_data!(
+    UNKNOWN_DATA,
+    "/lindera-ipadic/unk.bin",
+    "unk.bin",
+    unless "no-unknown-grouping"
+);
 ipadic_data!(
     WORDS_IDX_DATA,
     "/lindera-ipadic/dict.wordsidx",
-    "dict.wordsidx"
+    "dict.wordsidx",
+    unless "no-details"
+);
+ipadic_data!(
+    WORDS_DATA,
+    "/lindera-ipadic/dict.words",
+    "dict.words",
+    unless "no-details"
 );
-ipadic_data!(WORDS_DATA, "/lindera-ipadic/dict.words", "dict.words");
 ipadic_metadata!(
     METADATA_DATA,
     "/lindera-ipadic/metadata.json",
@@ -100,8 +122,8 @@ pub fn load() -> LinderaResult<Dictionary> {
                 WORDS_IDX_DATA.deref(),
                 WORDS_DATA.deref(),
                 true,
-            ),
-            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA.deref()),
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA.deref())?,
             character_definition: CharacterDefinition::load(&CHAR_DEFINITION_DATA)?,
             unknown_dictionary: UnknownDictionary::load(&UNKNOWN_DATA)?,
             metadata,
@@ -116,8 +138,8 @@ pub fn load() -> LinderaResult<Dictionary> {
                 WORDS_IDX_DATA,
                 WORDS_DATA,
                 true,
-            ),
-            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA),
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA)?,
             character_definition: CharacterDefinition::load(CHAR_DEFINITION_DATA)?,
             unknown_dictionary: UnknownDictionary::load(UNKNOWN_DATA)?,
             metadata,
@@ -155,6 +177,7 @@ impl DictionaryLoader for EmbeddedIPADICLoader {
 fn decompress_embedded_data(bytes: &[u8]) -> Vec<u8> {
     // First check if this is compressed data by attempting to check aligned root
     let mut aligned = rkyv::util::AlignedVec::<16>::new();
+    let bytes = lindera_dictionary::format::payload(bytes);
     aligned.extend_from_slice(bytes);
     match rkyv::from_bytes::<CompressedData, rkyv::rancor::Error>(&aligned) {
         Ok(compressed_data) => {
@@ -195,15 +218,22 @@ pub fn load_temporary() -> LinderaResult<Dictionary> {
         env!("LINDERA_WORKDIR"),
         "/lindera-ipadic/dict.vals"
     ));
+    #[cfg(not(feature = "no-details"))]
     let wordsidx_bytes = include_bytes!(concat!(
         env!("LINDERA_WORKDIR"),
         "/lindera-ipadic/dict.wordsidx"
     ));
+    #[cfg(not(feature = "no-details"))]
     let words_bytes = include_bytes!(concat!(
         env!("LINDERA_WORKDIR"),
         "/lindera-ipadic/dict.words"
     ));
+    #[cfg(feature = "no-details")]
+    let (wordsidx_bytes, words_bytes): (&[u8], &[u8]) = (&[], &[]);
+    #[cfg(not(feature = "no-unknown-grouping"))]
     let unk_bytes = include_bytes!(concat!(env!("LINDERA_WORKDIR"), "/lindera-ipadic/unk.bin"));
+    #[cfg(feature = "no-unknown-grouping")]
+    let unk_bytes: &[u8] = &[];
 
     #[cfg(feature = "compress")]
     {
@@ -222,8 +252,8 @@ pub fn load_temporary() -> LinderaResult<Dictionary> {
                 wordsidx_data,
                 words_data,
                 true,
-            ),
-            connection_cost_matrix: ConnectionCostMatrix::load(matrix_data),
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(matrix_data)?,
             character_definition: CharacterDefinition::load(&char_def_data)?,
             unknown_dictionary: UnknownDictionary::load(&unk_data)?,
             metadata,
@@ -231,17 +261,19 @@ pub fn load_temporary() -> LinderaResult<Dictionary> {
     }
     #[cfg(not(feature = "compress"))]
     {
+        use lindera_dictionary::format::payload;
+
         Ok(Dictionary {
             prefix_dictionary: PrefixDictionary::load(
-                da_bytes,
-                vals_bytes,
-                wordsidx_bytes,
-                words_bytes,
+                payload(da_bytes),
+                payload(vals_bytes),
+                payload(wordsidx_bytes),
+                payload(words_bytes),
                 true,
-            ),
-            connection_cost_matrix: ConnectionCostMatrix::load(matrix_bytes),
-            character_definition: CharacterDefinition::load(char_def_bytes)?,
-            unknown_dictionary: UnknownDictionary::load(unk_bytes)?,
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(payload(matrix_bytes))?,
+            character_definition: CharacterDefinition::load(payload(char_def_bytes))?,
+            unknown_dictionary: UnknownDictionary::load(payload(unk_bytes))?,
             metadata,
         })
     }
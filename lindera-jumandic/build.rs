@@ -0,0 +1,52 @@
+use std::error::Error;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn Error>> {
+    if std::env::var_os("LINDERA_DICTIONARIES_PATH").is_none()
+        && std::env::var_os("LINDERA_CACHE").is_none()
+        && cfg!(not(feature = "embed-jumandic"))
+    {
+        return Ok(());
+    }
+
+    // Without a local copy of the source, which is not downloaded, the dictionary can only be
+    // built when it is embedded, where the missing source is reported as an error
+    if cfg!(not(feature = "embed-jumandic"))
+        && std::env::var_os("LINDERA_JUMANDIC_SOURCE").is_none()
+    {
+        println!("cargo:rerun-if-changed=build.rs");
+        println!("cargo:rerun-if-env-changed=LINDERA_JUMANDIC_SOURCE");
+        return Ok(());
+    }
+
+    use std::fs;
+    use std::path::Path;
+
+    use lindera_dictionary::{
+        assets::{FetchParams, fetch},
+        builder::DictionaryBuilder,
+        dictionary::metadata::Metadata,
+    };
+
+    // The source of Jumandic is not mirrored, so it is not downloaded: set
+    // LINDERA_JUMANDIC_SOURCE to the MeCab dictionary mecab-jumandic-7.0-20130310
+    let fetch_params = FetchParams {
+        file_name: "mecab-jumandic-7.0-20130310.tar.gz",
+        input_dir: "mecab-jumandic-7.0-20130310",
+        output_dir: "lindera-jumandic",
+        dummy_input: "テスト,1,1,0,名詞,サ変名詞,*,*,テスト,てすと,代表表記:テスト/てすと\n",
+        download_urls: &[],
+        md5_hash: "",
+    };
+
+    // Read and deserialize metadata directly from JSON file
+    let metadata_path = Path::new("metadata.json");
+    let metadata_json = fs::read_to_string(metadata_path)?;
+    let metadata: Metadata = serde_json::from_str(&metadata_json)?;
+
+    let builder = DictionaryBuilder::new(metadata);
+
+    fetch(fetch_params, builder).await?;
+
+    Ok(())
+}
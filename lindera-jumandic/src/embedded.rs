@@ -0,0 +1,172 @@
+#[cfg(feature = "embed-jumandic")]
+use std::env;
+#[cfg(feature = "compress")]
+use std::ops::Deref;
+
+use lindera_dictionary::LinderaResult;
+#[cfg(feature = "compress")]
+use lindera_dictionary::decompress::{CompressedData, decompress};
+use lindera_dictionary::dictionary::Dictionary;
+use lindera_dictionary::dictionary::character_definition::CharacterDefinition;
+use lindera_dictionary::dictionary::connection_cost_matrix::ConnectionCostMatrix;
+use lindera_dictionary::dictionary::metadata::Metadata;
+use lindera_dictionary::dictionary::prefix_dictionary::PrefixDictionary;
+use lindera_dictionary::dictionary::unknown_dictionary::UnknownDictionary;
+use lindera_dictionary::loader::DictionaryLoader;
+
+macro_rules! decompress_data {
+    ($name: ident, $bytes: expr, $filename: literal) => {
+        #[cfg(feature = "compress")]
+        static $name: once_cell::sync::Lazy<Vec<u8>> = once_cell::sync::Lazy::new(|| {
+            // First check if this is compressed data by attempting to check aligned root
+            let mut aligned = rkyv::util::AlignedVec::<16>::new();
+            aligned.extend_from_slice(lindera_dictionary::format::payload(&$bytes[..]));
+            match rkyv::from_bytes::<CompressedData, rkyv::rancor::Error>(&aligned) {
+                Ok(compressed_data) => {
+                    // Decompress it
+                    match decompress(compressed_data) {
+                        Ok(decompressed) => decompressed,
+                        Err(_) => {
+                            // Decompression failed, fall back to raw data
+                            lindera_dictionary::format::payload(&$bytes[..]).to_vec()
+                        }
+                    }
+                }
+                Err(_) => {
+                    // Not compressed data format, use as raw binary
+                    lindera_dictionary::format::payload(&$bytes[..]).to_vec()
+                }
+            }
+        });
+        #[cfg(not(feature = "compress"))]
+        const $name: &'static [u8] = lindera_dictionary::format::payload($bytes);
+    };
+}
+
+macro_rules! jumandic_data {
+    ($name: ident, $path: literal, $filename: literal) => {
+        #[cfg(feature = "embed-jumandic")]
+        decompress_data!(
+            $name,
+            include_bytes!(concat!(env!("LINDERA_WORKDIR"), $path)),
+            $filename
+        );
+        #[cfg(not(feature = "embed-jumandic"))]
+        decompress_data!($name, &[], $filename);
+    };
+    // A component left out of the binary with the given feature
+    ($name: ident, $path: literal, $filename: literal, unless $feature: literal) => {
+        #[cfg(all(feature = "embed-jumandic", not(feature = $feature)))]
+        decompress_data!(
+            $name,
+            include_bytes!(concat!(env!("LINDERA_WORKDIR"), $path)),
+            $filename
+        );
+        #[cfg(any(not(feature = "embed-jumandic"), feature = $feature))]
+        decompress_data!($name, &[], $filename);
+    };
+}
+
+// Metadata-specific macro (skips compression/decompression processing)
+macro_rules! jumandic_metadata {
+    ($name: ident, $path: literal, $filename: literal) => {
+        #[cfg(feature = "embed-jumandic")]
+        const $name: &'static [u8] = include_bytes!(concat!(env!("LINDERA_WORKDIR"), $path));
+        #[cfg(not(feature = "embed-jumandic"))]
+        const $name: &'static [u8] = &[];
+    };
+}
+
+jumandic_data!(
+    CHAR_DEFINITION_DATA,
+    "/lindera-jumandic/char_def.bin",
+    "char_def.bin"
+);
+jumandic_data!(
+    CONNECTION_DATA,
+    "/lindera-jumandic/matrix.mtx",
+    "matrix.mtx"
+);
+jumandic_data!(DA_DATA, "/lindera-jumandic/dict.da", "dict.da");
+jumandic_data!(VALS_DATA, "/lindera-jumandic/dict.vals", "dict.vals");
+jumandic_data!(
+    UNKNOWN_DATA,
+    "/lindera-jumandic/unk.bin",
+    "unk.bin",
+    unless "no-unknown-grouping"
+);
+jumandic_data!(
+    WORDS_IDX_DATA,
+    "/lindera-jumandic/dict.wordsidx",
+    "dict.wordsidx",
+    unless "no-details"
+);
+jumandic_data!(
+    WORDS_DATA,
+    "/lindera-jumandic/dict.words",
+    "dict.words",
+    unless "no-details"
+);
+jumandic_metadata!(
+    METADATA_DATA,
+    "/lindera-jumandic/metadata.json",
+    "metadata.json"
+);
+
+pub fn load() -> LinderaResult<Dictionary> {
+    // Load metadata from embedded binary data
+    let metadata = Metadata::load(METADATA_DATA)?;
+
+    #[cfg(feature = "compress")]
+    {
+        Ok(Dictionary {
+            prefix_dictionary: PrefixDictionary::load(
+                DA_DATA.deref(),
+                VALS_DATA.deref(),
+                WORDS_IDX_DATA.deref(),
+                WORDS_DATA.deref(),
+                true,
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA.deref())?,
+            character_definition: CharacterDefinition::load(&CHAR_DEFINITION_DATA)?,
+            unknown_dictionary: UnknownDictionary::load(&UNKNOWN_DATA)?,
+            metadata,
+        })
+    }
+    #[cfg(not(feature = "compress"))]
+    {
+        Ok(Dictionary {
+            prefix_dictionary: PrefixDictionary::load(
+                DA_DATA,
+                VALS_DATA,
+                WORDS_IDX_DATA,
+                WORDS_DATA,
+                true,
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA)?,
+            character_definition: CharacterDefinition::load(CHAR_DEFINITION_DATA)?,
+            unknown_dictionary: UnknownDictionary::load(UNKNOWN_DATA)?,
+            metadata,
+        })
+    }
+}
+
+pub struct EmbeddedJumandicLoader;
+
+impl Default for EmbeddedJumandicLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmbeddedJumandicLoader {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DictionaryLoader for EmbeddedJumandicLoader {
+    fn load(&self) -> LinderaResult<Dictionary> {
+        load()
+    }
+}
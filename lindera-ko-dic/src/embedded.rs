@@ -20,7 +20,7 @@ macro_rules! decompress_data {
         static $name: once_cell::sync::Lazy<Vec<u8>> = once_cell::sync::Lazy::new(|| {
             // First check if this is compressed data by attempting to check aligned root
             let mut aligned = rkyv::util::AlignedVec::<16>::new();
-            aligned.extend_from_slice(&$bytes[..]);
+            aligned.extend_from_slice(lindera_dictionary::format::payload(&$bytes[..]));
             match rkyv::from_bytes::<CompressedData, rkyv::rancor::Error>(&aligned) {
                 Ok(compressed_data) => {
                     // Decompress it
@@ -28,18 +28,18 @@ macro_rules! decompress_data {
                         Ok(decompressed) => decompressed,
                         Err(_) => {
                             // Decompression failed, fall back to raw data
-                            $bytes.to_vec()
+                            lindera_dictionary::format::payload(&$bytes[..]).to_vec()
                         }
                     }
                 }
                 Err(_) => {
                     // Not compressed data format, use as raw binary
-                    $bytes.to_vec()
+                    lindera_dictionary::format::payload(&$bytes[..]).to_vec()
                 }
             }
         });
         #[cfg(not(feature = "compress"))]
-        const $name: &'static [u8] = $bytes;
+        const $name: &'static [u8] = lindera_dictionary::format::payload($bytes);
     };
 }
 
@@ -54,6 +54,17 @@ macro_rules! kodic_data {
         #[cfg(not(feature = "embed-ko-dic"))]
         decompress_data!($name, &[], $filename);
     };
+    // A component left out of the binary with the given feature
+    ($name: ident, $path: literal, $filename: literal, unless $feature: literal) => {
+        #[cfg(all(feature = "embed-ko-dic", not(feature = $feature)))]
+        decompress_data!(
+            $name,
+            include_bytes!(concat!(env!("LINDERA_WORKDIR"), $path)),
+            $filename
+        );
+        #[cfg(any(not(feature = "embed-ko-dic"), feature = $feature))]
+        decompress_data!($name, &[], $filename);
+    };
 }
 
 // Metadata-specific macro (skips compression/decompression processing)
@@ -74,13 +85,24 @@ kodic_data!(
 kodic_data!(CONNECTION_DATA, "/lindera-ko-dic/matrix.mtx", "matrix.mtx");
 kodic_data!(DA_DATA, "/lindera-ko-dic/dict.da", "dict.da");
 kodic_data!(VALS_DATA, "/lindera-ko-dic/dict.vals", "dict.vals");
-kodic_data!(UNKNOWN_DATA, "/lindera-ko-dic/unk.bin", "unk.bin");
+kodic_data!(
+    UNKNOWN_DATA,
+    "/lindera-ko-dic/unk.bin",
+    "unk.bin",
+    unless "no-unknown-grouping"
+);
 kodic_data!(
     WORDS_IDX_DATA,
     "/lindera-ko-dic/dict.wordsidx",
-    "dict.wordsidx"
+    "dict.wordsidx",
+    unless "no-details"
+);
+kodic_data!(
+    WORDS_DATA,
+    "/lindera-ko-dic/dict.words",
+    "dict.words",
+    unless "no-details"
 );
-kodic_data!(WORDS_DATA, "/lindera-ko-dic/dict.words", "dict.words");
 kodic_metadata!(
     METADATA_DATA,
     "/lindera-ko-dic/metadata.json",
@@ -100,8 +122,8 @@ pub fn load() -> LinderaResult<Dictionary> {
                 WORDS_IDX_DATA.deref(),
                 WORDS_DATA.deref(),
                 true,
-            ),
-            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA.deref()),
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA.deref())?,
             character_definition: CharacterDefinition::load(&CHAR_DEFINITION_DATA)?,
             unknown_dictionary: UnknownDictionary::load(&UNKNOWN_DATA)?,
             metadata,
@@ -116,8 +138,8 @@ pub fn load() -> LinderaResult<Dictionary> {
                 WORDS_IDX_DATA,
                 WORDS_DATA,
                 true,
-            ),
-            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA),
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA)?,
             character_definition: CharacterDefinition::load(CHAR_DEFINITION_DATA)?,
             unknown_dictionary: UnknownDictionary::load(UNKNOWN_DATA)?,
             metadata,
@@ -0,0 +1,505 @@
+//! # lindera-mecab
+//!
+//! A library exporting the core functions of the MeCab C API, backed by
+//! [Lindera](https://github.com/lindera/lindera).
+//!
+//! The crate builds `libmecab`, so an application linked against MeCab switches to Lindera by
+//! loading this library instead, without being rebuilt:
+//!
+//! ```c
+//! #include <mecab.h>
+//!
+//! mecab_t *mecab = mecab_new2("-d /path/to/lindera-ipadic");
+//! printf("%s", mecab_sparse_tostr(mecab, "すもももももももものうち"));
+//!
+//! for (const mecab_node_t *node = mecab_sparse_tonode(mecab, "関西国際空港"); node; node = node->next) {
+//!     if (node->stat != MECAB_BOS_NODE && node->stat != MECAB_EOS_NODE) {
+//!         printf("%.*s\t%s\n", node->length, node->surface, node->feature);
+//!     }
+//! }
+//!
+//! mecab_destroy(mecab);
+//! ```
+//!
+//! The dictionary given with `-d` is a Lindera dictionary (a directory built with `lindera build`
+//! or an `embedded://` URI), and the user dictionary given with `-u` is a Lindera user dictionary.
+//! The nodes are the ones of the best path, with their surface, feature, stat, context IDs and
+//! costs; the lattice and the N-best API are not provided.
+
+pub mod node;
+pub mod options;
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char, c_int, c_long};
+use std::ptr;
+use std::slice;
+
+use lindera::LinderaResult;
+use lindera::dictionary::{load_dictionary, load_user_dictionary};
+use lindera::error::LinderaErrorKind;
+use lindera::mode::Mode;
+use lindera::segmenter::Segmenter;
+use lindera::tokenizer::Tokenizer;
+
+use crate::node::{BOS_EOS_FEATURE, BOS_NODE, EOS_NODE, NOR_NODE, Node, UNK_NODE};
+use crate::options::{Options, OutputFormat};
+
+const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "\0");
+
+thread_local! {
+    /// The error of the last `mecab_new` or `mecab_new2` that failed on this thread.
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+/// Converts an error message to a C string, dropping the NUL bytes it cannot contain.
+fn error_string(message: impl ToString) -> CString {
+    let message = message.to_string().replace('\0', "");
+    CString::new(message).unwrap_or_default()
+}
+
+/// `mecab_t`: a tokenizer with the buffers of the strings and nodes it returns.
+pub struct Mecab {
+    tokenizer: Tokenizer,
+    output_format: OutputFormat,
+    output: CString,
+    features: Vec<CString>,
+    nodes: Vec<Node>,
+    error: CString,
+}
+
+impl Mecab {
+    pub fn new(options: &Options) -> LinderaResult<Self> {
+        let dictionary = load_dictionary(options.dictionary()?)?;
+        let user_dictionary = options
+            .userdic
+            .as_deref()
+            .map(|uri| load_user_dictionary(uri, &dictionary.metadata))
+            .transpose()?;
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, user_dictionary);
+
+        Ok(Self {
+            tokenizer: Tokenizer::new(segmenter),
+            output_format: options.output_format,
+            output: CString::default(),
+            features: Vec::new(),
+            nodes: Vec::new(),
+            error: CString::default(),
+        })
+    }
+
+    /// Tokenizes the sentence and returns the BOS node of the best path. The nodes point into
+    /// `sentence`, which must outlive them.
+    pub fn parse_to_node(&mut self, sentence: &[u8]) -> LinderaResult<*const Node> {
+        let text = std::str::from_utf8(sentence).map_err(|err| {
+            LinderaErrorKind::Decode.with_error(anyhow::anyhow!("invalid UTF-8 input: {err}"))
+        })?;
+        let mut tokens = self.tokenizer.tokenize(text)?;
+
+        self.features.clear();
+        for token in tokens.iter_mut() {
+            self.features.push(error_string(token.details().join(",")));
+        }
+
+        let base = sentence.as_ptr() as *const c_char;
+        self.nodes.clear();
+        self.nodes.reserve(tokens.len() + 2);
+        self.nodes.push(Node::new(
+            0,
+            base,
+            0,
+            0,
+            BOS_EOS_FEATURE.as_ptr() as *const c_char,
+            BOS_NODE,
+        ));
+        let mut end = 0;
+        for (token, feature) in tokens.iter().zip(&self.features) {
+            let stat = if token.word_id.is_unknown() {
+                UNK_NODE
+            } else {
+                NOR_NODE
+            };
+            let mut node = Node::new(
+                token.word_id.id,
+                base.wrapping_add(token.byte_start),
+                token.byte_end - token.byte_start,
+                token.byte_end.saturating_sub(end),
+                feature.as_ptr(),
+                stat,
+            );
+            if let Some(cost) = token.cost {
+                node.lc_attr = cost.left_id;
+                node.rc_attr = cost.right_id;
+                node.wcost = cost.word_cost;
+                node.cost = cost.path_cost as c_long;
+            }
+            self.nodes.push(node);
+            end = token.byte_end;
+        }
+        self.nodes.push(Node::new(
+            0,
+            base.wrapping_add(sentence.len()),
+            0,
+            sentence.len().saturating_sub(end),
+            BOS_EOS_FEATURE.as_ptr() as *const c_char,
+            EOS_NODE,
+        ));
+        node::link(&mut self.nodes);
+
+        Ok(self.nodes.as_ptr())
+    }
+
+    /// Tokenizes the sentence and returns the result in the output format of the options.
+    pub fn parse_to_str(&mut self, sentence: &[u8]) -> LinderaResult<&CStr> {
+        let text = std::str::from_utf8(sentence).map_err(|err| {
+            LinderaErrorKind::Decode.with_error(anyhow::anyhow!("invalid UTF-8 input: {err}"))
+        })?;
+        let mut tokens = self.tokenizer.tokenize(text)?;
+
+        let mut output = String::new();
+        match self.output_format {
+            OutputFormat::Mecab => {
+                for token in tokens.iter_mut() {
+                    output.push_str(&token.surface);
+                    output.push('\t');
+                    output.push_str(&token.details().join(","));
+                    output.push('\n');
+                }
+                output.push_str("EOS\n");
+            }
+            OutputFormat::Wakati => {
+                // MeCab ends each word, including the last one, with a space
+                for token in tokens.iter() {
+                    output.push_str(&token.surface);
+                    output.push(' ');
+                }
+                output.push('\n');
+            }
+        }
+        self.output = error_string(output);
+
+        Ok(self.output.as_c_str())
+    }
+}
+
+/// Reads the sentence given to the parse functions: `len` bytes, or up to the NUL byte if `len`
+/// is 0.
+unsafe fn sentence<'a>(str: *const c_char, len: usize) -> Option<&'a [u8]> {
+    if str.is_null() {
+        return None;
+    }
+    if len == 0 {
+        Some(unsafe { CStr::from_ptr(str) }.to_bytes())
+    } else {
+        Some(unsafe { slice::from_raw_parts(str as *const u8, len) })
+    }
+}
+
+fn new_mecab(options: LinderaResult<Options>) -> *mut Mecab {
+    match options.and_then(|options| Mecab::new(&options)) {
+        Ok(mecab) => Box::into_raw(Box::new(mecab)),
+        Err(err) => {
+            LAST_ERROR.with(|error| *error.borrow_mut() = error_string(err));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Creates a `mecab_t` from the arguments of a command line. Returns null on error; the error is
+/// given by `mecab_strerror(NULL)`.
+///
+/// # Safety
+///
+/// `argv` must point to `argc` NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mecab_new(argc: c_int, argv: *mut *mut c_char) -> *mut Mecab {
+    let args = if argv.is_null() || argc <= 0 {
+        Vec::new()
+    } else {
+        unsafe { slice::from_raw_parts(argv, argc as usize) }
+            .iter()
+            .filter(|arg| !arg.is_null())
+            .map(|arg| {
+                unsafe { CStr::from_ptr(*arg) }
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect()
+    };
+
+    new_mecab(Options::parse(args))
+}
+
+/// Creates a `mecab_t` from an argument string such as `"-d /path/to/dictionary"`. Returns null on
+/// error; the error is given by `mecab_strerror(NULL)`.
+///
+/// # Safety
+///
+/// `arg` must be null or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mecab_new2(arg: *const c_char) -> *mut Mecab {
+    let arg = if arg.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(arg) }
+            .to_string_lossy()
+            .into_owned()
+    };
+
+    new_mecab(Options::parse_str(&arg))
+}
+
+/// Returns the version of the library.
+#[unsafe(no_mangle)]
+pub extern "C" fn mecab_version() -> *const c_char {
+    VERSION.as_ptr() as *const c_char
+}
+
+/// Returns the last error of a `mecab_t`, or the last error of `mecab_new` and `mecab_new2` if
+/// `mecab` is null.
+///
+/// # Safety
+///
+/// `mecab` must be null or a `mecab_t` that was not destroyed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mecab_strerror(mecab: *mut Mecab) -> *const c_char {
+    match unsafe { mecab.as_ref() } {
+        Some(mecab) => mecab.error.as_ptr(),
+        None => LAST_ERROR.with(|error| error.borrow().as_ptr()),
+    }
+}
+
+/// Destroys a `mecab_t`, with the strings and nodes it returned.
+///
+/// # Safety
+///
+/// `mecab` must be null or a `mecab_t` that was not destroyed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mecab_destroy(mecab: *mut Mecab) {
+    if !mecab.is_null() {
+        drop(unsafe { Box::from_raw(mecab) });
+    }
+}
+
+/// Tokenizes a NUL-terminated sentence and returns the result as text. The string is valid until
+/// the next call with the same `mecab_t`. Returns null on error.
+///
+/// # Safety
+///
+/// `mecab` must be a `mecab_t` that was not destroyed, and `str` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mecab_sparse_tostr(
+    mecab: *mut Mecab,
+    str: *const c_char,
+) -> *const c_char {
+    unsafe { mecab_sparse_tostr2(mecab, str, 0) }
+}
+
+/// Tokenizes the first `len` bytes of a sentence and returns the result as text. The string is
+/// valid until the next call with the same `mecab_t`. Returns null on error.
+///
+/// # Safety
+///
+/// `mecab` must be a `mecab_t` that was not destroyed, and `str` must point to `len` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mecab_sparse_tostr2(
+    mecab: *mut Mecab,
+    str: *const c_char,
+    len: usize,
+) -> *const c_char {
+    let Some(mecab) = (unsafe { mecab.as_mut() }) else {
+        return ptr::null();
+    };
+    let Some(sentence) = (unsafe { sentence(str, len) }) else {
+        mecab.error = error_string("the sentence is null");
+        return ptr::null();
+    };
+
+    match mecab.parse_to_str(sentence) {
+        Ok(output) => output.as_ptr(),
+        Err(err) => {
+            mecab.error = error_string(err);
+            ptr::null()
+        }
+    }
+}
+
+/// Tokenizes the first `len` bytes of a sentence and writes the result as text into the `olen`
+/// bytes of `ostr`. Returns `ostr`, or null on error or if the result does not fit.
+///
+/// # Safety
+///
+/// `mecab` must be a `mecab_t` that was not destroyed, `str` must point to `len` bytes and `ostr`
+/// to `olen` writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mecab_sparse_tostr3(
+    mecab: *mut Mecab,
+    str: *const c_char,
+    len: usize,
+    ostr: *mut c_char,
+    olen: usize,
+) -> *const c_char {
+    let output = unsafe { mecab_sparse_tostr2(mecab, str, len) };
+    if output.is_null() || ostr.is_null() {
+        return ptr::null();
+    }
+
+    let output = unsafe { CStr::from_ptr(output) }.to_bytes_with_nul();
+    if output.len() > olen {
+        if let Some(mecab) = unsafe { mecab.as_mut() } {
+            mecab.error = error_string("output buffer overflow");
+        }
+        return ptr::null();
+    }
+    unsafe { ptr::copy_nonoverlapping(output.as_ptr() as *const c_char, ostr, output.len()) };
+
+    ostr
+}
+
+/// Tokenizes a NUL-terminated sentence and returns the BOS node of the best path. The nodes are
+/// valid until the next call with the same `mecab_t`, and point into the sentence. Returns null
+/// on error.
+///
+/// # Safety
+///
+/// `mecab` must be a `mecab_t` that was not destroyed, and `str` a NUL-terminated string that
+/// outlives the nodes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mecab_sparse_tonode(mecab: *mut Mecab, str: *const c_char) -> *const Node {
+    unsafe { mecab_sparse_tonode2(mecab, str, 0) }
+}
+
+/// Tokenizes the first `len` bytes of a sentence and returns the BOS node of the best path. The
+/// nodes are valid until the next call with the same `mecab_t`, and point into the sentence.
+/// Returns null on error.
+///
+/// # Safety
+///
+/// `mecab` must be a `mecab_t` that was not destroyed, and `str` must point to `len` bytes that
+/// outlive the nodes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mecab_sparse_tonode2(
+    mecab: *mut Mecab,
+    str: *const c_char,
+    len: usize,
+) -> *const Node {
+    let Some(mecab) = (unsafe { mecab.as_mut() }) else {
+        return ptr::null();
+    };
+    let Some(sentence) = (unsafe { sentence(str, len) }) else {
+        mecab.error = error_string("the sentence is null");
+        return ptr::null();
+    };
+
+    match mecab.parse_to_node(sentence) {
+        Ok(node) => node,
+        Err(err) => {
+            mecab.error = error_string(err);
+            ptr::null()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_error() {
+        let mecab = unsafe { mecab_new2(c"-d /path/to/missing".as_ptr()) };
+        assert!(mecab.is_null());
+
+        let error = unsafe { CStr::from_ptr(mecab_strerror(ptr::null_mut())) };
+        assert!(!error.to_bytes().is_empty());
+
+        let mecab = unsafe { mecab_new2(c"-Ochasen".as_ptr()) };
+        assert!(mecab.is_null());
+        let error = unsafe { CStr::from_ptr(mecab_strerror(ptr::null_mut())) };
+        assert!(error.to_string_lossy().contains("chasen"));
+    }
+
+    #[test]
+    fn test_version() {
+        let version = unsafe { CStr::from_ptr(mecab_version()) };
+        assert_eq!(env!("CARGO_PKG_VERSION"), version.to_str().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_sparse_ipadic() {
+        let mecab = unsafe { mecab_new2(c"-d embedded://ipadic".as_ptr()) };
+        assert!(!mecab.is_null());
+
+        let output =
+            unsafe { CStr::from_ptr(mecab_sparse_tostr(mecab, c"関西国際空港".as_ptr())) };
+        assert_eq!(
+            "関西国際空港\t名詞,固有名詞,組織,*,*,*,関西国際空港,カンサイコクサイクウコウ,カンサイコクサイクーコー\nEOS\n",
+            output.to_str().unwrap()
+        );
+
+        let sentence = "すもも も もも";
+        let mut node = unsafe {
+            mecab_sparse_tonode2(mecab, sentence.as_ptr() as *const c_char, sentence.len())
+        };
+        let mut surfaces = Vec::new();
+        while let Some(current) = unsafe { node.as_ref() } {
+            if current.stat == NOR_NODE || current.stat == UNK_NODE {
+                let surface = unsafe {
+                    slice::from_raw_parts(current.surface as *const u8, current.length as usize)
+                };
+                surfaces.push((
+                    std::str::from_utf8(surface).unwrap().to_string(),
+                    current.rlength,
+                ));
+            }
+            node = current.next;
+        }
+        // The spaces before the words are counted in rlength
+        assert_eq!(
+            vec![
+                ("すもも".to_string(), 9),
+                ("も".to_string(), 4),
+                ("もも".to_string(), 7)
+            ],
+            surfaces
+        );
+
+        unsafe { mecab_destroy(mecab) };
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_wakati_ipadic() {
+        let mecab = unsafe { mecab_new2(c"-Owakati".as_ptr()) };
+        assert!(!mecab.is_null());
+
+        let mut buffer = [0 as c_char; 64];
+        let sentence = "すもももももももものうち";
+        let output = unsafe {
+            mecab_sparse_tostr3(
+                mecab,
+                sentence.as_ptr() as *const c_char,
+                sentence.len(),
+                buffer.as_mut_ptr(),
+                buffer.len(),
+            )
+        };
+        assert_eq!(
+            "すもも も もも も もも の うち \n",
+            unsafe { CStr::from_ptr(output) }.to_str().unwrap()
+        );
+
+        // The buffer is too small
+        let output = unsafe {
+            mecab_sparse_tostr3(
+                mecab,
+                sentence.as_ptr() as *const c_char,
+                sentence.len(),
+                buffer.as_mut_ptr(),
+                8,
+            )
+        };
+        assert!(output.is_null());
+
+        unsafe { mecab_destroy(mecab) };
+    }
+}
@@ -0,0 +1,155 @@
+//! The nodes of `mecab_sparse_tonode`, laid out as `mecab_node_t` of `mecab.h`.
+
+use std::ffi::{c_char, c_float, c_long, c_short, c_uchar, c_uint, c_ushort};
+use std::ptr;
+
+/// A normal node (`MECAB_NOR_NODE`).
+pub const NOR_NODE: c_uchar = 0;
+/// A node of an unknown word (`MECAB_UNK_NODE`).
+pub const UNK_NODE: c_uchar = 1;
+/// The beginning of the sentence (`MECAB_BOS_NODE`).
+pub const BOS_NODE: c_uchar = 2;
+/// The end of the sentence (`MECAB_EOS_NODE`).
+pub const EOS_NODE: c_uchar = 3;
+
+/// The feature of the BOS and EOS nodes, as IPADIC has it.
+pub const BOS_EOS_FEATURE: &[u8] = b"BOS/EOS,*,*,*,*,*,*,*,*\0";
+
+/// `mecab_path_t`. Paths are not built, so the pointers to them are always null.
+#[repr(C)]
+pub struct Path {
+    _private: [u8; 0],
+}
+
+/// `mecab_node_t`.
+///
+/// `surface` points into the sentence given to `mecab_sparse_tonode` and is not NUL-terminated:
+/// read `length` bytes from it. The lattice is not kept, so `enext`, `bnext`, `rpath` and `lpath`
+/// are null, only the best path is linked through `prev` and `next`, and `posid`, `char_type`,
+/// `alpha`, `beta` and `prob` are 0.
+#[repr(C)]
+pub struct Node {
+    pub prev: *mut Node,
+    pub next: *mut Node,
+    pub enext: *mut Node,
+    pub bnext: *mut Node,
+    pub rpath: *mut Path,
+    pub lpath: *mut Path,
+    pub surface: *const c_char,
+    pub feature: *const c_char,
+    pub id: c_uint,
+    pub length: c_ushort,
+    /// The length including the whitespace before the surface.
+    pub rlength: c_ushort,
+    pub rc_attr: c_ushort,
+    pub lc_attr: c_ushort,
+    pub posid: c_ushort,
+    pub char_type: c_uchar,
+    pub stat: c_uchar,
+    pub isbest: c_uchar,
+    pub alpha: c_float,
+    pub beta: c_float,
+    pub prob: c_float,
+    pub wcost: c_short,
+    pub cost: c_long,
+}
+
+impl Node {
+    /// A node of the best path, not linked yet.
+    pub fn new(
+        id: u32,
+        surface: *const c_char,
+        length: usize,
+        rlength: usize,
+        feature: *const c_char,
+        stat: c_uchar,
+    ) -> Self {
+        Self {
+            prev: ptr::null_mut(),
+            next: ptr::null_mut(),
+            enext: ptr::null_mut(),
+            bnext: ptr::null_mut(),
+            rpath: ptr::null_mut(),
+            lpath: ptr::null_mut(),
+            surface,
+            feature,
+            id,
+            // As in MeCab, the lengths are 16 bits
+            length: length.min(c_ushort::MAX as usize) as c_ushort,
+            rlength: rlength.min(c_ushort::MAX as usize) as c_ushort,
+            rc_attr: 0,
+            lc_attr: 0,
+            posid: 0,
+            char_type: 0,
+            stat,
+            isbest: 1,
+            alpha: 0.0,
+            beta: 0.0,
+            prob: 0.0,
+            wcost: 0,
+            cost: 0,
+        }
+    }
+}
+
+/// Links the nodes in order through `prev` and `next`. The nodes must not move afterwards.
+pub fn link(nodes: &mut [Node]) {
+    let len = nodes.len();
+    let base = nodes.as_mut_ptr();
+    for (index, node) in nodes.iter_mut().enumerate() {
+        node.prev = if index > 0 {
+            base.wrapping_add(index - 1)
+        } else {
+            ptr::null_mut()
+        };
+        node.next = if index + 1 < len {
+            base.wrapping_add(index + 1)
+        } else {
+            ptr::null_mut()
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::mem::{offset_of, size_of};
+
+    #[test]
+    fn test_node_layout() {
+        // The offsets of mecab_node_t on LP64 platforms
+        if size_of::<c_long>() == 8 && size_of::<*const Node>() == 8 {
+            assert_eq!(48, offset_of!(Node, surface));
+            assert_eq!(64, offset_of!(Node, id));
+            assert_eq!(68, offset_of!(Node, length));
+            assert_eq!(78, offset_of!(Node, char_type));
+            assert_eq!(79, offset_of!(Node, stat));
+            assert_eq!(80, offset_of!(Node, isbest));
+            assert_eq!(84, offset_of!(Node, alpha));
+            assert_eq!(96, offset_of!(Node, wcost));
+            assert_eq!(104, offset_of!(Node, cost));
+            assert_eq!(112, size_of::<Node>());
+        }
+    }
+
+    #[test]
+    fn test_link() {
+        let mut nodes = (0..3)
+            .map(|id| Node::new(id, ptr::null(), 0, 0, ptr::null(), NOR_NODE))
+            .collect::<Vec<_>>();
+        link(&mut nodes);
+
+        assert!(nodes[0].prev.is_null());
+        assert!(nodes[2].next.is_null());
+        unsafe {
+            assert_eq!(1, (*nodes[0].next).id);
+            assert_eq!(2, (*(*nodes[0].next).next).id);
+            assert_eq!(1, (*nodes[2].prev).id);
+        }
+        assert_eq!(
+            c_ushort::MAX,
+            Node::new(0, ptr::null(), 70000, 0, ptr::null(), 0).length
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! The command line options of MeCab given to `mecab_new` and `mecab_new2`.
+
+use lindera::LinderaResult;
+use lindera::error::LinderaErrorKind;
+
+/// The dictionary used when no `-d` option is given.
+#[cfg(feature = "embed-ipadic")]
+pub const DEFAULT_DICTIONARY: Option<&str> = Some("embedded://ipadic");
+#[cfg(not(feature = "embed-ipadic"))]
+pub const DEFAULT_DICTIONARY: Option<&str> = None;
+
+/// The output of `mecab_sparse_tostr`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// `surface\tfeature` lines followed by `EOS`.
+    #[default]
+    Mecab,
+    /// The surfaces separated by spaces (`-Owakati`).
+    Wakati,
+}
+
+/// The options of a `mecab_t`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Options {
+    /// The dictionary, as a path or a URI (`-d`, `--dicdir`).
+    pub dicdir: Option<String>,
+    /// The user dictionary, as a Lindera CSV or binary user dictionary (`-u`, `--userdic`).
+    pub userdic: Option<String>,
+    /// `-O`, `--output-format-type`.
+    pub output_format: OutputFormat,
+}
+
+impl Options {
+    /// Parses the options of `mecab_new(argc, argv)`. The first argument is the program name, as
+    /// it is for MeCab.
+    pub fn parse<I, S>(args: I) -> LinderaResult<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let args = args
+            .into_iter()
+            .skip(1)
+            .map(|arg| arg.as_ref().to_string())
+            .collect::<Vec<_>>();
+
+        Self::parse_args(&args)
+    }
+
+    /// Parses the options of `mecab_new2(arg)`: the arguments separated by spaces, with double
+    /// quotes around the arguments that contain spaces.
+    pub fn parse_str(arg: &str) -> LinderaResult<Self> {
+        Self::parse_args(&split_args(arg)?)
+    }
+
+    fn parse_args(args: &[String]) -> LinderaResult<Self> {
+        let mut options = Self::default();
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.strip_prefix("--") {
+                Some(long) => match long.split_once('=') {
+                    Some((name, value)) => (name, Some(value.to_string())),
+                    None => (long, None),
+                },
+                None => match arg.strip_prefix('-') {
+                    // Short options take their value in the same argument (-Owakati) or the next
+                    Some(short) if short.len() > 1 => {
+                        let (name, value) = short.split_at(1);
+                        (name, Some(value.to_string()))
+                    }
+                    Some(short) => (short, None),
+                    None => {
+                        return Err(LinderaErrorKind::Args
+                            .with_error(anyhow::anyhow!("unexpected argument: {arg}")));
+                    }
+                },
+            };
+
+            let mut value = || -> LinderaResult<String> {
+                match inline_value.clone().or_else(|| args.next().cloned()) {
+                    Some(value) => Ok(value),
+                    None => Err(LinderaErrorKind::Args
+                        .with_error(anyhow::anyhow!("option {arg} requires an argument"))),
+                }
+            };
+
+            match name {
+                "d" | "dicdir" => options.dicdir = Some(value()?),
+                "u" | "userdic" => options.userdic = Some(value()?),
+                "O" | "output-format-type" => {
+                    options.output_format = match value()?.as_str() {
+                        "" | "none" => OutputFormat::Mecab,
+                        "wakati" => OutputFormat::Wakati,
+                        format => {
+                            return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                                "unsupported output format: {format}"
+                            )));
+                        }
+                    }
+                }
+                // Lindera has no resource file; the option is accepted so that existing
+                // arguments keep working
+                "r" | "rcfile" => {
+                    value()?;
+                }
+                _ => {
+                    return Err(LinderaErrorKind::Args
+                        .with_error(anyhow::anyhow!("unsupported option: {arg}")));
+                }
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// The dictionary to load: the `-d` option, or the embedded default.
+    pub fn dictionary(&self) -> LinderaResult<&str> {
+        self.dicdir
+            .as_deref()
+            .or(DEFAULT_DICTIONARY)
+            .ok_or_else(|| {
+                LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                    "no dictionary: give one with -d, or build with an embedded dictionary"
+                ))
+            })
+    }
+}
+
+/// Splits an argument string on spaces, keeping the text between double quotes together.
+fn split_args(arg: &str) -> LinderaResult<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+
+    for c in arg.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if quoted {
+        return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!("unbalanced quotes: {arg}")));
+    }
+    if in_arg {
+        args.push(current);
+    }
+
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(
+            vec!["-d", "/usr/local/lib/mecab dic", "-Owakati"],
+            split_args(r#" -d "/usr/local/lib/mecab dic"  -Owakati "#).unwrap()
+        );
+        assert_eq!(vec![""], split_args(r#""""#).unwrap());
+        assert!(split_args("").unwrap().is_empty());
+        assert!(split_args(r#"-d "/tmp"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_str() {
+        let options = Options::parse_str("-d /path/to/ipadic -u user.csv -Owakati").unwrap();
+        assert_eq!(Some("/path/to/ipadic"), options.dicdir.as_deref());
+        assert_eq!(Some("user.csv"), options.userdic.as_deref());
+        assert_eq!(OutputFormat::Wakati, options.output_format);
+        assert_eq!("/path/to/ipadic", options.dictionary().unwrap());
+
+        let options =
+            Options::parse_str("--dicdir=/path/to/ipadic --output-format-type wakati").unwrap();
+        assert_eq!(Some("/path/to/ipadic"), options.dicdir.as_deref());
+        assert_eq!(OutputFormat::Wakati, options.output_format);
+
+        // The resource file is ignored
+        let options = Options::parse_str("-r /etc/mecabrc -d/path/to/ipadic").unwrap();
+        assert_eq!(Some("/path/to/ipadic"), options.dicdir.as_deref());
+        assert_eq!(OutputFormat::Mecab, options.output_format);
+
+        assert_eq!(Options::default(), Options::parse_str("").unwrap());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Options::parse_str("-d").is_err());
+        assert!(Options::parse_str("-Ochasen").is_err());
+        assert!(Options::parse_str("--nbest 2").is_err());
+        assert!(Options::parse_str("ipadic").is_err());
+    }
+
+    #[test]
+    fn test_parse_argv() {
+        let options = Options::parse(["mecab", "-d", "/path/to/ipadic"]).unwrap();
+        assert_eq!(Some("/path/to/ipadic"), options.dicdir.as_deref());
+        assert_eq!(Options::default(), Options::parse(["mecab"]).unwrap());
+    }
+}
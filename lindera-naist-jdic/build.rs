@@ -0,0 +1,52 @@
+use std::error::Error;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn Error>> {
+    if std::env::var_os("LINDERA_DICTIONARIES_PATH").is_none()
+        && std::env::var_os("LINDERA_CACHE").is_none()
+        && cfg!(not(feature = "embed-naist-jdic"))
+    {
+        return Ok(());
+    }
+
+    // Without a local copy of the source, which is not downloaded, the dictionary can only be
+    // built when it is embedded, where the missing source is reported as an error
+    if cfg!(not(feature = "embed-naist-jdic"))
+        && std::env::var_os("LINDERA_NAIST_JDIC_SOURCE").is_none()
+    {
+        println!("cargo:rerun-if-changed=build.rs");
+        println!("cargo:rerun-if-env-changed=LINDERA_NAIST_JDIC_SOURCE");
+        return Ok(());
+    }
+
+    use std::fs;
+    use std::path::Path;
+
+    use lindera_dictionary::{
+        assets::{FetchParams, fetch},
+        builder::DictionaryBuilder,
+        dictionary::metadata::Metadata,
+    };
+
+    // The source of NAIST-jdic is not mirrored, so it is not downloaded: set
+    // LINDERA_NAIST_JDIC_SOURCE to the MeCab dictionary mecab-naist-jdic-0.6.3b-20111013
+    let fetch_params = FetchParams {
+        file_name: "mecab-naist-jdic-0.6.3b-20111013.tar.gz",
+        input_dir: "mecab-naist-jdic-0.6.3b-20111013",
+        output_dir: "lindera-naist-jdic",
+        dummy_input: "テスト,1288,1288,-1000,名詞,固有名詞,一般,*,*,*,*,*,*\n",
+        download_urls: &[],
+        md5_hash: "",
+    };
+
+    // Read and deserialize metadata directly from JSON file
+    let metadata_path = Path::new("metadata.json");
+    let metadata_json = fs::read_to_string(metadata_path)?;
+    let metadata: Metadata = serde_json::from_str(&metadata_json)?;
+
+    let builder = DictionaryBuilder::new(metadata);
+
+    fetch(fetch_params, builder).await?;
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+#[cfg(feature = "embed-naist-jdic")]
+use std::env;
+#[cfg(feature = "compress")]
+use std::ops::Deref;
+
+use lindera_dictionary::LinderaResult;
+#[cfg(feature = "compress")]
+use lindera_dictionary::decompress::{CompressedData, decompress};
+use lindera_dictionary::dictionary::Dictionary;
+use lindera_dictionary::dictionary::character_definition::CharacterDefinition;
+use lindera_dictionary::dictionary::connection_cost_matrix::ConnectionCostMatrix;
+use lindera_dictionary::dictionary::metadata::Metadata;
+use lindera_dictionary::dictionary::prefix_dictionary::PrefixDictionary;
+use lindera_dictionary::dictionary::unknown_dictionary::UnknownDictionary;
+use lindera_dictionary::loader::DictionaryLoader;
+
+macro_rules! decompress_data {
+    ($name: ident, $bytes: expr, $filename: literal) => {
+        #[cfg(feature = "compress")]
+        static $name: once_cell::sync::Lazy<Vec<u8>> = once_cell::sync::Lazy::new(|| {
+            // First check if this is compressed data by attempting to check aligned root
+            let mut aligned = rkyv::util::AlignedVec::<16>::new();
+            aligned.extend_from_slice(lindera_dictionary::format::payload(&$bytes[..]));
+            match rkyv::from_bytes::<CompressedData, rkyv::rancor::Error>(&aligned) {
+                Ok(compressed_data) => {
+                    // Decompress it
+                    match decompress(compressed_data) {
+                        Ok(decompressed) => decompressed,
+                        Err(_) => {
+                            // Decompression failed, fall back to raw data
+                            lindera_dictionary::format::payload(&$bytes[..]).to_vec()
+                        }
+                    }
+                }
+                Err(_) => {
+                    // Not compressed data format, use as raw binary
+                    lindera_dictionary::format::payload(&$bytes[..]).to_vec()
+                }
+            }
+        });
+        #[cfg(not(feature = "compress"))]
+        const $name: &'static [u8] = lindera_dictionary::format::payload($bytes);
+    };
+}
+
+macro_rules! naist_jdic_data {
+    ($name: ident, $path: literal, $filename: literal) => {
+        #[cfg(feature = "embed-naist-jdic")]
+        decompress_data!(
+            $name,
+            include_bytes!(concat!(env!("LINDERA_WORKDIR"), $path)),
+            $filename
+        );
+        #[cfg(not(feature = "embed-naist-jdic"))]
+        decompress_data!($name, &[], $filename);
+    };
+    // A component left out of the binary with the given feature
+    ($name: ident, $path: literal, $filename: literal, unless $feature: literal) => {
+        #[cfg(all(feature = "embed-naist-jdic", not(feature = $feature)))]
+        decompress_data!(
+            $name,
+            include_bytes!(concat!(env!("LINDERA_WORKDIR"), $path)),
+            $filename
+        );
+        #[cfg(any(not(feature = "embed-naist-jdic"), feature = $feature))]
+        decompress_data!($name, &[], $filename);
+    };
+}
+
+// Metadata-specific macro (skips compression/decompression processing)
+macro_rules! naist_jdic_metadata {
+    ($name: ident, $path: literal, $filename: literal) => {
+        #[cfg(feature = "embed-naist-jdic")]
+        const $name: &'static [u8] = include_bytes!(concat!(env!("LINDERA_WORKDIR"), $path));
+        #[cfg(not(feature = "embed-naist-jdic"))]
+        const $name: &'static [u8] = &[];
+    };
+}
+
+naist_jdic_data!(
+    CHAR_DEFINITION_DATA,
+    "/lindera-naist-jdic/char_def.bin",
+    "char_def.bin"
+);
+naist_jdic_data!(
+    CONNECTION_DATA,
+    "/lindera-naist-jdic/matrix.mtx",
+    "matrix.mtx"
+);
+naist_jdic_data!(DA_DATA, "/lindera-naist-jdic/dict.da", "dict.da");
+naist_jdic_data!(VALS_DATA, "/lindera-naist-jdic/dict.vals", "dict.vals");
+naist_jdic_data!(
+    UNKNOWN_DATA,
+    "/lindera-naist-jdic/unk.bin",
+    "unk.bin",
+    unless "no-unknown-grouping"
+);
+naist_jdic_data!(
+    WORDS_IDX_DATA,
+    "/lindera-naist-jdic/dict.wordsidx",
+    "dict.wordsidx",
+    unless "no-details"
+);
+naist_jdic_data!(
+    WORDS_DATA,
+    "/lindera-naist-jdic/dict.words",
+    "dict.words",
+    unless "no-details"
+);
+naist_jdic_metadata!(
+    METADATA_DATA,
+    "/lindera-naist-jdic/metadata.json",
+    "metadata.json"
+);
+
+pub fn load() -> LinderaResult<Dictionary> {
+    // Load metadata from embedded binary data
+    let metadata = Metadata::load(METADATA_DATA)?;
+
+    #[cfg(feature = "compress")]
+    {
+        Ok(Dictionary {
+            prefix_dictionary: PrefixDictionary::load(
+                DA_DATA.deref(),
+                VALS_DATA.deref(),
+                WORDS_IDX_DATA.deref(),
+                WORDS_DATA.deref(),
+                true,
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA.deref())?,
+            character_definition: CharacterDefinition::load(&CHAR_DEFINITION_DATA)?,
+            unknown_dictionary: UnknownDictionary::load(&UNKNOWN_DATA)?,
+            metadata,
+        })
+    }
+    #[cfg(not(feature = "compress"))]
+    {
+        Ok(Dictionary {
+            prefix_dictionary: PrefixDictionary::load(
+                DA_DATA,
+                VALS_DATA,
+                WORDS_IDX_DATA,
+                WORDS_DATA,
+                true,
+            )?,
+            connection_cost_matrix: ConnectionCostMatrix::load(CONNECTION_DATA)?,
+            character_definition: CharacterDefinition::load(CHAR_DEFINITION_DATA)?,
+            unknown_dictionary: UnknownDictionary::load(UNKNOWN_DATA)?,
+            metadata,
+        })
+    }
+}
+
+pub struct EmbeddedNaistJdicLoader;
+
+impl Default for EmbeddedNaistJdicLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmbeddedNaistJdicLoader {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DictionaryLoader for EmbeddedNaistJdicLoader {
+    fn load(&self) -> LinderaResult<Dictionary> {
+        load()
+    }
+}
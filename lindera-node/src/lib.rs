@@ -0,0 +1,44 @@
+//! # lindera-node
+//!
+//! Node.js bindings for [Lindera](https://github.com/lindera/lindera), a morphological analysis library.
+//!
+//! This crate is a native addon built with [napi-rs](https://napi.rs/). Unlike the WebAssembly
+//! build, it reads dictionaries from disk with memory maps and runs the analysis at native speed,
+//! so large dictionaries load quickly and tokenization can run on the libuv thread pool without
+//! blocking the event loop.
+//!
+//! ## Usage
+//!
+//! ```javascript
+//! const { TokenizerBuilder } = require('lindera-node');
+//!
+//! const builder = new TokenizerBuilder();
+//! builder.setDictionary("/path/to/lindera-ipadic");
+//! builder.setMode("normal");
+//!
+//! const tokenizer = builder.build();
+//! const tokens = tokenizer.tokenize("関西国際空港");
+//! console.log(tokens);
+//!
+//! // Tokenize on the thread pool
+//! tokenizer.tokenizeAsync("関西国際空港").then((tokens) => console.log(tokens));
+//! ```
+//!
+//! An analyzer config file (the JSON or YAML file used with `LINDERA_CONFIG_PATH`) is loaded with
+//! `TokenizerBuilder.fromFile(path)`, and a config object with `TokenizerBuilder.fromConfig(config)`.
+
+pub mod token;
+pub mod tokenizer;
+
+use napi_derive::napi;
+
+pub use crate::token::Token;
+pub use crate::tokenizer::{Tokenizer, TokenizerBuilder};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Returns the version of the lindera-node package.
+#[napi]
+pub fn version() -> String {
+    VERSION.to_string()
+}
@@ -0,0 +1,44 @@
+use napi_derive::napi;
+
+use lindera::token::Token as LinderaToken;
+
+/// A token, passed to JavaScript as a plain object.
+///
+/// The offsets count bytes of the UTF-8 encoded text, as in Rust. Use
+/// `Buffer.from(text).subarray(byteStart, byteEnd)` to slice the text with them.
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct Token {
+    /// Surface form of the token.
+    pub surface: String,
+
+    /// Start byte position in the original text.
+    pub byte_start: u32,
+
+    /// End byte position in the original text.
+    pub byte_end: u32,
+
+    /// Position index of the token.
+    pub position: u32,
+
+    /// Word ID in the dictionary.
+    pub word_id: u32,
+
+    /// Morphological details of the token.
+    pub details: Vec<String>,
+}
+
+impl Token {
+    pub fn from_token(mut token: LinderaToken) -> Self {
+        let details = token.details().iter().map(|s| s.to_string()).collect();
+
+        Self {
+            surface: token.surface.to_string(),
+            byte_start: token.byte_start as u32,
+            byte_end: token.byte_end as u32,
+            position: token.position as u32,
+            word_id: token.word_id.id,
+            details,
+        }
+    }
+}
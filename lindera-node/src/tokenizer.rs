@@ -0,0 +1,159 @@
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use napi::bindgen_prelude::{AsyncTask, Error, Result};
+use napi::{Env, Task};
+use napi_derive::napi;
+use serde_json::Value;
+
+use lindera::error::LinderaError;
+use lindera::mode::Mode;
+use lindera::tokenizer::{
+    Tokenizer as LinderaTokenizer, TokenizerBuilder as LinderaTokenizerBuilder,
+};
+
+use crate::token::Token;
+
+fn to_napi_error(err: LinderaError) -> Error {
+    Error::from_reason(err.to_string())
+}
+
+/// Builder for creating a [`Tokenizer`] instance.
+///
+/// `TokenizerBuilder` configures the dictionary, the tokenization mode, the character filters and
+/// the token filters of a tokenizer, starting from scratch or from an analyzer config.
+#[napi]
+pub struct TokenizerBuilder {
+    inner: LinderaTokenizerBuilder,
+}
+
+#[napi]
+impl TokenizerBuilder {
+    /// Creates a new `TokenizerBuilder`, loading the config given by `LINDERA_CONFIG_PATH` if it
+    /// is set.
+    #[napi(constructor)]
+    pub fn new() -> Result<Self> {
+        let inner = LinderaTokenizerBuilder::new().map_err(to_napi_error)?;
+
+        Ok(Self { inner })
+    }
+
+    /// Creates a `TokenizerBuilder` from an analyzer config file (JSON or YAML).
+    #[napi(factory)]
+    pub fn from_file(path: String) -> Result<Self> {
+        let inner = LinderaTokenizerBuilder::from_file(Path::new(&path)).map_err(to_napi_error)?;
+
+        Ok(Self { inner })
+    }
+
+    /// Creates a `TokenizerBuilder` from an analyzer config object.
+    #[napi(factory)]
+    pub fn from_config(config: Value) -> Result<Self> {
+        let inner = LinderaTokenizerBuilder::from_config(config).map_err(to_napi_error)?;
+
+        Ok(Self { inner })
+    }
+
+    /// Builds and returns a configured [`Tokenizer`] instance.
+    #[napi]
+    pub fn build(&self) -> Result<Tokenizer> {
+        let inner = self.inner.build().map_err(to_napi_error)?;
+
+        Ok(Tokenizer {
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// Sets the tokenization mode (`normal` or `decompose`).
+    #[napi]
+    pub fn set_mode(&mut self, mode: String) -> Result<()> {
+        let m = Mode::from_str(&mode).map_err(to_napi_error)?;
+        self.inner.set_segmenter_mode(&m);
+
+        Ok(())
+    }
+
+    /// Sets the dictionary to use for tokenization.
+    #[napi]
+    pub fn set_dictionary(&mut self, uri: String) {
+        self.inner.set_segmenter_dictionary(&uri);
+    }
+
+    /// Sets a user-defined dictionary.
+    #[napi]
+    pub fn set_user_dictionary(&mut self, uri: String) {
+        self.inner.set_segmenter_user_dictionary(&uri);
+    }
+
+    /// Sets whether to keep whitespace tokens in the output.
+    #[napi]
+    pub fn set_keep_whitespace(&mut self, keep: bool) {
+        self.inner.set_segmenter_keep_whitespace(keep);
+    }
+
+    /// Appends a character filter to the tokenization pipeline.
+    #[napi]
+    pub fn append_character_filter(&mut self, name: String, args: Option<Value>) {
+        let a = args.unwrap_or_else(|| Value::Object(serde_json::Map::new()));
+
+        self.inner.append_character_filter(&name, &a);
+    }
+
+    /// Appends a token filter to the tokenization pipeline.
+    #[napi]
+    pub fn append_token_filter(&mut self, name: String, args: Option<Value>) {
+        let a = args.unwrap_or_else(|| Value::Object(serde_json::Map::new()));
+
+        self.inner.append_token_filter(&name, &a);
+    }
+}
+
+/// A tokenizer for morphological analysis.
+#[napi]
+pub struct Tokenizer {
+    inner: Arc<LinderaTokenizer>,
+}
+
+#[napi]
+impl Tokenizer {
+    /// Tokenizes the input text on the calling thread.
+    #[napi]
+    pub fn tokenize(&self, text: String) -> Result<Vec<Token>> {
+        tokenize(&self.inner, &text)
+    }
+
+    /// Tokenizes the input text on the libuv thread pool, resolving to the tokens.
+    #[napi(ts_return_type = "Promise<Array<Token>>")]
+    pub fn tokenize_async(&self, text: String) -> AsyncTask<TokenizeTask> {
+        AsyncTask::new(TokenizeTask {
+            tokenizer: Arc::clone(&self.inner),
+            text,
+        })
+    }
+}
+
+fn tokenize(tokenizer: &LinderaTokenizer, text: &str) -> Result<Vec<Token>> {
+    let tokens = tokenizer.tokenize(text).map_err(to_napi_error)?;
+
+    Ok(tokens.into_iter().map(Token::from_token).collect())
+}
+
+/// The task run by [`Tokenizer::tokenize_async`].
+pub struct TokenizeTask {
+    tokenizer: Arc<LinderaTokenizer>,
+    text: String,
+}
+
+impl Task for TokenizeTask {
+    type Output = Vec<Token>;
+    type JsValue = Vec<Token>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        tokenize(&self.tokenizer, &self.text)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
@@ -0,0 +1,54 @@
+import { test } from "node:test";
+import assert from "node:assert/strict";
+import { createRequire } from "node:module";
+
+const require = createRequire(import.meta.url);
+const { Tokenizer, TokenizerBuilder, version } = require("../index.js");
+
+// Run after building with the embed-ipadic feature: napi build --platform --features embed-ipadic
+const DICTIONARY = process.env.LINDERA_TEST_DICTIONARY ?? "embedded://ipadic";
+
+function buildTokenizer() {
+  const builder = new TokenizerBuilder();
+  builder.setDictionary(DICTIONARY);
+  builder.setMode("normal");
+  return builder.build();
+}
+
+test("version", () => {
+  assert.match(version(), /^\d+\.\d+\.\d+/);
+});
+
+test("tokenize", () => {
+  const tokens = buildTokenizer().tokenize("関西国際空港限定トートバッグ");
+
+  assert.deepEqual(
+    tokens.map((token) => token.surface),
+    ["関西国際空港", "限定", "トートバッグ"],
+  );
+  assert.equal(tokens[0].byteStart, 0);
+  assert.equal(tokens[0].byteEnd, 18);
+  assert.equal(tokens[0].details[0], "名詞");
+});
+
+test("tokenizeAsync", async () => {
+  const tokenizer = buildTokenizer();
+  const text = "すもももももももものうち";
+
+  assert.deepEqual(await tokenizer.tokenizeAsync(text), tokenizer.tokenize(text));
+});
+
+test("fromConfig", () => {
+  const tokenizer = TokenizerBuilder.fromConfig({
+    segmenter: { mode: "normal", dictionary: DICTIONARY },
+    token_filters: [{ kind: "japanese_stop_tags", args: { tags: ["助詞,係助詞"] } }],
+  }).build();
+  assert.ok(tokenizer instanceof Tokenizer);
+
+  const surfaces = tokenizer.tokenize("すもももももももものうち").map((token) => token.surface);
+  assert.ok(!surfaces.includes("も"));
+});
+
+test("invalid mode", () => {
+  assert.throws(() => new TokenizerBuilder().setMode("fast"));
+});
@@ -0,0 +1,9 @@
+# frozen_string_literal: true
+
+require "mkmf"
+require "rb_sys/mkmf"
+
+create_rust_makefile("lindera/lindera") do |r|
+  # The dictionaries to embed, as features of the crate (e.g. "embed-ipadic,embed-ko-dic")
+  r.features = ENV.fetch("LINDERA_FEATURES", "embed-ipadic").split(",").map(&:strip).reject(&:empty?)
+end
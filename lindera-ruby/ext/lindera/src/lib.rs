@@ -0,0 +1,154 @@
+//! # lindera-ruby
+//!
+//! Ruby bindings for [Lindera](https://github.com/lindera/lindera), a morphological analysis library.
+//!
+//! The native extension defines `Lindera::Tokenizer` and `Lindera::Token`; the keyword
+//! constructor and the helpers around them are written in Ruby in `lib/lindera.rb`. Analyzer
+//! configs cross the boundary as JSON, so the extension does not depend on the Ruby object model
+//! beyond strings, arrays and hashes.
+
+use std::fmt;
+use std::path::Path;
+
+use magnus::value::Lazy;
+use magnus::{
+    Error, ExceptionClass, Module, Object, RHash, RModule, Ruby, function, method, prelude::*,
+};
+use serde_json::Value;
+
+use lindera::token::Token as LinderaToken;
+use lindera::tokenizer::{Tokenizer as LinderaTokenizer, TokenizerBuilder};
+
+/// `Lindera::Error`, raised for the errors of Lindera.
+static LINDERA_ERROR: Lazy<ExceptionClass> = Lazy::new(|ruby| {
+    ruby.class_object()
+        .const_get::<_, RModule>("Lindera")
+        .and_then(|module| module.const_get("Error"))
+        .expect("Lindera::Error is defined by the extension")
+});
+
+fn to_ruby_error(ruby: &Ruby, err: impl fmt::Display) -> Error {
+    Error::new(ruby.get_inner(&LINDERA_ERROR), err.to_string())
+}
+
+/// A token, with the offsets in bytes of the UTF-8 text.
+#[magnus::wrap(class = "Lindera::Token", free_immediately, size)]
+struct Token {
+    surface: String,
+    byte_start: usize,
+    byte_end: usize,
+    position: usize,
+    word_id: u32,
+    details: Vec<String>,
+}
+
+impl Token {
+    fn from_token(mut token: LinderaToken) -> Self {
+        let details = token.details().iter().map(|s| s.to_string()).collect();
+
+        Self {
+            surface: token.surface.to_string(),
+            byte_start: token.byte_start,
+            byte_end: token.byte_end,
+            position: token.position,
+            word_id: token.word_id.id,
+            details,
+        }
+    }
+
+    fn surface(&self) -> String {
+        self.surface.clone()
+    }
+
+    fn byte_start(&self) -> usize {
+        self.byte_start
+    }
+
+    fn byte_end(&self) -> usize {
+        self.byte_end
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn word_id(&self) -> u32 {
+        self.word_id
+    }
+
+    fn details(&self) -> Vec<String> {
+        self.details.clone()
+    }
+
+    fn to_h(ruby: &Ruby, rb_self: &Self) -> Result<RHash, Error> {
+        let hash = ruby.hash_new();
+        hash.aset(ruby.to_symbol("surface"), rb_self.surface.clone())?;
+        hash.aset(ruby.to_symbol("byte_start"), rb_self.byte_start)?;
+        hash.aset(ruby.to_symbol("byte_end"), rb_self.byte_end)?;
+        hash.aset(ruby.to_symbol("position"), rb_self.position)?;
+        hash.aset(ruby.to_symbol("word_id"), rb_self.word_id)?;
+        hash.aset(ruby.to_symbol("details"), rb_self.details.clone())?;
+
+        Ok(hash)
+    }
+}
+
+/// A tokenizer built from an analyzer config. It is immutable, so one instance can be shared by
+/// the threads of an application server.
+#[magnus::wrap(class = "Lindera::Tokenizer", free_immediately, size)]
+struct Tokenizer {
+    inner: LinderaTokenizer,
+}
+
+impl Tokenizer {
+    /// Builds a tokenizer from an analyzer config given as JSON.
+    fn from_json(ruby: &Ruby, config: String) -> Result<Self, Error> {
+        let config: Value =
+            serde_json::from_str(&config).map_err(|err| to_ruby_error(ruby, err))?;
+        let inner = TokenizerBuilder::from_config(config)
+            .and_then(|builder| builder.build())
+            .map_err(|err| to_ruby_error(ruby, err))?;
+
+        Ok(Self { inner })
+    }
+
+    /// Builds a tokenizer from an analyzer config file (JSON or YAML).
+    fn from_file(ruby: &Ruby, path: String) -> Result<Self, Error> {
+        let inner = TokenizerBuilder::from_file(Path::new(&path))
+            .and_then(|builder| builder.build())
+            .map_err(|err| to_ruby_error(ruby, err))?;
+
+        Ok(Self { inner })
+    }
+
+    fn tokenize(ruby: &Ruby, rb_self: &Self, text: String) -> Result<Vec<Token>, Error> {
+        let tokens = rb_self
+            .inner
+            .tokenize(&text)
+            .map_err(|err| to_ruby_error(ruby, err))?;
+
+        Ok(tokens.into_iter().map(Token::from_token).collect())
+    }
+}
+
+#[magnus::init]
+fn init(ruby: &Ruby) -> Result<(), Error> {
+    let module = ruby.define_module("Lindera")?;
+    module.define_error("Error", ruby.exception_standard_error())?;
+
+    let tokenizer = module.define_class("Tokenizer", ruby.class_object())?;
+    tokenizer.define_singleton_method("from_json", function!(Tokenizer::from_json, 1))?;
+    tokenizer.define_singleton_method("from_file", function!(Tokenizer::from_file, 1))?;
+    tokenizer.define_method("tokenize", method!(Tokenizer::tokenize, 1))?;
+
+    let token = module.define_class("Token", ruby.class_object())?;
+    token.define_method("surface", method!(Token::surface, 0))?;
+    token.define_method("byte_start", method!(Token::byte_start, 0))?;
+    token.define_method("byte_end", method!(Token::byte_end, 0))?;
+    token.define_method("position", method!(Token::position, 0))?;
+    token.define_method("word_id", method!(Token::word_id, 0))?;
+    token.define_method("details", method!(Token::details, 0))?;
+    token.define_method("to_h", method!(Token::to_h, 0))?;
+
+    Ok(())
+}
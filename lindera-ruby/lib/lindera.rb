@@ -0,0 +1,57 @@
+# frozen_string_literal: true
+
+require "json"
+
+require_relative "lindera/version"
+
+# Load the extension built for the running Ruby by rake-compiler, or the one built by the gem
+begin
+  RUBY_VERSION =~ /(\d+\.\d+)/
+  require_relative "lindera/#{Regexp.last_match(1)}/lindera"
+rescue LoadError
+  require_relative "lindera/lindera"
+end
+
+# Morphological analysis of Japanese, Korean and Chinese text.
+module Lindera
+  class Tokenizer
+    # Builds a tokenizer from its segmenter settings and filters:
+    #
+    #   tokenizer = Lindera::Tokenizer.new(
+    #     dictionary: "embedded://ipadic",
+    #     mode: :normal,
+    #     token_filters: [{ kind: "japanese_stop_tags", args: { tags: ["助詞,係助詞"] } }]
+    #   )
+    #
+    # Raises Lindera::Error if the dictionary cannot be loaded or a filter is invalid.
+    def self.new(dictionary: "embedded://ipadic", mode: :normal, user_dictionary: nil,
+                 keep_whitespace: false, character_filters: [], token_filters: [])
+      segmenter = { "dictionary" => dictionary, "mode" => mode.to_s, "keep_whitespace" => keep_whitespace }
+      segmenter["user_dictionary"] = user_dictionary if user_dictionary
+
+      from_config(
+        "segmenter" => segmenter,
+        "character_filters" => character_filters,
+        "token_filters" => token_filters
+      )
+    end
+
+    # Builds a tokenizer from an analyzer config, the Hash form of the JSON / YAML config files.
+    def self.from_config(config)
+      from_json(JSON.generate(config))
+    end
+
+    # Returns the surfaces of the tokens of the text.
+    def wakati(text)
+      tokenize(text).map(&:surface)
+    end
+  end
+
+  class Token
+    alias to_s surface
+
+    def inspect
+      "#<Lindera::Token #{surface.inspect} #{details.join(",")}>"
+    end
+  end
+end
@@ -0,0 +1,5 @@
+# frozen_string_literal: true
+
+module Lindera
+  VERSION = "2.1.1"
+end
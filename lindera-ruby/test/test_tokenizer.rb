@@ -0,0 +1,45 @@
+# frozen_string_literal: true
+
+require "minitest/autorun"
+require "lindera"
+
+# Run after compiling with the embedded IPADIC (the default of extconf.rb)
+class TestTokenizer < Minitest::Test
+  def setup
+    @tokenizer = Lindera::Tokenizer.new(dictionary: "embedded://ipadic", mode: :normal)
+  end
+
+  def test_tokenize
+    tokens = @tokenizer.tokenize("関西国際空港限定トートバッグ")
+
+    assert_equal %w[関西国際空港 限定 トートバッグ], tokens.map(&:surface)
+    assert_equal [0, 18], [tokens[0].byte_start, tokens[0].byte_end]
+    assert_equal "名詞", tokens[0].details[0]
+    assert_equal "関西国際空港", tokens[0].to_h[:surface]
+  end
+
+  def test_wakati
+    assert_equal %w[すもも も もも も もも の うち], @tokenizer.wakati("すもももももももものうち")
+  end
+
+  def test_token_filters
+    tokenizer = Lindera::Tokenizer.new(
+      token_filters: [{ kind: "japanese_stop_tags", args: { tags: ["助詞,係助詞"] } }]
+    )
+
+    refute_includes tokenizer.wakati("すもももももももものうち"), "も"
+  end
+
+  def test_from_config
+    tokenizer = Lindera::Tokenizer.from_config(
+      "segmenter" => { "mode" => "decompose", "dictionary" => "embedded://ipadic" }
+    )
+
+    assert_equal %w[関西 国際 空港], tokenizer.wakati("関西国際空港")
+  end
+
+  def test_errors
+    assert_raises(Lindera::Error) { Lindera::Tokenizer.new(mode: :fast) }
+    assert_raises(Lindera::Error) { Lindera::Tokenizer.new(token_filters: [{ kind: "missing" }]) }
+  end
+end
@@ -0,0 +1,52 @@
+use std::error::Error;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn Error>> {
+    if std::env::var_os("LINDERA_DICTIONARIES_PATH").is_none()
+        && std::env::var_os("LINDERA_CACHE").is_none()
+        && cfg!(not(feature = "embed-unidic-csj"))
+    {
+        return Ok(());
+    }
+
+    // Without a local copy of the source, which is not downloaded, the dictionary can only be
+    // built when it is embedded, where the missing source is reported as an error
+    if cfg!(not(feature = "embed-unidic-csj"))
+        && std::env::var_os("LINDERA_UNIDIC_CSJ_SOURCE").is_none()
+    {
+        println!("cargo:rerun-if-changed=build.rs");
+        println!("cargo:rerun-if-env-changed=LINDERA_UNIDIC_CSJ_SOURCE");
+        return Ok(());
+    }
+
+    use std::fs;
+    use std::path::Path;
+
+    use lindera_dictionary::{
+        assets::{FetchParams, fetch},
+        builder::DictionaryBuilder,
+        dictionary::metadata::Metadata,
+    };
+
+    // The source of UniDic-CSJ is not mirrored, so it is not downloaded: set
+    // LINDERA_UNIDIC_CSJ_SOURCE to the MeCab dictionary downloaded from the UniDic site
+    let fetch_params = FetchParams {
+        file_name: "unidic-csj-3.1.1.tar.gz",
+        input_dir: "unidic-csj-3.1.1",
+        output_dir: "lindera-unidic-csj",
+        dummy_input: "テスト,5131,5131,767,名詞,普通名詞,サ変可能,*,*,*,テスト,テスト-test,テスト,テスト,テスト,テスト,外,*,*,*,*,*,*,体,テスト,テスト,テスト,テスト,1,C1,*,1000000000000,10000000000\n",
+        download_urls: &[],
+        md5_hash: "",
+    };
+
+    // Read and deserialize metadata directly from JSON file
+    let metadata_path = Path::new("metadata.json");
+    let metadata_json = fs::read_to_string(metadata_path)?;
+    let metadata: Metadata = serde_json::from_str(&metadata_json)?;
+
+    let builder = DictionaryBuilder::new(metadata);
+
+    fetch(fetch_params, builder).await?;
+
+    Ok(())
+}
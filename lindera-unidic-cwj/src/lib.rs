@@ -0,0 +1,9 @@
+#[cfg(feature = "embed-unidic-cwj")]
+pub mod embedded;
+
+pub const DICTIONARY_NAME: &str = "unidic-cwj";
+const VERERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub fn get_version() -> &'static str {
+    VERERSION
+}
@@ -16,6 +16,7 @@ impl JsLinderaError {
     }
 
     #[wasm_bindgen(js_name = "toString")]
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         self.message.clone()
     }
@@ -100,14 +100,8 @@ fn main() -> LinderaResult<()> {
 
     #[cfg(not(feature = "embed-ipadic"))]
     {
-        eprintln!(
-            "This example requires the '{}' feature to be enabled.",
-            "embed-ipadic"
-        );
-        eprintln!(
-            "Run with: cargo run --features {} --example tokenize",
-            "embed-ipadic"
-        );
+        eprintln!("This example requires the 'embed-ipadic' feature to be enabled.");
+        eprintln!("Run with: cargo run --features embed-ipadic --example tokenize");
     }
 
     Ok(())
@@ -55,8 +55,12 @@
 ///
 /// # Modules
 /// - `japanese_iteration_mark`: Contains the Japanese iteration mark character filter.
+/// - `japanese_prolonged_sound`: Contains the Japanese prolonged sound and small kana character filter.
+/// - `kana_conversion`: Contains the hiragana/katakana conversion character filter.
 /// - `mapping`: Contains the mapping character filter.
 /// - `regex`: Contains the regex character filter.
+/// - `sanitize`: Contains the character filter removing control and zero-width characters, and the
+///   lossy UTF-8 decoding.
 /// - `unicode_normalize`: Contains the unicode normalization character filter.
 ///
 /// # Traits
@@ -72,8 +76,11 @@
 /// # Functions
 /// No public utility functions are exposed, as all offset management is handled through OffsetMapping.
 pub mod japanese_iteration_mark;
+pub mod japanese_prolonged_sound;
+pub mod kana_conversion;
 pub mod mapping;
 pub mod regex;
+pub mod sanitize;
 pub mod unicode_normalize;
 
 use std::ops::Deref;
@@ -84,8 +91,15 @@ use crate::LinderaResult;
 use crate::character_filter::japanese_iteration_mark::{
     JAPANESE_ITERATION_MARK_CHARACTER_FILTER_NAME, JapaneseIterationMarkCharacterFilter,
 };
+use crate::character_filter::japanese_prolonged_sound::{
+    JAPANESE_PROLONGED_SOUND_CHARACTER_FILTER_NAME, JapaneseProlongedSoundCharacterFilter,
+};
+use crate::character_filter::kana_conversion::{
+    KANA_CONVERSION_CHARACTER_FILTER_NAME, KanaConversionCharacterFilter,
+};
 use crate::character_filter::mapping::{MAPPING_CHARACTER_FILTER_NAME, MappingCharacterFilter};
 use crate::character_filter::regex::{REGEX_CHARACTER_FILTER_NAME, RegexCharacterFilter};
+use crate::character_filter::sanitize::{SANITIZE_CHARACTER_FILTER_NAME, SanitizeCharacterFilter};
 use crate::character_filter::unicode_normalize::{
     UNICODE_NORMALIZE_CHARACTER_FILTER_NAME, UnicodeNormalizeCharacterFilter,
 };
@@ -403,8 +417,11 @@ impl CharacterFilterLoader {
     /// # Supported Filters
     ///
     /// - `JAPANESE_ITERATION_MARK_CHARACTER_FILTER_NAME`: Loads a `JapaneseIterationMarkCharacterFilter`.
+    /// - `JAPANESE_PROLONGED_SOUND_CHARACTER_FILTER_NAME`: Loads a `JapaneseProlongedSoundCharacterFilter`.
+    /// - `KANA_CONVERSION_CHARACTER_FILTER_NAME`: Loads a `KanaConversionCharacterFilter`.
     /// - `MAPPING_CHARACTER_FILTER_NAME`: Loads a `MappingCharacterFilter`.
     /// - `REGEX_CHARACTER_FILTER_NAME`: Loads a `RegexCharacterFilter`.
+    /// - `SANITIZE_CHARACTER_FILTER_NAME`: Loads a `SanitizeCharacterFilter`.
     /// - `UNICODE_NORMALIZE_CHARACTER_FILTER_NAME`: Loads a `UnicodeNormalizeCharacterFilter`.
     ///
     /// # Errors
@@ -421,12 +438,21 @@ impl CharacterFilterLoader {
             JAPANESE_ITERATION_MARK_CHARACTER_FILTER_NAME => {
                 BoxCharacterFilter::from(JapaneseIterationMarkCharacterFilter::from_config(value)?)
             }
+            JAPANESE_PROLONGED_SOUND_CHARACTER_FILTER_NAME => {
+                BoxCharacterFilter::from(JapaneseProlongedSoundCharacterFilter::from_config(value)?)
+            }
+            KANA_CONVERSION_CHARACTER_FILTER_NAME => {
+                BoxCharacterFilter::from(KanaConversionCharacterFilter::from_config(value)?)
+            }
             MAPPING_CHARACTER_FILTER_NAME => {
                 BoxCharacterFilter::from(MappingCharacterFilter::from_config(value)?)
             }
             REGEX_CHARACTER_FILTER_NAME => {
                 BoxCharacterFilter::from(RegexCharacterFilter::from_config(value)?)
             }
+            SANITIZE_CHARACTER_FILTER_NAME => {
+                BoxCharacterFilter::from(SanitizeCharacterFilter::from_config(value)?)
+            }
             UNICODE_NORMALIZE_CHARACTER_FILTER_NAME => {
                 BoxCharacterFilter::from(UnicodeNormalizeCharacterFilter::from_config(value)?)
             }
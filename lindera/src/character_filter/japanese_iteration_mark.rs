@@ -533,6 +533,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_japanese_iteration_mark_character_filter_apply_offsets() {
+        let filter = JapaneseIterationMarkCharacterFilter::new(true, true);
+
+        // "𠮷" is 4 bytes while "々" is 3 bytes, so the expansion changes the length
+        let original_text = "𠮷々野家";
+        let mut text = original_text.to_string();
+        let mapping = filter.apply(&mut text).unwrap();
+        assert_eq!("𠮷𠮷野家", text);
+
+        assert_eq!(1, mapping.transformations.len());
+        let transform = &mapping.transformations[0];
+        assert_eq!(4, transform.original_start);
+        assert_eq!(7, transform.original_end);
+        assert_eq!(4, transform.filtered_start);
+        assert_eq!(8, transform.filtered_end);
+
+        // Test text fragments
+        let start = 8;
+        let end = 14;
+        assert_eq!("野家", &text[start..end]);
+        let correct_start = mapping.correct_offset(start, text.len());
+        let correct_end = mapping.correct_offset(end, text.len());
+        assert_eq!(7, correct_start);
+        assert_eq!(13, correct_end);
+        assert_eq!("野家", &original_text[correct_start..correct_end]);
+    }
+
+    #[test]
+    fn test_japanese_iteration_mark_character_filter_apply_disabled() {
+        let original_text = "ところゞゝゝ馬鹿々々しい";
+
+        let filter = JapaneseIterationMarkCharacterFilter::new(false, false);
+        let mut text = original_text.to_string();
+        let mapping = filter.apply(&mut text).unwrap();
+        assert_eq!(original_text, text);
+        assert!(mapping.is_empty());
+
+        let filter = JapaneseIterationMarkCharacterFilter::new(true, false);
+        let mut text = original_text.to_string();
+        filter.apply(&mut text).unwrap();
+        assert_eq!("ところゞゝゝ馬鹿馬鹿しい", text);
+
+        let filter = JapaneseIterationMarkCharacterFilter::new(false, true);
+        let mut text = original_text.to_string();
+        filter.apply(&mut text).unwrap();
+        assert_eq!("ところどころ馬鹿々々しい", text);
+    }
+
     #[test]
     fn test_katakana_has_dakuon() {
         for (k, v) in KATAKANA_DAKUON_MAP.iter() {
@@ -0,0 +1,306 @@
+use serde_json::Value;
+
+use crate::LinderaResult;
+use crate::character_filter::{CharacterFilter, OffsetMapping, Transformation};
+use crate::error::LinderaErrorKind;
+
+pub const JAPANESE_PROLONGED_SOUND_CHARACTER_FILTER_NAME: &str = "japanese_prolonged_sound";
+
+const PROLONGED_SOUND_MARK: char = 'ー';
+const HALFWIDTH_PROLONGED_SOUND_MARK: char = 'ｰ';
+const WAVE_DASH: char = '〜';
+const FULLWIDTH_TILDE: char = '～';
+
+pub type JapaneseProlongedSoundCharacterFilterConfig = Value;
+
+fn is_kana(c: char) -> bool {
+    matches!(c as u32, 0x3041..=0x3096 | 0x30a1..=0x30fa)
+}
+
+/// Returns the vowel of a kana, as the hiragana of the vowel.
+fn kana_vowel(c: char) -> Option<char> {
+    // Katakana are compared as hiragana
+    let c = match c as u32 {
+        codepoint @ 0x30a1..=0x30f6 => char::from_u32(codepoint - 0x60)?,
+        _ => c,
+    };
+    match c {
+        'あ' | 'か' | 'が' | 'さ' | 'ざ' | 'た' | 'だ' | 'な' | 'は' | 'ば' | 'ぱ' | 'ま'
+        | 'や' | 'ら' | 'わ' | 'ぁ' | 'ゃ' | 'ゎ' | 'ゕ' => Some('あ'),
+        'い' | 'き' | 'ぎ' | 'し' | 'じ' | 'ち' | 'ぢ' | 'に' | 'ひ' | 'び' | 'ぴ' | 'み'
+        | 'り' | 'ゐ' | 'ぃ' => Some('い'),
+        'う' | 'く' | 'ぐ' | 'す' | 'ず' | 'つ' | 'づ' | 'ぬ' | 'ふ' | 'ぶ' | 'ぷ' | 'む'
+        | 'ゆ' | 'る' | 'ゔ' | 'ぅ' | 'ゅ' => Some('う'),
+        'え' | 'け' | 'げ' | 'せ' | 'ぜ' | 'て' | 'で' | 'ね' | 'へ' | 'べ' | 'ぺ' | 'め'
+        | 'れ' | 'ゑ' | 'ぇ' | 'ゖ' => Some('え'),
+        'お' | 'こ' | 'ご' | 'そ' | 'ぞ' | 'と' | 'ど' | 'の' | 'ほ' | 'ぼ' | 'ぽ' | 'も'
+        | 'よ' | 'ろ' | 'を' | 'ぉ' | 'ょ' => Some('お'),
+        _ => None,
+    }
+}
+
+/// Returns `true` if `c` is a small vowel kana (ぁぃぅぇぉ, ァィゥェォ).
+fn is_small_vowel(c: char) -> bool {
+    matches!(
+        c,
+        'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ' | 'ァ' | 'ィ' | 'ゥ' | 'ェ' | 'ォ'
+    )
+}
+
+/// Normalizes the prolongation spellings common in casual Japanese text, so that they can be
+/// found in the dictionary.
+///
+/// - `normalize_prolonged_sound`: A run of prolonged sound marks after a kana is collapsed into
+///   a single `ー` (`スーーーパー` → `スーパー`).
+/// - `normalize_wave_dash`: Wave dashes (`〜`, `～`) after a kana are treated as prolonged sound
+///   marks (`すご〜い` → `すごーい`).
+/// - `normalize_small_kana`: Small vowel kana that only stretch the vowel of the preceding kana
+///   are removed (`すごぉぉい` → `すごい`). Small kana that form a sound with the preceding kana,
+///   such as `ファ` or `ティ`, are kept.
+///
+/// Marks that do not follow a kana (e.g. `1〜2`) are kept. All options default to `true`.
+#[derive(Clone, Debug)]
+pub struct JapaneseProlongedSoundCharacterFilter {
+    pub normalize_prolonged_sound: bool,
+    pub normalize_wave_dash: bool,
+    pub normalize_small_kana: bool,
+}
+
+impl JapaneseProlongedSoundCharacterFilter {
+    pub fn new(
+        normalize_prolonged_sound: bool,
+        normalize_wave_dash: bool,
+        normalize_small_kana: bool,
+    ) -> Self {
+        Self {
+            normalize_prolonged_sound,
+            normalize_wave_dash,
+            normalize_small_kana,
+        }
+    }
+
+    pub fn from_config(
+        config: &JapaneseProlongedSoundCharacterFilterConfig,
+    ) -> LinderaResult<Self> {
+        let get_bool = |key: &str| -> LinderaResult<bool> {
+            match config.get(key) {
+                Some(value) => value.as_bool().ok_or_else(|| {
+                    LinderaErrorKind::Deserialize
+                        .with_error(anyhow::anyhow!("invalid {key} config."))
+                }),
+                None => Ok(true),
+            }
+        };
+
+        Ok(Self::new(
+            get_bool("normalize_prolonged_sound")?,
+            get_bool("normalize_wave_dash")?,
+            get_bool("normalize_small_kana")?,
+        ))
+    }
+
+    fn is_prolonged_sound_mark(&self, c: char) -> bool {
+        match c {
+            PROLONGED_SOUND_MARK | HALFWIDTH_PROLONGED_SOUND_MARK => self.normalize_prolonged_sound,
+            WAVE_DASH | FULLWIDTH_TILDE => self.normalize_wave_dash,
+            _ => false,
+        }
+    }
+
+    fn is_stretching_small_vowel(&self, prev: char, c: char) -> bool {
+        self.normalize_small_kana && is_small_vowel(c) && kana_vowel(prev) == kana_vowel(c)
+    }
+}
+
+impl CharacterFilter for JapaneseProlongedSoundCharacterFilter {
+    fn name(&self) -> &'static str {
+        JAPANESE_PROLONGED_SOUND_CHARACTER_FILTER_NAME
+    }
+
+    /// Apply the filter using the OffsetMapping API
+    fn apply(&self, text: &mut String) -> LinderaResult<OffsetMapping> {
+        let mut filtered_text = String::with_capacity(text.len());
+        let mut mapping = OffsetMapping::new();
+
+        let chars = text.char_indices().collect::<Vec<_>>();
+        let byte_pos = |i: usize| chars.get(i).map_or(text.len(), |(pos, _)| *pos);
+
+        // The last kana written to the filtered text, if it is the last character
+        let mut prev_kana: Option<char> = None;
+        let mut i = 0;
+        while i < chars.len() {
+            let (start, c) = chars[i];
+
+            let replacement = match prev_kana {
+                Some(_) if self.is_prolonged_sound_mark(c) => {
+                    let mut end = i + 1;
+                    while end < chars.len() && self.is_prolonged_sound_mark(chars[end].1) {
+                        end += 1;
+                    }
+                    Some((end, PROLONGED_SOUND_MARK.to_string()))
+                }
+                Some(prev) if self.is_stretching_small_vowel(prev, c) => {
+                    let mut end = i + 1;
+                    while end < chars.len() && self.is_stretching_small_vowel(prev, chars[end].1) {
+                        end += 1;
+                    }
+                    Some((end, String::new()))
+                }
+                _ => None,
+            };
+
+            match replacement {
+                Some((end, replacement_text)) => {
+                    let original_len = byte_pos(end) - start;
+                    let replacement_len = replacement_text.len();
+
+                    // Record transformation if text changed
+                    if original_len != replacement_len {
+                        let transformation = Transformation::new(
+                            start,
+                            start + original_len,
+                            filtered_text.len(),
+                            filtered_text.len() + replacement_len,
+                        );
+                        mapping.add_transformation(transformation);
+                    }
+
+                    filtered_text.push_str(&replacement_text);
+                    if !replacement_text.is_empty() {
+                        prev_kana = None;
+                    }
+                    i = end;
+                }
+                None => {
+                    filtered_text.push(c);
+                    prev_kana = is_kana(c).then_some(c);
+                    i += 1;
+                }
+            }
+        }
+
+        *text = filtered_text;
+        Ok(mapping)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::character_filter::CharacterFilter;
+    use crate::character_filter::japanese_prolonged_sound::{
+        JapaneseProlongedSoundCharacterFilter, JapaneseProlongedSoundCharacterFilterConfig,
+    };
+
+    #[test]
+    fn test_japanese_prolonged_sound_character_filter_from_config() {
+        let config_str = r#"
+        {
+            "normalize_wave_dash": false
+        }
+        "#;
+        let config =
+            serde_json::from_str::<JapaneseProlongedSoundCharacterFilterConfig>(config_str)
+                .unwrap();
+
+        let filter = JapaneseProlongedSoundCharacterFilter::from_config(&config).unwrap();
+        assert!(filter.normalize_prolonged_sound);
+        assert!(!filter.normalize_wave_dash);
+        assert!(filter.normalize_small_kana);
+
+        let config = serde_json::from_str::<JapaneseProlongedSoundCharacterFilterConfig>(
+            r#"{"normalize_small_kana": "yes"}"#,
+        )
+        .unwrap();
+        assert!(JapaneseProlongedSoundCharacterFilter::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_japanese_prolonged_sound_character_filter_apply() {
+        let filter = JapaneseProlongedSoundCharacterFilter::new(true, true, true);
+
+        {
+            let original_text = "スーーーパー";
+            let mut text = original_text.to_string();
+            let mapping = filter.apply(&mut text).unwrap();
+            assert_eq!("スーパー", text);
+
+            // Verify transformation: "ーーー"(3-12) → "ー"(3-6)
+            assert_eq!(1, mapping.transformations.len());
+            let transform = &mapping.transformations[0];
+            assert_eq!(3, transform.original_start);
+            assert_eq!(12, transform.original_end);
+            assert_eq!(3, transform.filtered_start);
+            assert_eq!(6, transform.filtered_end);
+
+            // Test text fragments
+            let start = 6;
+            let end = 12;
+            assert_eq!("パー", &text[start..end]);
+            let correct_start = mapping.correct_offset(start, text.len());
+            let correct_end = mapping.correct_offset(end, text.len());
+            assert_eq!(12, correct_start);
+            assert_eq!(18, correct_end);
+            assert_eq!("パー", &original_text[correct_start..correct_end]);
+        }
+
+        {
+            let original_text = "すご〜〜い！ｰｰ";
+            let mut text = original_text.to_string();
+            filter.apply(&mut text).unwrap();
+            assert_eq!("すごーい！ｰｰ", text);
+        }
+
+        {
+            let original_text = "すごぉぉぉい";
+            let mut text = original_text.to_string();
+            let mapping = filter.apply(&mut text).unwrap();
+            assert_eq!("すごい", text);
+
+            // Verify transformation: "ぉぉぉ"(6-15) → ""(6-6)
+            assert_eq!(1, mapping.transformations.len());
+            let transform = &mapping.transformations[0];
+            assert_eq!(6, transform.original_start);
+            assert_eq!(15, transform.original_end);
+            assert_eq!(6, transform.filtered_start);
+            assert_eq!(6, transform.filtered_end);
+        }
+
+        {
+            // Small kana that form a sound are kept
+            let original_text = "ファイル、パーティ、トゥーン、ふぁぁぁ";
+            let mut text = original_text.to_string();
+            filter.apply(&mut text).unwrap();
+            assert_eq!("ファイル、パーティ、トゥーン、ふぁ", text);
+        }
+
+        {
+            // Marks that do not follow a kana are kept
+            let original_text = "1〜2時間、ーー";
+            let mut text = original_text.to_string();
+            let mapping = filter.apply(&mut text).unwrap();
+            assert_eq!(original_text, text);
+            assert!(mapping.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_japanese_prolonged_sound_character_filter_apply_disabled() {
+        let original_text = "スーーパー〜、すごぉい";
+
+        let filter = JapaneseProlongedSoundCharacterFilter::new(false, false, false);
+        let mut text = original_text.to_string();
+        let mapping = filter.apply(&mut text).unwrap();
+        assert_eq!(original_text, text);
+        assert!(mapping.is_empty());
+
+        let filter = JapaneseProlongedSoundCharacterFilter::new(true, false, false);
+        let mut text = original_text.to_string();
+        filter.apply(&mut text).unwrap();
+        assert_eq!("スーパー〜、すごぉい", text);
+
+        let filter = JapaneseProlongedSoundCharacterFilter::new(false, false, true);
+        let mut text = original_text.to_string();
+        filter.apply(&mut text).unwrap();
+        assert_eq!("スーーパー〜、すごい", text);
+    }
+}
@@ -0,0 +1,141 @@
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::LinderaResult;
+use crate::error::LinderaErrorKind;
+use crate::token_filter::japanese_kana::KanaKind;
+
+use crate::character_filter::{CharacterFilter, OffsetMapping};
+
+pub const KANA_CONVERSION_CHARACTER_FILTER_NAME: &str = "kana_conversion";
+
+pub type KanaConversionCharacterFilterConfig = Value;
+
+/// Distance between a hiragana and the corresponding katakana code point.
+const KANA_OFFSET: u32 = 0x60;
+
+/// Converts a hiragana character to katakana, leaving other characters as they are.
+fn hiragana_to_katakana(c: char) -> char {
+    match c as u32 {
+        // ぁ…ゖ, ゝ, ゞ
+        codepoint @ (0x3041..=0x3096 | 0x309d..=0x309e) => {
+            char::from_u32(codepoint + KANA_OFFSET).unwrap_or(c)
+        }
+        _ => c,
+    }
+}
+
+/// Converts a katakana character to hiragana, leaving other characters as they are.
+///
+/// ヷ, ヸ, ヹ and ヺ have no hiragana counterpart and are kept.
+fn katakana_to_hiragana(c: char) -> char {
+    match c as u32 {
+        // ァ…ヶ, ヽ, ヾ
+        codepoint @ (0x30a1..=0x30f6 | 0x30fd..=0x30fe) => {
+            char::from_u32(codepoint - KANA_OFFSET).unwrap_or(c)
+        }
+        _ => c,
+    }
+}
+
+/// Convert hiragana to katakana, or katakana to hiragana, before the text is segmented,
+/// that using the specified kind, one of 'katakana' (hiragana to katakana) or 'hiragana' (katakana to hiragana).
+///
+/// Every kana is replaced by a kana of the same UTF-8 length, so token offsets need no correction.
+///
+#[derive(Clone, Debug)]
+pub struct KanaConversionCharacterFilter {
+    kind: KanaKind,
+}
+
+impl KanaConversionCharacterFilter {
+    pub fn new(kind: KanaKind) -> Self {
+        Self { kind }
+    }
+
+    pub fn from_config(config: &KanaConversionCharacterFilterConfig) -> LinderaResult<Self> {
+        let kind = config
+            .get("kind")
+            .ok_or_else(|| {
+                LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!("missing kind config."))
+            })?
+            .as_str()
+            .ok_or_else(|| {
+                LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!("invalid kind config."))
+            })?;
+        let kind = KanaKind::from_str(kind)?;
+
+        Ok(Self::new(kind))
+    }
+}
+
+impl CharacterFilter for KanaConversionCharacterFilter {
+    fn name(&self) -> &'static str {
+        KANA_CONVERSION_CHARACTER_FILTER_NAME
+    }
+
+    /// Apply the filter using the OffsetMapping API
+    fn apply(&self, text: &mut String) -> LinderaResult<OffsetMapping> {
+        let convert = match self.kind {
+            KanaKind::Hiragana => katakana_to_hiragana,
+            KanaKind::Katakana => hiragana_to_katakana,
+        };
+        *text = text.chars().map(convert).collect();
+
+        // Both kana blocks are in the 3-byte UTF-8 range, so the offsets are unchanged
+        Ok(OffsetMapping::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::character_filter::CharacterFilter;
+    use crate::character_filter::kana_conversion::{
+        KanaConversionCharacterFilter, KanaConversionCharacterFilterConfig,
+    };
+    use crate::token_filter::japanese_kana::KanaKind;
+
+    #[test]
+    fn test_kana_conversion_character_filter_from_config() {
+        let config_str = r#"
+        {
+            "kind": "katakana"
+        }
+        "#;
+        let config =
+            serde_json::from_str::<KanaConversionCharacterFilterConfig>(config_str).unwrap();
+
+        let result = KanaConversionCharacterFilter::from_config(&config);
+        assert!(result.is_ok());
+
+        let config =
+            serde_json::from_str::<KanaConversionCharacterFilterConfig>(r#"{"kind": "romaji"}"#)
+                .unwrap();
+        assert!(KanaConversionCharacterFilter::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_kana_conversion_character_filter_apply_katakana() {
+        let filter = KanaConversionCharacterFilter::new(KanaKind::Katakana);
+
+        let original_text = "どきどきする、ゞゝぁゖ。カタカナ";
+        let mut text = original_text.to_string();
+        let mapping = filter.apply(&mut text).unwrap();
+        assert_eq!("ドキドキスル、ヾヽァヶ。カタカナ", text);
+        assert_eq!(original_text.len(), text.len());
+        assert!(mapping.is_empty());
+    }
+
+    #[test]
+    fn test_kana_conversion_character_filter_apply_hiragana() {
+        let filter = KanaConversionCharacterFilter::new(KanaKind::Hiragana);
+
+        let original_text = "ワクワクするヴァイオリン、ヷー、ひらがな";
+        let mut text = original_text.to_string();
+        let mapping = filter.apply(&mut text).unwrap();
+        assert_eq!("わくわくするゔぁいおりん、ヷー、ひらがな", text);
+        assert_eq!(original_text.len(), text.len());
+        assert!(mapping.is_empty());
+    }
+}
@@ -0,0 +1,275 @@
+use std::borrow::Cow;
+
+use serde_json::Value;
+
+use crate::LinderaResult;
+use crate::character_filter::{CharacterFilter, OffsetMapping, Transformation};
+use crate::error::LinderaErrorKind;
+
+pub const SANITIZE_CHARACTER_FILTER_NAME: &str = "sanitize";
+
+pub type SanitizeCharacterFilterConfig = Value;
+
+/// Returns `true` if `c` is a C0 control other than tab, line feed and carriage return, DEL, or
+/// a C1 control.
+fn is_control(c: char) -> bool {
+    matches!(c, '\u{0}'..='\u{8}' | '\u{b}' | '\u{c}' | '\u{e}'..='\u{1f}' | '\u{7f}'..='\u{9f}')
+}
+
+/// Returns `true` if `c` is a zero-width character: the zero width space, non-joiner and joiner,
+/// the word joiner, or the byte order mark (zero width no-break space).
+fn is_zero_width(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200b}' | '\u{200c}' | '\u{200d}' | '\u{2060}' | '\u{feff}'
+    )
+}
+
+/// Removes the characters that crawled text often carries but that never belong to a word.
+///
+/// - `remove_controls`: C0 controls other than `\t`, `\n` and `\r`, DEL and C1 controls are
+///   removed.
+/// - `remove_zero_width`: U+200B, U+200C, U+200D, U+2060 and U+FEFF are removed.
+///
+/// Both options default to `true`. Invalid UTF-8 can not reach a character filter; decode it with
+/// [`decode_lossy`] or tokenize it with [`crate::tokenizer::Tokenizer::tokenize_bytes`].
+#[derive(Clone, Debug)]
+pub struct SanitizeCharacterFilter {
+    pub remove_controls: bool,
+    pub remove_zero_width: bool,
+}
+
+impl SanitizeCharacterFilter {
+    pub fn new(remove_controls: bool, remove_zero_width: bool) -> Self {
+        Self {
+            remove_controls,
+            remove_zero_width,
+        }
+    }
+
+    pub fn from_config(config: &SanitizeCharacterFilterConfig) -> LinderaResult<Self> {
+        let get_bool = |key: &str| -> LinderaResult<bool> {
+            match config.get(key) {
+                Some(value) => value.as_bool().ok_or_else(|| {
+                    LinderaErrorKind::Deserialize
+                        .with_error(anyhow::anyhow!("invalid {key} config."))
+                }),
+                None => Ok(true),
+            }
+        };
+
+        Ok(Self::new(
+            get_bool("remove_controls")?,
+            get_bool("remove_zero_width")?,
+        ))
+    }
+
+    fn is_removed(&self, c: char) -> bool {
+        (self.remove_controls && is_control(c)) || (self.remove_zero_width && is_zero_width(c))
+    }
+}
+
+impl CharacterFilter for SanitizeCharacterFilter {
+    fn name(&self) -> &'static str {
+        SANITIZE_CHARACTER_FILTER_NAME
+    }
+
+    /// Apply the filter using the OffsetMapping API
+    fn apply(&self, text: &mut String) -> LinderaResult<OffsetMapping> {
+        let mut mapping = OffsetMapping::new();
+        if !text.chars().any(|c| self.is_removed(c)) {
+            return Ok(mapping);
+        }
+
+        let mut filtered_text = String::with_capacity(text.len());
+        // The original range of the removed characters not recorded yet
+        let mut removed: Option<(usize, usize)> = None;
+
+        for (start, c) in text.char_indices() {
+            let end = start + c.len_utf8();
+            if self.is_removed(c) {
+                removed = match removed {
+                    Some((removed_start, _)) => Some((removed_start, end)),
+                    None => Some((start, end)),
+                };
+                continue;
+            }
+
+            // A run of removed characters is recorded as one deletion
+            if let Some((removed_start, removed_end)) = removed.take() {
+                mapping.add_transformation(Transformation::new(
+                    removed_start,
+                    removed_end,
+                    filtered_text.len(),
+                    filtered_text.len(),
+                ));
+            }
+            filtered_text.push(c);
+        }
+        if let Some((removed_start, removed_end)) = removed {
+            mapping.add_transformation(Transformation::new(
+                removed_start,
+                removed_end,
+                filtered_text.len(),
+                filtered_text.len(),
+            ));
+        }
+
+        *text = filtered_text;
+        Ok(mapping)
+    }
+}
+
+/// Decodes bytes as UTF-8, replacing every invalid sequence with U+FFFD as
+/// [`String::from_utf8_lossy`] does.
+///
+/// The mapping gives the byte offsets in `bytes` of the offsets in the decoded text. Valid UTF-8
+/// is borrowed, with an empty mapping.
+pub fn decode_lossy(bytes: &[u8]) -> (Cow<'_, str>, OffsetMapping) {
+    let mut mapping = OffsetMapping::new();
+    let mut chunks = bytes.utf8_chunks();
+
+    let first = match chunks.next() {
+        Some(chunk) if chunk.invalid().is_empty() => {
+            return (Cow::Borrowed(chunk.valid()), mapping);
+        }
+        Some(chunk) => chunk,
+        None => return (Cow::Borrowed(""), mapping),
+    };
+
+    let mut text = String::with_capacity(bytes.len() + 2);
+    let mut original_pos = 0;
+    for chunk in std::iter::once(first).chain(chunks) {
+        text.push_str(chunk.valid());
+        original_pos += chunk.valid().len();
+
+        let invalid_len = chunk.invalid().len();
+        if invalid_len > 0 {
+            mapping.add_transformation(Transformation::new(
+                original_pos,
+                original_pos + invalid_len,
+                text.len(),
+                text.len() + char::REPLACEMENT_CHARACTER.len_utf8(),
+            ));
+            text.push(char::REPLACEMENT_CHARACTER);
+            original_pos += invalid_len;
+        }
+    }
+
+    (Cow::Owned(text), mapping)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::character_filter::CharacterFilter;
+    use crate::character_filter::sanitize::{
+        SanitizeCharacterFilter, SanitizeCharacterFilterConfig, decode_lossy,
+    };
+
+    #[test]
+    fn test_sanitize_character_filter_from_config() {
+        let config = serde_json::from_str::<SanitizeCharacterFilterConfig>(
+            r#"{"remove_zero_width": false}"#,
+        )
+        .unwrap();
+
+        let filter = SanitizeCharacterFilter::from_config(&config).unwrap();
+        assert!(filter.remove_controls);
+        assert!(!filter.remove_zero_width);
+
+        let config =
+            serde_json::from_str::<SanitizeCharacterFilterConfig>(r#"{"remove_controls": 1}"#)
+                .unwrap();
+        assert!(SanitizeCharacterFilter::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_sanitize_character_filter_apply() {
+        let filter = SanitizeCharacterFilter::new(true, true);
+
+        {
+            let original_text = "東京\u{200b}\u{feff}都\u{7}に\u{85}住む\t\n";
+            let mut text = original_text.to_string();
+            let mapping = filter.apply(&mut text).unwrap();
+            assert_eq!("東京都に住む\t\n", text);
+
+            // Verify transformation: "\u{200b}\u{feff}"(6-12) → ""(6-6)
+            assert_eq!(3, mapping.transformations.len());
+            let transform = &mapping.transformations[0];
+            assert_eq!(6, transform.original_start);
+            assert_eq!(12, transform.original_end);
+            assert_eq!(6, transform.filtered_start);
+            assert_eq!(6, transform.filtered_end);
+
+            // Test text fragments
+            let start = 15;
+            let end = 18;
+            assert_eq!("む", &text[start..end]);
+            let correct_start = mapping.correct_offset(start, text.len());
+            let correct_end = mapping.correct_offset(end, text.len());
+            assert_eq!(24, correct_start);
+            assert_eq!(27, correct_end);
+            assert_eq!("む", &original_text[correct_start..correct_end]);
+
+            // An offset at a removed run maps to its start
+            assert_eq!(19, mapping.correct_offset(12, text.len()));
+        }
+
+        {
+            // Trailing characters are removed too
+            let mut text = "abc\u{0}\u{1f}".to_string();
+            let mapping = filter.apply(&mut text).unwrap();
+            assert_eq!("abc", text);
+            assert_eq!(1, mapping.transformations.len());
+            assert_eq!(5, mapping.transformations[0].original_end);
+        }
+
+        {
+            let original_text = "東京都\r\n";
+            let mut text = original_text.to_string();
+            let mapping = filter.apply(&mut text).unwrap();
+            assert_eq!(original_text, text);
+            assert!(mapping.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_sanitize_character_filter_apply_disabled() {
+        let original_text = "a\u{200d}b\u{1b}c";
+
+        let filter = SanitizeCharacterFilter::new(false, true);
+        let mut text = original_text.to_string();
+        filter.apply(&mut text).unwrap();
+        assert_eq!("ab\u{1b}c", text);
+
+        let filter = SanitizeCharacterFilter::new(true, false);
+        let mut text = original_text.to_string();
+        filter.apply(&mut text).unwrap();
+        assert_eq!("a\u{200d}bc", text);
+    }
+
+    #[test]
+    fn test_decode_lossy() {
+        let (text, mapping) = decode_lossy("東京都".as_bytes());
+        assert!(matches!(text, Cow::Borrowed("東京都")));
+        assert!(mapping.is_empty());
+
+        let (text, mapping) = decode_lossy(b"");
+        assert_eq!("", text);
+        assert!(mapping.is_empty());
+
+        // A truncated "京" (e4 ba ac) and a lone continuation byte
+        let bytes = b"\xe6\x9d\xb1\xe4\xba\xe9\x83\xbd\x80";
+        let (text, mapping) = decode_lossy(bytes);
+        assert_eq!("東\u{fffd}都\u{fffd}", text);
+        assert_eq!(2, mapping.transformations.len());
+
+        // "都" is at 3..6 in bytes, 6..9 in the decoded text
+        assert_eq!(5, mapping.correct_offset(6, text.len()));
+        assert_eq!(8, mapping.correct_offset(9, text.len()));
+        assert_eq!(9, mapping.correct_offset(text.len(), text.len()));
+        assert_eq!("都".as_bytes(), &bytes[5..8]);
+    }
+}
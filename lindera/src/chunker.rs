@@ -0,0 +1,251 @@
+//! Rule-based chunking of Japanese tokens into bunsetsu (文節), the phrases of a content word
+//! and the particles and auxiliaries following it, used as the unit of dependency parsing and of
+//! the accent phrases of text-to-speech.
+//!
+//! The rules read the part-of-speech fields of IPADIC and UniDic (the first two details of the
+//! tokens):
+//!
+//! - a content word (noun, verb, adjective, adverb...) starts a chunk,
+//! - the particles, auxiliaries, suffixes and punctuation join the chunk before them,
+//! - the nouns of a compound noun (`東京` `都庁`) and the `する` of a verbal noun (`勉強` `する`)
+//!   stay in one chunk,
+//! - the dependent verbs and adjectives (`読んで` `いる`, `食べて` `しまう`) join the chunk of
+//!   the verb they follow,
+//! - a prefix or an opening bracket starts a chunk that the following content word joins.
+//!
+//! ```text
+//! 私は / 東京都庁へ / 行っています。
+//! ```
+
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::token::Token;
+
+/// A bunsetsu, a range of the tokens given to [`chunk`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Chunk {
+    /// The indices of the tokens of the chunk.
+    pub tokens: Range<usize>,
+    /// The index of the head of the chunk, its last content word (主辞).
+    pub head: usize,
+    /// The index of the last particle or auxiliary of the chunk (機能語), if it has one.
+    pub function: Option<usize>,
+}
+
+impl Chunk {
+    /// The surface of the chunk, the concatenation of the surfaces of its tokens.
+    pub fn surface(&self, tokens: &[Token]) -> String {
+        tokens[self.tokens.clone()]
+            .iter()
+            .map(|token| token.surface.as_ref())
+            .collect()
+    }
+}
+
+/// The role of a token in the chunking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Noun,
+    Content,
+    /// A verb or an adjective that joins the chunk of the verb before it.
+    Dependent,
+    Suffix,
+    Function,
+    Prefix,
+    OpenBracket,
+    Punctuation,
+}
+
+impl Role {
+    /// The role of a token of the part-of-speech `major_pos,subcategory`, after the token
+    /// `previous` as `(major_pos, surface)`.
+    fn new(major_pos: &str, subcategory: &str, previous: Option<(&str, &str)>) -> Self {
+        match major_pos {
+            "助詞" | "助動詞" => Role::Function,
+            "接頭詞" | "接頭辞" => Role::Prefix,
+            "接尾辞" => Role::Suffix,
+            "名詞" if subcategory == "接尾" => Role::Suffix,
+            "名詞" => Role::Noun,
+            "記号" | "補助記号" if subcategory == "括弧開" => Role::OpenBracket,
+            "記号" | "補助記号" | "空白" => Role::Punctuation,
+            "動詞" | "形容詞" => {
+                let after_te = previous
+                    .is_some_and(|(pos, surface)| pos == "助詞" && matches!(surface, "て" | "で"));
+                if matches!(subcategory, "非自立" | "接尾") || after_te {
+                    Role::Dependent
+                } else {
+                    Role::Content
+                }
+            }
+            _ => Role::Content,
+        }
+    }
+}
+
+/// Groups the tokens into bunsetsu. The tokens are expected to be the ones of a Japanese
+/// dictionary, in the order of the text; every token belongs to exactly one chunk.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # #[cfg(feature = "embed-ipadic")]
+/// # {
+/// use lindera::chunker::chunk;
+/// use lindera::dictionary::load_dictionary;
+/// use lindera::mode::Mode;
+/// use lindera::segmenter::Segmenter;
+/// use lindera::tokenizer::Tokenizer;
+///
+/// let dictionary = load_dictionary("embedded://ipadic")?;
+/// let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+///
+/// let mut tokens = tokenizer.tokenize("私は東京の本を読む。")?;
+/// let surfaces = chunk(&mut tokens)
+///     .iter()
+///     .map(|chunk| chunk.surface(&tokens))
+///     .collect::<Vec<_>>();
+/// assert_eq!(vec!["私は", "東京の", "本を", "読む。"], surfaces);
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub fn chunk(tokens: &mut [Token]) -> Vec<Chunk> {
+    let mut chunks: Vec<Chunk> = Vec::new();
+    // Whether the current chunk waits for its content word (after a prefix or a bracket)
+    let mut open = false;
+    let mut previous: Option<(String, String, Role)> = None;
+
+    for (index, token) in tokens.iter_mut().enumerate() {
+        let major_pos = token.get_detail(0).unwrap_or_default().to_string();
+        let subcategory = token.get_detail(1).unwrap_or_default().to_string();
+        let base_form = ["base_form", "orthographic_base_form"]
+            .into_iter()
+            .find_map(|field_name| token.get(field_name).map(str::to_string))
+            .unwrap_or_default();
+        let role = Role::new(
+            &major_pos,
+            &subcategory,
+            previous
+                .as_ref()
+                .map(|(pos, surface, _)| (pos.as_str(), surface.as_str())),
+        );
+
+        let joins = match chunks.last() {
+            None => false,
+            Some(current) => {
+                let previous_role = previous.as_ref().map(|(_, _, role)| *role);
+                match role {
+                    Role::Function | Role::Suffix | Role::Punctuation | Role::Dependent => true,
+                    Role::Prefix | Role::OpenBracket => open,
+                    Role::Noun | Role::Content if open => true,
+                    // A compound noun, or the する of a verbal noun
+                    Role::Noun => {
+                        current.function.is_none()
+                            && matches!(previous_role, Some(Role::Noun | Role::Suffix))
+                    }
+                    Role::Content => {
+                        current.function.is_none()
+                            && previous_role == Some(Role::Noun)
+                            && major_pos == "動詞"
+                            && matches!(base_form.as_str(), "する" | "為る")
+                    }
+                }
+            }
+        };
+
+        if joins {
+            let current = chunks.last_mut().expect("a chunk to join");
+            current.tokens.end = index + 1;
+            match role {
+                Role::Function => current.function = Some(index),
+                Role::Noun | Role::Content => current.head = index,
+                _ => {}
+            }
+        } else {
+            chunks.push(Chunk {
+                tokens: index..index + 1,
+                head: index,
+                function: (role == Role::Function).then_some(index),
+            });
+        }
+        // The first content word closes a chunk opened by a prefix or a bracket
+        open = match role {
+            Role::Prefix | Role::OpenBracket => true,
+            Role::Noun | Role::Content => false,
+            _ => open,
+        };
+        previous = Some((major_pos, token.surface.to_string(), role));
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Metadata;
+    use crate::test_util::TestDictionary;
+
+    #[test]
+    fn test_chunk() {
+        let (_dict_dir, tokenizer) = TestDictionary::new(
+            "\
+私,0,0,100,名詞,代名詞,一般,*,*,*,私,ワタシ,ワタシ
+は,0,0,100,助詞,係助詞,*,*,*,*,は,ハ,ワ
+東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー
+都庁,0,0,100,名詞,一般,*,*,*,*,都庁,トチョウ,トチョー
+へ,0,0,100,助詞,格助詞,一般,*,*,*,へ,ヘ,エ
+行っ,0,0,100,動詞,自立,*,*,五段・カ行促音便,連用タ接続,行く,イッ,イッ
+て,0,0,100,助詞,接続助詞,*,*,*,*,て,テ,テ
+い,0,0,100,動詞,非自立,*,*,一段,連用形,いる,イ,イ
+ます,0,0,100,助動詞,*,*,*,特殊・マス,基本形,ます,マス,マス
+。,0,0,100,記号,句点,*,*,*,*,。,。,。
+「,0,0,100,記号,括弧開,*,*,*,*,「,「,「
+」,0,0,100,記号,括弧閉,*,*,*,*,」,」,」
+お,0,0,100,接頭詞,名詞接続,*,*,*,*,お,オ,オ
+茶,0,0,100,名詞,一般,*,*,*,*,茶,チャ,チャ
+勉強,0,0,100,名詞,サ変接続,*,*,*,*,勉強,ベンキョウ,ベンキョー
+する,0,0,100,動詞,自立,*,*,サ変・スル,基本形,する,スル,スル
+",
+        )
+        .unknown_cost(5000)
+        .metadata(Metadata {
+            name: "ipadic".to_string(),
+            ..Metadata::default()
+        })
+        .tokenizer();
+
+        let surfaces = |text: &str| {
+            let mut tokens = tokenizer.tokenize(text).unwrap();
+            chunk(&mut tokens)
+                .iter()
+                .map(|chunk| chunk.surface(&tokens))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec!["私は", "東京都庁へ", "行っています。"],
+            surfaces("私は東京都庁へ行っています。")
+        );
+        assert_eq!(
+            vec!["「お茶」は", "勉強する"],
+            surfaces("「お茶」は勉強する")
+        );
+
+        let mut tokens = tokenizer.tokenize("私は東京都庁へ行っています。").unwrap();
+        let chunks = chunk(&mut tokens);
+        assert_eq!(
+            Chunk {
+                tokens: 2..5,
+                head: 3,
+                function: Some(4),
+            },
+            chunks[1]
+        );
+        // The head of 行っています。 is 行っ, not the dependent い
+        assert_eq!(5, chunks[2].head);
+        assert_eq!(Some(8), chunks[2].function);
+        assert!(chunk(&mut []).is_empty());
+    }
+}
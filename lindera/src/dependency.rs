@@ -0,0 +1,265 @@
+//! Heuristic dependency links between the bunsetsu of [`chunker`](crate::chunker), in the manner
+//! of CaboCha: each chunk depends on a chunk after it, and the last chunk of a sentence is its
+//! root. The links are rough, read from the particles and the parts-of-speech of the chunks
+//! without a model, for extracting structure such as the arguments of a predicate:
+//!
+//! - a chunk of the adnominal `の` or of an adnominal (連体詞) depends on the next noun,
+//! - a predicate directly followed by a noun modifies it (a relative clause, `買った` `本を`),
+//! - the other chunks (case particles, topics, adverbs, conjunctions) depend on the next
+//!   predicate, or on the root of the sentence if there is none,
+//! - a chunk ending with a full stop is the root of its sentence.
+//!
+//! The links do not cross: a chunk only depends on the chunk after it or on one of the chunks
+//! that one depends on, in turn.
+
+use std::fmt::Write;
+
+use crate::chunker::Chunk;
+use crate::token::Token;
+
+/// The kind of chunk a chunk depends on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Noun,
+    Predicate,
+}
+
+/// What the attachment reads of a chunk.
+#[derive(Debug, Clone, Copy, Default)]
+struct Features {
+    /// Whether the head is a noun and the chunk not a predicate.
+    noun: bool,
+    /// Whether the head is a verb or an adjective, or the chunk has an auxiliary (`学生です`).
+    predicate: bool,
+    /// Whether the last word of the chunk (before its punctuation) is a predicate.
+    ends_with_predicate: bool,
+    adnominal: bool,
+    comma: bool,
+    full_stop: bool,
+}
+
+impl Features {
+    fn new(tokens: &mut [Token], chunk: &Chunk) -> Self {
+        let mut features = Features::default();
+        let mut head_pos = String::new();
+        let mut last_word: Option<(String, String, String)> = None;
+        for index in chunk.tokens.clone() {
+            let token = &mut tokens[index];
+            let major_pos = token.get_detail(0).unwrap_or_default().to_string();
+            let subcategory = token.get_detail(1).unwrap_or_default().to_string();
+            if index == chunk.head {
+                head_pos = major_pos.clone();
+            }
+            match major_pos.as_str() {
+                "記号" | "補助記号" | "空白" => match subcategory.as_str() {
+                    "読点" => features.comma = true,
+                    "句点" => features.full_stop = true,
+                    _ => {}
+                },
+                "助動詞" => {
+                    features.predicate = true;
+                    last_word = Some((major_pos, subcategory, token.surface.to_string()));
+                }
+                _ => last_word = Some((major_pos, subcategory, token.surface.to_string())),
+            }
+        }
+
+        if matches!(head_pos.as_str(), "動詞" | "形容詞" | "形容動詞" | "形状詞") {
+            features.predicate = true;
+        }
+        features.noun = !features.predicate && matches!(head_pos.as_str(), "名詞" | "代名詞");
+        if let Some((major_pos, subcategory, surface)) = last_word {
+            features.ends_with_predicate =
+                matches!(major_pos.as_str(), "動詞" | "形容詞" | "助動詞");
+            // IPADIC tags the adnominal の as 連体化, UniDic as a case particle
+            features.adnominal = head_pos == "連体詞"
+                || (major_pos == "助詞"
+                    && (subcategory == "連体化" || (subcategory == "格助詞" && surface == "の")));
+        }
+        features
+    }
+}
+
+/// The index of the chunk each chunk depends on, `None` for the roots of the sentences. The
+/// chunks are the ones [`chunk`](crate::chunker::chunk) returned for `tokens`.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # #[cfg(feature = "embed-ipadic")]
+/// # {
+/// use lindera::chunker::chunk;
+/// use lindera::dependency::attach;
+/// use lindera::dictionary::load_dictionary;
+/// use lindera::mode::Mode;
+/// use lindera::segmenter::Segmenter;
+/// use lindera::tokenizer::Tokenizer;
+///
+/// let dictionary = load_dictionary("embedded://ipadic")?;
+/// let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+///
+/// let mut tokens = tokenizer.tokenize("私は東京の本を読む。")?;
+/// let chunks = chunk(&mut tokens);
+/// // 私は → 読む。, 東京の → 本を, 本を → 読む。
+/// assert_eq!(vec![Some(3), Some(2), Some(3), None], attach(&mut tokens, &chunks));
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub fn attach(tokens: &mut [Token], chunks: &[Chunk]) -> Vec<Option<usize>> {
+    let features = chunks
+        .iter()
+        .map(|chunk| Features::new(tokens, chunk))
+        .collect::<Vec<_>>();
+
+    // From the last chunk, so the heads of the chunks after the current one are known
+    let mut heads: Vec<Option<usize>> = vec![None; chunks.len()];
+    for index in (0..chunks.len()).rev() {
+        let current = features[index];
+        if current.full_stop || index + 1 == chunks.len() {
+            continue;
+        }
+        let next = index + 1;
+        if current.ends_with_predicate && !current.comma && features[next].noun {
+            heads[index] = Some(next);
+            continue;
+        }
+        let target = if current.adnominal && !current.comma {
+            Target::Noun
+        } else {
+            Target::Predicate
+        };
+
+        // The chunks the link can reach without crossing another one
+        let mut candidate = next;
+        let mut last = next;
+        let head = loop {
+            let matched = match target {
+                Target::Noun => features[candidate].noun,
+                Target::Predicate => features[candidate].predicate,
+            };
+            if matched {
+                break candidate;
+            }
+            match heads[candidate] {
+                Some(head) => {
+                    last = head;
+                    candidate = head;
+                }
+                None => {
+                    break match target {
+                        Target::Noun => next,
+                        Target::Predicate => last,
+                    };
+                }
+            }
+        };
+        heads[index] = Some(head);
+    }
+    heads
+}
+
+/// Writes the chunks and their links in the lattice format of CaboCha (`-f1`), a `*` line per
+/// chunk with its index, the index of its head (`-1` for a root), the offsets of its head and
+/// function word in the chunk and a score, followed by its tokens as `surface\tdetails`, and
+/// `EOS` at the end. The score is always 0, as the links are not scored.
+pub fn to_cabocha(tokens: &mut [Token], chunks: &[Chunk], heads: &[Option<usize>]) -> String {
+    let mut output = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let head = heads
+            .get(index)
+            .copied()
+            .flatten()
+            .map_or(-1, |head| head as i64);
+        let function = chunk.function.unwrap_or(chunk.head);
+        let _ = writeln!(
+            output,
+            "* {index} {head}D {}/{} 0.000000",
+            chunk.head - chunk.tokens.start,
+            function - chunk.tokens.start
+        );
+        for token in &mut tokens[chunk.tokens.clone()] {
+            let surface = token.surface.to_string();
+            let _ = writeln!(output, "{surface}\t{}", token.details().join(","));
+        }
+    }
+    output.push_str("EOS\n");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunker::chunk;
+    use crate::dictionary::Metadata;
+    use crate::test_util::TestDictionary;
+
+    #[test]
+    fn test_attach() {
+        let (_dict_dir, tokenizer) = TestDictionary::new(
+            "\
+私,0,0,100,名詞,代名詞,一般,*,*,*,私,ワタシ,ワタシ
+は,0,0,100,助詞,係助詞,*,*,*,*,は,ハ,ワ
+東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー
+の,0,0,100,助詞,連体化,*,*,*,*,の,ノ,ノ
+本,0,0,100,名詞,一般,*,*,*,*,本,ホン,ホン
+を,0,0,100,助詞,格助詞,一般,*,*,*,を,ヲ,ヲ
+読む,0,0,100,動詞,自立,*,*,五段・マ行,基本形,読む,ヨム,ヨム
+。,0,0,100,記号,句点,*,*,*,*,。,。,。
+昨日,0,0,100,名詞,副詞可能,*,*,*,*,昨日,キノウ,キノー
+買っ,0,0,100,動詞,自立,*,*,五段・ワ行促音便,連用タ接続,買う,カッ,カッ
+た,0,0,100,助動詞,*,*,*,特殊・タ,基本形,た,タ,タ
+",
+        )
+        .unknown_cost(5000)
+        .metadata(Metadata {
+            name: "ipadic".to_string(),
+            ..Metadata::default()
+        })
+        .tokenizer();
+
+        let mut tokens = tokenizer
+            .tokenize("私は東京の本を読む。昨日買った本を読む")
+            .unwrap();
+        let chunks = chunk(&mut tokens);
+        let surfaces = chunks
+            .iter()
+            .map(|chunk| chunk.surface(&tokens))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "私は",
+                "東京の",
+                "本を",
+                "読む。",
+                "昨日",
+                "買った",
+                "本を",
+                "読む"
+            ],
+            surfaces
+        );
+        // 私は skips 東京の and 本を for the predicate 読む。, 買った modifies 本を
+        assert_eq!(
+            vec![
+                Some(3),
+                Some(2),
+                Some(3),
+                None,
+                Some(5),
+                Some(6),
+                Some(7),
+                None
+            ],
+            attach(&mut tokens, &chunks)
+        );
+
+        let heads = attach(&mut tokens, &chunks);
+        let cabocha = to_cabocha(&mut tokens, &chunks[..4], &heads);
+        assert!(cabocha.starts_with("* 0 3D 0/1 0.000000\n私\t名詞,代名詞,一般,"));
+        assert!(cabocha.contains("* 3 -1D 0/0 0.000000\n読む\t"));
+        assert!(cabocha.ends_with("。\t記号,句点,*,*,*,*,。,。,。\nEOS\n"));
+        assert!(attach(&mut [], &[]).is_empty());
+    }
+}
@@ -8,16 +8,34 @@ use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 use url::Url;
 
+#[cfg(feature = "embed-cc-canto")]
+use lindera_cc_canto::DICTIONARY_NAME as CC_CANTO_DICTIONARY_NAME;
+#[cfg(feature = "embed-cc-canto")]
+use lindera_cc_canto::embedded::EmbeddedCcCantoLoader;
 #[cfg(feature = "embed-cc-cedict")]
 use lindera_cc_cedict::DICTIONARY_NAME as CC_CEDICT_DICTIONARY_NAME;
 #[cfg(feature = "embed-cc-cedict")]
 use lindera_cc_cedict::embedded::EmbeddedCcCedictLoader;
 use lindera_dictionary::loader::DictionaryLoader;
 use lindera_dictionary::loader::FSDictionaryLoader;
+use lindera_dictionary::loader::okurigana::OkuriganaVariantsLoader;
+use lindera_dictionary::loader::reading_index::ReadingIndexLoader;
 use lindera_dictionary::loader::user_dictionary::UserDictionaryLoader;
 
+pub use lindera_dictionary::builder::export;
+pub use lindera_dictionary::builder::mozc;
+pub use lindera_dictionary::builder::progress::peak_resident_memory;
+pub use lindera_dictionary::builder::skk;
+pub use lindera_dictionary::dictionary::diff;
+pub use lindera_dictionary::embed_user_dictionary;
+pub use lindera_dictionary::format;
+#[cfg(feature = "signature")]
+pub use lindera_dictionary::signature;
 #[cfg(feature = "train")]
 pub use lindera_dictionary::trainer;
+pub use lindera_dictionary::util::format_timestamp;
+#[cfg(feature = "mmap")]
+pub use lindera_dictionary::util::mmap_file;
 #[cfg(feature = "embed-ipadic")]
 use lindera_ipadic::DICTIONARY_NAME as IPADIC_DICTIONARY_NAME;
 #[cfg(feature = "embed-ipadic")]
@@ -26,30 +44,64 @@ use lindera_ipadic::embedded::EmbeddedIPADICLoader;
 use lindera_ipadic_neologd::DICTIONARY_NAME as IPADIC_NEOLOGD_DICTIONARY_NAME;
 #[cfg(feature = "embed-ipadic-neologd")]
 use lindera_ipadic_neologd::embedded::EmbeddedIPADICNEologdLoader;
+#[cfg(feature = "embed-jumandic")]
+use lindera_jumandic::DICTIONARY_NAME as JUMANDIC_DICTIONARY_NAME;
+#[cfg(feature = "embed-jumandic")]
+use lindera_jumandic::embedded::EmbeddedJumandicLoader;
 #[cfg(feature = "embed-ko-dic")]
 use lindera_ko_dic::DICTIONARY_NAME as KO_DIC_DICTIONARY_NAME;
 #[cfg(feature = "embed-ko-dic")]
 use lindera_ko_dic::embedded::EmbeddedKoDicLoader;
+#[cfg(feature = "embed-naist-jdic")]
+use lindera_naist_jdic::DICTIONARY_NAME as NAIST_JDIC_DICTIONARY_NAME;
+#[cfg(feature = "embed-naist-jdic")]
+use lindera_naist_jdic::embedded::EmbeddedNaistJdicLoader;
 #[cfg(feature = "embed-unidic")]
 use lindera_unidic::DICTIONARY_NAME as UNIDIC_DICTIONARY_NAME;
 #[cfg(feature = "embed-unidic")]
 use lindera_unidic::embedded::EmbeddedUniDicLoader;
+#[cfg(feature = "embed-unidic-csj")]
+use lindera_unidic_csj::DICTIONARY_NAME as UNIDIC_CSJ_DICTIONARY_NAME;
+#[cfg(feature = "embed-unidic-csj")]
+use lindera_unidic_csj::embedded::EmbeddedUniDicCsjLoader;
+#[cfg(feature = "embed-unidic-cwj")]
+use lindera_unidic_cwj::DICTIONARY_NAME as UNIDIC_CWJ_DICTIONARY_NAME;
+#[cfg(feature = "embed-unidic-cwj")]
+use lindera_unidic_cwj::embedded::EmbeddedUniDicCwjLoader;
 
 use crate::LinderaResult;
 use crate::error::{LinderaError, LinderaErrorKind};
 
+pub mod cache;
+pub mod registry;
+pub mod search_path;
+
 pub type Dictionary = lindera_dictionary::dictionary::Dictionary;
+pub type DictionaryBytes = lindera_dictionary::dictionary::DictionaryBytes;
 pub type Metadata = lindera_dictionary::dictionary::metadata::Metadata;
+pub type DuplicatePolicy = lindera_dictionary::dictionary::metadata::DuplicatePolicy;
+pub type MatrixEncoding = lindera_dictionary::dictionary::metadata::MatrixEncoding;
 pub type UserDictionary = lindera_dictionary::dictionary::UserDictionary;
+pub type EmbeddedUserDictionaryLoader =
+    lindera_dictionary::loader::user_dictionary::EmbeddedUserDictionaryLoader;
 pub type Lattice = lindera_dictionary::viterbi::Lattice;
 pub type WordId = lindera_dictionary::viterbi::WordId;
 pub type DictionaryBuilder = lindera_dictionary::builder::DictionaryBuilder;
+pub type UserDictionaryDiagnostic = lindera_dictionary::builder::user_dictionary::Diagnostic;
+pub type BuildJob = lindera_dictionary::builder::progress::BuildJob;
+pub type BuildPhase = lindera_dictionary::builder::progress::BuildPhase;
+pub type BuildProgress = lindera_dictionary::builder::progress::BuildProgress;
+pub type CancellationToken = lindera_dictionary::builder::progress::CancellationToken;
+pub type MemoryReport = lindera_dictionary::builder::progress::MemoryReport;
 pub type DictionaryConfig = Value;
 pub type UserDictionaryConfig = Value;
 pub type Schema = lindera_dictionary::dictionary::schema::Schema;
 pub type FieldDefinition = lindera_dictionary::dictionary::schema::FieldDefinition;
 pub type FieldType = lindera_dictionary::dictionary::schema::FieldType;
 pub type CompressionAlgorithm = lindera_dictionary::decompress::Algorithm;
+pub type ReadingIndex = lindera_dictionary::dictionary::reading_index::ReadingIndex;
+pub type Candidate = lindera_dictionary::dictionary::reading_index::Candidate;
+pub type OkuriganaVariants = lindera_dictionary::dictionary::okurigana::OkuriganaVariants;
 
 #[derive(Debug, Clone, EnumIter, Deserialize, Serialize, PartialEq, Eq)]
 pub enum DictionaryScheme {
@@ -59,6 +111,11 @@ pub enum DictionaryScheme {
         feature = "embed-unidic",
         feature = "embed-ko-dic",
         feature = "embed-cc-cedict",
+        feature = "embed-unidic-cwj",
+        feature = "embed-unidic-csj",
+        feature = "embed-jumandic",
+        feature = "embed-naist-jdic",
+        feature = "embed-cc-canto",
     ))]
     #[serde(rename = "embedded")]
     Embedded,
@@ -75,6 +132,11 @@ impl DictionaryScheme {
                 feature = "embed-unidic",
                 feature = "embed-ko-dic",
                 feature = "embed-cc-cedict",
+                feature = "embed-unidic-cwj",
+                feature = "embed-unidic-csj",
+                feature = "embed-jumandic",
+                feature = "embed-naist-jdic",
+                feature = "embed-cc-canto",
             ))]
             DictionaryScheme::Embedded => "embedded",
             DictionaryScheme::File => "file",
@@ -92,6 +154,11 @@ impl FromStr for DictionaryScheme {
                 feature = "embed-unidic",
                 feature = "embed-ko-dic",
                 feature = "embed-cc-cedict",
+                feature = "embed-unidic-cwj",
+                feature = "embed-unidic-csj",
+                feature = "embed-jumandic",
+                feature = "embed-naist-jdic",
+                feature = "embed-cc-canto",
             ))]
             "embedded" => Ok(DictionaryScheme::Embedded),
             "file" => Ok(DictionaryScheme::File),
@@ -118,6 +185,21 @@ pub enum DictionaryKind {
     #[cfg(feature = "embed-cc-cedict")]
     #[serde(rename = "cc-cedict")]
     CcCedict,
+    #[cfg(feature = "embed-unidic-cwj")]
+    #[serde(rename = "unidic-cwj")]
+    UniDicCwj,
+    #[cfg(feature = "embed-unidic-csj")]
+    #[serde(rename = "unidic-csj")]
+    UniDicCsj,
+    #[cfg(feature = "embed-jumandic")]
+    #[serde(rename = "jumandic")]
+    Jumandic,
+    #[cfg(feature = "embed-naist-jdic")]
+    #[serde(rename = "naist-jdic")]
+    NaistJdic,
+    #[cfg(feature = "embed-cc-canto")]
+    #[serde(rename = "cc-canto")]
+    CcCanto,
 }
 
 impl DictionaryKind {
@@ -139,6 +221,16 @@ impl DictionaryKind {
                 DictionaryKind::KoDic => cfg!(feature = "embed-ko-dic"),
                 #[cfg(feature = "embed-cc-cedict")]
                 DictionaryKind::CcCedict => cfg!(feature = "embed-cc-cedict"),
+                #[cfg(feature = "embed-unidic-cwj")]
+                DictionaryKind::UniDicCwj => cfg!(feature = "embed-unidic-cwj"),
+                #[cfg(feature = "embed-unidic-csj")]
+                DictionaryKind::UniDicCsj => cfg!(feature = "embed-unidic-csj"),
+                #[cfg(feature = "embed-jumandic")]
+                DictionaryKind::Jumandic => cfg!(feature = "embed-jumandic"),
+                #[cfg(feature = "embed-naist-jdic")]
+                DictionaryKind::NaistJdic => cfg!(feature = "embed-naist-jdic"),
+                #[cfg(feature = "embed-cc-canto")]
+                DictionaryKind::CcCanto => cfg!(feature = "embed-cc-canto"),
                 #[allow(unreachable_patterns)]
                 _ => false,
             })
@@ -157,6 +249,16 @@ impl DictionaryKind {
             DictionaryKind::KoDic => KO_DIC_DICTIONARY_NAME,
             #[cfg(feature = "embed-cc-cedict")]
             DictionaryKind::CcCedict => CC_CEDICT_DICTIONARY_NAME,
+            #[cfg(feature = "embed-unidic-cwj")]
+            DictionaryKind::UniDicCwj => UNIDIC_CWJ_DICTIONARY_NAME,
+            #[cfg(feature = "embed-unidic-csj")]
+            DictionaryKind::UniDicCsj => UNIDIC_CSJ_DICTIONARY_NAME,
+            #[cfg(feature = "embed-jumandic")]
+            DictionaryKind::Jumandic => JUMANDIC_DICTIONARY_NAME,
+            #[cfg(feature = "embed-naist-jdic")]
+            DictionaryKind::NaistJdic => NAIST_JDIC_DICTIONARY_NAME,
+            #[cfg(feature = "embed-cc-canto")]
+            DictionaryKind::CcCanto => CC_CANTO_DICTIONARY_NAME,
             #[allow(unreachable_patterns)]
             _ => "",
         }
@@ -177,6 +279,16 @@ impl FromStr for DictionaryKind {
             KO_DIC_DICTIONARY_NAME => Ok(DictionaryKind::KoDic),
             #[cfg(feature = "embed-cc-cedict")]
             CC_CEDICT_DICTIONARY_NAME => Ok(DictionaryKind::CcCedict),
+            #[cfg(feature = "embed-unidic-cwj")]
+            UNIDIC_CWJ_DICTIONARY_NAME => Ok(DictionaryKind::UniDicCwj),
+            #[cfg(feature = "embed-unidic-csj")]
+            UNIDIC_CSJ_DICTIONARY_NAME => Ok(DictionaryKind::UniDicCsj),
+            #[cfg(feature = "embed-jumandic")]
+            JUMANDIC_DICTIONARY_NAME => Ok(DictionaryKind::Jumandic),
+            #[cfg(feature = "embed-naist-jdic")]
+            NAIST_JDIC_DICTIONARY_NAME => Ok(DictionaryKind::NaistJdic),
+            #[cfg(feature = "embed-cc-canto")]
+            CC_CANTO_DICTIONARY_NAME => Ok(DictionaryKind::CcCanto),
             _ => Err(LinderaErrorKind::Dictionary
                 .with_error(anyhow::anyhow!("Invalid dictionary kind: {input}"))),
         }
@@ -185,7 +297,7 @@ impl FromStr for DictionaryKind {
 
 pub fn resolve_embedded_loader(
     dictionary_type: DictionaryKind,
-) -> LinderaResult<Box<dyn DictionaryLoader>> {
+) -> LinderaResult<Box<dyn DictionaryLoader + Send + Sync>> {
     match dictionary_type {
         #[cfg(feature = "embed-ipadic")]
         DictionaryKind::IPADIC => Ok(Box::new(EmbeddedIPADICLoader::new())),
@@ -213,6 +325,16 @@ pub fn resolve_embedded_loader(
         // #[cfg(not(feature = "embed-cc-cedict"))]
         // DictionaryKind::CcCedict => Err(LinderaErrorKind::FeatureDisabled
         //     .with_error(anyhow::anyhow!("CC-CEDICT embedded feature is not enabled"))),
+        #[cfg(feature = "embed-unidic-cwj")]
+        DictionaryKind::UniDicCwj => Ok(Box::new(EmbeddedUniDicCwjLoader::new())),
+        #[cfg(feature = "embed-unidic-csj")]
+        DictionaryKind::UniDicCsj => Ok(Box::new(EmbeddedUniDicCsjLoader::new())),
+        #[cfg(feature = "embed-jumandic")]
+        DictionaryKind::Jumandic => Ok(Box::new(EmbeddedJumandicLoader::new())),
+        #[cfg(feature = "embed-naist-jdic")]
+        DictionaryKind::NaistJdic => Ok(Box::new(EmbeddedNaistJdicLoader::new())),
+        #[cfg(feature = "embed-cc-canto")]
+        DictionaryKind::CcCanto => Ok(Box::new(EmbeddedCcCantoLoader::new())),
     }
 }
 
@@ -233,69 +355,69 @@ pub fn load_dictionary_temporary(kind: DictionaryKind) -> LinderaResult<Dictiona
     loader.load_temporary()
 }
 
+/// Loads the dictionary at a path or URI (e.g. `embedded://ipadic`, `file:///path/to/dictionary`).
+///
+/// URIs are resolved through the global [`registry`], so schemes registered by other crates
+/// are supported as well.
 pub fn load_dictionary(uri: &str) -> LinderaResult<Dictionary> {
-    // Try to parse as URI first, but only if it looks like a URI
-    // (contains "://" or starts with known schemes)
-    if uri.contains("://") {
-        match Url::parse(uri) {
-            Ok(parsed_uri) => {
-                // Parse the URI and return the appropriate dictionary
-                let scheme = DictionaryScheme::from_str(parsed_uri.scheme()).map_err(|err| {
-                    LinderaErrorKind::Dictionary
-                        .with_error(anyhow::anyhow!("Invalid dictionary scheme: {err}"))
-                })?;
+    registry::load_registered_dictionary(uri)
+}
 
-                match scheme {
-                    #[cfg(any(
-                        feature = "embed-ipadic",
-                        feature = "embed-ipadic-neologd",
-                        feature = "embed-unidic",
-                        feature = "embed-ko-dic",
-                        feature = "embed-cc-cedict",
-                    ))]
-                    DictionaryScheme::Embedded => {
-                        let kind = DictionaryKind::from_str(parsed_uri.host_str().unwrap_or(""))
-                            .map_err(|err| LinderaErrorKind::Dictionary.with_error(err))?;
+/// Converts a `file://` URI to a path.
+pub(crate) fn file_uri_to_path(uri: &Url) -> LinderaResult<PathBuf> {
+    // Extract path from file:// URL manually
+    let path_str = uri.path();
 
-                        // Load the embedded dictionary
-                        load_embedded_dictionary(kind)
-                    }
-                    DictionaryScheme::File => {
-                        // Extract path from file:// URL manually
-                        let path_str = parsed_uri.path();
-
-                        // Handle Windows paths that might start with /C:/ etc.
-                        let path_str =
-                            if cfg!(windows) && path_str.len() > 1 && path_str.starts_with('/') {
-                                &path_str[1..]
-                            } else {
-                                path_str
-                            };
-
-                        // Decode percent-encoded characters
-                        let decoded_path =
-                            percent_decode_str(path_str).decode_utf8().map_err(|e| {
-                                LinderaErrorKind::Dictionary
-                                    .with_error(anyhow::anyhow!("Invalid UTF-8 in path: {e}"))
-                            })?;
-
-                        let path = Path::new(decoded_path.as_ref());
-
-                        // Load the file-based dictionary
-                        load_fs_dictionary(path)
-                    }
-                }
-            }
-            Err(e) => {
-                Err(LinderaErrorKind::Dictionary
-                    .with_error(anyhow::anyhow!("Invalid URI format: {e}")))
-            }
-        }
+    // Handle Windows paths that might start with /C:/ etc.
+    let path_str = if cfg!(windows) && path_str.len() > 1 && path_str.starts_with('/') {
+        &path_str[1..]
     } else {
-        // Treat it as a file path directly
-        let path = Path::new(uri);
-        load_fs_dictionary(path)
+        path_str
+    };
+
+    // Decode percent-encoded characters
+    let decoded_path = percent_decode_str(path_str).decode_utf8().map_err(|e| {
+        LinderaErrorKind::Dictionary.with_error(anyhow::anyhow!("Invalid UTF-8 in path: {e}"))
+    })?;
+
+    Ok(PathBuf::from(decoded_path.as_ref()))
+}
+
+/// Loads the reading index of the dictionary at `path`, for looking up candidate surfaces by
+/// reading (kana-kanji conversion).
+pub fn load_reading_index(path: &Path) -> LinderaResult<ReadingIndex> {
+    ReadingIndexLoader::load(path)
+}
+
+/// Loads the okurigana variant table of the dictionary at `path`, for okurigana-tolerant
+/// lookups (see [`Segmenter::okurigana_variants`](crate::segmenter::Segmenter::okurigana_variants)).
+pub fn load_okurigana_variants(path: &Path) -> LinderaResult<OkuriganaVariants> {
+    OkuriganaVariantsLoader::load(path)
+}
+
+/// Builds the okurigana variant table of a dictionary from its words, the table
+/// [`load_okurigana_variants`] loads if the dictionary was built with it.
+pub fn build_okurigana_variants(dictionary: &Dictionary) -> LinderaResult<OkuriganaVariants> {
+    DictionaryBuilder::new(dictionary.metadata.clone()).build_okurigana_variants(dictionary)
+}
+
+/// Returns the directory of a dictionary given by a path or a `file://` URI, or `None` for the
+/// other URIs (e.g. `embedded://ipadic`). A relative path is looked up in the
+/// [search paths](search_path).
+pub(crate) fn dictionary_dir(uri: &str) -> LinderaResult<Option<PathBuf>> {
+    if uri.contains("://") {
+        let parsed_uri = Url::parse(uri).map_err(|err| {
+            LinderaErrorKind::Dictionary.with_error(anyhow::anyhow!("Invalid URI format: {err}"))
+        })?;
+        return match parsed_uri.scheme() {
+            registry::FILE_SCHEME => file_uri_to_path(&parsed_uri).map(Some),
+            _ => Ok(None),
+        };
     }
+    if uri.to_ascii_lowercase().starts_with("embedded:") {
+        return Ok(None);
+    }
+    Ok(search_path::locate_path(uri, &search_path::dictionary_search_paths()).path)
 }
 
 pub fn load_user_dictionary_from_csv(
@@ -306,14 +428,24 @@ pub fn load_user_dictionary_from_csv(
     UserDictionaryLoader::load_from_csv(builder, path)
 }
 
+/// Builds a user dictionary from the contents of a CSV file held in memory.
+pub fn load_user_dictionary_from_csv_bytes(
+    metadata: &Metadata,
+    data: &[u8],
+) -> LinderaResult<UserDictionary> {
+    let builder = DictionaryBuilder::new(metadata.clone());
+    UserDictionaryLoader::load_from_csv_bytes(builder, data)
+}
+
 pub fn load_user_dictionary_from_bin(path: &Path) -> LinderaResult<UserDictionary> {
     UserDictionaryLoader::load_from_bin(path)
 }
 
-pub fn load_user_dictionary(uri: &str, metadata: &Metadata) -> LinderaResult<UserDictionary> {
+/// Returns the path of a user dictionary given by a path or a `file://` URI.
+fn user_dictionary_path(uri: &str) -> LinderaResult<PathBuf> {
     // Try to parse as URI first, but only if it looks like a URI
     // (contains "://" or starts with known schemes)
-    let path = if uri.contains("://") {
+    if uri.contains("://") {
         match Url::parse(uri) {
             Ok(parsed_uri) => {
                 // Parse the URI and return the appropriate dictionary
@@ -323,50 +455,39 @@ pub fn load_user_dictionary(uri: &str, metadata: &Metadata) -> LinderaResult<Use
                 })?;
 
                 match scheme {
-                    DictionaryScheme::File => {
-                        // Extract path from file:// URL manually
-                        let path_str = parsed_uri.path();
-
-                        // Handle Windows paths that might start with /C:/ etc.
-                        let path_str =
-                            if cfg!(windows) && path_str.len() > 1 && path_str.starts_with('/') {
-                                &path_str[1..]
-                            } else {
-                                path_str
-                            };
-
-                        // Decode percent-encoded characters
-                        let decoded_path =
-                            percent_decode_str(path_str).decode_utf8().map_err(|e| {
-                                LinderaErrorKind::Dictionary
-                                    .with_error(anyhow::anyhow!("Invalid UTF-8 in path: {e}"))
-                            })?;
-
-                        PathBuf::from(decoded_path.as_ref())
-                    }
+                    DictionaryScheme::File => file_uri_to_path(&parsed_uri),
                     #[cfg(any(
                         feature = "embed-ipadic",
                         feature = "embed-ipadic-neologd",
                         feature = "embed-unidic",
                         feature = "embed-ko-dic",
                         feature = "embed-cc-cedict",
+                        feature = "embed-unidic-cwj",
+                        feature = "embed-unidic-csj",
+                        feature = "embed-jumandic",
+                        feature = "embed-naist-jdic",
+                        feature = "embed-cc-canto",
                     ))]
                     _ => {
                         // Unsupported dictionary scheme
-                        return Err(LinderaErrorKind::Dictionary
-                            .with_error(anyhow::anyhow!("Unsupported dictionary scheme")));
+                        Err(LinderaErrorKind::Dictionary
+                            .with_error(anyhow::anyhow!("Unsupported dictionary scheme")))
                     }
                 }
             }
             Err(e) => {
-                return Err(LinderaErrorKind::Dictionary
-                    .with_error(anyhow::anyhow!("Invalid URI format: {e}")));
+                Err(LinderaErrorKind::Dictionary
+                    .with_error(anyhow::anyhow!("Invalid URI format: {e}")))
             }
         }
     } else {
         // Treat it as a file path directly
-        PathBuf::from(uri)
-    };
+        Ok(PathBuf::from(uri))
+    }
+}
+
+pub fn load_user_dictionary(uri: &str, metadata: &Metadata) -> LinderaResult<UserDictionary> {
+    let path = user_dictionary_path(uri)?;
 
     // extract file extension
     let extension = path
@@ -385,3 +506,126 @@ pub fn load_user_dictionary(uri: &str, metadata: &Metadata) -> LinderaResult<Use
         ))),
     }
 }
+
+/// Loads the dictionary at a path or URI like [`load_dictionary`], refusing it unless it is signed
+/// by one of the keys trusted by `verifier`.
+///
+/// A dictionary directory must hold a valid `dictionary.sig`, and an archive of a dictionary a
+/// valid `<archive>.sig` next to it. Embedded dictionaries are part of the binary and are loaded
+/// as they are. Dictionaries from other URI schemes can not be checked and are refused.
+///
+/// Each file is read once, and the dictionary is loaded from the bytes that were checked, so a
+/// file replaced after the check is not used.
+#[cfg(feature = "signature")]
+pub fn load_verified_dictionary(
+    uri: &str,
+    verifier: &signature::SignatureVerifier,
+) -> LinderaResult<Dictionary> {
+    if uri.to_ascii_lowercase().starts_with("embedded:") {
+        return load_dictionary(uri);
+    }
+    let path = dictionary_dir(uri)?.ok_or_else(|| {
+        LinderaErrorKind::Signature.with_error(anyhow::anyhow!(
+            "the signature of dictionary {uri} can not be checked: only embedded dictionaries and dictionaries on the file system can be verified"
+        ))
+    })?;
+
+    if path.is_dir() {
+        let files = verifier.verify_dictionary_files(&path)?;
+        Dictionary::from_files(files)
+    } else {
+        let data = verifier.verify_file(&path)?;
+        Dictionary::from_archive_bytes(&data)
+    }
+}
+
+/// Loads the user dictionary at a path or `file://` URI like [`load_user_dictionary`], refusing
+/// it unless `<file>.sig` next to it is a signature by one of the keys trusted by `verifier`.
+///
+/// The user dictionary is loaded from the bytes that were checked.
+#[cfg(feature = "signature")]
+pub fn load_verified_user_dictionary(
+    uri: &str,
+    metadata: &Metadata,
+    verifier: &signature::SignatureVerifier,
+) -> LinderaResult<UserDictionary> {
+    let path = user_dictionary_path(uri)?;
+    let data = verifier.verify_file(&path)?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => load_user_dictionary_from_csv_bytes(metadata, &data),
+        Some("bin") => UserDictionary::load(&data),
+        Some(_) => Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+            "Invalid user dictionary source file extension"
+        ))),
+        None => Err(LinderaErrorKind::Args
+            .with_error(anyhow::anyhow!("Invalid user dictionary source file"))),
+    }
+}
+
+#[cfg(all(test, feature = "signature"))]
+mod tests {
+    use std::fs;
+
+    use crate::dictionary::signature::{SignatureVerifier, SigningKey};
+    use crate::dictionary::{
+        Dictionary, Metadata, load_verified_dictionary, load_verified_user_dictionary,
+    };
+    use crate::test_util::TestDictionary;
+
+    #[test]
+    fn test_load_verified_dictionary_uses_the_checked_files() {
+        let dict_dir = TestDictionary::new(
+            "東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー\n",
+        )
+        .build();
+
+        let key = SigningKey::generate().unwrap();
+        key.sign_dictionary(dict_dir.path()).unwrap();
+        let verifier = SignatureVerifier::new(vec![key.public_key()]);
+        let uri = dict_dir.path().to_str().unwrap();
+        let dictionary = load_verified_dictionary(uri, &verifier).unwrap();
+        assert_eq!(1, dictionary.num_entries());
+
+        // A file swapped once the directory was checked is not used
+        let files = verifier.verify_dictionary_files(dict_dir.path()).unwrap();
+        fs::write(dict_dir.path().join("dict.words"), b"").unwrap();
+        let dictionary = Dictionary::from_files(files).unwrap();
+        assert_eq!("名詞", dictionary.word_details(0)[0]);
+
+        // and the next load refuses it
+        assert!(load_verified_dictionary(uri, &verifier).is_err());
+
+        // A user dictionary is built from the checked bytes as well
+        let user_dict_path = dict_dir.path().join("userdic.csv");
+        fs::write(
+            &user_dict_path,
+            "東京スカイツリー,カスタム名詞,トウキョウスカイツリー\n",
+        )
+        .unwrap();
+        key.sign_file(&user_dict_path).unwrap();
+        let user_dictionary = load_verified_user_dictionary(
+            user_dict_path.to_str().unwrap(),
+            &Metadata::default(),
+            &verifier,
+        )
+        .unwrap();
+        assert_eq!(
+            Some(&"トウキョウスカイツリー"),
+            user_dictionary.word_details(0).last()
+        );
+
+        fs::write(
+            &user_dict_path,
+            "東京タワー,カスタム名詞,トウキョウタワー\n",
+        )
+        .unwrap();
+        assert!(
+            load_verified_user_dictionary(
+                user_dict_path.to_str().unwrap(),
+                &Metadata::default(),
+                &verifier,
+            )
+            .is_err()
+        );
+    }
+}
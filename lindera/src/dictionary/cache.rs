@@ -0,0 +1,133 @@
+//! A process-wide cache of the loaded dictionaries, so that the tokenizers of several subsystems
+//! configured with the same dictionary share one copy of it.
+//!
+//! [`load_shared_dictionary`] returns the dictionary already loaded for a URI if a tokenizer still
+//! uses it, and loads it otherwise. The cache does not keep the dictionaries alive: a dictionary
+//! is dropped with the last tokenizer that uses it, and loaded again the next time it is needed.
+//! The segmenter configuration opts in with `shared_dictionary: true`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Arc, Mutex, Weak};
+
+use once_cell::sync::Lazy;
+
+use crate::LinderaResult;
+use crate::dictionary::{Dictionary, dictionary_dir, load_dictionary};
+
+static DICTIONARY_CACHE: Lazy<Mutex<HashMap<String, Weak<Dictionary>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The key of a dictionary URI in the cache: the canonical directory of a dictionary on the file
+/// system, so that the relative and absolute paths and the `file://` URI of a directory share an
+/// entry, and the URI with the scheme in lowercase otherwise.
+pub fn cache_key(uri: &str) -> LinderaResult<String> {
+    if let Some(path) = dictionary_dir(uri)? {
+        let path = fs::canonicalize(&path).unwrap_or(path);
+        return Ok(format!("file://{}", path.display()));
+    }
+    Ok(match uri.split_once(':') {
+        Some((scheme, rest)) => format!("{}:{rest}", scheme.to_ascii_lowercase()),
+        None => uri.to_string(),
+    })
+}
+
+/// Loads the dictionary at a path or URI like [`load_dictionary`], or returns the copy already
+/// loaded for it.
+///
+/// The cache is locked while a dictionary loads, so that the tokenizers built at the same time
+/// by several threads load it once.
+pub fn load_shared_dictionary(uri: &str) -> LinderaResult<Arc<Dictionary>> {
+    let key = cache_key(uri)?;
+    let mut cache = DICTIONARY_CACHE
+        .lock()
+        .unwrap_or_else(|err| err.into_inner());
+    if let Some(dictionary) = cache.get(&key).and_then(Weak::upgrade) {
+        return Ok(dictionary);
+    }
+
+    let dictionary = Arc::new(load_dictionary(uri)?);
+    // Drop the entries of the dictionaries no tokenizer uses anymore
+    cache.retain(|_, dictionary| dictionary.strong_count() > 0);
+    cache.insert(key, Arc::downgrade(&dictionary));
+    Ok(dictionary)
+}
+
+/// Forgets the dictionaries loaded so far, so that the next [`load_shared_dictionary`] loads
+/// them again, for instance after they were updated on disk. The tokenizers that use them keep
+/// their copy.
+pub fn clear_dictionary_cache() {
+    DICTIONARY_CACHE
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::dictionary::cache::{cache_key, clear_dictionary_cache, load_shared_dictionary};
+    use crate::test_util::TestDictionary;
+    use crate::tokenizer::TokenizerBuilder;
+
+    fn build_dictionary() -> tempfile::TempDir {
+        TestDictionary::new("東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー\n")
+            .build()
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let dict_dir = tempfile::tempdir().unwrap();
+        let path = dict_dir.path().to_str().unwrap();
+        assert_eq!(
+            cache_key(path).unwrap(),
+            cache_key(&format!(
+                "{path}/../{}",
+                dict_dir.path().file_name().unwrap().to_str().unwrap()
+            ))
+            .unwrap()
+        );
+        assert_eq!(
+            cache_key(path).unwrap(),
+            cache_key(&format!("file://{path}")).unwrap()
+        );
+        assert_eq!("embedded://ipadic", cache_key("EMBEDDED://ipadic").unwrap());
+    }
+
+    #[test]
+    fn test_load_shared_dictionary() {
+        let dict_dir = build_dictionary();
+        let path = dict_dir.path().to_str().unwrap();
+
+        let dictionary = load_shared_dictionary(path).unwrap();
+        let shared = load_shared_dictionary(&format!("file://{path}")).unwrap();
+        assert!(Arc::ptr_eq(&dictionary, &shared));
+
+        // The cache does not keep the dictionary alive
+        let weak = Arc::downgrade(&dictionary);
+        drop(dictionary);
+        drop(shared);
+        assert!(weak.upgrade().is_none());
+
+        let dictionary = load_shared_dictionary(path).unwrap();
+        clear_dictionary_cache();
+        let reloaded = load_shared_dictionary(path).unwrap();
+        assert!(!Arc::ptr_eq(&dictionary, &reloaded));
+
+        // The tokenizers configured with shared_dictionary share the cached copy
+        let mut builder = TokenizerBuilder::from_config(serde_json::json!({})).unwrap();
+        builder
+            .set_segmenter_dictionary(path)
+            .set_segmenter_keep_whitespace(true)
+            .set_segmenter_shared_dictionary(true);
+        let first = builder.build().unwrap();
+        let second = builder.build().unwrap();
+        assert!(Arc::ptr_eq(&reloaded, &first.segmenter.dictionary));
+        assert!(Arc::ptr_eq(&reloaded, &second.segmenter.dictionary));
+
+        builder.set_segmenter_shared_dictionary(false);
+        let unshared = builder.build().unwrap();
+        assert!(!Arc::ptr_eq(&reloaded, &unshared.segmenter.dictionary));
+    }
+}
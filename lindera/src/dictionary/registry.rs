@@ -0,0 +1,391 @@
+//! Registry of dictionary loaders keyed by URI scheme and embedded dictionary name.
+//!
+//! [`load_dictionary`](super::load_dictionary) and the `dictionary` field of tokenizer
+//! configuration files resolve URIs through the global registry, so a crate can add support
+//! for a new location (e.g. `https://` or `s3://`) by registering a loader:
+//!
+//! ```
+//! use std::path::PathBuf;
+//!
+//! use lindera::LinderaResult;
+//! use lindera::dictionary::registry::register_dictionary_loader;
+//! use lindera::dictionary::{Dictionary, load_dictionary};
+//! use lindera::error::LinderaErrorKind;
+//! use lindera_dictionary::loader::DictionaryLoader;
+//!
+//! /// Loads `mirror://<name>` from a directory where the dictionaries are synced to.
+//! struct MirrorLoader {
+//!     root: PathBuf,
+//! }
+//!
+//! impl DictionaryLoader for MirrorLoader {
+//!     fn load_from_uri(&self, uri: &str) -> LinderaResult<Dictionary> {
+//!         let name = uri.trim_start_matches("mirror://");
+//!         Dictionary::load_from_path(&self.root.join(name))
+//!     }
+//! }
+//!
+//! let root = std::env::temp_dir().join("lindera-mirror");
+//! register_dictionary_loader("mirror", MirrorLoader { root });
+//!
+//! // The URI goes to the loader, which does not find the dictionary in the mirror
+//! let err = load_dictionary("mirror://no-such-dictionary").err().unwrap();
+//! assert_eq!(LinderaErrorKind::Io, err.kind());
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use once_cell::sync::Lazy;
+use url::Url;
+
+use lindera_dictionary::loader::DictionaryLoader;
+
+use crate::LinderaResult;
+use crate::dictionary::search_path::{
+    DictionaryLocation, DictionaryOrigin, dictionary_search_paths, locate_path, not_found_message,
+};
+use crate::dictionary::{
+    Dictionary, DictionaryKind, dictionary_dir, file_uri_to_path, load_fs_dictionary,
+    resolve_embedded_loader,
+};
+use crate::error::LinderaErrorKind;
+
+/// Scheme of URIs that refer to embedded dictionaries by name (`embedded://ipadic`).
+pub const EMBEDDED_SCHEME: &str = "embedded";
+
+/// Scheme of URIs that refer to dictionary directories (`file:///path/to/dictionary`).
+pub const FILE_SCHEME: &str = "file";
+
+type SharedLoader = Arc<dyn DictionaryLoader + Send + Sync>;
+
+static GLOBAL_REGISTRY: Lazy<RwLock<DictionaryRegistry>> =
+    Lazy::new(|| RwLock::new(DictionaryRegistry::default()));
+
+/// Loads `file://` URIs.
+struct FileUriLoader;
+
+impl DictionaryLoader for FileUriLoader {
+    fn load_from_uri(&self, uri: &str) -> LinderaResult<Dictionary> {
+        let parsed_uri = Url::parse(uri).map_err(|err| {
+            LinderaErrorKind::Dictionary.with_error(anyhow::anyhow!("Invalid URI format: {err}"))
+        })?;
+        load_fs_dictionary(&file_uri_to_path(&parsed_uri)?)
+    }
+}
+
+/// What a dictionary URI resolved to.
+enum Resolved {
+    Embedded(SharedLoader),
+    Uri(SharedLoader, String),
+    Path(DictionaryLocation),
+}
+
+impl Resolved {
+    fn load(self) -> LinderaResult<Dictionary> {
+        match self {
+            Resolved::Embedded(loader) => loader
+                .load()
+                .map_err(|err| LinderaErrorKind::NotFound.with_error(err)),
+            Resolved::Uri(loader, uri) => loader.load_from_uri(&uri),
+            Resolved::Path(location) => {
+                let path = location.path.unwrap_or_default();
+                // A relative path that was not found in any of the search paths
+                if path.is_relative() && !path.exists() {
+                    return Err(LinderaErrorKind::NotFound.with_error(anyhow::anyhow!(
+                        not_found_message(&path.to_string_lossy(), &dictionary_search_paths())
+                    )));
+                }
+                load_fs_dictionary(&path)
+            }
+        }
+    }
+
+    fn location(&self, uri: &str) -> LinderaResult<DictionaryLocation> {
+        Ok(match self {
+            Resolved::Embedded(_) => DictionaryLocation {
+                origin: DictionaryOrigin::Embedded,
+                path: None,
+            },
+            Resolved::Uri(..) => match dictionary_dir(uri)? {
+                Some(path) => DictionaryLocation {
+                    origin: DictionaryOrigin::Given,
+                    path: Some(path),
+                },
+                None => DictionaryLocation {
+                    origin: DictionaryOrigin::Uri,
+                    path: None,
+                },
+            },
+            Resolved::Path(location) => location.clone(),
+        })
+    }
+}
+
+/// Maps URI schemes and embedded dictionary names to dictionary loaders.
+///
+/// A URI is resolved as follows:
+///
+/// - `embedded://<name>` (or `embedded:<name>`) calls [`DictionaryLoader::load`] on the
+///   loader registered for `<name>`.
+/// - `<scheme>://...` (or `<scheme>:...` for registered schemes) calls
+///   [`DictionaryLoader::load_from_uri`] with the whole URI on the loader registered for
+///   `<scheme>`.
+/// - Anything else is a path to a dictionary directory, looked up in the
+///   [search paths](crate::dictionary::search_path) if it is relative.
+pub struct DictionaryRegistry {
+    schemes: HashMap<String, SharedLoader>,
+    embedded: HashMap<String, SharedLoader>,
+}
+
+impl Default for DictionaryRegistry {
+    /// Creates a registry with the `file` scheme, the `s3` scheme with the `s3` feature, and the
+    /// embedded dictionaries enabled by features.
+    // `DictionaryKind` has no variants when no embedded dictionary is enabled
+    #[allow(unreachable_code)]
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register_scheme(FILE_SCHEME, FileUriLoader);
+        #[cfg(feature = "s3")]
+        registry.register_scheme(
+            "s3",
+            lindera_dictionary::loader::s3::S3DictionaryLoader::from_env(),
+        );
+        for kind in DictionaryKind::contained_variants() {
+            if let Ok(loader) = resolve_embedded_loader(kind.clone()) {
+                registry
+                    .embedded
+                    .insert(kind.as_str().to_string(), Arc::from(loader));
+            }
+        }
+        registry
+    }
+}
+
+impl DictionaryRegistry {
+    /// Creates an empty registry. Use [`DictionaryRegistry::default`] for the built-in loaders.
+    pub fn new() -> Self {
+        Self {
+            schemes: HashMap::new(),
+            embedded: HashMap::new(),
+        }
+    }
+
+    /// Registers the loader for URIs with the given scheme, replacing any previous one.
+    pub fn register_scheme<L>(&mut self, scheme: &str, loader: L)
+    where
+        L: DictionaryLoader + Send + Sync + 'static,
+    {
+        self.schemes
+            .insert(scheme.to_ascii_lowercase(), Arc::new(loader));
+    }
+
+    /// Registers the loader for `embedded://<name>`, replacing any previous one.
+    pub fn register_embedded<L>(&mut self, name: &str, loader: L)
+    where
+        L: DictionaryLoader + Send + Sync + 'static,
+    {
+        self.embedded.insert(name.to_string(), Arc::new(loader));
+    }
+
+    /// Registered URI schemes, sorted.
+    pub fn schemes(&self) -> Vec<&str> {
+        let mut schemes: Vec<&str> = self.schemes.keys().map(String::as_str).collect();
+        schemes.sort_unstable();
+        schemes
+    }
+
+    /// Registered embedded dictionary names, sorted.
+    pub fn embedded_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.embedded.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Loads the dictionary the URI refers to.
+    pub fn load(&self, uri: &str) -> LinderaResult<Dictionary> {
+        self.resolve(uri)?.load()
+    }
+
+    /// Tells where the dictionary the URI refers to is, without loading it.
+    pub fn locate(&self, uri: &str) -> LinderaResult<DictionaryLocation> {
+        self.resolve(uri)?.location(uri)
+    }
+
+    fn resolve(&self, uri: &str) -> LinderaResult<Resolved> {
+        // A single letter before the colon is a Windows drive, not a scheme
+        let scheme = match uri.split_once(':') {
+            Some((scheme, _)) if uri.contains("://") => scheme.to_ascii_lowercase(),
+            Some((scheme, _))
+                if scheme.len() > 1
+                    && (scheme.eq_ignore_ascii_case(EMBEDDED_SCHEME)
+                        || self.schemes.contains_key(&scheme.to_ascii_lowercase())) =>
+            {
+                scheme.to_ascii_lowercase()
+            }
+            _ => {
+                return Ok(Resolved::Path(locate_path(uri, &dictionary_search_paths())));
+            }
+        };
+
+        let parsed_uri = Url::parse(uri).map_err(|err| {
+            LinderaErrorKind::Dictionary.with_error(anyhow::anyhow!("Invalid URI format: {err}"))
+        })?;
+
+        if scheme == EMBEDDED_SCHEME {
+            let name = parsed_uri
+                .host_str()
+                .unwrap_or_else(|| parsed_uri.path().trim_matches('/'));
+            return match self.embedded.get(name) {
+                Some(loader) => Ok(Resolved::Embedded(loader.clone())),
+                None => Err(LinderaErrorKind::Dictionary.with_error(anyhow::anyhow!(
+                    "Invalid dictionary kind: {name} (available: {})",
+                    self.embedded_names().join(", ")
+                ))),
+            };
+        }
+
+        match self.schemes.get(&scheme) {
+            Some(loader) => Ok(Resolved::Uri(loader.clone(), uri.to_string())),
+            None => Err(LinderaErrorKind::Dictionary.with_error(anyhow::anyhow!(
+                "Invalid dictionary scheme: {scheme} (registered: {})",
+                self.schemes().join(", ")
+            ))),
+        }
+    }
+}
+
+/// Registers a loader for URIs with the given scheme in the global registry.
+pub fn register_dictionary_loader<L>(scheme: &str, loader: L)
+where
+    L: DictionaryLoader + Send + Sync + 'static,
+{
+    GLOBAL_REGISTRY
+        .write()
+        .unwrap_or_else(|err| err.into_inner())
+        .register_scheme(scheme, loader);
+}
+
+/// Registers a loader for `embedded://<name>` in the global registry.
+pub fn register_embedded_dictionary<L>(name: &str, loader: L)
+where
+    L: DictionaryLoader + Send + Sync + 'static,
+{
+    GLOBAL_REGISTRY
+        .write()
+        .unwrap_or_else(|err| err.into_inner())
+        .register_embedded(name, loader);
+}
+
+/// Loads the dictionary the URI refers to using the global registry.
+pub fn load_registered_dictionary(uri: &str) -> LinderaResult<Dictionary> {
+    // Release the lock before loading, so that slow loaders do not block registrations
+    let resolved = GLOBAL_REGISTRY
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .resolve(uri)?;
+    resolved.load()
+}
+
+/// Tells where the dictionary the URI refers to is, using the global registry, without loading
+/// it. A relative path is looked up in the [search paths](crate::dictionary::search_path).
+pub fn locate_dictionary(uri: &str) -> LinderaResult<DictionaryLocation> {
+    GLOBAL_REGISTRY
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .locate(uri)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FailingLoader(&'static str);
+
+    impl DictionaryLoader for FailingLoader {
+        fn load(&self) -> LinderaResult<Dictionary> {
+            Err(LinderaErrorKind::Io.with_error(anyhow::anyhow!("{}", self.0)))
+        }
+
+        fn load_from_uri(&self, uri: &str) -> LinderaResult<Dictionary> {
+            Err(LinderaErrorKind::Io.with_error(anyhow::anyhow!("{}: {uri}", self.0)))
+        }
+    }
+
+    #[test]
+    fn test_resolve_registered_scheme() {
+        let mut registry = DictionaryRegistry::new();
+        registry.register_scheme("S3", FailingLoader("s3 loader"));
+        assert_eq!(registry.schemes(), vec!["s3"]);
+
+        let err = registry.load("s3://bucket/lindera-ipadic").err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::Io);
+        assert!(
+            err.to_string()
+                .contains("s3 loader: s3://bucket/lindera-ipadic")
+        );
+
+        let err = registry.load("https://example.com/dict").err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::Dictionary);
+    }
+
+    #[test]
+    fn test_resolve_embedded_name() {
+        let mut registry = DictionaryRegistry::new();
+        registry.register_embedded("custom", FailingLoader("custom loader"));
+
+        for uri in ["embedded://custom", "embedded:custom"] {
+            let err = registry.load(uri).err().unwrap();
+            assert_eq!(err.kind(), LinderaErrorKind::NotFound);
+            assert!(err.to_string().contains("custom loader"));
+        }
+
+        let err = registry.load("embedded://unknown").err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::Dictionary);
+    }
+
+    #[test]
+    fn test_resolve_path() {
+        let registry = DictionaryRegistry::new();
+        assert!(matches!(
+            registry.resolve("/path/to/dictionary").ok(),
+            Some(Resolved::Path(_))
+        ));
+        assert!(matches!(
+            registry.resolve("C:\\dictionary").ok(),
+            Some(Resolved::Path(_))
+        ));
+    }
+
+    #[test]
+    fn test_locate() {
+        let mut registry = DictionaryRegistry::new();
+        registry.register_scheme("s3", FailingLoader("s3 loader"));
+        registry.register_embedded("custom", FailingLoader("custom loader"));
+
+        assert_eq!(
+            DictionaryOrigin::Embedded,
+            registry.locate("embedded://custom").unwrap().origin
+        );
+        assert_eq!(
+            DictionaryOrigin::Uri,
+            registry.locate("s3://bucket/dict").unwrap().origin
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            DictionaryLocation {
+                origin: DictionaryOrigin::Given,
+                path: Some(dir.path().to_path_buf()),
+            },
+            registry.locate(dir.path().to_str().unwrap()).unwrap()
+        );
+
+        let err = registry.load("lindera-missing-dic").err().unwrap();
+        assert_eq!(err.kind(), LinderaErrorKind::NotFound);
+        assert!(
+            err.to_string()
+                .contains("dictionary \"lindera-missing-dic\" was not found; searched:")
+        );
+    }
+}
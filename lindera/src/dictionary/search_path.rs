@@ -0,0 +1,286 @@
+//! Directories searched for dictionaries given by a relative path.
+//!
+//! A dictionary given by a path, such as `"dictionary": "lindera-ipadic"` in a tokenizer
+//! configuration or `--dict lindera-ipadic` on the command line, is looked up in this order:
+//!
+//! 1. The path as given: an absolute path, or a path relative to the working directory.
+//! 2. The directories set with [`set_dictionary_search_paths`].
+//! 3. The directories of the `LINDERA_DICTIONARY_PATH` environment variable, separated like
+//!    the ones of `PATH`.
+//! 4. `lindera/dictionaries` in the XDG data directories: `$XDG_DATA_HOME` (by default
+//!    `~/.local/share`), then each of `$XDG_DATA_DIRS` (by default `/usr/local/share` and
+//!    `/usr/share`).
+//!
+//! The first directory that exists is used. [`locate_dictionary`] tells where a path or URI
+//! resolves to, and the error of a dictionary that is not found lists the directories searched.
+//!
+//! [`locate_dictionary`]: crate::dictionary::registry::locate_dictionary
+
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+/// Environment variable with the directories searched for dictionaries.
+pub const DICTIONARY_PATH_VAR: &str = "LINDERA_DICTIONARY_PATH";
+
+/// Directory of the dictionaries under an XDG data directory.
+const XDG_SUBDIR: &str = "lindera/dictionaries";
+
+static SEARCH_PATHS: Lazy<RwLock<Vec<PathBuf>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Where a dictionary was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DictionaryOrigin {
+    /// The path as given (or the path of a `file://` URI).
+    Given,
+    /// A directory set with [`set_dictionary_search_paths`].
+    SearchPath,
+    /// A directory of the `LINDERA_DICTIONARY_PATH` environment variable.
+    EnvVar,
+    /// An XDG data directory.
+    XdgDataDir,
+    /// A dictionary embedded in the binary (`embedded://<name>`).
+    Embedded,
+    /// A URI loaded by the loader registered for its scheme (e.g. `s3://`).
+    Uri,
+}
+
+impl fmt::Display for DictionaryOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DictionaryOrigin::Given => "path as given",
+            DictionaryOrigin::SearchPath => "search path",
+            DictionaryOrigin::EnvVar => DICTIONARY_PATH_VAR,
+            DictionaryOrigin::XdgDataDir => "XDG data directory",
+            DictionaryOrigin::Embedded => "embedded",
+            DictionaryOrigin::Uri => "URI",
+        })
+    }
+}
+
+/// Where a dictionary path or URI resolves to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DictionaryLocation {
+    pub origin: DictionaryOrigin,
+    /// The directory of the dictionary, or `None` for embedded dictionaries and URIs of other
+    /// schemes.
+    pub path: Option<PathBuf>,
+}
+
+impl fmt::Display for DictionaryLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{} ({})", path.display(), self.origin),
+            None => write!(f, "{}", self.origin),
+        }
+    }
+}
+
+/// Sets the directories searched for dictionaries given by a relative path, before the ones of
+/// `LINDERA_DICTIONARY_PATH` and the XDG data directories. Replaces the directories set before.
+pub fn set_dictionary_search_paths<I, P>(paths: I)
+where
+    I: IntoIterator<Item = P>,
+    P: Into<PathBuf>,
+{
+    *SEARCH_PATHS.write().unwrap_or_else(|err| err.into_inner()) =
+        paths.into_iter().map(Into::into).collect();
+}
+
+/// Returns the directories searched for dictionaries given by a relative path, in order, along
+/// with where each comes from.
+pub fn dictionary_search_paths() -> Vec<(PathBuf, DictionaryOrigin)> {
+    let explicit = SEARCH_PATHS
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone();
+    search_paths(
+        &explicit,
+        env::var_os(DICTIONARY_PATH_VAR),
+        env::var_os("XDG_DATA_HOME"),
+        env::var_os("HOME"),
+        env::var_os("XDG_DATA_DIRS"),
+    )
+}
+
+/// The search directories, from the directories set in code and the values of the environment
+/// variables.
+fn search_paths(
+    explicit: &[PathBuf],
+    dictionary_path: Option<OsString>,
+    xdg_data_home: Option<OsString>,
+    home: Option<OsString>,
+    xdg_data_dirs: Option<OsString>,
+) -> Vec<(PathBuf, DictionaryOrigin)> {
+    let mut paths = explicit
+        .iter()
+        .map(|path| (path.clone(), DictionaryOrigin::SearchPath))
+        .collect::<Vec<_>>();
+    if let Some(dictionary_path) = dictionary_path {
+        paths.extend(
+            env::split_paths(&dictionary_path)
+                .filter(|path| !path.as_os_str().is_empty())
+                .map(|path| (path, DictionaryOrigin::EnvVar)),
+        );
+    }
+
+    // Relative paths in the XDG variables are invalid and ignored, as the specification says
+    let absolute = |path: &PathBuf| path.is_absolute();
+    let data_home = xdg_data_home
+        .map(PathBuf::from)
+        .filter(absolute)
+        .or_else(|| home.map(|home| Path::new(&home).join(".local/share")));
+    let data_dirs = match xdg_data_dirs.filter(|dirs| !dirs.is_empty()) {
+        Some(dirs) => env::split_paths(&dirs).filter(absolute).collect(),
+        None if cfg!(unix) => vec![
+            PathBuf::from("/usr/local/share"),
+            PathBuf::from("/usr/share"),
+        ],
+        None => Vec::new(),
+    };
+    paths.extend(
+        data_home
+            .into_iter()
+            .chain(data_dirs)
+            .map(|dir| (dir.join(XDG_SUBDIR), DictionaryOrigin::XdgDataDir)),
+    );
+    paths
+}
+
+/// Looks up a dictionary given by a path in the search directories.
+///
+/// Returns the path as given if it exists, is absolute, or is not found in any search
+/// directory, in which case loading it fails.
+pub(crate) fn locate_path(
+    path: &str,
+    search_paths: &[(PathBuf, DictionaryOrigin)],
+) -> DictionaryLocation {
+    let given = PathBuf::from(path);
+    if given.is_absolute() || given.exists() {
+        return DictionaryLocation {
+            origin: DictionaryOrigin::Given,
+            path: Some(given),
+        };
+    }
+
+    search_paths
+        .iter()
+        .map(|(dir, origin)| (dir.join(&given), *origin))
+        .find(|(candidate, _)| candidate.is_dir())
+        .map_or(
+            DictionaryLocation {
+                origin: DictionaryOrigin::Given,
+                path: Some(given),
+            },
+            |(candidate, origin)| DictionaryLocation {
+                origin,
+                path: Some(candidate),
+            },
+        )
+}
+
+/// Describes the places searched for a dictionary that was not found, for errors.
+pub(crate) fn not_found_message(
+    path: &str,
+    search_paths: &[(PathBuf, DictionaryOrigin)],
+) -> String {
+    let mut message = format!("dictionary {path:?} was not found; searched: {path}");
+    for (dir, origin) in search_paths {
+        message.push_str(&format!(", {} ({origin})", dir.join(path).display()));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn test_search_paths_order() {
+        let dirs = search_paths(
+            &[PathBuf::from("/opt/dictionaries")],
+            Some(env::join_paths(["/srv/a", "/srv/b"]).unwrap()),
+            Some(OsString::from("/data")),
+            Some(OsString::from("/home/user")),
+            Some(env::join_paths(["/share", "relative"]).unwrap()),
+        );
+        assert_eq!(
+            vec![
+                (
+                    PathBuf::from("/opt/dictionaries"),
+                    DictionaryOrigin::SearchPath
+                ),
+                (PathBuf::from("/srv/a"), DictionaryOrigin::EnvVar),
+                (PathBuf::from("/srv/b"), DictionaryOrigin::EnvVar),
+                (
+                    PathBuf::from("/data/lindera/dictionaries"),
+                    DictionaryOrigin::XdgDataDir
+                ),
+                (
+                    PathBuf::from("/share/lindera/dictionaries"),
+                    DictionaryOrigin::XdgDataDir
+                ),
+            ],
+            dirs
+        );
+
+        // XDG_DATA_HOME defaults to ~/.local/share
+        let dirs = search_paths(&[], None, None, Some(OsString::from("/home/user")), None);
+        assert_eq!(
+            (
+                PathBuf::from("/home/user/.local/share/lindera/dictionaries"),
+                DictionaryOrigin::XdgDataDir
+            ),
+            dirs[0]
+        );
+    }
+
+    #[test]
+    fn test_locate_path() {
+        let env_dir = tempfile::tempdir().unwrap();
+        let xdg_dir = tempfile::tempdir().unwrap();
+        fs::create_dir(env_dir.path().join("lindera-test-dic")).unwrap();
+        fs::create_dir(xdg_dir.path().join("lindera-test-dic")).unwrap();
+        fs::create_dir(xdg_dir.path().join("lindera-other-dic")).unwrap();
+        let dirs = vec![
+            (env_dir.path().to_path_buf(), DictionaryOrigin::EnvVar),
+            (xdg_dir.path().to_path_buf(), DictionaryOrigin::XdgDataDir),
+        ];
+
+        // The first directory that has the dictionary wins
+        assert_eq!(
+            DictionaryLocation {
+                origin: DictionaryOrigin::EnvVar,
+                path: Some(env_dir.path().join("lindera-test-dic")),
+            },
+            locate_path("lindera-test-dic", &dirs)
+        );
+        assert_eq!(
+            DictionaryOrigin::XdgDataDir,
+            locate_path("lindera-other-dic", &dirs).origin
+        );
+
+        // A path that is not found anywhere is kept as given
+        assert_eq!(
+            DictionaryLocation {
+                origin: DictionaryOrigin::Given,
+                path: Some(PathBuf::from("lindera-missing-dic")),
+            },
+            locate_path("lindera-missing-dic", &dirs)
+        );
+        let message = not_found_message("lindera-missing-dic", &dirs);
+        assert!(message.contains(&format!(
+            "{} ({DICTIONARY_PATH_VAR})",
+            env_dir.path().join("lindera-missing-dic").display()
+        )));
+    }
+}
@@ -1,8 +1,15 @@
 pub mod character_filter;
+pub mod chunker;
+#[cfg(feature = "dependency")]
+pub mod dependency;
 pub mod dictionary;
 pub mod error;
 pub mod mode;
+pub mod readability;
+pub mod segmentation;
 pub mod segmenter;
+#[cfg(test)]
+mod test_util;
 pub mod token;
 pub mod token_filter;
 pub mod tokenizer;
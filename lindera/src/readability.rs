@@ -0,0 +1,305 @@
+//! Readability features of Japanese texts, read from the tokens: the length of the sentences, the
+//! proportions of the parts-of-speech, of the word origins (語種) and of the character kinds, and
+//! the score of jReadability (Lee and Hasebe), a regression of the features on the levels of
+//! learners of Japanese.
+//!
+//! The word origins are read from the `word_type` field of UniDic (`和`, `漢`, `外`, `混`...);
+//! with the other dictionaries they are not counted and the jReadability score is `None`.
+
+use serde::Serialize;
+
+use crate::token::Token;
+
+/// The readability features of a text. The proportions are percentages of the words, the tokens
+/// that are not punctuation or whitespace.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Readability {
+    pub sentences: usize,
+    pub words: usize,
+    /// The number of characters of the words.
+    pub characters: usize,
+    /// The number of words of each origin, if the dictionary has the origins.
+    pub origins: Option<Origins>,
+    pub nouns: usize,
+    pub verbs: usize,
+    pub adjectives: usize,
+    pub adverbs: usize,
+    pub particles: usize,
+    pub auxiliaries: usize,
+    pub kanji: usize,
+    pub hiragana: usize,
+    pub katakana: usize,
+}
+
+/// The number of words of each origin (語種).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Origins {
+    /// 和語, native Japanese words.
+    pub wago: usize,
+    /// 漢語, Sino-Japanese words.
+    pub kango: usize,
+    /// 外来語, loanwords.
+    pub gairaigo: usize,
+    /// 混種語, words mixing origins, proper nouns and the other words.
+    pub other: usize,
+}
+
+fn percentage(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 * 100.0 / total as f64
+    }
+}
+
+impl Readability {
+    /// The mean number of words of the sentences.
+    pub fn mean_sentence_length(&self) -> f64 {
+        if self.sentences == 0 {
+            0.0
+        } else {
+            self.words as f64 / self.sentences as f64
+        }
+    }
+
+    /// The mean number of characters of the words.
+    pub fn mean_word_length(&self) -> f64 {
+        if self.words == 0 {
+            0.0
+        } else {
+            self.characters as f64 / self.words as f64
+        }
+    }
+
+    /// The percentage of the words that are 和語, `None` without the word origins.
+    pub fn wago_ratio(&self) -> Option<f64> {
+        self.origins
+            .map(|origins| percentage(origins.wago, self.words))
+    }
+
+    pub fn kango_ratio(&self) -> Option<f64> {
+        self.origins
+            .map(|origins| percentage(origins.kango, self.words))
+    }
+
+    pub fn gairaigo_ratio(&self) -> Option<f64> {
+        self.origins
+            .map(|origins| percentage(origins.gairaigo, self.words))
+    }
+
+    /// The percentage of the words that are nouns.
+    pub fn noun_ratio(&self) -> f64 {
+        percentage(self.nouns, self.words)
+    }
+
+    pub fn verb_ratio(&self) -> f64 {
+        percentage(self.verbs, self.words)
+    }
+
+    pub fn particle_ratio(&self) -> f64 {
+        percentage(self.particles, self.words)
+    }
+
+    pub fn auxiliary_ratio(&self) -> f64 {
+        percentage(self.auxiliaries, self.words)
+    }
+
+    /// The percentage of the characters of the words that are kanji.
+    pub fn kanji_ratio(&self) -> f64 {
+        percentage(self.kanji, self.characters)
+    }
+
+    pub fn hiragana_ratio(&self) -> f64 {
+        percentage(self.hiragana, self.characters)
+    }
+
+    pub fn katakana_ratio(&self) -> f64 {
+        percentage(self.katakana, self.characters)
+    }
+
+    /// The jReadability score, higher for easier texts: about 0.5 to 1.4 for upper advanced
+    /// learners and 5.5 to 6.4 for lower elementary learners.
+    ///
+    /// Returns `None` without the word origins, or for a text without words.
+    pub fn jreadability(&self) -> Option<f64> {
+        if self.words == 0 {
+            return None;
+        }
+        Some(
+            11.724
+                + self.mean_sentence_length() * -0.056
+                + self.kango_ratio()? * -0.126
+                + self.wago_ratio()? * -0.042
+                + self.verb_ratio() * -0.145
+                + self.auxiliary_ratio() * -0.044,
+        )
+    }
+}
+
+/// Computes the readability features of the tokens of a text. The sentences end with a full stop
+/// (`。`, `！`, `？`), and a text not ending with one counts a last sentence.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # #[cfg(feature = "embed-ipadic")]
+/// # {
+/// use lindera::dictionary::load_dictionary;
+/// use lindera::mode::Mode;
+/// use lindera::readability::analyze;
+/// use lindera::segmenter::Segmenter;
+/// use lindera::tokenizer::Tokenizer;
+///
+/// let dictionary = load_dictionary("embedded://ipadic")?;
+/// let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+///
+/// let mut tokens = tokenizer.tokenize("今日は晴れです。明日は雨です。")?;
+/// let readability = analyze(&mut tokens);
+/// assert_eq!(2, readability.sentences);
+/// println!("{:.1} words per sentence", readability.mean_sentence_length());
+/// if let Some(score) = readability.jreadability() {
+///     println!("jReadability {score:.2}");
+/// }
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub fn analyze(tokens: &mut [Token]) -> Readability {
+    let mut readability = Readability::default();
+    // Whether some words follow the last full stop
+    let mut open_sentence = false;
+
+    for token in tokens.iter_mut() {
+        let surface = token.surface.to_string();
+        let major_pos = token.get_detail(0).unwrap_or_default().to_string();
+        let subcategory = token.get_detail(1).unwrap_or_default().to_string();
+        let punctuation = matches!(major_pos.as_str(), "記号" | "補助記号" | "空白")
+            || surface.chars().all(char::is_whitespace);
+        if punctuation {
+            let full_stop = subcategory == "句点"
+                || matches!(surface.as_str(), "。" | "！" | "？" | "!" | "?" | "．");
+            if full_stop && open_sentence {
+                readability.sentences += 1;
+                open_sentence = false;
+            }
+            continue;
+        }
+        open_sentence = true;
+
+        readability.words += 1;
+        match major_pos.as_str() {
+            "名詞" | "代名詞" => readability.nouns += 1,
+            "動詞" => readability.verbs += 1,
+            "形容詞" | "形状詞" | "形容動詞" => readability.adjectives += 1,
+            "副詞" => readability.adverbs += 1,
+            "助詞" => readability.particles += 1,
+            "助動詞" => readability.auxiliaries += 1,
+            _ => {}
+        }
+        if let Some(word_type) = token.get("word_type") {
+            let origins = readability.origins.get_or_insert_with(Origins::default);
+            match word_type {
+                "和" => origins.wago += 1,
+                "漢" => origins.kango += 1,
+                "外" => origins.gairaigo += 1,
+                _ => origins.other += 1,
+            }
+        }
+        for c in surface.chars() {
+            readability.characters += 1;
+            match c as u32 {
+                0x3041..=0x309f => readability.hiragana += 1,
+                0x30a0..=0x30ff | 0x31f0..=0x31ff | 0xff66..=0xff9f => readability.katakana += 1,
+                0x3400..=0x4dbf | 0x4e00..=0x9fff | 0xf900..=0xfaff | 0x3005 => {
+                    readability.kanji += 1
+                }
+                _ => {}
+            }
+        }
+    }
+    if open_sentence {
+        readability.sentences += 1;
+    }
+    readability
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use lindera_dictionary::dictionary::schema::Schema;
+
+    use super::*;
+    use crate::dictionary::Metadata;
+    use crate::mode::Mode;
+    use crate::segmenter::Segmenter;
+    use crate::test_util::TestDictionary;
+
+    #[test]
+    fn test_analyze() {
+        let metadata = Metadata {
+            dictionary_schema: Schema::new(
+                [
+                    "surface",
+                    "left_context_id",
+                    "right_context_id",
+                    "cost",
+                    "part_of_speech",
+                    "part_of_speech_subcategory_1",
+                    "word_type",
+                ]
+                .map(str::to_string)
+                .to_vec(),
+            ),
+            ..Metadata::default()
+        };
+        let (_dict_dir, dictionary) = TestDictionary::new(
+            "\
+私,0,0,100,代名詞,*,和
+は,0,0,100,助詞,係助詞,和
+学生,0,0,100,名詞,普通名詞,漢
+です,0,0,100,助動詞,*,和
+。,0,0,100,補助記号,句点,記号
+本,0,0,100,名詞,普通名詞,漢
+を,0,0,100,助詞,格助詞,和
+読む,0,0,100,動詞,一般,和
+",
+        )
+        .unk_def("DEFAULT,0,0,5000,名詞,普通名詞,*\nKANJI,0,0,5000,名詞,普通名詞,*\n")
+        .metadata(metadata)
+        .load();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let mut tokens = segmenter
+            .segment(Cow::Borrowed("私は学生です。本を読む"))
+            .unwrap();
+        let readability = analyze(&mut tokens);
+        assert_eq!(2, readability.sentences);
+        assert_eq!(7, readability.words);
+        assert_eq!(3.5, readability.mean_sentence_length());
+        assert_eq!(
+            Some(Origins {
+                wago: 5,
+                kango: 2,
+                gairaigo: 0,
+                other: 0,
+            }),
+            readability.origins
+        );
+        assert_eq!(
+            (2, 1, 1),
+            (
+                readability.particles,
+                readability.verbs,
+                readability.auxiliaries
+            )
+        );
+        // 私 学 生 本 読 of the 10 characters of 私は学生です本を読む
+        assert_eq!(10, readability.characters);
+        assert_eq!(50.0, readability.kanji_ratio());
+        // 11.724 - 3.5 * 0.056 - 2 / 7 * 12.6 - 5 / 7 * 4.2 - 1 / 7 * 14.5 - 1 / 7 * 4.4
+        assert!((readability.jreadability().unwrap() - 2.228).abs() < 1e-9);
+
+        assert_eq!(None, analyze(&mut []).jreadability());
+    }
+}
@@ -0,0 +1,215 @@
+//! A minimal segmentation interface for embedding Lindera in other analyzers.
+//!
+//! Libraries that only need word boundaries (e.g. the segmenters of a search engine) can depend on
+//! the [`Segmenter`] trait rather than on the tokenizer types: it returns the byte ranges of the
+//! words of a text, with the kind of each, and is implemented by [`Tokenizer`] and by the
+//! segmenter of the [`segmenter`](crate::segmenter) module.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::LinderaResult;
+use crate::token::Token;
+use crate::tokenizer::Tokenizer;
+
+/// The kind of a segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenKind {
+    /// A word of the dictionary.
+    Word,
+    /// A word not found in the dictionary.
+    Unknown,
+    /// Characters that are neither letters, digits nor whitespace.
+    Punctuation,
+    Whitespace,
+}
+
+impl TokenKind {
+    fn of(text: &str, token: &Token) -> Self {
+        if !text.is_empty() && text.chars().all(char::is_whitespace) {
+            Self::Whitespace
+        } else if !text.is_empty()
+            && text
+                .chars()
+                .all(|c| !c.is_alphanumeric() && !c.is_whitespace())
+        {
+            Self::Punctuation
+        } else if token.word_id.is_unknown() {
+            Self::Unknown
+        } else {
+            Self::Word
+        }
+    }
+}
+
+/// A byte range of the segmented text with its kind.
+pub type Segment = (Range<usize>, TokenKind);
+
+/// The iterator over the segments of a text, in the order of the text.
+#[derive(Debug, Clone)]
+pub struct Segments(std::vec::IntoIter<Segment>);
+
+impl Iterator for Segments {
+    type Item = Segment;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Segments {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+impl ExactSizeIterator for Segments {}
+
+/// Splits texts into segments.
+///
+/// The ranges are byte ranges of `text` on character boundaries, so `&text[range]` is the text of
+/// a segment.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # #[cfg(feature = "embed-ipadic")]
+/// # {
+/// use lindera::segmentation::{Segmenter, TokenKind};
+///
+/// fn words<'t>(segmenter: &dyn Segmenter, text: &'t str) -> Vec<&'t str> {
+///     segmenter
+///         .segments(text)
+///         .unwrap()
+///         .filter(|(_, kind)| matches!(kind, TokenKind::Word | TokenKind::Unknown))
+///         .map(|(range, _)| &text[range])
+///         .collect()
+/// }
+///
+/// # use lindera::dictionary::load_dictionary;
+/// # use lindera::mode::Mode;
+/// # use lindera::tokenizer::Tokenizer;
+/// # let dictionary = load_dictionary("embedded://ipadic")?;
+/// # let tokenizer = Tokenizer::new(lindera::segmenter::Segmenter::new(Mode::Normal, dictionary, None));
+/// assert_eq!(
+///     vec!["関西国際空港", "限定", "トートバッグ"],
+///     words(&tokenizer, "関西国際空港限定トートバッグ")
+/// );
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub trait Segmenter {
+    fn segments(&self, text: &str) -> LinderaResult<Segments>;
+}
+
+impl Segmenter for Tokenizer {
+    /// The ranges are the spans of [`Tokenizer::highlight`], ranges of the text before the
+    /// character filters.
+    fn segments(&self, text: &str) -> LinderaResult<Segments> {
+        let segments = self
+            .highlight(text)?
+            .into_iter()
+            .map(|highlight| {
+                let kind = TokenKind::of(&text[highlight.span.clone()], &highlight.token);
+                (highlight.span, kind)
+            })
+            .collect::<Vec<_>>();
+        Ok(Segments(segments.into_iter()))
+    }
+}
+
+impl Segmenter for crate::segmenter::Segmenter {
+    fn segments(&self, text: &str) -> LinderaResult<Segments> {
+        let segments = self
+            .segment(Cow::Borrowed(text))?
+            .iter()
+            .map(|token| {
+                let range = token.byte_start..token.byte_end;
+                (range.clone(), TokenKind::of(&text[range], token))
+            })
+            .collect::<Vec<_>>();
+        Ok(Segments(segments.into_iter()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::load_dictionary;
+    use crate::mode::Mode;
+    use crate::test_util::TestDictionary;
+    use crate::tokenizer::TokenizerBuilder;
+
+    #[test]
+    fn test_segments() {
+        let dict_dir = TestDictionary::new(
+            "\
+東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー
+に,0,0,100,助詞,格助詞,一般,*,*,*,に,ニ,ニ
+。,0,0,100,記号,句点,*,*,*,*,。,。,。
+",
+        )
+        .char_def("DEFAULT 0 1 0\nSPACE 0 1 0\nKANJI 0 0 2\n0x0020 SPACE\n0x4E00..0x9FAF KANJI\n")
+        .unk_def(
+            "\
+DEFAULT,0,0,5000,名詞,一般,*,*,*,*,*,*,*
+SPACE,0,0,5000,記号,空白,*,*,*,*,*,*,*
+KANJI,0,0,5000,名詞,一般,*,*,*,*,*,*,*
+",
+        )
+        .build();
+        let dict_path = dict_dir.path().to_str().unwrap();
+
+        let mut builder = TokenizerBuilder::from_config(serde_json::json!({
+            "character_filters": [
+                { "kind": "unicode_normalize", "args": { "kind": "nfkc" } }
+            ]
+        }))
+        .unwrap();
+        builder
+            .set_segmenter_dictionary(dict_path)
+            .set_segmenter_keep_whitespace(true);
+        let tokenizer = builder.build().unwrap();
+
+        // The ranges are of the text before NFKC
+        let text = "東京に　鯖。";
+        let segments = Segmenter::segments(&tokenizer, text)
+            .unwrap()
+            .map(|(range, kind)| (&text[range], kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("東京", TokenKind::Word),
+                ("に", TokenKind::Word),
+                ("　", TokenKind::Whitespace),
+                ("鯖", TokenKind::Unknown),
+                ("。", TokenKind::Punctuation),
+            ],
+            segments
+        );
+
+        let segmenter = crate::segmenter::Segmenter::new(
+            Mode::Normal,
+            load_dictionary(dict_path).unwrap(),
+            None,
+        );
+        let segmenter: &dyn Segmenter = &segmenter;
+        assert_eq!(
+            vec![
+                (0..6, TokenKind::Word),
+                (6..9, TokenKind::Word),
+                (9..12, TokenKind::Unknown),
+            ],
+            segmenter.segments("東京に鯖").unwrap().collect::<Vec<_>>()
+        );
+    }
+}
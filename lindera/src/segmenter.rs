@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use lindera_dictionary::mode::Mode;
 
@@ -9,9 +10,13 @@ use lindera_dictionary::viterbi::Lattice;
 use serde_json::Value;
 
 use crate::LinderaResult;
-use crate::dictionary::{load_dictionary, load_user_dictionary};
+use crate::dictionary::cache::load_shared_dictionary;
+use crate::dictionary::{
+    OkuriganaVariants, build_okurigana_variants, dictionary_dir, load_dictionary,
+    load_okurigana_variants, load_user_dictionary,
+};
 use crate::error::LinderaErrorKind;
-use crate::token::Token;
+use crate::token::{Token, TokenCost};
 
 pub type SegmenterConfig = Value;
 
@@ -24,7 +29,9 @@ pub struct Segmenter {
 
     /// The dictionary used for segmenting text. This dictionary contains the necessary
     /// data structures and algorithms to perform morphological analysis and tokenization.
-    pub dictionary: Dictionary,
+    /// It is shared by the clones of the segmenter, and can be replaced with
+    /// [`Segmenter::set_dictionary`].
+    pub dictionary: Arc<Dictionary>,
 
     /// An optional user-defined dictionary that can be used to customize the segmentation process.
     /// If provided, this dictionary will be used in addition to the default dictionary to improve
@@ -37,6 +44,13 @@ pub struct Segmenter {
     /// When true, whitespace tokens are included in the output.
     pub keep_whitespace: bool,
 
+    /// An optional okurigana variant table of the dictionary.
+    ///
+    /// If provided, the spellings of the words of the dictionary with some okurigana left out
+    /// (`受付け` for `受け付け`) are looked up as well, and analyzed as the words they are spelled
+    /// from.
+    pub okurigana_variants: Option<OkuriganaVariants>,
+
     /// The category ID for space characters, used when keep_whitespace is false.
     space_category_id: Option<CategoryId>,
 }
@@ -47,7 +61,7 @@ impl Segmenter {
     /// # Arguments
     ///
     /// * `mode` - The `Mode` in which the instance will operate. This typically defines how aggressively the text is segmented or processed.
-    /// * `dictionary` - A `Dictionary` object that provides the core data and rules for processing text.
+    /// * `dictionary` - A `Dictionary` object, or an `Arc<Dictionary>` shared with other segmenters, that provides the core data and rules for processing text.
     /// * `user_dictionary` - An optional `UserDictionary` that allows for additional, user-defined tokens or rules to be used in conjunction with the main dictionary.
     ///
     /// # Returns
@@ -61,9 +75,10 @@ impl Segmenter {
     /// - `user_dictionary`: This is optional. If provided, it allows the user to extend or override the rules of the main dictionary with custom tokens.
     pub fn new(
         mode: Mode,
-        dictionary: Dictionary,
+        dictionary: impl Into<Arc<Dictionary>>,
         user_dictionary: Option<UserDictionary>,
     ) -> Self {
+        let dictionary = dictionary.into();
         // Get SPACE category ID for MeCab compatibility (ignore whitespace by default)
         let space_category_id = dictionary.character_definition.category_id_by_name("SPACE");
 
@@ -72,6 +87,7 @@ impl Segmenter {
             dictionary,
             user_dictionary,
             keep_whitespace: false, // Default: ignore whitespace for MeCab compatibility
+            okurigana_variants: None,
             space_category_id,
         }
     }
@@ -107,6 +123,46 @@ impl Segmenter {
         self
     }
 
+    /// Builder method to look up the okurigana variants of the words as well.
+    ///
+    /// The table is written to `dict.okurigana` by building the dictionary with
+    /// `okurigana_variants` enabled in its metadata, and loaded with
+    /// [`load_okurigana_variants`](crate::dictionary::load_okurigana_variants).
+    ///
+    /// # Arguments
+    ///
+    /// * `okurigana_variants` - The okurigana variant table of the dictionary.
+    pub fn okurigana_variants(mut self, okurigana_variants: OkuriganaVariants) -> Self {
+        self.okurigana_variants = Some(okurigana_variants);
+        self
+    }
+
+    /// Replaces the dictionary, keeping the mode, the user dictionary and the other settings.
+    ///
+    /// The user dictionary is kept, so every word of it must have context ids within the
+    /// connection cost matrix of the new dictionary; otherwise an error is returned and the
+    /// segmenter keeps its dictionary. If okurigana variants are looked up, the table is rebuilt
+    /// from the words of the new dictionary.
+    pub fn set_dictionary(
+        &mut self,
+        dictionary: impl Into<Arc<Dictionary>>,
+    ) -> LinderaResult<&mut Self> {
+        let dictionary = dictionary.into();
+        if let Some(user_dictionary) = &self.user_dictionary {
+            check_context_ids(user_dictionary, &dictionary)?;
+        }
+        // The table refers to the words of the old dictionary
+        let okurigana_variants = match self.okurigana_variants {
+            Some(_) => Some(build_okurigana_variants(&dictionary)?),
+            None => None,
+        };
+
+        self.space_category_id = dictionary.character_definition.category_id_by_name("SPACE");
+        self.dictionary = dictionary;
+        self.okurigana_variants = okurigana_variants;
+        Ok(self)
+    }
+
     /// A struct representing a segmenter for tokenizing text.
     ///
     /// The `Segmenter` struct provides methods for creating a segmenter from a configuration,
@@ -122,30 +178,92 @@ impl Segmenter {
     ///
     /// Methods that return `LinderaResult` may produce errors related to dictionary loading,
     /// user dictionary loading, or tokenization process.
-    pub fn from_config(config: &SegmenterConfig) -> LinderaResult<Self> {
-        // Load the dictionary from the config
-        let dictionary = load_dictionary(
-            config
-                .get("dictionary")
-                .and_then(Value::as_str)
-                .ok_or_else(|| {
-                    LinderaErrorKind::Parse
-                        .with_error(anyhow::anyhow!("dictionary field is missing"))
-                })?,
-        )?;
+    /// Loads the dictionary and the user dictionary of a config with `trusted_keys`, a list of
+    /// hex-encoded ed25519 public keys.
+    #[cfg(feature = "signature")]
+    fn load_verified_dictionaries(
+        dictionary_uri: &str,
+        user_dictionary_uri: Option<&str>,
+        trusted_keys: &Value,
+    ) -> LinderaResult<(Dictionary, Option<UserDictionary>)> {
+        use crate::dictionary::signature::SignatureVerifier;
+        use crate::dictionary::{load_verified_dictionary, load_verified_user_dictionary};
+
+        let trusted_keys = trusted_keys
+            .as_array()
+            .and_then(|keys| keys.iter().map(Value::as_str).collect::<Option<Vec<_>>>())
+            .ok_or_else(|| {
+                LinderaErrorKind::Parse.with_error(anyhow::anyhow!(
+                    "trusted_keys must be an array of hex-encoded public keys"
+                ))
+            })?;
+        let verifier = SignatureVerifier::from_hex_keys(&trusted_keys)?;
+
+        let dictionary = load_verified_dictionary(dictionary_uri, &verifier)?;
+        let user_dictionary = match user_dictionary_uri {
+            Some(uri) => Some(load_verified_user_dictionary(
+                uri,
+                &dictionary.metadata,
+                &verifier,
+            )?),
+            None => None,
+        };
+        Ok((dictionary, user_dictionary))
+    }
 
-        // Get metadata from the dictionary
-        let metadata = &dictionary.metadata;
+    /// Without the `signature` feature, signatures can not be checked, so a config with
+    /// `trusted_keys` is refused rather than loaded unchecked.
+    #[cfg(not(feature = "signature"))]
+    fn load_verified_dictionaries(
+        _dictionary_uri: &str,
+        _user_dictionary_uri: Option<&str>,
+        _trusted_keys: &Value,
+    ) -> LinderaResult<(Dictionary, Option<UserDictionary>)> {
+        Err(
+            LinderaErrorKind::FeatureDisabled.with_error(anyhow::anyhow!(
+                "trusted_keys requires lindera to be built with the signature feature"
+            )),
+        )
+    }
 
-        // Load the user dictionary from the config
-        let user_dictionary_uri = config
-            .get("user_dictionary")
+    pub fn from_config(config: &SegmenterConfig) -> LinderaResult<Self> {
+        // Load the dictionary from the config
+        let dictionary_uri = config
+            .get("dictionary")
             .and_then(Value::as_str)
-            .map(String::from);
-
-        let user_dictionary = match user_dictionary_uri {
-            Some(uri) => Some(load_user_dictionary(&uri, metadata)?),
-            None => None,
+            .ok_or_else(|| {
+                LinderaErrorKind::Parse.with_error(anyhow::anyhow!("dictionary field is missing"))
+            })?;
+        let user_dictionary_uri = config.get("user_dictionary").and_then(Value::as_str);
+
+        // Share the dictionary with the other segmenters configured with it if enabled
+        let shared_dictionary = config
+            .get("shared_dictionary")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+        // Refuse the dictionaries not signed by a trusted key if keys are given
+        let (dictionary, user_dictionary) = match config.get("trusted_keys") {
+            Some(trusted_keys) => {
+                let (dictionary, user_dictionary) = Self::load_verified_dictionaries(
+                    dictionary_uri,
+                    user_dictionary_uri,
+                    trusted_keys,
+                )?;
+                (Arc::new(dictionary), user_dictionary)
+            }
+            None => {
+                let dictionary = if shared_dictionary {
+                    load_shared_dictionary(dictionary_uri)?
+                } else {
+                    Arc::new(load_dictionary(dictionary_uri)?)
+                };
+                let user_dictionary = match user_dictionary_uri {
+                    Some(uri) => Some(load_user_dictionary(uri, &dictionary.metadata)?),
+                    None => None,
+                };
+                (dictionary, user_dictionary)
+            }
         };
 
         // Load the mode from the config
@@ -194,11 +312,28 @@ impl Segmenter {
             None
         };
 
+        // Load the okurigana variant table next to the dictionary if enabled
+        let okurigana_variants = if config
+            .get("okurigana_variants")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+        {
+            let dictionary_dir = dictionary_dir(dictionary_uri)?.ok_or_else(|| {
+                LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                    "okurigana variants need a dictionary directory: {dictionary_uri}"
+                ))
+            })?;
+            Some(load_okurigana_variants(&dictionary_dir)?)
+        } else {
+            None
+        };
+
         Ok(Self {
             mode,
             dictionary,
             user_dictionary,
             keep_whitespace,
+            okurigana_variants,
             space_category_id,
         })
     }
@@ -285,6 +420,35 @@ impl Segmenter {
         &'a self,
         text: Cow<'a, str>,
         lattice: &mut Lattice,
+    ) -> LinderaResult<Vec<Token<'a>>> {
+        self.segment_sentences(text, lattice, None)
+    }
+
+    /// Segments the text like [`Segmenter::segment`], and returns each token with its marginal
+    /// probability in the lattice of its sentence, the share of the segmentations of the sentence
+    /// that have the token, weighted by `exp(-theta * cost)` (see
+    /// [`Lattice::marginal_probabilities`]).
+    ///
+    /// A token with a low probability is one the segmenter is unsure about: other segmentations
+    /// of its part of the text have a cost close to the one of the best path.
+    pub fn segment_with_marginals<'a>(
+        &'a self,
+        text: Cow<'a, str>,
+        theta: f64,
+    ) -> LinderaResult<Vec<(Token<'a>, f64)>> {
+        let mut lattice = Lattice::default();
+        let mut marginals = Vec::new();
+        let tokens = self.segment_sentences(text, &mut lattice, Some((theta, &mut marginals)))?;
+        Ok(tokens.into_iter().zip(marginals).collect())
+    }
+
+    /// Segments the text, and pushes the marginal probabilities of the tokens if a `theta` is
+    /// given.
+    fn segment_sentences<'a>(
+        &'a self,
+        text: Cow<'a, str>,
+        lattice: &mut Lattice,
+        mut marginals: Option<(f64, &mut Vec<f64>)>,
     ) -> LinderaResult<Vec<Token<'a>>> {
         let mut tokens: Vec<Token> = Vec::new();
 
@@ -322,9 +486,10 @@ impl Segmenter {
             }
 
             // Process the sentence through lattice
-            lattice.set_text(
+            lattice.set_text_with_variants(
                 &self.dictionary.prefix_dictionary,
                 &self.user_dictionary.as_ref().map(|d| &d.dict),
+                &self.okurigana_variants.as_ref().map(|v| &v.dict),
                 &self.dictionary.character_definition,
                 &self.dictionary.unknown_dictionary,
                 &self.dictionary.connection_cost_matrix,
@@ -332,39 +497,67 @@ impl Segmenter {
                 &self.mode,
             );
             // Forward Viterbi implementation handles cost calculation within `set_text`.
+            if lattice.is_interrupted() {
+                return Err(LinderaErrorKind::Timeout.with_error(anyhow::anyhow!(
+                    "segmentation did not finish before the deadline"
+                )));
+            }
 
-            let offsets = lattice.tokens_offset();
-
-            for i in 0..offsets.len() {
-                let (byte_start, word_id) = offsets[i];
-                let byte_end = if i == offsets.len() - 1 {
+            let path = lattice.best_path();
+            let path_marginals = match &marginals {
+                Some((theta, _)) => lattice.best_path_marginals(
+                    &self.dictionary.connection_cost_matrix,
+                    &self.mode,
+                    *theta,
+                ),
+                None => Vec::new(),
+            };
+
+            // The right context ID of the previous word, starting from BOS
+            let mut left_right_id = 0;
+            for i in 0..path.len() {
+                let edge = path[i];
+                let byte_start = edge.start_index as usize;
+                let word_id = edge.word_entry.word_id;
+                let byte_end = if i == path.len() - 1 {
                     sentence.len()
                 } else {
-                    let (next_start, _word_id) = offsets[i + 1];
-                    next_start
+                    path[i + 1].start_index as usize
+                };
+
+                let cost = TokenCost {
+                    left_id: edge.word_entry.left_id,
+                    right_id: edge.word_entry.right_id,
+                    word_cost: edge.word_entry.word_cost,
+                    connection_cost: self
+                        .dictionary
+                        .connection_cost_matrix
+                        .cost(left_right_id, edge.word_entry.left_id()),
+                    path_cost: edge.path_cost,
                 };
+                left_right_id = edge.word_entry.right_id();
 
                 // Calculate absolute position in the original text
                 let absolute_start = sentence_start + byte_start;
                 let absolute_end = sentence_start + byte_end;
 
                 // Skip whitespace tokens if keep_whitespace is false (default MeCab behavior)
-                if !self.keep_whitespace {
-                    if let Some(space_category_id) = self.space_category_id {
-                        // Check if this token consists only of whitespace characters
-                        let token_text = &sentence[byte_start..byte_end];
-                        let is_space = token_text.chars().all(|c| {
-                            self.dictionary
-                                .character_definition
-                                .lookup_categories(c)
-                                .contains(&space_category_id)
-                        });
-
-                        if is_space {
-                            // Update byte_position to maintain correct offsets
-                            byte_position += byte_end - byte_start;
-                            continue;
-                        }
+                if !self.keep_whitespace
+                    && let Some(space_category_id) = self.space_category_id
+                {
+                    // Check if this token consists only of whitespace characters
+                    let token_text = &sentence[byte_start..byte_end];
+                    let is_space = token_text.chars().all(|c| {
+                        self.dictionary
+                            .character_definition
+                            .lookup_categories(c)
+                            .contains(&space_category_id)
+                    });
+
+                    if is_space {
+                        // Update byte_position to maintain correct offsets
+                        byte_position += byte_end - byte_start;
+                        continue;
                     }
                 }
 
@@ -382,7 +575,7 @@ impl Segmenter {
                 byte_position += byte_end - byte_start;
                 let token_end = byte_position;
 
-                tokens.push(Token::new(
+                let mut token = Token::new(
                     surface_cow,
                     token_start,
                     token_end,
@@ -390,7 +583,12 @@ impl Segmenter {
                     word_id,
                     &self.dictionary,
                     self.user_dictionary.as_ref(),
-                ));
+                );
+                token.cost = Some(cost);
+                tokens.push(token);
+                if let Some((_, marginals)) = &mut marginals {
+                    marginals.push(path_marginals[i]);
+                }
 
                 position += 1;
             }
@@ -402,6 +600,30 @@ impl Segmenter {
     }
 }
 
+/// Fails if a word of the user dictionary has a context id outside the connection cost matrix
+/// of the dictionary.
+pub(crate) fn check_context_ids(
+    user_dictionary: &UserDictionary,
+    dictionary: &Dictionary,
+) -> LinderaResult<()> {
+    let matrix = &dictionary.connection_cost_matrix;
+    for (surface, entry) in user_dictionary.dict.entries() {
+        // The left id of a word is the backward id of the connection to it
+        if entry.left_id as u32 >= matrix.backward_size
+            || entry.right_id as u32 >= matrix.forward_size
+        {
+            return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                "the user dictionary word {surface} has context ids ({}, {}) outside the {}x{} connection cost matrix of the dictionary",
+                entry.left_id,
+                entry.right_id,
+                matrix.forward_size,
+                matrix.backward_size
+            )));
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
@@ -463,6 +685,18 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_segmenter_config_okurigana_variants_embedded() {
+        let config = serde_json::json!({
+            "dictionary": "embedded://ipadic",
+            "okurigana_variants": true
+        });
+
+        // Embedded dictionaries have no directory to read dict.okurigana from
+        assert!(Segmenter::from_config(&config).is_err());
+    }
+
     #[test]
     #[cfg(feature = "embed-ipadic")]
     fn test_segment_ipadic() {
@@ -721,6 +955,51 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_segment_cost_ipadic() {
+        use std::borrow::Cow;
+
+        let config_str = r#"
+        {
+            "dictionary": "embedded://ipadic",
+            "mode": "normal"
+        }
+        "#;
+        let config = serde_json::from_str::<SegmenterConfig>(config_str).unwrap();
+
+        let segmenter = Segmenter::from_config(&config).unwrap();
+        let tokens = segmenter
+            .segment(Cow::Borrowed(
+                "関西国際空港限定トートバッグ。すもももももももものうち",
+            ))
+            .unwrap();
+
+        let matrix = &segmenter.dictionary.connection_cost_matrix;
+        // The path cost accumulates the connection and word costs, sentence by sentence
+        let mut path_cost = 0;
+        let mut right_id = 0;
+        for token in tokens.iter() {
+            let cost = token.cost.unwrap();
+            if token.surface == "すもも" {
+                path_cost = 0;
+                right_id = 0;
+            }
+            assert_eq!(
+                matrix.cost(right_id, cost.left_id as u32),
+                cost.connection_cost
+            );
+            path_cost += cost.connection_cost + cost.word_cost as i32;
+            assert_eq!(path_cost, cost.path_cost);
+            right_id = cost.right_id as u32;
+        }
+
+        // The costs are kept by the token filters
+        let mut tokens = tokens;
+        tokens.retain(|token| token.surface != "も");
+        assert!(tokens.iter().all(|token| token.cost.is_some()));
+    }
+
     #[test]
     #[cfg(feature = "embed-unidic")]
     fn test_segment_unidic() {
@@ -3229,4 +3508,115 @@ mod tests {
             .unwrap();
         assert!(!tokens.is_empty());
     }
+
+    #[test]
+    #[cfg(not(feature = "signature"))]
+    fn test_segmenter_trusted_keys_without_signature_feature() {
+        use crate::error::LinderaErrorKind;
+        use crate::segmenter::Segmenter;
+
+        // Refused before the dictionary is loaded
+        let config = serde_json::json!({
+            "dictionary": "/path/to/dictionary",
+            "trusted_keys": []
+        });
+        let err = Segmenter::from_config(&config).err().unwrap();
+        assert_eq!(LinderaErrorKind::FeatureDisabled, err.kind());
+    }
+
+    #[test]
+    #[cfg(feature = "signature")]
+    fn test_segmenter_trusted_keys() {
+        use std::fs;
+
+        use crate::dictionary::signature::SigningKey;
+        use crate::error::LinderaErrorKind;
+        use crate::segmenter::Segmenter;
+        use crate::test_util::TestDictionary;
+
+        let dict_dir = TestDictionary::new(
+            "東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー\n",
+        )
+        .build();
+
+        let key = SigningKey::generate().unwrap();
+        let other_key = SigningKey::generate().unwrap();
+        key.sign_dictionary(dict_dir.path()).unwrap();
+
+        let config = |trusted_key: String| {
+            serde_json::json!({
+                "dictionary": dict_dir.path().to_str().unwrap(),
+                "keep_whitespace": true,
+                "trusted_keys": [trusted_key]
+            })
+        };
+        let segmenter = Segmenter::from_config(&config(key.public_key().to_string())).unwrap();
+        assert_eq!("東京", segmenter.segment("東京".into()).unwrap()[0].surface);
+
+        let err = Segmenter::from_config(&config(other_key.public_key().to_string()))
+            .err()
+            .unwrap();
+        assert_eq!(LinderaErrorKind::Signature, err.kind());
+
+        // A tampered dictionary
+        fs::write(dict_dir.path().join("dict.words"), b"").unwrap();
+        let err = Segmenter::from_config(&config(key.public_key().to_string()))
+            .err()
+            .unwrap();
+        assert_eq!(LinderaErrorKind::Signature, err.kind());
+
+        let err = Segmenter::from_config(&config("not a key".to_string()))
+            .err()
+            .unwrap();
+        assert_eq!(LinderaErrorKind::Signature, err.kind());
+    }
+
+    #[test]
+    #[cfg(feature = "no-unknown-grouping")]
+    fn test_segment_without_unknown_dictionary() {
+        use lindera_dictionary::dictionary::unknown_dictionary::UnknownDictionary;
+
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+        use crate::test_util::TestDictionary;
+
+        let (_dict_dir, mut dictionary) = TestDictionary::new(
+            "東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー\n",
+        )
+        .char_def("DEFAULT 0 1 0\nKANJI 0 1 2\n0x4E00..0x9FAF KANJI\n")
+        .unknown_cost(5000)
+        .load();
+
+        // The dictionary of an embedded dictionary without unk.bin
+        dictionary.unknown_dictionary = UnknownDictionary::load(&[]).unwrap();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let tokens = segmenter.segment("東京鯖鮪".into()).unwrap();
+        assert_eq!(
+            vec!["東京", "鯖", "鮪"],
+            tokens
+                .iter()
+                .map(|token| token.surface.as_ref())
+                .collect::<Vec<_>>()
+        );
+        assert!(tokens[1].word_id.is_unknown());
+    }
+
+    #[test]
+    #[cfg(feature = "no-details")]
+    fn test_segment_without_details() {
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+        use crate::test_util::TestDictionary;
+
+        let (_dict_dir, dictionary) = TestDictionary::new(
+            "東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー\n",
+        )
+        .unknown_cost(5000)
+        .load();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let mut tokens = segmenter.segment("東京".into()).unwrap();
+        assert_eq!("東京", tokens[0].surface);
+        assert_eq!(vec!["UNK"], tokens[0].details());
+        assert_eq!(None, tokens[0].get("reading"));
+    }
 }
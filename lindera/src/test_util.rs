@@ -0,0 +1,113 @@
+//! Small dictionaries built from sources written in the tests, for the tests that cannot rely on
+//! an embedded dictionary.
+
+use std::fs;
+use std::path::Path;
+
+use tempfile::TempDir;
+
+use crate::dictionary::{Dictionary, DictionaryBuilder, Metadata, load_dictionary};
+use crate::tokenizer::{Tokenizer, TokenizerBuilder};
+
+/// The sources of a test dictionary.
+///
+/// By default a run of kanji is an unknown noun of up to two characters, any other character an
+/// unknown noun of its own, the unknown words cost nothing and every connection costs 0. The
+/// words are read with the default metadata, so they have the IPADIC fields.
+pub(crate) struct TestDictionary {
+    char_def: String,
+    unk_def: String,
+    matrix_def: String,
+    lex_csv: String,
+    metadata: Metadata,
+}
+
+impl TestDictionary {
+    /// The dictionary of the words of `lex_csv`.
+    pub(crate) fn new(lex_csv: &str) -> Self {
+        Self {
+            char_def: "DEFAULT 0 1 0\nKANJI 0 0 2\n0x4E00..0x9FAF KANJI\n".to_string(),
+            unk_def: "DEFAULT,0,0,0,名詞,一般,*,*,*,*,*,*,*\nKANJI,0,0,0,名詞,一般,*,*,*,*,*,*,*\n"
+                .to_string(),
+            matrix_def: "1 1\n0 0 0\n".to_string(),
+            lex_csv: lex_csv.to_string(),
+            metadata: Metadata::default(),
+        }
+    }
+
+    /// Sets the cost of the unknown words.
+    pub(crate) fn unknown_cost(mut self, cost: i32) -> Self {
+        self.unk_def = format!(
+            "DEFAULT,0,0,{cost},名詞,一般,*,*,*,*,*,*,*\nKANJI,0,0,{cost},名詞,一般,*,*,*,*,*,*,*\n"
+        );
+        self
+    }
+
+    /// Uses `char_def` as the character definitions.
+    pub(crate) fn char_def(mut self, char_def: &str) -> Self {
+        self.char_def = char_def.to_string();
+        self
+    }
+
+    /// Uses `unk_def` as the definitions of the unknown words.
+    pub(crate) fn unk_def(mut self, unk_def: &str) -> Self {
+        self.unk_def = unk_def.to_string();
+        self
+    }
+
+    /// Uses a `size`x`size` connection cost matrix, every connection of which costs 0.
+    pub(crate) fn matrix_size(mut self, size: usize) -> Self {
+        let mut matrix_def = format!("{size} {size}\n");
+        for forward_id in 0..size {
+            for backward_id in 0..size {
+                matrix_def.push_str(&format!("{forward_id} {backward_id} 0\n"));
+            }
+        }
+        self.matrix_def = matrix_def;
+        self
+    }
+
+    /// Builds the dictionary with `metadata` instead of the default metadata.
+    pub(crate) fn metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Writes the sources to `dir`.
+    pub(crate) fn write_sources(&self, dir: &Path) {
+        fs::write(dir.join("char.def"), &self.char_def).unwrap();
+        fs::write(dir.join("unk.def"), &self.unk_def).unwrap();
+        fs::write(dir.join("matrix.def"), &self.matrix_def).unwrap();
+        fs::write(dir.join("lex.csv"), &self.lex_csv).unwrap();
+    }
+
+    /// Builds the dictionary into a temporary directory.
+    pub(crate) fn build(&self) -> TempDir {
+        let input_dir = tempfile::tempdir().unwrap();
+        self.write_sources(input_dir.path());
+        let dict_dir = tempfile::tempdir().unwrap();
+        DictionaryBuilder::new(self.metadata.clone())
+            .build_dictionary(input_dir.path(), dict_dir.path())
+            .unwrap();
+        dict_dir
+    }
+
+    /// Builds the dictionary into a temporary directory and loads it.
+    pub(crate) fn load(&self) -> (TempDir, Dictionary) {
+        let dict_dir = self.build();
+        let dictionary = load_dictionary(dict_dir.path().to_str().unwrap()).unwrap();
+        (dict_dir, dictionary)
+    }
+
+    /// Builds the dictionary into a temporary directory and a tokenizer keeping the whitespace
+    /// with it.
+    pub(crate) fn tokenizer(&self) -> (TempDir, Tokenizer) {
+        let dict_dir = self.build();
+        let mut builder = TokenizerBuilder::from_config(serde_json::json!({})).unwrap();
+        builder
+            .set_segmenter_dictionary(dict_dir.path().to_str().unwrap())
+            .set_segmenter_keep_whitespace(true);
+        let tokenizer = builder.build().unwrap();
+        (dict_dir, tokenizer)
+    }
+}
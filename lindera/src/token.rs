@@ -1,9 +1,35 @@
+pub mod conjugation;
+pub mod intern;
+pub mod pronunciation;
+
 use std::borrow::Cow;
+use std::sync::Arc;
 
 use lindera_dictionary::dictionary::UNK;
 use serde_json::{Value, json};
 
 use crate::dictionary::{Dictionary, UserDictionary, WordId};
+use crate::token::conjugation::{ConjugationForm, conjugation_field_indices};
+use crate::token::intern::{DetailId, DetailPool};
+use crate::token::pronunciation::{morae, syllables};
+
+/// The connection IDs and the costs of a token on the best path of the lattice, for analyzing the
+/// choices of the segmenter or re-ranking its results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TokenCost {
+    /// The left context ID of the word, the column of the connection cost matrix.
+    pub left_id: u16,
+    /// The right context ID of the word, the row of the connection cost matrix.
+    pub right_id: u16,
+    /// The cost of the word itself (unigram cost).
+    pub word_cost: i16,
+    /// The cost of connecting the previous word of the path (or the beginning of the sentence) to
+    /// the word.
+    pub connection_cost: i32,
+    /// The cost of the best path from the beginning of the sentence up to and including the word.
+    /// In decompose mode it includes the penalties of the long words.
+    pub path_cost: i32,
+}
 
 #[derive(Clone)]
 pub struct Token<'a> {
@@ -44,13 +70,22 @@ pub struct Token<'a> {
     /// in the default dictionary. If `None`, the default dictionary is used.
     pub user_dictionary: Option<&'a UserDictionary>,
 
+    /// The costs of the token on the best path of the lattice.
+    ///
+    /// This field is set for the tokens of the segmenter, and is `None` for the tokens made by
+    /// other means (e.g. merged by a token filter).
+    pub cost: Option<TokenCost>,
+
     /// An optional vector containing detailed information about the token.
     /// Each element in the vector is a `Cow` (Copy-On-Write) type, which allows
     /// for efficient handling of both owned and borrowed string data.
     ///
     /// # Note
     ///
-    /// This field is optional and may be `None` if no detailed information is available.
+    /// The segmenter leaves this field `None`: the details are split from the record of the word
+    /// in the dictionary, found through `word_id`, only when [`Token::details`] is called. The
+    /// field accessors such as [`Token::get_detail`] and [`Token::get`] read the detail they need
+    /// without loading the others.
     pub details: Option<Vec<Cow<'a, str>>>,
 }
 
@@ -96,6 +131,7 @@ impl<'a> Token<'a> {
             word_id,
             dictionary,
             user_dictionary,
+            cost: None,
             details: None,
         }
     }
@@ -152,6 +188,49 @@ impl<'a> Token<'a> {
         }
     }
 
+    /// Returns the details of the token as shared strings of `pool`, which outlive the token and
+    /// the dictionary. Each distinct detail is stored once in the pool, however many tokens have
+    /// it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "embed-ipadic")]
+    /// # {
+    /// # use lindera::dictionary::load_dictionary;
+    /// # use lindera::mode::Mode;
+    /// # use lindera::segmenter::Segmenter;
+    /// # use lindera::tokenizer::Tokenizer;
+    /// # let dictionary = load_dictionary("embedded://ipadic")?;
+    /// # let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+    /// # let mut tokens = tokenizer.tokenize("東京")?;
+    /// # let token = &mut tokens[0];
+    /// use lindera::token::intern::DetailPool;
+    ///
+    /// let pool = DetailPool::new();
+    /// let details = token.interned_details(&pool);
+    /// assert_eq!("名詞", &*details[0]);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn interned_details(&mut self, pool: &DetailPool) -> Vec<Arc<str>> {
+        self.details()
+            .into_iter()
+            .map(|detail| pool.intern_arc(detail))
+            .collect()
+    }
+
+    /// Returns the ids in `pool` of the details of the token, to store the details of many
+    /// tokens in 4 bytes each. [`DetailPool::resolve`] gives the strings back.
+    pub fn detail_ids(&mut self, pool: &DetailPool) -> Vec<DetailId> {
+        self.details()
+            .into_iter()
+            .map(|detail| pool.intern(detail))
+            .collect()
+    }
+
     /// Retrieves the token's detail at the specified index, if available.
     ///
     /// # Arguments
@@ -165,11 +244,31 @@ impl<'a> Token<'a> {
     ///
     /// # Details
     ///
-    /// - This method first ensures that the token's details are populated by calling `self.details()`.
+    /// - If the token's details have not been loaded yet, only the detail at `index` is read from the dictionary; the other details are neither split nor stored on the token.
     /// - If details are available and the provided index is valid, the detail at the specified index is returned as `Some(&str)`.
     /// - If the index is out of range, `None` is returned.
     pub fn get_detail(&mut self, index: usize) -> Option<&str> {
-        self.details().get(index).copied()
+        match &self.details {
+            Some(details) => details.get(index).map(|detail| detail.as_ref()),
+            // Only the field asked for is read from the dictionary
+            None => self.dictionary_detail(index),
+        }
+    }
+
+    /// Reads a single detail of the word from the dictionary, without splitting the others.
+    fn dictionary_detail(&self, index: usize) -> Option<&'a str> {
+        if self.word_id.is_unknown() {
+            UNK.get(index).copied()
+        } else if self.word_id.is_system() {
+            self.dictionary.word_detail(self.word_id.id as usize, index)
+        } else {
+            match self.user_dictionary {
+                Some(user_dictionary) => {
+                    user_dictionary.word_detail(self.word_id.id as usize, index)
+                }
+                None => UNK.get(index).copied(),
+            }
+        }
     }
 
     /// Sets the token's detail at the specified index with the provided value.
@@ -181,16 +280,15 @@ impl<'a> Token<'a> {
     ///
     /// # Details
     ///
-    /// - If the token's details have already been populated (`self.details` is `Some`), this method updates the detail at the specified index.
+    /// - The token's details are loaded from the dictionary first if they have not been yet, so that the other details are kept.
     /// - If the provided index is valid (within bounds of the `details` vector), the detail at that index is replaced by the new `detail` value.
-    /// - If the details have not been set (`self.details` is `None`), this method does nothing.
     /// - This method does not handle index out-of-bounds errors explicitly, so it assumes that the index provided is valid.
     ///
     /// # Notes
     ///
     /// - The `Cow<'a, str>` type allows flexibility, as it can handle either borrowed or owned strings.
-    /// - This method does not initialize the details if they are not already set. To ensure the details are set, `details()` can be called prior to calling this method.
     pub fn set_detail(&mut self, index: usize, detail: Cow<'a, str>) {
+        self.ensure_details();
         if let Some(details) = self.details.as_mut() {
             details[index] = detail;
         }
@@ -209,11 +307,24 @@ impl<'a> Token<'a> {
     ///
     /// # Example
     ///
-    /// ```no_run
-    /// # use lindera::token::Token;
-    /// # let mut token: Token = unimplemented!();
-    /// let base_form = token.get("base_form");
-    /// let pos = token.get("major_pos");
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "embed-ipadic")]
+    /// # {
+    /// # use lindera::dictionary::load_dictionary;
+    /// # use lindera::mode::Mode;
+    /// # use lindera::segmenter::Segmenter;
+    /// # use lindera::tokenizer::Tokenizer;
+    /// # let dictionary = load_dictionary("embedded://ipadic")?;
+    /// # let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+    /// # let mut tokens = tokenizer.tokenize("東京")?;
+    /// # let token = &mut tokens[0];
+    /// assert_eq!(Some("名詞"), token.get("part_of_speech"));
+    /// assert_eq!(Some("東京"), token.get("base_form"));
+    /// assert_eq!(None, token.get("no_such_field"));
+    /// # }
+    /// # Ok(())
+    /// # }
     /// ```
     pub fn get(&mut self, field_name: &str) -> Option<&str> {
         // Get field index from schema
@@ -235,6 +346,268 @@ impl<'a> Token<'a> {
         }
     }
 
+    /// Returns the reading of the token, from the field the dictionary metadata names in
+    /// `reading_index_field`: the katakana reading of the Japanese dictionaries, the pinyin of
+    /// CC-CEDICT, or the jyutping of the Cantonese dictionaries. The field is `reading` for the
+    /// dictionaries built without the reading index.
+    ///
+    /// Returns `None` if the dictionary has no reading field or the token has no reading.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "embed-ipadic")]
+    /// # {
+    /// # use lindera::dictionary::load_dictionary;
+    /// # use lindera::mode::Mode;
+    /// # use lindera::segmenter::Segmenter;
+    /// # use lindera::tokenizer::Tokenizer;
+    /// # let dictionary = load_dictionary("embedded://ipadic")?;
+    /// # let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+    /// # let mut tokens = tokenizer.tokenize("東京")?;
+    /// # let token = &mut tokens[0];
+    /// assert_eq!(Some("トウキョウ"), token.reading());
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reading(&mut self) -> Option<&str> {
+        let dictionary = self.dictionary;
+        let field_name = dictionary
+            .metadata
+            .reading_index_field
+            .as_deref()
+            .unwrap_or("reading");
+        self.get(field_name).filter(|value| *value != "*")
+    }
+
+    fn conjugation_indices(&self) -> Option<(usize, usize)> {
+        conjugation_field_indices(&self.dictionary.metadata.dictionary_schema)
+    }
+
+    /// Returns the conjugation type of the token (e.g. `五段・ラ行`, `サ変・スル`).
+    ///
+    /// Returns `None` if the dictionary has no conjugation fields or the token does not conjugate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "embed-ipadic")]
+    /// # {
+    /// # use lindera::dictionary::load_dictionary;
+    /// # use lindera::mode::Mode;
+    /// # use lindera::segmenter::Segmenter;
+    /// # use lindera::tokenizer::Tokenizer;
+    /// # let dictionary = load_dictionary("embedded://ipadic")?;
+    /// # let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+    /// # let mut tokens = tokenizer.tokenize("住む")?;
+    /// # let token = &mut tokens[0];
+    /// // 住む conjugates like the verbs ending in む
+    /// assert_eq!(Some("五段・マ行"), token.conjugation_type());
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn conjugation_type(&mut self) -> Option<&str> {
+        let (index, _) = self.conjugation_indices()?;
+        self.get_detail(index).filter(|value| *value != "*")
+    }
+
+    /// Returns the conjugation form of the token, classified from the conjugation form field
+    /// (e.g. `連用タ接続` or `連用形-促音便` as [`ConjugationForm::Continuative`]).
+    ///
+    /// Returns `None` if the dictionary has no conjugation fields or the token does not conjugate.
+    pub fn conjugation_form(&mut self) -> Option<ConjugationForm> {
+        let (_, index) = self.conjugation_indices()?;
+        self.get_detail(index)
+            .filter(|value| *value != "*")
+            .map(ConjugationForm::parse)
+    }
+
+    /// Returns the pronunciation of the token in katakana (e.g. `トーキョー` for `東京`), from the
+    /// `pronunciation` field of IPADIC or the `phonological_surface_form` field of UniDic.
+    ///
+    /// Returns `None` if the dictionary has no pronunciation field or the token has no
+    /// pronunciation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "embed-ipadic")]
+    /// # {
+    /// # use lindera::dictionary::load_dictionary;
+    /// # use lindera::mode::Mode;
+    /// # use lindera::segmenter::Segmenter;
+    /// # use lindera::tokenizer::Tokenizer;
+    /// # let dictionary = load_dictionary("embedded://ipadic")?;
+    /// # let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+    /// # let mut tokens = tokenizer.tokenize("東京")?;
+    /// # let token = &mut tokens[0];
+    /// assert_eq!(Some("トーキョー"), token.pronunciation());
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pronunciation(&mut self) -> Option<&str> {
+        let schema = &self.dictionary.metadata.dictionary_schema;
+        let field_name = ["pronunciation", "phonological_surface_form"]
+            .into_iter()
+            .find(|field_name| schema.get_field_index(field_name).is_some())?;
+        self.get(field_name).filter(|value| *value != "*")
+    }
+
+    /// The pronunciation of the token, or its reading if the dictionary has no pronunciation.
+    fn spoken_form(&mut self) -> Option<String> {
+        match self.pronunciation() {
+            Some(pronunciation) => Some(pronunciation.to_string()),
+            None => self.reading().map(str::to_string),
+        }
+    }
+
+    /// Returns the morae of the pronunciation of the token, or of its reading if the dictionary has
+    /// no pronunciation (e.g. `ト`, `ー`, `キョ`, `ー` for `東京`). See
+    /// [`pronunciation::morae`](crate::token::pronunciation::morae).
+    ///
+    /// Returns an empty vector if the token has neither a pronunciation nor a reading.
+    pub fn morae(&mut self) -> Vec<String> {
+        match self.spoken_form() {
+            Some(spoken_form) => morae(&spoken_form)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the number of morae of the token (4 for `東京`, `ト` `ー` `キョ` `ー`), counted on
+    /// its pronunciation or its reading. A token without either, such as an unknown word, is
+    /// counted on its surface, where only the kana count: 0 for punctuation and an unknown word in
+    /// kanji.
+    pub fn mora_count(&mut self) -> usize {
+        let spoken_form = self
+            .spoken_form()
+            .unwrap_or_else(|| self.surface.to_string());
+        morae(&spoken_form).len()
+    }
+
+    /// Returns the number of syllables of the token (2 for `東京`, `トー` `キョー`), counted on the
+    /// same kana as [`Token::mora_count`]. See
+    /// [`pronunciation::syllables`](crate::token::pronunciation::syllables).
+    pub fn syllable_count(&mut self) -> usize {
+        let spoken_form = self
+            .spoken_form()
+            .unwrap_or_else(|| self.surface.to_string());
+        syllables(&spoken_form).len()
+    }
+
+    /// Returns the accent type of the token, the mora after which the pitch falls (0 for a word
+    /// without a fall), from the `accent_type` field of UniDic-CWJ and UniDic-CSJ. When the
+    /// dictionary lists several accent types (`0,2`), the first one is returned.
+    ///
+    /// Returns `None` if the dictionary has no accent fields or the token has no accent type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "embed-ipadic")]
+    /// # {
+    /// # use lindera::dictionary::load_dictionary;
+    /// # use lindera::mode::Mode;
+    /// # use lindera::segmenter::Segmenter;
+    /// # use lindera::tokenizer::Tokenizer;
+    /// # let dictionary = load_dictionary("embedded://ipadic")?;
+    /// # let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+    /// # let mut tokens = tokenizer.tokenize("東京")?;
+    /// # let token = &mut tokens[0];
+    /// // IPADIC has no accent fields, UniDic-CWJ and UniDic-CSJ have them
+    /// if let Some(accent_type) = token.accent_type() {
+    ///     println!("{} is accented on mora {accent_type}", token.surface);
+    /// }
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn accent_type(&mut self) -> Option<u8> {
+        self.get("accent_type")?
+            .split(',')
+            .next()
+            .and_then(|accent_type| accent_type.trim().parse().ok())
+    }
+
+    /// Returns the accent connection type of the token, how its accent combines with the word
+    /// before it (e.g. `C1`, or `動詞%F2@0` for a particle), from the `accent_connection_type`
+    /// field of UniDic-CWJ and UniDic-CSJ.
+    ///
+    /// Returns `None` if the dictionary has no accent fields or the token has no accent
+    /// connection type.
+    pub fn accent_connection_type(&mut self) -> Option<&str> {
+        self.get("accent_connection_type")
+            .filter(|value| *value != "*")
+    }
+
+    /// Returns the accent modification type of the token (e.g. `M4@1`), from the
+    /// `accent_modification_type` field of UniDic-CWJ and UniDic-CSJ.
+    ///
+    /// Returns `None` if the dictionary has no accent fields or the token has no accent
+    /// modification type.
+    pub fn accent_modification_type(&mut self) -> Option<&str> {
+        self.get("accent_modification_type")
+            .filter(|value| *value != "*")
+    }
+
+    /// Returns the Universal POS tag of the token (e.g. `PROPN` for `名詞,固有名詞,地域,一般`),
+    /// mapped from its part-of-speech fields by the `upos_mapping` of the dictionary metadata.
+    ///
+    /// Returns `None` if the dictionary has no mapping or the part-of-speech is not mapped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "embed-ipadic")]
+    /// # {
+    /// # use lindera::dictionary::load_dictionary;
+    /// # use lindera::mode::Mode;
+    /// # use lindera::segmenter::Segmenter;
+    /// # use lindera::tokenizer::Tokenizer;
+    /// # let dictionary = load_dictionary("embedded://ipadic")?;
+    /// # let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+    /// # let mut tokens = tokenizer.tokenize("東京")?;
+    /// # let token = &mut tokens[0];
+    /// assert_eq!(Some("PROPN"), token.upos());
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn upos(&mut self) -> Option<&'a str> {
+        let metadata = &self.dictionary.metadata;
+        if metadata.upos_mapping.is_empty() {
+            return None;
+        }
+
+        // The part-of-speech fields are the ones named `part_of_speech*`
+        let indices = metadata
+            .dictionary_schema
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| name.starts_with("part_of_speech"))
+            .filter_map(|(index, _)| index.checked_sub(4))
+            .collect::<Vec<_>>();
+        let details = self.details();
+        let part_of_speech = indices
+            .iter()
+            .filter_map(|index| details.get(*index).copied())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        metadata.upos(&part_of_speech)
+    }
+
     /// Returns all token fields as a JSON Value.
     ///
     /// # Returns
@@ -245,13 +618,25 @@ impl<'a> Token<'a> {
     ///
     /// # Example
     ///
-    /// ```no_run
-    /// # use lindera::token::Token;
-    /// # let mut token: Token = unimplemented!();
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "embed-ipadic")]
+    /// # {
+    /// # use lindera::dictionary::load_dictionary;
+    /// # use lindera::mode::Mode;
+    /// # use lindera::segmenter::Segmenter;
+    /// # use lindera::tokenizer::Tokenizer;
+    /// # let dictionary = load_dictionary("embedded://ipadic")?;
+    /// # let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+    /// # let mut tokens = tokenizer.tokenize("東京")?;
+    /// # let token = &mut tokens[0];
     /// let value = token.as_value();
-    /// println!("Surface: {}", value["surface"]);
-    /// println!("Byte start: {}", value["byte_start"]); // This is a number
+    /// assert_eq!("東京", value["surface"]);
+    /// assert_eq!(0, value["byte_start"]); // This is a number
     /// println!("Word ID: {}", value["word_id"]); // This is a number
+    /// # }
+    /// # Ok(())
+    /// # }
     /// ```
     pub fn as_value(&mut self) -> Value {
         // Get schema info first
@@ -300,3 +685,104 @@ impl<'a> Token<'a> {
         Value::Object(obj)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_reading() {
+        use std::borrow::Cow;
+        use std::fs;
+
+        use lindera_dictionary::dictionary::schema::Schema;
+
+        use crate::dictionary::{DictionaryBuilder, Metadata, load_dictionary};
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+
+        let source_dir = tempfile::tempdir().unwrap();
+        let source_file = source_dir.path().join("cccanto.txt");
+        fs::write(
+            &source_file,
+            "香港 香港 [Xiang1 gang3] {hoeng1 gong2} /Hong Kong/\n唔該 唔该 [wu2 gai1] {m4 goi1} /thank you/\n",
+        )
+        .unwrap();
+        let metadata = Metadata {
+            reading_index_field: Some("jyutping".to_string()),
+            dictionary_schema: Schema::new(
+                [
+                    "surface",
+                    "left_context_id",
+                    "right_context_id",
+                    "cost",
+                    "pinyin",
+                    "jyutping",
+                    "traditional",
+                    "simplified",
+                    "definition",
+                ]
+                .map(str::to_string)
+                .to_vec(),
+            ),
+            ..Metadata::default()
+        };
+        let dict_dir = tempfile::tempdir().unwrap();
+        DictionaryBuilder::new(metadata)
+            .build_dictionary(&source_file, dict_dir.path())
+            .unwrap();
+
+        let dictionary = load_dictionary(dict_dir.path().to_str().unwrap()).unwrap();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let mut tokens = segmenter.segment(Cow::Borrowed("唔該香港人")).unwrap();
+        assert_eq!(
+            vec!["唔該", "香港", "人"],
+            tokens
+                .iter()
+                .map(|token| token.surface.as_ref())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some("m4 goi1"), tokens[0].reading());
+        assert_eq!(Some("wu2 gai1"), tokens[0].get("pinyin"));
+        assert_eq!(Some("唔该"), tokens[0].get("simplified"));
+        assert_eq!(Some("hoeng1 gong2"), tokens[1].reading());
+        // The unknown words have no reading
+        assert_eq!(None, tokens[2].reading());
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_lazy_details_ipadic() {
+        use std::borrow::Cow;
+
+        use crate::dictionary::load_dictionary;
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+
+        let dictionary = load_dictionary("embedded://ipadic").unwrap();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let mut tokens = segmenter.segment(Cow::Borrowed("東京へ")).unwrap();
+
+        // A single detail is read without loading the others
+        let token = &mut tokens[0];
+        assert_eq!(Some("名詞"), token.get_detail(0));
+        assert_eq!(Some("トウキョウ"), token.get("reading"));
+        assert_eq!(None, token.get_detail(100));
+        assert!(token.details.is_none());
+
+        // Setting a detail keeps the others
+        token.set_detail(0, Cow::Borrowed("固有名詞"));
+        assert_eq!(
+            vec![
+                "固有名詞",
+                "固有名詞",
+                "地域",
+                "一般",
+                "*",
+                "*",
+                "東京",
+                "トウキョウ",
+                "トーキョー"
+            ],
+            token.details()
+        );
+    }
+}
@@ -0,0 +1,767 @@
+//! Conjugation analysis of Japanese verbs and adjectives, for grammar checking and intent parsing.
+//!
+//! [`deconjugate`] reads a verb or an adjective followed by its auxiliaries (e.g. `さ|れ|た`) and
+//! returns the base form of the verb with the meaning of each auxiliary (`する` + passive + past).
+//! [`Conjugator`] does the inverse (`書く` + passive + polite + past → `書かれました`), using the
+//! conjugated entries of the dictionary.
+
+use std::collections::HashMap;
+
+use kanaria::string::UCSStr;
+use serde::Serialize;
+
+use crate::LinderaResult;
+use crate::dictionary::{Dictionary, Schema};
+use crate::error::LinderaErrorKind;
+use crate::token::Token;
+
+/// Returns the indices in the details of the conjugation type and conjugation form fields.
+///
+/// IPADIC's metadata names the two columns the other way round, so the fields are told apart by
+/// their order: in every dictionary with both fields the conjugation type comes first.
+pub(crate) fn conjugation_field_indices(schema: &Schema) -> Option<(usize, usize)> {
+    let a = schema.get_field_index("conjugation_type")?;
+    let b = schema.get_field_index("conjugation_form")?;
+    if a < 4 || b < 4 {
+        return None;
+    }
+    Some((a.min(b) - 4, a.max(b) - 4))
+}
+
+/// The conjugation form of a verb, an adjective or an auxiliary.
+///
+/// IPADIC and UniDic name the forms differently (`連用タ接続` and `連用形-促音便` are both
+/// continuative forms); the form is classified by its prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConjugationForm {
+    /// 基本形 / 終止形, the dictionary form.
+    Terminal,
+    /// 連体形 / 体言接続, the form modifying a noun.
+    Attributive,
+    /// 連用形, the form followed by `た`, `て` or `ます`.
+    Continuative,
+    /// 未然形, the form followed by `ない` or `れる`.
+    Irrealis,
+    /// 仮定形 / 已然形, the form followed by `ば`.
+    Conditional,
+    /// 命令形.
+    Imperative,
+    /// 意志推量形.
+    Volitional,
+    /// 語幹 / ガル接続.
+    Stem,
+    /// Any other form.
+    Other,
+}
+
+impl ConjugationForm {
+    /// Classifies the value of a conjugation form field.
+    pub fn parse(form: &str) -> Self {
+        if form.contains("基本形") || form.starts_with("終止") {
+            Self::Terminal
+        } else if form.starts_with("連体") || form.starts_with("体言接続") {
+            Self::Attributive
+        } else if form.starts_with("連用") {
+            Self::Continuative
+        } else if form.starts_with("未然") {
+            Self::Irrealis
+        } else if form.starts_with("仮定") || form.starts_with("已然") {
+            Self::Conditional
+        } else if form.starts_with("命令") {
+            Self::Imperative
+        } else if form.starts_with("意志推量") {
+            Self::Volitional
+        } else if form.starts_with("語幹") || form.starts_with("ガル接続") {
+            Self::Stem
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// The meaning of an auxiliary following a verb or an adjective.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Auxiliary {
+    /// `れる`, `られる`. The same auxiliaries also express potential and honorific meanings.
+    Passive,
+    /// `せる`, `させる`.
+    Causative,
+    /// `ない`, `ぬ`, `ん`, `ず`.
+    Negative,
+    /// `た`, `だ`.
+    Past,
+    /// `ます`, `です`.
+    Polite,
+    /// `たい`, `たがる`.
+    Desiderative,
+    /// `う`, `よう`.
+    Volitional,
+    /// `ている`, `でいる`.
+    Progressive,
+    /// Any other auxiliary, by its base form.
+    Other(String),
+}
+
+impl Auxiliary {
+    /// Returns the meaning of an auxiliary from its base form.
+    pub fn from_base_form(base_form: &str) -> Self {
+        match base_form {
+            "れる" | "られる" => Self::Passive,
+            "せる" | "させる" | "しめる" => Self::Causative,
+            "ない" | "ぬ" | "ん" | "ず" => Self::Negative,
+            "た" | "だ" => Self::Past,
+            "ます" | "です" => Self::Polite,
+            "たい" | "たがる" => Self::Desiderative,
+            "う" | "よう" => Self::Volitional,
+            _ => Self::Other(base_form.to_string()),
+        }
+    }
+}
+
+/// A verb or an adjective with its auxiliaries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Deconjugation {
+    /// The base form of the verb or the adjective.
+    pub base_form: String,
+    /// The auxiliaries, in the order of the text.
+    pub auxiliaries: Vec<Auxiliary>,
+    /// The number of tokens read, including the verb or the adjective.
+    pub len: usize,
+}
+
+/// The details of a token used by the analysis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Morpheme {
+    major_pos: String,
+    subcategory: String,
+    base_form: String,
+}
+
+impl Morpheme {
+    fn new(token: &mut Token) -> Self {
+        let major_pos = token.get_detail(0).unwrap_or_default().to_string();
+        let subcategory = token.get_detail(1).unwrap_or_default().to_string();
+        let base_form = ["base_form", "orthographic_base_form"]
+            .into_iter()
+            .find_map(|field_name| {
+                token
+                    .get(field_name)
+                    .filter(|value| *value != "*")
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| token.surface.to_string());
+
+        Self {
+            major_pos,
+            subcategory,
+            base_form,
+        }
+    }
+
+    fn is_predicate(&self) -> bool {
+        self.major_pos == "動詞" || self.major_pos == "形容詞"
+    }
+
+    /// An auxiliary verb, or a suffix verb such as IPADIC's `れる` and `させる`.
+    fn is_auxiliary(&self) -> bool {
+        self.major_pos == "助動詞" || (self.major_pos == "動詞" && self.subcategory == "接尾")
+    }
+
+    fn is_te(&self) -> bool {
+        self.major_pos == "助詞" && matches!(self.base_form.as_str(), "て" | "で")
+    }
+
+    fn is_iru(&self) -> bool {
+        self.major_pos == "動詞" && matches!(self.base_form.as_str(), "いる" | "居る")
+    }
+}
+
+fn deconjugate_morphemes(morphemes: &[Morpheme]) -> Option<Deconjugation> {
+    let head = morphemes
+        .first()
+        .filter(|morpheme| morpheme.is_predicate())?;
+
+    let mut auxiliaries = Vec::new();
+    let mut len = 1;
+    while let Some(morpheme) = morphemes.get(len) {
+        if morpheme.is_auxiliary() {
+            auxiliaries.push(Auxiliary::from_base_form(&morpheme.base_form));
+            len += 1;
+        } else if morpheme.is_te() && morphemes.get(len + 1).is_some_and(Morpheme::is_iru) {
+            auxiliaries.push(Auxiliary::Progressive);
+            len += 2;
+        } else {
+            break;
+        }
+    }
+
+    Some(Deconjugation {
+        base_form: head.base_form.clone(),
+        auxiliaries,
+        len,
+    })
+}
+
+/// Reads the verb or the adjective at the start of `tokens` and the auxiliaries following it.
+///
+/// Returns `None` if the first token is neither a verb nor an adjective.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # #[cfg(feature = "embed-ipadic")]
+/// # {
+/// use lindera::dictionary::load_dictionary;
+/// use lindera::mode::Mode;
+/// use lindera::segmenter::Segmenter;
+/// use lindera::token::conjugation::{Auxiliary, deconjugate};
+/// use lindera::tokenizer::Tokenizer;
+///
+/// let dictionary = load_dictionary("embedded://ipadic")?;
+/// let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+///
+/// let mut tokens = tokenizer.tokenize("中止された")?;
+/// // "された" → する + passive + past
+/// let deconjugation = deconjugate(&mut tokens[1..]).unwrap();
+/// assert_eq!("する", deconjugation.base_form);
+/// assert_eq!(vec![Auxiliary::Passive, Auxiliary::Past], deconjugation.auxiliaries);
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub fn deconjugate(tokens: &mut [Token]) -> Option<Deconjugation> {
+    // Only read the details of the tokens that may belong to the chain
+    let mut morphemes: Vec<Morpheme> = Vec::new();
+    for token in tokens.iter_mut() {
+        let morpheme = Morpheme::new(token);
+        let in_chain = if morphemes.is_empty() {
+            morpheme.is_predicate()
+        } else {
+            morpheme.is_auxiliary() || morpheme.is_te() || morpheme.is_iru()
+        };
+        if !in_chain {
+            break;
+        }
+        morphemes.push(morpheme);
+    }
+
+    deconjugate_morphemes(&morphemes)
+}
+
+/// Returns the surfaces that may be written for `reading` by a word whose base form is
+/// `base_form`, most kana first.
+///
+/// The conjugated entries of a word keep the characters of its base form before the trailing kana
+/// (`書` of `書く`, `取り扱` of `取り扱う`), followed by some of the kana of their reading. A word
+/// written in kana only is written as its reading.
+fn surface_candidates(base_form: &str, reading: &str) -> Vec<String> {
+    let okurigana_len = base_form
+        .chars()
+        .rev()
+        .take_while(|c| matches!(*c as u32, 0x3041..=0x309f))
+        .count();
+    let stem = base_form
+        .char_indices()
+        .nth(base_form.chars().count() - okurigana_len)
+        .map_or(base_form, |(index, _)| &base_form[..index]);
+    let kana = UCSStr::from_str(reading).hiragana().to_string();
+    if stem.is_empty() {
+        return vec![kana];
+    }
+
+    let kana_chars = kana.chars().collect::<Vec<_>>();
+    (0..=kana_chars.len())
+        .rev()
+        .map(|len| {
+            let mut surface = stem.to_string();
+            surface.extend(&kana_chars[kana_chars.len() - len..]);
+            surface
+        })
+        .collect()
+}
+
+/// The conjugated forms of the preceding word accepted by an auxiliary in IPADIC (in order of
+/// preference), and the words appended to it.
+///
+/// The last of the words takes the place of the preceding word; the others (the particle `て`)
+/// do not conjugate.
+fn auxiliary_rule(
+    conjugation_type: &str,
+    major_pos: &str,
+    base_form: &str,
+    auxiliary: &Auxiliary,
+) -> Option<(&'static [&'static str], &'static [&'static str])> {
+    let godan = conjugation_type.starts_with("五段");
+    let suru = conjugation_type.starts_with("サ変");
+    let ichidan = conjugation_type.starts_with("一段") || conjugation_type.starts_with("カ変");
+    let verb = major_pos == "動詞";
+    let adjective = major_pos == "形容詞" || conjugation_type.starts_with("形容詞");
+    let masu = base_form == "ます";
+    // The past and the te form of 五段 verbs ending in ぐ, ぬ, ぶ and む are voiced
+    let voiced = matches!(
+        conjugation_type.split('・').nth(1),
+        Some(row) if row.starts_with("ガ行")
+            || row.starts_with("ナ行")
+            || row.starts_with("バ行")
+            || row.starts_with("マ行")
+    );
+
+    match auxiliary {
+        Auxiliary::Passive if godan => Some((&["未然形"], &["れる"])),
+        Auxiliary::Passive if suru => Some((&["未然レル接続"], &["れる"])),
+        Auxiliary::Passive if ichidan => Some((&["未然形"], &["られる"])),
+        Auxiliary::Causative if godan => Some((&["未然形"], &["せる"])),
+        Auxiliary::Causative if suru => Some((&["未然レル接続"], &["せる"])),
+        Auxiliary::Causative if ichidan => Some((&["未然形"], &["させる"])),
+        Auxiliary::Negative if masu => Some((&["未然形"], &["ん"])),
+        Auxiliary::Negative if adjective => Some((&["連用テ接続"], &["ない"])),
+        Auxiliary::Negative if verb => Some((&["未然形"], &["ない"])),
+        Auxiliary::Past if masu => Some((&["連用形"], &["た"])),
+        Auxiliary::Past if voiced => Some((&["連用タ接続"], &["だ"])),
+        Auxiliary::Past => Some((&["連用タ接続", "連用形"], &["た"])),
+        Auxiliary::Polite if verb => Some((&["連用形"], &["ます"])),
+        Auxiliary::Polite => Some((&["基本形"], &["です"])),
+        Auxiliary::Desiderative if verb => Some((&["連用形"], &["たい"])),
+        Auxiliary::Volitional if verb || masu => Some((&["未然ウ接続"], &["う"])),
+        Auxiliary::Progressive if verb && voiced => Some((&["連用タ接続"], &["で", "いる"])),
+        Auxiliary::Progressive if verb => Some((&["連用タ接続", "連用形"], &["て", "いる"])),
+        _ => None,
+    }
+}
+
+/// A conjugated entry of the dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ConjugatedEntry<'a> {
+    word_id: u32,
+    major_pos: &'a str,
+    subcategory: &'a str,
+    conjugation_type: &'a str,
+    conjugation_form: &'a str,
+    reading: &'a str,
+}
+
+/// Generates the conjugated surfaces of verbs and adjectives from the conjugated entries of a
+/// dictionary.
+///
+/// [`Conjugator::conjugate`] works with any dictionary with conjugation fields; the auxiliary
+/// chains of [`Conjugator::inflect`] follow the conjugation forms of IPADIC.
+///
+/// # Example
+///
+/// ```no_run
+/// # use lindera::dictionary::load_dictionary;
+/// # use lindera::token::conjugation::{Auxiliary, Conjugator};
+/// let dictionary = load_dictionary("embedded://ipadic")?;
+/// let conjugator = Conjugator::new(&dictionary)?;
+/// let surface = conjugator.inflect(
+///     "書く",
+///     &[Auxiliary::Passive, Auxiliary::Polite, Auxiliary::Past],
+/// );
+/// assert_eq!(Some("書かれました".to_string()), surface);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct Conjugator<'a> {
+    dictionary: &'a Dictionary,
+    entries: HashMap<&'a str, Vec<ConjugatedEntry<'a>>>,
+}
+
+impl<'a> Conjugator<'a> {
+    /// Indexes the conjugated entries of the dictionary by base form.
+    ///
+    /// Returns an error if the dictionary has no conjugation, base form or reading fields.
+    pub fn new(dictionary: &'a Dictionary) -> LinderaResult<Self> {
+        let schema = &dictionary.metadata.dictionary_schema;
+        let custom_index = |field_names: &[&str]| {
+            field_names
+                .iter()
+                .find_map(|field_name| schema.get_field_index(field_name))
+                .filter(|index| *index >= 4)
+                .map(|index| index - 4)
+        };
+        let (Some((type_index, form_index)), Some(base_form_index), Some(reading_index)) = (
+            conjugation_field_indices(schema),
+            custom_index(&["base_form", "orthographic_base_form"]),
+            custom_index(&["reading"]),
+        ) else {
+            return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                "the dictionary has no conjugation, base form or reading fields"
+            )));
+        };
+
+        let mut entries: HashMap<&str, Vec<ConjugatedEntry>> = HashMap::new();
+        for word_id in 0..dictionary.num_entries() {
+            let details = dictionary.word_details(word_id);
+            let field = |index: usize| details.get(index).copied().unwrap_or("*");
+            if field(type_index) == "*" || field(base_form_index) == "*" {
+                continue;
+            }
+            entries
+                .entry(field(base_form_index))
+                .or_default()
+                .push(ConjugatedEntry {
+                    word_id: word_id as u32,
+                    major_pos: field(0),
+                    subcategory: field(1),
+                    conjugation_type: field(type_index),
+                    conjugation_form: field(form_index),
+                    reading: field(reading_index),
+                });
+        }
+
+        Ok(Self {
+            dictionary,
+            entries,
+        })
+    }
+
+    /// Returns the entry of the word with the base form, or of the auxiliary with the base form.
+    ///
+    /// A base form may be shared by an independent word and a suffix (`ない`, `いる`); the
+    /// independent word is preferred unless `auxiliary` is set.
+    fn word(&self, base_form: &str, auxiliary: bool) -> Option<&ConjugatedEntry<'a>> {
+        let entries = self.entries.get(base_form)?;
+        let is_auxiliary = |entry: &&ConjugatedEntry| {
+            entry.major_pos == "助動詞" || matches!(entry.subcategory, "接尾" | "非自立")
+        };
+        entries
+            .iter()
+            .find(|entry| is_auxiliary(entry) == auxiliary)
+            .or_else(|| entries.first())
+    }
+
+    /// Returns the surface of the word in the first of the conjugation forms it has.
+    fn surface(
+        &self,
+        base_form: &str,
+        word: &ConjugatedEntry,
+        conjugation_forms: &[&str],
+    ) -> Option<String> {
+        let entries = self.entries.get(base_form)?;
+        conjugation_forms.iter().find_map(|conjugation_form| {
+            let entry = entries.iter().find(|entry| {
+                entry.conjugation_type == word.conjugation_type
+                    && entry.conjugation_form == *conjugation_form
+            })?;
+            // The reading of the entry tells the kana only; the surface is the candidate that
+            // the dictionary holds for the entry
+            surface_candidates(base_form, entry.reading)
+                .into_iter()
+                .find(|surface| {
+                    self.dictionary
+                        .prefix_dictionary
+                        .find_surface_iter(surface)
+                        .any(|word_entry| word_entry.word_id.id == entry.word_id)
+                })
+        })
+    }
+
+    /// Returns the surface of the verb or the adjective in the conjugation form, as named by the
+    /// dictionary (e.g. `未然形` in IPADIC, `未然形-一般` in UniDic).
+    ///
+    /// Returns `None` if the dictionary has no such word or form.
+    pub fn conjugate(&self, base_form: &str, conjugation_form: &str) -> Option<String> {
+        let word = self.word(base_form, false)?;
+        self.surface(base_form, word, &[conjugation_form])
+    }
+
+    /// Returns the surface of the verb or the adjective followed by the auxiliaries, the last one
+    /// in its terminal form (`書く` + passive + polite + past → `書かれました`).
+    ///
+    /// This is the inverse of [`deconjugate`]. Returns `None` if the dictionary has no such word,
+    /// or if an auxiliary cannot follow the word before it.
+    pub fn inflect(&self, base_form: &str, auxiliaries: &[Auxiliary]) -> Option<String> {
+        let mut surface = String::new();
+        let mut base_form = base_form;
+        let mut word = self.word(base_form, false)?;
+        for auxiliary in auxiliaries {
+            let (conjugation_forms, following) =
+                auxiliary_rule(word.conjugation_type, word.major_pos, base_form, auxiliary)?;
+            surface.push_str(&self.surface(base_form, word, conjugation_forms)?);
+
+            let (last, particles) = following.split_last()?;
+            for particle in particles {
+                surface.push_str(particle);
+            }
+            // The entries are keyed by the base forms borrowed from the dictionary
+            let (key, _) = self.entries.get_key_value(*last)?;
+            base_form = key;
+            word = self.word(base_form, true)?;
+        }
+        surface.push_str(base_form);
+
+        Some(surface)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn morpheme(major_pos: &str, subcategory: &str, base_form: &str) -> Morpheme {
+        Morpheme {
+            major_pos: major_pos.to_string(),
+            subcategory: subcategory.to_string(),
+            base_form: base_form.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_conjugation_form_parse() {
+        assert_eq!(ConjugationForm::Terminal, ConjugationForm::parse("基本形"));
+        assert_eq!(
+            ConjugationForm::Terminal,
+            ConjugationForm::parse("終止形-一般")
+        );
+        assert_eq!(
+            ConjugationForm::Terminal,
+            ConjugationForm::parse("音便基本形")
+        );
+        assert_eq!(
+            ConjugationForm::Continuative,
+            ConjugationForm::parse("連用タ接続")
+        );
+        assert_eq!(
+            ConjugationForm::Continuative,
+            ConjugationForm::parse("連用形-促音便")
+        );
+        assert_eq!(
+            ConjugationForm::Irrealis,
+            ConjugationForm::parse("未然レル接続")
+        );
+        assert_eq!(
+            ConjugationForm::Conditional,
+            ConjugationForm::parse("仮定縮約１")
+        );
+        assert_eq!(
+            ConjugationForm::Conditional,
+            ConjugationForm::parse("已然形-一般")
+        );
+        assert_eq!(
+            ConjugationForm::Imperative,
+            ConjugationForm::parse("命令ｅ")
+        );
+        assert_eq!(
+            ConjugationForm::Attributive,
+            ConjugationForm::parse("体言接続")
+        );
+        assert_eq!(ConjugationForm::Stem, ConjugationForm::parse("ガル接続"));
+        assert_eq!(ConjugationForm::Other, ConjugationForm::parse("ク語法"));
+    }
+
+    #[test]
+    fn test_auxiliary_from_base_form() {
+        assert_eq!(Auxiliary::Passive, Auxiliary::from_base_form("られる"));
+        assert_eq!(Auxiliary::Causative, Auxiliary::from_base_form("させる"));
+        assert_eq!(Auxiliary::Negative, Auxiliary::from_base_form("ん"));
+        assert_eq!(Auxiliary::Past, Auxiliary::from_base_form("だ"));
+        assert_eq!(
+            Auxiliary::Other("らしい".to_string()),
+            Auxiliary::from_base_form("らしい")
+        );
+    }
+
+    #[test]
+    fn test_deconjugate_morphemes() {
+        // "された" in IPADIC: さ (する) | れ (れる, 動詞,接尾) | た
+        let morphemes = vec![
+            morpheme("動詞", "自立", "する"),
+            morpheme("動詞", "接尾", "れる"),
+            morpheme("助動詞", "*", "た"),
+            morpheme("記号", "句点", "。"),
+        ];
+        assert_eq!(
+            Some(Deconjugation {
+                base_form: "する".to_string(),
+                auxiliaries: vec![Auxiliary::Passive, Auxiliary::Past],
+                len: 3,
+            }),
+            deconjugate_morphemes(&morphemes)
+        );
+
+        // "読んでいません": 読ん | で | い | ませ | ん
+        let morphemes = vec![
+            morpheme("動詞", "自立", "読む"),
+            morpheme("助詞", "接続助詞", "で"),
+            morpheme("動詞", "非自立", "いる"),
+            morpheme("助動詞", "*", "ます"),
+            morpheme("助動詞", "*", "ん"),
+        ];
+        assert_eq!(
+            Some(Deconjugation {
+                base_form: "読む".to_string(),
+                auxiliaries: vec![
+                    Auxiliary::Progressive,
+                    Auxiliary::Polite,
+                    Auxiliary::Negative
+                ],
+                len: 5,
+            }),
+            deconjugate_morphemes(&morphemes)
+        );
+
+        // "読んで" without a following いる ends at the verb
+        let morphemes = vec![
+            morpheme("動詞", "自立", "読む"),
+            morpheme("助詞", "接続助詞", "で"),
+        ];
+        assert_eq!(1, deconjugate_morphemes(&morphemes).unwrap().len);
+
+        // A noun has no conjugation
+        let morphemes = vec![
+            morpheme("名詞", "一般", "本"),
+            morpheme("助動詞", "*", "だ"),
+        ];
+        assert_eq!(None, deconjugate_morphemes(&morphemes));
+        assert_eq!(None, deconjugate_morphemes(&[]));
+    }
+
+    #[test]
+    fn test_surface_candidates() {
+        assert_eq!(
+            vec!["書かか".to_string(), "書か".to_string(), "書".to_string()],
+            surface_candidates("書く", "カカ")
+        );
+        assert_eq!(
+            vec!["取り扱わ".to_string(), "取り扱".to_string()],
+            surface_candidates("取り扱う", "ワ")
+        );
+        // The stem of 来る changes its reading: 来 (コ) is one of the candidates
+        assert!(surface_candidates("来る", "コ").contains(&"来".to_string()));
+        assert_eq!(vec!["さ".to_string()], surface_candidates("する", "サ"));
+    }
+
+    #[test]
+    fn test_auxiliary_rule() {
+        assert_eq!(
+            Some((&["未然形"][..], &["れる"][..])),
+            auxiliary_rule("五段・カ行イ音便", "動詞", "書く", &Auxiliary::Passive)
+        );
+        assert_eq!(
+            Some((&["未然レル接続"][..], &["れる"][..])),
+            auxiliary_rule("サ変・スル", "動詞", "する", &Auxiliary::Passive)
+        );
+        assert_eq!(
+            Some((&["未然形"][..], &["られる"][..])),
+            auxiliary_rule("一段", "動詞", "食べる", &Auxiliary::Passive)
+        );
+        assert_eq!(
+            Some((&["連用タ接続"][..], &["だ"][..])),
+            auxiliary_rule("五段・ガ行", "動詞", "泳ぐ", &Auxiliary::Past)
+        );
+        assert_eq!(
+            Some((&["連用形"][..], &["た"][..])),
+            auxiliary_rule("特殊・マス", "助動詞", "ます", &Auxiliary::Past)
+        );
+        assert_eq!(
+            Some((&["未然形"][..], &["ん"][..])),
+            auxiliary_rule("特殊・マス", "助動詞", "ます", &Auxiliary::Negative)
+        );
+        assert_eq!(
+            Some((&["連用テ接続"][..], &["ない"][..])),
+            auxiliary_rule("形容詞・アウオ段", "形容詞", "高い", &Auxiliary::Negative)
+        );
+        assert_eq!(
+            Some((&["連用タ接続"][..], &["で", "いる"][..])),
+            auxiliary_rule("五段・マ行", "動詞", "読む", &Auxiliary::Progressive)
+        );
+        // An adjective has no passive
+        assert_eq!(
+            None,
+            auxiliary_rule("形容詞・アウオ段", "形容詞", "高い", &Auxiliary::Passive)
+        );
+        assert_eq!(
+            None,
+            auxiliary_rule(
+                "五段・カ行イ音便",
+                "動詞",
+                "書く",
+                &Auxiliary::Other("らしい".to_string())
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_conjugator_ipadic() {
+        use crate::dictionary::load_dictionary;
+
+        let dictionary = load_dictionary("embedded://ipadic").unwrap();
+        let conjugator = Conjugator::new(&dictionary).unwrap();
+
+        assert_eq!(
+            Some("書かれました".to_string()),
+            conjugator.inflect(
+                "書く",
+                &[Auxiliary::Passive, Auxiliary::Polite, Auxiliary::Past]
+            )
+        );
+        assert_eq!(
+            Some("泳いでいる".to_string()),
+            conjugator.inflect("泳ぐ", &[Auxiliary::Progressive])
+        );
+        assert_eq!(
+            Some("しなかった".to_string()),
+            conjugator.inflect("する", &[Auxiliary::Negative, Auxiliary::Past])
+        );
+        assert_eq!(
+            Some("食べさせられる".to_string()),
+            conjugator.inflect("食べる", &[Auxiliary::Causative, Auxiliary::Passive])
+        );
+        assert_eq!(
+            Some("高かった".to_string()),
+            conjugator.inflect("高い", &[Auxiliary::Past])
+        );
+        assert_eq!(Some("書く".to_string()), conjugator.inflect("書く", &[]));
+        assert_eq!(
+            Some("来".to_string()),
+            conjugator.conjugate("来る", "未然形")
+        );
+        assert_eq!(
+            Some("書け".to_string()),
+            conjugator.conjugate("書く", "仮定形")
+        );
+
+        assert_eq!(None, conjugator.inflect("高い", &[Auxiliary::Passive]));
+        assert_eq!(None, conjugator.conjugate("書く", "存在しない形"));
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_deconjugate_ipadic() {
+        use crate::dictionary::load_dictionary;
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+        use crate::tokenizer::Tokenizer;
+
+        let dictionary = load_dictionary("embedded://ipadic").unwrap();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let tokenizer = Tokenizer::new(segmenter);
+
+        let mut tokens = tokenizer.tokenize("会議が中止された").unwrap();
+        let start = tokens
+            .iter_mut()
+            .position(|token| token.get_detail(0) == Some("動詞"))
+            .unwrap();
+        assert_eq!(Some("サ変・スル"), tokens[start].conjugation_type());
+        assert_eq!(
+            Some(ConjugationForm::Irrealis),
+            tokens[start].conjugation_form()
+        );
+
+        let deconjugation = deconjugate(&mut tokens[start..]).unwrap();
+        assert_eq!("する", deconjugation.base_form);
+        assert_eq!(
+            vec![Auxiliary::Passive, Auxiliary::Past],
+            deconjugation.auxiliaries
+        );
+        assert_eq!(tokens.len() - start, deconjugation.len);
+
+        // Nouns have no conjugation
+        assert_eq!(None, tokens[0].conjugation_type());
+        assert_eq!(None, tokens[0].conjugation_form());
+        assert_eq!(None, deconjugate(&mut tokens[..]));
+    }
+}
@@ -0,0 +1,160 @@
+//! A pool of interned detail strings, for pipelines that keep the details of millions of tokens.
+//!
+//! The details of a token borrow the dictionary, but a pipeline that keeps them beyond the
+//! lifetime of the tokens (a corpus index, the rows of a training set) has to own them, and
+//! copies the same few hundred part-of-speech and conjugation strings millions of times. A
+//! [`DetailPool`] stores each distinct string once and hands out [`Arc<str>`]s or [`DetailId`]s
+//! to it.
+//!
+//! Use one pool per dictionary: the details of a dictionary are drawn from a small vocabulary, so
+//! the pool stays small, while the surfaces and the readings are better left out of it.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// The id of a string in a [`DetailPool`], valid in that pool only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DetailId(pub u32);
+
+#[derive(Debug, Default)]
+struct Strings {
+    ids: HashMap<Arc<str>, DetailId>,
+    strings: Vec<Arc<str>>,
+}
+
+/// A thread-safe pool of interned strings. Interning a string already in the pool only takes a
+/// read lock.
+#[derive(Debug, Default)]
+pub struct DetailPool {
+    strings: RwLock<Strings>,
+}
+
+impl DetailPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of `value`, adding it to the pool if it is not there yet.
+    pub fn intern(&self, value: &str) -> DetailId {
+        self.intern_entry(value).0
+    }
+
+    /// Returns the shared copy of `value`, adding it to the pool if it is not there yet.
+    pub fn intern_arc(&self, value: &str) -> Arc<str> {
+        self.intern_entry(value).1
+    }
+
+    fn intern_entry(&self, value: &str) -> (DetailId, Arc<str>) {
+        {
+            let strings = self.strings.read().unwrap_or_else(|err| err.into_inner());
+            if let Some((string, id)) = strings.ids.get_key_value(value) {
+                return (*id, string.clone());
+            }
+        }
+
+        let mut strings = self.strings.write().unwrap_or_else(|err| err.into_inner());
+        // Another thread may have added it between the locks
+        if let Some((string, id)) = strings.ids.get_key_value(value) {
+            return (*id, string.clone());
+        }
+
+        let id = DetailId(strings.strings.len() as u32);
+        let string: Arc<str> = Arc::from(value);
+        strings.ids.insert(string.clone(), id);
+        strings.strings.push(string.clone());
+        (id, string)
+    }
+
+    /// Returns the string of `id`, or `None` if `id` is not from this pool.
+    pub fn resolve(&self, id: DetailId) -> Option<Arc<str>> {
+        let strings = self.strings.read().unwrap_or_else(|err| err.into_inner());
+        strings.strings.get(id.0 as usize).cloned()
+    }
+
+    /// The number of distinct strings in the pool.
+    pub fn len(&self) -> usize {
+        let strings = self.strings.read().unwrap_or_else(|err| err.into_inner());
+        strings.strings.len()
+    }
+
+    /// Returns `true` if the pool holds no strings.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::token::intern::{DetailId, DetailPool};
+
+    #[test]
+    fn test_detail_pool() {
+        let pool = DetailPool::new();
+        assert!(pool.is_empty());
+
+        assert_eq!(DetailId(0), pool.intern("名詞"));
+        assert_eq!(DetailId(1), pool.intern("一般"));
+        assert_eq!(DetailId(0), pool.intern("名詞"));
+        assert_eq!(2, pool.len());
+
+        let a = pool.intern_arc("一般");
+        let b = pool.intern_arc("一般");
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(Arc::ptr_eq(&a, &pool.resolve(DetailId(1)).unwrap()));
+        assert!(pool.resolve(DetailId(2)).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_interned_details_ipadic() {
+        use crate::dictionary::load_dictionary;
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+        use crate::tokenizer::Tokenizer;
+
+        let dictionary = load_dictionary("embedded://ipadic").unwrap();
+        let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+        let pool = DetailPool::new();
+
+        let mut tokens = tokenizer.tokenize("東京と大阪").unwrap();
+        let tokyo = tokens[0].interned_details(&pool);
+        let osaka = tokens[2].interned_details(&pool);
+        assert_eq!("名詞", tokyo[0].as_ref());
+        assert!(Arc::ptr_eq(&tokyo[0], &osaka[0]));
+
+        let ids = tokens[2].detail_ids(&pool);
+        assert_eq!(
+            tokens[2].details(),
+            ids.iter()
+                .map(|id| pool.resolve(*id).unwrap())
+                .collect::<Vec<_>>()
+                .iter()
+                .map(|detail| detail.as_ref())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_detail_pool_threads() {
+        let pool = Arc::new(DetailPool::new());
+        let handles = (0..4)
+            .map(|_| {
+                let pool = pool.clone();
+                std::thread::spawn(move || {
+                    (0..100)
+                        .map(|i| pool.intern(&(i % 10).to_string()))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        let ids = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(10, pool.len());
+        assert!(ids.windows(2).all(|pair| pair[0] == pair[1]));
+    }
+}
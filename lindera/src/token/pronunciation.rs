@@ -0,0 +1,331 @@
+//! Mora-level pronunciations of Japanese tokens, for the front-ends of text-to-speech.
+//!
+//! The pronunciation of a token is read from the `pronunciation` field of IPADIC or the
+//! `phonological_surface_form` field of UniDic, and its accent from the accent fields of
+//! UniDic-CWJ and UniDic-CSJ (see [`Token::accent_type`]). [`pronounce`] returns them with the
+//! morae of the pronunciation for each token of a text, and [`count_morae`] and
+//! [`count_syllables`] count the morae and the syllables of a text, as haiku detection,
+//! readability scores and speech timing do.
+
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::token::Token;
+
+/// Whether the kana is a small vowel or a small ya, yu, yo or wa, which forms a mora with the
+/// kana before it (`キョ`, `ファ`).
+fn is_combining(c: char) -> bool {
+    matches!(
+        c,
+        'ァ' | 'ィ'
+            | 'ゥ'
+            | 'ェ'
+            | 'ォ'
+            | 'ャ'
+            | 'ュ'
+            | 'ョ'
+            | 'ヮ'
+            | 'ぁ'
+            | 'ぃ'
+            | 'ぅ'
+            | 'ぇ'
+            | 'ぉ'
+            | 'ゃ'
+            | 'ゅ'
+            | 'ょ'
+            | 'ゎ'
+    )
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c as u32, 0x3041..=0x3096 | 0x30a1..=0x30fa | 0x30fc)
+}
+
+/// Splits a pronunciation in katakana or hiragana into morae: a kana with the small kana following
+/// it (`キョ`), and the long vowel mark `ー`, the sokuon `ッ` and the `ン` on their own.
+/// The characters that are not kana (punctuation, Latin letters) are not morae and are skipped.
+///
+/// # Example
+///
+/// ```
+/// use lindera::token::pronunciation::morae;
+///
+/// assert_eq!(vec!["ト", "ー", "キョ", "ー"], morae("トーキョー"));
+/// assert_eq!(vec!["ガ", "ッ", "コ", "ー"], morae("ガッコー"));
+/// ```
+pub fn morae(pronunciation: &str) -> Vec<&str> {
+    mora_ranges(pronunciation)
+        .into_iter()
+        .map(|range| &pronunciation[range])
+        .collect()
+}
+
+/// The byte ranges of the morae of `pronunciation`.
+fn mora_ranges(pronunciation: &str) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    // Whether the last range can take a small kana, i.e. no other character followed it
+    let mut open = false;
+    for (index, c) in pronunciation.char_indices() {
+        let end = index + c.len_utf8();
+        if !is_kana(c) {
+            open = false;
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(last) if open && is_combining(c) => last.end = end,
+            _ => ranges.push(index..end),
+        }
+        open = true;
+    }
+    ranges
+}
+
+fn is_special_mora(mora: &str) -> bool {
+    matches!(mora, "ー" | "ッ" | "っ" | "ン" | "ん")
+}
+
+/// Splits a pronunciation in kana into syllables: a mora with the long vowel mark `ー`, the
+/// sokuon `ッ` and the `ン` following it (`トー`, `ガッ`, `ホン`), which do not form syllables
+/// on their own. The vowel sequences (`アイ`) are counted as two syllables.
+///
+/// # Example
+///
+/// ```
+/// use lindera::token::pronunciation::syllables;
+///
+/// assert_eq!(vec!["トー", "キョー"], syllables("トーキョー"));
+/// assert_eq!(vec!["ガッ", "コー"], syllables("ガッコー"));
+/// ```
+pub fn syllables(pronunciation: &str) -> Vec<&str> {
+    let mut syllables: Vec<Range<usize>> = Vec::new();
+    for range in mora_ranges(pronunciation) {
+        match syllables.last_mut() {
+            // The special mora directly follows the last syllable
+            Some(last)
+                if last.end == range.start && is_special_mora(&pronunciation[range.clone()]) =>
+            {
+                last.end = range.end
+            }
+            _ => syllables.push(range),
+        }
+    }
+    syllables
+        .into_iter()
+        .map(|range| &pronunciation[range])
+        .collect()
+}
+
+/// The spoken form of a sequence of tokens, their pronunciations (or readings, or surfaces)
+/// concatenated.
+fn spoken_text(tokens: &mut [Token]) -> String {
+    tokens
+        .iter_mut()
+        .map(|token| {
+            token
+                .spoken_form()
+                .unwrap_or_else(|| token.surface.to_string())
+        })
+        .collect()
+}
+
+/// Returns the number of morae of the tokens, the sum of their [`Token::mora_count`].
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # #[cfg(feature = "embed-ipadic")]
+/// # {
+/// use lindera::dictionary::load_dictionary;
+/// use lindera::mode::Mode;
+/// use lindera::segmenter::Segmenter;
+/// use lindera::token::pronunciation::count_morae;
+/// use lindera::tokenizer::Tokenizer;
+///
+/// let dictionary = load_dictionary("embedded://ipadic")?;
+/// let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+///
+/// let mut tokens = tokenizer.tokenize("東京")?;
+/// // ト ー キョ ー
+/// assert_eq!(4, count_morae(&mut tokens));
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub fn count_morae(tokens: &mut [Token]) -> usize {
+    morae(&spoken_text(tokens)).len()
+}
+
+/// Returns the number of syllables of the tokens. The syllables are counted on the spoken form of
+/// all the tokens, so a token starting with `ン` or `ー` (the `ん` of `行くんだ`) extends the last
+/// syllable of the token before it.
+pub fn count_syllables(tokens: &mut [Token]) -> usize {
+    syllables(&spoken_text(tokens)).len()
+}
+
+/// The pronunciation of a token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Pronunciation {
+    pub surface: String,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    /// The pronunciation in katakana, or the reading if the dictionary has no pronunciation.
+    pub pronunciation: Option<String>,
+    pub morae: Vec<String>,
+    /// The mora after which the pitch falls, 0 for a word without a fall.
+    pub accent_type: Option<u8>,
+    pub accent_connection_type: Option<String>,
+    pub accent_modification_type: Option<String>,
+}
+
+/// Returns the pronunciation, the morae and the accent of each token.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # #[cfg(feature = "embed-ipadic")]
+/// # {
+/// use lindera::dictionary::load_dictionary;
+/// use lindera::mode::Mode;
+/// use lindera::segmenter::Segmenter;
+/// use lindera::token::pronunciation::pronounce;
+/// use lindera::tokenizer::Tokenizer;
+///
+/// let dictionary = load_dictionary("embedded://ipadic")?;
+/// let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+///
+/// let mut tokens = tokenizer.tokenize("東京へ行く")?;
+/// let pronunciations = pronounce(&mut tokens);
+/// assert_eq!(Some("トーキョー"), pronunciations[0].pronunciation.as_deref());
+/// for pronunciation in &pronunciations {
+///     println!("{} {:?} {:?}", pronunciation.surface, pronunciation.morae, pronunciation.accent_type);
+/// }
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub fn pronounce(tokens: &mut [Token]) -> Vec<Pronunciation> {
+    tokens
+        .iter_mut()
+        .map(|token| {
+            let pronunciation = token.spoken_form();
+            let morae = pronunciation
+                .as_deref()
+                .map(|pronunciation| {
+                    morae(pronunciation)
+                        .into_iter()
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default();
+            Pronunciation {
+                surface: token.surface.to_string(),
+                byte_start: token.byte_start,
+                byte_end: token.byte_end,
+                pronunciation,
+                morae,
+                accent_type: token.accent_type(),
+                accent_connection_type: token.accent_connection_type().map(str::to_string),
+                accent_modification_type: token.accent_modification_type().map(str::to_string),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use lindera_dictionary::dictionary::schema::Schema;
+
+    use super::*;
+    use crate::dictionary::Metadata;
+    use crate::mode::Mode;
+    use crate::segmenter::Segmenter;
+    use crate::test_util::TestDictionary;
+
+    #[test]
+    fn test_morae() {
+        assert_eq!(vec!["ト", "ー", "キョ", "ー"], morae("トーキョー"));
+        assert_eq!(vec!["ファ", "ン", "タ", "ジ", "ー"], morae("ファンタジー"));
+        assert_eq!(vec!["き", "っ", "て"], morae("きって"));
+        // A small kana at the start is a mora of its own, the punctuation is skipped
+        assert_eq!(vec!["ャ", "ア"], morae("ャ、ア"));
+        assert!(morae("").is_empty());
+    }
+
+    #[test]
+    fn test_syllables() {
+        assert_eq!(vec!["トー", "キョー"], syllables("トーキョー"));
+        assert_eq!(vec!["ニッ", "ポン"], syllables("ニッポン"));
+        assert_eq!(vec!["ア", "イ"], syllables("アイ"));
+        // A special mora after a character that is not a kana starts a syllable
+        assert_eq!(vec!["ア", "ン"], syllables("ア・ン"));
+    }
+
+    #[test]
+    fn test_pronounce() {
+        let metadata = Metadata {
+            dictionary_schema: Schema::new(
+                [
+                    "surface",
+                    "left_context_id",
+                    "right_context_id",
+                    "cost",
+                    "part_of_speech",
+                    "part_of_speech_subcategory_1",
+                    "reading",
+                    "phonological_surface_form",
+                    "accent_type",
+                    "accent_connection_type",
+                    "accent_modification_type",
+                ]
+                .map(str::to_string)
+                .to_vec(),
+            ),
+            ..Metadata::default()
+        };
+        let (_dict_dir, dictionary) = TestDictionary::new(
+            "\
+東京,0,0,100,名詞,固有名詞,トウキョウ,トーキョー,0,C1,*
+へ,0,0,100,助詞,格助詞,ヘ,エ,*,名詞%F1,*
+今日,0,0,100,名詞,普通名詞,キョウ,キョー,\"1,0\",C3,*
+",
+        )
+        .unk_def(
+            "DEFAULT,0,0,5000,名詞,普通名詞,*,*,*,*,*\nKANJI,0,0,5000,名詞,普通名詞,*,*,*,*,*\n",
+        )
+        .metadata(metadata)
+        .load();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let mut tokens = segmenter.segment(Cow::Borrowed("今日東京へ")).unwrap();
+        let pronunciations = pronounce(&mut tokens);
+        assert_eq!(
+            Pronunciation {
+                surface: "東京".to_string(),
+                byte_start: 6,
+                byte_end: 12,
+                pronunciation: Some("トーキョー".to_string()),
+                morae: ["ト", "ー", "キョ", "ー"].map(str::to_string).to_vec(),
+                accent_type: Some(0),
+                accent_connection_type: Some("C1".to_string()),
+                accent_modification_type: None,
+            },
+            pronunciations[1]
+        );
+        // The first of the accent types, the pronunciation rather than the reading
+        assert_eq!(Some(1), pronunciations[0].accent_type);
+        assert_eq!(Some("エ".to_string()), pronunciations[2].pronunciation);
+        assert_eq!(None, pronunciations[2].accent_type);
+        assert_eq!(Some("名詞%F1"), tokens[2].accent_connection_type());
+        assert_eq!(vec!["キョ", "ー"], tokens[0].morae());
+
+        assert_eq!(4, tokens[1].mora_count());
+        assert_eq!(2, tokens[1].syllable_count());
+        // キョー トーキョー エ
+        assert_eq!(7, count_morae(&mut tokens));
+        assert_eq!(4, count_syllables(&mut tokens));
+    }
+}
@@ -1,23 +1,30 @@
 /// This module defines various token filters and provides functionality to load them.
 ///
 /// # Modules
+/// - `cjk_width`: Contains the CJK width token filter.
+/// - `conditional`: Contains the conditional token filter.
 /// - `japanese_base_form`: Contains the Japanese base form token filter.
 /// - `japanese_compound_word`: Contains the Japanese compound word token filter.
 /// - `japanese_kana`: Contains the Japanese kana token filter.
 /// - `japanese_katakana_stem`: Contains the Japanese katakana stem token filter.
 /// - `japanese_keep_tags`: Contains the Japanese keep tags token filter.
 /// - `japanese_number`: Contains the Japanese number token filter.
+/// - `japanese_proper_noun`: Contains the Japanese proper noun token filter.
 /// - `japanese_reading_form`: Contains the Japanese reading form token filter.
 /// - `japanese_stop_tags`: Contains the Japanese stop tags token filter.
 /// - `keep_words`: Contains the keep words token filter.
+/// - `korean_decompound`: Contains the Korean decompound token filter.
 /// - `korean_keep_tags`: Contains the Korean keep tags token filter.
 /// - `korean_reading_form`: Contains the Korean reading form token filter.
 /// - `korean_stop_tags`: Contains the Korean stop tags token filter.
 /// - `length`: Contains the length token filter.
+/// - `limit_token_count`: Contains the limit token count token filter.
 /// - `lowercase`: Contains the lowercase token filter.
 /// - `mapping`: Contains the mapping token filter.
 /// - `remove_diacritical_mark`: Contains the remove diacritical mark token filter.
 /// - `stop_words`: Contains the stop words token filter.
+/// - `truncate`: Contains the truncate token filter.
+/// - `unique`: Contains the unique token filter.
 /// - `uppercase`: Contains the uppercase token filter.
 ///
 /// # Traits
@@ -32,23 +39,30 @@
 /// The `TokenFilterLoader` struct provides methods to load token filters from configuration values
 /// or command-line flags. The `TokenFilter` trait defines the interface for token filters, and
 /// `BoxTokenFilter` is a boxed implementation of a token filter.
+pub mod cjk_width;
+pub mod conditional;
 pub mod japanese_base_form;
 pub mod japanese_compound_word;
 pub mod japanese_kana;
 pub mod japanese_katakana_stem;
 pub mod japanese_keep_tags;
 pub mod japanese_number;
+pub mod japanese_proper_noun;
 pub mod japanese_reading_form;
 pub mod japanese_stop_tags;
 pub mod keep_words;
+pub mod korean_decompound;
 pub mod korean_keep_tags;
 pub mod korean_reading_form;
 pub mod korean_stop_tags;
 pub mod length;
+pub mod limit_token_count;
 pub mod lowercase;
 pub mod mapping;
 pub mod remove_diacritical_mark;
 pub mod stop_words;
+pub mod truncate;
+pub mod unique;
 pub mod uppercase;
 
 use serde_json::Value;
@@ -56,6 +70,8 @@ use std::ops::Deref;
 
 use crate::parse_cli_flag;
 use crate::token::Token;
+use crate::token_filter::cjk_width::{CJK_WIDTH_TOKEN_FILTER_NAME, CjkWidthTokenFilter};
+use crate::token_filter::conditional::{CONDITIONAL_TOKEN_FILTER_NAME, ConditionalTokenFilter};
 use crate::token_filter::japanese_base_form::{
     JAPANESE_BASE_FORM_TOKEN_FILTER_NAME, JapaneseBaseFormTokenFilter,
 };
@@ -74,6 +90,9 @@ use crate::token_filter::japanese_keep_tags::{
 use crate::token_filter::japanese_number::{
     JAPANESE_NUMBER_TOKEN_FILTER_NAME, JapaneseNumberTokenFilter,
 };
+use crate::token_filter::japanese_proper_noun::{
+    JAPANESE_PROPER_NOUN_TOKEN_FILTER_NAME, JapaneseProperNounTokenFilter,
+};
 use crate::token_filter::japanese_reading_form::{
     JAPANESE_READING_FORM_TOKEN_FILTER_NAME, JapaneseReadingFormTokenFilter,
 };
@@ -81,6 +100,9 @@ use crate::token_filter::japanese_stop_tags::{
     JAPANESE_STOP_TAGS_TOKEN_FILTER_NAME, JapaneseStopTagsTokenFilter,
 };
 use crate::token_filter::keep_words::{KEEP_WORDS_TOKEN_FILTER_NAME, KeepWordsTokenFilter};
+use crate::token_filter::korean_decompound::{
+    KOREAN_DECOMPOUND_TOKEN_FILTER_NAME, KoreanDecompoundTokenFilter,
+};
 use crate::token_filter::korean_keep_tags::{
     KOREAN_KEEP_TAGS_TOKEN_FILTER_NAME, KoreanKeepTagsTokenFilter,
 };
@@ -91,12 +113,17 @@ use crate::token_filter::korean_stop_tags::{
     KOREAN_STOP_TAGS_TOKEN_FILTER_NAME, KoreanStopTagsTokenFilter,
 };
 use crate::token_filter::length::{LENGTH_TOKEN_FILTER_NAME, LengthTokenFilter};
+use crate::token_filter::limit_token_count::{
+    LIMIT_TOKEN_COUNT_TOKEN_FILTER_NAME, LimitTokenCountTokenFilter,
+};
 use crate::token_filter::lowercase::{LOWERCASE_TOKEN_FILTER_NAME, LowercaseTokenFilter};
 use crate::token_filter::mapping::{MAPPING_TOKEN_FILTER_NAME, MappingTokenFilter};
 use crate::token_filter::remove_diacritical_mark::{
     REMOVE_DIACRITICAL_TOKEN_FILTER_NAME, RemoveDiacriticalMarkTokenFilter,
 };
 use crate::token_filter::stop_words::{STOP_WORDS_TOKEN_FILTER_NAME, StopWordsTokenFilter};
+use crate::token_filter::truncate::{TRUNCATE_TOKEN_FILTER_NAME, TruncateTokenFilter};
+use crate::token_filter::unique::{UNIQUE_TOKEN_FILTER_NAME, UniqueTokenFilter};
 use crate::token_filter::uppercase::{UPPERCASE_TOKEN_FILTER_NAME, UppercaseTokenFilter};
 use crate::{LinderaErrorKind, LinderaResult};
 
@@ -174,6 +201,12 @@ impl TokenFilterLoader {
         // - Returns `LinderaErrorKind::Deserialize` if the `kind` is not supported or if there is an
         //   error in creating the token filter from the provided `value`.
         let token_filter = match kind {
+            CJK_WIDTH_TOKEN_FILTER_NAME => {
+                BoxTokenFilter::from(CjkWidthTokenFilter::from_config(value)?)
+            }
+            CONDITIONAL_TOKEN_FILTER_NAME => {
+                BoxTokenFilter::from(ConditionalTokenFilter::from_config(value)?)
+            }
             JAPANESE_BASE_FORM_TOKEN_FILTER_NAME => {
                 BoxTokenFilter::from(JapaneseBaseFormTokenFilter::from_config(value)?)
             }
@@ -192,6 +225,9 @@ impl TokenFilterLoader {
             JAPANESE_NUMBER_TOKEN_FILTER_NAME => {
                 BoxTokenFilter::from(JapaneseNumberTokenFilter::from_config(value)?)
             }
+            JAPANESE_PROPER_NOUN_TOKEN_FILTER_NAME => {
+                BoxTokenFilter::from(JapaneseProperNounTokenFilter::from_config(value)?)
+            }
             JAPANESE_READING_FORM_TOKEN_FILTER_NAME => {
                 BoxTokenFilter::from(JapaneseReadingFormTokenFilter::from_config(value)?)
             }
@@ -201,6 +237,9 @@ impl TokenFilterLoader {
             KEEP_WORDS_TOKEN_FILTER_NAME => {
                 BoxTokenFilter::from(KeepWordsTokenFilter::from_config(value)?)
             }
+            KOREAN_DECOMPOUND_TOKEN_FILTER_NAME => {
+                BoxTokenFilter::from(KoreanDecompoundTokenFilter::from_config(value)?)
+            }
             KOREAN_KEEP_TAGS_TOKEN_FILTER_NAME => {
                 BoxTokenFilter::from(KoreanKeepTagsTokenFilter::from_config(value)?)
             }
@@ -213,6 +252,9 @@ impl TokenFilterLoader {
             LENGTH_TOKEN_FILTER_NAME => {
                 BoxTokenFilter::from(LengthTokenFilter::from_config(value)?)
             }
+            LIMIT_TOKEN_COUNT_TOKEN_FILTER_NAME => {
+                BoxTokenFilter::from(LimitTokenCountTokenFilter::from_config(value)?)
+            }
             LOWERCASE_TOKEN_FILTER_NAME => {
                 BoxTokenFilter::from(LowercaseTokenFilter::from_config(value)?)
             }
@@ -225,6 +267,12 @@ impl TokenFilterLoader {
             STOP_WORDS_TOKEN_FILTER_NAME => {
                 BoxTokenFilter::from(StopWordsTokenFilter::from_config(value)?)
             }
+            TRUNCATE_TOKEN_FILTER_NAME => {
+                BoxTokenFilter::from(TruncateTokenFilter::from_config(value)?)
+            }
+            UNIQUE_TOKEN_FILTER_NAME => {
+                BoxTokenFilter::from(UniqueTokenFilter::from_config(value)?)
+            }
             UPPERCASE_TOKEN_FILTER_NAME => {
                 BoxTokenFilter::from(UppercaseTokenFilter::from_config(value)?)
             }
@@ -0,0 +1,263 @@
+use std::borrow::Cow;
+
+use serde_json::Value;
+
+use crate::LinderaResult;
+use crate::token::Token;
+use crate::token_filter::TokenFilter;
+
+pub const CJK_WIDTH_TOKEN_FILTER_NAME: &str = "cjk_width";
+
+pub type CjkWidthTokenFilterConfig = Value;
+
+/// Fields of the details folded in addition to the surface.
+const READING_FIELDS: &[&str] = &["reading", "pronunciation"];
+
+/// The full-width forms of the half-width katakana and punctuation, U+FF61 to U+FF9F.
+const HALF_WIDTH_KATAKANA: &str = "。「」、・ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン゛゜";
+
+const HALF_WIDTH_VOICED_MARK: char = '\u{ff9e}';
+const HALF_WIDTH_SEMI_VOICED_MARK: char = '\u{ff9f}';
+
+/// Katakana followed by their voiced forms in Unicode (`カ` U+30AB, `ガ` U+30AC).
+const VOICEABLE_KATAKANA: &str = "カキクケコサシスセソタチツテトハヒフヘホ";
+
+/// Katakana followed by their voiced and semi-voiced forms in Unicode (`ハ`, `バ`, `パ`).
+const SEMI_VOICEABLE_KATAKANA: &str = "ハヒフヘホ";
+
+/// Returns the katakana combined with a following voiced (`ﾞ`) or semi-voiced (`ﾟ`) mark, if
+/// there is one (`カ` and `ﾞ` to `ガ`, `ハ` and `ﾟ` to `パ`).
+fn combine_mark(kana: char, mark: char) -> Option<char> {
+    let offset = match mark {
+        HALF_WIDTH_VOICED_MARK if kana == 'ウ' => return Some('ヴ'),
+        HALF_WIDTH_VOICED_MARK if VOICEABLE_KATAKANA.contains(kana) => 1,
+        HALF_WIDTH_SEMI_VOICED_MARK if SEMI_VOICEABLE_KATAKANA.contains(kana) => 2,
+        _ => return None,
+    };
+    char::from_u32(kana as u32 + offset)
+}
+
+/// Folds the width of the characters of a text: full-width ASCII (`Ａ`, `１`, `！`) to ASCII, and
+/// half-width katakana (`ｶﾞｿﾘﾝ`) to full-width katakana, combining the voiced and semi-voiced
+/// marks with the preceding kana.
+///
+/// The text is borrowed as it is if there is nothing to fold.
+pub fn fold_width(text: &str) -> Cow<'_, str> {
+    if !text
+        .chars()
+        .any(|c| matches!(c, '\u{ff01}'..='\u{ff5e}' | '\u{ff61}'..='\u{ff9f}'))
+    {
+        return Cow::Borrowed(text);
+    }
+
+    let mut folded = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{ff01}'..='\u{ff5e}' => {
+                folded.push(char::from_u32(c as u32 - 0xfee0).unwrap_or(c));
+            }
+            '\u{ff61}'..='\u{ff9f}' => {
+                let kana = HALF_WIDTH_KATAKANA
+                    .chars()
+                    .nth((c as u32 - 0xff61) as usize)
+                    .unwrap_or(c);
+                match chars.peek().and_then(|mark| combine_mark(kana, *mark)) {
+                    Some(combined) => {
+                        folded.push(combined);
+                        chars.next();
+                    }
+                    None => folded.push(kana),
+                }
+            }
+            _ => folded.push(c),
+        }
+    }
+
+    Cow::Owned(folded)
+}
+
+/// Folds the width of the surface and the readings of the tokens, so that an index built from
+/// text that was not normalized matches queries written in either width.
+///
+/// Full-width ASCII is folded to ASCII and half-width katakana to full-width katakana, as the
+/// `unicode_normalize` character filter does with NFKC, but after tokenization: the text the
+/// tokenizer sees, and the offsets of the tokens, are left as they are. The `reading` and
+/// `pronunciation` fields are folded too, for the dictionaries that have them.
+#[derive(Clone, Debug)]
+pub struct CjkWidthTokenFilter {}
+
+impl CjkWidthTokenFilter {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn from_config(_config: &CjkWidthTokenFilterConfig) -> LinderaResult<Self> {
+        Ok(Self::new())
+    }
+}
+
+impl Default for CjkWidthTokenFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenFilter for CjkWidthTokenFilter {
+    fn name(&self) -> &'static str {
+        CJK_WIDTH_TOKEN_FILTER_NAME
+    }
+
+    fn apply(&self, tokens: &mut Vec<Token<'_>>) -> LinderaResult<()> {
+        for token in tokens.iter_mut() {
+            if let Cow::Owned(surface) = fold_width(&token.surface) {
+                token.surface = Cow::Owned(surface);
+            }
+
+            for field_name in READING_FIELDS {
+                let Some(index) = token
+                    .dictionary
+                    .metadata
+                    .dictionary_schema
+                    .get_field_index(field_name)
+                    .and_then(|index| index.checked_sub(4))
+                else {
+                    continue;
+                };
+                let folded = match token.get_detail(index).map(fold_width) {
+                    Some(Cow::Owned(folded)) => folded,
+                    _ => continue,
+                };
+                token.set_detail(index, Cow::Owned(folded));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_width() {
+        assert_eq!("ABC123!", fold_width("ＡＢＣ１２３！"));
+        assert_eq!("ガソリン", fold_width("ｶﾞｿﾘﾝ"));
+        assert_eq!("パピプペポ", fold_width("ﾊﾟﾋﾟﾌﾟﾍﾟﾎﾟ"));
+        assert_eq!("ヴァイオリン", fold_width("ｳﾞｧｲｵﾘﾝ"));
+        assert_eq!("ヅヂ", fold_width("ﾂﾞﾁﾞ"));
+        assert_eq!("「ラーメン」。", fold_width("｢ﾗｰﾒﾝ｣｡"));
+        assert_eq!("Lindera 1.0", fold_width("Ｌｉｎｄｅｒａ １.０"));
+
+        // Marks that cannot be combined are kept as full-width marks
+        assert_eq!("ア゛ッ゛", fold_width("ｱﾞｯﾞ"));
+        assert_eq!("カ゜", fold_width("ｶﾟ"));
+
+        // Text that needs no folding is borrowed
+        assert!(matches!(fold_width("東京タワー abc"), Cow::Borrowed(_)));
+        // The ideographic space is not full-width ASCII
+        assert_eq!("東京\u{3000}", fold_width("東京\u{3000}"));
+    }
+
+    #[test]
+    fn test_half_width_katakana_table() {
+        assert_eq!(0xff9f - 0xff61 + 1, HALF_WIDTH_KATAKANA.chars().count());
+        assert_eq!(Some('ヲ'), HALF_WIDTH_KATAKANA.chars().nth(0xff66 - 0xff61));
+        assert_eq!(Some('ン'), HALF_WIDTH_KATAKANA.chars().nth(0xff9d - 0xff61));
+    }
+
+    #[test]
+    fn test_combine_mark() {
+        let voiced = "カキクケコサシスセソタチツテトハヒフヘホ"
+            .chars()
+            .map(|kana| combine_mark(kana, HALF_WIDTH_VOICED_MARK))
+            .collect::<Option<String>>();
+        assert_eq!(
+            Some("ガギグゲゴザジズゼゾダヂヅデドバビブベボ".to_string()),
+            voiced
+        );
+        assert_eq!(None, combine_mark('ッ', HALF_WIDTH_VOICED_MARK));
+        assert_eq!(None, combine_mark('ナ', HALF_WIDTH_VOICED_MARK));
+        assert_eq!(Some('ポ'), combine_mark('ホ', HALF_WIDTH_SEMI_VOICED_MARK));
+        assert_eq!(None, combine_mark('カ', HALF_WIDTH_SEMI_VOICED_MARK));
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_cjk_width_token_filter_apply_ipadic() {
+        use crate::dictionary::{DictionaryKind, WordId, load_embedded_dictionary};
+        use lindera_dictionary::viterbi::LexType;
+
+        let filter = CjkWidthTokenFilter::new();
+
+        let dictionary = load_embedded_dictionary(DictionaryKind::IPADIC).unwrap();
+
+        let mut tokens: Vec<Token> = vec![
+            Token {
+                surface: Cow::Borrowed("ｶﾞｿﾘﾝ"),
+                byte_start: 0,
+                byte_end: 15,
+                position: 0,
+                position_length: 1,
+                word_id: WordId {
+                    id: 0,
+                    is_system: false,
+                    lex_type: LexType::User,
+                },
+                dictionary: &dictionary,
+                user_dictionary: None,
+                cost: None,
+                details: Some(vec![
+                    Cow::Borrowed("名詞"),
+                    Cow::Borrowed("一般"),
+                    Cow::Borrowed("*"),
+                    Cow::Borrowed("*"),
+                    Cow::Borrowed("*"),
+                    Cow::Borrowed("*"),
+                    Cow::Borrowed("ｶﾞｿﾘﾝ"),
+                    Cow::Borrowed("ｶﾞｿﾘﾝ"),
+                    Cow::Borrowed("ｶﾞｿﾘﾝ"),
+                ]),
+            },
+            Token {
+                surface: Cow::Borrowed("ＵＳＢ"),
+                byte_start: 15,
+                byte_end: 24,
+                position: 1,
+                position_length: 1,
+                word_id: WordId {
+                    id: 0,
+                    is_system: false,
+                    lex_type: LexType::Unknown,
+                },
+                dictionary: &dictionary,
+                user_dictionary: None,
+                cost: None,
+                details: Some(vec![Cow::Borrowed("UNK")]),
+            },
+        ];
+
+        filter.apply(&mut tokens).unwrap();
+
+        assert_eq!("ガソリン", tokens[0].surface);
+        // The base form is not a reading, and is kept as it is
+        assert_eq!(
+            vec![
+                "名詞",
+                "一般",
+                "*",
+                "*",
+                "*",
+                "*",
+                "ｶﾞｿﾘﾝ",
+                "ガソリン",
+                "ガソリン"
+            ],
+            tokens[0].details()
+        );
+        assert_eq!((0, 15), (tokens[0].byte_start, tokens[0].byte_end));
+        assert_eq!("USB", tokens[1].surface);
+        assert_eq!(vec!["UNK"], tokens[1].details());
+    }
+}
@@ -0,0 +1,380 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::LinderaResult;
+use crate::error::{LinderaError, LinderaErrorKind};
+use crate::token::Token;
+use crate::token_filter::{BoxTokenFilter, TokenFilter, TokenFilterLoader};
+
+pub const CONDITIONAL_TOKEN_FILTER_NAME: &str = "conditional";
+
+pub type ConditionalTokenFilterConfig = Value;
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub enum Script {
+    #[serde(rename = "latin")]
+    Latin,
+    #[serde(rename = "hiragana")]
+    Hiragana,
+    #[serde(rename = "katakana")]
+    Katakana,
+    #[serde(rename = "han")]
+    Han,
+    #[serde(rename = "hangul")]
+    Hangul,
+    #[serde(rename = "digit")]
+    Digit,
+}
+
+impl Script {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Script::Latin => "latin",
+            Script::Hiragana => "hiragana",
+            Script::Katakana => "katakana",
+            Script::Han => "han",
+            Script::Hangul => "hangul",
+            Script::Digit => "digit",
+        }
+    }
+
+    /// Returns `true` if the character is written in this script.
+    pub fn contains(&self, c: char) -> bool {
+        match self {
+            Script::Latin => {
+                // Basic Latin to Latin Extended-B, and fullwidth Latin letters
+                c.is_alphabetic()
+                    && matches!(c as u32, 0x0000..=0x024f | 0xff21..=0xff3a | 0xff41..=0xff5a)
+            }
+            // The prolonged sound mark is used with both kana
+            Script::Hiragana => matches!(c as u32, 0x3041..=0x309f) || c == 'ー',
+            Script::Katakana => {
+                matches!(c as u32, 0x30a0..=0x30ff | 0x31f0..=0x31ff | 0xff66..=0xff9f)
+            }
+            Script::Han => matches!(
+                c as u32,
+                0x3005 | 0x3006 | 0x3007 | 0x3400..=0x4dbf | 0x4e00..=0x9fff | 0xf900..=0xfaff | 0x20000..=0x3134f
+            ),
+            Script::Hangul => matches!(
+                c as u32,
+                0x1100..=0x11ff | 0x3130..=0x318f | 0xa960..=0xa97f | 0xac00..=0xd7af | 0xd7b0..=0xd7ff
+            ),
+            Script::Digit => c.is_numeric(),
+        }
+    }
+
+    /// Returns `true` if every character of the text is written in this script.
+    pub fn matches(&self, text: &str) -> bool {
+        !text.is_empty() && text.chars().all(|c| self.contains(c))
+    }
+}
+
+impl FromStr for Script {
+    type Err = LinderaError;
+    fn from_str(script: &str) -> Result<Self, Self::Err> {
+        match script {
+            "latin" => Ok(Script::Latin),
+            "hiragana" => Ok(Script::Hiragana),
+            "katakana" => Ok(Script::Katakana),
+            "han" => Ok(Script::Han),
+            "hangul" => Ok(Script::Hangul),
+            "digit" => Ok(Script::Digit),
+            _ => Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!("Invalid script"))),
+        }
+    }
+}
+
+/// The tokens a [`ConditionalTokenFilter`] applies its filter to.
+///
+/// A token matches when it satisfies every condition that is set.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TokenCondition {
+    /// Part-of-speech prefixes, e.g. `名詞,固有名詞`. The token matches if its details start
+    /// with any of them.
+    pub tags: Option<Vec<Vec<String>>>,
+    /// The script every character of the token text is written in.
+    pub script: Option<Script>,
+    /// Minimum number of characters of the token text.
+    pub min_length: Option<usize>,
+    /// Maximum number of characters of the token text.
+    pub max_length: Option<usize>,
+}
+
+impl TokenCondition {
+    pub fn from_config(config: &Value) -> LinderaResult<Self> {
+        let tags = match config.get("tags") {
+            Some(tags) => Some(
+                tags.as_array()
+                    .ok_or_else(|| {
+                        LinderaErrorKind::Deserialize
+                            .with_error(anyhow::anyhow!("tags must be an array"))
+                    })?
+                    .iter()
+                    .map(|tag| {
+                        tag.as_str()
+                            .ok_or_else(|| {
+                                LinderaErrorKind::Deserialize
+                                    .with_error(anyhow::anyhow!("tag must be string"))
+                            })
+                            .map(|tag| tag.split(',').map(str::to_string).collect())
+                    })
+                    .collect::<LinderaResult<Vec<Vec<String>>>>()?,
+            ),
+            None => None,
+        };
+        let script = match config.get("script") {
+            Some(script) => Some(Script::from_str(script.as_str().ok_or_else(|| {
+                LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!("invalid script config."))
+            })?)?),
+            None => None,
+        };
+        let get_length = |key: &str| -> LinderaResult<Option<usize>> {
+            config
+                .get(key)
+                .map(|value| {
+                    value.as_u64().map(|value| value as usize).ok_or_else(|| {
+                        LinderaErrorKind::Deserialize
+                            .with_error(anyhow::anyhow!("invalid {key} config."))
+                    })
+                })
+                .transpose()
+        };
+
+        Ok(Self {
+            tags,
+            script,
+            min_length: get_length("min_length")?,
+            max_length: get_length("max_length")?,
+        })
+    }
+
+    /// Returns `true` if the token satisfies the condition.
+    pub fn matches(&self, token: &mut Token) -> bool {
+        if let Some(script) = &self.script
+            && !script.matches(&token.surface)
+        {
+            return false;
+        }
+
+        let len = token.surface.chars().count();
+        if let Some(min_length) = self.min_length
+            && len < min_length
+        {
+            return false;
+        }
+        if let Some(max_length) = self.max_length
+            && len > max_length
+        {
+            return false;
+        }
+
+        if let Some(tags) = &self.tags {
+            let details = token.details();
+            return tags.iter().any(|tag| {
+                tag.len() <= details.len() && tag.iter().zip(details.iter()).all(|(t, d)| t == d)
+            });
+        }
+
+        true
+    }
+}
+
+/// Apply a token filter only to the tokens matching a [`TokenCondition`], and pass the other
+/// tokens through unchanged.
+///
+/// The filter is applied to each run of consecutive matching tokens, so filters that combine
+/// adjacent tokens still see them together.
+///
+pub struct ConditionalTokenFilter {
+    condition: TokenCondition,
+    filter: BoxTokenFilter,
+}
+
+impl ConditionalTokenFilter {
+    pub fn new(condition: TokenCondition, filter: BoxTokenFilter) -> Self {
+        Self { condition, filter }
+    }
+
+    pub fn from_config(config: &ConditionalTokenFilterConfig) -> LinderaResult<Self> {
+        let filter_config = config.get("filter").ok_or_else(|| {
+            LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!("missing filter config."))
+        })?;
+        let kind = filter_config["kind"].as_str().ok_or_else(|| {
+            LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!("invalid filter kind config."))
+        })?;
+        let filter = TokenFilterLoader::load_from_value(kind, &filter_config["args"])?;
+
+        let condition = match config.get("condition") {
+            Some(condition) => TokenCondition::from_config(condition)?,
+            None => TokenCondition::default(),
+        };
+
+        Ok(Self::new(condition, filter))
+    }
+}
+
+impl Clone for ConditionalTokenFilter {
+    fn clone(&self) -> Self {
+        Self {
+            condition: self.condition.clone(),
+            filter: self.filter.box_clone(),
+        }
+    }
+}
+
+impl TokenFilter for ConditionalTokenFilter {
+    fn name(&self) -> &'static str {
+        CONDITIONAL_TOKEN_FILTER_NAME
+    }
+
+    fn apply(&self, tokens: &mut Vec<Token<'_>>) -> LinderaResult<()> {
+        let mut filtered_tokens = Vec::with_capacity(tokens.len());
+        let mut run = Vec::new();
+
+        for mut token in tokens.drain(..) {
+            if self.condition.matches(&mut token) {
+                run.push(token);
+                continue;
+            }
+            if !run.is_empty() {
+                self.filter.apply(&mut run)?;
+                filtered_tokens.append(&mut run);
+            }
+            filtered_tokens.push(token);
+        }
+        if !run.is_empty() {
+            self.filter.apply(&mut run)?;
+            filtered_tokens.append(&mut run);
+        }
+
+        *tokens = filtered_tokens;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::token_filter::conditional::{
+        ConditionalTokenFilter, ConditionalTokenFilterConfig, Script,
+    };
+
+    #[test]
+    fn test_conditional_token_filter_config() {
+        let config_str = r#"
+            {
+                "condition": {
+                    "tags": ["名詞,固有名詞"],
+                    "script": "latin",
+                    "min_length": 2
+                },
+                "filter": {
+                    "kind": "lowercase",
+                    "args": {}
+                }
+            }
+            "#;
+        let config: ConditionalTokenFilterConfig = serde_json::from_str(config_str).unwrap();
+        let filter = ConditionalTokenFilter::from_config(&config).unwrap();
+        assert_eq!(filter.filter.name(), "lowercase");
+        assert_eq!(
+            filter.condition.tags,
+            Some(vec![vec!["名詞".to_string(), "固有名詞".to_string()]])
+        );
+        assert_eq!(filter.condition.script, Some(Script::Latin));
+        assert_eq!(filter.condition.min_length, Some(2));
+        assert_eq!(filter.condition.max_length, None);
+
+        let config_str = r#"
+            {
+                "condition": {
+                    "script": "latin"
+                }
+            }
+            "#;
+        let config: ConditionalTokenFilterConfig = serde_json::from_str(config_str).unwrap();
+        assert!(ConditionalTokenFilter::from_config(&config).is_err());
+
+        let config_str = r#"
+            {
+                "condition": {
+                    "script": "cyrillic"
+                },
+                "filter": {
+                    "kind": "lowercase"
+                }
+            }
+            "#;
+        let config: ConditionalTokenFilterConfig = serde_json::from_str(config_str).unwrap();
+        assert!(ConditionalTokenFilter::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_script_matches() {
+        assert!(Script::Latin.matches("Lindera"));
+        assert!(Script::Latin.matches("Ｌｉｎｄｅｒａ"));
+        assert!(Script::Latin.matches("café"));
+        assert!(!Script::Latin.matches("Lindera2"));
+        assert!(!Script::Latin.matches(""));
+        assert!(Script::Hiragana.matches("すもも"));
+        assert!(Script::Katakana.matches("スーパー"));
+        assert!(!Script::Katakana.matches("すもも"));
+        assert!(Script::Han.matches("東京都"));
+        assert!(Script::Han.matches("人々"));
+        assert!(Script::Hangul.matches("한국어"));
+        assert!(Script::Digit.matches("2024"));
+        assert!(!Script::Digit.matches("2024年"));
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_conditional_token_filter_apply_ipadic() {
+        use std::borrow::Cow;
+
+        use crate::dictionary::{DictionaryKind, WordId, load_embedded_dictionary};
+        use crate::token::Token;
+        use crate::token_filter::TokenFilter;
+
+        let config_str = r#"
+            {
+                "condition": {
+                    "script": "latin"
+                },
+                "filter": {
+                    "kind": "lowercase"
+                }
+            }
+            "#;
+        let config: ConditionalTokenFilterConfig = serde_json::from_str(config_str).unwrap();
+        let filter = ConditionalTokenFilter::from_config(&config).unwrap();
+
+        let dictionary = load_embedded_dictionary(DictionaryKind::IPADIC).unwrap();
+
+        let mut tokens: Vec<Token> = ["Rust", "と", "ＡＰＩ", "Ｐａｒｓｅｒ2"]
+            .iter()
+            .enumerate()
+            .map(|(position, surface)| Token {
+                surface: Cow::Borrowed(*surface),
+                byte_start: 0,
+                byte_end: surface.len(),
+                position,
+                position_length: 1,
+                word_id: WordId::default(),
+                dictionary: &dictionary,
+                user_dictionary: None,
+                cost: None,
+                details: Some(vec![Cow::Borrowed("UNK")]),
+            })
+            .collect();
+
+        filter.apply(&mut tokens).unwrap();
+
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(&tokens[0].surface, "rust");
+        assert_eq!(&tokens[1].surface, "と");
+        assert_eq!(&tokens[2].surface, "ａｐｉ");
+        assert_eq!(&tokens[3].surface, "Ｐａｒｓｅｒ2");
+    }
+}
@@ -89,6 +89,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("固有名詞"),
@@ -114,6 +115,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("助詞"),
                     Cow::Borrowed("格助詞"),
@@ -139,6 +141,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("動詞"),
                     Cow::Borrowed("自立"),
@@ -164,6 +167,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("助動詞"),
                     Cow::Borrowed("*"),
@@ -216,6 +220,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("固有名詞"),
@@ -249,6 +254,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("普通名詞"),
@@ -282,6 +288,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("助詞"),
                     Cow::Borrowed("格助詞"),
@@ -315,6 +322,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("動詞"),
                     Cow::Borrowed("非自立可能"),
@@ -348,6 +356,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("助動詞"),
                     Cow::Borrowed("*"),
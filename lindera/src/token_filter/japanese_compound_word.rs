@@ -285,6 +285,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("数"),
@@ -310,6 +311,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("数"),
@@ -335,6 +337,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("数"),
@@ -360,6 +363,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("接尾"),
@@ -385,6 +389,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("接尾"),
@@ -410,6 +415,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("接尾"),
@@ -435,6 +441,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("動詞"),
                     Cow::Borrowed("自立"),
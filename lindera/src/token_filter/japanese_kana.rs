@@ -249,6 +249,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("固有名詞"),
@@ -274,6 +275,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("サ変接続"),
@@ -299,6 +301,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![Cow::Borrowed("UNK")]),
             },
         ];
@@ -348,6 +351,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("固有名詞"),
@@ -373,6 +377,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("接尾"),
@@ -398,6 +403,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("固有名詞"),
@@ -423,6 +429,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("接尾"),
@@ -483,6 +490,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("固有名詞"),
@@ -508,6 +516,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("サ変接続"),
@@ -533,6 +542,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![Cow::Borrowed("UNK")]),
             },
         ];
@@ -582,6 +592,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("固有名詞"),
@@ -607,6 +618,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("接尾"),
@@ -632,6 +644,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("固有名詞"),
@@ -657,6 +670,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("接尾"),
@@ -717,6 +731,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("固有名詞"),
@@ -742,6 +757,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("接尾"),
@@ -767,6 +783,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("固有名詞"),
@@ -792,6 +809,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("接尾"),
@@ -852,6 +870,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("固有名詞"),
@@ -877,6 +896,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("固有名詞"),
@@ -902,6 +922,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("接尾"),
@@ -239,6 +239,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("一般"),
@@ -264,6 +265,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("一般"),
@@ -304,6 +304,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("一般"),
@@ -329,6 +330,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("助詞"),
                     Cow::Borrowed("係助詞"),
@@ -354,6 +356,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("一般"),
@@ -379,6 +382,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("助詞"),
                     Cow::Borrowed("係助詞"),
@@ -404,6 +408,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("一般"),
@@ -429,6 +434,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("助詞"),
                     Cow::Borrowed("連体化"),
@@ -454,6 +460,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("非自立"),
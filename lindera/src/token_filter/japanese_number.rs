@@ -942,6 +942,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("数"),
@@ -975,6 +976,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("数"),
@@ -1010,6 +1012,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("数"),
@@ -1044,6 +1047,7 @@ mod tests {
                     },
                     dictionary: &dictionary,
                     user_dictionary: None,
+                    cost: None,
                     details: Some(vec![
                         Cow::Borrowed("名詞"),
                         Cow::Borrowed("固有名詞"),
@@ -1069,6 +1073,7 @@ mod tests {
                     },
                     dictionary: &dictionary,
                     user_dictionary: None,
+                    cost: None,
                     details: Some(vec![
                         Cow::Borrowed("名詞"),
                         Cow::Borrowed("固有名詞"),
@@ -1122,6 +1127,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("数"),
@@ -1155,6 +1161,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("数"),
@@ -1190,6 +1197,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("数"),
@@ -1224,6 +1232,7 @@ mod tests {
                     },
                     dictionary: &dictionary,
                     user_dictionary: None,
+                    cost: None,
                     details: Some(vec![
                         Cow::Borrowed("名詞"),
                         Cow::Borrowed("固有名詞"),
@@ -1249,6 +1258,7 @@ mod tests {
                     },
                     dictionary: &dictionary,
                     user_dictionary: None,
+                    cost: None,
                     details: Some(vec![
                         Cow::Borrowed("名詞"),
                         Cow::Borrowed("固有名詞"),
@@ -0,0 +1,455 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::mem;
+
+use serde_json::Value;
+
+use crate::LinderaResult;
+use crate::error::LinderaErrorKind;
+use crate::token::Token;
+use crate::token_filter::TokenFilter;
+
+pub const JAPANESE_PROPER_NOUN_TOKEN_FILTER_NAME: &str = "japanese_proper_noun";
+
+pub type JapaneseProperNounTokenFilterConfig = Value;
+
+/// Categories of proper nouns merged by default: person names, places (IPADIC `地域`, UniDic
+/// `地名`) and organizations.
+const DEFAULT_CATEGORIES: &[&str] = &["人名", "地域", "地名", "組織"];
+
+const PERSON_CATEGORY: &str = "人名";
+const SURNAME: &str = "姓";
+const GIVEN_NAME: &str = "名";
+
+/// Fields that are concatenated when tokens are merged. The other fields, except the
+/// part-of-speech, are set to `*`.
+const CONCATENATED_FIELDS: &[&str] = &[
+    "base_form",
+    "reading",
+    "pronunciation",
+    "lexeme",
+    "orthographic_surface_form",
+    "phonological_surface_form",
+    "orthographic_base_form",
+    "phonological_base_form",
+];
+
+/// The part-of-speech of a token, if it is a proper noun: `(category, subcategory)`, e.g.
+/// `("人名", "姓")` for `名詞,固有名詞,人名,姓`.
+fn proper_noun_category<'a>(details: &[&'a str]) -> Option<(&'a str, &'a str)> {
+    match details {
+        ["名詞", "固有名詞", category, subcategory, ..] => Some((category, subcategory)),
+        _ => None,
+    }
+}
+
+/// Returns the ranges of tokens to merge into a single token.
+///
+/// `tokens` holds the part-of-speech (proper noun category and subcategory) of each token, and
+/// whether the token starts where the previous one ends. A person name is merged only from a
+/// surname followed by a given name; the other categories merge any run of tokens of the same
+/// category.
+fn merge_ranges(
+    tokens: &[(Option<(&str, &str)>, bool)],
+    categories: &HashSet<String>,
+) -> Vec<std::ops::Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let start = i;
+        i += 1;
+        let Some((category, _)) = tokens[start].0 else {
+            continue;
+        };
+        if !categories.contains(category) {
+            continue;
+        }
+
+        while i < tokens.len() {
+            let (Some((next_category, next_subcategory)), contiguous) = tokens[i] else {
+                break;
+            };
+            let (_, prev_subcategory) = tokens[i - 1].0.unwrap_or_default();
+            let mergeable = contiguous
+                && next_category == category
+                && (category != PERSON_CATEGORY
+                    || (prev_subcategory == SURNAME && next_subcategory == GIVEN_NAME));
+            if !mergeable {
+                break;
+            }
+            i += 1;
+        }
+
+        if i - start > 1 {
+            ranges.push(start..i);
+        }
+    }
+    ranges
+}
+
+/// Returns the details of the token merged from tokens with the given details.
+///
+/// `field_names` are the names of the detail fields. The part-of-speech is the common
+/// category of the tokens, with the subcategory kept if all tokens share it (`一般` otherwise).
+fn merge_details(details: &[Vec<&str>], field_names: &[&str]) -> Vec<String> {
+    let first = &details[0];
+    let mut merged = first
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match field_names.get(index) {
+            Some(name) if CONCATENATED_FIELDS.contains(name) => {
+                let values = details
+                    .iter()
+                    .map(|token_details| token_details.get(index).copied().unwrap_or("*"))
+                    .collect::<Vec<_>>();
+                if values.contains(&"*") {
+                    "*".to_string()
+                } else {
+                    values.concat()
+                }
+            }
+            _ if index < 3 => field.to_string(),
+            _ => "*".to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    if merged.len() > 3 {
+        let subcategory = first[3];
+        merged[3] = if details
+            .iter()
+            .all(|token_details| token_details.get(3) == Some(&subcategory))
+        {
+            subcategory.to_string()
+        } else {
+            "一般".to_string()
+        };
+    }
+    merged
+}
+
+/// Merges consecutive proper nouns that form a single named entity into one token, for indexes of
+/// entities.
+///
+/// A surname followed by a given name (`名詞,固有名詞,人名,姓` + `名詞,固有名詞,人名,名`) becomes a
+/// `名詞,固有名詞,人名,一般` token, and runs of place names (`地域`, `地名`) or organization names
+/// (`組織`) become a single token of the same category. The merged token spans the offsets and
+/// positions of the tokens it replaces; its reading, pronunciation and base form are concatenated.
+/// Only tokens that are adjacent in the text are merged.
+#[derive(Clone, Debug)]
+pub struct JapaneseProperNounTokenFilter {
+    categories: HashSet<String>,
+}
+
+impl JapaneseProperNounTokenFilter {
+    pub fn new(categories: HashSet<String>) -> Self {
+        Self { categories }
+    }
+
+    pub fn from_config(config: &JapaneseProperNounTokenFilterConfig) -> LinderaResult<Self> {
+        let categories = match config.get("categories") {
+            Some(categories) => categories
+                .as_array()
+                .ok_or_else(|| {
+                    LinderaErrorKind::Deserialize
+                        .with_error(anyhow::anyhow!("categories must be an array"))
+                })?
+                .iter()
+                .map(|v| {
+                    v.as_str()
+                        .ok_or_else(|| {
+                            LinderaErrorKind::Deserialize
+                                .with_error(anyhow::anyhow!("category must be a string"))
+                        })
+                        .map(|s| s.to_string())
+                })
+                .collect::<LinderaResult<HashSet<String>>>()?,
+            None => DEFAULT_CATEGORIES.iter().map(|s| s.to_string()).collect(),
+        };
+
+        Ok(Self::new(categories))
+    }
+}
+
+impl Default for JapaneseProperNounTokenFilter {
+    fn default() -> Self {
+        Self::new(DEFAULT_CATEGORIES.iter().map(|s| s.to_string()).collect())
+    }
+}
+
+impl TokenFilter for JapaneseProperNounTokenFilter {
+    fn name(&self) -> &'static str {
+        JAPANESE_PROPER_NOUN_TOKEN_FILTER_NAME
+    }
+
+    fn apply(&self, tokens: &mut Vec<Token<'_>>) -> LinderaResult<()> {
+        let owned_details = tokens
+            .iter_mut()
+            .map(|token| {
+                token
+                    .details()
+                    .iter()
+                    .map(|detail| detail.to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let details = owned_details
+            .iter()
+            .map(|token_details| token_details.iter().map(String::as_str).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let pos = details
+            .iter()
+            .enumerate()
+            .map(|(index, token_details)| {
+                let contiguous =
+                    index > 0 && tokens[index - 1].byte_end == tokens[index].byte_start;
+                (proper_noun_category(token_details), contiguous)
+            })
+            .collect::<Vec<_>>();
+        let ranges = merge_ranges(&pos, &self.categories);
+        if ranges.is_empty() {
+            return Ok(());
+        }
+
+        let mut new_tokens = Vec::with_capacity(tokens.len());
+        let mut ranges = ranges.into_iter().peekable();
+        for (index, token) in mem::take(tokens).into_iter().enumerate() {
+            let Some(range) = ranges.peek().filter(|range| range.contains(&index)) else {
+                new_tokens.push(token);
+                continue;
+            };
+            if index == range.start {
+                new_tokens.push(token);
+                continue;
+            }
+
+            // Append the token to the merged token
+            let Some(merged) = new_tokens.last_mut() else {
+                continue;
+            };
+            merged.surface = Cow::Owned(format!("{}{}", merged.surface, token.surface));
+            merged.byte_end = token.byte_end;
+            merged.position_length += token.position_length;
+
+            if index + 1 == range.end {
+                let field_names = merged
+                    .dictionary
+                    .metadata
+                    .dictionary_schema
+                    .fields
+                    .iter()
+                    .skip(4)
+                    .map(String::as_str)
+                    .collect::<Vec<_>>();
+                merged.details = Some(
+                    merge_details(&details[range.clone()], &field_names)
+                        .into_iter()
+                        .map(Cow::Owned)
+                        .collect(),
+                );
+                ranges.next();
+            }
+        }
+        *tokens = new_tokens;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn categories() -> HashSet<String> {
+        DEFAULT_CATEGORIES.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_japanese_proper_noun_token_filter_from_config() {
+        let config = serde_json::json!({});
+        let filter = JapaneseProperNounTokenFilter::from_config(&config).unwrap();
+        assert_eq!(categories(), filter.categories);
+
+        let config = serde_json::json!({"categories": ["人名"]});
+        let filter = JapaneseProperNounTokenFilter::from_config(&config).unwrap();
+        assert_eq!(1, filter.categories.len());
+
+        let config = serde_json::json!({"categories": "人名"});
+        assert!(JapaneseProperNounTokenFilter::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_proper_noun_category() {
+        assert_eq!(
+            Some(("人名", "姓")),
+            proper_noun_category(&["名詞", "固有名詞", "人名", "姓", "*"])
+        );
+        assert_eq!(None, proper_noun_category(&["名詞", "一般", "*", "*"]));
+        assert_eq!(None, proper_noun_category(&["UNK"]));
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        let surname = Some(("人名", "姓"));
+        let given_name = Some(("人名", "名"));
+        let place = Some(("地域", "一般"));
+        let country = Some(("地域", "国"));
+        let organization = Some(("組織", "*"));
+
+        // 山田|太郎|が|日本|東京|に|行く
+        let tokens = vec![
+            (surname, false),
+            (given_name, true),
+            (None, true),
+            (country, true),
+            (place, true),
+            (None, true),
+            (None, true),
+        ];
+        assert_eq!(vec![0..2, 3..5], merge_ranges(&tokens, &categories()));
+
+        // Two surnames, or a given name followed by a surname, are not one person
+        let tokens = vec![(surname, false), (surname, true), (given_name, true)];
+        assert_eq!(vec![1..3], merge_ranges(&tokens, &categories()));
+        let tokens = vec![(given_name, false), (surname, true)];
+        assert!(merge_ranges(&tokens, &categories()).is_empty());
+
+        // Tokens of different categories or that are not adjacent are not merged
+        let tokens = vec![(organization, false), (place, true), (place, false)];
+        assert!(merge_ranges(&tokens, &categories()).is_empty());
+
+        // Only the configured categories are merged
+        let tokens = vec![
+            (place, false),
+            (place, true),
+            (surname, true),
+            (given_name, true),
+        ];
+        let only_people = HashSet::from(["人名".to_string()]);
+        assert_eq!(vec![2..4], merge_ranges(&tokens, &only_people));
+    }
+
+    #[test]
+    fn test_merge_details() {
+        let field_names = vec![
+            "part_of_speech",
+            "part_of_speech_subcategory_1",
+            "part_of_speech_subcategory_2",
+            "part_of_speech_subcategory_3",
+            "conjugation_form",
+            "conjugation_type",
+            "base_form",
+            "reading",
+            "pronunciation",
+        ];
+        let details = vec![
+            vec![
+                "名詞",
+                "固有名詞",
+                "人名",
+                "姓",
+                "*",
+                "*",
+                "山田",
+                "ヤマダ",
+                "ヤマダ",
+            ],
+            vec![
+                "名詞",
+                "固有名詞",
+                "人名",
+                "名",
+                "*",
+                "*",
+                "太郎",
+                "タロウ",
+                "タロー",
+            ],
+        ];
+        assert_eq!(
+            vec![
+                "名詞",
+                "固有名詞",
+                "人名",
+                "一般",
+                "*",
+                "*",
+                "山田太郎",
+                "ヤマダタロウ",
+                "ヤマダタロー"
+            ],
+            merge_details(&details, &field_names)
+        );
+
+        // The subcategory is kept if it is shared; an unknown reading makes the reading unknown
+        let details = vec![
+            vec![
+                "名詞",
+                "固有名詞",
+                "組織",
+                "*",
+                "*",
+                "*",
+                "国連",
+                "コクレン",
+                "コクレン",
+            ],
+            vec![
+                "名詞",
+                "固有名詞",
+                "組織",
+                "*",
+                "*",
+                "*",
+                "ＸＹＺ",
+                "*",
+                "*",
+            ],
+        ];
+        assert_eq!(
+            vec![
+                "名詞",
+                "固有名詞",
+                "組織",
+                "*",
+                "*",
+                "*",
+                "国連ＸＹＺ",
+                "*",
+                "*"
+            ],
+            merge_details(&details, &field_names)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_japanese_proper_noun_token_filter_apply_ipadic() {
+        use crate::dictionary::load_dictionary;
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+        use crate::token_filter::BoxTokenFilter;
+        use crate::tokenizer::Tokenizer;
+
+        let dictionary = load_dictionary("embedded://ipadic").unwrap();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let mut tokenizer = Tokenizer::new(segmenter);
+        tokenizer.append_token_filter(BoxTokenFilter::from(
+            JapaneseProperNounTokenFilter::default(),
+        ));
+
+        let text = "山田太郎は東京に住んでいる";
+        let mut tokens = tokenizer.tokenize(text).unwrap();
+        let first = &mut tokens[0];
+        assert_eq!("山田太郎", first.surface);
+        assert_eq!((0, 12), (first.byte_start, first.byte_end));
+        assert_eq!(2, first.position_length);
+        assert_eq!(
+            vec!["名詞", "固有名詞", "人名", "一般"],
+            first.details()[..4].to_vec()
+        );
+        assert_eq!("ヤマダタロウ", first.details()[7]);
+        assert_eq!("は", tokens[1].surface);
+        assert_eq!(2, tokens[1].position);
+    }
+}
@@ -0,0 +1,430 @@
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::LinderaResult;
+use crate::error::{LinderaError, LinderaErrorKind};
+use crate::token::Token;
+use crate::token_filter::TokenFilter;
+
+pub const KOREAN_DECOMPOUND_TOKEN_FILTER_NAME: &str = "korean_decompound";
+
+pub type KoreanDecompoundTokenFilterConfig = Value;
+
+/// How the compound and inflected words are output, as the `decompound_mode` of Nori.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum DecompoundMode {
+    /// Output the morphemes of the words, without the words themselves.
+    #[serde(rename = "discard")]
+    Discard,
+    /// Output the words followed by their morphemes.
+    #[serde(rename = "mixed")]
+    Mixed,
+}
+
+impl DecompoundMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DecompoundMode::Discard => "discard",
+            DecompoundMode::Mixed => "mixed",
+        }
+    }
+}
+
+impl FromStr for DecompoundMode {
+    type Err = LinderaError;
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode {
+            "discard" => Ok(DecompoundMode::Discard),
+            "mixed" => Ok(DecompoundMode::Mixed),
+            _ => Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                "Invalid decompound mode: {mode} (expected discard or mixed)"
+            ))),
+        }
+    }
+}
+
+/// A morpheme of the `expression` column of ko-dic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Morpheme<'a> {
+    pub surface: &'a str,
+    pub tag: &'a str,
+    /// The semantic class of the morpheme, `None` for `*`.
+    pub semantic: Option<&'a str>,
+}
+
+/// Parses the `expression` column of ko-dic, the morphemes of a compound, inflected or
+/// pre-analyzed word: `surface/tag/semantic` separated by `+`, as in `가/VV/*+았/EP/*`. The
+/// semantic class is missing in the records of the older releases (`가/VV+았/EP`).
+///
+/// Returns `None` for `*` and for the expressions that are not in this format.
+pub fn parse_expression(expression: &str) -> Option<Vec<Morpheme<'_>>> {
+    if expression.is_empty() || expression == "*" {
+        return None;
+    }
+
+    expression
+        .split('+')
+        .map(|morpheme| {
+            let mut fields = morpheme.split('/');
+            let surface = fields.next().filter(|surface| !surface.is_empty())?;
+            let tag = fields.next().filter(|tag| !tag.is_empty())?;
+            let semantic = fields.next().filter(|semantic| *semantic != "*");
+            Some(Morpheme {
+                surface,
+                tag,
+                semantic,
+            })
+        })
+        .collect()
+}
+
+/// The tag of a word that a part-of-speech filter matches: the tag of its first morpheme for the
+/// compound tags of the inflected words (`VV` for `VV+EC`), as Nori does.
+pub fn leading_tag(tag: &str) -> &str {
+    tag.split('+').next().unwrap_or(tag)
+}
+
+/// Split the compound (`Compound`), inflected (`Inflect`) and pre-analyzed (`Preanalysis`) words
+/// of ko-dic into the morphemes of their `expression` column, as the Nori tokenizer of Lucene
+/// does.
+///
+/// The morphemes of a compound word keep their offsets in the text. The morphemes of the other
+/// words are not substrings of the text (`감싸여` is `감싸이` and `어`), so each has the offsets
+/// of the whole word. In the `mixed` mode the word is kept before its morphemes, and spans their
+/// positions.
+///
+#[derive(Clone, Debug)]
+pub struct KoreanDecompoundTokenFilter {
+    mode: DecompoundMode,
+}
+
+impl KoreanDecompoundTokenFilter {
+    pub fn new(mode: DecompoundMode) -> Self {
+        Self { mode }
+    }
+
+    pub fn from_config(config: &KoreanDecompoundTokenFilterConfig) -> LinderaResult<Self> {
+        let mode = match config.get("mode") {
+            Some(mode) => DecompoundMode::from_str(mode.as_str().ok_or_else(|| {
+                LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!("mode must be string"))
+            })?)?,
+            None => DecompoundMode::Discard,
+        };
+
+        Ok(Self::new(mode))
+    }
+
+    /// The morphemes of a token as tokens, or `None` if it is not decompounded.
+    fn decompound<'a>(&self, token: &mut Token<'a>) -> Option<Vec<Token<'a>>> {
+        let is_compound = match token.get("type")? {
+            "Compound" => true,
+            "Inflect" | "Preanalysis" => false,
+            _ => return None,
+        };
+        let expression = token.get("expression")?.to_string();
+        let morphemes = parse_expression(&expression)?;
+
+        // The morphemes of a compound word are laid out in it, unless they do not spell it
+        let tiled = is_compound
+            && morphemes
+                .iter()
+                .map(|morpheme| morpheme.surface)
+                .collect::<String>()
+                == token.surface;
+        let schema = &token.dictionary.metadata.dictionary_schema;
+        let mut byte_start = token.byte_start;
+        let parts = morphemes
+            .iter()
+            .map(|morpheme| {
+                let details = schema
+                    .get_custom_fields()
+                    .iter()
+                    .map(|field| {
+                        Cow::Owned(
+                            match field.as_str() {
+                                "part_of_speech_tag"
+                                | "first_part_of_speech"
+                                | "last_part_of_speech" => morpheme.tag,
+                                "meaning" => morpheme.semantic.unwrap_or("*"),
+                                "reading" => morpheme.surface,
+                                _ => "*",
+                            }
+                            .to_string(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut part = token.clone();
+                part.surface = Cow::Owned(morpheme.surface.to_string());
+                if tiled {
+                    part.byte_start = byte_start;
+                    byte_start += morpheme.surface.len();
+                    part.byte_end = byte_start;
+                }
+                part.position_length = 1;
+                part.cost = None;
+                part.details = Some(details);
+                part
+            })
+            .collect();
+
+        Some(parts)
+    }
+}
+
+impl TokenFilter for KoreanDecompoundTokenFilter {
+    fn name(&self) -> &'static str {
+        KOREAN_DECOMPOUND_TOKEN_FILTER_NAME
+    }
+
+    /// Replaces the compound, inflected and pre-analyzed words with their morphemes, or inserts
+    /// the morphemes after them in the `mixed` mode.
+    ///
+    /// The morphemes take consecutive positions from the position of the word, and the positions
+    /// of the following tokens are shifted accordingly.
+    fn apply(&self, tokens: &mut Vec<Token<'_>>) -> LinderaResult<()> {
+        let mut decompounded = Vec::with_capacity(tokens.len());
+        // The positions added by the morphemes so far
+        let mut shift = 0;
+
+        for mut token in tokens.drain(..) {
+            token.position += shift;
+            match self.decompound(&mut token) {
+                Some(mut parts) => {
+                    let count = parts.len();
+                    for (offset, part) in parts.iter_mut().enumerate() {
+                        part.position = token.position + offset;
+                    }
+                    if self.mode == DecompoundMode::Mixed {
+                        token.position_length = count;
+                        decompounded.push(token);
+                    }
+                    decompounded.extend(parts);
+                    shift += count - 1;
+                }
+                None => decompounded.push(token),
+            }
+        }
+
+        *tokens = decompounded;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lindera_dictionary::dictionary::schema::Schema;
+    use serde_json::json;
+
+    use crate::dictionary::{Dictionary, Metadata};
+    use crate::mode::Mode;
+    use crate::segmenter::Segmenter;
+    use crate::test_util::TestDictionary;
+    use crate::token_filter::korean_decompound::{
+        DecompoundMode, KoreanDecompoundTokenFilter, Morpheme, parse_expression,
+    };
+    use crate::token_filter::korean_stop_tags::KoreanStopTagsTokenFilter;
+    use crate::tokenizer::Tokenizer;
+
+    /// A dictionary with the schema of ko-dic and the words of the examples.
+    fn build_dictionary() -> (tempfile::TempDir, Dictionary) {
+        let metadata = Metadata {
+            dictionary_schema: Schema::new(
+                [
+                    "surface",
+                    "left_context_id",
+                    "right_context_id",
+                    "cost",
+                    "part_of_speech_tag",
+                    "meaning",
+                    "presence_absence",
+                    "reading",
+                    "type",
+                    "first_part_of_speech",
+                    "last_part_of_speech",
+                    "expression",
+                ]
+                .map(String::from)
+                .to_vec(),
+            ),
+            ..Metadata::default()
+        };
+        TestDictionary::new(
+            "가락지나물,0,0,0,NNG,*,T,가락지나물,Compound,*,*,가락지/NNG/*+나물/NNG/*\n\
+             은,0,0,0,JX,*,T,은,*,*,*,*\n\
+             감싸여,0,0,0,VV+EC,*,F,감싸여,Inflect,VV,EC,감싸이/VV/*+어/EC/*\n\
+             뿌리,0,0,0,NNG,*,F,뿌리,*,*,*,*\n\
+             가,0,0,0,JKS,*,F,가,*,*,*,*\n\
+             깊은,0,0,0,VA+ETM,*,T,깊은,Inflect,VA,ETM,깊/VA/*+은/ETM/*\n\
+             나무,0,0,0,NNG,*,F,나무,*,*,*,*\n\
+             는,0,0,0,JX,*,T,는,*,*,*,*\n",
+        )
+        .char_def("DEFAULT 0 1 0\nSPACE 0 1 0\nHANGUL 0 1 2\n0x0020 SPACE\n0xAC00..0xD7A3 HANGUL\n")
+        .unk_def(
+            "DEFAULT,0,0,1000,SL,*,*,*,*,*,*,*\nSPACE,0,0,1000,SP,*,*,*,*,*,*,*\nHANGUL,0,0,1000,NNG,*,*,*,*,*,*,*\n",
+        )
+        .metadata(metadata)
+        .load()
+    }
+
+    /// The surface, tag, offsets and position of the tokens, for the comparison with the output
+    /// of Nori.
+    type Analysis<'a> = Vec<(&'a str, &'a str, usize, usize, usize, usize)>;
+
+    fn analyze(
+        tokenizer: &Tokenizer,
+        text: &str,
+    ) -> Vec<(String, String, usize, usize, usize, usize)> {
+        tokenizer
+            .tokenize(text)
+            .unwrap()
+            .iter_mut()
+            .map(|token| {
+                (
+                    token.surface.to_string(),
+                    token.get_detail(0).unwrap().to_string(),
+                    token.byte_start,
+                    token.byte_end,
+                    token.position,
+                    token.position_length,
+                )
+            })
+            .collect()
+    }
+
+    fn assert_analysis(
+        expected: Analysis,
+        actual: Vec<(String, String, usize, usize, usize, usize)>,
+    ) {
+        let actual = actual
+            .iter()
+            .map(|(surface, tag, start, end, position, length)| {
+                (
+                    surface.as_str(),
+                    tag.as_str(),
+                    *start,
+                    *end,
+                    *position,
+                    *length,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_expression() {
+        assert_eq!(
+            Some(vec![
+                Morpheme {
+                    surface: "가락지",
+                    tag: "NNG",
+                    semantic: None
+                },
+                Morpheme {
+                    surface: "나물",
+                    tag: "NNG",
+                    semantic: Some("식물")
+                },
+            ]),
+            parse_expression("가락지/NNG/*+나물/NNG/식물")
+        );
+        // The older releases have no semantic class
+        assert_eq!(
+            Some(vec![
+                Morpheme {
+                    surface: "가",
+                    tag: "VV",
+                    semantic: None
+                },
+                Morpheme {
+                    surface: "았",
+                    tag: "EP",
+                    semantic: None
+                },
+            ]),
+            parse_expression("가/VV+았/EP")
+        );
+        assert_eq!(None, parse_expression("*"));
+        assert_eq!(None, parse_expression("가/VV+"));
+    }
+
+    #[test]
+    fn test_korean_decompound_token_filter_config() {
+        let filter = KoreanDecompoundTokenFilter::from_config(&json!({})).unwrap();
+        assert_eq!(DecompoundMode::Discard, filter.mode);
+        let filter = KoreanDecompoundTokenFilter::from_config(&json!({"mode": "mixed"})).unwrap();
+        assert_eq!(DecompoundMode::Mixed, filter.mode);
+        assert!(KoreanDecompoundTokenFilter::from_config(&json!({"mode": "none"})).is_err());
+    }
+
+    /// The outputs of Nori for the examples of its documentation and tests.
+    #[test]
+    fn test_korean_decompound_token_filter_apply() {
+        let (_dict_dir, dictionary) = build_dictionary();
+        let mut tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+        tokenizer
+            .append_token_filter(KoreanDecompoundTokenFilter::new(DecompoundMode::Discard).into());
+
+        // The parts of a compound word keep their offsets
+        assert_analysis(
+            vec![
+                ("가락지", "NNG", 0, 9, 0, 1),
+                ("나물", "NNG", 9, 15, 1, 1),
+                ("은", "JX", 15, 18, 2, 1),
+            ],
+            analyze(&tokenizer, "가락지나물은"),
+        );
+        // The morphemes of an inflected word span the word
+        assert_analysis(
+            vec![("감싸이", "VV", 0, 9, 0, 1), ("어", "EC", 0, 9, 1, 1)],
+            analyze(&tokenizer, "감싸여"),
+        );
+        assert_analysis(
+            vec![
+                ("뿌리", "NNG", 0, 6, 0, 1),
+                ("가", "JKS", 6, 9, 1, 1),
+                ("깊", "VA", 10, 16, 2, 1),
+                ("은", "ETM", 10, 16, 3, 1),
+                ("나무", "NNG", 17, 23, 4, 1),
+                ("는", "JX", 23, 26, 5, 1),
+            ],
+            analyze(&tokenizer, "뿌리가 깊은 나무는"),
+        );
+
+        let mut tokenizer = tokenizer.clone();
+        tokenizer.token_filters.clear();
+        tokenizer
+            .append_token_filter(KoreanDecompoundTokenFilter::new(DecompoundMode::Mixed).into());
+        assert_analysis(
+            vec![
+                ("가락지나물", "NNG", 0, 15, 0, 2),
+                ("가락지", "NNG", 0, 9, 0, 1),
+                ("나물", "NNG", 9, 15, 1, 1),
+                ("은", "JX", 15, 18, 2, 1),
+            ],
+            analyze(&tokenizer, "가락지나물은"),
+        );
+
+        // The compound tags match on their first tag
+        tokenizer.token_filters.clear();
+        tokenizer.append_token_filter(
+            KoreanStopTagsTokenFilter::from_config(&json!({"tags": ["VA", "JX"]}))
+                .unwrap()
+                .into(),
+        );
+        assert_eq!(
+            vec!["뿌리", "가", "나무"],
+            tokenizer
+                .tokenize("뿌리가 깊은 나무는")
+                .unwrap()
+                .iter()
+                .map(|token| token.surface.as_ref())
+                .collect::<Vec<_>>()
+        );
+    }
+}
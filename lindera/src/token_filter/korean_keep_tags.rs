@@ -6,12 +6,14 @@ use crate::LinderaResult;
 use crate::error::LinderaErrorKind;
 use crate::token::Token;
 use crate::token_filter::TokenFilter;
+use crate::token_filter::korean_decompound::leading_tag;
 
 pub const KOREAN_KEEP_TAGS_TOKEN_FILTER_NAME: &str = "korean_keep_tags";
 
 pub type KoreanKeepTagsTokenFilterConfig = Value;
 
 /// Keep only tokens with the specified part-of-speech tag.
+/// The compound tags of the inflected words match on their first tag (`VV+EC` on `VV`).
 ///
 #[derive(Clone, Debug)]
 pub struct KoreanKeepTagsTokenFilter {
@@ -42,6 +44,11 @@ impl KoreanKeepTagsTokenFilter {
 
         Ok(Self::new(tags))
     }
+
+    /// Whether a tag is one of the tags, or a compound tag whose first tag is (`VV+EC` for `VV`).
+    fn matches(&self, tag: &str) -> bool {
+        self.tags.contains(tag) || self.tags.contains(leading_tag(tag))
+    }
 }
 
 impl TokenFilter for KoreanKeepTagsTokenFilter {
@@ -64,7 +71,7 @@ impl TokenFilter for KoreanKeepTagsTokenFilter {
     /// 1. **Token Filtering**:
     ///    - The function iterates over each token and retrieves the first part-of-speech tag using `get_detail(0)`.
     ///    - If the first tag is `None`, a default empty string is used instead.
-    ///    - The function then checks if the tag is present in the configured set of tags (`self.config.tags`).
+    ///    - The function then checks if the tag, or the first tag of a compound tag such as `VV+EC`, is present in the configured set of tags (`self.config.tags`).
     ///
     /// 2. **Token Retention**:
     ///    - Tokens whose part-of-speech tags are in the configuration are retained, while others are removed from the `tokens` vector.
@@ -86,7 +93,7 @@ impl TokenFilter for KoreanKeepTagsTokenFilter {
             let tag = token.get_detail(0).unwrap_or_default();
 
             // Add the token to the filtered_tokens vector if the tag is in the set of tags.
-            if self.tags.contains(tag) {
+            if self.matches(tag) {
                 filtered_tokens.push(token);
             }
         }
@@ -168,6 +175,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("NNG"),
                     Cow::Borrowed("*"),
@@ -192,6 +200,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("JKG"),
                     Cow::Borrowed("*"),
@@ -216,6 +225,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("NNG"),
                     Cow::Borrowed("*"),
@@ -240,6 +250,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("NNG"),
                     Cow::Borrowed("행위"),
@@ -264,6 +275,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("JKO"),
                     Cow::Borrowed("*"),
@@ -288,6 +300,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("VV+ETM"),
                     Cow::Borrowed("*"),
@@ -312,6 +325,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("NNG"),
                     Cow::Borrowed("*"),
@@ -336,6 +350,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("VX"),
                     Cow::Borrowed("*"),
@@ -360,6 +375,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("EF"),
                     Cow::Borrowed("*"),
@@ -84,6 +84,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("NNG"),
                     Cow::Borrowed("*"),
@@ -108,6 +109,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("JKG"),
                     Cow::Borrowed("*"),
@@ -132,6 +134,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("NNG"),
                     Cow::Borrowed("*"),
@@ -156,6 +159,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("NNG"),
                     Cow::Borrowed("행위"),
@@ -180,6 +184,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("JKO"),
                     Cow::Borrowed("*"),
@@ -204,6 +209,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("VV+ETM"),
                     Cow::Borrowed("*"),
@@ -228,6 +234,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("NNG"),
                     Cow::Borrowed("*"),
@@ -252,6 +259,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("VX"),
                     Cow::Borrowed("*"),
@@ -276,6 +284,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("EF"),
                     Cow::Borrowed("*"),
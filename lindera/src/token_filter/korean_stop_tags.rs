@@ -6,12 +6,14 @@ use crate::LinderaResult;
 use crate::error::LinderaErrorKind;
 use crate::token::Token;
 use crate::token_filter::TokenFilter;
+use crate::token_filter::korean_decompound::leading_tag;
 
 pub const KOREAN_STOP_TAGS_TOKEN_FILTER_NAME: &str = "korean_stop_tags";
 
 pub type KoreanStopTagsTokenFilterConfig = Value;
 
 /// Remove tokens with the specified part-of-speech tag.
+/// The compound tags of the inflected words match on their first tag (`VV+EC` on `VV`).
 ///
 #[derive(Clone, Debug)]
 pub struct KoreanStopTagsTokenFilter {
@@ -42,6 +44,11 @@ impl KoreanStopTagsTokenFilter {
 
         Ok(Self::new(tags))
     }
+
+    /// Whether a tag is one of the tags, or a compound tag whose first tag is (`VV+EC` for `VV`).
+    fn matches(&self, tag: &str) -> bool {
+        self.tags.contains(tag) || self.tags.contains(leading_tag(tag))
+    }
 }
 
 impl TokenFilter for KoreanStopTagsTokenFilter {
@@ -59,7 +66,7 @@ impl TokenFilter for KoreanStopTagsTokenFilter {
             let tag = token.get_detail(0).unwrap_or_default();
 
             // Add the token to the filtered tokens vector if the part-of-speech tag is not in the config.
-            if !self.tags.contains(tag) {
+            if !self.matches(tag) {
                 filtered_tokens.push(token);
             }
         }
@@ -228,6 +235,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("NNG"),
                     Cow::Borrowed("*"),
@@ -252,6 +260,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("JKG"),
                     Cow::Borrowed("*"),
@@ -276,6 +285,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("NNG"),
                     Cow::Borrowed("*"),
@@ -300,6 +310,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("NNG"),
                     Cow::Borrowed("행위"),
@@ -324,6 +335,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("JKO"),
                     Cow::Borrowed("*"),
@@ -348,6 +360,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("VV+ETM"),
                     Cow::Borrowed("*"),
@@ -372,6 +385,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("NNG"),
                     Cow::Borrowed("*"),
@@ -396,6 +410,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("VX"),
                     Cow::Borrowed("*"),
@@ -420,6 +435,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("EF"),
                     Cow::Borrowed("*"),
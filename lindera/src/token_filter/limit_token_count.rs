@@ -0,0 +1,128 @@
+use serde_json::Value;
+
+use crate::LinderaResult;
+use crate::error::LinderaErrorKind;
+use crate::token::Token;
+use crate::token_filter::TokenFilter;
+
+pub const LIMIT_TOKEN_COUNT_TOKEN_FILTER_NAME: &str = "limit_token_count";
+
+pub type LimitTokenCountTokenFilterConfig = Value;
+
+/// Keep only the first `max_token_count` tokens of the text, and drop the rest.
+///
+/// This bounds the number of tokens indexed for a single document or field, however long the
+/// input is.
+///
+#[derive(Clone, Debug)]
+pub struct LimitTokenCountTokenFilter {
+    max_token_count: usize,
+}
+
+impl LimitTokenCountTokenFilter {
+    pub fn new(max_token_count: usize) -> Self {
+        Self { max_token_count }
+    }
+
+    pub fn from_config(config: &LimitTokenCountTokenFilterConfig) -> LinderaResult<Self> {
+        let max_token_count = config
+            .get("max_token_count")
+            .ok_or_else(|| {
+                LinderaErrorKind::Deserialize
+                    .with_error(anyhow::anyhow!("missing max_token_count config."))
+            })?
+            .as_u64()
+            .ok_or_else(|| {
+                LinderaErrorKind::Deserialize
+                    .with_error(anyhow::anyhow!("invalid max_token_count config."))
+            })?;
+
+        Ok(Self::new(max_token_count as usize))
+    }
+}
+
+impl TokenFilter for LimitTokenCountTokenFilter {
+    fn name(&self) -> &'static str {
+        LIMIT_TOKEN_COUNT_TOKEN_FILTER_NAME
+    }
+
+    fn apply(&self, tokens: &mut Vec<Token<'_>>) -> LinderaResult<()> {
+        tokens.truncate(self.max_token_count);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::token_filter::limit_token_count::{
+        LimitTokenCountTokenFilter, LimitTokenCountTokenFilterConfig,
+    };
+
+    #[test]
+    fn test_limit_token_count_token_filter_config() {
+        let config_str = r#"
+            {
+                "max_token_count": 2
+            }
+            "#;
+        let config: LimitTokenCountTokenFilterConfig = serde_json::from_str(config_str).unwrap();
+        let result = LimitTokenCountTokenFilter::from_config(&config);
+        assert!(result.is_ok());
+
+        let config: LimitTokenCountTokenFilterConfig = serde_json::from_str("{}").unwrap();
+        let result = LimitTokenCountTokenFilter::from_config(&config);
+        assert!(result.is_err());
+
+        let config_str = r#"
+            {
+                "max_token_count": -1
+            }
+            "#;
+        let config: LimitTokenCountTokenFilterConfig = serde_json::from_str(config_str).unwrap();
+        let result = LimitTokenCountTokenFilter::from_config(&config);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_limit_token_count_token_filter_apply_ipadic() {
+        use std::borrow::Cow;
+
+        use crate::dictionary::{DictionaryKind, WordId, load_embedded_dictionary};
+        use crate::token::Token;
+        use crate::token_filter::TokenFilter;
+        use lindera_dictionary::viterbi::LexType;
+
+        let filter = LimitTokenCountTokenFilter::new(2);
+
+        let dictionary = load_embedded_dictionary(DictionaryKind::IPADIC).unwrap();
+
+        let mut tokens: Vec<Token> = ["すもも", "も", "もも"]
+            .iter()
+            .enumerate()
+            .map(|(position, surface)| Token {
+                surface: Cow::Borrowed(*surface),
+                byte_start: 0,
+                byte_end: surface.len(),
+                position,
+                position_length: 1,
+                word_id: WordId {
+                    id: 4294967295,
+                    is_system: true,
+                    lex_type: LexType::System,
+                },
+                dictionary: &dictionary,
+                user_dictionary: None,
+                cost: None,
+                details: Some(vec![Cow::Borrowed("UNK")]),
+            })
+            .collect();
+
+        filter.apply(&mut tokens).unwrap();
+
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(&tokens[0].surface, "すもも");
+        assert_eq!(&tokens[1].surface, "も");
+    }
+}
@@ -75,6 +75,7 @@ mod tests {
             },
             dictionary: &dictionary,
             user_dictionary: None,
+            cost: None,
             details: Some(vec![Cow::Borrowed("UNK")]),
         }];
 
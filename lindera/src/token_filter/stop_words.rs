@@ -1,9 +1,14 @@
 use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+use std::time::SystemTime;
 
+use log::warn;
 use serde_json::Value;
 
 use crate::LinderaResult;
-use crate::error::LinderaErrorKind;
+use crate::error::{LinderaError, LinderaErrorKind};
 use crate::token::Token;
 use crate::token_filter::TokenFilter;
 
@@ -11,36 +16,188 @@ pub const STOP_WORDS_TOKEN_FILTER_NAME: &str = "stop_words";
 
 pub type StopWordsTokenFilterConfig = Value;
 
+/// Parses a stop word list: one word per line, with `#` starting a comment that runs to the end
+/// of the line. Leading and trailing whitespace, blank lines and a byte order mark at the start
+/// of the text are ignored.
+pub fn parse_stop_words(text: &str) -> HashSet<String> {
+    text.strip_prefix('\u{feff}')
+        .unwrap_or(text)
+        .lines()
+        .map(|line| line.split_once('#').map_or(line, |(word, _)| word).trim())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Loads a stop word list file in the format of [`parse_stop_words`].
+pub fn load_stop_words(path: &Path) -> LinderaResult<HashSet<String>> {
+    let text = fs::read_to_string(path).map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to read stop words: {}", path.display()))
+    })?;
+
+    Ok(parse_stop_words(&text))
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// A stop word list file that is loaded again when it is modified.
+#[derive(Debug)]
+struct StopWordsFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    words: HashSet<String>,
+}
+
+impl StopWordsFile {
+    fn load(path: PathBuf) -> LinderaResult<Self> {
+        let modified = modified(&path);
+        let words = load_stop_words(&path)?;
+
+        Ok(Self {
+            path,
+            modified,
+            words,
+        })
+    }
+
+    fn is_modified(&self) -> bool {
+        modified(&self.path) != self.modified
+    }
+
+    /// Loads the file again. The previous words are kept if the file cannot be read, so a file
+    /// that is being replaced does not empty the list.
+    fn reload(&mut self) {
+        let modified = modified(&self.path);
+        match load_stop_words(&self.path) {
+            Ok(words) => {
+                self.words = words;
+                self.modified = modified;
+            }
+            Err(err) => warn!("Failed to reload stop words: {err}"),
+        }
+    }
+}
+
 /// Remove the tokens of the specified text.
 ///
+/// The words are given inline, or loaded from stop word list files (see [`parse_stop_words`]
+/// for the format). With `reload`, the files are checked before each use and loaded again when
+/// they have been modified, so the lists can be edited without rebuilding the tokenizer.
+///
 #[derive(Clone, Debug)]
 pub struct StopWordsTokenFilter {
     words: HashSet<String>,
+    files: Vec<Arc<RwLock<StopWordsFile>>>,
 }
 
 impl StopWordsTokenFilter {
     pub fn new(words: HashSet<String>) -> Self {
-        Self { words }
+        Self {
+            words,
+            files: Vec::new(),
+        }
     }
 
-    pub fn from_config(config: &StopWordsTokenFilterConfig) -> LinderaResult<Self> {
-        let words: HashSet<String> = config["words"]
-            .as_array()
-            .ok_or_else(|| {
-                LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!("words is required"))
-            })?
+    /// Creates a filter removing the inline `words` and the words of the files at `paths`.
+    ///
+    /// Without `reload`, the files are read once here; otherwise they are loaded again whenever
+    /// they are modified.
+    pub fn from_paths(
+        mut words: HashSet<String>,
+        paths: &[PathBuf],
+        reload: bool,
+    ) -> LinderaResult<Self> {
+        let mut files = Vec::new();
+        for path in paths {
+            if reload {
+                files.push(Arc::new(RwLock::new(StopWordsFile::load(path.clone())?)));
+            } else {
+                words.extend(load_stop_words(path)?);
+            }
+        }
+
+        Ok(Self { words, files })
+    }
+
+    /// Loads the modified files again, and returns the current lists of the files.
+    fn refresh_files(&self) -> LinderaResult<Vec<RwLockReadGuard<'_, StopWordsFile>>> {
+        fn poisoned<T>(_: T) -> LinderaError {
+            LinderaErrorKind::Content.with_error(anyhow::anyhow!("stop words lock is poisoned"))
+        }
+        for file in &self.files {
+            if file.read().map_err(poisoned)?.is_modified() {
+                file.write().map_err(poisoned)?.reload();
+            }
+        }
+
+        self.files
             .iter()
-            .map(|v| {
-                v.as_str()
-                    .ok_or_else(|| {
+            .map(|file| file.read().map_err(poisoned))
+            .collect()
+    }
+
+    pub fn from_config(config: &StopWordsTokenFilterConfig) -> LinderaResult<Self> {
+        let words = match config.get("words") {
+            Some(words) => words
+                .as_array()
+                .ok_or_else(|| {
+                    LinderaErrorKind::Deserialize
+                        .with_error(anyhow::anyhow!("words must be an array"))
+                })?
+                .iter()
+                .map(|v| {
+                    v.as_str()
+                        .ok_or_else(|| {
+                            LinderaErrorKind::Deserialize
+                                .with_error(anyhow::anyhow!("words must be string"))
+                        })
+                        .map(|s| s.to_string())
+                })
+                .collect::<LinderaResult<HashSet<String>>>()?,
+            None => HashSet::new(),
+        };
+
+        let paths = match config.get("paths") {
+            Some(paths) => paths
+                .as_array()
+                .ok_or_else(|| {
+                    LinderaErrorKind::Deserialize
+                        .with_error(anyhow::anyhow!("paths must be an array"))
+                })?
+                .iter()
+                .map(|v| {
+                    v.as_str().map(PathBuf::from).ok_or_else(|| {
                         LinderaErrorKind::Deserialize
-                            .with_error(anyhow::anyhow!("words must be string"))
+                            .with_error(anyhow::anyhow!("paths must be string"))
                     })
-                    .map(|s| s.to_string())
+                })
+                .collect::<LinderaResult<Vec<PathBuf>>>()?,
+            None => Vec::new(),
+        };
+
+        if config.get("words").is_none() && paths.is_empty() {
+            return Err(LinderaErrorKind::Deserialize
+                .with_error(anyhow::anyhow!("words or paths is required")));
+        }
+
+        let reload = config
+            .get("reload")
+            .map(|reload| {
+                reload.as_bool().ok_or_else(|| {
+                    LinderaErrorKind::Deserialize
+                        .with_error(anyhow::anyhow!("reload must be a boolean"))
+                })
             })
-            .collect::<LinderaResult<HashSet<String>>>()?;
+            .transpose()?
+            .unwrap_or(false);
 
-        Ok(Self::new(words))
+        Self::from_paths(words, &paths, reload)
     }
 }
 
@@ -50,7 +207,16 @@ impl TokenFilter for StopWordsTokenFilter {
     }
 
     fn apply(&self, tokens: &mut Vec<Token<'_>>) -> LinderaResult<()> {
-        tokens.retain(|token| !self.words.contains(token.surface.as_ref()));
+        if self.files.is_empty() {
+            tokens.retain(|token| !self.words.contains(token.surface.as_ref()));
+            return Ok(());
+        }
+
+        let files = self.refresh_files()?;
+        tokens.retain(|token| {
+            let surface = token.surface.as_ref();
+            !self.words.contains(surface) && !files.iter().any(|file| file.words.contains(surface))
+        });
 
         Ok(())
     }
@@ -58,6 +224,7 @@ impl TokenFilter for StopWordsTokenFilter {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::token_filter::stop_words::{StopWordsTokenFilter, StopWordsTokenFilterConfig};
 
     #[test]
@@ -90,6 +257,94 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_parse_stop_words() {
+        let words =
+            parse_stop_words("\u{feff}# Japanese stop words\nも\n\n  の  \nは # topic marker\n");
+
+        assert_eq!(
+            HashSet::from(["も".to_string(), "の".to_string(), "は".to_string()]),
+            words
+        );
+    }
+
+    #[test]
+    fn test_stop_words_token_filter_from_paths() {
+        let dir = std::env::temp_dir().join(format!(
+            "lindera-stop-words-{}-from-paths",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let particles = dir.join("particles.txt");
+        let auxiliaries = dir.join("auxiliaries.txt");
+        fs::write(&particles, "# particles\nも\nの\n").unwrap();
+        fs::write(&auxiliaries, "です\n").unwrap();
+
+        let config = serde_json::json!({
+            "words": ["うち"],
+            "paths": [particles.to_str().unwrap(), auxiliaries.to_str().unwrap()]
+        });
+        let filter = StopWordsTokenFilter::from_config(&config).unwrap();
+        assert_eq!(
+            HashSet::from([
+                "うち".to_string(),
+                "も".to_string(),
+                "の".to_string(),
+                "です".to_string()
+            ]),
+            filter.words
+        );
+        assert!(filter.files.is_empty());
+
+        // A missing file is an error
+        let config = serde_json::json!({ "paths": [dir.join("missing.txt")] });
+        assert!(StopWordsTokenFilter::from_config(&config).is_err());
+        // Either words or paths is required
+        assert!(StopWordsTokenFilter::from_config(&serde_json::json!({})).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_stop_words_token_filter_reload() {
+        let dir =
+            std::env::temp_dir().join(format!("lindera-stop-words-{}-reload", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("stop_words.txt");
+        fs::write(&path, "も\n").unwrap();
+
+        let filter =
+            StopWordsTokenFilter::from_paths(HashSet::new(), std::slice::from_ref(&path), true)
+                .unwrap();
+        assert_eq!(
+            HashSet::from(["も".to_string()]),
+            filter.refresh_files().unwrap()[0].words
+        );
+
+        // The file is loaded again once it is modified
+        fs::write(&path, "の\n").unwrap();
+        let modified = SystemTime::now() + std::time::Duration::from_secs(10);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        assert_eq!(
+            HashSet::from(["の".to_string()]),
+            filter.refresh_files().unwrap()[0].words
+        );
+
+        // The previous words are kept while the file is missing
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            HashSet::from(["の".to_string()]),
+            filter.refresh_files().unwrap()[0].words
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     #[cfg(feature = "embed-ipadic")]
     fn test_stop_words_token_filter_apply_ipadic() {
@@ -127,6 +382,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("一般"),
@@ -152,6 +408,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("助詞"),
                     Cow::Borrowed("係助詞"),
@@ -177,6 +434,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("一般"),
@@ -202,6 +460,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("助詞"),
                     Cow::Borrowed("係助詞"),
@@ -227,6 +486,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("一般"),
@@ -252,6 +512,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("助詞"),
                     Cow::Borrowed("連体化"),
@@ -277,6 +538,7 @@ mod tests {
                 },
                 dictionary: &dictionary,
                 user_dictionary: None,
+                cost: None,
                 details: Some(vec![
                     Cow::Borrowed("名詞"),
                     Cow::Borrowed("非自立"),
@@ -0,0 +1,139 @@
+use std::borrow::Cow;
+
+use serde_json::Value;
+
+use crate::LinderaResult;
+use crate::error::LinderaErrorKind;
+use crate::token::Token;
+use crate::token_filter::TokenFilter;
+
+pub const TRUNCATE_TOKEN_FILTER_NAME: &str = "truncate";
+
+pub type TruncateTokenFilterConfig = Value;
+
+/// Truncate the text of tokens longer than `length` characters to their first `length`
+/// characters.
+///
+/// The byte offsets of truncated tokens still cover the whole surface in the original text.
+///
+#[derive(Clone, Debug)]
+pub struct TruncateTokenFilter {
+    length: usize,
+}
+
+impl TruncateTokenFilter {
+    pub fn new(length: usize) -> Self {
+        Self { length }
+    }
+
+    pub fn from_config(config: &TruncateTokenFilterConfig) -> LinderaResult<Self> {
+        let length = config
+            .get("length")
+            .ok_or_else(|| {
+                LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!("missing length config."))
+            })?
+            .as_u64()
+            .filter(|length| *length > 0)
+            .ok_or_else(|| {
+                LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!("invalid length config."))
+            })?;
+
+        Ok(Self::new(length as usize))
+    }
+}
+
+impl TokenFilter for TruncateTokenFilter {
+    fn name(&self) -> &'static str {
+        TRUNCATE_TOKEN_FILTER_NAME
+    }
+
+    fn apply(&self, tokens: &mut Vec<Token<'_>>) -> LinderaResult<()> {
+        for token in tokens.iter_mut() {
+            if let Some((end, _)) = token.surface.char_indices().nth(self.length) {
+                token.surface = match std::mem::take(&mut token.surface) {
+                    Cow::Borrowed(surface) => Cow::Borrowed(&surface[..end]),
+                    Cow::Owned(mut surface) => {
+                        surface.truncate(end);
+                        Cow::Owned(surface)
+                    }
+                };
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::token_filter::truncate::{TruncateTokenFilter, TruncateTokenFilterConfig};
+
+    #[test]
+    fn test_truncate_token_filter_config() {
+        let config_str = r#"
+            {
+                "length": 2
+            }
+            "#;
+        let config: TruncateTokenFilterConfig = serde_json::from_str(config_str).unwrap();
+        let result = TruncateTokenFilter::from_config(&config);
+        assert!(result.is_ok());
+
+        let config: TruncateTokenFilterConfig = serde_json::from_str("{}").unwrap();
+        let result = TruncateTokenFilter::from_config(&config);
+        assert!(result.is_err());
+
+        let config_str = r#"
+            {
+                "length": 0
+            }
+            "#;
+        let config: TruncateTokenFilterConfig = serde_json::from_str(config_str).unwrap();
+        let result = TruncateTokenFilter::from_config(&config);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_truncate_token_filter_apply_ipadic() {
+        use std::borrow::Cow;
+
+        use crate::dictionary::{DictionaryKind, WordId, load_embedded_dictionary};
+        use crate::token::Token;
+        use crate::token_filter::TokenFilter;
+        use lindera_dictionary::viterbi::LexType;
+
+        let filter = TruncateTokenFilter::new(2);
+
+        let dictionary = load_embedded_dictionary(DictionaryKind::IPADIC).unwrap();
+
+        let mut tokens: Vec<Token> = ["すもも", "も", "もも"]
+            .iter()
+            .enumerate()
+            .map(|(position, surface)| Token {
+                surface: Cow::Borrowed(*surface),
+                byte_start: 0,
+                byte_end: surface.len(),
+                position,
+                position_length: 1,
+                word_id: WordId {
+                    id: 4294967295,
+                    is_system: true,
+                    lex_type: LexType::System,
+                },
+                dictionary: &dictionary,
+                user_dictionary: None,
+                cost: None,
+                details: Some(vec![Cow::Borrowed("UNK")]),
+            })
+            .collect();
+
+        filter.apply(&mut tokens).unwrap();
+
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(&tokens[0].surface, "すも");
+        assert_eq!(tokens[0].byte_end, 9);
+        assert_eq!(&tokens[1].surface, "も");
+        assert_eq!(&tokens[2].surface, "もも");
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::LinderaResult;
+use crate::error::{LinderaError, LinderaErrorKind};
+use crate::token::Token;
+use crate::token_filter::TokenFilter;
+
+pub const UNIQUE_TOKEN_FILTER_NAME: &str = "unique";
+
+pub type UniqueTokenFilterConfig = Value;
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub enum UniqueMode {
+    /// Remove every token whose text has already appeared.
+    #[serde(rename = "all")]
+    All,
+    /// Remove tokens with the same text as the token just before them.
+    #[serde(rename = "consecutive")]
+    Consecutive,
+    /// Remove tokens with the same text as an earlier token at the same position.
+    #[serde(rename = "same_position")]
+    SamePosition,
+}
+
+impl UniqueMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            UniqueMode::All => "all",
+            UniqueMode::Consecutive => "consecutive",
+            UniqueMode::SamePosition => "same_position",
+        }
+    }
+}
+
+impl FromStr for UniqueMode {
+    type Err = LinderaError;
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode {
+            "all" => Ok(UniqueMode::All),
+            "consecutive" => Ok(UniqueMode::Consecutive),
+            "same_position" => Ok(UniqueMode::SamePosition),
+            _ => Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!("Invalid unique mode"))),
+        }
+    }
+}
+
+/// Remove tokens with duplicate text, keeping the first one.
+///
+/// The `mode` is one of 'all' (default), 'consecutive' or 'same_position'. 'same_position' only
+/// removes the duplicates stacked on one position, such as those added by synonym or
+/// decompounding filters.
+///
+#[derive(Clone, Debug)]
+pub struct UniqueTokenFilter {
+    mode: UniqueMode,
+}
+
+impl UniqueTokenFilter {
+    pub fn new(mode: UniqueMode) -> Self {
+        Self { mode }
+    }
+
+    pub fn from_config(config: &UniqueTokenFilterConfig) -> LinderaResult<Self> {
+        let mode = match config.get("mode") {
+            Some(mode) => UniqueMode::from_str(mode.as_str().ok_or_else(|| {
+                LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!("invalid mode config."))
+            })?)?,
+            None => UniqueMode::All,
+        };
+
+        Ok(Self::new(mode))
+    }
+}
+
+impl Default for UniqueTokenFilter {
+    fn default() -> Self {
+        Self::new(UniqueMode::All)
+    }
+}
+
+impl TokenFilter for UniqueTokenFilter {
+    fn name(&self) -> &'static str {
+        UNIQUE_TOKEN_FILTER_NAME
+    }
+
+    fn apply(&self, tokens: &mut Vec<Token<'_>>) -> LinderaResult<()> {
+        match self.mode {
+            UniqueMode::All => {
+                let mut seen = HashSet::new();
+                tokens.retain(|token| seen.insert(token.surface.to_string()));
+            }
+            UniqueMode::Consecutive => {
+                tokens.dedup_by(|token, prev| token.surface == prev.surface);
+            }
+            UniqueMode::SamePosition => {
+                let mut seen = HashSet::new();
+                tokens.retain(|token| seen.insert((token.position, token.surface.to_string())));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::token_filter::unique::{UniqueMode, UniqueTokenFilter, UniqueTokenFilterConfig};
+
+    #[test]
+    fn test_unique_token_filter_config() {
+        let config_str = r#"
+            {
+                "mode": "consecutive"
+            }
+            "#;
+        let config: UniqueTokenFilterConfig = serde_json::from_str(config_str).unwrap();
+        let result = UniqueTokenFilter::from_config(&config);
+        assert_eq!(result.unwrap().mode, UniqueMode::Consecutive);
+
+        let config: UniqueTokenFilterConfig = serde_json::from_str("{}").unwrap();
+        let result = UniqueTokenFilter::from_config(&config);
+        assert_eq!(result.unwrap().mode, UniqueMode::All);
+
+        let config_str = r#"
+            {
+                "mode": "sometimes"
+            }
+            "#;
+        let config: UniqueTokenFilterConfig = serde_json::from_str(config_str).unwrap();
+        let result = UniqueTokenFilter::from_config(&config);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_unique_token_filter_apply_ipadic() {
+        use std::borrow::Cow;
+
+        use crate::dictionary::{DictionaryKind, WordId, load_embedded_dictionary};
+        use crate::token::Token;
+        use crate::token_filter::TokenFilter;
+        use lindera_dictionary::viterbi::LexType;
+
+        let dictionary = load_embedded_dictionary(DictionaryKind::IPADIC).unwrap();
+
+        // "すもも" and its synonym "スモモ" share position 0
+        let tokens: Vec<Token> = [
+            ("すもも", 0),
+            ("スモモ", 0),
+            ("すもも", 0),
+            ("も", 1),
+            ("も", 2),
+            ("すもも", 3),
+        ]
+        .iter()
+        .map(|(surface, position)| Token {
+            surface: Cow::Borrowed(*surface),
+            byte_start: 0,
+            byte_end: surface.len(),
+            position: *position,
+            position_length: 1,
+            word_id: WordId {
+                id: 4294967295,
+                is_system: true,
+                lex_type: LexType::System,
+            },
+            dictionary: &dictionary,
+            user_dictionary: None,
+            cost: None,
+            details: Some(vec![Cow::Borrowed("UNK")]),
+        })
+        .collect();
+
+        let surfaces = |mode: UniqueMode| {
+            let mut tokens = tokens.clone();
+            UniqueTokenFilter::new(mode).apply(&mut tokens).unwrap();
+            tokens
+                .iter()
+                .map(|token| token.surface.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(surfaces(UniqueMode::All), vec!["すもも", "スモモ", "も"]);
+        assert_eq!(
+            surfaces(UniqueMode::Consecutive),
+            vec!["すもも", "スモモ", "すもも", "も", "すもも"]
+        );
+        assert_eq!(
+            surfaces(UniqueMode::SamePosition),
+            vec!["すもも", "スモモ", "も", "も", "すもも"]
+        );
+    }
+}
@@ -1,23 +1,51 @@
 use std::borrow::Cow;
 use std::env;
+use std::fmt;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use serde_json::{Value, json};
 
 use crate::LinderaResult;
+use crate::character_filter::sanitize::decode_lossy;
 use crate::character_filter::{BoxCharacterFilter, CharacterFilterLoader, OffsetMapping};
-use crate::dictionary::Lattice;
+use crate::dictionary::{Dictionary, Lattice, UserDictionary, load_user_dictionary};
 use crate::error::LinderaErrorKind;
 use crate::mode::Mode;
-use crate::segmenter::Segmenter;
+use crate::segmenter::{Segmenter, check_context_ids};
 use crate::token::Token;
 use crate::token_filter::{BoxTokenFilter, TokenFilterLoader};
+use crate::tokenizer::audit::OffsetTrail;
+#[cfg(feature = "cache")]
+use crate::tokenizer::cache::{CacheStats, TokenCache};
+
+mod audit;
+pub mod bench;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod collation;
+pub mod compare;
+pub mod elasticsearch;
+pub mod eval;
+pub mod expansion;
+pub mod explain;
+pub mod golden;
+pub mod graph;
+pub mod highlight;
+pub mod incremental;
+pub mod kana;
+pub mod redact;
+pub mod review;
+pub mod schema;
+pub mod shared;
 
 pub type TokenizerConfig = Value;
 
-fn yaml_to_config(file_path: &Path) -> LinderaResult<TokenizerConfig> {
+fn read_config_file(file_path: &Path) -> LinderaResult<Vec<u8>> {
     let mut input_read = File::open(file_path).map_err(|err| {
         LinderaErrorKind::Io.with_error(err).add_context(format!(
             "Failed to open tokenizer config file: {}",
@@ -33,6 +61,12 @@ fn yaml_to_config(file_path: &Path) -> LinderaResult<TokenizerConfig> {
         ))
     })?;
 
+    Ok(buffer)
+}
+
+fn yaml_to_config(file_path: &Path) -> LinderaResult<TokenizerConfig> {
+    let buffer = read_config_file(file_path)?;
+
     match serde_yaml_ng::from_slice::<serde_yaml_ng::Value>(&buffer) {
         Ok(value) => {
             // Check if the value is a mapping.
@@ -90,9 +124,103 @@ fn ensure_keys(mut config: Value) -> Value {
     config
 }
 
-#[derive(Debug)]
+/// The dictionary of a tokenizer built by [`TokenizerBuilder::dictionary`]: a path or URI to load
+/// it from, or a dictionary already loaded.
+#[derive(Clone)]
+pub enum DictionarySource {
+    Uri(String),
+    Loaded(Arc<Dictionary>),
+}
+
+impl From<&str> for DictionarySource {
+    fn from(uri: &str) -> Self {
+        DictionarySource::Uri(uri.to_string())
+    }
+}
+
+impl From<String> for DictionarySource {
+    fn from(uri: String) -> Self {
+        DictionarySource::Uri(uri)
+    }
+}
+
+impl From<Dictionary> for DictionarySource {
+    fn from(dictionary: Dictionary) -> Self {
+        DictionarySource::Loaded(Arc::new(dictionary))
+    }
+}
+
+impl From<Arc<Dictionary>> for DictionarySource {
+    fn from(dictionary: Arc<Dictionary>) -> Self {
+        DictionarySource::Loaded(dictionary)
+    }
+}
+
+/// The user dictionary of a tokenizer built by [`TokenizerBuilder::user_dictionary`]: a path or
+/// URI to load it from, or a user dictionary already loaded.
+#[derive(Clone)]
+pub enum UserDictionarySource {
+    Uri(String),
+    Loaded(UserDictionary),
+}
+
+impl From<&str> for UserDictionarySource {
+    fn from(uri: &str) -> Self {
+        UserDictionarySource::Uri(uri.to_string())
+    }
+}
+
+impl From<String> for UserDictionarySource {
+    fn from(uri: String) -> Self {
+        UserDictionarySource::Uri(uri)
+    }
+}
+
+impl From<UserDictionary> for UserDictionarySource {
+    fn from(user_dictionary: UserDictionary) -> Self {
+        UserDictionarySource::Loaded(user_dictionary)
+    }
+}
+
+/// Builds a [`Tokenizer`] from a configuration.
+///
+/// The configuration is the same as the one of [`TokenizerBuilder::from_file`], and can be
+/// assembled with the `set_*` and `append_*` methods. Rust code can set the dictionary, the
+/// user dictionary and the mode with typed values instead, including dictionaries already
+/// loaded, and [`build`](Self::build) checks the combination before anything is loaded:
+///
+/// ```no_run
+/// use lindera::mode::{Mode, Penalty};
+/// use lindera::tokenizer::TokenizerBuilder;
+///
+/// # fn main() -> lindera::LinderaResult<()> {
+/// let tokenizer = TokenizerBuilder::from_config(serde_json::json!({}))?
+///     .dictionary("/path/to/dictionary")
+///     .mode(Mode::Decompose(Penalty::default()))
+///     .user_dictionary("/path/to/userdic.csv")
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
 pub struct TokenizerBuilder {
     config: TokenizerConfig,
+    /// A dictionary already loaded, used instead of the one of the configuration.
+    dictionary: Option<Arc<Dictionary>>,
+    /// A user dictionary already loaded, used instead of the one of the configuration.
+    user_dictionary: Option<UserDictionary>,
+}
+
+impl fmt::Debug for TokenizerBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenizerBuilder")
+            .field("config", &self.config)
+            .field("dictionary", &self.dictionary.as_ref().map(|_| "<loaded>"))
+            .field(
+                "user_dictionary",
+                &self.user_dictionary.as_ref().map(|_| "<loaded>"),
+            )
+            .finish()
+    }
 }
 
 impl TokenizerBuilder {
@@ -102,6 +230,8 @@ impl TokenizerBuilder {
         } else {
             Ok(Self {
                 config: empty_config(),
+                dictionary: None,
+                user_dictionary: None,
             })
         }
     }
@@ -111,27 +241,93 @@ impl TokenizerBuilder {
 
         Ok(TokenizerBuilder {
             config: ensure_keys(config),
+            dictionary: None,
+            user_dictionary: None,
         })
     }
 
+    /// Reads the configuration like [`from_file`](Self::from_file), but fails on a key that the
+    /// configuration does not define instead of ignoring it. See [`schema::parse_config_strict`].
+    pub fn from_file_strict(file_path: &Path) -> LinderaResult<Self> {
+        let buffer = read_config_file(file_path)?;
+        let text = String::from_utf8(buffer).map_err(|err| {
+            LinderaErrorKind::Deserialize
+                .with_error(err)
+                .add_context(format!(
+                    "Invalid UTF-8 in config file: {}",
+                    file_path.display()
+                ))
+        })?;
+        let config = schema::parse_config_strict(&text).map_err(|err| {
+            err.add_context(format!("Invalid config file: {}", file_path.display()))
+        })?;
+
+        Self::from_config(config)
+    }
+
     pub fn from_config(config: TokenizerConfig) -> LinderaResult<Self> {
         Ok(TokenizerBuilder {
             config: ensure_keys(config),
+            dictionary: None,
+            user_dictionary: None,
         })
     }
 
+    /// Sets the mode, with the penalty of [`Mode::Decompose`].
     pub fn set_segmenter_mode(&mut self, mode: &Mode) -> &mut Self {
-        self.config["segmenter"]["mode"] = json!(mode.as_str());
+        self.config["segmenter"]["mode"] = serde_json::to_value(mode).unwrap_or_default();
         self
     }
 
     pub fn set_segmenter_dictionary(&mut self, uri: &str) -> &mut Self {
         self.config["segmenter"]["dictionary"] = json!(uri);
+        self.dictionary = None;
         self
     }
 
     pub fn set_segmenter_user_dictionary(&mut self, uri: &str) -> &mut Self {
         self.config["segmenter"]["user_dictionary"] = json!(uri);
+        self.user_dictionary = None;
+        self
+    }
+
+    /// Sets the dictionary: a path or URI (e.g. `embedded://ipadic`) to load it from, or a
+    /// [`Dictionary`] already loaded, possibly shared with other tokenizers in an `Arc`.
+    pub fn dictionary(mut self, dictionary: impl Into<DictionarySource>) -> Self {
+        match dictionary.into() {
+            DictionarySource::Uri(uri) => {
+                self.set_segmenter_dictionary(&uri);
+            }
+            DictionarySource::Loaded(dictionary) => {
+                if let Some(segmenter) = self.config["segmenter"].as_object_mut() {
+                    segmenter.remove("dictionary");
+                }
+                self.dictionary = Some(dictionary);
+            }
+        }
+        self
+    }
+
+    /// Sets the user dictionary: a path to a CSV or `.bin` file, or a [`UserDictionary`]
+    /// already loaded.
+    pub fn user_dictionary(mut self, user_dictionary: impl Into<UserDictionarySource>) -> Self {
+        match user_dictionary.into() {
+            UserDictionarySource::Uri(uri) => {
+                self.set_segmenter_user_dictionary(&uri);
+            }
+            UserDictionarySource::Loaded(user_dictionary) => {
+                if let Some(segmenter) = self.config["segmenter"].as_object_mut() {
+                    segmenter.remove("user_dictionary");
+                }
+                self.user_dictionary = Some(user_dictionary);
+            }
+        }
+        self
+    }
+
+    /// Sets the mode, e.g. `Mode::Decompose(penalty)` with a custom penalty.
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.set_segmenter_mode(&mode);
         self
     }
 
@@ -140,6 +336,16 @@ impl TokenizerBuilder {
         self
     }
 
+    pub fn set_segmenter_okurigana_variants(&mut self, okurigana_variants: bool) -> &mut Self {
+        self.config["segmenter"]["okurigana_variants"] = json!(okurigana_variants);
+        self
+    }
+
+    pub fn set_segmenter_shared_dictionary(&mut self, shared_dictionary: bool) -> &mut Self {
+        self.config["segmenter"]["shared_dictionary"] = json!(shared_dictionary);
+        self
+    }
+
     pub fn append_character_filter(&mut self, kind: &str, args: &Value) -> &mut Self {
         if let Some(array) = self.config["character_filters"].as_array_mut() {
             array.push(json!({ "kind": kind, "args": args }));
@@ -154,11 +360,112 @@ impl TokenizerBuilder {
         self
     }
 
+    pub fn set_audit_offsets(&mut self, audit_offsets: bool) -> &mut Self {
+        self.config["audit_offsets"] = json!(audit_offsets);
+        self
+    }
+
+    pub fn set_max_input_bytes(&mut self, max_input_bytes: usize) -> &mut Self {
+        self.config["max_input_bytes"] = json!(max_input_bytes);
+        self
+    }
+
+    pub fn set_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.config["timeout_ms"] = json!(timeout.as_millis() as u64);
+        self
+    }
+
+    pub fn set_cache_capacity(&mut self, cache_capacity: usize) -> &mut Self {
+        self.config["cache_capacity"] = json!(cache_capacity);
+        self
+    }
+
+    /// Builds the tokenizer.
+    ///
+    /// The settings are checked before the dictionaries are loaded: a dictionary must be set,
+    /// the penalties of [`Mode::Decompose`] must not be negative, and the settings that need a
+    /// dictionary loaded from a path (`okurigana_variants`, `shared_dictionary` and
+    /// `trusted_keys`) cannot be used with a dictionary already loaded. A user dictionary
+    /// already loaded must use context ids within the connection cost matrix of the dictionary.
     pub fn build(&self) -> LinderaResult<Tokenizer> {
-        Tokenizer::from_config(&self.config).map_err(|err| {
+        self.validate()?;
+
+        let segmenter = match &self.dictionary {
+            Some(dictionary) => self.segmenter_with(dictionary.clone()),
+            None => Segmenter::from_config(&self.config["segmenter"]),
+        };
+        let mut segmenter = segmenter.map_err(|err| {
+            LinderaErrorKind::Parse.with_error(anyhow::anyhow!("failed to build tokenizer: {err}"))
+        })?;
+        if let Some(user_dictionary) = &self.user_dictionary {
+            check_context_ids(user_dictionary, &segmenter.dictionary)?;
+            segmenter.user_dictionary = Some(user_dictionary.clone());
+        }
+
+        Tokenizer::from_segmenter_and_config(segmenter, &self.config).map_err(|err| {
             LinderaErrorKind::Parse.with_error(anyhow::anyhow!("failed to build tokenizer: {err}"))
         })
     }
+
+    /// Checks the combination of the settings without loading anything.
+    fn validate(&self) -> LinderaResult<()> {
+        let segmenter = &self.config["segmenter"];
+        if self.dictionary.is_none() && segmenter.get("dictionary").is_none() {
+            return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                "no dictionary is set; call TokenizerBuilder::dictionary"
+            )));
+        }
+
+        if let Some(mode) = segmenter.get("mode").filter(|mode| mode.is_object())
+            && let Ok(Mode::Decompose(penalty)) = serde_json::from_value::<Mode>(mode.clone())
+            && (penalty.kanji_penalty_length_penalty < 0
+                || penalty.other_penalty_length_penalty < 0)
+        {
+            return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                "the penalties of the decompose mode must not be negative: {penalty:?}"
+            )));
+        }
+
+        if self.dictionary.is_some() {
+            for key in ["okurigana_variants", "shared_dictionary", "trusted_keys"] {
+                let enabled = match segmenter.get(key) {
+                    Some(Value::Bool(enabled)) => *enabled,
+                    Some(Value::Null) | None => false,
+                    Some(_) => true,
+                };
+                if enabled {
+                    return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                        "{key} needs a dictionary loaded from a path, not a dictionary already loaded"
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Creates the segmenter of the configuration with a dictionary already loaded.
+    fn segmenter_with(&self, dictionary: Arc<Dictionary>) -> LinderaResult<Segmenter> {
+        let config = &self.config["segmenter"];
+        let mode = match config.get("mode") {
+            None => Mode::Normal,
+            Some(Value::String(mode)) => Mode::from_str(mode)?,
+            Some(mode) => serde_json::from_value(mode.clone()).map_err(|err| {
+                LinderaErrorKind::Parse
+                    .with_error(anyhow::anyhow!("mode field is invalid object: {err}"))
+            })?,
+        };
+        let user_dictionary = match config.get("user_dictionary").and_then(Value::as_str) {
+            Some(uri) => Some(load_user_dictionary(uri, &dictionary.metadata)?),
+            None => None,
+        };
+        let keep_whitespace = config
+            .get("keep_whitespace")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+        Ok(Segmenter::new(mode, dictionary, user_dictionary).keep_whitespace(keep_whitespace))
+    }
 }
 
 pub struct Tokenizer {
@@ -180,6 +487,28 @@ pub struct Tokenizer {
     /// Each token filter is a boxed trait object implementing the `TokenFilter` trait, allowing
     /// for various transformations and processing steps to be applied to the tokens.
     pub token_filters: Vec<BoxTokenFilter>,
+
+    /// Offset audit
+    /// When enabled, the offsets are checked after every character filter, the segmentation and
+    /// every token filter, and tokenization fails with an error naming the first filter that
+    /// broke them. This is a debugging aid and slows tokenization down.
+    pub audit_offsets: bool,
+
+    /// Input size limit
+    /// The largest text, in bytes, that [`Tokenizer::tokenize`] accepts. Larger texts fail with
+    /// [`LinderaErrorKind::InputTooLarge`] before the lattice is built.
+    pub max_input_bytes: Option<usize>,
+
+    /// Timeout
+    /// The time a call to [`Tokenizer::tokenize`] may take. A call that takes longer fails with
+    /// [`LinderaErrorKind::Timeout`].
+    pub timeout: Option<Duration>,
+
+    /// Token cache
+    /// An optional cache of the tokens of the texts tokenized most recently. A text found in the
+    /// cache is not analyzed again.
+    #[cfg(feature = "cache")]
+    pub cache: Option<TokenCache>,
 }
 
 impl Tokenizer {
@@ -203,6 +532,11 @@ impl Tokenizer {
             segmenter,
             character_filters: Vec::new(),
             token_filters: Vec::new(),
+            audit_offsets: false,
+            max_input_bytes: None,
+            timeout: None,
+            #[cfg(feature = "cache")]
+            cache: None,
         }
     }
 
@@ -212,8 +546,32 @@ impl Tokenizer {
         })?;
         let segmenter = Segmenter::from_config(segmenter_config)?;
 
+        Self::from_segmenter_and_config(segmenter, config)
+    }
+
+    /// Creates a tokenizer from a segmenter and the settings of a configuration other than the
+    /// segmenter ones.
+    fn from_segmenter_and_config(
+        segmenter: Segmenter,
+        config: &TokenizerConfig,
+    ) -> LinderaResult<Self> {
         // Create a tokenizer from the segmenter.
         let mut tokenizer = Tokenizer::new(segmenter);
+        tokenizer.set_audit_offsets(config["audit_offsets"].as_bool().unwrap_or(false));
+        tokenizer.set_max_input_bytes(
+            config["max_input_bytes"]
+                .as_u64()
+                .map(|max_input_bytes| max_input_bytes as usize),
+        );
+        tokenizer.set_timeout(config["timeout_ms"].as_u64().map(Duration::from_millis));
+        if let Some(cache_capacity) = config["cache_capacity"].as_u64() {
+            #[cfg(feature = "cache")]
+            tokenizer.set_cache_capacity(Some(cache_capacity as usize));
+            #[cfg(not(feature = "cache"))]
+            log::warn!(
+                "cache_capacity ({cache_capacity}) is ignored: lindera is built without the cache feature"
+            );
+        }
 
         // Load character filter settings from the tokenizer config if it is not empty.
         if let Some(character_filter_settings) = config["character_filters"].as_array() {
@@ -262,6 +620,8 @@ impl Tokenizer {
     /// - It returns a mutable reference to `self`, allowing multiple character filters to be appended in a chain of method calls.
     pub fn append_character_filter(&mut self, character_filter: BoxCharacterFilter) -> &mut Self {
         self.character_filters.push(character_filter);
+        #[cfg(feature = "cache")]
+        self.clear_cache();
 
         self
     }
@@ -282,42 +642,97 @@ impl Tokenizer {
     /// - It returns a mutable reference to `self`, allowing multiple token filters to be appended in a chain of method calls.
     pub fn append_token_filter(&mut self, token_filter: BoxTokenFilter) -> &mut Self {
         self.token_filters.push(token_filter);
+        #[cfg(feature = "cache")]
+        self.clear_cache();
 
         self
     }
 
-    /// Tokenizes the input text using the tokenizer's segmenter, character filters, and token filters.
+    /// Enables or disables the offset audit.
     ///
-    /// # Arguments
-    ///
-    /// * `text` - A reference to the input text (`&str`) that will be tokenized.
-    ///
-    /// # Returns
-    ///
-    /// Returns a `LinderaResult` containing a vector of `Token`s, where each `Token` represents a segment of the tokenized text.
-    ///
-    /// # Process
-    ///
-    /// 1. **Apply character filters**:
-    ///    - If any character filters are defined, they are applied to the input text before tokenization.
-    ///    - The `offsets`, `diffs`, and `text_len` are recorded for each character filter.
-    /// 2. **Segment the text**:
-    ///    - The `segmenter` divides the (potentially filtered) text into tokens.
-    /// 3. **Apply token filters**:
-    ///    - If any token filters are defined, they are applied to the segmented tokens.
-    /// 4. **Correct token offsets**:
-    ///    - If character filters were applied, the byte offsets of each token are corrected to account for changes introduced by those filters.
+    /// With the audit enabled, [`Tokenizer::tokenize`] checks after each filter that the token
+    /// offsets still slice the filtered text and the original text on character boundaries, and
+    /// that the text produced by each character filter maps back onto the original text. A
+    /// violation is reported as an error naming the filter, e.g.
+    /// `offset audit failed after character filter #1 (mapping): ...`.
+    pub fn set_audit_offsets(&mut self, audit_offsets: bool) -> &mut Self {
+        self.audit_offsets = audit_offsets;
+
+        self
+    }
+
+    /// Sets the largest text, in bytes, that the tokenizer accepts, or removes the limit.
     ///
-    /// # Errors
+    /// The lattice of a text grows with its length, so a limit keeps a multi-megabyte input from
+    /// holding a thread and its memory; such an input fails fast with
+    /// [`LinderaErrorKind::InputTooLarge`]. The limit applies to the text before the character
+    /// filters.
+    pub fn set_max_input_bytes(&mut self, max_input_bytes: Option<usize>) -> &mut Self {
+        self.max_input_bytes = max_input_bytes;
+
+        self
+    }
+
+    /// Sets the time a call to [`Tokenizer::tokenize`] may take, or removes the limit.
     ///
-    /// - Returns an error if any of the character or token filters fail during processing.
-    /// - Returns an error if the segmentation process fails.
+    /// The deadline is checked after each filter and while the lattice is built, and a call that
+    /// reaches it fails with [`LinderaErrorKind::Timeout`]. A filter is not interrupted, so a call
+    /// can run past the deadline by the time of one filter. The timeout is not supported on
+    /// `wasm32-unknown-unknown`, which has no clock.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.timeout = timeout;
+
+        self
+    }
+
+    /// Caches the tokens of up to `cache_capacity` texts, or removes the cache.
     ///
-    /// # Details
+    /// Query analysis tokenizes the same short strings over and over; with a cache, a text
+    /// tokenized before is not analyzed again, and the least recently used texts make room for
+    /// new ones. Texts longer than [`cache::MAX_CACHED_TEXT_BYTES`] are not cached. Setting the
+    /// capacity replaces the cache with an empty one.
+    #[cfg(feature = "cache")]
+    pub fn set_cache_capacity(&mut self, cache_capacity: Option<usize>) -> &mut Self {
+        self.cache = cache_capacity.map(TokenCache::new);
+
+        self
+    }
+
+    /// The hit and miss counts of the cache, or `None` without a cache.
+    #[cfg(feature = "cache")]
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(TokenCache::stats)
+    }
+
+    /// Drops the tokens in the cache, for instance after the user dictionary changed.
+    #[cfg(feature = "cache")]
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Replaces the dictionary of the segmenter with the one returned by `loader`, keeping the
+    /// filters and the other settings.
     ///
-    /// - `Cow<'a, str>` is used for the `normalized_text`, allowing the function to either borrow the original text or create an owned version if the text needs modification.
-    /// - If no character filters are applied, the original `text` is used as-is for segmentation.
-    /// - Token offsets are adjusted after the tokenization process if character filters were applied to ensure the byte positions of each token are accurate relative to the original text.
+    /// If `loader` fails, or the new dictionary does not fit the user dictionary as described in
+    /// [`Segmenter::set_dictionary`], the tokenizer keeps its dictionary. The cached tokens are
+    /// dropped. To
+    /// replace the dictionary of a tokenizer that other threads are using, share it through a
+    /// [`SharedTokenizer`](shared::SharedTokenizer).
+    pub fn reload_dictionary<F, D>(&mut self, loader: F) -> LinderaResult<&mut Self>
+    where
+        F: FnOnce() -> LinderaResult<D>,
+        D: Into<Arc<Dictionary>>,
+    {
+        let dictionary = loader()?;
+        self.segmenter.set_dictionary(dictionary)?;
+        #[cfg(feature = "cache")]
+        self.clear_cache();
+
+        Ok(self)
+    }
+
     /// Tokenizes the input text using the tokenizer's segmenter, character filters, and token filters.
     ///
     /// # Arguments
@@ -344,6 +759,10 @@ impl Tokenizer {
     ///
     /// - Returns an error if any of the character or token filters fail during processing.
     /// - Returns an error if the segmentation process fails.
+    /// - Returns [`LinderaErrorKind::InputTooLarge`] if `text` is longer than the `max_input_bytes`
+    ///   of the tokenizer.
+    /// - Returns [`LinderaErrorKind::Timeout`] if the tokenization does not finish within the
+    ///   timeout of the tokenizer.
     ///
     /// # Details
     ///
@@ -393,10 +812,82 @@ impl Tokenizer {
         text: &'a str,
         lattice: &mut Lattice,
     ) -> LinderaResult<Vec<Token<'a>>> {
-        let mut normalized_text: Cow<'a, str> = Cow::Borrowed(text);
+        self.tokenize_cow_with_lattice(Cow::Borrowed(text), lattice)
+    }
+
+    /// Tokenizes a borrowed or owned text, so that the tokens of an owned text only borrow the
+    /// tokenizer.
+    pub(crate) fn tokenize_cow_with_lattice<'a>(
+        &'a self,
+        text: Cow<'a, str>,
+        lattice: &mut Lattice,
+    ) -> LinderaResult<Vec<Token<'a>>> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        self.tokenize_cow_with_deadline(text, lattice, deadline)
+    }
+
+    /// Tokenizes the input text like [`Tokenizer::tokenize`], failing with
+    /// [`LinderaErrorKind::Timeout`] if the tokenization does not finish before `deadline`, or
+    /// before the timeout of the tokenizer if it comes first.
+    pub fn tokenize_with_deadline<'a>(
+        &'a self,
+        text: &'a str,
+        deadline: Instant,
+    ) -> LinderaResult<Vec<Token<'a>>> {
+        let deadline = match self.timeout {
+            Some(timeout) => deadline.min(Instant::now() + timeout),
+            None => deadline,
+        };
+        let mut lattice = Lattice::default();
+        self.tokenize_cow_with_deadline(Cow::Borrowed(text), &mut lattice, Some(deadline))
+    }
+
+    /// Tokenizes bytes that may not be valid UTF-8, as crawled text often is. Every invalid
+    /// sequence is replaced with U+FFFD before the character filters, and the byte offsets of the
+    /// tokens are offsets in `bytes`.
+    pub fn tokenize_bytes<'a>(&'a self, bytes: &'a [u8]) -> LinderaResult<Vec<Token<'a>>> {
+        let (text, mapping) = decode_lossy(bytes);
+        let text_len = text.len();
+
+        let mut lattice = Lattice::default();
+        let mut tokens = self.tokenize_cow_with_lattice(text, &mut lattice)?;
+        if !mapping.is_empty() {
+            for token in tokens.iter_mut() {
+                correct_token_offsets(token, std::slice::from_ref(&mapping), text_len);
+            }
+        }
+
+        Ok(tokens)
+    }
+
+    fn tokenize_cow_with_deadline<'a>(
+        &'a self,
+        text: Cow<'a, str>,
+        lattice: &mut Lattice,
+        deadline: Option<Instant>,
+    ) -> LinderaResult<Vec<Token<'a>>> {
+        if let Some(max_input_bytes) = self.max_input_bytes
+            && text.len() > max_input_bytes
+        {
+            return Err(LinderaErrorKind::InputTooLarge.with_error(anyhow::anyhow!(
+                "the input is {} bytes, more than max_input_bytes ({max_input_bytes})",
+                text.len()
+            )));
+        }
+
+        #[cfg(feature = "cache")]
+        let cache = self.cache.as_ref().filter(|cache| cache.accepts(&text));
+        #[cfg(feature = "cache")]
+        if let Some(tokens) = cache.and_then(|cache| cache.get(&text, &self.segmenter)) {
+            return Ok(tokens);
+        }
+
+        let mut normalized_text: Cow<'a, str> = text.clone();
+        let text = text.as_ref();
 
         let mut offset_mappings: Vec<OffsetMapping> =
             Vec::with_capacity(self.character_filters.len());
+        let mut trail = OffsetTrail::default();
 
         // Apply character filters to the text if it is not empty.
         // Optimize: Only convert to mutable when we have filters to apply
@@ -404,49 +895,117 @@ impl Tokenizer {
             // Convert to owned string once for all filters
             let text_mut = normalized_text.to_mut();
 
-            for character_filter in &self.character_filters {
+            for (index, character_filter) in self.character_filters.iter().enumerate() {
                 let mapping = character_filter.apply(text_mut)?;
 
+                if self.audit_offsets {
+                    trail.push(mapping.clone(), text_mut.len());
+                    audit::check_character_filter(
+                        &trail,
+                        text,
+                        text_mut,
+                        index,
+                        character_filter.name(),
+                    )?;
+                }
+
                 if !mapping.is_empty() {
                     // Record the offset mapping of each character filter in reverse order
                     // since we need to apply corrections in reverse order
                     offset_mappings.push(mapping);
                 }
+
+                check_deadline(deadline, || {
+                    format!("character filter #{index} ({})", character_filter.name())
+                })?;
             }
         }
 
         // Store the final text length for offset correction
         let final_text_len = normalized_text.len();
 
-        // Segment a text.
-        // Segment a text.
-        let mut tokens = self
+        // Keep the filtered text to check the token offsets against
+        let filtered_text = self.audit_offsets.then(|| normalized_text.to_string());
+
+        // Segment a text. The lattice stops at the deadline, and is given back without it.
+        lattice.set_deadline(deadline);
+        let tokens = self
             .segmenter
-            .segment_with_lattice(normalized_text, lattice)?;
+            .segment_with_lattice(normalized_text, lattice);
+        lattice.set_deadline(None);
+        let mut tokens = tokens?;
+
+        if let Some(filtered_text) = &filtered_text {
+            trail
+                .check_tokens(text, filtered_text, &tokens, true)
+                .map_err(|reason| audit::offset_error("segmentation", reason))?;
+        }
 
         // Apply token filters to the tokens if they are not empty.
-        for token_filter in &self.token_filters {
+        for (index, token_filter) in self.token_filters.iter().enumerate() {
             token_filter.apply(&mut tokens)?;
+
+            if let Some(filtered_text) = &filtered_text {
+                trail
+                    .check_tokens(text, filtered_text, &tokens, false)
+                    .map_err(|reason| {
+                        audit::offset_error(
+                            &format!("token filter #{index} ({})", token_filter.name()),
+                            reason,
+                        )
+                    })?;
+            }
+
+            check_deadline(deadline, || {
+                format!("token filter #{index} ({})", token_filter.name())
+            })?;
         }
 
         // Correct token offsets if character filters are applied.
-        // Apply corrections in reverse order (last filter first)
         if !offset_mappings.is_empty() {
             for token in tokens.iter_mut() {
-                // Apply corrections in reverse order to undo the transformations
-                for mapping in offset_mappings.iter().rev() {
-                    // Override start.
-                    token.byte_start = mapping.correct_offset(token.byte_start, final_text_len);
-                    // Override end.
-                    token.byte_end = mapping.correct_offset(token.byte_end, final_text_len);
-                }
+                correct_token_offsets(token, &offset_mappings, final_text_len);
             }
         }
 
+        if self.audit_offsets {
+            audit::check_corrected_tokens(text, &tokens)?;
+        }
+
+        #[cfg(feature = "cache")]
+        if let Some(cache) = cache {
+            cache.insert(text, &tokens);
+        }
+
         Ok(tokens)
     }
 }
 
+/// Fails with [`LinderaErrorKind::Timeout`] if the deadline has passed, naming the stage that
+/// ran past it.
+fn check_deadline(deadline: Option<Instant>, stage: impl FnOnce() -> String) -> LinderaResult<()> {
+    match deadline {
+        Some(deadline) if Instant::now() >= deadline => {
+            Err(LinderaErrorKind::Timeout.with_error(anyhow::anyhow!(
+                "tokenization did not finish before the deadline (after {})",
+                stage()
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Maps the byte offsets of a token in the filtered text back to the original text.
+fn correct_token_offsets(token: &mut Token, offset_mappings: &[OffsetMapping], text_len: usize) {
+    // Apply corrections in reverse order (last filter first) to undo the transformations
+    for mapping in offset_mappings.iter().rev() {
+        // Override start.
+        token.byte_start = mapping.correct_offset(token.byte_start, text_len);
+        // Override end.
+        token.byte_end = mapping.correct_offset(token.byte_end, text_len);
+    }
+}
+
 impl Clone for Tokenizer {
     /// Creates a deep clone of the `Tokenizer` instance, including all character filters, token filters, and the segmenter.
     ///
@@ -459,6 +1018,7 @@ impl Clone for Tokenizer {
     /// - **Character Filters**: Each character filter is cloned by calling its `box_clone` method, which ensures that any dynamically dispatched filters are properly cloned.
     /// - **Token Filters**: Similarly, each token filter is cloned using the `box_clone` method to handle dynamic dispatch.
     /// - **Segmenter**: The segmenter is cloned using its `clone` method.
+    /// - **Cache**: The clone gets an empty cache of the same capacity.
     ///
     /// # Notes
     ///
@@ -481,6 +1041,11 @@ impl Clone for Tokenizer {
             character_filters,
             segmenter: self.segmenter.clone(),
             token_filters,
+            audit_offsets: self.audit_offsets,
+            max_input_bytes: self.max_input_bytes,
+            timeout: self.timeout,
+            #[cfg(feature = "cache")]
+            cache: self.cache.clone(),
         }
     }
 }
@@ -813,6 +1378,159 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_tokenize_input_limits_ipadic() {
+        use std::time::{Duration, Instant};
+
+        use crate::dictionary::load_dictionary;
+        use crate::error::LinderaErrorKind;
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+        use crate::tokenizer::Tokenizer;
+
+        let dictionary = load_dictionary("embedded://ipadic").unwrap();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let mut tokenizer = Tokenizer::new(segmenter);
+        tokenizer.set_max_input_bytes(Some(36));
+
+        // 12 characters of 3 bytes
+        assert_eq!(
+            7,
+            tokenizer
+                .tokenize("すもももももももものうち")
+                .unwrap()
+                .len()
+        );
+        let err = tokenizer
+            .tokenize("すもももももももものうちの")
+            .err()
+            .unwrap();
+        assert_eq!(LinderaErrorKind::InputTooLarge, err.kind());
+
+        tokenizer.set_max_input_bytes(None);
+        let text = "すもももももももものうち".repeat(1000);
+        let err = tokenizer
+            .tokenize_with_deadline(&text, Instant::now())
+            .err()
+            .unwrap();
+        assert_eq!(LinderaErrorKind::Timeout, err.kind());
+
+        tokenizer.set_timeout(Some(Duration::ZERO));
+        let err = tokenizer.tokenize(&text).err().unwrap();
+        assert_eq!(LinderaErrorKind::Timeout, err.kind());
+
+        // The lattice is usable again after a timeout
+        tokenizer.set_timeout(Some(Duration::from_secs(60)));
+        assert_eq!(7000, tokenizer.tokenize(&text).unwrap().len());
+    }
+
+    #[test]
+    fn test_tokenizer_builder_input_limits() {
+        use std::time::Duration;
+
+        use crate::tokenizer::TokenizerBuilder;
+
+        let mut builder = TokenizerBuilder::from_config(serde_json::json!({})).unwrap();
+        builder
+            .set_max_input_bytes(1048576)
+            .set_timeout(Duration::from_millis(500));
+
+        assert_eq!(1048576, builder.config["max_input_bytes"]);
+        assert_eq!(500, builder.config["timeout_ms"]);
+    }
+
+    #[test]
+    fn test_tokenizer_builder_cache_capacity() {
+        use crate::tokenizer::TokenizerBuilder;
+
+        let mut builder = TokenizerBuilder::from_config(serde_json::json!({})).unwrap();
+        builder.set_cache_capacity(10000);
+
+        assert_eq!(10000, builder.config["cache_capacity"]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "cache", feature = "embed-ipadic"))]
+    fn test_tokenize_cache_ipadic() {
+        use crate::dictionary::load_dictionary;
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+        use crate::token_filter::BoxTokenFilter;
+        use crate::token_filter::lowercase::LowercaseTokenFilter;
+        use crate::tokenizer::Tokenizer;
+
+        let dictionary = load_dictionary("embedded://ipadic").unwrap();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let mut tokenizer = Tokenizer::new(segmenter);
+        assert!(tokenizer.cache_stats().is_none());
+        tokenizer.set_cache_capacity(Some(1));
+
+        let surfaces = |tokenizer: &Tokenizer, text: &str| {
+            tokenizer
+                .tokenize(text)
+                .unwrap()
+                .into_iter()
+                .map(|mut token| {
+                    let details = token.details().join(",");
+                    (token.surface.to_string(), token.byte_end, details)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let tokens = surfaces(&tokenizer, "東京都");
+        assert_eq!(tokens, surfaces(&tokenizer, "東京都"));
+        surfaces(&tokenizer, "関西国際空港");
+        assert_eq!(tokens, surfaces(&tokenizer, "東京都"));
+
+        let stats = tokenizer.cache_stats().unwrap();
+        assert_eq!((1, 3), (stats.hits, stats.misses));
+        assert_eq!(2, stats.evictions);
+
+        // The cache is dropped when the analysis chain changes
+        tokenizer.append_token_filter(BoxTokenFilter::from(LowercaseTokenFilter::new()));
+        assert_eq!(0, tokenizer.cache_stats().unwrap().len);
+        assert_eq!(1, tokenizer.clone().cache_stats().unwrap().capacity);
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_tokenize_bytes_ipadic() {
+        use crate::character_filter::BoxCharacterFilter;
+        use crate::character_filter::sanitize::SanitizeCharacterFilter;
+        use crate::dictionary::load_dictionary;
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+        use crate::tokenizer::Tokenizer;
+
+        let dictionary = load_dictionary("embedded://ipadic").unwrap();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let mut tokenizer = Tokenizer::new(segmenter);
+        tokenizer.append_character_filter(BoxCharacterFilter::from(SanitizeCharacterFilter::new(
+            true, true,
+        )));
+
+        // "東京都に\x07\u{200b}住む" with a lone continuation byte after "に"
+        let mut bytes = "東京都に".as_bytes().to_vec();
+        bytes.push(0x80);
+        bytes.extend_from_slice("\u{7}\u{200b}住む".as_bytes());
+
+        let tokens = tokenizer.tokenize_bytes(&bytes).unwrap();
+        let surfaces = tokens
+            .iter()
+            .map(|token| token.surface.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["東京", "都", "に", "\u{fffd}", "住む"], surfaces);
+
+        let offsets = tokens
+            .iter()
+            .map(|token| (token.byte_start, token.byte_end))
+            .collect::<Vec<_>>();
+        // The byte of the replacement character is 12..13, and the removed characters are part
+        // of the range of the token after them
+        assert_eq!(vec![(0, 6), (6, 9), (9, 12), (12, 13), (13, 23)], offsets);
+    }
+
     #[test]
     #[cfg(not(windows))]
     #[should_panic(expected = "No such file or directory")]
@@ -859,4 +1577,159 @@ mod tests {
 
         TokenizerBuilder::from_file(&config_file).unwrap();
     }
+
+    /// Builds a tiny dictionary into a temporary directory.
+    fn build_test_dictionary() -> tempfile::TempDir {
+        use crate::test_util::TestDictionary;
+
+        TestDictionary::new(
+            "\
+東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー
+都庁,0,0,100,名詞,一般,*,*,*,*,都庁,トチョウ,トチョー
+",
+        )
+        .char_def("DEFAULT 0 1 0\nSPACE 0 1 0\nKANJI 0 0 2\n0x0020 SPACE\n0x4E00..0x9FAF KANJI\n")
+        .unknown_cost(5000)
+        .build()
+    }
+
+    #[test]
+    fn test_build_with_typed_settings() {
+        use crate::dictionary::load_dictionary;
+        use crate::mode::{Mode, Penalty};
+        use crate::tokenizer::TokenizerBuilder;
+
+        let dict_dir = build_test_dictionary();
+        let uri = dict_dir.path().to_str().unwrap();
+
+        let tokenizer = TokenizerBuilder::from_config(serde_json::json!({}))
+            .unwrap()
+            .dictionary(uri)
+            .mode(Mode::Decompose(Penalty::default()))
+            .build()
+            .unwrap();
+        let surfaces = tokenizer
+            .tokenize("東京都庁")
+            .unwrap()
+            .iter()
+            .map(|token| token.surface.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["東京", "都庁"], surfaces);
+
+        // A dictionary already loaded gives the same tokens
+        let dictionary = load_dictionary(uri).unwrap();
+        let tokenizer = TokenizerBuilder::from_config(serde_json::json!({}))
+            .unwrap()
+            .dictionary(dictionary)
+            .build()
+            .unwrap();
+        assert_eq!(2, tokenizer.tokenize("東京都庁").unwrap().len());
+    }
+
+    #[test]
+    fn test_set_segmenter_mode_keeps_penalty() {
+        use crate::mode::{Mode, Penalty};
+        use crate::tokenizer::TokenizerBuilder;
+
+        let penalty = Penalty {
+            kanji_penalty_length_threshold: 3,
+            ..Penalty::default()
+        };
+        let mut builder = TokenizerBuilder::from_config(serde_json::json!({})).unwrap();
+        builder.set_segmenter_mode(&Mode::Decompose(penalty.clone()));
+        let mode: Mode =
+            serde_json::from_value(builder.config["segmenter"]["mode"].clone()).unwrap();
+        assert_eq!(Mode::Decompose(penalty), mode);
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_settings() {
+        use crate::dictionary::load_dictionary;
+        use crate::mode::{Mode, Penalty};
+        use crate::tokenizer::TokenizerBuilder;
+
+        let error = TokenizerBuilder::from_config(serde_json::json!({}))
+            .unwrap()
+            .build()
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("no dictionary is set"));
+
+        let dict_dir = build_test_dictionary();
+        let uri = dict_dir.path().to_str().unwrap();
+        let error = TokenizerBuilder::from_config(serde_json::json!({}))
+            .unwrap()
+            .dictionary(uri)
+            .mode(Mode::Decompose(Penalty {
+                kanji_penalty_length_penalty: -1,
+                ..Penalty::default()
+            }))
+            .build()
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("must not be negative"));
+
+        let mut builder = TokenizerBuilder::from_config(serde_json::json!({}))
+            .unwrap()
+            .dictionary(load_dictionary(uri).unwrap());
+        builder.set_segmenter_okurigana_variants(true);
+        let error = builder.build().err().unwrap();
+        assert!(error.to_string().contains("okurigana_variants"));
+    }
+
+    #[test]
+    fn test_build_rejects_user_dictionary_outside_matrix() {
+        use std::fs;
+
+        use crate::dictionary::{load_dictionary, load_user_dictionary};
+        use crate::tokenizer::TokenizerBuilder;
+
+        let dict_dir = build_test_dictionary();
+        let dictionary = load_dictionary(dict_dir.path().to_str().unwrap()).unwrap();
+        let user_dir = tempfile::tempdir().unwrap();
+        let user_path = user_dir.path().join("userdic.csv");
+        fs::write(
+            &user_path,
+            "東京都庁,5,5,-1000,名詞,固有名詞,組織,*,*,*,東京都庁,トウキョウトチョウ,トーキョートチョー\n",
+        )
+        .unwrap();
+        let user_dictionary =
+            load_user_dictionary(user_path.to_str().unwrap(), &dictionary.metadata).unwrap();
+
+        let error = TokenizerBuilder::from_config(serde_json::json!({}))
+            .unwrap()
+            .dictionary(dictionary)
+            .user_dictionary(user_dictionary)
+            .build()
+            .err()
+            .unwrap();
+        assert!(
+            error
+                .to_string()
+                .contains("outside the 1x1 connection cost matrix")
+        );
+    }
+
+    #[test]
+    fn test_create_tokenizer_builder_from_file_strict() {
+        use std::fs;
+
+        use crate::tokenizer::TokenizerBuilder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config_file = dir.path().join("lindera.yml");
+        fs::write(
+            &config_file,
+            "segmenter:\n  dictionary: embedded://ipadic\n  keep_whitspace: true\n",
+        )
+        .unwrap();
+
+        assert!(TokenizerBuilder::from_file(&config_file).is_ok());
+        let error = format!(
+            "{:?}",
+            TokenizerBuilder::from_file_strict(&config_file).unwrap_err()
+        );
+        assert!(error.contains("did you mean `keep_whitespace`?"), "{error}");
+        assert!(error.contains("line 3 column 3"), "{error}");
+    }
 }
@@ -0,0 +1,269 @@
+//! Offset checks run after each filter when [`Tokenizer::set_audit_offsets`](super::Tokenizer::set_audit_offsets)
+//! is enabled.
+//!
+//! Character filters may replace text with text of the same byte length without recording a
+//! transformation, so outside the recorded transformations the filtered text is only required to
+//! map onto character boundaries of the original text, not to the same content. Token filters may rewrite surfaces, so surfaces are
+//! only compared with the text right after segmentation; after each token filter the offsets must
+//! still slice the text on character boundaries.
+
+use crate::LinderaResult;
+use crate::character_filter::OffsetMapping;
+use crate::error::{LinderaError, LinderaErrorKind};
+use crate::token::Token;
+
+/// The offset mappings of the character filters applied so far, with the length of the text each
+/// of them produced.
+#[derive(Debug, Default)]
+pub(crate) struct OffsetTrail {
+    stages: Vec<(OffsetMapping, usize)>,
+}
+
+impl OffsetTrail {
+    pub(crate) fn push(&mut self, mapping: OffsetMapping, text_len: usize) {
+        self.stages.push((mapping, text_len));
+    }
+
+    /// Maps a byte range of the filtered text to the original text.
+    ///
+    /// The flag is `true` if a recorded transformation touches the range at any stage, in which
+    /// case the mapped range is not expected to have the same length.
+    fn to_original(&self, start: usize, end: usize) -> (usize, usize, bool) {
+        self.stages.iter().rev().fold(
+            (start, end, false),
+            |(start, end, touched), (mapping, len)| {
+                let touched = touched
+                    || mapping
+                        .transformations
+                        .iter()
+                        .any(|t| t.filtered_start <= end && start <= t.filtered_end);
+                (
+                    mapping.correct_offset(start, *len),
+                    mapping.correct_offset(end, *len),
+                    touched,
+                )
+            },
+        )
+    }
+
+    /// Checks that every character of the filtered text maps back onto the original text.
+    pub(crate) fn check_text(&self, original: &str, filtered: &str) -> Result<(), String> {
+        let mut prev_start = 0;
+        for (start, c) in filtered.char_indices() {
+            let end = start + c.len_utf8();
+            let (original_start, original_end, touched) = self.to_original(start, end);
+
+            let range = format!(
+                "filtered bytes {start}..{end} ({c:?}) map to original bytes {original_start}..{original_end}"
+            );
+            if original_start < prev_start {
+                return Err(format!(
+                    "{range}, before the previous character at {prev_start}"
+                ));
+            }
+            if original_start > original_end || original_end > original.len() {
+                return Err(format!(
+                    "{range}, outside of the original text of {} bytes",
+                    original.len()
+                ));
+            }
+            if !touched
+                && (!original.is_char_boundary(original_start)
+                    || !original.is_char_boundary(original_end))
+            {
+                return Err(format!(
+                    "{range}, not on character boundaries outside of any recorded transformation"
+                ));
+            }
+            prev_start = original_start;
+        }
+
+        // A transformation at the end, such as a deletion, may leave the end of the text short
+        let (_, original_end, touched) = self.to_original(filtered.len(), filtered.len());
+        if !touched && original_end != original.len() {
+            return Err(format!(
+                "the end of the filtered text maps to original byte {original_end}, not to the end at {}",
+                original.len()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the offsets of every token slice both the filtered and the original text, and,
+    /// if `compare_surfaces` is set, that they slice the filtered text to the token surface.
+    pub(crate) fn check_tokens(
+        &self,
+        original: &str,
+        filtered: &str,
+        tokens: &[Token],
+        compare_surfaces: bool,
+    ) -> Result<(), String> {
+        for token in tokens {
+            let (start, end) = (token.byte_start, token.byte_end);
+            let surface = &token.surface;
+            if start > end || filtered.get(start..end).is_none() {
+                return Err(format!(
+                    "token {surface:?} at position {} has byte offsets {start}..{end}, which do not slice the filtered text of {} bytes",
+                    token.position,
+                    filtered.len()
+                ));
+            }
+            if compare_surfaces && &filtered[start..end] != surface {
+                return Err(format!(
+                    "token {surface:?} at position {} has byte offsets {start}..{end}, which slice the filtered text to {:?}",
+                    token.position,
+                    &filtered[start..end]
+                ));
+            }
+
+            let (original_start, original_end, _) = self.to_original(start, end);
+            if original_start > original_end || original.get(original_start..original_end).is_none()
+            {
+                return Err(format!(
+                    "token {surface:?} at position {} has byte offsets {start}..{end}, which map to original bytes {original_start}..{original_end} that do not slice the original text of {} bytes",
+                    token.position,
+                    original.len()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates the error reported when the offsets no longer match after `stage`.
+pub(crate) fn offset_error(stage: &str, reason: String) -> LinderaError {
+    LinderaErrorKind::Content.with_error(anyhow::anyhow!(
+        "offset audit failed after {stage}: {reason}"
+    ))
+}
+
+/// Checks the text after a character filter, and converts a failure into an error naming it.
+pub(crate) fn check_character_filter(
+    trail: &OffsetTrail,
+    original: &str,
+    filtered: &str,
+    index: usize,
+    name: &str,
+) -> LinderaResult<()> {
+    trail
+        .check_text(original, filtered)
+        .map_err(|reason| offset_error(&format!("character filter #{index} ({name})"), reason))
+}
+
+/// Checks that the corrected offsets of every token slice the original text.
+pub(crate) fn check_corrected_tokens(original: &str, tokens: &[Token]) -> LinderaResult<()> {
+    for token in tokens {
+        let (start, end) = (token.byte_start, token.byte_end);
+        if start > end || original.get(start..end).is_none() {
+            return Err(offset_error(
+                "offset correction",
+                format!(
+                    "token {:?} at position {} has byte offsets {start}..{end}, which do not slice the original text of {} bytes",
+                    token.surface,
+                    token.position,
+                    original.len()
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character_filter::Transformation;
+
+    #[test]
+    fn test_check_text() {
+        // "１０㍑" → "10リットル"
+        let original = "１０㍑";
+        let filtered = "10リットル";
+        let mut trail = OffsetTrail::default();
+        trail.push(
+            OffsetMapping::with_transformations(vec![
+                Transformation::new(0, 3, 0, 1),
+                Transformation::new(3, 6, 1, 2),
+                Transformation::new(6, 9, 2, 14),
+            ]),
+            filtered.len(),
+        );
+        assert!(trail.check_text(original, filtered).is_ok());
+
+        // Equal-length replacements need no transformation
+        let mut trail = OffsetTrail::default();
+        trail.push(OffsetMapping::new(), "スモモ".len());
+        assert!(trail.check_text("すもも", "スモモ").is_ok());
+    }
+
+    #[test]
+    fn test_check_text_filters() {
+        use crate::character_filter::CharacterFilter;
+        use crate::character_filter::japanese_prolonged_sound::JapaneseProlongedSoundCharacterFilter;
+        use crate::character_filter::unicode_normalize::{
+            UnicodeNormalizeCharacterFilter, UnicodeNormalizeKind,
+        };
+
+        let original = "ｽｰｰﾊﾟｰ㍑、すごぉぉい〜";
+        let mut text = original.to_string();
+        let mut trail = OffsetTrail::default();
+
+        let mapping = UnicodeNormalizeCharacterFilter::new(UnicodeNormalizeKind::NFKC)
+            .apply(&mut text)
+            .unwrap();
+        trail.push(mapping, text.len());
+        assert!(trail.check_text(original, &text).is_ok());
+
+        let mapping = JapaneseProlongedSoundCharacterFilter::new(true, true, true)
+            .apply(&mut text)
+            .unwrap();
+        trail.push(mapping, text.len());
+        assert_eq!("スーパーリットル、すごいー", text);
+        assert!(trail.check_text(original, &text).is_ok());
+    }
+
+    #[test]
+    fn test_check_text_missing_transformation() {
+        // "リンデラ" → "Lindera" without recording the transformation
+        let mut trail = OffsetTrail::default();
+        trail.push(OffsetMapping::new(), "Lindera".len());
+        let err = trail.check_text("リンデラ", "Lindera").unwrap_err();
+        assert!(
+            err.contains("outside of any recorded transformation"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_check_text_wrong_transformation() {
+        // "ーーー" (3..12) collapsed into "ー" (3..6), recorded with a wrong original end
+        let original = "スーーーパー";
+        let filtered = "スーパー";
+        let mut trail = OffsetTrail::default();
+        trail.push(
+            OffsetMapping::with_transformations(vec![Transformation::new(3, 9, 3, 6)]),
+            filtered.len(),
+        );
+        let err = trail.check_text(original, filtered).unwrap_err();
+        assert!(err.contains("not to the end"), "{err}");
+    }
+
+    #[test]
+    fn test_check_text_chained() {
+        // "ｽｰﾊﾟｰ" → "スーパー" → "スーパ"
+        let original = "ｽｰﾊﾟｰ";
+        let mut trail = OffsetTrail::default();
+        trail.push(
+            OffsetMapping::with_transformations(vec![Transformation::new(6, 12, 6, 9)]),
+            "スーパー".len(),
+        );
+        trail.push(
+            OffsetMapping::with_transformations(vec![Transformation::new(9, 12, 9, 9)]),
+            "スーパ".len(),
+        );
+        assert!(trail.check_text(original, "スーパ").is_ok());
+    }
+}
@@ -0,0 +1,338 @@
+//! Micro-benchmarks of the analysis chain, for finding the character filter, the segmentation or
+//! the token filter that slows down indexing.
+//!
+//! [`Tokenizer::bench`] runs the chain of a tokenizer over a corpus and measures the time spent in
+//! each stage. The allocations of each stage are counted as well when [`CountingAllocator`] is the
+//! global allocator of the program:
+//!
+//! ```no_run
+//! use lindera::tokenizer::bench::CountingAllocator;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: CountingAllocator = CountingAllocator;
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::LinderaResult;
+use crate::dictionary::Lattice;
+use crate::error::LinderaErrorKind;
+use crate::tokenizer::Tokenizer;
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// A global allocator that counts the allocations made through it, and otherwise defers to the
+/// system allocator.
+///
+/// The counters are shared by all threads, so the allocations of a stage include the ones made by
+/// other threads while it runs. Run the benchmark while the program is otherwise idle.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_allocation(layout.size());
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count_allocation(layout.size());
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count_allocation(new_size);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+fn count_allocation(size: usize) {
+    INSTALLED.store(true, Ordering::Relaxed);
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED_BYTES.fetch_add(size as u64, Ordering::Relaxed);
+}
+
+/// The allocation counters, or `None` if [`CountingAllocator`] is not the global allocator.
+fn allocation_counters() -> Option<(u64, u64)> {
+    INSTALLED.load(Ordering::Relaxed).then(|| {
+        (
+            ALLOCATIONS.load(Ordering::Relaxed),
+            ALLOCATED_BYTES.load(Ordering::Relaxed),
+        )
+    })
+}
+
+/// The kind of a stage of the analysis chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageKind {
+    CharacterFilter,
+    Segmenter,
+    TokenFilter,
+}
+
+impl StageKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            StageKind::CharacterFilter => "character_filter",
+            StageKind::Segmenter => "segmenter",
+            StageKind::TokenFilter => "token_filter",
+        }
+    }
+}
+
+/// The measurements of a stage over the whole corpus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageReport {
+    pub kind: StageKind,
+    /// The name of the filter, or `segmenter`.
+    pub name: String,
+    pub elapsed: Duration,
+    /// The number of allocations, if [`CountingAllocator`] is the global allocator.
+    pub allocations: Option<u64>,
+    /// The number of bytes allocated, if [`CountingAllocator`] is the global allocator.
+    pub allocated_bytes: Option<u64>,
+    /// The number of tokens after the stage (0 for character filters).
+    pub tokens: usize,
+}
+
+impl StageReport {
+    fn new(kind: StageKind, name: &str) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+            elapsed: Duration::ZERO,
+            allocations: None,
+            allocated_bytes: None,
+            tokens: 0,
+        }
+    }
+
+    /// Runs `f` and adds its time and allocations to the stage.
+    fn measure<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        let before = allocation_counters();
+        let start = Instant::now();
+        let result = f();
+        self.elapsed += start.elapsed();
+
+        if let (Some((allocations, bytes)), Some((allocations_after, bytes_after))) =
+            (before, allocation_counters())
+        {
+            *self.allocations.get_or_insert(0) += allocations_after - allocations;
+            *self.allocated_bytes.get_or_insert(0) += bytes_after - bytes;
+        }
+
+        result
+    }
+}
+
+/// The result of [`Tokenizer::bench`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchReport {
+    /// The number of texts analyzed, counting each iteration.
+    pub texts: usize,
+    /// The stages in the order they are applied.
+    pub stages: Vec<StageReport>,
+}
+
+impl BenchReport {
+    /// The time spent in all the stages.
+    pub fn elapsed(&self) -> Duration {
+        self.stages.iter().map(|stage| stage.elapsed).sum()
+    }
+
+    /// The stage that took the most time.
+    pub fn slowest(&self) -> Option<&StageReport> {
+        self.stages.iter().max_by_key(|stage| stage.elapsed)
+    }
+}
+
+impl fmt::Display for BenchReport {
+    /// Formats the report as a table with a row per stage.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.elapsed().as_secs_f64();
+        writeln!(
+            f,
+            "{:<4} {:<18} {:<28} {:>10} {:>7} {:>12} {:>12} {:>10}",
+            "#", "stage", "name", "time_ms", "share", "allocs", "alloc_kb", "tokens"
+        )?;
+        for (index, stage) in self.stages.iter().enumerate() {
+            let share = if total > 0.0 {
+                stage.elapsed.as_secs_f64() / total * 100.0
+            } else {
+                0.0
+            };
+            writeln!(
+                f,
+                "{:<4} {:<18} {:<28} {:>10.3} {:>6.1}% {:>12} {:>12} {:>10}",
+                index,
+                stage.kind.as_str(),
+                stage.name,
+                stage.elapsed.as_secs_f64() * 1000.0,
+                share,
+                stage
+                    .allocations
+                    .map_or_else(|| "n/a".to_string(), |count| count.to_string()),
+                stage.allocated_bytes.map_or_else(
+                    || "n/a".to_string(),
+                    |bytes| format!("{:.1}", bytes as f64 / 1024.0)
+                ),
+                stage.tokens,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Tokenizer {
+    /// Runs the analysis chain over each text of the corpus `iterations` times, and reports the
+    /// time and the allocations of each character filter, of the segmentation and of each token
+    /// filter.
+    ///
+    /// The stages run as in [`Tokenizer::tokenize`], except that the offsets of the tokens are not
+    /// corrected to the original text and are not audited. Run the benchmark once beforehand to
+    /// warm up the caches if the first iterations should not be counted.
+    pub fn bench<S: AsRef<str>>(
+        &self,
+        corpus: &[S],
+        iterations: usize,
+    ) -> LinderaResult<BenchReport> {
+        if iterations == 0 {
+            return Err(LinderaErrorKind::Args
+                .with_error(anyhow::anyhow!("iterations must be greater than 0")));
+        }
+
+        let mut character_filters = self
+            .character_filters
+            .iter()
+            .map(|filter| StageReport::new(StageKind::CharacterFilter, filter.name()))
+            .collect::<Vec<_>>();
+        let mut segmenter = StageReport::new(StageKind::Segmenter, "segmenter");
+        let mut token_filters = self
+            .token_filters
+            .iter()
+            .map(|filter| StageReport::new(StageKind::TokenFilter, filter.name()))
+            .collect::<Vec<_>>();
+
+        let mut lattice = Lattice::default();
+        for _ in 0..iterations {
+            for text in corpus {
+                let mut text = text.as_ref().to_string();
+                for (filter, stage) in self.character_filters.iter().zip(&mut character_filters) {
+                    stage.measure(|| filter.apply(&mut text))?;
+                }
+
+                let mut tokens = segmenter.measure(|| {
+                    self.segmenter
+                        .segment_with_lattice(Cow::Borrowed(text.as_str()), &mut lattice)
+                })?;
+                segmenter.tokens += tokens.len();
+
+                for (filter, stage) in self.token_filters.iter().zip(&mut token_filters) {
+                    stage.measure(|| filter.apply(&mut tokens))?;
+                    stage.tokens += tokens.len();
+                }
+            }
+        }
+
+        let mut stages = character_filters;
+        stages.push(segmenter);
+        stages.extend(token_filters);
+
+        Ok(BenchReport {
+            texts: corpus.len() * iterations,
+            stages,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counting_allocator() {
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        unsafe {
+            let ptr = CountingAllocator.alloc(layout);
+            assert!(!ptr.is_null());
+            CountingAllocator.dealloc(ptr, layout);
+        }
+
+        let (allocations, bytes) = allocation_counters().unwrap();
+        assert!(allocations >= 1);
+        assert!(bytes >= 64);
+    }
+
+    #[test]
+    fn test_bench_report() {
+        let stage = |kind, name: &str, millis, tokens| StageReport {
+            kind,
+            name: name.to_string(),
+            elapsed: Duration::from_millis(millis),
+            allocations: None,
+            allocated_bytes: None,
+            tokens,
+        };
+        let report = BenchReport {
+            texts: 10,
+            stages: vec![
+                stage(StageKind::CharacterFilter, "unicode_normalize", 2, 0),
+                stage(StageKind::Segmenter, "segmenter", 5, 40),
+                stage(StageKind::TokenFilter, "japanese_stop_tags", 3, 30),
+            ],
+        };
+
+        assert_eq!(Duration::from_millis(10), report.elapsed());
+        assert_eq!("segmenter", report.slowest().unwrap().name);
+
+        let table = report.to_string();
+        let rows = table.lines().collect::<Vec<_>>();
+        assert_eq!(4, rows.len());
+        assert!(rows[2].contains("segmenter"));
+        assert!(rows[2].contains("50.0%"));
+        assert!(rows[3].contains("japanese_stop_tags"));
+        assert!(rows[3].contains("n/a"));
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_bench_ipadic() {
+        use crate::dictionary::load_dictionary;
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+        use crate::token_filter::BoxTokenFilter;
+        use crate::token_filter::japanese_stop_tags::JapaneseStopTagsTokenFilter;
+
+        let dictionary = load_dictionary("embedded://ipadic").unwrap();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let mut tokenizer = Tokenizer::new(segmenter);
+        tokenizer.append_token_filter(BoxTokenFilter::from(JapaneseStopTagsTokenFilter::new(
+            ["助詞,係助詞".to_string(), "助詞,連体化".to_string()]
+                .into_iter()
+                .collect(),
+        )));
+
+        let corpus = ["すもももももももものうち", "関西国際空港限定トートバッグ"];
+        let report = tokenizer.bench(&corpus, 3).unwrap();
+
+        assert_eq!(6, report.texts);
+        assert_eq!(2, report.stages.len());
+        assert_eq!(StageKind::Segmenter, report.stages[0].kind);
+        assert_eq!(StageKind::TokenFilter, report.stages[1].kind);
+        assert_eq!("japanese_stop_tags", report.stages[1].name);
+        // The particles are removed by the filter
+        assert!(report.stages[1].tokens < report.stages[0].tokens);
+
+        assert!(tokenizer.bench(&corpus, 0).is_err());
+    }
+}
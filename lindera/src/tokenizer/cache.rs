@@ -0,0 +1,316 @@
+//! A size-bounded cache of the tokens of the texts analyzed most recently, for query analysis
+//! where the same short strings are tokenized over and over.
+//!
+//! The cache keeps the tokens after the token filters, so a hit skips the whole analysis chain.
+//! Texts longer than [`MAX_CACHED_TEXT_BYTES`] are neither looked up nor stored.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use crate::dictionary::WordId;
+use crate::segmenter::Segmenter;
+use crate::token::{Token, TokenCost};
+
+/// The longest text, in bytes, whose tokens are cached.
+pub const MAX_CACHED_TEXT_BYTES: usize = 1024;
+
+/// The hit and miss counts of a [`TokenCache`], to tune its capacity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The lookups that found the tokens of the text.
+    pub hits: u64,
+    /// The lookups that did not, and analyzed the text.
+    pub misses: u64,
+    /// The texts whose tokens were dropped to make room for others.
+    pub evictions: u64,
+    /// The texts cached now.
+    pub len: usize,
+    /// The most texts the cache holds.
+    pub capacity: usize,
+}
+
+impl CacheStats {
+    /// The share of the lookups that were hits, between 0 and 1.
+    pub fn hit_ratio(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// A token without the references to the text and the dictionaries.
+#[derive(Debug, Clone)]
+struct CachedToken {
+    surface: String,
+    byte_start: usize,
+    byte_end: usize,
+    position: usize,
+    position_length: usize,
+    word_id: WordId,
+    user_dictionary: bool,
+    cost: Option<TokenCost>,
+    details: Option<Vec<String>>,
+}
+
+impl CachedToken {
+    fn new(token: &Token) -> Self {
+        Self {
+            surface: token.surface.to_string(),
+            byte_start: token.byte_start,
+            byte_end: token.byte_end,
+            position: token.position,
+            position_length: token.position_length,
+            word_id: token.word_id,
+            user_dictionary: token.user_dictionary.is_some(),
+            cost: token.cost,
+            details: token
+                .details
+                .as_ref()
+                .map(|details| details.iter().map(|detail| detail.to_string()).collect()),
+        }
+    }
+
+    fn to_token<'a>(&self, segmenter: &'a Segmenter) -> Token<'a> {
+        let user_dictionary = if self.user_dictionary {
+            segmenter.user_dictionary.as_ref()
+        } else {
+            None
+        };
+        let mut token = Token::new(
+            Cow::Owned(self.surface.clone()),
+            self.byte_start,
+            self.byte_end,
+            self.position,
+            self.word_id,
+            &segmenter.dictionary,
+            user_dictionary,
+        );
+        token.position_length = self.position_length;
+        token.cost = self.cost;
+        token.details = self
+            .details
+            .as_ref()
+            .map(|details| details.iter().cloned().map(Cow::Owned).collect());
+        token
+    }
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    /// The tokens of each text, and the tick of its last use.
+    tokens: HashMap<String, (u64, Vec<CachedToken>)>,
+    /// The texts by the tick of their last use, the least recently used first.
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+    stats: CacheStats,
+}
+
+impl Entries {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+/// A least recently used cache of the tokens of texts, shared by the threads using a tokenizer.
+#[derive(Debug)]
+pub struct TokenCache {
+    capacity: usize,
+    entries: Mutex<Entries>,
+}
+
+impl TokenCache {
+    /// A cache holding the tokens of up to `capacity` texts.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(Entries {
+                stats: CacheStats {
+                    capacity,
+                    ..CacheStats::default()
+                },
+                ..Entries::default()
+            }),
+        }
+    }
+
+    /// The most texts the cache holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns `true` if the tokens of `text` can be cached.
+    pub(crate) fn accepts(&self, text: &str) -> bool {
+        self.capacity > 0 && text.len() <= MAX_CACHED_TEXT_BYTES
+    }
+
+    /// Looks up the tokens of `text`, counting a hit or a miss.
+    pub(crate) fn get<'a>(&self, text: &str, segmenter: &'a Segmenter) -> Option<Vec<Token<'a>>> {
+        let mut entries = self.lock();
+        let tick = entries.next_tick();
+
+        let Entries {
+            tokens,
+            recency,
+            stats,
+            ..
+        } = &mut *entries;
+        match tokens.get_mut(text) {
+            Some((last_used, cached_tokens)) => {
+                stats.hits += 1;
+                if let Some(key) = recency.remove(last_used) {
+                    recency.insert(tick, key);
+                }
+                *last_used = tick;
+                Some(
+                    cached_tokens
+                        .iter()
+                        .map(|token| token.to_token(segmenter))
+                        .collect(),
+                )
+            }
+            None => {
+                stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Stores the tokens of `text`, dropping the least recently used texts when the cache is full.
+    pub(crate) fn insert(&self, text: &str, tokens: &[Token]) {
+        let mut entries = self.lock();
+        let tick = entries.next_tick();
+        let cached_tokens = tokens.iter().map(CachedToken::new).collect();
+
+        if let Some((last_used, _)) = entries
+            .tokens
+            .insert(text.to_string(), (tick, cached_tokens))
+        {
+            entries.recency.remove(&last_used);
+        }
+        entries.recency.insert(tick, text.to_string());
+
+        while entries.tokens.len() > self.capacity {
+            let Some((_, key)) = entries.recency.pop_first() else {
+                break;
+            };
+            entries.tokens.remove(&key);
+            entries.stats.evictions += 1;
+        }
+        entries.stats.len = entries.tokens.len();
+    }
+
+    /// Drops all the cached tokens. The counts are kept.
+    pub fn clear(&self) {
+        let mut entries = self.lock();
+        entries.tokens.clear();
+        entries.recency.clear();
+        entries.stats.len = 0;
+    }
+
+    /// The hit and miss counts since the cache was created or the counts were reset.
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    /// Resets the hit, miss and eviction counts.
+    pub fn reset_stats(&self) {
+        let mut entries = self.lock();
+        entries.stats = CacheStats {
+            len: entries.tokens.len(),
+            capacity: self.capacity,
+            ..CacheStats::default()
+        };
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Entries> {
+        // The entries are consistent between statements, so a panic of another thread does not
+        // leave them broken
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Clone for TokenCache {
+    /// An empty cache of the same capacity.
+    fn clone(&self) -> Self {
+        Self::new(self.capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::cache::{MAX_CACHED_TEXT_BYTES, TokenCache};
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_token_cache_eviction_ipadic() {
+        use std::borrow::Cow;
+
+        use crate::dictionary::load_dictionary;
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+        use crate::token::Token;
+
+        let dictionary = load_dictionary("embedded://ipadic").unwrap();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+
+        let cache = TokenCache::new(2);
+        let token = Token::new(
+            Cow::Borrowed("東京"),
+            0,
+            6,
+            0,
+            Default::default(),
+            &segmenter.dictionary,
+            None,
+        );
+        cache.insert("a", std::slice::from_ref(&token));
+        cache.insert("b", &[]);
+        assert!(cache.get("a", &segmenter).is_some());
+        cache.insert("c", &[]);
+
+        // "b" was the least recently used
+        assert!(cache.get("b", &segmenter).is_none());
+        let tokens = cache.get("a", &segmenter).unwrap();
+        assert_eq!("東京", tokens[0].surface);
+        assert_eq!(6, tokens[0].byte_end);
+
+        let stats = cache.stats();
+        assert_eq!(
+            (2, 1, 1, 2),
+            (stats.hits, stats.misses, stats.evictions, stats.len)
+        );
+    }
+
+    #[test]
+    fn test_token_cache_stats() {
+        let cache = TokenCache::new(2);
+        assert!(cache.accepts("東京"));
+        assert!(!cache.accepts(&"a".repeat(MAX_CACHED_TEXT_BYTES + 1)));
+        assert!(!TokenCache::new(0).accepts("東京"));
+
+        let stats = cache.stats();
+        assert_eq!(2, stats.capacity);
+        assert_eq!(0.0, stats.hit_ratio());
+
+        cache.insert("a", &[]);
+        cache.insert("b", &[]);
+        cache.insert("c", &[]);
+        let stats = cache.stats();
+        assert_eq!((1, 2), (stats.evictions, stats.len));
+
+        cache.clear();
+        assert_eq!(0, cache.stats().len);
+        assert_eq!(1, cache.stats().evictions);
+
+        cache.reset_stats();
+        assert_eq!(0, cache.stats().evictions);
+        assert_eq!(2, cache.clone().stats().capacity);
+    }
+}
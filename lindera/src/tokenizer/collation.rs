@@ -0,0 +1,280 @@
+//! Dictionary-order (五十音順) sort keys of Japanese texts from their readings, for sorting names
+//! and titles without ICU.
+//!
+//! The keys compare the kana in the order of the gojūon table (`ア` `イ` `ウ`... `ワ` `ヲ` `ン`),
+//! hiragana and katakana alike, with a small kana sorting as its large kana (`キャ` as `キヤ`).
+//! The differences ignored at this first level (small kana, voicing marks with
+//! [`CollationOptions::fold_voiced`], long vowel marks with
+//! [`CollationOptions::expand_long_vowels`], and hiragana against katakana) only break the ties,
+//! in the manner of JIS X 4061. The characters that are not kana sort after the kana, by code
+//! point.
+
+use serde::Serialize;
+
+use crate::LinderaResult;
+use crate::tokenizer::Tokenizer;
+
+/// The kana of the gojūon table, in order.
+const GOJUON: &str = "アイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワヰヱヲン";
+
+/// The weight of the first character that is not a kana.
+const OTHER_WEIGHT: u32 = 0x100;
+
+/// How the collation keys compare the kana.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollationOptions {
+    /// Whether the long vowel mark sorts as the vowel of the kana before it (`コーヒー` as
+    /// `コオヒイ`). Otherwise it sorts before all the kana.
+    pub expand_long_vowels: bool,
+    /// Whether a voiced or semi-voiced kana sorts as its unvoiced kana (`ガ` as `カ`), the
+    /// voicing only breaking the ties. Otherwise it sorts right after its unvoiced kana, before
+    /// the next kana of the table (`カ` `ガ` `キ`).
+    pub fold_voiced: bool,
+}
+
+impl Default for CollationOptions {
+    fn default() -> Self {
+        Self {
+            expand_long_vowels: true,
+            fold_voiced: true,
+        }
+    }
+}
+
+/// A sort key. Keys compare as their texts sort in dictionary order.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct CollationKey {
+    /// The weights of the characters.
+    pub primary: Vec<u32>,
+    /// The weights breaking the ties between the texts of the same primary weights.
+    pub secondary: Vec<u8>,
+}
+
+impl CollationKey {
+    /// The key as bytes that compare the same way, e.g. for the index of a database.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.primary.len() * 4 + 4 + self.secondary.len());
+        for weight in &self.primary {
+            bytes.extend_from_slice(&weight.to_be_bytes());
+        }
+        // The weights are never 0, so a key sorts before the longer keys it is a prefix of
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&self.secondary);
+        bytes
+    }
+}
+
+/// The unvoiced katakana of a kana, with its voicing (0 unvoiced, 1 voiced, 2 semi-voiced),
+/// whether it is small and whether it is a hiragana.
+fn decompose(c: char) -> Option<(char, u8, bool, bool)> {
+    let (c, hiragana) = match c as u32 {
+        0x3041..=0x3096 => (char::from_u32(c as u32 + 0x60)?, true),
+        0x30a1..=0x30fa => (c, false),
+        _ => return None,
+    };
+    let (base, voicing, small) = match c {
+        'ァ' | 'ィ' | 'ゥ' | 'ェ' | 'ォ' => (char::from_u32(c as u32 + 1)?, 0, true),
+        'ャ' | 'ュ' | 'ョ' | 'ッ' | 'ヮ' => (char::from_u32(c as u32 + 1)?, 0, true),
+        'ヵ' => ('カ', 0, true),
+        'ヶ' => ('ケ', 0, true),
+        'ヴ' => ('ウ', 1, false),
+        'ヷ' => ('ワ', 1, false),
+        'ヸ' => ('ヰ', 1, false),
+        'ヹ' => ('ヱ', 1, false),
+        'ヺ' => ('ヲ', 1, false),
+        // ガ..ド are the kana after their unvoiced ones, and バ, パ the two after ハ
+        'ガ'..='ド' if GOJUON.contains(char::from_u32(c as u32 - 1)?) => {
+            (char::from_u32(c as u32 - 1)?, 1, false)
+        }
+        'ハ'..='ポ' => {
+            let offset = (c as u32 - 'ハ' as u32) % 3;
+            (char::from_u32(c as u32 - offset)?, offset as u8, false)
+        }
+        _ => (c, 0, false),
+    };
+    Some((base, voicing, small, hiragana))
+}
+
+/// The vowel of the kana, for the long vowel mark after it.
+fn vowel(kana: char) -> Option<char> {
+    let index = GOJUON.chars().position(|c| c == kana)?;
+    if kana == 'ン' {
+        return None;
+    }
+    // ヤ ユ ヨ and ワ ヰ ヱ ヲ skip some vowels of their rows
+    let vowel_index = match kana {
+        'ヤ' => 0,
+        'ユ' => 2,
+        'ヨ' => 4,
+        'ワ' => 0,
+        'ヰ' => 1,
+        'ヱ' => 3,
+        'ヲ' => 4,
+        _ if index < 35 => index % 5,
+        _ => (index - 38) % 5,
+    };
+    GOJUON.chars().nth(vowel_index)
+}
+
+/// Returns the sort key of a text in kana, such as a reading.
+///
+/// # Example
+///
+/// ```
+/// use lindera::tokenizer::collation::{CollationOptions, collation_key};
+///
+/// let options = CollationOptions::default();
+/// let mut names = vec!["さとう", "サイトウ", "ざいつ"];
+/// names.sort_by_key(|name| collation_key(name, &options));
+/// // ざいつ sorts as さいつ
+/// assert_eq!(vec!["ざいつ", "サイトウ", "さとう"], names);
+/// ```
+pub fn collation_key(kana: &str, options: &CollationOptions) -> CollationKey {
+    let mut key = CollationKey {
+        primary: Vec::with_capacity(kana.len() / 3),
+        secondary: Vec::with_capacity(kana.len() / 3),
+    };
+    // The kana before, for the long vowel mark
+    let mut last: Option<char> = None;
+    for c in kana.chars() {
+        if c == 'ー' {
+            match last.and_then(vowel).filter(|_| options.expand_long_vowels) {
+                Some(vowel) => {
+                    let index = GOJUON.chars().position(|c| c == vowel).unwrap_or_default();
+                    key.primary.push((index as u32 + 1) * 3);
+                }
+                None => key.primary.push(1),
+            }
+            // After the vowel written as a kana
+            key.secondary.push(0x80);
+            continue;
+        }
+
+        let Some((base, voicing, small, hiragana)) = decompose(c) else {
+            key.primary.push(OTHER_WEIGHT + c as u32);
+            key.secondary.push(0);
+            last = None;
+            continue;
+        };
+        let index = GOJUON.chars().position(|c| c == base).unwrap_or_default() as u32;
+        if options.fold_voiced {
+            key.primary.push((index + 1) * 3);
+        } else {
+            key.primary.push((index + 1) * 3 + voicing as u32);
+        }
+        // The voicing first, then the small kana before the large ones, then the hiragana
+        // before the katakana
+        key.secondary
+            .push((voicing << 2) | (u8::from(!small) << 1) | u8::from(!hiragana));
+        last = Some(base);
+    }
+    key
+}
+
+impl Tokenizer {
+    /// Returns the sort key of `text` from its reading (see [`Tokenizer::to_kana`]), e.g. to sort
+    /// the names written in kanji in dictionary order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "embed-ipadic")]
+    /// # {
+    /// # use lindera::dictionary::load_dictionary;
+    /// # use lindera::mode::Mode;
+    /// # use lindera::segmenter::Segmenter;
+    /// # use lindera::tokenizer::Tokenizer;
+    /// # let dictionary = load_dictionary("embedded://ipadic")?;
+    /// # let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+    /// use lindera::tokenizer::collation::CollationOptions;
+    ///
+    /// let options = CollationOptions::default();
+    /// let mut names = vec!["渡辺", "伊藤", "佐藤"];
+    /// names.sort_by_cached_key(|name| tokenizer.collation_key(name, &options).unwrap());
+    /// // イトウ, サトウ, ワタナベ
+    /// assert_eq!(vec!["伊藤", "佐藤", "渡辺"], names);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn collation_key(
+        &self,
+        text: &str,
+        options: &CollationOptions,
+    ) -> LinderaResult<CollationKey> {
+        Ok(collation_key(&self.to_kana(text)?, options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TestDictionary;
+
+    fn sorted<'a>(mut texts: Vec<&'a str>, options: &CollationOptions) -> Vec<&'a str> {
+        texts.sort_by_key(|text| collation_key(text, options));
+        texts
+    }
+
+    #[test]
+    fn test_collation_key() {
+        let options = CollationOptions::default();
+        assert_eq!(
+            vec!["あい", "カ", "かあ", "ガア", "キ", "ぱん", "ヲ", "ン", "A"],
+            sorted(
+                vec!["A", "ン", "ぱん", "ガア", "キ", "ヲ", "かあ", "カ", "あい"],
+                &options
+            )
+        );
+        // The voicing breaks the ties only
+        assert_eq!(vec!["ガア", "カイ"], sorted(vec!["カイ", "ガア"], &options));
+        assert!(collation_key("カ", &options) < collation_key("ガ", &options));
+        assert!(collation_key("ハ", &options) < collation_key("バ", &options));
+        assert!(collation_key("バ", &options) < collation_key("パ", &options));
+        // The small kana before the large one, the hiragana before the katakana
+        assert!(collation_key("ッ", &options) < collation_key("ツ", &options));
+        assert!(collation_key("つ", &options) < collation_key("ツ", &options));
+        assert!(collation_key("キャ", &options) < collation_key("キヤ", &options));
+        // The long vowel mark as the vowel, after the vowel written as a kana
+        assert_eq!(
+            collation_key("コーヒー", &options).primary,
+            collation_key("コオヒイ", &options).primary
+        );
+        assert!(collation_key("コオ", &options) < collation_key("コー", &options));
+        assert!(collation_key("コー", &options) < collation_key("コカ", &options));
+        assert_eq!(
+            collation_key("キョー", &options).primary,
+            collation_key("キヨオ", &options).primary
+        );
+
+        let options = CollationOptions {
+            expand_long_vowels: false,
+            fold_voiced: false,
+        };
+        assert_eq!(vec!["カイ", "ガア"], sorted(vec!["ガア", "カイ"], &options));
+        assert_eq!(vec!["コー", "コア"], sorted(vec!["コア", "コー"], &options));
+
+        let bytes = |text: &str| collation_key(text, &options).to_bytes();
+        assert!(bytes("カ") < bytes("カア"));
+        assert!(bytes("カイ") < bytes("ガア"));
+    }
+
+    #[test]
+    fn test_tokenizer_collation_key() {
+        let (_dict_dir, tokenizer) = TestDictionary::new(
+            "\
+渡辺,0,0,100,名詞,固有名詞,人名,姓,*,*,渡辺,ワタナベ,ワタナベ
+伊藤,0,0,100,名詞,固有名詞,人名,姓,*,*,伊藤,イトウ,イトー
+佐藤,0,0,100,名詞,固有名詞,人名,姓,*,*,佐藤,サトウ,サトー
+",
+        )
+        .unknown_cost(5000)
+        .tokenizer();
+
+        let options = CollationOptions::default();
+        let mut names = vec!["渡辺", "佐藤", "伊藤", "さとう"];
+        names.sort_by_cached_key(|name| tokenizer.collation_key(name, &options).unwrap());
+        assert_eq!(vec!["伊藤", "佐藤", "さとう", "渡辺"], names);
+    }
+}
@@ -0,0 +1,212 @@
+//! Side-by-side tokenization of a text with several tokenizers, e.g. configured with IPADIC,
+//! UniDic and IPADIC NEologd, for choosing a dictionary or building regression corpora.
+//!
+//! The tokens are aligned on the boundaries all the tokenizers agree on: the text is split into
+//! segments between these boundaries, and each segment holds the tokens of every tokenizer that
+//! start in it. A segment where all the tokenizers produced the same token boundaries is agreed,
+//! even if the details of the tokens differ as the dictionaries do.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::LinderaResult;
+use crate::tokenizer::Tokenizer;
+
+/// A token of a compared tokenization, with byte offsets in the original text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComparedToken {
+    pub surface: String,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub details: Vec<String>,
+}
+
+/// A part of the text between two boundaries of all the tokenizations.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComparedSegment {
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub text: String,
+    /// Whether all the tokenizers split the segment the same way.
+    pub agreed: bool,
+    /// The tokens of each tokenizer in the segment, in the order of the tokenizers.
+    pub tokens: Vec<Vec<ComparedToken>>,
+}
+
+/// The result of [`compare_tokenizers`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Comparison {
+    /// The names of the tokenizers.
+    pub names: Vec<String>,
+    pub segments: Vec<ComparedSegment>,
+}
+
+impl Comparison {
+    /// Whether all the tokenizers split the text the same way.
+    pub fn is_agreed(&self) -> bool {
+        self.segments.iter().all(|segment| segment.agreed)
+    }
+
+    /// The segments the tokenizers split differently.
+    pub fn disagreements(&self) -> impl Iterator<Item = &ComparedSegment> {
+        self.segments.iter().filter(|segment| !segment.agreed)
+    }
+
+    /// The ratio of the agreed segments, 1.0 for a text without tokens.
+    pub fn agreement(&self) -> f64 {
+        if self.segments.is_empty() {
+            return 1.0;
+        }
+        let agreed = self
+            .segments
+            .iter()
+            .filter(|segment| segment.agreed)
+            .count();
+        agreed as f64 / self.segments.len() as f64
+    }
+}
+
+/// Tokenizes `text` with each of the named tokenizers and aligns their tokens.
+pub fn compare_tokenizers(
+    tokenizers: &[(&str, &Tokenizer)],
+    text: &str,
+) -> LinderaResult<Comparison> {
+    let mut tokenizations = Vec::with_capacity(tokenizers.len());
+    for (_, tokenizer) in tokenizers {
+        let tokens = tokenizer
+            .tokenize(text)?
+            .iter_mut()
+            .map(|token| ComparedToken {
+                surface: token.surface.to_string(),
+                byte_start: token.byte_start,
+                byte_end: token.byte_end,
+                details: token
+                    .details()
+                    .iter()
+                    .map(|detail| detail.to_string())
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        tokenizations.push(tokens);
+    }
+
+    // The boundaries of every tokenization
+    let boundaries = tokenizations
+        .iter()
+        .map(|tokens| {
+            tokens
+                .iter()
+                .flat_map(|token| [token.byte_start, token.byte_end])
+                .collect::<BTreeSet<_>>()
+        })
+        .reduce(|common, boundaries| common.intersection(&boundaries).copied().collect())
+        .unwrap_or_default();
+    let mut boundaries = boundaries.into_iter().collect::<Vec<_>>();
+    boundaries.insert(0, 0);
+    boundaries.push(text.len());
+    boundaries.dedup();
+
+    let mut segments = Vec::new();
+    for window in boundaries.windows(2) {
+        let (byte_start, byte_end) = (window[0], window[1]);
+        let tokens = tokenizations
+            .iter()
+            .map(|tokens| {
+                tokens
+                    .iter()
+                    .filter(|token| byte_start <= token.byte_start && token.byte_start < byte_end)
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        if tokens.iter().all(Vec::is_empty) {
+            continue;
+        }
+
+        let spans = |tokens: &[ComparedToken]| {
+            tokens
+                .iter()
+                .map(|token| (token.byte_start, token.byte_end))
+                .collect::<Vec<_>>()
+        };
+        let agreed = tokens
+            .windows(2)
+            .all(|pair| spans(&pair[0]) == spans(&pair[1]));
+        segments.push(ComparedSegment {
+            byte_start,
+            byte_end,
+            text: text[byte_start..byte_end].to_string(),
+            agreed,
+            tokens,
+        });
+    }
+
+    Ok(Comparison {
+        names: tokenizers
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect(),
+        segments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TestDictionary;
+
+    #[test]
+    fn test_compare_tokenizers() {
+        let (_short_dir, short) = TestDictionary::new(
+            "\
+東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー
+都庁,0,0,100,名詞,一般,*,*,*,*,都庁,トチョウ,トチョー
+前,0,0,100,名詞,副詞可能,*,*,*,*,前,マエ,マエ
+",
+        )
+        .unknown_cost(5000)
+        .tokenizer();
+        let (_long_dir, long) = TestDictionary::new(
+            "\
+東京都庁,0,0,100,名詞,固有名詞,組織,*,*,*,東京都庁,トウキョウトチョウ,トーキョートチョー
+前,0,0,100,名詞,一般,*,*,*,*,前,マエ,マエ
+",
+        )
+        .unknown_cost(5000)
+        .tokenizer();
+
+        let comparison =
+            compare_tokenizers(&[("short", &short), ("long", &long)], "東京都庁前").unwrap();
+        assert_eq!(vec!["short", "long"], comparison.names);
+        assert_eq!(2, comparison.segments.len());
+
+        let segment = &comparison.segments[0];
+        assert_eq!("東京都庁", segment.text);
+        assert!(!segment.agreed);
+        let surfaces = |tokens: &[ComparedToken]| {
+            tokens
+                .iter()
+                .map(|token| token.surface.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["東京", "都庁"], surfaces(&segment.tokens[0]));
+        assert_eq!(vec!["東京都庁"], surfaces(&segment.tokens[1]));
+
+        // The boundaries agree even though the details differ
+        let segment = &comparison.segments[1];
+        assert_eq!((12, 15), (segment.byte_start, segment.byte_end));
+        assert!(segment.agreed);
+        assert_eq!("副詞可能", segment.tokens[0][0].details[1]);
+        assert_eq!("一般", segment.tokens[1][0].details[1]);
+
+        assert_eq!(1, comparison.disagreements().count());
+        assert_eq!(0.5, comparison.agreement());
+        assert!(!comparison.is_agreed());
+        assert!(
+            compare_tokenizers(&[("short", &short), ("again", &short)], "東京都庁前")
+                .unwrap()
+                .is_agreed()
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Responses in the shape of the Elasticsearch `_analyze` API, for clients that call Elasticsearch
+//! only to analyze text.
+
+use serde::Serialize;
+
+use crate::LinderaResult;
+use crate::token::Token;
+use crate::tokenizer::Tokenizer;
+
+/// The token type reported for every token, as the Kuromoji and Nori tokenizers do.
+pub const TOKEN_TYPE: &str = "word";
+
+/// The response of the `_analyze` API: `{"tokens": [...]}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AnalyzeResponse {
+    pub tokens: Vec<AnalyzeToken>,
+}
+
+/// A token of the `_analyze` API.
+///
+/// The offsets count UTF-16 code units, as the offsets of Elasticsearch (Java `char`s) do, and not
+/// bytes as the offsets of [`Token`] do.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AnalyzeToken {
+    pub token: String,
+    pub start_offset: usize,
+    pub end_offset: usize,
+    #[serde(rename = "type")]
+    pub token_type: String,
+    pub position: usize,
+    /// Only written when the token spans more than one position, like Elasticsearch does.
+    #[serde(rename = "positionLength", skip_serializing_if = "is_one")]
+    pub position_length: usize,
+}
+
+fn is_one(value: &usize) -> bool {
+    *value == 1
+}
+
+/// Converts byte offsets of a text to UTF-16 offsets, walking the text once for offsets given in
+/// increasing order.
+struct Utf16Offsets<'a> {
+    text: &'a str,
+    byte_offset: usize,
+    utf16_offset: usize,
+}
+
+impl<'a> Utf16Offsets<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            byte_offset: 0,
+            utf16_offset: 0,
+        }
+    }
+
+    fn get(&mut self, byte_offset: usize) -> usize {
+        let byte_offset = byte_offset.min(self.text.len());
+        if byte_offset < self.byte_offset {
+            self.byte_offset = 0;
+            self.utf16_offset = 0;
+        }
+        // Offsets that are not on a character boundary are counted up to the boundary before them
+        let mut end = byte_offset;
+        while !self.text.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end >= self.byte_offset {
+            self.utf16_offset += self.text[self.byte_offset..end]
+                .chars()
+                .map(char::len_utf16)
+                .sum::<usize>();
+            self.byte_offset = end;
+        }
+
+        self.utf16_offset
+    }
+}
+
+impl AnalyzeResponse {
+    /// Builds the response from the tokens of `text`.
+    pub fn new(text: &str, tokens: &[Token]) -> Self {
+        let mut offsets = Utf16Offsets::new(text);
+        let tokens = tokens
+            .iter()
+            .map(|token| AnalyzeToken {
+                token: token.surface.to_string(),
+                start_offset: offsets.get(token.byte_start),
+                end_offset: offsets.get(token.byte_end),
+                token_type: TOKEN_TYPE.to_string(),
+                position: token.position,
+                position_length: token.position_length.max(1),
+            })
+            .collect();
+
+        Self { tokens }
+    }
+}
+
+impl Tokenizer {
+    /// Tokenizes the text and returns the tokens as the Elasticsearch `_analyze` API does.
+    pub fn analyze(&self, text: &str) -> LinderaResult<AnalyzeResponse> {
+        let tokens = self.tokenize(text)?;
+
+        Ok(AnalyzeResponse::new(text, &tokens))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf16_offsets() {
+        // "𠮷" is 4 bytes and 2 UTF-16 code units
+        let text = "𠮷野家でaを";
+        let mut offsets = Utf16Offsets::new(text);
+
+        assert_eq!(0, offsets.get(0));
+        assert_eq!(2, offsets.get(4));
+        assert_eq!(4, offsets.get(10));
+        assert_eq!(5, offsets.get(13));
+        assert_eq!(6, offsets.get(14));
+        assert_eq!(7, offsets.get(17));
+        // Going back starts over
+        assert_eq!(3, offsets.get(7));
+        // Offsets inside a character and past the end are clamped
+        assert_eq!(3, offsets.get(8));
+        assert_eq!(7, offsets.get(100));
+    }
+
+    #[test]
+    fn test_analyze_token_serialization() {
+        let response = AnalyzeResponse {
+            tokens: vec![
+                AnalyzeToken {
+                    token: "関西国際空港".to_string(),
+                    start_offset: 0,
+                    end_offset: 6,
+                    token_type: TOKEN_TYPE.to_string(),
+                    position: 0,
+                    position_length: 3,
+                },
+                AnalyzeToken {
+                    token: "関西".to_string(),
+                    start_offset: 0,
+                    end_offset: 2,
+                    token_type: TOKEN_TYPE.to_string(),
+                    position: 0,
+                    position_length: 1,
+                },
+            ],
+        };
+
+        assert_eq!(
+            serde_json::json!({
+                "tokens": [
+                    {
+                        "token": "関西国際空港",
+                        "start_offset": 0,
+                        "end_offset": 6,
+                        "type": "word",
+                        "position": 0,
+                        "positionLength": 3
+                    },
+                    {
+                        "token": "関西",
+                        "start_offset": 0,
+                        "end_offset": 2,
+                        "type": "word",
+                        "position": 0
+                    }
+                ]
+            }),
+            serde_json::to_value(&response).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_analyze_ipadic() {
+        use crate::dictionary::load_dictionary;
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+
+        let dictionary = load_dictionary("embedded://ipadic").unwrap();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let tokenizer = Tokenizer::new(segmenter);
+
+        let response = tokenizer.analyze("𠮷野家の牛丼").unwrap();
+        let last = response.tokens.last().unwrap();
+        assert_eq!("牛丼", last.token);
+        // "𠮷" counts as two UTF-16 code units
+        assert_eq!((5, 7), (last.start_offset, last.end_offset));
+        assert!(
+            response
+                .tokens
+                .iter()
+                .all(|token| token.token_type == "word")
+        );
+    }
+}
@@ -0,0 +1,387 @@
+//! Evaluation of segmentations and parts-of-speech against an annotated corpus, for measuring
+//! the effect of a cost tuning or a dictionary change.
+//!
+//! The corpus is read in the CoNLL-U format of Universal Dependencies (e.g. UD Japanese GSD), or
+//! in the output format of MeCab, a line of a surface and its comma-separated features separated
+//! by a tab per token and an `EOS` line after each sentence. The word and the boundary scores are
+//! computed on the characters of the sentences without their whitespace, so the reference and the
+//! tokenizer do not need to agree on the spaces between the words.
+//!
+//! The parts-of-speech are compared on their first levels only (by default the major
+//! part-of-speech), as the XPOS of UD Japanese (`名詞-普通名詞-一般`) and the fields of the
+//! dictionaries (`名詞,固有名詞,...`) split them differently.
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::LinderaResult;
+use crate::error::LinderaErrorKind;
+use crate::tokenizer::Tokenizer;
+
+/// The format of an annotated corpus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorpusFormat {
+    Conllu,
+    Mecab,
+}
+
+impl FromStr for CorpusFormat {
+    type Err = crate::error::LinderaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "conllu" => Ok(CorpusFormat::Conllu),
+            "mecab" => Ok(CorpusFormat::Mecab),
+            _ => {
+                Err(LinderaErrorKind::Args
+                    .with_error(anyhow::anyhow!("Invalid corpus format: {s}")))
+            }
+        }
+    }
+}
+
+/// A word of an annotated sentence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnotatedWord {
+    pub surface: String,
+    /// The part-of-speech, with its levels separated by `-`, if annotated.
+    pub part_of_speech: Option<String>,
+}
+
+/// A sentence of an annotated corpus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnotatedSentence {
+    /// The text given to the tokenizer.
+    pub text: String,
+    pub words: Vec<AnnotatedWord>,
+}
+
+/// Parses an annotated corpus.
+pub fn parse_corpus(corpus: &str, format: CorpusFormat) -> LinderaResult<Vec<AnnotatedSentence>> {
+    match format {
+        CorpusFormat::Conllu => parse_conllu(corpus),
+        CorpusFormat::Mecab => Ok(parse_mecab(corpus)),
+    }
+}
+
+fn parse_conllu(corpus: &str) -> LinderaResult<Vec<AnnotatedSentence>> {
+    let mut sentences = Vec::new();
+    let mut text = None;
+    let mut joined = String::new();
+    let mut words = Vec::new();
+    for (index, line) in corpus.lines().chain([""]).enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() {
+            if !words.is_empty() {
+                sentences.push(AnnotatedSentence {
+                    text: text.take().unwrap_or_else(|| joined.trim_end().to_string()),
+                    words: std::mem::take(&mut words),
+                });
+            }
+            text = None;
+            joined.clear();
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            if let Some(sentence_text) = comment.trim_start().strip_prefix("text =") {
+                text = Some(sentence_text.trim().to_string());
+            }
+            continue;
+        }
+
+        let columns = line.split('\t').collect::<Vec<_>>();
+        if columns.len() != 10 {
+            return Err(LinderaErrorKind::Parse.with_error(anyhow::anyhow!(
+                "line {}: expected 10 columns, found {}",
+                index + 1,
+                columns.len()
+            )));
+        }
+        // The multiword tokens and the empty nodes are not words of the text
+        if columns[0].contains(['-', '.']) {
+            continue;
+        }
+        joined.push_str(columns[1]);
+        if !columns[9].split('|').any(|misc| misc == "SpaceAfter=No") {
+            joined.push(' ');
+        }
+        let part_of_speech = [columns[4], columns[3]]
+            .into_iter()
+            .find(|pos| *pos != "_")
+            .map(str::to_string);
+        words.push(AnnotatedWord {
+            surface: columns[1].to_string(),
+            part_of_speech,
+        });
+    }
+    Ok(sentences)
+}
+
+fn parse_mecab(corpus: &str) -> Vec<AnnotatedSentence> {
+    let mut sentences = Vec::new();
+    let mut words = Vec::new();
+    for line in corpus.lines().chain(["EOS"]) {
+        let line = line.trim_end_matches('\r');
+        if line == "EOS" {
+            if !words.is_empty() {
+                let words = std::mem::take(&mut words);
+                sentences.push(AnnotatedSentence {
+                    text: words
+                        .iter()
+                        .map(|word: &AnnotatedWord| word.surface.as_str())
+                        .collect(),
+                    words,
+                });
+            }
+            continue;
+        }
+        let Some((surface, features)) = line.split_once('\t') else {
+            continue;
+        };
+        let part_of_speech = features
+            .split(',')
+            .take_while(|feature| *feature != "*")
+            .collect::<Vec<_>>();
+        words.push(AnnotatedWord {
+            surface: surface.to_string(),
+            part_of_speech: (!part_of_speech.is_empty()).then(|| part_of_speech.join("-")),
+        });
+    }
+    sentences
+}
+
+/// The numbers of correct, predicted and reference items of a score.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Score {
+    pub correct: usize,
+    pub predicted: usize,
+    pub reference: usize,
+}
+
+impl Score {
+    pub fn precision(&self) -> f64 {
+        ratio(self.correct, self.predicted)
+    }
+
+    pub fn recall(&self) -> f64 {
+        ratio(self.correct, self.reference)
+    }
+
+    pub fn f1(&self) -> f64 {
+        let (precision, recall) = (self.precision(), self.recall());
+        if precision + recall == 0.0 {
+            return 0.0;
+        }
+        2.0 * precision * recall / (precision + recall)
+    }
+
+    fn add<T: Ord>(&mut self, predicted: &BTreeSet<T>, reference: &BTreeSet<T>) {
+        self.correct += predicted.intersection(reference).count();
+        self.predicted += predicted.len();
+        self.reference += reference.len();
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        return 0.0;
+    }
+    numerator as f64 / denominator as f64
+}
+
+/// The scores of a tokenizer on an annotated corpus.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Evaluation {
+    pub sentences: usize,
+    /// The words whose span matches a reference word.
+    pub words: Score,
+    /// The boundaries between two words, the start and the end of the sentences excluded.
+    pub boundaries: Score,
+    /// The correctly segmented words that have an annotated part-of-speech.
+    pub pos_total: usize,
+    /// The correctly segmented words whose part-of-speech matches the annotated one.
+    pub pos_correct: usize,
+    /// The sentences whose text does not have the characters of their words, which are not
+    /// scored.
+    pub mismatched_sentences: usize,
+}
+
+impl Evaluation {
+    /// The ratio of the correctly segmented words with the annotated part-of-speech.
+    pub fn pos_accuracy(&self) -> f64 {
+        ratio(self.pos_correct, self.pos_total)
+    }
+}
+
+/// Compares the segmentations and parts-of-speech of `tokenizer` with the annotated sentences.
+///
+/// The parts-of-speech are compared on their first `pos_depth` levels, the details of the
+/// tokens being read as the levels of their part-of-speech until the first `*`.
+pub fn evaluate(
+    tokenizer: &Tokenizer,
+    sentences: &[AnnotatedSentence],
+    pos_depth: usize,
+) -> LinderaResult<Evaluation> {
+    let mut evaluation = Evaluation::default();
+    for sentence in sentences {
+        // The spans of the reference words in the characters of the text without whitespace
+        let mut reference = Vec::with_capacity(sentence.words.len());
+        let mut length = 0;
+        for word in &sentence.words {
+            let end = length + non_whitespace_len(&word.surface);
+            reference.push(((length, end), word.part_of_speech.as_deref()));
+            length = end;
+        }
+        if length != non_whitespace_len(&sentence.text) {
+            evaluation.mismatched_sentences += 1;
+            continue;
+        }
+        evaluation.sentences += 1;
+
+        let mut tokens = tokenizer.tokenize(&sentence.text)?;
+        let mut predicted = Vec::with_capacity(tokens.len());
+        for token in tokens.iter_mut() {
+            let start = non_whitespace_len(&sentence.text[..token.byte_start]);
+            let end = start + non_whitespace_len(&sentence.text[token.byte_start..token.byte_end]);
+            if start == end {
+                continue;
+            }
+            let part_of_speech = token
+                .details()
+                .into_iter()
+                .take_while(|detail| *detail != "*")
+                .collect::<Vec<_>>()
+                .join("-");
+            predicted.push(((start, end), part_of_speech));
+        }
+
+        let reference_words = reference
+            .iter()
+            .map(|(span, _)| *span)
+            .collect::<BTreeSet<_>>();
+        let predicted_words = predicted
+            .iter()
+            .map(|(span, _)| *span)
+            .collect::<BTreeSet<_>>();
+        evaluation.words.add(&predicted_words, &reference_words);
+
+        let boundaries = |words: &BTreeSet<(usize, usize)>| {
+            words
+                .iter()
+                .map(|&(_, end)| end)
+                .filter(|&end| end != length)
+                .collect::<BTreeSet<_>>()
+        };
+        evaluation
+            .boundaries
+            .add(&boundaries(&predicted_words), &boundaries(&reference_words));
+
+        for (span, reference_pos) in &reference {
+            let Some(reference_pos) = reference_pos else {
+                continue;
+            };
+            let Some((_, predicted_pos)) = predicted.iter().find(|(other, _)| other == span) else {
+                continue;
+            };
+            evaluation.pos_total += 1;
+            if truncate_pos(reference_pos, pos_depth) == truncate_pos(predicted_pos, pos_depth) {
+                evaluation.pos_correct += 1;
+            }
+        }
+    }
+    Ok(evaluation)
+}
+
+fn non_whitespace_len(text: &str) -> usize {
+    text.chars().filter(|c| !c.is_whitespace()).count()
+}
+
+fn truncate_pos(part_of_speech: &str, depth: usize) -> Vec<&str> {
+    part_of_speech.split('-').take(depth).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TestDictionary;
+
+    const CONLLU: &str = "\
+# sent_id = test-1
+# text = 東京都庁前
+1\t東京\t東京\tPROPN\t名詞-固有名詞-地名-一般\t_\t2\tcompound\t_\tSpaceAfter=No
+2\t都庁\t都庁\tNOUN\t名詞-普通名詞-一般\t_\t3\tcompound\t_\tSpaceAfter=No
+3\t前\t前\tNOUN\t名詞-普通名詞-副詞可能\t_\t0\troot\t_\tSpaceAfter=No
+
+# text = 都庁前
+1\t都庁前\t都庁前\tNOUN\t_\t_\t0\troot\t_\tSpaceAfter=No
+";
+
+    #[test]
+    fn test_parse_corpus() {
+        let sentences = parse_corpus(CONLLU, CorpusFormat::Conllu).unwrap();
+        assert_eq!(2, sentences.len());
+        assert_eq!("東京都庁前", sentences[0].text);
+        assert_eq!(
+            AnnotatedWord {
+                surface: "東京".to_string(),
+                part_of_speech: Some("名詞-固有名詞-地名-一般".to_string()),
+            },
+            sentences[0].words[0]
+        );
+        assert_eq!(
+            Some("NOUN"),
+            sentences[1].words[0].part_of_speech.as_deref()
+        );
+        assert!(parse_corpus("1\t東京\n", CorpusFormat::Conllu).is_err());
+
+        let sentences = parse_corpus(
+            "東京\t名詞,固有名詞,地域,一般,*,*\n前\t名詞,一般,*,*\nEOS\n",
+            CorpusFormat::Mecab,
+        )
+        .unwrap();
+        assert_eq!("東京前", sentences[0].text);
+        assert_eq!(
+            Some("名詞-固有名詞-地域-一般"),
+            sentences[0].words[0].part_of_speech.as_deref()
+        );
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let (_dict_dir, tokenizer) = TestDictionary::new(
+            "\
+東京都庁,0,0,100,名詞,固有名詞,組織,*,*,*,東京都庁,トウキョウトチョウ,トーキョートチョー
+都庁,0,0,100,名詞,一般,*,*,*,*,都庁,トチョウ,トチョー
+前,0,0,100,副詞,*,*,*,*,*,前,マエ,マエ
+",
+        )
+        .unknown_cost(5000)
+        .tokenizer();
+
+        // 東京都庁 / 前 against 東京 / 都庁 / 前, and 都庁 / 前 against 都庁前
+        let sentences = parse_corpus(CONLLU, CorpusFormat::Conllu).unwrap();
+        let evaluation = evaluate(&tokenizer, &sentences, 1).unwrap();
+        assert_eq!(2, evaluation.sentences);
+        assert_eq!(
+            Score {
+                correct: 1,
+                predicted: 4,
+                reference: 4,
+            },
+            evaluation.words
+        );
+        assert_eq!(0.25, evaluation.words.f1());
+        assert_eq!(
+            Score {
+                correct: 1,
+                predicted: 2,
+                reference: 2,
+            },
+            evaluation.boundaries
+        );
+        assert_eq!(1, evaluation.pos_total);
+        assert_eq!(0.0, evaluation.pos_accuracy());
+    }
+}
@@ -0,0 +1,322 @@
+//! Expansion of search queries into the terms a search backend can match, with the origin of each
+//! term, so that boolean queries can be built without running the analysis chain again.
+//!
+//! The query is analyzed by the tokenizer, and the terms of each position are the text of the
+//! query, the token output by the filters (e.g. replaced by the `mapping` filter), its base form,
+//! its reading and its hiragana and katakana forms. The tokens stacked on the same position by the
+//! filters are synonyms, and the tokens covered by a token spanning several positions (the
+//! morphemes of the `mixed` mode of `korean_decompound`) are the parts of a compound.
+
+use std::fmt::Write;
+
+use kanaria::string::UCSStr;
+use serde::Serialize;
+
+use crate::LinderaResult;
+use crate::token::Token;
+use crate::tokenizer::Tokenizer;
+
+/// Where an expanded term comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TermOrigin {
+    /// The text of the query.
+    Surface,
+    /// The token output by the token filters, when it differs from the text of the query.
+    Analyzed,
+    BaseForm,
+    /// The reading in katakana.
+    Reading,
+    Hiragana,
+    Katakana,
+    /// A token stacked on the same position by a token filter.
+    Synonym,
+    /// A part of a token spanning several positions.
+    Decompound,
+}
+
+/// A term of an expanded query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExpandedTerm {
+    pub term: String,
+    pub origin: TermOrigin,
+}
+
+/// The terms of a position of the query, any of which may match.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExpandedPosition {
+    pub position: usize,
+    /// The number of positions the terms span.
+    pub position_length: usize,
+    /// The byte range of the query covered by the position.
+    pub byte_start: usize,
+    pub byte_end: usize,
+    /// The alternative terms, without the parts of a compound.
+    pub terms: Vec<ExpandedTerm>,
+    /// The parts of a compound, which match together in this order.
+    pub parts: Vec<ExpandedTerm>,
+}
+
+impl ExpandedPosition {
+    fn push(&mut self, term: &str, origin: TermOrigin) {
+        if term.is_empty() || term == "*" || self.terms.iter().any(|known| known.term == term) {
+            return;
+        }
+        self.terms.push(ExpandedTerm {
+            term: term.to_string(),
+            origin,
+        });
+    }
+}
+
+/// The result of [`Tokenizer::expand_query`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueryExpansion {
+    pub positions: Vec<ExpandedPosition>,
+}
+
+fn quote(term: &str) -> String {
+    format!("\"{}\"", term.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl QueryExpansion {
+    /// All the terms, with the parts of the compounds.
+    pub fn terms(&self) -> impl Iterator<Item = &ExpandedTerm> {
+        self.positions
+            .iter()
+            .flat_map(|position| position.terms.iter().chain(&position.parts))
+    }
+
+    /// The expansion as a boolean query of quoted terms, the terms of a position joined by `OR`
+    /// and the positions by `AND`, e.g. `("東京" OR "トウキョウ") AND ("駅" OR "エキ")`. The parts of a
+    /// compound are one more alternative of its position, joined by `AND`.
+    pub fn to_boolean_query(&self) -> String {
+        let mut query = String::new();
+        for position in &self.positions {
+            if !query.is_empty() {
+                query.push_str(" AND ");
+            }
+            let mut alternatives = position
+                .terms
+                .iter()
+                .map(|term| quote(&term.term))
+                .collect::<Vec<_>>();
+            if !position.parts.is_empty() {
+                let parts = position
+                    .parts
+                    .iter()
+                    .map(|part| quote(&part.term))
+                    .collect::<Vec<_>>();
+                alternatives.push(format!("({})", parts.join(" AND ")));
+            }
+            let _ = write!(query, "({})", alternatives.join(" OR "));
+        }
+        query
+    }
+}
+
+/// Expands the tokens of `query`, as returned by [`Tokenizer::tokenize`].
+pub fn expand_tokens(query: &str, tokens: &mut [Token]) -> QueryExpansion {
+    let mut positions: Vec<ExpandedPosition> = Vec::new();
+    for token in tokens.iter_mut() {
+        let position_length = token.position_length.max(1);
+        let surface = token.surface.to_string();
+        let text = query
+            .get(token.byte_start..token.byte_end)
+            .unwrap_or(&surface)
+            .to_string();
+
+        if let Some(last) = positions.last_mut() {
+            // A token stacked on the last position
+            if token.position == last.position && position_length == last.position_length {
+                last.push(&surface, TermOrigin::Synonym);
+                continue;
+            }
+            // A part of the last token
+            if last.position_length > 1
+                && last.position <= token.position
+                && token.position + position_length <= last.position + last.position_length
+            {
+                last.parts.push(ExpandedTerm {
+                    term: surface,
+                    origin: TermOrigin::Decompound,
+                });
+                continue;
+            }
+        }
+
+        let mut position = ExpandedPosition {
+            position: token.position,
+            position_length,
+            byte_start: token.byte_start,
+            byte_end: token.byte_end,
+            terms: Vec::new(),
+            parts: Vec::new(),
+        };
+        position.push(&text, TermOrigin::Surface);
+        position.push(&surface, TermOrigin::Analyzed);
+        if let Some(base_form) = ["base_form", "orthographic_base_form"]
+            .into_iter()
+            .find_map(|field_name| token.get(field_name).map(str::to_string))
+        {
+            position.push(&base_form, TermOrigin::BaseForm);
+        }
+        let reading = token.reading().map(str::to_string);
+        if let Some(reading) = &reading {
+            position.push(reading, TermOrigin::Reading);
+        }
+        // The kana forms of the surface, or of the reading for a word in kanji
+        let kana = reading.unwrap_or_else(|| surface.clone());
+        position.push(
+            &UCSStr::from_str(&kana).hiragana().to_string(),
+            TermOrigin::Hiragana,
+        );
+        position.push(
+            &UCSStr::from_str(&surface).katakana().to_string(),
+            TermOrigin::Katakana,
+        );
+        positions.push(position);
+    }
+    QueryExpansion { positions }
+}
+
+impl Tokenizer {
+    /// Analyzes `query` and expands each of its positions into the terms that may match it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "embed-ipadic")]
+    /// # {
+    /// # use lindera::dictionary::load_dictionary;
+    /// # use lindera::mode::Mode;
+    /// # use lindera::segmenter::Segmenter;
+    /// # use lindera::tokenizer::Tokenizer;
+    /// # let dictionary = load_dictionary("embedded://ipadic")?;
+    /// # let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+    /// let expansion = tokenizer.expand_query("東京駅")?;
+    /// for term in expansion.terms() {
+    ///     println!("{} {:?}", term.term, term.origin);
+    /// }
+    /// println!("{}", expansion.to_boolean_query());
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn expand_query(&self, query: &str) -> LinderaResult<QueryExpansion> {
+        let mut tokens = self.tokenize(query)?;
+        Ok(expand_tokens(query, &mut tokens))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+    use crate::test_util::TestDictionary;
+    use crate::tokenizer::TokenizerBuilder;
+
+    #[test]
+    fn test_expand_query() {
+        let dict_dir = TestDictionary::new(
+            "\
+東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー
+駅,0,0,100,名詞,接尾,地域,*,*,*,駅,エキ,エキ
+行っ,0,0,100,動詞,自立,*,*,五段・カ行促音便,連用タ接続,行く,イッ,イッ
+",
+        )
+        .unknown_cost(5000)
+        .build();
+        let mut builder = TokenizerBuilder::from_config(serde_json::json!({
+            "token_filters": [
+                { "kind": "mapping", "args": { "mapping": { "駅": "station" } } }
+            ]
+        }))
+        .unwrap();
+        builder
+            .set_segmenter_dictionary(dict_dir.path().to_str().unwrap())
+            .set_segmenter_keep_whitespace(true);
+        let tokenizer = builder.build().unwrap();
+
+        let expansion = tokenizer.expand_query("東京駅").unwrap();
+        fn terms(position: &ExpandedPosition) -> Vec<(&str, TermOrigin)> {
+            position
+                .terms
+                .iter()
+                .map(|term| (term.term.as_str(), term.origin))
+                .collect()
+        }
+        assert_eq!(
+            vec![
+                ("東京", TermOrigin::Surface),
+                ("トウキョウ", TermOrigin::Reading),
+                ("とうきょう", TermOrigin::Hiragana),
+            ],
+            terms(&expansion.positions[0])
+        );
+        assert_eq!(
+            vec![
+                ("駅", TermOrigin::Surface),
+                ("station", TermOrigin::Analyzed),
+                ("エキ", TermOrigin::Reading),
+                ("えき", TermOrigin::Hiragana),
+            ],
+            terms(&expansion.positions[1])
+        );
+
+        let expansion = tokenizer.expand_query("行っ").unwrap();
+        assert_eq!(
+            vec![
+                ("行っ", TermOrigin::Surface),
+                ("行く", TermOrigin::BaseForm),
+                ("イッ", TermOrigin::Reading),
+                ("いっ", TermOrigin::Hiragana),
+                ("行ッ", TermOrigin::Katakana),
+            ],
+            terms(&expansion.positions[0])
+        );
+
+        // A compound of 東京 and 駅, followed by its parts
+        let tokens = tokenizer.tokenize("東京駅").unwrap();
+        let mut compound = tokens[0].clone();
+        compound.surface = Cow::Borrowed("東京駅");
+        compound.byte_end = tokens[1].byte_end;
+        compound.position_length = 2;
+        let mut tokens = vec![compound, tokens[0].clone(), tokens[1].clone()];
+        let expansion = expand_tokens("東京駅", &mut tokens);
+        assert_eq!(1, expansion.positions.len());
+        assert_eq!(
+            vec!["東京", "station"],
+            expansion.positions[0]
+                .parts
+                .iter()
+                .map(|part| part.term.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!(
+            expansion
+                .to_boolean_query()
+                .ends_with(" OR (\"東京\" AND \"station\"))")
+        );
+
+        // A synonym stacked on 東京
+        let mut tokens = tokenizer.tokenize("東京駅").unwrap();
+        let mut synonym = tokens[0].clone();
+        synonym.surface = Cow::Borrowed("東京都");
+        tokens.insert(1, synonym);
+        let expansion = expand_tokens("東京駅", &mut tokens);
+        assert_eq!(
+            Some(&ExpandedTerm {
+                term: "東京都".to_string(),
+                origin: TermOrigin::Synonym,
+            }),
+            expansion.positions[0].terms.last()
+        );
+        assert_eq!(
+            "(\"東京\" OR \"トウキョウ\" OR \"とうきょう\" OR \"東京都\") AND (\"駅\" OR \"station\" OR \"エキ\" OR \"えき\")",
+            expansion.to_boolean_query()
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Intermediate results of the analysis chain, for seeing which stage transformed or dropped a
+//! token (like `_analyze` with `explain` in Elasticsearch).
+
+use std::borrow::Cow;
+
+use serde::Serialize;
+
+use crate::LinderaResult;
+use crate::character_filter::OffsetMapping;
+use crate::dictionary::Lattice;
+use crate::token::Token;
+use crate::tokenizer::{Tokenizer, correct_token_offsets};
+
+/// The result of [`Tokenizer::explain`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Explanation {
+    /// The text after each character filter, in the order they are applied.
+    pub character_filters: Vec<CharacterFilterStep>,
+    /// The tokens produced by the segmenter, before any token filter.
+    pub tokenizer: Vec<ExplainedToken>,
+    /// The tokens after each token filter, in the order they are applied.
+    pub token_filters: Vec<TokenFilterStep>,
+}
+
+/// The text after a character filter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CharacterFilterStep {
+    pub name: String,
+    pub text: String,
+}
+
+/// The tokens after a token filter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TokenFilterStep {
+    pub name: String,
+    pub tokens: Vec<ExplainedToken>,
+}
+
+/// A snapshot of a token, with byte offsets in the original text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExplainedToken {
+    pub surface: String,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub position: usize,
+    pub position_length: usize,
+    pub word_id: u32,
+    pub details: Vec<String>,
+}
+
+impl ExplainedToken {
+    fn new(token: &Token, offset_mappings: &[OffsetMapping], text_len: usize) -> Self {
+        let mut token = token.clone();
+        correct_token_offsets(&mut token, offset_mappings, text_len);
+
+        Self {
+            surface: token.surface.to_string(),
+            byte_start: token.byte_start,
+            byte_end: token.byte_end,
+            position: token.position,
+            position_length: token.position_length,
+            word_id: token.word_id.id,
+            details: token
+                .details()
+                .iter()
+                .map(|detail| detail.to_string())
+                .collect(),
+        }
+    }
+}
+
+fn explain_tokens(
+    tokens: &[Token],
+    offset_mappings: &[OffsetMapping],
+    text_len: usize,
+) -> Vec<ExplainedToken> {
+    tokens
+        .iter()
+        .map(|token| ExplainedToken::new(token, offset_mappings, text_len))
+        .collect()
+}
+
+impl Tokenizer {
+    /// Tokenizes the text like [`Tokenizer::tokenize`], and returns the text after every
+    /// character filter and the tokens after the segmenter and every token filter.
+    ///
+    /// This copies the tokens at every stage, so it is meant for debugging analysis chains rather
+    /// than for indexing.
+    pub fn explain(&self, text: &str) -> LinderaResult<Explanation> {
+        let mut normalized_text = text.to_string();
+        let mut offset_mappings: Vec<OffsetMapping> = Vec::new();
+
+        let mut character_filters = Vec::with_capacity(self.character_filters.len());
+        for character_filter in &self.character_filters {
+            let mapping = character_filter.apply(&mut normalized_text)?;
+            if !mapping.is_empty() {
+                offset_mappings.push(mapping);
+            }
+            character_filters.push(CharacterFilterStep {
+                name: character_filter.name().to_string(),
+                text: normalized_text.clone(),
+            });
+        }
+
+        let text_len = normalized_text.len();
+        let mut lattice = Lattice::default();
+        let mut tokens = self
+            .segmenter
+            .segment_with_lattice(Cow::Owned(normalized_text), &mut lattice)?;
+        let tokenizer = explain_tokens(&tokens, &offset_mappings, text_len);
+
+        let mut token_filters = Vec::with_capacity(self.token_filters.len());
+        for token_filter in &self.token_filters {
+            token_filter.apply(&mut tokens)?;
+            token_filters.push(TokenFilterStep {
+                name: token_filter.name().to_string(),
+                tokens: explain_tokens(&tokens, &offset_mappings, text_len),
+            });
+        }
+
+        Ok(Explanation {
+            character_filters,
+            tokenizer,
+            token_filters,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_explain_ipadic() {
+        use std::path::PathBuf;
+
+        use crate::tokenizer::TokenizerBuilder;
+
+        let config_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../resources")
+            .join("config")
+            .join("lindera.yml");
+
+        let tokenizer = TokenizerBuilder::from_file(&config_file)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let text = "ﾘﾝﾃﾞﾗは形態素解析ｴﾝｼﾞﾝです。";
+        let explanation = tokenizer.explain(text).unwrap();
+
+        let names = explanation
+            .character_filters
+            .iter()
+            .map(|step| step.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["unicode_normalize", "japanese_iteration_mark", "mapping"]
+        );
+        assert_eq!(
+            explanation.character_filters[0].text,
+            "リンデラは形態素解析エンジンです。"
+        );
+        assert_eq!(
+            explanation.character_filters[2].text,
+            "Linderaは形態素解析エンジンです。"
+        );
+
+        // The particle is dropped by the stop tags filter
+        assert!(
+            explanation
+                .tokenizer
+                .iter()
+                .any(|token| token.surface == "は")
+        );
+        let step = explanation
+            .token_filters
+            .iter()
+            .find(|step| step.name == "japanese_stop_tags")
+            .unwrap();
+        assert!(step.tokens.iter().all(|token| token.surface != "は"));
+
+        // The last step is the result of tokenize
+        let tokens = tokenizer.tokenize(text).unwrap();
+        let last = &explanation.token_filters.last().unwrap().tokens;
+        assert_eq!(last.len(), tokens.len());
+        for (explained, token) in last.iter().zip(tokens.iter()) {
+            assert_eq!(explained.surface, token.surface);
+            assert_eq!(explained.byte_start, token.byte_start);
+            assert_eq!(explained.byte_end, token.byte_end);
+        }
+    }
+}
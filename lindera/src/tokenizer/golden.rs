@@ -0,0 +1,238 @@
+//! Regression tests of segmentations against a golden corpus, for gating dictionary and config
+//! changes on the segmentations they break.
+//!
+//! A golden corpus is a text file of tab-separated lines of an input text and its expected
+//! segmentation, with the surfaces of the tokens separated by spaces (the tab is shown as `\t`):
+//!
+//! ```text
+//! # Proper nouns
+//! 東京都庁前で待つ\t東京 都庁 前 で 待つ
+//! ```
+//!
+//! The empty lines and the lines starting with `#` are ignored. The surfaces are compared after
+//! the character filters and the token filters of the tokenizer, as they are output.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::LinderaResult;
+use crate::error::LinderaErrorKind;
+use crate::tokenizer::Tokenizer;
+
+/// A text of a golden corpus with its expected segmentation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GoldenCase {
+    /// The line number of the case in the corpus, starting at 1.
+    pub line: usize,
+    pub text: String,
+    pub expected: Vec<String>,
+}
+
+/// Parses a golden corpus.
+pub fn parse_corpus(corpus: &str) -> LinderaResult<Vec<GoldenCase>> {
+    let mut cases = Vec::new();
+    for (index, line) in corpus.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (text, expected) = line.split_once('\t').ok_or_else(|| {
+            LinderaErrorKind::Parse.with_error(anyhow::anyhow!(
+                "line {}: expected a text and its segmentation separated by a tab",
+                index + 1
+            ))
+        })?;
+        cases.push(GoldenCase {
+            line: index + 1,
+            text: text.to_string(),
+            expected: expected.split_whitespace().map(str::to_string).collect(),
+        });
+    }
+    Ok(cases)
+}
+
+/// Reads and parses the golden corpus file `path`.
+pub fn read_corpus(path: &Path) -> LinderaResult<Vec<GoldenCase>> {
+    let corpus = fs::read_to_string(path).map_err(|err| {
+        LinderaErrorKind::Io
+            .with_error(anyhow::anyhow!(err))
+            .add_context(format!("Failed to read golden corpus: {}", path.display()))
+    })?;
+    parse_corpus(&corpus)
+}
+
+/// A case whose segmentation differs from the expected one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GoldenFailure {
+    pub line: usize,
+    pub text: String,
+    pub expected: Vec<String>,
+    pub actual: Vec<String>,
+}
+
+impl GoldenFailure {
+    /// The index of the first token that differs.
+    pub fn first_difference(&self) -> usize {
+        self.expected
+            .iter()
+            .zip(&self.actual)
+            .position(|(expected, actual)| expected != actual)
+            .unwrap_or_else(|| self.expected.len().min(self.actual.len()))
+    }
+}
+
+/// The result of [`run_golden_corpus`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GoldenReport {
+    pub cases: usize,
+    pub passed: usize,
+    pub failures: Vec<GoldenFailure>,
+}
+
+impl GoldenReport {
+    /// Whether all the cases have their expected segmentation.
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// The report as a JUnit XML test suite named `name`, with a test case per failure and one
+    /// for all the passed cases, as read by most CI services.
+    pub fn to_junit(&self, name: &str) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">",
+            escape_xml(name),
+            self.cases,
+            self.failures.len()
+        );
+        for failure in &self.failures {
+            let _ = writeln!(
+                xml,
+                "  <testcase classname=\"{}\" name=\"line {}\">",
+                escape_xml(name),
+                failure.line
+            );
+            let _ = writeln!(
+                xml,
+                "    <failure message=\"{}\">expected: {}\nactual: {}</failure>",
+                escape_xml(&failure.text),
+                escape_xml(&failure.expected.join(" ")),
+                escape_xml(&failure.actual.join(" "))
+            );
+            xml.push_str("  </testcase>\n");
+        }
+        if self.passed > 0 {
+            let _ = writeln!(
+                xml,
+                "  <testcase classname=\"{}\" name=\"{} passed case(s)\"/>",
+                escape_xml(name),
+                self.passed
+            );
+        }
+        xml.push_str("</testsuite>\n");
+        xml
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Tokenizes the text of each case with `tokenizer` and compares the surfaces of the tokens with
+/// the expected segmentation.
+pub fn run_golden_corpus(
+    tokenizer: &Tokenizer,
+    cases: &[GoldenCase],
+) -> LinderaResult<GoldenReport> {
+    let mut report = GoldenReport {
+        cases: cases.len(),
+        ..GoldenReport::default()
+    };
+    for case in cases {
+        let actual = tokenizer
+            .tokenize(&case.text)?
+            .iter()
+            .map(|token| token.surface.to_string())
+            .collect::<Vec<_>>();
+        if actual == case.expected {
+            report.passed += 1;
+        } else {
+            report.failures.push(GoldenFailure {
+                line: case.line,
+                text: case.text.clone(),
+                expected: case.expected.clone(),
+                actual,
+            });
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TestDictionary;
+
+    #[test]
+    fn test_parse_corpus() {
+        let cases = parse_corpus("# comment\n\n東京都庁\t東京 都庁\r\n前\t前\n").unwrap();
+        assert_eq!(
+            vec![
+                GoldenCase {
+                    line: 3,
+                    text: "東京都庁".to_string(),
+                    expected: vec!["東京".to_string(), "都庁".to_string()],
+                },
+                GoldenCase {
+                    line: 4,
+                    text: "前".to_string(),
+                    expected: vec!["前".to_string()],
+                },
+            ],
+            cases
+        );
+        assert!(parse_corpus("東京都庁\n").is_err());
+    }
+
+    #[test]
+    fn test_run_golden_corpus() {
+        let (_dict_dir, tokenizer) = TestDictionary::new(
+            "\
+東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー
+都庁,0,0,100,名詞,一般,*,*,*,*,都庁,トチョウ,トチョー
+",
+        )
+        .unknown_cost(5000)
+        .tokenizer();
+
+        let cases = parse_corpus("東京都庁\t東京 都庁\n東京都庁\t東京都庁\n").unwrap();
+        let report = run_golden_corpus(&tokenizer, &cases).unwrap();
+        assert_eq!(2, report.cases);
+        assert_eq!(1, report.passed);
+        assert!(!report.is_ok());
+        let failure = &report.failures[0];
+        assert_eq!(2, failure.line);
+        assert_eq!(vec!["東京", "都庁"], failure.actual);
+        assert_eq!(0, failure.first_difference());
+
+        let junit = report.to_junit("golden.tsv");
+        assert!(junit.contains("<testsuite name=\"golden.tsv\" tests=\"2\" failures=\"1\">"));
+        assert!(junit.contains("<testcase classname=\"golden.tsv\" name=\"line 2\">"));
+        assert!(junit.contains("expected: 東京都庁\nactual: 東京 都庁</failure>"));
+    }
+}
@@ -0,0 +1,191 @@
+//! The token stream as a graph, for checking the output of filters that add tokens spanning
+//! several positions (compound words, decompounding, synonyms).
+//!
+//! As in Lucene's `TokenStreamToDot`, the nodes are the positions and each token is an edge from
+//! its position to its position plus its position length. The edges use the attribute names of the
+//! Elasticsearch `_analyze` API.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::token::Token;
+
+/// Lucene's default token type.
+const DEFAULT_TOKEN_TYPE: &str = "word";
+
+/// A position in the token graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphNode {
+    pub id: usize,
+}
+
+/// A token in the token graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    /// The position of the token.
+    pub from: usize,
+    /// The position following the last position spanned by the token.
+    pub to: usize,
+    pub token: String,
+    pub start_offset: usize,
+    pub end_offset: usize,
+    #[serde(rename = "type")]
+    pub token_type: String,
+    pub position: usize,
+    #[serde(rename = "positionLength")]
+    pub position_length: usize,
+    pub details: Vec<String>,
+}
+
+impl GraphEdge {
+    fn new(token: &mut Token) -> Self {
+        let position_length = token.position_length.max(1);
+        Self {
+            from: token.position,
+            to: token.position + position_length,
+            token: token.surface.to_string(),
+            start_offset: token.byte_start,
+            end_offset: token.byte_end,
+            token_type: DEFAULT_TOKEN_TYPE.to_string(),
+            position: token.position,
+            position_length,
+            details: token
+                .details()
+                .iter()
+                .map(|detail| detail.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// A token stream as a graph of positions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TokenGraph {
+    /// The positions that begin or end a token, in ascending order.
+    pub nodes: Vec<GraphNode>,
+    /// The tokens, in the order of the token stream.
+    pub edges: Vec<GraphEdge>,
+}
+
+impl TokenGraph {
+    /// Builds the graph of the tokens returned by [`Tokenizer::tokenize`](super::Tokenizer::tokenize).
+    pub fn from_tokens(tokens: &mut [Token]) -> Self {
+        Self::from_edges(tokens.iter_mut().map(GraphEdge::new).collect())
+    }
+
+    fn from_edges(edges: Vec<GraphEdge>) -> Self {
+        let nodes = edges
+            .iter()
+            .flat_map(|edge| [edge.from, edge.to])
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|id| GraphNode { id })
+            .collect();
+
+        Self { nodes, edges }
+    }
+
+    /// Returns the positions that are not reachable from the first position by following the
+    /// edges.
+    ///
+    /// A filter that leaves a hole in the positions (e.g. a stop filter) or a token spanning more
+    /// positions than the tokens it replaces shows up here. An empty result means every path
+    /// through the graph starts at the first node and ends at the last one.
+    pub fn unreachable_nodes(&self) -> Vec<usize> {
+        let Some(first) = self.nodes.first() else {
+            return Vec::new();
+        };
+
+        let mut reachable = BTreeSet::from([first.id]);
+        // Edges always point forward, so the nodes can be visited in order
+        for node in &self.nodes {
+            if reachable.contains(&node.id) {
+                for edge in self.edges.iter().filter(|edge| edge.from == node.id) {
+                    reachable.insert(edge.to);
+                }
+            }
+        }
+
+        self.nodes
+            .iter()
+            .map(|node| node.id)
+            .filter(|id| !reachable.contains(id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(token: &str, position: usize, position_length: usize) -> GraphEdge {
+        GraphEdge {
+            from: position,
+            to: position + position_length,
+            token: token.to_string(),
+            start_offset: 0,
+            end_offset: 0,
+            token_type: DEFAULT_TOKEN_TYPE.to_string(),
+            position,
+            position_length,
+            details: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_token_graph_from_edges() {
+        // "関西国際空港" decompounded, with the compound word kept
+        let graph = TokenGraph::from_edges(vec![
+            edge("関西国際空港", 0, 3),
+            edge("関西", 0, 1),
+            edge("国際", 1, 1),
+            edge("空港", 2, 1),
+        ]);
+        assert_eq!(
+            vec![0, 1, 2, 3],
+            graph.nodes.iter().map(|node| node.id).collect::<Vec<_>>()
+        );
+        assert!(graph.unreachable_nodes().is_empty());
+
+        let value = serde_json::to_value(&graph).unwrap();
+        assert_eq!(3, value["edges"][0]["to"]);
+        assert_eq!(3, value["edges"][0]["positionLength"]);
+        assert_eq!("word", value["edges"][0]["type"]);
+        assert_eq!("関西", value["edges"][1]["token"]);
+    }
+
+    #[test]
+    fn test_token_graph_unreachable_nodes() {
+        // The token at position 1 was removed
+        let graph = TokenGraph::from_edges(vec![edge("東京", 0, 1), edge("行く", 2, 1)]);
+        assert_eq!(vec![2, 3], graph.unreachable_nodes());
+
+        assert!(
+            TokenGraph::from_edges(Vec::new())
+                .unreachable_nodes()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_token_graph_from_tokens_ipadic() {
+        use crate::dictionary::load_dictionary;
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+        use crate::tokenizer::Tokenizer;
+
+        let dictionary = load_dictionary("embedded://ipadic").unwrap();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let tokenizer = Tokenizer::new(segmenter);
+
+        let mut tokens = tokenizer.tokenize("東京スカイツリーの最寄り駅").unwrap();
+        let graph = TokenGraph::from_tokens(&mut tokens);
+        assert_eq!(tokens.len(), graph.edges.len());
+        assert_eq!(tokens.len() + 1, graph.nodes.len());
+        assert!(graph.unreachable_nodes().is_empty());
+        assert_eq!("東京", graph.edges[0].token);
+        assert_eq!("名詞", graph.edges[0].details[0]);
+    }
+}
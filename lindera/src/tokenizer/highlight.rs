@@ -0,0 +1,239 @@
+//! Ranges of the original text covered by tokens, for highlighting matches in the text that was
+//! analyzed.
+//!
+//! The offsets of the tokens returned by [`Tokenizer::tokenize`] are corrected to the original
+//! text, but a token that covers part of a substitution (e.g. `株式` from `㍿` after NFKC) is
+//! mapped proportionally into it, which may split a character. The spans returned here always
+//! cover whole characters: a token inside a substitution is widened to the whole substituted text.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::LinderaResult;
+use crate::character_filter::OffsetMapping;
+use crate::dictionary::Lattice;
+use crate::token::Token;
+use crate::tokenizer::{Tokenizer, correct_token_offsets};
+
+/// A token with the range of the original text it was produced from.
+#[derive(Clone)]
+pub struct Highlight<'a> {
+    /// The token, with the same offsets as returned by [`Tokenizer::tokenize`].
+    pub token: Token<'a>,
+    /// The byte range of the original text to highlight for the token.
+    pub span: Range<usize>,
+}
+
+fn floor_char_boundary(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+fn ceil_char_boundary(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset += 1;
+    }
+    offset
+}
+
+/// Maps a byte range of the filtered text to the range of the original text it was produced from.
+///
+/// `stages` holds the offset mapping of each character filter with the length of the text it
+/// produced, in the order the filters were applied.
+pub(crate) fn source_span(
+    original: &str,
+    stages: &[(OffsetMapping, usize)],
+    start: usize,
+    end: usize,
+) -> Range<usize> {
+    let (mut start, mut end) = (start, end);
+    for (mapping, text_len) in stages.iter().rev() {
+        let mut original_start = mapping.correct_offset(start, *text_len);
+        let mut original_end = mapping.correct_offset(end, *text_len);
+
+        // Widen the range to the whole substitution if it begins or ends inside one
+        for transformation in &mapping.transformations {
+            if transformation.filtered_start < start && start < transformation.filtered_end {
+                original_start = transformation.original_start;
+            }
+            if transformation.filtered_start < end && end < transformation.filtered_end {
+                original_end = transformation.original_end;
+            }
+        }
+
+        start = original_start;
+        end = original_end.max(original_start);
+    }
+
+    floor_char_boundary(original, start)..ceil_char_boundary(original, end)
+}
+
+/// Returns the byte range of `text` to highlight for each token.
+///
+/// The tokens must have offsets in `text`, as returned by [`Tokenizer::tokenize`]. The offsets are
+/// clamped to `text` and widened to character boundaries. Use [`Tokenizer::highlight`] to also
+/// widen tokens that cover part of a substitution made by a character filter.
+pub fn highlight_spans(text: &str, tokens: &[Token]) -> Vec<Range<usize>> {
+    tokens
+        .iter()
+        .map(|token| {
+            let start = floor_char_boundary(text, token.byte_start);
+            let end = ceil_char_boundary(text, token.byte_end.max(token.byte_start));
+            start..end
+        })
+        .collect()
+}
+
+impl Tokenizer {
+    /// Tokenizes the text like [`Tokenizer::tokenize`], and returns each token with the exact
+    /// range of `text` it was produced from.
+    ///
+    /// The span of a token always starts and ends on character boundaries of `text`, so
+    /// `&text[span]` can be wrapped in highlight tags. Text removed by a character filter is
+    /// included in the span of the token that follows it.
+    pub fn highlight<'a>(&'a self, text: &'a str) -> LinderaResult<Vec<Highlight<'a>>> {
+        let mut normalized_text: Cow<'a, str> = Cow::Borrowed(text);
+
+        let mut offset_mappings: Vec<OffsetMapping> = Vec::new();
+        let mut stages: Vec<(OffsetMapping, usize)> = Vec::new();
+        if !self.character_filters.is_empty() {
+            let text_mut = normalized_text.to_mut();
+            for character_filter in &self.character_filters {
+                let mapping = character_filter.apply(text_mut)?;
+                if !mapping.is_empty() {
+                    offset_mappings.push(mapping.clone());
+                    stages.push((mapping, text_mut.len()));
+                }
+            }
+        }
+
+        let final_text_len = normalized_text.len();
+        let mut lattice = Lattice::default();
+        let mut tokens = self
+            .segmenter
+            .segment_with_lattice(normalized_text, &mut lattice)?;
+
+        for token_filter in &self.token_filters {
+            token_filter.apply(&mut tokens)?;
+        }
+
+        Ok(tokens
+            .into_iter()
+            .map(|mut token| {
+                let span = source_span(text, &stages, token.byte_start, token.byte_end);
+                correct_token_offsets(&mut token, &offset_mappings, final_text_len);
+                Highlight { token, span }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character_filter::CharacterFilter;
+    use crate::character_filter::Transformation;
+    use crate::character_filter::japanese_prolonged_sound::JapaneseProlongedSoundCharacterFilter;
+    use crate::character_filter::unicode_normalize::{
+        UnicodeNormalizeCharacterFilter, UnicodeNormalizeKind,
+    };
+
+    #[test]
+    fn test_source_span() {
+        // "㍿です" → "株式会社です"
+        let original = "㍿です";
+        let stages = vec![(
+            OffsetMapping::with_transformations(vec![Transformation::new(0, 3, 0, 12)]),
+            "株式会社です".len(),
+        )];
+
+        // "株式" and "会社" both highlight "㍿"
+        assert_eq!(0..3, source_span(original, &stages, 0, 6));
+        assert_eq!(0..3, source_span(original, &stages, 6, 12));
+        assert_eq!(0..3, source_span(original, &stages, 0, 12));
+        assert_eq!(3..9, source_span(original, &stages, 12, 18));
+
+        // Without transformations the range is unchanged
+        assert_eq!(3..6, source_span(original, &[], 3, 6));
+    }
+
+    #[test]
+    fn test_source_span_filters() {
+        let original = "ｽｰｰﾊﾟｰ、すごぉぉい";
+        let mut text = original.to_string();
+        let mut stages = Vec::new();
+
+        let mapping = UnicodeNormalizeCharacterFilter::new(UnicodeNormalizeKind::NFKC)
+            .apply(&mut text)
+            .unwrap();
+        stages.push((mapping, text.len()));
+        let mapping = JapaneseProlongedSoundCharacterFilter::new(true, true, true)
+            .apply(&mut text)
+            .unwrap();
+        stages.push((mapping, text.len()));
+        assert_eq!("スーパー、すごい", text);
+
+        let span_of = |surface: &str| {
+            let start = text.find(surface).unwrap();
+            let span = source_span(original, &stages, start, start + surface.len());
+            &original[span]
+        };
+        assert_eq!("ｽｰｰﾊﾟｰ", span_of("スーパー"));
+        assert_eq!("ﾊﾟ", span_of("パ"));
+        assert_eq!("すご", span_of("すご"));
+        assert_eq!("ぉぉい", span_of("い"));
+    }
+
+    #[test]
+    fn test_floor_ceil_char_boundary() {
+        let text = "㍿a";
+        assert_eq!(0, floor_char_boundary(text, 1));
+        assert_eq!(3, ceil_char_boundary(text, 1));
+        assert_eq!(3, floor_char_boundary(text, 3));
+        assert_eq!(4, ceil_char_boundary(text, 10));
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_highlight_ipadic() {
+        use crate::character_filter::BoxCharacterFilter;
+        use crate::dictionary::load_dictionary;
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+
+        let dictionary = load_dictionary("embedded://ipadic").unwrap();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let mut tokenizer = Tokenizer::new(segmenter);
+        tokenizer.append_character_filter(BoxCharacterFilter::from(
+            UnicodeNormalizeCharacterFilter::new(UnicodeNormalizeKind::NFKC),
+        ));
+
+        let text = "ﾘﾝﾃﾞﾗは㍿です";
+        let highlights = tokenizer.highlight(text).unwrap();
+        let spans = highlights
+            .iter()
+            .map(|highlight| &text[highlight.span.clone()])
+            .collect::<Vec<_>>();
+        assert!(spans.iter().all(|span| !span.is_empty()));
+        assert_eq!(Some(&"ﾘﾝﾃﾞﾗ"), spans.first());
+        assert_eq!(Some(&"です"), spans.last());
+
+        // The spans cover the text without gaps
+        let mut offset = 0;
+        for highlight in &highlights {
+            assert!(highlight.span.start >= offset);
+            offset = highlight.span.end;
+        }
+        assert_eq!(text.len(), offset);
+
+        let tokens = tokenizer.tokenize(text).unwrap();
+        assert_eq!(highlights.len(), tokens.len());
+        for (highlight, range) in highlights.iter().zip(highlight_spans(text, &tokens)) {
+            assert!(highlight.span.start <= range.start && range.end <= highlight.span.end);
+        }
+    }
+}
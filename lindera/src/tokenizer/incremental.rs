@@ -0,0 +1,298 @@
+//! Re-tokenization of an edited text, for editors and input methods that analyze the text again
+//! on every keystroke.
+//!
+//! Only the tokens touched by an edit, plus a safety window of tokens on each side, are analyzed
+//! again; the tokens before and after them are kept and shifted. The window gives the Viterbi search
+//! enough context to settle on the same boundaries as a full tokenization in almost all cases.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::LinderaResult;
+use crate::dictionary::Lattice;
+use crate::error::LinderaErrorKind;
+use crate::token::Token;
+use crate::tokenizer::Tokenizer;
+
+/// Default number of tokens analyzed again on each side of an edit.
+pub const DEFAULT_SAFETY_WINDOW: usize = 2;
+
+/// An edit of a text: the bytes in `range` are replaced with `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range<usize>, replacement: impl Into<String>) -> Self {
+        Self {
+            range,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// An insertion of `text` at `offset`.
+    pub fn insert(offset: usize, text: impl Into<String>) -> Self {
+        Self::new(offset..offset, text)
+    }
+
+    /// A deletion of the bytes in `range`.
+    pub fn delete(range: Range<usize>) -> Self {
+        Self::new(range, String::new())
+    }
+}
+
+/// Returns the range of the tokens to analyze again for an edit of `edit` (a byte range of the text
+/// before the edit), and the byte range of the text they cover.
+///
+/// `offsets` are the byte offsets of the tokens, in the order of the text. The tokens that overlap or
+/// touch the edit are always included. The byte range extends to the end of the last token kept
+/// before it and to the start of the first token kept after it, so that the text between tokens is
+/// analyzed too.
+fn affected_window(
+    offsets: &[(usize, usize)],
+    text_len: usize,
+    edit: &Range<usize>,
+    safety_window: usize,
+) -> (Range<usize>, Range<usize>) {
+    let start = offsets
+        .iter()
+        .take_while(|(_, byte_end)| *byte_end < edit.start)
+        .count()
+        .saturating_sub(safety_window);
+    let end = (offsets.len()
+        - offsets
+            .iter()
+            .rev()
+            .take_while(|(byte_start, _)| *byte_start > edit.end)
+            .count()
+        + safety_window)
+        .min(offsets.len());
+
+    let byte_start = if start == 0 { 0 } else { offsets[start - 1].1 };
+    let byte_end = if end == offsets.len() {
+        text_len
+    } else {
+        offsets[end].0
+    };
+
+    (start..end, byte_start..byte_end)
+}
+
+/// The end of the positions taken by the tokens, or `default` if there are none.
+fn position_end(tokens: &[Token], default: usize) -> usize {
+    tokens
+        .iter()
+        .map(|token| token.position + token.position_length)
+        .max()
+        .unwrap_or(default)
+}
+
+/// A text that is kept tokenized while it is edited.
+///
+/// The tokens are the same as the ones returned by [`Tokenizer::tokenize`] for the current text,
+/// unless an edit changes the analysis of the text further away than the safety window.
+pub struct IncrementalTokenizer<'a> {
+    tokenizer: &'a Tokenizer,
+    text: String,
+    tokens: Vec<Token<'a>>,
+    safety_window: usize,
+    lattice: Lattice,
+}
+
+impl<'a> IncrementalTokenizer<'a> {
+    /// Tokenizes the whole text.
+    pub fn new(tokenizer: &'a Tokenizer, text: impl Into<String>) -> LinderaResult<Self> {
+        let text = text.into();
+        let mut lattice = Lattice::default();
+        let tokens = tokenizer.tokenize_cow_with_lattice(Cow::Owned(text.clone()), &mut lattice)?;
+
+        Ok(Self {
+            tokenizer,
+            text,
+            tokens,
+            safety_window: DEFAULT_SAFETY_WINDOW,
+            lattice,
+        })
+    }
+
+    /// Sets the number of tokens analyzed again on each side of an edit.
+    pub fn set_safety_window(&mut self, safety_window: usize) -> &mut Self {
+        self.safety_window = safety_window;
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn tokens(&self) -> &[Token<'a>] {
+        &self.tokens
+    }
+
+    pub fn into_tokens(self) -> Vec<Token<'a>> {
+        self.tokens
+    }
+
+    /// Applies the edit to the text and re-tokenizes the region around it.
+    ///
+    /// Returns the range of [`IncrementalTokenizer::tokens`] that holds the new tokens. The tokens
+    /// after it are the tokens that followed the region before the edit, with their offsets and
+    /// positions shifted.
+    pub fn edit(&mut self, edit: &TextEdit) -> LinderaResult<Range<usize>> {
+        let range = &edit.range;
+        if range.start > range.end
+            || range.end > self.text.len()
+            || !self.text.is_char_boundary(range.start)
+            || !self.text.is_char_boundary(range.end)
+        {
+            return Err(LinderaErrorKind::Args.with_error(anyhow::anyhow!(
+                "invalid edit range {range:?} for a text of {} bytes",
+                self.text.len()
+            )));
+        }
+
+        let offsets = self
+            .tokens
+            .iter()
+            .map(|token| (token.byte_start, token.byte_end))
+            .collect::<Vec<_>>();
+        let (token_range, byte_range) =
+            affected_window(&offsets, self.text.len(), range, self.safety_window);
+
+        self.text.replace_range(range.clone(), &edit.replacement);
+        let byte_delta = edit.replacement.len() as isize - range.len() as isize;
+        let region_end = (byte_range.end as isize + byte_delta) as usize;
+        let region = self.text[byte_range.start..region_end].to_string();
+
+        let mut tokens = self
+            .tokenizer
+            .tokenize_cow_with_lattice(Cow::Owned(region), &mut self.lattice)?;
+
+        // Move the new tokens to the region, after the positions of the tokens kept before it
+        let position_start = position_end(&self.tokens[..token_range.start], 0);
+        for token in tokens.iter_mut() {
+            token.byte_start += byte_range.start;
+            token.byte_end += byte_range.start;
+            token.position += position_start;
+        }
+
+        // Shift the tokens kept after the region
+        let position_delta = position_end(&tokens, position_start) as isize
+            - position_end(&self.tokens[token_range.clone()], position_start) as isize;
+        for token in self.tokens[token_range.end..].iter_mut() {
+            token.byte_start = (token.byte_start as isize + byte_delta) as usize;
+            token.byte_end = (token.byte_end as isize + byte_delta) as usize;
+            token.position = (token.position as isize + position_delta) as usize;
+        }
+
+        let new_range = token_range.start..token_range.start + tokens.len();
+        self.tokens.splice(token_range, tokens);
+
+        Ok(new_range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_affected_window() {
+        // "すもも|も|もも|も|もも|の|うち"
+        let offsets = vec![
+            (0, 9),
+            (9, 12),
+            (12, 18),
+            (18, 21),
+            (21, 27),
+            (27, 30),
+            (30, 36),
+        ];
+
+        // Replacing "もも" (12..18), without a safety window: the tokens touching it are included
+        assert_eq!((1..4, 9..21), affected_window(&offsets, 36, &(12..18), 0));
+        // With a safety window of one token
+        assert_eq!((0..5, 0..27), affected_window(&offsets, 36, &(12..18), 1));
+        // An insertion inside a token
+        assert_eq!((4..5, 21..27), affected_window(&offsets, 36, &(24..24), 0));
+        // The window is clamped to the tokens
+        assert_eq!((4..7, 21..36), affected_window(&offsets, 36, &(36..36), 2));
+        assert_eq!((0..2, 0..12), affected_window(&offsets, 36, &(0..0), 1));
+    }
+
+    #[test]
+    fn test_affected_window_between_tokens() {
+        // "東京 | 大阪" with the whitespace dropped
+        let offsets = vec![(0, 6), (9, 15)];
+
+        // An edit of the whitespace only covers the text between the tokens
+        assert_eq!((1..1, 6..9), affected_window(&offsets, 15, &(7..8), 0));
+        assert_eq!((0..2, 0..15), affected_window(&offsets, 15, &(7..8), 1));
+
+        // Without tokens the whole text is analyzed
+        assert_eq!((0..0, 0..3), affected_window(&[], 3, &(1..2), 2));
+    }
+
+    #[test]
+    fn test_text_edit() {
+        assert_eq!(TextEdit::new(3..3, "も"), TextEdit::insert(3, "も"));
+        assert_eq!(TextEdit::new(3..6, ""), TextEdit::delete(3..6));
+    }
+
+    #[test]
+    #[cfg(feature = "embed-ipadic")]
+    fn test_incremental_tokenizer_ipadic() {
+        use crate::dictionary::load_dictionary;
+        use crate::mode::Mode;
+        use crate::segmenter::Segmenter;
+
+        let dictionary = load_dictionary("embedded://ipadic").unwrap();
+        let segmenter = Segmenter::new(Mode::Normal, dictionary, None);
+        let tokenizer = Tokenizer::new(segmenter);
+
+        let summarize = |tokens: &[Token]| {
+            tokens
+                .iter()
+                .map(|token| {
+                    (
+                        token.surface.to_string(),
+                        token.byte_start,
+                        token.byte_end,
+                        token.position,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut incremental =
+            IncrementalTokenizer::new(&tokenizer, "すもももももももものうち").unwrap();
+        let edits = vec![
+            // "すもももももももものうち" → "すもももももももものうちです"
+            TextEdit::insert(36, "です"),
+            // → "すももももも、ももものうちです"
+            TextEdit::insert(18, "、"),
+            // → "すももももも、もものうちです"
+            TextEdit::delete(21..24),
+            // → "関西国際空港、もものうちです"
+            TextEdit::new(0..18, "関西国際空港"),
+            // → "関西国際空港"
+            TextEdit::delete(18..39),
+        ];
+        for edit in &edits {
+            let range = incremental.edit(edit).unwrap();
+            let expected = tokenizer.tokenize(incremental.text()).unwrap();
+            assert_eq!(
+                summarize(&expected),
+                summarize(incremental.tokens()),
+                "{edit:?}"
+            );
+            assert!(range.end <= incremental.tokens().len());
+        }
+        assert_eq!("関西国際空港", incremental.text());
+
+        assert!(incremental.edit(&TextEdit::delete(1..2)).is_err());
+        assert!(incremental.edit(&TextEdit::insert(100, "a")).is_err());
+    }
+}
@@ -0,0 +1,398 @@
+//! Conversion of Japanese sentences to kana and to romaji from the readings of their tokens, for
+//! slugs, sort keys and phonetic comparison.
+//!
+//! The words of the dictionary are replaced by their reading (`東京へ行く` → `トウキョウヘイク`).
+//! The other spans of the text are kept as they are: the unknown words (in katakana if they are
+//! written in kana), the ASCII words and numbers, the symbols and the whitespace between the
+//! tokens.
+
+use kanaria::string::UCSStr;
+
+use crate::LinderaResult;
+use crate::tokenizer::Tokenizer;
+
+/// The romanization of the kana, two-kana combinations first.
+const ROMAJI: &[(&str, &str)] = &[
+    ("キャ", "kya"),
+    ("キュ", "kyu"),
+    ("キョ", "kyo"),
+    ("ギャ", "gya"),
+    ("ギュ", "gyu"),
+    ("ギョ", "gyo"),
+    ("シャ", "sha"),
+    ("シュ", "shu"),
+    ("ショ", "sho"),
+    ("シェ", "she"),
+    ("ジャ", "ja"),
+    ("ジュ", "ju"),
+    ("ジョ", "jo"),
+    ("ジェ", "je"),
+    ("チャ", "cha"),
+    ("チュ", "chu"),
+    ("チョ", "cho"),
+    ("チェ", "che"),
+    ("ヂャ", "ja"),
+    ("ヂュ", "ju"),
+    ("ヂョ", "jo"),
+    ("ニャ", "nya"),
+    ("ニュ", "nyu"),
+    ("ニョ", "nyo"),
+    ("ヒャ", "hya"),
+    ("ヒュ", "hyu"),
+    ("ヒョ", "hyo"),
+    ("ビャ", "bya"),
+    ("ビュ", "byu"),
+    ("ビョ", "byo"),
+    ("ピャ", "pya"),
+    ("ピュ", "pyu"),
+    ("ピョ", "pyo"),
+    ("ミャ", "mya"),
+    ("ミュ", "myu"),
+    ("ミョ", "myo"),
+    ("リャ", "rya"),
+    ("リュ", "ryu"),
+    ("リョ", "ryo"),
+    ("ファ", "fa"),
+    ("フィ", "fi"),
+    ("フェ", "fe"),
+    ("フォ", "fo"),
+    ("ティ", "ti"),
+    ("ディ", "di"),
+    ("トゥ", "tu"),
+    ("ドゥ", "du"),
+    ("ツァ", "tsa"),
+    ("ウィ", "wi"),
+    ("ウェ", "we"),
+    ("ウォ", "wo"),
+    ("イェ", "ye"),
+    ("ヴァ", "va"),
+    ("ヴィ", "vi"),
+    ("ヴェ", "ve"),
+    ("ヴォ", "vo"),
+    ("ア", "a"),
+    ("イ", "i"),
+    ("ウ", "u"),
+    ("エ", "e"),
+    ("オ", "o"),
+    ("カ", "ka"),
+    ("キ", "ki"),
+    ("ク", "ku"),
+    ("ケ", "ke"),
+    ("コ", "ko"),
+    ("ガ", "ga"),
+    ("ギ", "gi"),
+    ("グ", "gu"),
+    ("ゲ", "ge"),
+    ("ゴ", "go"),
+    ("サ", "sa"),
+    ("シ", "shi"),
+    ("ス", "su"),
+    ("セ", "se"),
+    ("ソ", "so"),
+    ("ザ", "za"),
+    ("ジ", "ji"),
+    ("ズ", "zu"),
+    ("ゼ", "ze"),
+    ("ゾ", "zo"),
+    ("タ", "ta"),
+    ("チ", "chi"),
+    ("ツ", "tsu"),
+    ("テ", "te"),
+    ("ト", "to"),
+    ("ダ", "da"),
+    ("ヂ", "ji"),
+    ("ヅ", "zu"),
+    ("デ", "de"),
+    ("ド", "do"),
+    ("ナ", "na"),
+    ("ニ", "ni"),
+    ("ヌ", "nu"),
+    ("ネ", "ne"),
+    ("ノ", "no"),
+    ("ハ", "ha"),
+    ("ヒ", "hi"),
+    ("フ", "fu"),
+    ("ヘ", "he"),
+    ("ホ", "ho"),
+    ("バ", "ba"),
+    ("ビ", "bi"),
+    ("ブ", "bu"),
+    ("ベ", "be"),
+    ("ボ", "bo"),
+    ("パ", "pa"),
+    ("ピ", "pi"),
+    ("プ", "pu"),
+    ("ペ", "pe"),
+    ("ポ", "po"),
+    ("マ", "ma"),
+    ("ミ", "mi"),
+    ("ム", "mu"),
+    ("メ", "me"),
+    ("モ", "mo"),
+    ("ヤ", "ya"),
+    ("ユ", "yu"),
+    ("ヨ", "yo"),
+    ("ラ", "ra"),
+    ("リ", "ri"),
+    ("ル", "ru"),
+    ("レ", "re"),
+    ("ロ", "ro"),
+    ("ワ", "wa"),
+    ("ヰ", "i"),
+    ("ヱ", "e"),
+    ("ヲ", "wo"),
+    ("ヴ", "vu"),
+    ("ァ", "a"),
+    ("ィ", "i"),
+    ("ゥ", "u"),
+    ("ェ", "e"),
+    ("ォ", "o"),
+    ("ャ", "ya"),
+    ("ュ", "yu"),
+    ("ョ", "yo"),
+    ("ヮ", "wa"),
+    ("ヵ", "ka"),
+    ("ヶ", "ke"),
+];
+
+/// Converts kana to romaji in Hepburn style (`シンブン` → `shinbun`, `キッテ` → `kitte`). The
+/// long vowel mark repeats the vowel before it (`ラーメン` → `raamen`), a `ン` before a vowel
+/// or `y` is written `n'`, and the characters that are not kana are kept.
+///
+/// # Example
+///
+/// ```
+/// use lindera::tokenizer::kana::kana_to_romaji;
+///
+/// assert_eq!("toukyou", kana_to_romaji("トウキョウ"));
+/// assert_eq!("kon'ya", kana_to_romaji("こんや"));
+/// ```
+pub fn kana_to_romaji(kana: &str) -> String {
+    let katakana = UCSStr::from_str(kana).katakana().to_string();
+    let mut romaji = String::with_capacity(katakana.len());
+    // Whether a ッ or a ン waits for the syllable after it
+    let mut sokuon = false;
+    let mut moraic_n = false;
+    let mut rest = katakana.as_str();
+    while let Some(c) = rest.chars().next() {
+        match c {
+            'ッ' => {
+                sokuon = true;
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+            'ン' => {
+                if moraic_n {
+                    romaji.push('n');
+                }
+                moraic_n = true;
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+            _ => {}
+        }
+
+        let syllable = ROMAJI
+            .iter()
+            .find(|(kana, _)| rest.starts_with(kana))
+            .copied();
+        if moraic_n {
+            let before_vowel = syllable
+                .is_some_and(|(_, romaji)| romaji.starts_with(['a', 'i', 'u', 'e', 'o', 'y']));
+            romaji.push_str(if before_vowel { "n'" } else { "n" });
+            moraic_n = false;
+        }
+        match syllable {
+            Some((kana, syllable)) => {
+                if sokuon {
+                    // ッチ is written tchi
+                    match syllable.chars().next() {
+                        Some('c') => romaji.push('t'),
+                        Some(consonant) if !"aiueo".contains(consonant) => romaji.push(consonant),
+                        _ => {}
+                    }
+                }
+                romaji.push_str(syllable);
+                rest = &rest[kana.len()..];
+            }
+            None => {
+                if c == 'ー' {
+                    if let Some(vowel) = romaji.chars().last().filter(|c| "aiueo".contains(*c)) {
+                        romaji.push(vowel);
+                    }
+                } else {
+                    romaji.push(c);
+                }
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        sokuon = false;
+    }
+    if moraic_n {
+        romaji.push('n');
+    }
+    romaji
+}
+
+/// Converts the full-width ASCII and the Japanese punctuation to ASCII, for romaji.
+fn to_ascii(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+            '\u{3000}' | '・' => ' ',
+            '。' => '.',
+            '、' => ',',
+            '「' | '」' | '『' | '』' => '"',
+            _ => c,
+        })
+        .collect()
+}
+
+impl Tokenizer {
+    /// The spans of the text, the tokens with a reading replaced by their reading.
+    fn reading_spans(&self, text: &str) -> LinderaResult<Vec<String>> {
+        let mut spans = Vec::new();
+        let mut end = 0;
+        for mut highlight in self.highlight(text)? {
+            // The tokens of a substitution share a span of the original text
+            if highlight.span.start < end {
+                continue;
+            }
+            if end < highlight.span.start {
+                spans.push(text[end..highlight.span.start].to_string());
+            }
+            match highlight.token.reading() {
+                Some(reading) => spans.push(reading.to_string()),
+                None => spans.push(text[highlight.span.clone()].to_string()),
+            }
+            end = highlight.span.end;
+        }
+        if end < text.len() {
+            spans.push(text[end..].to_string());
+        }
+        Ok(spans)
+    }
+
+    /// Converts `text` to katakana, the words replaced by their reading. The spans of the text
+    /// without a reading are kept, converted to katakana if they are in hiragana.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "embed-ipadic")]
+    /// # {
+    /// # use lindera::dictionary::load_dictionary;
+    /// # use lindera::mode::Mode;
+    /// # use lindera::segmenter::Segmenter;
+    /// # use lindera::tokenizer::Tokenizer;
+    /// # let dictionary = load_dictionary("embedded://ipadic")?;
+    /// # let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+    /// assert_eq!("トウキョウヘイク", tokenizer.to_kana("東京へ行く")?);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_kana(&self, text: &str) -> LinderaResult<String> {
+        Ok(self
+            .reading_spans(text)?
+            .into_iter()
+            .map(|span| UCSStr::from_str(&span).katakana().to_string())
+            .collect())
+    }
+
+    /// Converts `text` to romaji, the words replaced by the romanization of their reading (see
+    /// [`kana_to_romaji`]) and separated by spaces. The spans of the text without a reading are
+    /// romanized if they are in kana and kept otherwise, and the full-width ASCII and the Japanese
+    /// punctuation are converted to ASCII.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "embed-ipadic")]
+    /// # {
+    /// # use lindera::dictionary::load_dictionary;
+    /// # use lindera::mode::Mode;
+    /// # use lindera::segmenter::Segmenter;
+    /// # use lindera::tokenizer::Tokenizer;
+    /// # let dictionary = load_dictionary("embedded://ipadic")?;
+    /// # let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+    /// assert_eq!("toukyou he iku", tokenizer.to_romaji("東京へ行く")?);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_romaji(&self, text: &str) -> LinderaResult<String> {
+        let mut romaji = String::with_capacity(text.len());
+        for span in self.reading_spans(text)? {
+            let span = kana_to_romaji(&to_ascii(&span));
+            // The words are separated from the words and numbers before them
+            let separated = romaji
+                .chars()
+                .last()
+                .is_some_and(|c| c.is_ascii_alphanumeric())
+                && span.starts_with(|c: char| c.is_ascii_alphanumeric());
+            if separated {
+                romaji.push(' ');
+            }
+            romaji.push_str(&span);
+        }
+        Ok(romaji)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TestDictionary;
+    use crate::tokenizer::TokenizerBuilder;
+
+    #[test]
+    fn test_kana_to_romaji() {
+        assert_eq!("shinbun", kana_to_romaji("シンブン"));
+        assert_eq!("kitte", kana_to_romaji("キッテ"));
+        assert_eq!("matcha", kana_to_romaji("マッチャ"));
+        assert_eq!("raamen", kana_to_romaji("ラーメン"));
+        assert_eq!("kon'ya", kana_to_romaji("コンヤ"));
+        assert_eq!("tennin", kana_to_romaji("テンニン"));
+        assert_eq!("fairu", kana_to_romaji("ファイル"));
+        assert_eq!("ABC", kana_to_romaji("ABC"));
+    }
+
+    #[test]
+    fn test_to_kana_to_romaji() {
+        let dict_dir = TestDictionary::new(
+            "\
+東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー
+へ,0,0,100,助詞,格助詞,一般,*,*,*,へ,ヘ,エ
+行く,0,0,100,動詞,自立,*,*,五段・カ行促音便,基本形,行く,イク,イク
+年,0,0,100,名詞,接尾,助数詞,*,*,*,年,ネン,ネン
+。,0,0,100,記号,句点,*,*,*,*,。,。,。
+",
+        )
+        .char_def(
+            "DEFAULT 0 1 0\nSPACE 0 1 0\nALPHA 1 1 0\nNUMERIC 1 1 0\nKANJI 0 0 2\n0x0020 SPACE\n0x0030..0x0039 NUMERIC\n0x0041..0x005A ALPHA\n0x0061..0x007A ALPHA\n0x4E00..0x9FAF KANJI\n",
+        )
+        .unk_def(
+            "DEFAULT,0,0,5000,名詞,一般,*,*,*,*,*,*,*\nSPACE,0,0,5000,記号,空白,*,*,*,*,*,*,*\nALPHA,0,0,5000,名詞,一般,*,*,*,*,*,*,*\nNUMERIC,0,0,5000,名詞,数,*,*,*,*,*,*,*\nKANJI,0,0,5000,名詞,一般,*,*,*,*,*,*,*\n",
+        )
+        .build();
+        let mut builder = TokenizerBuilder::from_config(serde_json::json!({})).unwrap();
+        builder.set_segmenter_dictionary(dict_dir.path().to_str().unwrap());
+        let tokenizer = builder.build().unwrap();
+
+        assert_eq!("トウキョウヘイク", tokenizer.to_kana("東京へ行く").unwrap());
+        assert_eq!("toukyou he iku", tokenizer.to_romaji("東京へ行く").unwrap());
+        // The whitespace dropped by the segmenter and the ASCII words are kept
+        assert_eq!(
+            "2024ネン Lindera。",
+            tokenizer.to_kana("2024年 Lindera。").unwrap()
+        );
+        assert_eq!(
+            "2024 nen Lindera.",
+            tokenizer.to_romaji("2024年 Lindera。").unwrap()
+        );
+        assert_eq!("", tokenizer.to_romaji("").unwrap());
+    }
+}
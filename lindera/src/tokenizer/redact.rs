@@ -0,0 +1,243 @@
+//! Redaction of the person names, the places and other sensitive words of a text, e.g. before
+//! logging user input.
+//!
+//! The words are found by their part-of-speech: the person names (`名詞,固有名詞,人名`, the
+//! surnames `姓` and given names `名`), the places (`名詞,固有名詞,地域` of IPADIC and `地名` of
+//! UniDic), the organizations, and the tags given to the words of a user dictionary (e.g. a
+//! `社員番号` part-of-speech). The consecutive tokens of the same kind are redacted as one span
+//! (`山田` `太郎`), and the spans are ranges of the original text, before the character filters.
+
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::LinderaResult;
+use crate::tokenizer::Tokenizer;
+
+/// The kind of a redacted span.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionKind {
+    Person,
+    Location,
+    Organization,
+    /// A word with one of the tags of [`Redactor::tags`].
+    Tag(String),
+}
+
+impl RedactionKind {
+    /// The label replacing the span with [`Replacement::Label`], e.g. `[PERSON]`.
+    pub fn label(&self) -> String {
+        match self {
+            Self::Person => "[PERSON]".to_string(),
+            Self::Location => "[LOCATION]".to_string(),
+            Self::Organization => "[ORGANIZATION]".to_string(),
+            Self::Tag(tag) => format!("[{tag}]"),
+        }
+    }
+}
+
+/// What replaces a redacted span.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Replacement {
+    /// The label of the kind of the span, e.g. `[PERSON]`.
+    Label,
+    /// The character repeated once per character of the span, so the character offsets of the
+    /// rest of the text are kept.
+    Mask(char),
+    /// A fixed text.
+    Text(String),
+}
+
+/// A redacted span.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Redaction {
+    pub kind: RedactionKind,
+    /// The redacted text.
+    pub text: String,
+    /// The byte range of the span in the original text.
+    pub span: Range<usize>,
+    /// The byte range of the replacement in the redacted text.
+    pub redacted_span: Range<usize>,
+}
+
+/// The result of [`Redactor::redact`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Redacted {
+    pub text: String,
+    pub redactions: Vec<Redaction>,
+}
+
+/// Finds and replaces the sensitive words of texts.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    pub persons: bool,
+    pub locations: bool,
+    pub organizations: bool,
+    /// The part-of-speech tags of the other words to redact, matched as prefixes of the
+    /// comma-separated part-of-speech of the tokens (`名詞,固有名詞` matches all the proper
+    /// nouns).
+    pub tags: Vec<String>,
+    pub replacement: Replacement,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self {
+            persons: true,
+            locations: true,
+            organizations: false,
+            tags: Vec::new(),
+            replacement: Replacement::Label,
+        }
+    }
+}
+
+impl Redactor {
+    /// The kind of the token of the details, if it is redacted.
+    fn kind(&self, details: &[&str]) -> Option<RedactionKind> {
+        if let ["名詞", "固有名詞", category, ..] = details {
+            match *category {
+                "人名" if self.persons => return Some(RedactionKind::Person),
+                "地域" | "地名" if self.locations => return Some(RedactionKind::Location),
+                "組織" if self.organizations => return Some(RedactionKind::Organization),
+                _ => {}
+            }
+        }
+        self.tags
+            .iter()
+            .find(|tag| {
+                let tag = tag.split(',').collect::<Vec<_>>();
+                tag.len() <= details.len() && details[..tag.len()] == tag[..]
+            })
+            .map(|tag| RedactionKind::Tag(tag.clone()))
+    }
+
+    fn replacement(&self, kind: &RedactionKind, text: &str) -> String {
+        match &self.replacement {
+            Replacement::Label => kind.label(),
+            Replacement::Mask(c) => text.chars().map(|_| *c).collect(),
+            Replacement::Text(replacement) => replacement.clone(),
+        }
+    }
+
+    /// Tokenizes `text` with `tokenizer` and replaces its sensitive words.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "embed-ipadic")]
+    /// # {
+    /// # use lindera::dictionary::load_dictionary;
+    /// # use lindera::mode::Mode;
+    /// # use lindera::segmenter::Segmenter;
+    /// # use lindera::tokenizer::Tokenizer;
+    /// # let dictionary = load_dictionary("embedded://ipadic")?;
+    /// # let tokenizer = Tokenizer::new(Segmenter::new(Mode::Normal, dictionary, None));
+    /// use lindera::tokenizer::redact::Redactor;
+    ///
+    /// let redacted = Redactor::default().redact(&tokenizer, "山田太郎さんは東京に住む")?;
+    /// assert_eq!("[PERSON]さんは[LOCATION]に住む", redacted.text);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn redact(&self, tokenizer: &Tokenizer, text: &str) -> LinderaResult<Redacted> {
+        // The spans of the original text to redact, the consecutive ones of the same kind merged
+        let mut spans: Vec<(RedactionKind, Range<usize>)> = Vec::new();
+        for mut highlight in tokenizer.highlight(text)? {
+            let Some(kind) = self.kind(&highlight.token.details()) else {
+                continue;
+            };
+            match spans.last_mut() {
+                Some((last_kind, last_span))
+                    if *last_kind == kind && last_span.end == highlight.span.start =>
+                {
+                    last_span.end = highlight.span.end;
+                }
+                // A span inside the last one, when a character filter expanded a character
+                Some((_, last_span)) if highlight.span.start < last_span.end => {
+                    last_span.end = last_span.end.max(highlight.span.end);
+                }
+                _ => spans.push((kind, highlight.span)),
+            }
+        }
+
+        let mut redacted = Redacted {
+            text: String::with_capacity(text.len()),
+            redactions: Vec::with_capacity(spans.len()),
+        };
+        let mut end = 0;
+        for (kind, span) in spans {
+            redacted.text.push_str(&text[end..span.start]);
+            let replacement = self.replacement(&kind, &text[span.clone()]);
+            let redacted_start = redacted.text.len();
+            redacted.text.push_str(&replacement);
+            redacted.redactions.push(Redaction {
+                kind,
+                text: text[span.clone()].to_string(),
+                redacted_span: redacted_start..redacted.text.len(),
+                span: span.clone(),
+            });
+            end = span.end;
+        }
+        redacted.text.push_str(&text[end..]);
+        Ok(redacted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TestDictionary;
+
+    #[test]
+    fn test_redact() {
+        let (_dict_dir, tokenizer) = TestDictionary::new(
+            "\
+山田,0,0,100,名詞,固有名詞,人名,姓,*,*,山田,ヤマダ,ヤマダ
+太郎,0,0,100,名詞,固有名詞,人名,名,*,*,太郎,タロウ,タロー
+さん,0,0,100,名詞,接尾,人名,*,*,*,さん,サン,サン
+は,0,0,100,助詞,係助詞,*,*,*,*,は,ハ,ワ
+東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー
+に,0,0,100,助詞,格助詞,一般,*,*,*,に,ニ,ニ
+住む,0,0,100,動詞,自立,*,*,五段・マ行,基本形,住む,スム,スム
+極秘,0,0,100,機密,*,*,*,*,*,極秘,ゴクヒ,ゴクヒ
+",
+        )
+        .unknown_cost(5000)
+        .tokenizer();
+
+        let text = "山田太郎さんは東京に住む極秘";
+        let redacted = Redactor::default().redact(&tokenizer, text).unwrap();
+        assert_eq!("[PERSON]さんは[LOCATION]に住む極秘", redacted.text);
+        assert_eq!(
+            Redaction {
+                kind: RedactionKind::Person,
+                text: "山田太郎".to_string(),
+                span: 0..12,
+                redacted_span: 0..8,
+            },
+            redacted.redactions[0]
+        );
+        let location = &redacted.redactions[1];
+        assert_eq!("東京", &text[location.span.clone()]);
+        assert_eq!("[LOCATION]", &redacted.text[location.redacted_span.clone()]);
+
+        // The mask keeps the number of characters
+        let redactor = Redactor {
+            locations: false,
+            tags: vec!["機密".to_string()],
+            replacement: Replacement::Mask('＊'),
+            ..Redactor::default()
+        };
+        let redacted = redactor.redact(&tokenizer, text).unwrap();
+        assert_eq!("＊＊＊＊さんは東京に住む＊＊", redacted.text);
+        assert_eq!(text.chars().count(), redacted.text.chars().count());
+        assert_eq!(
+            RedactionKind::Tag("機密".to_string()),
+            redacted.redactions[1].kind
+        );
+    }
+}
@@ -0,0 +1,212 @@
+//! Selection of the sentences of a corpus the segmenter is the least sure about, for targeting
+//! the annotation effort of improving a user dictionary (active learning).
+//!
+//! The confidence of a token is its marginal probability in the lattice (see
+//! [`Segmenter::segment_with_marginals`](crate::segmenter::Segmenter::segment_with_marginals)),
+//! and the confidence of a sentence is the lowest confidence of its tokens. The selected
+//! sentences are written as a golden corpus (see [`golden`](super::golden)) to be corrected, with
+//! a comment line listing the uncertain tokens before each sentence, so the reviewed file can be
+//! used as is for regression testing.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::LinderaResult;
+use crate::tokenizer::Tokenizer;
+
+/// The default `theta` of the marginal probabilities, the inverse of the cost that makes a path
+/// `e` times less probable.
+pub const DEFAULT_THETA: f64 = 0.001;
+
+/// A token with its marginal probability.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UncertainToken {
+    pub surface: String,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub probability: f64,
+    pub details: Vec<String>,
+}
+
+/// A sentence with a token below the confidence threshold.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewSentence {
+    /// The line number of the sentence in the corpus, starting at 1.
+    pub line: usize,
+    pub text: String,
+    /// The lowest marginal probability of the tokens of the sentence.
+    pub confidence: f64,
+    /// The surfaces of the tokens of the best segmentation.
+    pub segmentation: Vec<String>,
+    /// The tokens below the threshold.
+    pub uncertain: Vec<UncertainToken>,
+}
+
+/// Selects the sentences of the corpus the segmenter of the tokenizer is unsure about.
+#[derive(Debug, Clone)]
+pub struct ReviewSelector {
+    /// The marginal probability under which a token is uncertain.
+    pub threshold: f64,
+    pub theta: f64,
+    /// The maximum number of sentences to select, all of them if `None`.
+    pub limit: Option<usize>,
+}
+
+impl Default for ReviewSelector {
+    fn default() -> Self {
+        Self {
+            threshold: 0.9,
+            theta: DEFAULT_THETA,
+            limit: None,
+        }
+    }
+}
+
+impl ReviewSelector {
+    /// The sentence with its uncertain tokens, or `None` if all its tokens are above the
+    /// threshold.
+    ///
+    /// The text is segmented as is: the character filters and the token filters of the tokenizer
+    /// are not applied.
+    pub fn review(
+        &self,
+        tokenizer: &Tokenizer,
+        line: usize,
+        text: &str,
+    ) -> LinderaResult<Option<ReviewSentence>> {
+        let tokens = tokenizer
+            .segmenter
+            .segment_with_marginals(Cow::Borrowed(text), self.theta)?;
+
+        let mut sentence = ReviewSentence {
+            line,
+            text: text.to_string(),
+            confidence: 1.0,
+            segmentation: Vec::with_capacity(tokens.len()),
+            uncertain: Vec::new(),
+        };
+        for (mut token, probability) in tokens {
+            sentence.segmentation.push(token.surface.to_string());
+            sentence.confidence = sentence.confidence.min(probability);
+            if probability < self.threshold {
+                sentence.uncertain.push(UncertainToken {
+                    surface: token.surface.to_string(),
+                    byte_start: token.byte_start,
+                    byte_end: token.byte_end,
+                    probability,
+                    details: token
+                        .details()
+                        .iter()
+                        .map(|detail| detail.to_string())
+                        .collect(),
+                });
+            }
+        }
+        Ok((!sentence.uncertain.is_empty()).then_some(sentence))
+    }
+
+    /// The sentences of `texts`, the lines of a corpus, that have uncertain tokens, the least
+    /// confident first.
+    pub fn select<'t>(
+        &self,
+        tokenizer: &Tokenizer,
+        texts: impl IntoIterator<Item = &'t str>,
+    ) -> LinderaResult<Vec<ReviewSentence>> {
+        let mut sentences = Vec::new();
+        for (index, text) in texts.into_iter().enumerate() {
+            if text.trim().is_empty() {
+                continue;
+            }
+            if let Some(sentence) = self.review(tokenizer, index + 1, text)? {
+                sentences.push(sentence);
+            }
+        }
+        sentences.sort_by(|a, b| a.confidence.total_cmp(&b.confidence));
+        if let Some(limit) = self.limit {
+            sentences.truncate(limit);
+        }
+        Ok(sentences)
+    }
+}
+
+/// Writes the sentences as a golden corpus to be reviewed, with a comment line of the uncertain
+/// tokens and their probabilities before each sentence (the tab is shown as `\t`):
+///
+/// ```text
+/// # line 12, confidence 0.412: 都庁前 (0.412)
+/// 東京都庁前で待つ\t東京 都庁前 で 待つ
+/// ```
+pub fn to_review_corpus(sentences: &[ReviewSentence]) -> String {
+    let mut corpus = String::new();
+    for sentence in sentences {
+        let uncertain = sentence
+            .uncertain
+            .iter()
+            .map(|token| format!("{} ({:.3})", token.surface, token.probability))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let _ = writeln!(
+            corpus,
+            "# line {}, confidence {:.3}: {uncertain}",
+            sentence.line, sentence.confidence
+        );
+        let _ = writeln!(
+            corpus,
+            "{}\t{}",
+            sentence.text,
+            sentence.segmentation.join(" ")
+        );
+    }
+    corpus
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TestDictionary;
+    use crate::tokenizer::golden::parse_corpus;
+
+    #[test]
+    fn test_select() {
+        // 東京 都庁 and 東京都 庁 have close costs, 大阪 has a single segmentation
+        let (_dict_dir, tokenizer) = TestDictionary::new(
+            "\
+東京,0,0,1000,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー
+都庁,0,0,1000,名詞,一般,*,*,*,*,都庁,トチョウ,トチョー
+東京都,0,0,1100,名詞,固有名詞,地域,一般,*,*,東京都,トウキョウト,トーキョート
+庁,0,0,1000,名詞,接尾,*,*,*,*,庁,チョウ,チョー
+大阪,0,0,1000,名詞,固有名詞,地域,一般,*,*,大阪,オオサカ,オーサカ
+",
+        )
+        .unknown_cost(20000)
+        .tokenizer();
+
+        let tokens = tokenizer
+            .segmenter
+            .segment_with_marginals(Cow::Borrowed("東京都庁"), DEFAULT_THETA)
+            .unwrap();
+        assert_eq!(2, tokens.len());
+        assert_eq!("東京", tokens[0].0.surface);
+        // 1 / (1 + exp(-0.1)), the cost of 東京都 庁 being higher by 100
+        assert!((tokens[0].1 - 0.525).abs() < 0.001);
+
+        let selector = ReviewSelector::default();
+        let sentences = selector
+            .select(&tokenizer, ["大阪", "", "東京都庁"])
+            .unwrap();
+        assert_eq!(1, sentences.len());
+        let sentence = &sentences[0];
+        assert_eq!(3, sentence.line);
+        assert_eq!(vec!["東京", "都庁"], sentence.segmentation);
+        assert_eq!(2, sentence.uncertain.len());
+
+        let corpus = to_review_corpus(&sentences);
+        assert!(corpus.starts_with("# line 3, confidence 0.525: 東京 (0.525), 都庁 (0.525)\n"));
+        assert_eq!(
+            vec!["東京", "都庁"],
+            parse_corpus(&corpus).unwrap()[0].expected
+        );
+    }
+}
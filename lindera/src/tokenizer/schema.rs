@@ -0,0 +1,770 @@
+//! The JSON Schema of the tokenizer configuration, and a strict parser for configurations.
+//!
+//! The configuration is read leniently by [`TokenizerBuilder::from_file`]: a key that is not
+//! known, such as a misspelled `"stop_wrods"`, is ignored and the setting keeps its default.
+//! [`parse_config_strict`] reads the same JSON or YAML but fails on any key that
+//! [`config_schema`] does not define, with the line and column of the key.
+//!
+//! [`TokenizerBuilder::from_file`]: crate::tokenizer::TokenizerBuilder::from_file
+
+use std::fmt;
+
+use serde::Deserializer;
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value, json};
+
+use crate::LinderaResult;
+use crate::character_filter::japanese_iteration_mark::JAPANESE_ITERATION_MARK_CHARACTER_FILTER_NAME;
+use crate::character_filter::japanese_prolonged_sound::JAPANESE_PROLONGED_SOUND_CHARACTER_FILTER_NAME;
+use crate::character_filter::kana_conversion::KANA_CONVERSION_CHARACTER_FILTER_NAME;
+use crate::character_filter::mapping::MAPPING_CHARACTER_FILTER_NAME;
+use crate::character_filter::regex::REGEX_CHARACTER_FILTER_NAME;
+use crate::character_filter::sanitize::SANITIZE_CHARACTER_FILTER_NAME;
+use crate::character_filter::unicode_normalize::UNICODE_NORMALIZE_CHARACTER_FILTER_NAME;
+use crate::error::LinderaErrorKind;
+use crate::token_filter::cjk_width::CJK_WIDTH_TOKEN_FILTER_NAME;
+use crate::token_filter::conditional::CONDITIONAL_TOKEN_FILTER_NAME;
+use crate::token_filter::japanese_base_form::JAPANESE_BASE_FORM_TOKEN_FILTER_NAME;
+use crate::token_filter::japanese_compound_word::JAPANESE_COMPOUND_WORD_TOKEN_FILTER_NAME;
+use crate::token_filter::japanese_kana::JAPANESE_KANA_TOKEN_FILTER_NAME;
+use crate::token_filter::japanese_katakana_stem::JAPANESE_KATAKANA_STEM_TOKEN_FILTER_NAME;
+use crate::token_filter::japanese_keep_tags::JAPANESE_KEEP_TAGS_TOKEN_FILTER_NAME;
+use crate::token_filter::japanese_number::JAPANESE_NUMBER_TOKEN_FILTER_NAME;
+use crate::token_filter::japanese_proper_noun::JAPANESE_PROPER_NOUN_TOKEN_FILTER_NAME;
+use crate::token_filter::japanese_reading_form::JAPANESE_READING_FORM_TOKEN_FILTER_NAME;
+use crate::token_filter::japanese_stop_tags::JAPANESE_STOP_TAGS_TOKEN_FILTER_NAME;
+use crate::token_filter::keep_words::KEEP_WORDS_TOKEN_FILTER_NAME;
+use crate::token_filter::korean_decompound::KOREAN_DECOMPOUND_TOKEN_FILTER_NAME;
+use crate::token_filter::korean_keep_tags::KOREAN_KEEP_TAGS_TOKEN_FILTER_NAME;
+use crate::token_filter::korean_reading_form::KOREAN_READING_FORM_TOKEN_FILTER_NAME;
+use crate::token_filter::korean_stop_tags::KOREAN_STOP_TAGS_TOKEN_FILTER_NAME;
+use crate::token_filter::length::LENGTH_TOKEN_FILTER_NAME;
+use crate::token_filter::limit_token_count::LIMIT_TOKEN_COUNT_TOKEN_FILTER_NAME;
+use crate::token_filter::lowercase::LOWERCASE_TOKEN_FILTER_NAME;
+use crate::token_filter::mapping::MAPPING_TOKEN_FILTER_NAME;
+use crate::token_filter::remove_diacritical_mark::REMOVE_DIACRITICAL_TOKEN_FILTER_NAME;
+use crate::token_filter::stop_words::STOP_WORDS_TOKEN_FILTER_NAME;
+use crate::token_filter::truncate::TRUNCATE_TOKEN_FILTER_NAME;
+use crate::token_filter::unique::UNIQUE_TOKEN_FILTER_NAME;
+use crate::token_filter::uppercase::UPPERCASE_TOKEN_FILTER_NAME;
+use crate::tokenizer::TokenizerConfig;
+
+fn boolean() -> Value {
+    json!({ "type": "boolean" })
+}
+
+fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+fn strings() -> Value {
+    json!({ "type": "array", "items": { "type": "string" } })
+}
+
+fn count(minimum: u64) -> Value {
+    json!({ "type": "integer", "minimum": minimum })
+}
+
+fn one_of(values: &[&str]) -> Value {
+    json!({ "type": "string", "enum": values })
+}
+
+/// The schema of an object with the given properties and no others.
+fn object(properties: Value, required: &[&str]) -> Value {
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// The schema of a filter: its `kind` and the `args` it accepts.
+fn filter(kind: &str, args: Value, required: &[&str]) -> Value {
+    let filter_required: &[&str] = if required.is_empty() {
+        &["kind"]
+    } else {
+        &["kind", "args"]
+    };
+    object(
+        json!({
+            "kind": { "const": kind },
+            "args": object(args, required),
+        }),
+        filter_required,
+    )
+}
+
+fn character_filters() -> Vec<Value> {
+    vec![
+        filter(
+            JAPANESE_ITERATION_MARK_CHARACTER_FILTER_NAME,
+            json!({ "normalize_kanji": boolean(), "normalize_kana": boolean() }),
+            &[],
+        ),
+        filter(
+            JAPANESE_PROLONGED_SOUND_CHARACTER_FILTER_NAME,
+            json!({
+                "normalize_prolonged_sound": boolean(),
+                "normalize_wave_dash": boolean(),
+                "normalize_small_kana": boolean(),
+            }),
+            &[],
+        ),
+        filter(
+            KANA_CONVERSION_CHARACTER_FILTER_NAME,
+            json!({ "kind": one_of(&["hiragana", "katakana"]) }),
+            &["kind"],
+        ),
+        filter(
+            MAPPING_CHARACTER_FILTER_NAME,
+            json!({ "mapping": { "type": "object", "additionalProperties": string() } }),
+            &["mapping"],
+        ),
+        filter(
+            REGEX_CHARACTER_FILTER_NAME,
+            json!({ "pattern": string(), "replacement": string() }),
+            &["pattern", "replacement"],
+        ),
+        filter(
+            SANITIZE_CHARACTER_FILTER_NAME,
+            json!({ "remove_controls": boolean(), "remove_zero_width": boolean() }),
+            &[],
+        ),
+        filter(
+            UNICODE_NORMALIZE_CHARACTER_FILTER_NAME,
+            json!({ "kind": one_of(&["nfc", "nfd", "nfkc", "nfkd"]) }),
+            &["kind"],
+        ),
+    ]
+}
+
+fn token_filters() -> Vec<Value> {
+    vec![
+        filter(CJK_WIDTH_TOKEN_FILTER_NAME, json!({}), &[]),
+        filter(
+            CONDITIONAL_TOKEN_FILTER_NAME,
+            json!({
+                "filter": { "$ref": "#/$defs/token_filter" },
+                "condition": object(
+                    json!({
+                        "tags": strings(),
+                        "script": one_of(&["latin", "hiragana", "katakana", "han", "hangul", "digit"]),
+                        "min_length": count(0),
+                        "max_length": count(0),
+                    }),
+                    &[],
+                ),
+            }),
+            &["filter"],
+        ),
+        filter(JAPANESE_BASE_FORM_TOKEN_FILTER_NAME, json!({}), &[]),
+        filter(
+            JAPANESE_COMPOUND_WORD_TOKEN_FILTER_NAME,
+            json!({ "tags": strings(), "new_tag": string() }),
+            &["tags"],
+        ),
+        filter(
+            JAPANESE_KANA_TOKEN_FILTER_NAME,
+            json!({ "kind": one_of(&["hiragana", "katakana"]) }),
+            &["kind"],
+        ),
+        filter(
+            JAPANESE_KATAKANA_STEM_TOKEN_FILTER_NAME,
+            json!({ "min": count(1) }),
+            &["min"],
+        ),
+        filter(
+            JAPANESE_KEEP_TAGS_TOKEN_FILTER_NAME,
+            json!({ "tags": strings() }),
+            &["tags"],
+        ),
+        filter(
+            JAPANESE_NUMBER_TOKEN_FILTER_NAME,
+            json!({ "tags": strings() }),
+            &[],
+        ),
+        filter(
+            JAPANESE_PROPER_NOUN_TOKEN_FILTER_NAME,
+            json!({ "categories": strings() }),
+            &[],
+        ),
+        filter(JAPANESE_READING_FORM_TOKEN_FILTER_NAME, json!({}), &[]),
+        filter(
+            JAPANESE_STOP_TAGS_TOKEN_FILTER_NAME,
+            json!({ "tags": strings() }),
+            &["tags"],
+        ),
+        filter(
+            KEEP_WORDS_TOKEN_FILTER_NAME,
+            json!({ "words": strings() }),
+            &["words"],
+        ),
+        filter(
+            KOREAN_DECOMPOUND_TOKEN_FILTER_NAME,
+            json!({ "mode": one_of(&["discard", "mixed"]) }),
+            &[],
+        ),
+        filter(
+            KOREAN_KEEP_TAGS_TOKEN_FILTER_NAME,
+            json!({ "tags": strings() }),
+            &["tags"],
+        ),
+        filter(KOREAN_READING_FORM_TOKEN_FILTER_NAME, json!({}), &[]),
+        filter(
+            KOREAN_STOP_TAGS_TOKEN_FILTER_NAME,
+            json!({ "tags": strings() }),
+            &["tags"],
+        ),
+        filter(
+            LENGTH_TOKEN_FILTER_NAME,
+            json!({ "min": count(0), "max": count(0) }),
+            &[],
+        ),
+        filter(
+            LIMIT_TOKEN_COUNT_TOKEN_FILTER_NAME,
+            json!({ "max_token_count": count(0) }),
+            &["max_token_count"],
+        ),
+        filter(LOWERCASE_TOKEN_FILTER_NAME, json!({}), &[]),
+        filter(
+            MAPPING_TOKEN_FILTER_NAME,
+            json!({ "mapping": { "type": "object", "additionalProperties": string() } }),
+            &["mapping"],
+        ),
+        filter(
+            REMOVE_DIACRITICAL_TOKEN_FILTER_NAME,
+            json!({ "japanese": boolean() }),
+            &[],
+        ),
+        filter(
+            STOP_WORDS_TOKEN_FILTER_NAME,
+            json!({ "words": strings(), "paths": strings(), "reload": boolean() }),
+            &[],
+        ),
+        filter(
+            TRUNCATE_TOKEN_FILTER_NAME,
+            json!({ "length": count(0) }),
+            &["length"],
+        ),
+        filter(
+            UNIQUE_TOKEN_FILTER_NAME,
+            json!({ "mode": one_of(&["all", "consecutive", "same_position"]) }),
+            &[],
+        ),
+        filter(UPPERCASE_TOKEN_FILTER_NAME, json!({}), &[]),
+    ]
+}
+
+/// Returns the JSON Schema (draft 2020-12) of the tokenizer configuration read by
+/// [`TokenizerBuilder::from_file`](crate::tokenizer::TokenizerBuilder::from_file).
+///
+/// Editors can use it to complete and check configuration files. Every object of the schema
+/// sets `additionalProperties` to `false`, as [`parse_config_strict`] does.
+pub fn config_schema() -> Value {
+    let penalty = object(
+        json!({
+            "kanji_penalty_length_threshold": count(0),
+            "kanji_penalty_length_penalty": count(0),
+            "other_penalty_length_threshold": count(0),
+            "other_penalty_length_penalty": count(0),
+        }),
+        &[
+            "kanji_penalty_length_threshold",
+            "kanji_penalty_length_penalty",
+            "other_penalty_length_threshold",
+            "other_penalty_length_penalty",
+        ],
+    );
+    let mode = json!({
+        "oneOf": [
+            one_of(&["normal", "decompose"]),
+            object(json!({ "decompose": { "$ref": "#/$defs/penalty" } }), &["decompose"]),
+        ],
+    });
+    let segmenter = object(
+        json!({
+            "dictionary": string(),
+            "user_dictionary": string(),
+            "mode": { "$ref": "#/$defs/mode" },
+            "keep_whitespace": boolean(),
+            "okurigana_variants": boolean(),
+            "shared_dictionary": boolean(),
+            "trusted_keys": {
+                "type": "array",
+                "items": { "type": "string", "pattern": "^[0-9a-fA-F]{64}$" },
+            },
+        }),
+        &["dictionary"],
+    );
+
+    let mut schema = object(
+        json!({
+            "segmenter": segmenter,
+            "character_filters": { "type": "array", "items": { "$ref": "#/$defs/character_filter" } },
+            "token_filters": { "type": "array", "items": { "$ref": "#/$defs/token_filter" } },
+            "audit_offsets": boolean(),
+            "max_input_bytes": count(0),
+            "timeout_ms": count(0),
+            "cache_capacity": count(0),
+        }),
+        &["segmenter"],
+    );
+    schema["$schema"] = json!("https://json-schema.org/draft/2020-12/schema");
+    schema["title"] = json!("Lindera tokenizer configuration");
+    schema["$defs"] = json!({
+        "penalty": penalty,
+        "mode": mode,
+        "character_filter": { "oneOf": character_filters() },
+        "token_filter": { "oneOf": token_filters() },
+    });
+    schema
+}
+
+/// Parses a tokenizer configuration in JSON or YAML, failing on any key that
+/// [`config_schema`] does not define.
+///
+/// The error names the key, the keys expected in its place and where it is, e.g.
+/// ``segmenter: unknown field `mdoe`, did you mean `mode`? at line 3 column 5``.
+/// The values are not checked against the schema here; they are checked when the tokenizer is
+/// built, as for a configuration parsed leniently.
+pub fn parse_config_strict(text: &str) -> LinderaResult<TokenizerConfig> {
+    let schema = config_schema();
+    let seed = Strict {
+        root: &schema,
+        node: &schema,
+    };
+    let config = seed
+        .deserialize(serde_yaml_ng::Deserializer::from_str(text))
+        .map_err(|err| LinderaErrorKind::Deserialize.with_error(anyhow::anyhow!("{err}")))?;
+    if !config.is_object() {
+        return Err(LinderaErrorKind::Deserialize
+            .with_error(anyhow::anyhow!("the configuration must be a mapping")));
+    }
+    Ok(config)
+}
+
+/// Deserializes a value, rejecting the keys of objects that its schema node does not define.
+#[derive(Clone, Copy)]
+struct Strict<'a> {
+    root: &'a Value,
+    node: &'a Value,
+}
+
+impl<'a> Strict<'a> {
+    fn with(self, node: &'a Value) -> Self {
+        Self {
+            root: self.root,
+            node: resolve(self.root, node),
+        }
+    }
+}
+
+/// Follows a `$ref` to a definition of the schema.
+fn resolve<'a>(root: &'a Value, node: &'a Value) -> &'a Value {
+    match node
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|path| path.strip_prefix("#/$defs/"))
+    {
+        Some(name) => &root["$defs"][name],
+        None => node,
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Strict<'_> {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Strict<'_> {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a configuration value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let items = self.with(self.node.get("items").unwrap_or(&Value::Null));
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element_seed(items)? {
+            values.push(value);
+        }
+        Ok(Value::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Value, A::Error> {
+        let branches = self
+            .node
+            .get("oneOf")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let kinds = branches
+            .iter()
+            .filter_map(|branch| branch["properties"]["kind"]["const"].as_str())
+            .collect::<Vec<_>>();
+        if !kinds.is_empty() {
+            return self.visit_filter(access, branches, &kinds);
+        }
+
+        // An object given where a string is also accepted, such as the mode
+        let node = branches
+            .iter()
+            .find(|branch| branch["type"] == "object")
+            .unwrap_or(self.node);
+        let properties = node.get("properties").and_then(Value::as_object);
+        let additional = node.get("additionalProperties").unwrap_or(&Value::Null);
+        let allowed = match (properties, additional) {
+            (Some(properties), Value::Bool(false)) => {
+                Some(properties.keys().map(String::as_str).collect())
+            }
+            _ => None,
+        };
+
+        let mut map = Map::new();
+        while let Some(key) = access.next_key_seed(Key {
+            what: "field",
+            allowed: allowed.as_ref(),
+        })? {
+            let node = properties
+                .and_then(|properties| properties.get(&key))
+                .unwrap_or(additional);
+            let value = access.next_value_seed(self.with(node))?;
+            map.insert(key, value);
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+impl Strict<'_> {
+    /// Deserializes a filter, checking its `args` against the arguments of its `kind`.
+    fn visit_filter<'de, A: MapAccess<'de>>(
+        self,
+        mut access: A,
+        branches: &[Value],
+        kinds: &[&str],
+    ) -> Result<Value, A::Error> {
+        let keys = vec!["kind", "args"];
+        let mut branch = None;
+        let mut args_before_kind = false;
+        let mut map = Map::new();
+        while let Some(key) = access.next_key_seed(Key {
+            what: "field",
+            allowed: Some(&keys),
+        })? {
+            if key == "kind" {
+                let kind = access.next_value_seed(Key {
+                    what: "filter kind",
+                    allowed: Some(&kinds.to_vec()),
+                })?;
+                branch = branches
+                    .iter()
+                    .find(|branch| branch["properties"]["kind"]["const"] == kind.as_str());
+                map.insert(key, Value::String(kind));
+            } else {
+                args_before_kind = branch.is_none();
+                let node = branch.map_or(&Value::Null, |branch| &branch["properties"]["args"]);
+                let args = access.next_value_seed(self.with(node))?;
+                map.insert(key, args);
+            }
+        }
+
+        // The arguments given before the kind are checked once the kind is known
+        if args_before_kind && let (Some(branch), Some(args)) = (branch, map.get("args")) {
+            self.with(&branch["properties"]["args"])
+                .deserialize(args.clone())
+                .map_err(|err| de::Error::custom(format!("args: {err}")))?;
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+/// Deserializes a key, or a name such as the kind of a filter, failing if it is not one of
+/// the allowed names. Failing here, rather than once the key is read, places the error at the
+/// key.
+struct Key<'a, 'b> {
+    /// What the name is, for the error: a field or a filter kind.
+    what: &'static str,
+    allowed: Option<&'a Vec<&'b str>>,
+}
+
+impl<'de> DeserializeSeed<'de> for Key<'_, '_> {
+    type Value = String;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<String, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Key<'_, '_> {
+    type Value = String;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<String, E> {
+        let Some(allowed) = self.allowed else {
+            return Ok(v.to_string());
+        };
+        if allowed.contains(&v) {
+            return Ok(v.to_string());
+        }
+
+        let suggestion = allowed
+            .iter()
+            .map(|name| (edit_distance(v, name), name))
+            .filter(|(distance, _)| *distance <= 2)
+            .min_by_key(|(distance, _)| *distance);
+        Err(match suggestion {
+            Some((_, name)) => E::custom(format_args!(
+                "unknown {} `{v}`, did you mean `{name}`?",
+                self.what
+            )),
+            None => E::custom(format_args!(
+                "unknown {} `{v}`, expected one of {}",
+                self.what,
+                allowed
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        })
+    }
+
+    // Mapping keys of YAML can be numbers, which become strings in the configuration
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<String, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<String, E> {
+        self.visit_str(&v.to_string())
+    }
+}
+
+/// The Levenshtein distance between two strings, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_config_strict() {
+        let text = r#"{
+  "segmenter": { "dictionary": "embedded://ipadic", "mode": { "decompose": {
+    "kanji_penalty_length_threshold": 2, "kanji_penalty_length_penalty": 3000,
+    "other_penalty_length_threshold": 7, "other_penalty_length_penalty": 1700 } } },
+  "character_filters": [ { "kind": "mapping", "args": { "mapping": { "ｱ": "ア" } } } ],
+  "token_filters": [
+    { "kind": "stop_words", "args": { "words": ["の"], "reload": false } },
+    { "args": { "length": 3 }, "kind": "truncate" },
+    { "kind": "lowercase" }
+  ]
+}"#;
+        let config = parse_config_strict(text).unwrap();
+        assert_eq!(config, serde_json::from_str::<Value>(text).unwrap());
+
+        let yaml = "segmenter:\n  dictionary: embedded://ipadic\ntoken_filters:\n  - kind: stop_words\n    args:\n      stop_wrods: [\"の\"]\n";
+        let error = parse_config_strict(yaml).err().unwrap().to_string();
+        assert!(
+            error.contains(
+                "token_filters[0].args: unknown field `stop_wrods`, expected one of `paths`, `reload`, `words`"
+            ),
+            "{error}"
+        );
+        assert!(error.contains("line 6 column 7"), "{error}");
+    }
+
+    #[test]
+    fn test_parse_config_strict_rejects_unknown_fields() {
+        let error = |text: &str| parse_config_strict(text).err().unwrap().to_string();
+
+        let message =
+            error(r#"{"segmenter": {"dictionary": "embedded://ipadic", "mdoe": "normal"}}"#);
+        assert!(message.contains("did you mean `mode`?"), "{message}");
+        assert!(message.contains("line 1 column 51"), "{message}");
+
+        let message = error(
+            r#"{"segmenter": {"dictionary": "x"}, "token_filters": [{"kind": "stop_word", "args": {}}]}"#,
+        );
+        assert!(
+            message.contains("unknown filter kind `stop_word`, did you mean `stop_words`?"),
+            "{message}"
+        );
+
+        // Arguments given before the kind of the filter
+        let message = error(
+            r#"{"segmenter": {"dictionary": "x"}, "token_filters": [{"args": {"size": 3}, "kind": "truncate"}]}"#,
+        );
+        assert!(message.contains("unknown field `size`"), "{message}");
+
+        // The filter of a conditional filter
+        let message = error(
+            r#"{"segmenter": {"dictionary": "x"}, "token_filters": [{"kind": "conditional", "args": {"filter": {"kind": "length", "args": {"minimum": 2}}}}]}"#,
+        );
+        assert!(message.contains("unknown field `minimum`"), "{message}");
+    }
+
+    #[test]
+    fn test_config_schema_covers_filters() {
+        use crate::character_filter::CharacterFilterLoader;
+        use crate::token_filter::TokenFilterLoader;
+
+        let schema = config_schema();
+        let kinds = |name: &str| {
+            schema["$defs"][name]["oneOf"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|branch| {
+                    branch["properties"]["kind"]["const"]
+                        .as_str()
+                        .unwrap()
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Every kind of the schema is a filter, and no other name is
+        for kind in kinds("character_filter") {
+            let result = CharacterFilterLoader::load_from_value(&kind, &json!({}));
+            assert!(
+                !matches!(&result, Err(err) if err.to_string().contains("unsupported")),
+                "{kind}"
+            );
+        }
+        for kind in kinds("token_filter") {
+            let result = TokenFilterLoader::load_from_value(&kind, &json!({}));
+            assert!(
+                !matches!(&result, Err(err) if err.to_string().contains("unsupported")),
+                "{kind}"
+            );
+        }
+        assert_eq!(7, kinds("character_filter").len());
+        assert_eq!(25, kinds("token_filter").len());
+    }
+
+    /// Returns the keys of the arguments read by the `from_config` functions of `source`, the
+    /// source of a filter, where they are string literals given to `get` or indexing.
+    fn loader_keys(source: &str) -> BTreeSet<String> {
+        let key = regex::Regex::new(r#"[(\[]"([a-z_]+)"[)\]]"#).unwrap();
+        let mut keys = BTreeSet::new();
+        for (start, _) in source.match_indices("fn from_config") {
+            let body = &source[start..];
+            let body = &body[..body.find("\n    }\n").unwrap()];
+            keys.extend(
+                key.captures_iter(body)
+                    .map(|captures| captures[1].to_string()),
+            );
+        }
+        keys
+    }
+
+    /// Returns the names of the properties of `schema` and of the objects nested in it.
+    fn property_names(schema: &Value, names: &mut BTreeSet<String>) {
+        if let Some(properties) = schema["properties"].as_object() {
+            for (name, property) in properties {
+                names.insert(name.clone());
+                property_names(property, names);
+            }
+        }
+    }
+
+    #[test]
+    fn test_config_schema_covers_filter_args() {
+        let schema = config_schema();
+        for name in ["character_filter", "token_filter"] {
+            for branch in schema["$defs"][name]["oneOf"].as_array().unwrap() {
+                let kind = branch["properties"]["kind"]["const"].as_str().unwrap();
+                let path = format!("{}/src/{name}/{kind}.rs", env!("CARGO_MANIFEST_DIR"));
+                let read = loader_keys(&std::fs::read_to_string(&path).unwrap());
+
+                // Every argument of the schema is read by the loader
+                let args = branch["properties"]["args"]["properties"]
+                    .as_object()
+                    .unwrap();
+                for arg in args.keys() {
+                    assert!(read.contains(arg), "{kind}: {arg} is not read");
+                }
+
+                // Every key read by the loader is in the schema, which would otherwise reject it
+                let mut defined = BTreeSet::new();
+                property_names(branch, &mut defined);
+                for key in &read {
+                    assert!(defined.contains(key), "{kind}: {key} is not in the schema");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(0, edit_distance("words", "words"));
+        assert_eq!(2, edit_distance("wrods", "words"));
+        assert_eq!(2, edit_distance("mdoe", "mode"));
+        assert_eq!(5, edit_distance("", "words"));
+    }
+}
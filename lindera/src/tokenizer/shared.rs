@@ -0,0 +1,257 @@
+//! A tokenizer shared by the threads of a long-running service, whose dictionary can be replaced
+//! without a restart.
+//!
+//! A [`SharedTokenizer`] holds the current [`Tokenizer`] behind an [`Arc`]. Each tokenization takes
+//! a snapshot of it with [`SharedTokenizer::tokenizer`], so a reload swaps in a new tokenizer for
+//! the tokenizations that start afterwards, while those in progress finish on the old one. The old
+//! dictionary is dropped with the last snapshot that uses it.
+
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::LinderaResult;
+use crate::dictionary::Dictionary;
+use crate::tokenizer::Tokenizer;
+
+/// A tokenizer whose dictionary can be reloaded while other threads tokenize with it.
+pub struct SharedTokenizer {
+    current: RwLock<Arc<Tokenizer>>,
+    /// Serializes the reloads, so that no reload is based on a tokenizer replaced in the meantime.
+    reload: Mutex<()>,
+}
+
+impl SharedTokenizer {
+    pub fn new(tokenizer: Tokenizer) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(tokenizer)),
+            reload: Mutex::new(()),
+        }
+    }
+
+    /// A snapshot of the current tokenizer. A reload does not affect the snapshots taken before it.
+    pub fn tokenizer(&self) -> Arc<Tokenizer> {
+        self.current
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    /// The dictionary of the current tokenizer.
+    pub fn dictionary(&self) -> Arc<Dictionary> {
+        self.tokenizer().segmenter.dictionary.clone()
+    }
+
+    /// Replaces the current tokenizer.
+    pub fn replace(&self, tokenizer: Tokenizer) {
+        let _reload = self.reload.lock().unwrap_or_else(|err| err.into_inner());
+        self.swap(tokenizer);
+    }
+
+    /// Swaps in a copy of the current tokenizer with the dictionary returned by `loader`, as
+    /// [`Tokenizer::reload_dictionary`] does.
+    ///
+    /// The dictionary is loaded before the current tokenizer is replaced, so the tokenizations keep
+    /// running while it loads, and the current tokenizer is kept if `loader` fails or the
+    /// dictionary does not fit the user dictionary. The copy shares the filters' settings and the
+    /// user dictionary of the current tokenizer.
+    pub fn reload_dictionary<F, D>(&self, loader: F) -> LinderaResult<()>
+    where
+        F: FnOnce() -> LinderaResult<D>,
+        D: Into<Arc<Dictionary>>,
+    {
+        let _reload = self.reload.lock().unwrap_or_else(|err| err.into_inner());
+        let dictionary = loader()?;
+
+        let mut tokenizer = Tokenizer::clone(&self.tokenizer());
+        tokenizer.reload_dictionary(|| Ok(dictionary))?;
+        self.swap(tokenizer);
+
+        Ok(())
+    }
+
+    fn swap(&self, tokenizer: Tokenizer) {
+        let tokenizer = Arc::new(tokenizer);
+        // The old tokenizer is dropped after the lock is released, if no snapshot holds it
+        let _old = std::mem::replace(
+            &mut *self.current.write().unwrap_or_else(|err| err.into_inner()),
+            tokenizer,
+        );
+    }
+}
+
+impl From<Tokenizer> for SharedTokenizer {
+    fn from(tokenizer: Tokenizer) -> Self {
+        Self::new(tokenizer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tempfile::TempDir;
+
+    use crate::dictionary::{Dictionary, DictionaryBuilder, build_okurigana_variants};
+    use crate::error::LinderaErrorKind;
+    use crate::mode::Mode;
+    use crate::segmenter::Segmenter;
+    use crate::test_util::TestDictionary;
+    use crate::tokenizer::Tokenizer;
+    use crate::tokenizer::shared::SharedTokenizer;
+
+    /// Builds a dictionary of the single word 東京 with the part-of-speech subcategory `pos`.
+    fn build_dictionary(pos: &str) -> (TempDir, Dictionary) {
+        TestDictionary::new(&format!(
+            "東京,0,0,100,名詞,{pos},地域,一般,*,*,東京,トウキョウ,トーキョー\n"
+        ))
+        .load()
+    }
+
+    fn pos(tokenizer: &Tokenizer) -> String {
+        let mut tokens = tokenizer.tokenize("東京").unwrap();
+        tokens[0].details()[1].to_string()
+    }
+
+    #[test]
+    fn test_reload_dictionary() {
+        let (_old_dir, old) = build_dictionary("固有名詞");
+        let (_new_dir, new) = build_dictionary("一般");
+
+        let mut tokenizer =
+            Tokenizer::new(Segmenter::new(Mode::Normal, old, None).keep_whitespace(true));
+        assert_eq!("固有名詞", pos(&tokenizer));
+
+        let err = tokenizer
+            .reload_dictionary(|| {
+                Err::<Dictionary, _>(
+                    LinderaErrorKind::NotFound.with_error(anyhow::anyhow!("no dictionary")),
+                )
+            })
+            .err()
+            .unwrap();
+        assert_eq!(LinderaErrorKind::NotFound, err.kind());
+        assert_eq!("固有名詞", pos(&tokenizer));
+
+        tokenizer.reload_dictionary(|| Ok(new)).unwrap();
+        assert_eq!("一般", pos(&tokenizer));
+        assert!(tokenizer.segmenter.keep_whitespace);
+    }
+
+    #[test]
+    fn test_reload_dictionary_checks_user_dictionary() {
+        let (_old_dir, old) = TestDictionary::new(
+            "東京,0,0,100,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー\n",
+        )
+        .matrix_size(6)
+        .load();
+        let (_new_dir, new) = build_dictionary("一般");
+        let user_dictionary = DictionaryBuilder::new(old.metadata.clone())
+            .build_user_dict_from_bytes(
+                "東京都庁,5,5,-1000,名詞,固有名詞,組織,*,*,*,東京都庁,トウキョウトチョウ,トーキョートチョー\n"
+                    .as_bytes(),
+            )
+            .unwrap();
+
+        let mut tokenizer =
+            Tokenizer::new(Segmenter::new(Mode::Normal, old, Some(user_dictionary)));
+        let err = tokenizer.reload_dictionary(|| Ok(new)).err().unwrap();
+        assert_eq!(LinderaErrorKind::Args, err.kind());
+        assert!(
+            err.to_string()
+                .contains("outside the 1x1 connection cost matrix")
+        );
+
+        // The tokenizer keeps the dictionary the user dictionary was built for
+        assert_eq!(
+            6,
+            tokenizer
+                .segmenter
+                .dictionary
+                .connection_cost_matrix
+                .forward_size
+        );
+        assert_eq!("固有名詞", pos(&tokenizer));
+    }
+
+    #[test]
+    fn test_reload_dictionary_rebuilds_okurigana_variants() {
+        let word = |pos: &str| {
+            format!("受け付け,0,0,-1000,名詞,{pos},*,*,*,*,受け付け,ウケツケ,ウケツケ\n")
+        };
+        let (_old_dir, old) = TestDictionary::new(&word("サ変接続")).load();
+        // The word ids of the new dictionary differ from those of the old one
+        let (_new_dir, new) = TestDictionary::new(&format!(
+            "申し込み,0,0,-1000,名詞,サ変接続,*,*,*,*,申し込み,モウシコミ,モーシコミ\n{}",
+            word("一般")
+        ))
+        .load();
+        let okurigana_variants = build_okurigana_variants(&old).unwrap();
+
+        let mut tokenizer = Tokenizer::new(
+            Segmenter::new(Mode::Normal, old, None).okurigana_variants(okurigana_variants),
+        );
+        tokenizer.reload_dictionary(|| Ok(new)).unwrap();
+        assert!(tokenizer.segmenter.okurigana_variants.is_some());
+
+        let mut tokens = tokenizer.tokenize("受付け").unwrap();
+        assert_eq!(1, tokens.len());
+        assert_eq!("受付け", tokens[0].surface.as_ref());
+        assert_eq!(vec!["名詞", "一般"], tokens[0].details()[..2].to_vec());
+        assert_eq!("受け付け", tokens[0].details()[6]);
+    }
+
+    #[test]
+    fn test_shared_tokenizer() {
+        let (_old_dir, old) = build_dictionary("固有名詞");
+        let (_new_dir, new) = build_dictionary("一般");
+        let new = Arc::new(new);
+
+        let shared = SharedTokenizer::new(Tokenizer::new(Segmenter::new(Mode::Normal, old, None)));
+        let snapshot = shared.tokenizer();
+        let old = shared.dictionary();
+
+        shared.reload_dictionary(|| Ok(new.clone())).unwrap();
+        assert!(Arc::ptr_eq(&new, &shared.dictionary()));
+        assert_eq!("一般", pos(&shared.tokenizer()));
+
+        // The snapshot taken before the reload keeps the old dictionary
+        assert_eq!("固有名詞", pos(&snapshot));
+        assert_eq!(2, Arc::strong_count(&old));
+        drop(snapshot);
+        assert_eq!(1, Arc::strong_count(&old));
+    }
+
+    #[test]
+    fn test_shared_tokenizer_threads() {
+        let (_old_dir, old) = build_dictionary("固有名詞");
+        let (_new_dir, new) = build_dictionary("一般");
+
+        let shared = Arc::new(SharedTokenizer::new(Tokenizer::new(Segmenter::new(
+            Mode::Normal,
+            old,
+            None,
+        ))));
+        let handles = (0..4)
+            .map(|_| {
+                let shared = shared.clone();
+                std::thread::spawn(move || {
+                    (0..200)
+                        .map(|_| pos(&shared.tokenizer()))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        shared.reload_dictionary(|| Ok(new)).unwrap();
+
+        for handle in handles {
+            let results = handle.join().unwrap();
+            // Each thread sees the old dictionary, then the new one
+            let switched = results
+                .iter()
+                .position(|pos| pos == "一般")
+                .unwrap_or(results.len());
+            assert!(results[..switched].iter().all(|pos| pos == "固有名詞"));
+            assert!(results[switched..].iter().all(|pos| pos == "一般"));
+        }
+        assert_eq!("一般", pos(&shared.tokenizer()));
+    }
+}